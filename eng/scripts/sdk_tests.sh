@@ -10,6 +10,16 @@ rustup target add --toolchain ${BUILD} wasm32-unknown-unknown
 
 export RUSTFLAGS="-Dwarnings"
 cargo +${BUILD} check -p azure_core --no-default-features
-cargo +${BUILD} check --target=wasm32-unknown-unknown --no-default-features
+cargo +${BUILD} check -p azure_storage_blobs --no-default-features --features crypto-rust
+cargo +${BUILD} check -p azure_storage_blobs --no-default-features --features enable_reqwest_rustls,crypto-ring
+cargo +${BUILD} check -p azure_storage_blobs --no-default-features --features crypto-openssl,md5-openssl
+cargo +${BUILD} check -p azure_storage_blobs --features mock-transport --benches
+cargo +${BUILD} check --target=wasm32-unknown-unknown --no-default-features --features azure_storage/crypto-rust
 cargo +${BUILD} check --all --features azurite_workaround
 cargo +${BUILD} test --all
+# without MD5, block lists and uploads are checked with CRC64 instead
+cargo +${BUILD} test -p azure_storage_blobs --lib --no-default-features --features crypto-rust,mock-transport
+if cargo +${BUILD} tree -p azure_storage_blobs --no-default-features --features crypto-rust -e normal -i md5; then
+  echo "the md5 crate is built without the md5 feature"
+  exit 1
+fi
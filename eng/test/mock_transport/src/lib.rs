@@ -3,10 +3,12 @@ mod mock_response;
 mod mock_transaction;
 mod player_policy;
 mod recorder_policy;
+mod session_options;
 
 use mock_transaction::MockTransaction;
 use player_policy::MockTransportPlayerPolicy;
 use recorder_policy::MockTransportRecorderPolicy;
+pub use session_options::{SessionOptions, SCRUBBED};
 use std::sync::Arc;
 
 use azure_core::{HttpClient, Policy};
@@ -15,37 +17,68 @@ pub const TESTING_MODE_KEY: &str = "TESTING_MODE";
 pub const TESTING_MODE_REPLAY: &str = "REPLAY";
 pub const TESTING_MODE_RECORD: &str = "RECORD";
 
+/// Whether the environment variable "TESTING_MODE" is set to "RECORD", i.e.
+/// tests run against live services.
+pub fn is_recording() -> bool {
+    std::env::var(TESTING_MODE_KEY).as_deref() == Ok(TESTING_MODE_RECORD)
+}
+
 /// Create a new mock transport policy.
 ///
 /// Returns a reply mock policy unless the environment variable  "TESTING_MODE" is set to "RECORD".
 pub fn new_mock_transport(transaction_name: String) -> Arc<dyn Policy> {
-    match std::env::var(TESTING_MODE_KEY)
-        .as_deref()
-        .unwrap_or(TESTING_MODE_REPLAY)
-    {
-        TESTING_MODE_RECORD => {
-            log::warn!("mock testing framework record mode enabled");
-            new_recorder_transport(transaction_name, azure_core::new_http_client())
-        }
-        _ => {
-            log::info!("mock testing framework replay mode enabled");
-            new_replay_transport(transaction_name)
-        }
+    new_mock_transport_with_options(transaction_name, SessionOptions::default())
+}
+
+/// Like [`new_mock_transport`], with custom scrubbing, matching and location
+/// of the transactions.
+pub fn new_mock_transport_with_options(
+    transaction_name: String,
+    options: SessionOptions,
+) -> Arc<dyn Policy> {
+    if is_recording() {
+        log::warn!("mock testing framework record mode enabled");
+        new_recorder_transport_with_options(
+            transaction_name,
+            azure_core::new_http_client(),
+            options,
+        )
+    } else {
+        log::info!("mock testing framework replay mode enabled");
+        new_replay_transport_with_options(transaction_name, options)
     }
 }
 
 /// Create a mock transport policy that replays recorded mock requests/responses.
 pub fn new_replay_transport(transaction_name: String) -> Arc<dyn Policy> {
-    Arc::new(MockTransportPlayerPolicy::new(transaction_name))
+    new_replay_transport_with_options(transaction_name, SessionOptions::default())
+}
+
+/// Like [`new_replay_transport`], with custom options.
+pub fn new_replay_transport_with_options(
+    transaction_name: String,
+    options: SessionOptions,
+) -> Arc<dyn Policy> {
+    Arc::new(MockTransportPlayerPolicy::new(transaction_name, options))
 }
 
 /// Create a mock transport policy that records live calls.
 pub fn new_recorder_transport(
     transaction_name: String,
     http_client: Arc<dyn HttpClient>,
+) -> Arc<dyn Policy> {
+    new_recorder_transport_with_options(transaction_name, http_client, SessionOptions::default())
+}
+
+/// Like [`new_recorder_transport`], with custom options.
+pub fn new_recorder_transport_with_options(
+    transaction_name: String,
+    http_client: Arc<dyn HttpClient>,
+    options: SessionOptions,
 ) -> Arc<dyn Policy> {
     Arc::new(MockTransportRecorderPolicy::new(
         transaction_name,
         http_client,
+        options,
     ))
 }
@@ -121,11 +121,7 @@ impl<'a> Serialize for RequestSerializer<'a> {
     {
         let mut hm = std::collections::BTreeMap::new();
         for (h, v) in self.0.headers().iter() {
-            if h.as_str().to_lowercase() == "authorization" {
-                hm.insert(h.as_str(), "<<STRIPPED>>");
-            } else {
-                hm.insert(h.as_str(), v.as_str());
-            }
+            hm.insert(h.as_str(), v.as_str());
         }
 
         let mut state = serializer.serialize_struct("Request", 4)?;
@@ -8,14 +8,16 @@ pub(crate) struct MockTransaction {
     pub(crate) name: String,
     pub(crate) number: Arc<AtomicUsize>,
     workspace_root: Arc<Mutex<Option<String>>>,
+    transactions_dir: Option<PathBuf>,
 }
 
 impl MockTransaction {
-    pub(crate) fn new(name: impl Into<String>) -> Self {
+    pub(crate) fn new(name: impl Into<String>, transactions_dir: Option<PathBuf>) -> Self {
         Self {
             name: name.into(),
             number: Arc::new(AtomicUsize::new(0)),
             workspace_root: Arc::new(Mutex::new(None)),
+            transactions_dir,
         }
     }
 
@@ -32,23 +34,10 @@ impl MockTransaction {
     }
 
     pub(crate) fn file_path(&self, create_when_not_exist: bool) -> azure_core::Result<PathBuf> {
-        let root_path = {
-            let mut cache = self.workspace_root.lock().unwrap();
-            match &*cache {
-                Some(root) => root.clone(),
-                None => {
-                    let root = workspace_root().context(
-                        ErrorKind::MockFramework,
-                        "could not read the workspace_root from the cargo metadata",
-                    )?;
-                    *cache = Some(root.clone());
-                    root
-                }
-            }
+        let mut path = match &self.transactions_dir {
+            Some(dir) => dir.clone(),
+            None => self.workspace_transactions_dir()?,
         };
-        let mut path = PathBuf::from(root_path);
-        path.push("test");
-        path.push("transactions");
         let name = self.name();
         if name.is_empty() {
             panic!(
@@ -74,6 +63,27 @@ impl MockTransaction {
 
         Ok(path)
     }
+
+    fn workspace_transactions_dir(&self) -> azure_core::Result<PathBuf> {
+        let root_path = {
+            let mut cache = self.workspace_root.lock().unwrap();
+            match &*cache {
+                Some(root) => root.clone(),
+                None => {
+                    let root = workspace_root().context(
+                        ErrorKind::MockFramework,
+                        "could not read the workspace_root from the cargo metadata",
+                    )?;
+                    *cache = Some(root.clone());
+                    root
+                }
+            }
+        };
+        let mut path = PathBuf::from(root_path);
+        path.push("test");
+        path.push("transactions");
+        Ok(path)
+    }
 }
 
 /// Run cargo to get the root of the workspace
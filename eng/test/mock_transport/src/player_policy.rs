@@ -2,6 +2,7 @@ use crate::mock_request::RequestDeserializer;
 
 use super::mock_response::MockResponse;
 use super::mock_transaction::MockTransaction;
+use crate::SessionOptions;
 use azure_core::error::{Error, ErrorKind};
 use azure_core::{Body, Context, Policy, PolicyResult, Request};
 use std::collections::HashMap;
@@ -10,12 +11,16 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct MockTransportPlayerPolicy {
     transaction: MockTransaction,
+    options: SessionOptions,
 }
 
 impl MockTransportPlayerPolicy {
-    pub fn new(transaction_name: String) -> Self {
-        let transaction = MockTransaction::new(transaction_name);
-        Self { transaction }
+    pub fn new(transaction_name: String, options: SessionOptions) -> Self {
+        let transaction = MockTransaction::new(transaction_name, options.transactions_dir.clone());
+        Self {
+            transaction,
+            options,
+        }
     }
 }
 
@@ -50,6 +55,9 @@ impl Policy for MockTransportPlayerPolicy {
             serde_json::from_str::<RequestDeserializer>(&expected_request)?.into_inner();
         let expected_response = serde_json::from_str::<MockResponse>(&expected_response)?;
 
+        // the recorded request had its secrets scrubbed
+        let request = self.options.scrub(request);
+
         let expected_uri = expected_request.path_and_query();
         let actual_uri = request.path_and_query();
         if expected_uri != actual_uri {
@@ -60,18 +68,16 @@ impl Policy for MockTransportPlayerPolicy {
 
         // check if the passed request matches the one read from disk
         // We will ignore some headers that are bound to change every time
-        // We'll probabily want to make the exclusion list dynamic at some point.
-        const SKIPPED_HEADERS: &[&str] = &["Date", "x-ms-date", "authorization", "user-agent"];
         let actual_headers = request
             .headers()
             .iter()
-            .filter(|(h, _)| !SKIPPED_HEADERS.contains(&h.as_str()))
+            .filter(|(h, _)| !self.options.is_ignored(h.as_str()))
             .collect::<HashMap<_, _>>();
 
         let expected_headers = expected_request
             .headers()
             .iter()
-            .filter(|(h, _)| !SKIPPED_HEADERS.contains(&h.as_str()))
+            .filter(|(h, _)| !self.options.is_ignored(h.as_str()))
             .collect::<HashMap<_, _>>();
         let more_headers = if expected_headers.len() > actual_headers.len() {
             expected_headers.iter()
@@ -2,6 +2,7 @@ use crate::mock_request::RequestSerializer;
 
 use super::mock_response::MockResponse;
 use super::MockTransaction;
+use crate::SessionOptions;
 use azure_core::error::{ErrorKind, ResultExt};
 use azure_core::{Context, HttpClient, Policy, PolicyResult, Request};
 use std::io::Write;
@@ -11,14 +12,20 @@ use std::sync::Arc;
 pub struct MockTransportRecorderPolicy {
     transaction: MockTransaction,
     http_client: Arc<dyn HttpClient>,
+    options: SessionOptions,
 }
 
 impl MockTransportRecorderPolicy {
-    pub fn new(transaction_name: String, http_client: Arc<dyn HttpClient>) -> Self {
-        let transaction = MockTransaction::new(transaction_name);
+    pub fn new(
+        transaction_name: String,
+        http_client: Arc<dyn HttpClient>,
+        options: SessionOptions,
+    ) -> Self {
+        let transaction = MockTransaction::new(transaction_name, options.transactions_dir.clone());
         Self {
             transaction,
             http_client,
+            options,
         }
     }
 }
@@ -43,7 +50,8 @@ impl Policy for MockTransportRecorderPolicy {
         request_path.push(format!("{number}_request.json"));
         response_path.push(format!("{number}_response.json"));
 
-        let request_contents = serde_json::to_string(&RequestSerializer::new(request)).unwrap();
+        let scrubbed = self.options.scrub(request);
+        let request_contents = serde_json::to_string(&RequestSerializer::new(&scrubbed)).unwrap();
         {
             let mut request_contents_stream = std::fs::File::create(&request_path).unwrap();
             request_contents_stream
@@ -0,0 +1,147 @@
+use azure_core::Request;
+use std::path::PathBuf;
+
+/// The value recorded in place of scrubbed secrets.
+pub const SCRUBBED: &str = "<<STRIPPED>>";
+
+/// How requests are recorded and matched during replay.
+///
+/// By default secrets in the `authorization`, `x-ms-copy-source-authorization`
+/// and `x-ms-encryption-key` headers and in the `sig` query parameter are
+/// scrubbed, and headers which change on every run (dates, user agent) are
+/// ignored when matching requests.
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    pub(crate) transactions_dir: Option<PathBuf>,
+    scrubbed_headers: Vec<String>,
+    scrubbed_query_parameters: Vec<String>,
+    ignored_headers: Vec<String>,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            transactions_dir: None,
+            scrubbed_headers: vec![
+                "authorization".to_owned(),
+                "x-ms-copy-source-authorization".to_owned(),
+                "x-ms-encryption-key".to_owned(),
+            ],
+            scrubbed_query_parameters: vec!["sig".to_owned()],
+            ignored_headers: vec![
+                "date".to_owned(),
+                "x-ms-date".to_owned(),
+                "authorization".to_owned(),
+                "x-ms-client-request-id".to_owned(),
+                "user-agent".to_owned(),
+            ],
+        }
+    }
+}
+
+impl SessionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the transactions in `dir` instead of the `test/transactions`
+    /// folder of the cargo workspace.
+    pub fn transactions_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.transactions_dir = Some(dir.into());
+        self
+    }
+
+    /// Do not record the value of the header `name`.
+    pub fn scrub_header(mut self, name: impl Into<String>) -> Self {
+        self.scrubbed_headers.push(name.into().to_lowercase());
+        self
+    }
+
+    /// Do not record the value of the query parameter `name`.
+    pub fn scrub_query_parameter(mut self, name: impl Into<String>) -> Self {
+        self.scrubbed_query_parameters.push(name.into());
+        self
+    }
+
+    /// Do not compare the header `name` when matching replayed requests.
+    pub fn ignore_header(mut self, name: impl Into<String>) -> Self {
+        self.ignored_headers.push(name.into().to_lowercase());
+        self
+    }
+
+    pub(crate) fn is_ignored(&self, header: &str) -> bool {
+        self.ignored_headers.iter().any(|ignored| ignored == header)
+    }
+
+    /// A copy of `request` with the secrets replaced by [`SCRUBBED`].
+    pub(crate) fn scrub(&self, request: &Request) -> Request {
+        let mut scrubbed = request.clone();
+
+        let is_scrubbed = |name: &str| {
+            self.scrubbed_query_parameters
+                .iter()
+                .any(|scrubbed| scrubbed == name)
+        };
+        if request
+            .url()
+            .query_pairs()
+            .any(|(name, _)| is_scrubbed(&name))
+        {
+            let pairs: Vec<(String, String)> = request
+                .url()
+                .query_pairs()
+                .map(|(name, value)| {
+                    let value = if is_scrubbed(&name) {
+                        SCRUBBED.to_owned()
+                    } else {
+                        value.into_owned()
+                    };
+                    (name.into_owned(), value)
+                })
+                .collect();
+            scrubbed
+                .url_mut()
+                .query_pairs_mut()
+                .clear()
+                .extend_pairs(pairs);
+        }
+
+        for (name, _) in request.headers().iter() {
+            if self
+                .scrubbed_headers
+                .iter()
+                .any(|scrubbed| scrubbed == name.as_str())
+            {
+                scrubbed.insert_header(name.clone(), SCRUBBED);
+            }
+        }
+        scrubbed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{headers::AUTHORIZATION, Method};
+    use url::Url;
+
+    #[test]
+    fn test_scrub() {
+        let mut request = Request::new(
+            Url::parse("https://account.blob.core.windows.net/c?sv=2021-08-06&sig=secret").unwrap(),
+            Method::Get,
+        );
+        request.insert_header(AUTHORIZATION, "Bearer secret");
+        request.insert_header("x-ms-secret", "secret");
+
+        let options = SessionOptions::new().scrub_header("X-Ms-Secret");
+        let scrubbed = options.scrub(&request);
+        assert_eq!(
+            scrubbed.path_and_query(),
+            "/c?sv=2021-08-06&sig=%3C%3CSTRIPPED%3E%3E"
+        );
+        for (_, value) in scrubbed.headers().iter() {
+            assert_eq!(value.as_str(), SCRUBBED);
+        }
+    }
+}
@@ -3,8 +3,9 @@
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, fmt::Debug};
 use time::OffsetDateTime;
+use zeroize::Zeroize;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct AccessToken(Cow<'static, str>);
 
 impl AccessToken {
@@ -20,6 +21,68 @@ impl AccessToken {
     }
 }
 
+impl Debug for AccessToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AccessToken(<REDACTED>)")
+    }
+}
+
+/// A secret, such as an account key, a SAS signature or a bearer token.
+///
+/// The value is left out of `Debug` output and there is no `Display`, so it is
+/// not logged by accident. It is zeroed in memory when dropped, and comparisons
+/// take the same time however early the values differ. It can be read from
+/// configuration, but not serialized.
+#[derive(Clone, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new<T>(secret: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self(secret.into())
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(<REDACTED>)")
+    }
+}
+
+impl PartialEq for Secret {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.0.as_bytes(), other.0.as_bytes());
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+impl Eq for Secret {}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(secret: &str) -> Self {
+        Self(secret.to_owned())
+    }
+}
+
 /// Represents an Azure service bearer access token with expiry information.
 #[derive(Debug, Clone)]
 pub struct TokenResponse {
@@ -43,3 +106,21 @@ pub trait TokenCredential: Send + Sync {
     /// Gets a `TokenResponse` for the specified resource
     async fn get_token(&self, resource: &str) -> crate::Result<TokenResponse>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret() {
+        let secret = Secret::new("SOMEACCESSKEY");
+        assert_eq!(secret.secret(), "SOMEACCESSKEY");
+        assert_eq!(format!("{secret:?}"), "Secret(<REDACTED>)");
+        assert_eq!(secret, Secret::from("SOMEACCESSKEY"));
+        assert_ne!(secret, Secret::from("SOMEACCESSKEZ"));
+        assert_ne!(secret, Secret::from("SOMEACCESS"));
+
+        let token = AccessToken::new("TOKEN");
+        assert!(!format!("{token:?}").contains("TOKEN"));
+    }
+}
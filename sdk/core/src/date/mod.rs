@@ -15,6 +15,7 @@ use time::{
 pub use time::serde::rfc3339;
 pub use time::serde::timestamp;
 pub mod rfc1123;
+pub mod rfc7231;
 
 /// RFC 3339: Date and Time on the Internet: Timestamps
 ///
@@ -85,6 +86,58 @@ pub fn to_rfc1123(date: &OffsetDateTime) -> String {
     date.format(&RFC1123_FORMAT).unwrap()
 }
 
+/// RFC 7231: the HTTP-date formats
+///
+/// <https://www.rfc-editor.org/rfc/rfc7231#section-7.1.1.1>
+///
+/// Besides the preferred RFC 1123 format, recipients have to accept two
+/// obsolete formats, which some services still produce, e.g. the last
+/// modified times of storage analytics logs:
+///
+/// Sun, 06 Nov 1994 08:49:37 GMT
+///
+/// Sunday, 06-Nov-94 08:49:37 GMT (RFC 850)
+///
+/// Sun Nov  6 08:49:37 1994 (ANSI C's asctime)
+///
+/// Two digit years are taken to be between 1970 and 2069.
+pub fn parse_rfc7231(s: &str) -> crate::Result<OffsetDateTime> {
+    if let Ok(date) = parse_rfc1123(s) {
+        return Ok(date);
+    }
+    let normalized = if let Some((_weekday, rest)) = s.split_once(", ") {
+        // RFC 850: the full weekday, and the date separated by dashes
+        rest.strip_suffix(" GMT").and_then(|rest| {
+            let (date, time) = rest.split_once(' ')?;
+            let mut date = date.splitn(3, '-');
+            let (day, month, year) = (date.next()?, date.next()?, date.next()?);
+            let year: i32 = year.parse().ok().filter(|_| year.len() == 2)?;
+            let year = if year < 70 { 2000 + year } else { 1900 + year };
+            Some(format!("{day} {month} {year} {time}"))
+        })
+    } else {
+        // asctime: the day is padded with a space rather than a zero
+        match s.split_whitespace().collect::<Vec<_>>()[..] {
+            [_weekday, month, day, time, year] => day
+                .parse::<u8>()
+                .ok()
+                .map(|day| format!("{day:02} {month} {year} {time}")),
+            _ => None,
+        }
+    };
+    normalized
+        .and_then(|normalized| PrimitiveDateTime::parse(&normalized, HTTP_DATE_FORMAT).ok())
+        .map(PrimitiveDateTime::assume_utc)
+        .ok_or_else(|| {
+            crate::Error::with_message(ErrorKind::DataConversion, || {
+                format!("unable to parse rfc7231 date '{s}")
+            })
+        })
+}
+
+const HTTP_DATE_FORMAT: &[FormatItem] =
+    format_description!("[day] [month repr:short] [year] [hour]:[minute]:[second]");
+
 /// Similar to RFC 1123, but includes milliseconds.
 ///
 /// <https://docs.microsoft.com/rest/api/cosmos-db/patch-a-document>
@@ -186,6 +239,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_rfc7231() -> crate::Result<()> {
+        let dt = datetime!(1994-11-06 08:49:37 UTC);
+        assert_eq!(parse_rfc7231("Sun, 06 Nov 1994 08:49:37 GMT")?, dt);
+        assert_eq!(parse_rfc7231("Sunday, 06-Nov-94 08:49:37 GMT")?, dt);
+        assert_eq!(parse_rfc7231("Sun Nov  6 08:49:37 1994")?, dt);
+        assert_eq!(
+            parse_rfc7231("Tuesday, 09-Aug-11 18:52:40 GMT")?,
+            datetime!(2011-08-09 18:52:40 UTC)
+        );
+        assert!(parse_rfc7231("Sunday, 06-Nov-1994 08:49:37 GMT").is_err());
+        assert!(parse_rfc7231("1994-11-06T08:49:37Z").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rfc3339_storage() -> crate::Result<()> {
+        // storage writes seven fractional digits, the precision of .NET ticks
+        assert_eq!(
+            parse_rfc3339("2011-03-09T01:42:34.9360000Z")?,
+            datetime!(2011-03-09 01:42:34.936 UTC)
+        );
+        assert_eq!(
+            parse_rfc3339("2011-03-09T01:42:34.1234567Z")?,
+            datetime!(2011-03-09 01:42:34.1234567 UTC)
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_parse_last_state_change() -> crate::Result<()> {
         assert_eq!(
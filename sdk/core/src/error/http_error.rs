@@ -46,6 +46,21 @@ impl HttpError {
         self.details.code.as_deref()
     }
 
+    /// Get the value of a response header, by its lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// Get the `x-ms-request-id` the service assigned to the request.
+    pub fn request_id(&self) -> Option<&str> {
+        self.header(headers::REQUEST_ID.as_str())
+    }
+
+    /// Get the `x-ms-client-request-id` of the request, as echoed by the service.
+    pub fn client_request_id(&self) -> Option<&str> {
+        self.header(headers::CLIENT_REQUEST_ID.as_str())
+    }
+
     /// Get a reference to the http error's error message.
     pub fn error_message(&self) -> Option<&str> {
         self.details.message.as_deref()
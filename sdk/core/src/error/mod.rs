@@ -3,7 +3,9 @@ use std::borrow::Cow;
 use std::fmt::{Debug, Display};
 mod http_error;
 mod macros;
+mod operation_error;
 pub use http_error::HttpError;
+pub use operation_error::OperationError;
 
 /// A convenience alias for `Result` where the error type is hard coded to `Error`
 pub type Result<T> = std::result::Result<T, Error>;
@@ -24,6 +26,18 @@ pub enum ErrorKind {
     DataConversion,
     /// An error getting an API credential token
     Credential,
+    /// The service rejected a feature which the requested API version does not support
+    FeatureNotSupportedByApiVersion {
+        feature: String,
+        api_version: String,
+        minimum_api_version: String,
+    },
+    /// A ranged read started beyond the end of the resource. `length` is the
+    /// current length of the resource, when the service reported it.
+    RangeNotSatisfiable { length: Option<u64> },
+    /// The shared access signature authorizing a request expired at `expiry`,
+    /// its signed expiry (`se`).
+    SasExpired { expiry: time::OffsetDateTime },
     /// An error having to do with the mock framework
     MockFramework,
     /// A catch all for other kinds of errors
@@ -61,6 +75,21 @@ impl Display for ErrorKind {
             ErrorKind::Io => write!(f, "Io"),
             ErrorKind::DataConversion => write!(f, "DataConversion"),
             ErrorKind::Credential => write!(f, "Credential"),
+            ErrorKind::FeatureNotSupportedByApiVersion {
+                feature,
+                api_version,
+                minimum_api_version,
+            } => write!(
+                f,
+                "FeatureNotSupportedByApiVersion({feature},{api_version},{minimum_api_version})"
+            ),
+            ErrorKind::RangeNotSatisfiable { length } => match length {
+                Some(length) => write!(f, "RangeNotSatisfiable({length})"),
+                None => write!(f, "RangeNotSatisfiable(unknown)"),
+            },
+            ErrorKind::SasExpired { expiry } => {
+                write!(f, "SasExpired({})", crate::date::to_rfc3339(expiry))
+            }
             ErrorKind::MockFramework => write!(f, "MockFramework"),
             ErrorKind::Other => write!(f, "Other"),
         }
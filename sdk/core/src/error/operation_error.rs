@@ -0,0 +1,141 @@
+use super::Error;
+
+/// Names the operation and the client request id of a failed request.
+///
+/// Client pipelines attach it with [`Error::with_operation`], and it is read
+/// back with [`Error::operation`] and [`Error::client_request_id`].
+#[derive(Debug)]
+pub struct OperationError {
+    operation: String,
+    client_request_id: Option<String>,
+    source: Error,
+}
+
+impl OperationError {
+    /// The name of the operation which failed.
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    /// The `x-ms-client-request-id` the request was sent with.
+    pub fn client_request_id(&self) -> Option<&str> {
+        self.client_request_id.as_deref()
+    }
+}
+
+impl std::fmt::Display for OperationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed", self.operation)?;
+        if let Some(client_request_id) = &self.client_request_id {
+            write!(f, " (client request id {client_request_id})")?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl std::error::Error for OperationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl Error {
+    /// Attach the name of the operation and the client request id of the
+    /// request which failed. The kind of the error is kept.
+    pub fn with_operation(
+        self,
+        operation: impl Into<String>,
+        client_request_id: Option<String>,
+    ) -> Error {
+        let kind = self.kind().clone();
+        let operation_error = OperationError {
+            operation: operation.into(),
+            client_request_id,
+            source: self,
+        };
+        let message = operation_error.to_string();
+        Error::full(kind, operation_error, message)
+    }
+
+    /// The name of the operation which failed, if a client pipeline attached
+    /// one.
+    pub fn operation(&self) -> Option<&str> {
+        self.find_source::<OperationError>()
+            .map(OperationError::operation)
+    }
+
+    /// The HTTP status of the response, if the error was caused by one.
+    pub fn http_status(&self) -> Option<crate::StatusCode> {
+        match self.kind() {
+            super::ErrorKind::HttpResponse { status, .. } => Some(*status),
+            _ => self.as_http_error().map(super::HttpError::status),
+        }
+    }
+
+    /// The `x-ms-request-id` the service assigned to the failed request.
+    pub fn request_id(&self) -> Option<&str> {
+        self.as_http_error()?.request_id()
+    }
+
+    /// The `x-ms-client-request-id` of the failed request.
+    pub fn client_request_id(&self) -> Option<&str> {
+        self.as_http_error()
+            .and_then(super::HttpError::client_request_id)
+            .or_else(|| {
+                self.find_source::<OperationError>()
+                    .and_then(OperationError::client_request_id)
+            })
+    }
+
+    /// Searches the source chain for an error of type `T`.
+    fn find_source<T: std::error::Error + 'static>(&self) -> Option<&T> {
+        let mut error = self.get_ref()? as &dyn std::error::Error;
+        loop {
+            match error.downcast_ref::<T>() {
+                Some(e) => return Some(e),
+                None => error = error.source()?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::{ErrorKind, HttpError},
+        headers::Headers,
+        Response, StatusCode,
+    };
+
+    #[test]
+    fn test_operation_error() {
+        let mut headers = Headers::new();
+        headers.insert("x-ms-request-id", "server-id");
+        headers.insert("x-ms-client-request-id", "client-id");
+        headers.insert("x-ms-error-code", "BlobNotFound");
+        let response = Response::new(
+            StatusCode::NotFound,
+            headers,
+            Box::pin(futures::stream::empty()),
+        );
+        let error = Error::full(
+            ErrorKind::http_response(StatusCode::NotFound, Some("BlobNotFound".to_owned())),
+            futures::executor::block_on(HttpError::new(response)),
+            "not found",
+        )
+        .with_operation("GET /container/blob", Some("client-id".to_owned()));
+
+        assert_eq!(error.operation(), Some("GET /container/blob"));
+        assert_eq!(error.http_status(), Some(StatusCode::NotFound));
+        assert_eq!(error.request_id(), Some("server-id"));
+        assert_eq!(error.client_request_id(), Some("client-id"));
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+        assert!(error.as_http_error().is_some());
+
+        let error = Error::message(ErrorKind::Io, "connection reset")
+            .with_operation("PUT /container/blob", Some("other-id".to_owned()));
+        assert_eq!(error.http_status(), None);
+        assert_eq!(error.client_request_id(), Some("other-id"));
+    }
+}
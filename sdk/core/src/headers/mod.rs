@@ -2,6 +2,7 @@
 mod utilities;
 
 use crate::error::{Error, ErrorKind, ResultExt};
+use crate::Context;
 use std::{fmt::Debug, str::FromStr};
 pub use utilities::*;
 
@@ -51,8 +52,19 @@ pub trait Header {
 }
 
 /// A collection of headers
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
-pub struct Headers(std::collections::HashMap<HeaderName, HeaderValue>);
+#[derive(Clone, Debug, Default)]
+pub struct Headers {
+    headers: std::collections::HashMap<HeaderName, HeaderValue>,
+    extensions: Context,
+}
+
+impl PartialEq for Headers {
+    fn eq(&self, other: &Self) -> bool {
+        self.headers == other.headers
+    }
+}
+
+impl Eq for Headers {}
 
 impl Headers {
     pub fn new() -> Self {
@@ -115,7 +127,7 @@ impl Headers {
         F: FnOnce(&'a HeaderValue) -> Result<V, E>,
         E: std::error::Error + Send + Sync + 'static,
     {
-        self.0
+        self.headers
             .get(key)
             .map(|v: &HeaderValue| {
                 parser(v).with_context(ErrorKind::DataConversion, || {
@@ -132,7 +144,7 @@ impl Headers {
         K: Into<HeaderName>,
         V: Into<HeaderValue>,
     {
-        self.0.insert(key.into(), value.into());
+        self.headers.insert(key.into(), value.into());
     }
 
     /// Add headers to the headers collection
@@ -147,7 +159,18 @@ impl Headers {
 
     /// Iterate over all the header name/value pairs
     pub fn iter(&self) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
-        self.0.iter()
+        self.headers.iter()
+    }
+
+    /// Values attached to a response by the pipeline, which are not sent by
+    /// the service. They are kept with the headers so that they can be read
+    /// wherever the headers are parsed.
+    pub fn extensions(&self) -> &Context {
+        &self.extensions
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Context {
+        &mut self.extensions
     }
 }
 
@@ -157,13 +180,16 @@ impl IntoIterator for Headers {
     type IntoIter = std::collections::hash_map::IntoIter<HeaderName, HeaderValue>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.headers.into_iter()
     }
 }
 
 impl From<std::collections::HashMap<HeaderName, HeaderValue>> for Headers {
     fn from(c: std::collections::HashMap<HeaderName, HeaderValue>) -> Self {
-        Self(c)
+        Self {
+            headers: c,
+            extensions: Context::new(),
+        }
     }
 }
 
@@ -264,6 +290,21 @@ impl From<&String> for HeaderValue {
 
 pub const ACCEPT: HeaderName = HeaderName::from_static("accept");
 pub const ACCEPT_ENCODING: HeaderName = HeaderName::from_static("accept-encoding");
+pub const ACCESS_CONTROL_ALLOW_CREDENTIALS: HeaderName =
+    HeaderName::from_static("access-control-allow-credentials");
+pub const ACCESS_CONTROL_ALLOW_HEADERS: HeaderName =
+    HeaderName::from_static("access-control-allow-headers");
+pub const ACCESS_CONTROL_ALLOW_METHODS: HeaderName =
+    HeaderName::from_static("access-control-allow-methods");
+pub const ACCESS_CONTROL_ALLOW_ORIGIN: HeaderName =
+    HeaderName::from_static("access-control-allow-origin");
+pub const ACCESS_CONTROL_EXPOSE_HEADERS: HeaderName =
+    HeaderName::from_static("access-control-expose-headers");
+pub const ACCESS_CONTROL_MAX_AGE: HeaderName = HeaderName::from_static("access-control-max-age");
+pub const ACCESS_CONTROL_REQUEST_HEADERS: HeaderName =
+    HeaderName::from_static("access-control-request-headers");
+pub const ACCESS_CONTROL_REQUEST_METHOD: HeaderName =
+    HeaderName::from_static("access-control-request-method");
 pub const ACL: HeaderName = HeaderName::from_static("x-ms-acl");
 pub const ACCOUNT_KIND: HeaderName = HeaderName::from_static("x-ms-account-kind");
 pub const ACTIVITY_ID: HeaderName = HeaderName::from_static("x-ms-activity-id");
@@ -332,6 +373,7 @@ pub const META_PREFIX: HeaderName = HeaderName::from_static("x-ms-meta-");
 pub const MS_DATE: HeaderName = HeaderName::from_static("x-ms-date");
 pub const MS_RANGE: HeaderName = HeaderName::from_static("x-ms-range");
 pub const NAMESPACE_ENABLED: HeaderName = HeaderName::from_static("x-ms-namespace-enabled");
+pub const ORIGIN: HeaderName = HeaderName::from_static("origin");
 pub const PAGE_WRITE: HeaderName = HeaderName::from_static("x-ms-page-write");
 pub const PROPERTIES: HeaderName = HeaderName::from_static("x-ms-properties");
 pub const PREFER: HeaderName = HeaderName::from_static("prefer");
@@ -356,6 +398,7 @@ pub const SOURCE_IF_NONE_MATCH: HeaderName = HeaderName::from_static("x-ms-sourc
 pub const SOURCE_IF_UNMODIFIED_SINCE: HeaderName =
     HeaderName::from_static("x-ms-source-if-unmodified-since");
 pub const SOURCE_LEASE_ID: HeaderName = HeaderName::from_static("x-ms-source-lease-id");
+pub const TAG_COUNT: HeaderName = HeaderName::from_static("x-ms-tag-count");
 pub const TAGS: HeaderName = HeaderName::from_static("x-ms-tags");
 pub const USER: HeaderName = HeaderName::from_static("x-ms-user");
 pub const USER_AGENT: HeaderName = HeaderName::from_static("user-agent");
@@ -57,16 +57,28 @@ pub fn account_kind_from_headers(headers: &Headers) -> crate::Result<String> {
     headers.get_as(&ACCOUNT_KIND)
 }
 
-pub fn etag_from_headers_optional(headers: &Headers) -> crate::Result<Option<String>> {
-    Ok(headers.get_optional_string(&ETAG))
-}
-
-pub fn etag_from_headers(headers: &Headers) -> crate::Result<String> {
+pub fn etag_from_headers_optional<T, E>(headers: &Headers) -> crate::Result<Option<T>>
+where
+    T: std::str::FromStr<Err = E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    headers.get_optional_as(&ETAG)
+}
+
+/// The `ETag` header, as an [`Etag`](crate::Etag) or as the raw `String`.
+pub fn etag_from_headers<T, E>(headers: &Headers) -> crate::Result<T>
+where
+    T: std::str::FromStr<Err = E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
     headers.get_as(&ETAG)
 }
 
-pub fn lease_time_from_headers(headers: &Headers) -> crate::Result<u8> {
-    headers.get_as(&LEASE_TIME)
+/// The `x-ms-lease-time` header: how long a broken lease remains until it
+/// can be acquired again.
+pub fn lease_time_from_headers(headers: &Headers) -> crate::Result<std::time::Duration> {
+    let seconds: u64 = headers.get_as(&LEASE_TIME)?;
+    Ok(std::time::Duration::from_secs(seconds))
 }
 
 #[cfg(not(feature = "azurite_workaround"))]
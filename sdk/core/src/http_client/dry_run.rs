@@ -0,0 +1,187 @@
+use crate::error::{Error, ErrorKind};
+use crate::headers::Headers;
+use crate::{Method, Request};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// Headers whose values are replaced by [`REDACTED`] in recorded requests.
+const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "x-ms-copy-source-authorization",
+    "x-ms-encryption-key",
+];
+
+/// Headers holding the URL of another resource, whose query parameters are
+/// redacted as those of the request.
+const SOURCE_HEADERS: &[&str] = &["x-ms-copy-source", "x-ms-rename-source"];
+
+/// Query parameters whose values are replaced by [`REDACTED`] in recorded requests.
+const REDACTED_QUERY_PARAMETERS: &[&str] = &["sig"];
+
+/// The value recorded in place of secrets.
+pub const REDACTED: &str = "REDACTED";
+
+/// An `HttpClient` which records requests instead of sending them.
+///
+/// Every request fails with an `ErrorKind::Other` error whose source is the
+/// recorded [`DryRunRequest`], so operations stop right before they would
+/// have gone over the wire, after all the policies (including signing) ran.
+/// The requests are also kept, and can be inspected with
+/// [`requests`](Self::requests). Clones share the recorded requests.
+///
+/// ```
+/// use azure_core::{DryRunClient, TransportOptions};
+/// use std::sync::Arc;
+/// let dry_run = DryRunClient::new();
+/// let transport = TransportOptions::new(Arc::new(dry_run.clone()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DryRunClient {
+    requests: Arc<Mutex<Vec<DryRunRequest>>>,
+}
+
+impl DryRunClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The requests recorded so far, oldest first.
+    pub fn requests(&self) -> Vec<DryRunRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Removes and returns the requests recorded so far.
+    pub fn take(&self) -> Vec<DryRunRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl crate::HttpClient for DryRunClient {
+    async fn execute_request(&self, request: &Request) -> crate::Result<crate::Response> {
+        let recorded = DryRunRequest::from(request);
+        self.requests.lock().unwrap().push(recorded.clone());
+        Err(Error::new(ErrorKind::Other, recorded))
+    }
+}
+
+/// A request as it would have been sent, with secrets redacted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: Headers,
+    pub body_len: usize,
+}
+
+impl From<&Request> for DryRunRequest {
+    fn from(request: &Request) -> Self {
+        let mut url = request.url().clone();
+        if let Some(query) = url.query().map(redact_query) {
+            url.set_query(Some(&query));
+        }
+
+        let mut headers = Headers::new();
+        for (name, value) in request.headers().iter() {
+            if REDACTED_HEADERS.contains(&name.as_str()) {
+                headers.insert(name.clone(), REDACTED);
+            } else if SOURCE_HEADERS.contains(&name.as_str()) {
+                let value = match value.as_str().split_once('?') {
+                    Some((path, query)) => format!("{path}?{}", redact_query(query)),
+                    None => value.as_str().to_owned(),
+                };
+                headers.insert(name.clone(), value);
+            } else {
+                headers.insert(name.clone(), value.clone());
+            }
+        }
+
+        Self {
+            method: *request.method(),
+            url,
+            headers,
+            body_len: request.body().len(),
+        }
+    }
+}
+
+/// Replaces the values of the [`REDACTED_QUERY_PARAMETERS`] of `query`,
+/// leaving the other parameters as they are.
+fn redact_query(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| {
+            let name = pair.split_once('=').map_or(pair, |(name, _)| name);
+            if REDACTED_QUERY_PARAMETERS.contains(&name) {
+                format!("{name}={REDACTED}")
+            } else {
+                pair.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+impl std::fmt::Display for DryRunRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "dry run: {} {}", self.method, self.url)
+    }
+}
+
+impl std::error::Error for DryRunRequest {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::{AUTHORIZATION, VERSION};
+    use crate::HttpClient;
+
+    #[test]
+    fn test_dry_run_redacts_secrets() {
+        let client = DryRunClient::new();
+        let mut request = Request::new(
+            Url::parse("https://account.blob.core.windows.net/c/b?sv=2021-08-06&sig=secret")
+                .unwrap(),
+            Method::Get,
+        );
+        request.insert_header(AUTHORIZATION, "SharedKey account:secret");
+        request.insert_header(VERSION, "2019-12-12");
+        request.insert_header(
+            "x-ms-copy-source",
+            "https://source.blob.core.windows.net/c/b?se=2021-01-01T00%3A00%3A00Z&sig=secret",
+        );
+        request.insert_header("x-ms-rename-source", "/filesystem/old?sig=secret");
+
+        let error = futures::executor::block_on(client.execute_request(&request)).unwrap_err();
+        let recorded = error.into_downcast::<DryRunRequest>().unwrap();
+        assert_eq!(
+            recorded.url.as_str(),
+            "https://account.blob.core.windows.net/c/b?sv=2021-08-06&sig=REDACTED"
+        );
+        assert_eq!(
+            recorded.headers.get_optional_str(&AUTHORIZATION),
+            Some(REDACTED)
+        );
+        assert_eq!(
+            recorded.headers.get_optional_str(&VERSION),
+            Some("2019-12-12")
+        );
+        assert_eq!(
+            recorded
+                .headers
+                .get_optional_str(&"x-ms-copy-source".into()),
+            Some(
+                "https://source.blob.core.windows.net/c/b?se=2021-01-01T00%3A00%3A00Z&sig=REDACTED"
+            )
+        );
+        assert_eq!(
+            recorded
+                .headers
+                .get_optional_str(&"x-ms-rename-source".into()),
+            Some("/filesystem/old?sig=REDACTED")
+        );
+        assert_eq!(client.requests(), vec![recorded]);
+    }
+}
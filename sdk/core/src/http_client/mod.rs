@@ -1,3 +1,4 @@
+mod dry_run;
 mod noop;
 #[cfg(all(
     not(target_arch = "wasm32"),
@@ -15,6 +16,7 @@ compile_error!("The `enable_request` and `enable_reqwest_rustls` features are no
     any(feature = "enable_reqwest", feature = "enable_reqwest_rustls")
 ))]
 pub use self::reqwest::*;
+pub use dry_run::*;
 pub use noop::*;
 
 use std::sync::Arc;
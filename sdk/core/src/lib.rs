@@ -49,7 +49,7 @@ pub use context::Context;
 pub use error::{Error, Result};
 #[doc(inline)]
 pub use headers::Header;
-pub use http_client::{new_http_client, to_json, HttpClient};
+pub use http_client::{new_http_client, to_json, DryRunClient, DryRunRequest, HttpClient};
 pub use models::*;
 pub use options::*;
 pub use pageable::*;
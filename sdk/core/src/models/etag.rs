@@ -1,15 +1,68 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::{fmt, str::FromStr};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An entity tag ([RFC 7232](https://www.rfc-editor.org/rfc/rfc7232#section-2.3)).
+///
+/// Services do not quote ETags consistently, e.g. Azure Storage quotes them in
+/// headers but not in XML listings, so the tag is kept in its canonical quoted
+/// form: `"0x8D93C7D4629C227"`, or `W/"0x8D93C7D4629C227"` for a weak tag.
+/// Tags which only differ in their quoting are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct Etag(String);
 
+impl Etag {
+    /// The tag matching any entity, `*`, for `If-Match` and `If-None-Match`.
+    pub fn any() -> Self {
+        Self("*".to_owned())
+    }
+
+    /// Whether the tag is weak, i.e. prefixed with `W/`.
+    pub fn is_weak(&self) -> bool {
+        self.0.starts_with("W/")
+    }
+
+    /// The tag without its weak prefix and quotes.
+    pub fn opaque_tag(&self) -> &str {
+        self.0
+            .trim_start_matches("W/")
+            .trim_start_matches('"')
+            .trim_end_matches('"')
+    }
+
+    /// The strong comparison of RFC 7232: both tags are strong and equal.
+    pub fn strong_eq(&self, other: &Etag) -> bool {
+        !self.is_weak() && !other.is_weak() && self.opaque_tag() == other.opaque_tag()
+    }
+
+    /// The weak comparison of RFC 7232: the tags are equal, ignoring whether
+    /// they are weak.
+    pub fn weak_eq(&self, other: &Etag) -> bool {
+        self.opaque_tag() == other.opaque_tag()
+    }
+
+    fn normalize(etag: &str) -> String {
+        let etag = etag.trim();
+        if etag == "*" {
+            return etag.to_owned();
+        }
+        let (weak, tag) = match etag.strip_prefix("W/").or_else(|| etag.strip_prefix("w/")) {
+            Some(tag) => ("W/", tag),
+            None => ("", etag),
+        };
+        let tag = tag
+            .strip_prefix('"')
+            .and_then(|tag| tag.strip_suffix('"'))
+            .unwrap_or(tag);
+        format!("{weak}\"{tag}\"")
+    }
+}
+
 impl<T> From<T> for Etag
 where
     T: Into<String>,
 {
     fn from(t: T) -> Self {
-        Self(t.into())
+        Self(Self::normalize(&t.into()))
     }
 }
 
@@ -22,7 +75,7 @@ impl AsRef<str> for Etag {
 impl FromStr for Etag {
     type Err = crate::error::Error;
     fn from_str(s: &str) -> crate::Result<Self> {
-        Ok(Self(s.into()))
+        Ok(Self(Self::normalize(s)))
     }
 }
 
@@ -31,3 +84,40 @@ impl fmt::Display for Etag {
         write!(f, "{}", self.0)
     }
 }
+
+impl<'de> Deserialize<'de> for Etag {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(Etag::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalization() {
+        let quoted = Etag::from("\"0x8D93C7D4629C227\"");
+        let unquoted = Etag::from("0x8D93C7D4629C227");
+        assert_eq!(quoted, unquoted);
+        assert_eq!(unquoted.to_string(), "\"0x8D93C7D4629C227\"");
+        assert_eq!(unquoted.opaque_tag(), "0x8D93C7D4629C227");
+
+        let weak: Etag = "W/\"0x8D93C7D4629C227\"".parse().unwrap();
+        assert!(weak.is_weak());
+        assert_eq!(Etag::from("w/0x8D93C7D4629C227"), weak);
+        assert_ne!(weak, quoted);
+        assert!(weak.weak_eq(&quoted));
+        assert!(!weak.strong_eq(&quoted));
+        assert!(quoted.strong_eq(&unquoted));
+
+        assert_eq!(Etag::from(" * "), Etag::any());
+        assert_eq!(Etag::any().to_string(), "*");
+
+        let json: Etag = serde_json::from_str("\"0x8D93C7D4629C227\"").unwrap();
+        assert_eq!(json, quoted);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::policies::{ExponentialRetryPolicy, FixedRetryPolicy, NoRetryPolicy, Policy};
+use crate::policies::{
+    ExponentialRetryPolicy, FixedRetryPolicy, NoRetryPolicy, OperationClassRetryPolicy, Policy,
+};
 use crate::{http_client, TimeoutPolicy};
 use crate::{HttpClient, RetryPolicy};
 use std::fmt::Debug;
@@ -55,6 +57,21 @@ impl ClientOptions {
         &mut self.per_retry_policies
     }
 
+    /// The retry options, e.g. to log the configuration of a client.
+    pub fn retry_options(&self) -> &RetryOptions {
+        &self.retry
+    }
+
+    /// The telemetry options.
+    pub fn telemetry_options(&self) -> &TelemetryOptions {
+        &self.telemetry
+    }
+
+    /// The transport options.
+    pub fn transport_options(&self) -> &TransportOptions {
+        &self.transport
+    }
+
     setters! {
         per_call_policies: Vec<Arc<dyn Policy>> => per_call_policies,
         per_retry_policies: Vec<Arc<dyn Policy>> => per_retry_policies,
@@ -82,6 +99,12 @@ enum RetryMode {
 
     /// Do not retry attempts.
     None,
+
+    /// Retry attempts of idempotent and non-idempotent requests differently.
+    PerOperationClass {
+        idempotent: Box<RetryOptions>,
+        non_idempotent: Box<RetryOptions>,
+    },
 }
 
 impl Debug for RetryMode {
@@ -91,6 +114,14 @@ impl Debug for RetryMode {
             RetryMode::Fixed(o) => write!(f, "Fixed({o:?})"),
             RetryMode::Custom(_) => write!(f, "Custom"),
             RetryMode::None => write!(f, "None"),
+            RetryMode::PerOperationClass {
+                idempotent,
+                non_idempotent,
+            } => f
+                .debug_struct("PerOperationClass")
+                .field("idempotent", &idempotent.mode)
+                .field("non_idempotent", &non_idempotent.mode)
+                .finish(),
         }
     }
 }
@@ -142,6 +173,22 @@ impl RetryOptions {
         }
     }
 
+    /// Separate retry strategies for idempotent and non-idempotent requests,
+    /// e.g. retrying reads aggressively while not retrying unconditional writes.
+    ///
+    /// The strategy is selected per request by its
+    /// [`OperationClass`](crate::OperationClass), which a client may set in the
+    /// request's `Context`, and which otherwise follows the request's method
+    /// and conditional headers.
+    pub fn per_operation_class(idempotent: RetryOptions, non_idempotent: RetryOptions) -> Self {
+        Self {
+            mode: RetryMode::PerOperationClass {
+                idempotent: Box::new(idempotent),
+                non_idempotent: Box::new(non_idempotent),
+            },
+        }
+    }
+
     pub(crate) fn to_policy(&self) -> Arc<dyn Policy> {
         match &self.mode {
             RetryMode::Exponential(options) => Arc::new(ExponentialRetryPolicy::new(
@@ -157,6 +204,13 @@ impl RetryOptions {
             )),
             RetryMode::Custom(c) => c.clone(),
             RetryMode::None => Arc::new(NoRetryPolicy::default()),
+            RetryMode::PerOperationClass {
+                idempotent,
+                non_idempotent,
+            } => Arc::new(OperationClassRetryPolicy::new(
+                idempotent.to_policy(),
+                non_idempotent.to_policy(),
+            )),
         }
     }
 }
@@ -266,15 +320,24 @@ impl Default for FixedRetryOptions {
 }
 
 /// Telemetry options.
+///
+/// By default the `User-Agent` header names the SDK crate, its version and
+/// the platform, prefixed by the application ID if one is set.
 #[derive(Clone, Debug, Default)]
 pub struct TelemetryOptions {
     /// Optional application ID to telemeter.
     pub(crate) application_id: Option<String>,
+    /// Optional `User-Agent` replacing the one built by the SDK.
+    pub(crate) user_agent: Option<String>,
+    /// Whether to leave out the `User-Agent` header altogether.
+    pub(crate) disabled: bool,
 }
 
 impl TelemetryOptions {
     setters! {
         application_id: String => Some(application_id),
+        user_agent: String => Some(user_agent),
+        disabled: bool => disabled,
     }
 }
 
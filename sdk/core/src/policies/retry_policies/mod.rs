@@ -1,9 +1,11 @@
 mod exponential_retry;
 mod fixed_retry;
 mod no_retry;
+mod operation_class_retry;
 mod retry_policy;
 
 pub use exponential_retry::*;
 pub use fixed_retry::*;
 pub use no_retry::*;
+pub use operation_class_retry::*;
 pub use retry_policy::RetryPolicy;
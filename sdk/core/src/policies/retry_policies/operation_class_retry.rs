@@ -0,0 +1,141 @@
+use crate::headers::{IF_MATCH, IF_NONE_MATCH, IF_UNMODIFIED_SINCE};
+use crate::policies::{Policy, PolicyResult, Request};
+use crate::{Context, Method};
+use std::sync::Arc;
+
+/// Whether sending a request more than once is safe, which selects the retry
+/// options of [`RetryOptions::per_operation_class`](crate::RetryOptions::per_operation_class).
+///
+/// A client can set the class of an operation by inserting it in the
+/// [`Context`] of the request; otherwise [`OperationClass::of`] decides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationClass {
+    /// Reads, and writes conditional on the state of the resource.
+    Idempotent,
+    /// Writes which may take effect twice when repeated.
+    NonIdempotent,
+}
+
+impl OperationClass {
+    /// The class of `request`, from its method and conditional headers.
+    ///
+    /// `GET`, `HEAD` and `OPTIONS` requests are idempotent. Other requests
+    /// are idempotent when they carry `If-Match`, `If-None-Match` or
+    /// `If-Unmodified-Since`, since a repeated request then fails rather
+    /// than writing again.
+    pub fn of(request: &Request) -> Self {
+        let method = request.method();
+        let is_read =
+            *method == Method::Get || *method == Method::Head || *method == Method::Options;
+        let headers = request.headers();
+        let is_conditional = [IF_MATCH, IF_NONE_MATCH, IF_UNMODIFIED_SINCE]
+            .iter()
+            .any(|name| headers.get_optional_str(name).is_some());
+        if is_read || is_conditional {
+            Self::Idempotent
+        } else {
+            Self::NonIdempotent
+        }
+    }
+}
+
+/// Retries each request with the policy of its [`OperationClass`].
+#[derive(Debug, Clone)]
+pub struct OperationClassRetryPolicy {
+    idempotent: Arc<dyn Policy>,
+    non_idempotent: Arc<dyn Policy>,
+}
+
+impl OperationClassRetryPolicy {
+    pub(crate) fn new(idempotent: Arc<dyn Policy>, non_idempotent: Arc<dyn Policy>) -> Self {
+        Self {
+            idempotent,
+            non_idempotent,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for OperationClassRetryPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let class = ctx
+            .get::<OperationClass>()
+            .copied()
+            .unwrap_or_else(|| OperationClass::of(request));
+        match class {
+            OperationClass::Idempotent => self.idempotent.send(ctx, request, next).await,
+            OperationClass::NonIdempotent => self.non_idempotent.send(ctx, request, next).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{headers::Headers, FixedRetryOptions, Response, RetryOptions, StatusCode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Fails every request as unavailable.
+    #[derive(Debug, Default)]
+    struct Unavailable {
+        requests: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for Unavailable {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            Ok(Response::new(
+                StatusCode::ServiceUnavailable,
+                Headers::new(),
+                Box::pin(futures::stream::empty()),
+            ))
+        }
+    }
+
+    fn attempts(ctx: &Context, mut request: Request) -> usize {
+        let retry = RetryOptions::per_operation_class(
+            RetryOptions::fixed(
+                FixedRetryOptions::default()
+                    .max_retries(1u32)
+                    .delay(Duration::ZERO),
+            ),
+            RetryOptions::none(),
+        )
+        .to_policy();
+        let service = Arc::new(Unavailable::default());
+        let next: Vec<Arc<dyn Policy>> = vec![service.clone()];
+        let result = futures::executor::block_on(retry.send(ctx, &mut request, &next));
+        assert!(result.is_err());
+        service.requests.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_retries_by_operation_class() {
+        let url: url::Url = "https://account.blob.core.windows.net/c/b".parse().unwrap();
+        let ctx = Context::new();
+
+        assert_eq!(attempts(&ctx, Request::new(url.clone(), Method::Get)), 2);
+        assert_eq!(attempts(&ctx, Request::new(url.clone(), Method::Put)), 1);
+
+        let mut conditional = Request::new(url.clone(), Method::Put);
+        conditional.insert_header(IF_MATCH, "\"0x8D93C7D4629C227\"");
+        assert_eq!(attempts(&ctx, conditional), 2);
+
+        let mut ctx = Context::new();
+        ctx.insert(OperationClass::NonIdempotent);
+        assert_eq!(attempts(&ctx, Request::new(url, Method::Get)), 1);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::{Error, ErrorKind, HttpError};
 use crate::policies::{Policy, PolicyResult, Request};
+use crate::request_options::Deadline;
 use crate::sleep::sleep;
 use crate::{Context, StatusCode};
 
@@ -127,6 +128,15 @@ where
             }
             retry_count += 1;
 
+            if let Some(deadline) = ctx.get::<Deadline>() {
+                let resume_at = OffsetDateTime::now_utc() + self.sleep_duration(retry_count);
+                if resume_at >= deadline.at() {
+                    return Err(last_error.context(
+                        "the deadline would pass before the next retry and the request will no longer be retried",
+                    ));
+                }
+            }
+
             self.wait(&last_error, retry_count).await;
         }
     }
@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct TelemetryPolicy {
-    header: String,
+    header: Option<String>,
 }
 
 /// Sets the User-Agent header with useful information in a typical format for Azure SDKs.
@@ -41,11 +41,19 @@ impl<'a> TelemetryPolicy {
             crate_name = name;
         }
 
-        let header = match &options.application_id {
-            Some(application_id) => {
-                format!("{application_id} azsdk-rust-{crate_name}/{crate_version} {platform_info}")
-            }
-            None => format!("azsdk-rust-{crate_name}/{crate_version} {platform_info}"),
+        let header = if options.disabled {
+            None
+        } else if let Some(user_agent) = &options.user_agent {
+            Some(user_agent.clone())
+        } else {
+            Some(match &options.application_id {
+                Some(application_id) => {
+                    format!(
+                        "{application_id} azsdk-rust-{crate_name}/{crate_version} {platform_info}"
+                    )
+                }
+                None => format!("azsdk-rust-{crate_name}/{crate_version} {platform_info}"),
+            })
         };
 
         TelemetryPolicy { header }
@@ -61,7 +69,9 @@ impl Policy for TelemetryPolicy {
         request: &mut Request,
         next: &[Arc<dyn Policy>],
     ) -> PolicyResult {
-        request.insert_header(USER_AGENT, HeaderValue::from(self.header.to_string()));
+        if let Some(header) = &self.header {
+            request.insert_header(USER_AGENT, HeaderValue::from(header.to_string()));
+        }
 
         next[0].send(ctx, request, &next[1..]).await
     }
@@ -80,16 +90,14 @@ mod test {
             &TelemetryOptions::default(),
         );
         assert_eq!(
-            policy.header,
+            policy.header.unwrap(),
             format!("azsdk-rust-test/1.2.3 (4.5.6; {OS}; {ARCH})")
         );
     }
 
     #[test]
     fn test_with_application_id() {
-        let options = TelemetryOptions {
-            application_id: Some("my_app".to_string()),
-        };
+        let options = TelemetryOptions::default().application_id("my_app");
         let policy = TelemetryPolicy::new_with_rustc_version(
             Some("test"),
             Some("1.2.3"),
@@ -97,7 +105,7 @@ mod test {
             &options,
         );
         assert_eq!(
-            policy.header,
+            policy.header.unwrap(),
             format!("my_app azsdk-rust-test/1.2.3 (4.5.6; {OS}; {ARCH})")
         );
     }
@@ -108,8 +116,26 @@ mod test {
         let policy =
             TelemetryPolicy::new_with_rustc_version(None, None, None, &TelemetryOptions::default());
         assert_eq!(
-            policy.header,
+            policy.header.unwrap(),
             format!("azsdk-rust-unknown/unknown (unknown; {OS}; {ARCH})")
         )
     }
+
+    #[test]
+    fn test_user_agent_override() {
+        let options = TelemetryOptions::default()
+            .application_id("my_app")
+            .user_agent("custom/1.0");
+        let policy =
+            TelemetryPolicy::new_with_rustc_version(Some("test"), Some("1.2.3"), None, &options);
+        assert_eq!(policy.header.as_deref(), Some("custom/1.0"));
+
+        let policy = TelemetryPolicy::new_with_rustc_version(
+            Some("test"),
+            Some("1.2.3"),
+            None,
+            &options.disabled(true),
+        );
+        assert_eq!(policy.header, None);
+    }
 }
@@ -1,15 +1,41 @@
-use crate::request_options::Timeout;
+use crate::error::{Error, ErrorKind};
+use crate::request_options::{Deadline, Timeout};
 use crate::{AppendToUrlQuery, Context, Policy, PolicyResult, Request};
+use futures::future::Either;
 use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
 
-#[derive(Debug, Clone, Default)]
+/// How much longer than the service timeout an attempt waits for a response,
+/// so that the service can answer that it timed out.
+const LOCAL_TIMEOUT_MARGIN: Duration = Duration::from_secs(5);
+
+/// Sets the server timeout of every attempt, and fails an attempt with an
+/// `Io` error, which is retried, if no response arrived shortly after it, e.g.
+/// because the connection hung.
+#[derive(Debug, Clone)]
 pub struct TimeoutPolicy {
     default_timeout: Option<Timeout>,
+    local_timeout_margin: Duration,
+}
+
+impl Default for TimeoutPolicy {
+    fn default() -> Self {
+        Self::new(None)
+    }
 }
 
 impl TimeoutPolicy {
     pub fn new(default_timeout: Option<Timeout>) -> Self {
-        Self { default_timeout }
+        Self {
+            default_timeout,
+            local_timeout_margin: LOCAL_TIMEOUT_MARGIN,
+        }
+    }
+
+    /// The timeout of requests which do not set their own.
+    pub fn default_timeout(&self) -> Option<Timeout> {
+        self.default_timeout
     }
 }
 
@@ -23,7 +49,161 @@ impl Policy for TimeoutPolicy {
         next: &[Arc<dyn Policy>],
     ) -> PolicyResult {
         let timeout = ctx.get::<Timeout>().or(self.default_timeout.as_ref());
+        let timeout = attempt_timeout(timeout, ctx.get::<Deadline>(), OffsetDateTime::now_utc())?;
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => return next[0].send(ctx, request, &next[1..]).await,
+        };
+        // the request is reused across attempts, which must not send the
+        // timeout of the previous ones
+        remove_query_pair(request.url_mut(), "timeout");
         timeout.append_to_url_query(request.url_mut());
-        next[0].send(ctx, request, &next[1..]).await
+
+        let send = next[0].send(ctx, request, &next[1..]);
+        let expired = crate::sleep::sleep(timeout.duration() + self.local_timeout_margin);
+        match futures::future::select(send, expired).await {
+            Either::Left((result, _)) => result,
+            Either::Right(((), _)) => Err(Error::with_message(ErrorKind::Io, || {
+                format!(
+                    "no response within the timeout of {}s",
+                    timeout.duration().as_secs()
+                )
+            })),
+        }
+    }
+}
+
+/// Remove every `name` pair from the query of `url`.
+fn remove_query_pair(url: &mut url::Url, name: &str) {
+    if !url.query_pairs().any(|(key, _)| key == name) {
+        return;
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| key != name)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+}
+
+/// The timeout of the next attempt: the configured timeout, bounded by the time
+/// left until the deadline.
+fn attempt_timeout(
+    timeout: Option<&Timeout>,
+    deadline: Option<&Deadline>,
+    now: OffsetDateTime,
+) -> crate::Result<Option<Timeout>> {
+    let deadline = match deadline {
+        Some(deadline) => deadline,
+        None => return Ok(timeout.copied()),
+    };
+    let remaining = deadline.remaining_at(now).ok_or_else(|| {
+        Error::with_message(ErrorKind::Other, || {
+            format!("deadline of {} exceeded", deadline.at())
+        })
+    })?;
+    // the service timeout has a granularity of seconds, round up so that it is
+    // never zero
+    let remaining =
+        Duration::from_secs(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0));
+    let timeout = match timeout {
+        Some(timeout) => timeout.duration().min(remaining),
+        None => remaining,
+    };
+    Ok(Some(Timeout::new(timeout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{headers::Headers, FixedRetryPolicy, Method, Response, StatusCode, Url};
+    use std::{collections::VecDeque, sync::Mutex};
+
+    #[test]
+    fn test_attempt_timeout() {
+        let now = OffsetDateTime::now_utc();
+        let timeout = Timeout::new(Duration::from_secs(30));
+
+        let result = attempt_timeout(Some(&timeout), None, now).unwrap();
+        assert_eq!(result.unwrap().duration(), Duration::from_secs(30));
+
+        let deadline = Deadline::new(now + Duration::from_millis(2500));
+        let result = attempt_timeout(Some(&timeout), Some(&deadline), now).unwrap();
+        assert_eq!(result.unwrap().duration(), Duration::from_secs(3));
+
+        let result = attempt_timeout(None, Some(&deadline), now).unwrap();
+        assert_eq!(result.unwrap().duration(), Duration::from_secs(3));
+
+        let deadline = Deadline::new(now + Duration::from_secs(60));
+        let result = attempt_timeout(Some(&timeout), Some(&deadline), now).unwrap();
+        assert_eq!(result.unwrap().duration(), Duration::from_secs(30));
+
+        let deadline = Deadline::new(now);
+        assert!(attempt_timeout(Some(&timeout), Some(&deadline), now).is_err());
+    }
+
+    /// Records the URL of the requests, and answers each of them after the
+    /// next of `delays`.
+    #[derive(Debug)]
+    struct Transport {
+        urls: Mutex<Vec<Url>>,
+        delays: Mutex<VecDeque<Duration>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for Transport {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            self.urls.lock().unwrap().push(request.url().clone());
+            let delay = self.delays.lock().unwrap().pop_front().unwrap();
+            crate::sleep::sleep(delay).await;
+            Ok(Response::new(
+                StatusCode::Ok,
+                Headers::new(),
+                Box::pin(futures::stream::empty()),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_timeout_per_attempt() {
+        // the first attempt hangs, and is retried once it timed out
+        let transport = Arc::new(Transport {
+            urls: Mutex::default(),
+            delays: Mutex::new(VecDeque::from([Duration::from_secs(60), Duration::ZERO])),
+        });
+        let policy = TimeoutPolicy {
+            local_timeout_margin: Duration::ZERO,
+            ..TimeoutPolicy::new(Some(Timeout::new(Duration::from_secs(1))))
+        };
+        let retry = FixedRetryPolicy::new(Duration::ZERO, 3, Duration::from_secs(60));
+        let next: [Arc<dyn Policy>; 2] = [Arc::new(policy), transport.clone()];
+        let mut request = Request::new(
+            Url::parse("https://account.blob.core.windows.net/container?comp=list").unwrap(),
+            Method::Get,
+        );
+        let response =
+            futures::executor::block_on(retry.send(&Context::new(), &mut request, &next));
+        assert_eq!(response.unwrap().status(), StatusCode::Ok);
+
+        let urls = transport.urls.lock().unwrap();
+        assert_eq!(urls.len(), 2);
+        for url in urls.iter() {
+            let timeouts: Vec<_> = url
+                .query_pairs()
+                .filter(|(key, _)| key == "timeout")
+                .map(|(_, value)| value.into_owned())
+                .collect();
+            assert_eq!(timeouts, ["1"]);
+            assert!(url.query_pairs().any(|(key, _)| key == "comp"));
+        }
     }
 }
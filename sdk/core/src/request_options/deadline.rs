@@ -0,0 +1,44 @@
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// An absolute point in time by which an operation must complete.
+///
+/// Insert it into the operation's [`Context`](crate::Context): the
+/// `TimeoutPolicy` then bounds the server timeout of every attempt by the time
+/// left, and retries stop once the deadline would be missed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(OffsetDateTime);
+
+impl Deadline {
+    pub fn new(at: OffsetDateTime) -> Self {
+        Self(at)
+    }
+
+    /// A deadline `duration` from now.
+    pub fn after(duration: Duration) -> Self {
+        Self(OffsetDateTime::now_utc() + duration)
+    }
+
+    pub fn at(&self) -> OffsetDateTime {
+        self.0
+    }
+
+    /// The time left at `now`, or `None` if the deadline has passed.
+    pub fn remaining_at(&self, now: OffsetDateTime) -> Option<Duration> {
+        (self.0 - now)
+            .try_into()
+            .ok()
+            .filter(|remaining: &Duration| !remaining.is_zero())
+    }
+
+    /// The time left, or `None` if the deadline has passed.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining_at(OffsetDateTime::now_utc())
+    }
+}
+
+impl From<OffsetDateTime> for Deadline {
+    fn from(at: OffsetDateTime) -> Self {
+        Self(at)
+    }
+}
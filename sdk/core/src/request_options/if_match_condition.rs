@@ -1,10 +1,11 @@
 use crate::headers::{self, Header};
+use crate::Etag;
 use headers::{IF_MATCH, IF_NONE_MATCH};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IfMatchCondition {
-    Match(String),
-    NotMatch(String),
+    Match(Etag),
+    NotMatch(Etag),
 }
 
 impl Header for IfMatchCondition {
@@ -16,8 +17,10 @@ impl Header for IfMatchCondition {
     }
 
     fn value(&self) -> headers::HeaderValue {
-        match self.clone() {
-            IfMatchCondition::Match(etag) | IfMatchCondition::NotMatch(etag) => etag.into(),
+        match self {
+            IfMatchCondition::Match(etag) | IfMatchCondition::NotMatch(etag) => {
+                etag.to_string().into()
+            }
         }
     }
 }
@@ -2,9 +2,30 @@ use crate::headers::{self, Header};
 use std::str::FromStr;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The id of a lease.
+///
+/// Lease ids are chosen by the service unless one is proposed when the lease
+/// is acquired. A process which persists the id it proposes can take its
+/// lease back after a restart by proposing it again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LeaseId(Uuid);
 
+impl LeaseId {
+    pub fn new(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+impl From<Uuid> for LeaseId {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
 impl std::fmt::Display for LeaseId {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         self.0.fmt(fmt)
@@ -1,12 +1,52 @@
+use crate::error::{Error, ErrorKind};
 use crate::headers::{self, Header};
 use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LeaseDuration {
+    /// A lease which lasts until it is released or broken, sent as `-1`.
     Infinite,
     Seconds(u8),
 }
 
+impl LeaseDuration {
+    /// The shortest lease the service grants, in seconds.
+    pub const MIN_SECONDS: u8 = 15;
+    /// The longest finite lease the service grants, in seconds.
+    pub const MAX_SECONDS: u8 = 60;
+
+    /// Checks the service's constraints: a lease is infinite or lasts from
+    /// 15 to 60 seconds.
+    pub fn validate(&self) -> crate::Result<()> {
+        match self {
+            LeaseDuration::Seconds(seconds)
+                if !(Self::MIN_SECONDS..=Self::MAX_SECONDS).contains(seconds) =>
+            {
+                Err(Error::with_message(ErrorKind::Other, || {
+                    format!(
+                        "a lease lasts from {} to {} seconds, or is infinite, not {seconds} seconds",
+                        Self::MIN_SECONDS,
+                        Self::MAX_SECONDS
+                    )
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// How often to renew the lease to keep it, or `None` for an infinite
+    /// lease.
+    ///
+    /// Renewing at half the duration leaves the other half for a renewal
+    /// to be retried before the lease expires.
+    pub fn renewal_interval(&self) -> Option<Duration> {
+        match self {
+            LeaseDuration::Infinite => None,
+            LeaseDuration::Seconds(seconds) => Some(Duration::from_secs(u64::from(*seconds)) / 2),
+        }
+    }
+}
+
 impl Header for LeaseDuration {
     fn name(&self) -> headers::HeaderName {
         headers::LEASE_DURATION
@@ -23,8 +63,48 @@ impl Header for LeaseDuration {
     }
 }
 
+/// Durations too long for the service saturate rather than wrap, so that
+/// [`LeaseDuration::validate`] rejects them.
 impl From<Duration> for LeaseDuration {
     fn from(d: Duration) -> Self {
-        LeaseDuration::Seconds(d.as_secs() as u8)
+        LeaseDuration::Seconds(d.as_secs().min(u64::from(u8::MAX)) as u8)
+    }
+}
+
+/// Negative durations are rejected by [`LeaseDuration::validate`].
+impl From<time::Duration> for LeaseDuration {
+    fn from(d: time::Duration) -> Self {
+        LeaseDuration::Seconds(d.whole_seconds().clamp(0, i64::from(u8::MAX)) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lease_duration() {
+        let lease_duration = LeaseDuration::from(Duration::from_secs(30));
+        assert!(lease_duration.validate().is_ok());
+        assert_eq!(
+            lease_duration.renewal_interval(),
+            Some(Duration::from_secs(15))
+        );
+        assert!(LeaseDuration::from(time::Duration::seconds(60))
+            .validate()
+            .is_ok());
+        assert!(LeaseDuration::Infinite.validate().is_ok());
+        assert_eq!(LeaseDuration::Infinite.renewal_interval(), None);
+
+        assert!(LeaseDuration::from(Duration::from_secs(10))
+            .validate()
+            .is_err());
+        // 300 seconds must not wrap around to a valid 44
+        assert!(LeaseDuration::from(Duration::from_secs(300))
+            .validate()
+            .is_err());
+        assert!(LeaseDuration::from(time::Duration::seconds(-30))
+            .validate()
+            .is_err());
     }
 }
@@ -1,5 +1,6 @@
 mod content_length;
 mod content_range;
+mod deadline;
 mod if_match_condition;
 mod if_modified_since;
 mod if_modified_since_condition;
@@ -21,6 +22,7 @@ mod timeout;
 
 pub use content_length::ContentLength;
 pub use content_range::ContentRange;
+pub use deadline::Deadline;
 pub use if_match_condition::IfMatchCondition;
 pub use if_modified_since::IfModifiedSince;
 pub use if_modified_since_condition::IfModifiedSinceCondition;
@@ -1,9 +1,23 @@
 use super::LeaseId;
 use crate::{headers, Header};
+use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy)]
+/// The id proposed for a lease when it is acquired or changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ProposedLeaseId(LeaseId);
 
+impl ProposedLeaseId {
+    pub fn lease_id(&self) -> LeaseId {
+        self.0
+    }
+}
+
+impl From<Uuid> for ProposedLeaseId {
+    fn from(uuid: Uuid) -> Self {
+        Self(LeaseId::new(uuid))
+    }
+}
+
 impl From<LeaseId> for ProposedLeaseId {
     fn from(lease_id: LeaseId) -> Self {
         Self(lease_id)
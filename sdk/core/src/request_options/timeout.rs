@@ -8,6 +8,10 @@ impl Timeout {
     pub fn new(duration: Duration) -> Self {
         Self(duration)
     }
+
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
 }
 
 impl AppendToUrlQuery for Timeout {
@@ -3,6 +3,7 @@ use crate::headers::Headers;
 use crate::StatusCode;
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 use std::pin::Pin;
 
@@ -34,6 +35,11 @@ impl Response {
         &self.headers
     }
 
+    /// Attach a value to the response, see [`Headers::extensions`].
+    pub fn extensions_mut(&mut self) -> &mut crate::Context {
+        self.headers.extensions_mut()
+    }
+
     /// Deconstruct the HTTP response into its components.
     pub fn deconstruct(self) -> (StatusCode, Headers, ResponseBody) {
         (self.status, self.headers, self.body)
@@ -43,6 +49,25 @@ impl Response {
     pub fn into_body(self) -> ResponseBody {
         self.body
     }
+
+    /// Consume the HTTP response and deserialize the JSON body, see
+    /// [`ResponseBody::collect_json`].
+    pub async fn collect_json<T>(self) -> crate::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.body.collect_json().await
+    }
+
+    /// Consume the HTTP response and deserialize the XML body, see
+    /// [`ResponseBody::collect_xml`].
+    #[cfg(feature = "xml")]
+    pub async fn collect_xml<T>(self) -> crate::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.body.collect_xml().await
+    }
 }
 
 impl std::fmt::Debug for Response {
@@ -127,6 +152,29 @@ impl ResponseBody {
             )
             .map(ToOwned::to_owned)
     }
+
+    /// Collect the stream and deserialize it from JSON
+    pub async fn collect_json<T>(self) -> crate::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let body = self.collect().await?;
+        serde_json::from_slice(&body).with_context(ErrorKind::DataConversion, || {
+            let t = std::any::type_name::<T>();
+            let json = std::str::from_utf8(&body).unwrap_or("<JSON IS NOT UTF-8>");
+            format!("failed to deserialize the following json into a {t}\n{json}")
+        })
+    }
+
+    /// Collect the stream and deserialize it from XML, skipping a byte order
+    /// mark like the service models do
+    #[cfg(feature = "xml")]
+    pub async fn collect_xml<T>(self) -> crate::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        crate::xml::read_xml(&self.collect().await?)
+    }
 }
 
 impl Stream for ResponseBody {
@@ -145,3 +193,48 @@ impl Debug for ResponseBody {
         f.write_str("ResonseBody")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct Container {
+        name: String,
+    }
+
+    fn response(chunks: &[&'static str]) -> Response {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))
+            .collect::<Vec<_>>();
+        Response::new(
+            StatusCode::Ok,
+            Headers::new(),
+            Box::pin(futures::stream::iter(chunks)),
+        )
+    }
+
+    #[test]
+    fn test_collect_json() {
+        let container: Container =
+            block_on(response(&["{\"Name\":", "\"photos\"}"]).collect_json()).unwrap();
+        assert_eq!(container.name, "photos");
+
+        let error = block_on(response(&["{\"Name\":"]).collect_json::<Container>()).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::DataConversion);
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn test_collect_xml() {
+        let container: Container = block_on(
+            response(&["\u{FEFF}<Container><Name>pho", "tos</Name></Container>"]).collect_xml(),
+        )
+        .unwrap();
+        assert_eq!(container.name, "photos");
+    }
+}
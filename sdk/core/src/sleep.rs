@@ -1,40 +1,102 @@
 use futures::Future;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::task::{Context, Poll, Waker};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 pub fn sleep(duration: Duration) -> Sleep {
     Sleep {
-        thread: None,
+        timer: None,
         duration,
     }
 }
 
 #[derive(Debug)]
 pub struct Sleep {
-    thread: Option<thread::JoinHandle<()>>,
+    timer: Option<Timer>,
     duration: Duration,
 }
 
+/// The thread waiting for the end of a [`Sleep`], which stops early when the
+/// sleep is dropped.
+#[derive(Debug)]
+struct Timer {
+    thread: thread::JoinHandle<()>,
+    state: Arc<TimerState>,
+}
+
+#[derive(Debug, Default)]
+struct TimerState {
+    elapsed: AtomicBool,
+    cancelled: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
 impl Future for Sleep {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if let Some(thread) = &self.thread {
-            if thread.is_finished() {
-                Poll::Ready(())
-            } else {
-                Poll::Pending
-            }
+        let this = self.get_mut();
+        let duration = this.duration;
+        let timer = this.timer.get_or_insert_with(|| {
+            let state = Arc::new(TimerState::default());
+            let thread_state = state.clone();
+            let thread = thread::spawn(move || {
+                let deadline = Instant::now() + duration;
+                loop {
+                    if thread_state.cancelled.load(Ordering::Acquire) {
+                        return;
+                    }
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break;
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+                thread_state.elapsed.store(true, Ordering::Release);
+                if let Some(waker) = thread_state.waker.lock().unwrap().take() {
+                    waker.wake();
+                }
+            });
+            Timer { thread, state }
+        });
+        // the waker is stored before checking whether the time elapsed, so
+        // that the thread either sees it or is seen to be done
+        *timer.state.waker.lock().unwrap() = Some(cx.waker().clone());
+        if timer.state.elapsed.load(Ordering::Acquire) {
+            Poll::Ready(())
         } else {
-            let waker = cx.waker().clone();
-            let duration = self.duration;
-            self.get_mut().thread = Some(thread::spawn(move || {
-                thread::sleep(duration);
-                waker.wake();
-            }));
             Poll::Pending
         }
     }
 }
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(timer) = &self.timer {
+            timer.state.cancelled.store(true, Ordering::Release);
+            timer.thread.thread().unpark();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleep() {
+        let start = Instant::now();
+        futures::executor::block_on(sleep(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        // many short sleeps, each of which used to be able to miss its wake up
+        for _ in 0..100 {
+            futures::executor::block_on(sleep(Duration::ZERO));
+        }
+    }
+}
@@ -169,7 +169,7 @@ async fn main() -> azure_core::Result<()> {
             .clone()
             .document_client(doc.id.clone(), &doc.id)?
             .replace_document(doc)
-            .if_match_condition(IfMatchCondition::Match(document.etag))
+            .if_match_condition(IfMatchCondition::Match(document.etag.into()))
             .await?;
         println!("replace_document_response == {replace_document_response:#?}");
     }
@@ -133,7 +133,7 @@ async fn main() -> azure_core::Result<()> {
         .document_client(id.clone(), &id)?
         .replace_document(doc.document)
         .consistency_level(ConsistencyLevel::from(&response))
-        .if_match_condition(IfMatchCondition::Match(doc.etag)) // use optimistic concurrency check
+        .if_match_condition(IfMatchCondition::Match(doc.etag.into())) // use optimistic concurrency check
         .await?;
 
     println!("replace_document_response == {replace_document_response:#?}");
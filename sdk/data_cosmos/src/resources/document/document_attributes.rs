@@ -68,6 +68,6 @@ impl std::convert::TryFrom<&bytes::Bytes> for DocumentAttributes {
 
 impl<'a> std::convert::From<&'a DocumentAttributes> for IfMatchCondition {
     fn from(document_attributes: &'a DocumentAttributes) -> Self {
-        IfMatchCondition::Match(document_attributes.etag.clone())
+        IfMatchCondition::Match(document_attributes.etag.clone().into())
     }
 }
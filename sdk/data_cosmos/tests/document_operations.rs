@@ -107,10 +107,7 @@ async fn document_operations() {
         .replace_document(document_data)
         .consistency_level(ConsistencyLevel::from(&documents))
         .if_match_condition(IfMatchCondition::Match(
-            documents.documents[0]
-                .document_attributes
-                .etag()
-                .to_string(),
+            documents.documents[0].document_attributes.etag().into(),
         ))
         .await
         .unwrap();
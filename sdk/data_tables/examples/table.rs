@@ -27,7 +27,7 @@ async fn main() -> azure_core::Result<()> {
         .nth(1)
         .expect("please specify the table name as first command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
+    let storage_credentials = StorageCredentials::access_key(account.clone(), access_key);
     let table_service = TableServiceClient::new(account, storage_credentials);
 
     let table_client = table_service.table_client(table_name);
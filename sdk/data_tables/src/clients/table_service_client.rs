@@ -76,6 +76,14 @@ impl TableServiceClientBuilder {
         self
     }
 
+    /// Set the telemetry options, such as an application ID prepended to the
+    /// `User-Agent` header.
+    #[must_use]
+    pub fn telemetry(mut self, telemetry: impl Into<azure_core::TelemetryOptions>) -> Self {
+        self.options = self.options.telemetry(telemetry);
+        self
+    }
+
     /// Override all of the client options.
     ///
     /// *Warning!*: This overrides all client options that have been previously set on this builder.
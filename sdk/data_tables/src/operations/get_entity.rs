@@ -94,7 +94,7 @@ where
             common_storage_response_headers: (&headers).try_into()?,
             metadata: get_entity_response_internal.metadata,
             entity: get_entity_response_internal.value,
-            etag: etag_from_headers(&headers)?.into(),
+            etag: etag_from_headers(&headers)?,
         })
     }
 }
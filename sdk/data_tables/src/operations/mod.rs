@@ -54,7 +54,7 @@ impl TryFrom<CollectedResponse> for OperationOnEntityResponse {
     fn try_from(response: CollectedResponse) -> azure_core::Result<Self> {
         Ok(OperationOnEntityResponse {
             common_storage_response_headers: response.headers().try_into()?,
-            etag: etag_from_headers(response.headers())?.into(),
+            etag: etag_from_headers(response.headers())?,
         })
     }
 }
@@ -92,7 +92,7 @@ where
 
         Ok(InsertEntityResponse {
             common_storage_response_headers: headers.try_into()?,
-            etag: etag_from_headers(headers)?.into(),
+            etag: etag_from_headers(headers)?,
             location: headers.get_optional_as(&headers::LOCATION)?,
             entity_with_metadata,
         })
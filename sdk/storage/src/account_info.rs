@@ -0,0 +1,173 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// The SKU of a storage account, i.e. its performance tier and redundancy, as
+/// returned in the `x-ms-sku-name` header.
+///
+/// ref: <https://docs.microsoft.com/rest/api/storageservices/get-account-information#response-headers>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SkuName {
+    StandardLrs,
+    StandardGrs,
+    StandardRagrs,
+    StandardZrs,
+    StandardGzrs,
+    StandardRagzrs,
+    PremiumLrs,
+    PremiumZrs,
+    /// A SKU unknown to this version of the crate.
+    Other(String),
+}
+
+impl SkuName {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SkuName::StandardLrs => "Standard_LRS",
+            SkuName::StandardGrs => "Standard_GRS",
+            SkuName::StandardRagrs => "Standard_RAGRS",
+            SkuName::StandardZrs => "Standard_ZRS",
+            SkuName::StandardGzrs => "Standard_GZRS",
+            SkuName::StandardRagzrs => "Standard_RAGZRS",
+            SkuName::PremiumLrs => "Premium_LRS",
+            SkuName::PremiumZrs => "Premium_ZRS",
+            SkuName::Other(sku_name) => sku_name,
+        }
+    }
+
+    /// Whether the account is backed by SSDs. Premium accounts have no access
+    /// tiers: the tiers of their page blobs are sizes, e.g. `P10`.
+    pub fn is_premium(&self) -> bool {
+        self.as_str().starts_with("Premium_")
+    }
+
+    /// Whether the data is replicated to a secondary region.
+    pub fn is_geo_redundant(&self) -> bool {
+        matches!(
+            self,
+            SkuName::StandardGrs
+                | SkuName::StandardRagrs
+                | SkuName::StandardGzrs
+                | SkuName::StandardRagzrs
+        )
+    }
+
+    /// Whether the secondary region can be read from, at the
+    /// `{account}-secondary` endpoints.
+    pub fn has_readable_secondary(&self) -> bool {
+        matches!(self, SkuName::StandardRagrs | SkuName::StandardRagzrs)
+    }
+}
+
+impl FromStr for SkuName {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Standard_LRS" => SkuName::StandardLrs,
+            "Standard_GRS" => SkuName::StandardGrs,
+            "Standard_RAGRS" => SkuName::StandardRagrs,
+            "Standard_ZRS" => SkuName::StandardZrs,
+            "Standard_GZRS" => SkuName::StandardGzrs,
+            "Standard_RAGZRS" => SkuName::StandardRagzrs,
+            "Premium_LRS" => SkuName::PremiumLrs,
+            "Premium_ZRS" => SkuName::PremiumZrs,
+            other => SkuName::Other(other.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for SkuName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The kind of a storage account, as returned in the `x-ms-account-kind`
+/// header.
+///
+/// ref: <https://docs.microsoft.com/azure/storage/common/storage-account-overview#types-of-storage-accounts>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AccountKind {
+    /// A legacy general-purpose v1 account.
+    Storage,
+    /// A general-purpose v2 account.
+    StorageV2,
+    /// A legacy account for block and append blobs only.
+    BlobStorage,
+    /// A premium account for block and append blobs only.
+    BlockBlobStorage,
+    /// A premium account for file shares only.
+    FileStorage,
+    /// A kind unknown to this version of the crate.
+    Other(String),
+}
+
+impl AccountKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AccountKind::Storage => "Storage",
+            AccountKind::StorageV2 => "StorageV2",
+            AccountKind::BlobStorage => "BlobStorage",
+            AccountKind::BlockBlobStorage => "BlockBlobStorage",
+            AccountKind::FileStorage => "FileStorage",
+            AccountKind::Other(account_kind) => account_kind,
+        }
+    }
+
+    /// Whether accounts of this kind can have blobs in the hot, cool and
+    /// archive access tiers, when they are not premium accounts.
+    pub fn supports_tiering(&self) -> bool {
+        matches!(self, AccountKind::StorageV2 | AccountKind::BlobStorage)
+    }
+}
+
+impl FromStr for AccountKind {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Storage" => AccountKind::Storage,
+            "StorageV2" => AccountKind::StorageV2,
+            "BlobStorage" => AccountKind::BlobStorage,
+            "BlockBlobStorage" => AccountKind::BlockBlobStorage,
+            "FileStorage" => AccountKind::FileStorage,
+            other => AccountKind::Other(other.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for AccountKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_info() {
+        let sku_name: SkuName = "Standard_RAGZRS".parse().unwrap();
+        assert_eq!(sku_name, SkuName::StandardRagzrs);
+        assert!(sku_name.is_geo_redundant());
+        assert!(sku_name.has_readable_secondary());
+        assert!(!sku_name.is_premium());
+
+        let sku_name: SkuName = "Premium_ZRS".parse().unwrap();
+        assert!(sku_name.is_premium());
+        assert!(!sku_name.is_geo_redundant());
+
+        let sku_name: SkuName = "Premium_GZRS".parse().unwrap();
+        assert_eq!(sku_name, SkuName::Other("Premium_GZRS".to_owned()));
+        assert!(sku_name.is_premium());
+        assert_eq!(sku_name.to_string(), "Premium_GZRS");
+
+        let account_kind: AccountKind = "StorageV2".parse().unwrap();
+        assert!(account_kind.supports_tiering());
+        assert!(!"Storage".parse::<AccountKind>().unwrap().supports_tiering());
+        assert_eq!(
+            "DataLake".parse::<AccountKind>().unwrap().as_str(),
+            "DataLake"
+        );
+    }
+}
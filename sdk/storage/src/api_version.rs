@@ -0,0 +1,238 @@
+use azure_core::{
+    error::{Error, ErrorKind},
+    headers::{self, HeaderName, Headers},
+    Context, Policy, PolicyResult, Request, StatusCode,
+};
+use std::sync::Arc;
+use url::Url;
+
+/// Error codes the service uses when it does not understand a header or query
+/// parameter, which is what happens when the request's `x-ms-version` predates
+/// the feature.
+const VERSION_ERROR_CODES: &[&str] = &[
+    "InvalidHeaderValue",
+    "InvalidQueryParameterValue",
+    "UnsupportedHeader",
+    "UnsupportedQueryParameter",
+    "FeatureVersionMismatch",
+];
+
+/// A feature of the storage services which is not available everywhere: from
+/// a given service version onwards, or not at all on some endpoints, see
+/// [`EndpointProfile`](crate::EndpointProfile).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Feature {
+    BlobTags,
+    BlobTagConditions,
+    FindBlobsByTags,
+    BlobVersions,
+    BlobExpiry,
+    PermanentDelete,
+    AccessTiers,
+    SoftDelete,
+}
+
+impl Feature {
+    pub const ALL: &'static [Feature] = &[
+        Feature::BlobTags,
+        Feature::BlobTagConditions,
+        Feature::FindBlobsByTags,
+        Feature::BlobVersions,
+        Feature::BlobExpiry,
+        Feature::PermanentDelete,
+        Feature::AccessTiers,
+        Feature::SoftDelete,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::BlobTags => "blob tags",
+            Feature::BlobTagConditions => "blob tag conditions",
+            Feature::FindBlobsByTags => "find blobs by tags",
+            Feature::BlobVersions => "blob versions",
+            Feature::BlobExpiry => "blob expiry",
+            Feature::PermanentDelete => "permanent delete",
+            Feature::AccessTiers => "access tiers",
+            Feature::SoftDelete => "soft delete",
+        }
+    }
+
+    /// The first service version with the feature, if it came after the
+    /// oldest version the crate supports.
+    pub fn minimum_api_version(&self) -> Option<&'static str> {
+        match self {
+            Feature::BlobTags
+            | Feature::BlobTagConditions
+            | Feature::FindBlobsByTags
+            | Feature::BlobVersions => Some("2019-12-12"),
+            Feature::BlobExpiry | Feature::PermanentDelete => Some("2020-02-10"),
+            Feature::AccessTiers | Feature::SoftDelete => None,
+        }
+    }
+
+    /// Whether the service version has the feature. Service versions are
+    /// ISO 8601 dates, so they sort lexicographically.
+    pub fn is_supported_by(&self, api_version: &str) -> bool {
+        self.minimum_api_version()
+            .map_or(true, |minimum| api_version >= minimum)
+    }
+
+    /// Whether the request uses the feature.
+    pub(crate) fn is_used_by(&self, url: &Url, headers: &Headers) -> bool {
+        match self {
+            Feature::BlobTags => {
+                has_query(url, "comp", Some("tags")) || has_header(headers, &headers::TAGS)
+            }
+            Feature::BlobTagConditions => has_header(headers, &headers::IF_TAGS),
+            Feature::FindBlobsByTags => has_query(url, "comp", Some("blobs")),
+            Feature::BlobVersions => has_query(url, "versionid", None),
+            Feature::BlobExpiry => has_query(url, "comp", Some("expiry")),
+            Feature::PermanentDelete => {
+                has_query(url, "deletetype", Some("permanent"))
+                    || has_header(headers, &headers::DELETE_TYPE_PERMANENT)
+            }
+            Feature::AccessTiers => {
+                has_query(url, "comp", Some("tier"))
+                    || has_header(headers, &headers::BLOB_ACCESS_TIER)
+            }
+            Feature::SoftDelete => {
+                has_query(url, "comp", Some("undelete"))
+                    || url.query_pairs().any(|(k, v)| {
+                        k == "include" && v.split(',').any(|include| include == "deleted")
+                    })
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Feature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn has_query(url: &Url, key: &str, value: Option<&str>) -> bool {
+    url.query_pairs()
+        .any(|(k, v)| k == key && value.map_or(true, |value| v == value))
+}
+
+fn has_header(headers: &Headers, name: &HeaderName) -> bool {
+    headers.get_optional_str(name).is_some()
+}
+
+/// Finds the first feature used by the request which needs a newer service
+/// version than `api_version`.
+fn unsupported_feature(url: &Url, headers: &Headers, api_version: &str) -> Option<Feature> {
+    Feature::ALL
+        .iter()
+        .copied()
+        .find(|feature| !feature.is_supported_by(api_version) && feature.is_used_by(url, headers))
+}
+
+/// Converts generic `400 Bad Request` responses caused by a pinned service
+/// version that is too old into `ErrorKind::FeatureNotSupportedByApiVersion`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ApiVersionPolicy;
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for ApiVersionPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        result.map_err(|error| map_version_error(request, error))
+    }
+}
+
+fn map_version_error(request: &Request, error: Error) -> Error {
+    let is_version_error = matches!(
+        error.kind(),
+        ErrorKind::HttpResponse {
+            status: StatusCode::BadRequest,
+            error_code: Some(error_code),
+        } if VERSION_ERROR_CODES.contains(&error_code.as_str())
+    );
+    if !is_version_error {
+        return error;
+    }
+
+    let api_version = match request.headers().get_optional_str(&headers::VERSION) {
+        Some(api_version) => api_version.to_owned(),
+        None => return error,
+    };
+
+    match unsupported_feature(request.url(), request.headers(), &api_version) {
+        Some(feature) => {
+            // only features with a minimum version are unsupported by one
+            let minimum_api_version = feature.minimum_api_version().unwrap_or_default();
+            let message = format!(
+                "{feature} requires service version {minimum_api_version} or later, but the request used {api_version}"
+            );
+            Error::full(
+                ErrorKind::FeatureNotSupportedByApiVersion {
+                    feature: feature.name().to_owned(),
+                    api_version,
+                    minimum_api_version: minimum_api_version.to_owned(),
+                },
+                error,
+                message,
+            )
+        }
+        None => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::Method;
+
+    fn new_request(url: &str, api_version: &str) -> Request {
+        let mut request = Request::new(Url::parse(url).unwrap(), Method::Get);
+        request.insert_header(headers::VERSION, api_version.to_owned());
+        request
+    }
+
+    fn bad_request(error_code: &str) -> Error {
+        ErrorKind::http_response(StatusCode::BadRequest, Some(error_code.to_owned())).into_error()
+    }
+
+    #[test]
+    fn test_maps_unsupported_feature() {
+        let request = new_request(
+            "https://account.blob.core.windows.net/container/blob?comp=tags",
+            "2019-07-07",
+        );
+        let error = map_version_error(&request, bad_request("InvalidQueryParameterValue"));
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::FeatureNotSupportedByApiVersion {
+                feature: "blob tags".to_owned(),
+                api_version: "2019-07-07".to_owned(),
+                minimum_api_version: "2019-12-12".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_keeps_unrelated_errors() {
+        let request = new_request(
+            "https://account.blob.core.windows.net/container/blob?comp=tags",
+            "2019-12-12",
+        );
+        let error = map_version_error(&request, bad_request("InvalidQueryParameterValue"));
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+
+        let request = new_request(
+            "https://account.blob.core.windows.net/container/blob?comp=tags",
+            "2019-07-07",
+        );
+        let error = map_version_error(&request, bad_request("AuthenticationFailed"));
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+    }
+}
@@ -1,10 +1,11 @@
 use crate::clients::ServiceType;
 use crate::StorageCredentials;
-use azure_core::error::{ErrorKind, ResultExt};
-use azure_core::Method;
-use azure_core::{headers::*, Context, Policy, PolicyResult, Request};
+use azure_core::error::{Error, ErrorKind, ResultExt};
+use azure_core::{headers::*, Context, Policy, PolicyResult, Request, Response};
+use azure_core::{Method, StatusCode};
 use std::borrow::Cow;
 use std::sync::Arc;
+use time::OffsetDateTime;
 use url::Url;
 
 const STORAGE_TOKEN_SCOPE: &str = "https://storage.azure.com/";
@@ -35,51 +36,136 @@ impl Policy for AuthorizationPolicy {
             !next.is_empty(),
             "Authorization policies cannot be the last policy of a pipeline"
         );
-        let request = match &self.credentials {
-            StorageCredentials::Key(account, key) => {
-                if !request.url().query_pairs().any(|(k, _)| &*k == "sig") {
-                    let auth = generate_authorization(
-                        request.headers(),
-                        request.url(),
-                        request.method(),
-                        account,
-                        key,
-                        ctx.get()
-                            .expect("ServiceType must be in the Context at this point"),
-                    )?;
-                    request.insert_header(AUTHORIZATION, auth)
+        let credentials = match &self.credentials {
+            StorageCredentials::Fallback(credentials) => credentials,
+            StorageCredentials::KeyWithSecondary(account, _, secondary) => {
+                let unauthorized = request.clone();
+                authorize(request, &self.credentials, ctx.get()).await?;
+                let response = next[0].send(ctx, request, &next[1..]).await?;
+                if !is_authentication_failure(&response) {
+                    return Ok(response);
                 }
-                request
+                debug!("request rejected with the primary key, signing it with the secondary key");
+                *request = unauthorized;
+                let secondary = StorageCredentials::Key(account.clone(), secondary.clone());
+                authorize(request, &secondary, ctx.get()).await?;
+                return next[0].send(ctx, request, &next[1..]).await;
             }
-            StorageCredentials::SASToken(query_pairs) => {
-                request
-                    .url_mut()
-                    .query_pairs_mut()
-                    .extend_pairs(query_pairs);
-
-                request
+            credentials => {
+                authorize(request, credentials, ctx.get()).await?;
+                return next[0].send(ctx, request, &next[1..]).await;
             }
-            StorageCredentials::BearerToken(token) => {
-                request.insert_header(AUTHORIZATION, format!("Bearer {token}"));
-                request
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let usable: Vec<&StorageCredentials> = credentials
+            .iter()
+            .filter(|credentials| credentials.sas_expiry().map_or(true, |expiry| expiry > now))
+            .collect();
+        let unauthorized = request.clone();
+        for (index, credentials) in usable.iter().enumerate() {
+            *request = unauthorized.clone();
+            authorize(request, credentials, ctx.get()).await?;
+            let response = next[0].send(ctx, request, &next[1..]).await?;
+            if response.status() != StatusCode::Forbidden || index + 1 == usable.len() {
+                return Ok(response);
             }
-            StorageCredentials::TokenCredential(token_credential) => {
-                let bearer_token = token_credential
-                    .get_token(STORAGE_TOKEN_SCOPE)
-                    .await
-                    .context(ErrorKind::Credential, "failed to get bearer token")?;
-
-                request.insert_header(
-                    AUTHORIZATION,
-                    format!("Bearer {}", bearer_token.token.secret()),
-                );
-                request
+            debug!(
+                "request rejected with {:?}, falling back to the next credential",
+                credentials
+            );
+        }
+        Err(Error::message(
+            ErrorKind::Credential,
+            "none of the fallback credentials is usable",
+        ))
+    }
+}
+
+/// Whether the service rejected the account key a request was signed with,
+/// e.g. after it was regenerated. Account keys have every permission, so other
+/// rejections would be the same with the secondary key.
+fn is_authentication_failure(response: &Response) -> bool {
+    response.status() == StatusCode::Forbidden
+        && response.headers().get_optional_str(&ERROR_CODE) == Some("AuthenticationFailed")
+}
+
+/// Authorizes a request which is not sent through a pipeline, e.g. a
+/// sub-request of a batch, as the pipeline would. Fallback credentials
+/// authorize it with the first of them whose SAS has not expired, and keys
+/// with a secondary key with the primary key.
+pub async fn authorize_request(
+    request: &mut Request,
+    credentials: &StorageCredentials,
+    service_type: ServiceType,
+) -> azure_core::Result<()> {
+    let credentials = match credentials {
+        StorageCredentials::Fallback(credentials) => {
+            let now = OffsetDateTime::now_utc();
+            credentials
+                .iter()
+                .find(|credentials| credentials.sas_expiry().map_or(true, |expiry| expiry > now))
+                .ok_or_else(|| {
+                    Error::message(
+                        ErrorKind::Credential,
+                        "none of the fallback credentials is usable",
+                    )
+                })?
+        }
+        credentials => credentials,
+    };
+    authorize(request, credentials, Some(&service_type)).await
+}
+
+async fn authorize(
+    request: &mut Request,
+    credentials: &StorageCredentials,
+    service_type: Option<&ServiceType>,
+) -> azure_core::Result<()> {
+    match credentials {
+        StorageCredentials::Key(account, key)
+        | StorageCredentials::KeyWithSecondary(account, key, _) => {
+            if !request.url().query_pairs().any(|(k, _)| &*k == "sig") {
+                let auth = generate_authorization(
+                    request.headers(),
+                    request.url(),
+                    request.method(),
+                    account,
+                    key.secret(),
+                    service_type.expect("ServiceType must be in the Context at this point"),
+                )?;
+                request.insert_header(AUTHORIZATION, auth)
             }
-            StorageCredentials::Anonymous => request,
-        };
+        }
+        StorageCredentials::SASToken(query_pairs) => {
+            request
+                .url_mut()
+                .query_pairs_mut()
+                .extend_pairs(query_pairs.iter().map(|(k, v)| (k, v.secret())));
+        }
+        StorageCredentials::BearerToken(token) => {
+            request.insert_header(AUTHORIZATION, format!("Bearer {}", token.secret()));
+        }
+        StorageCredentials::TokenCredential(token_credential) => {
+            let bearer_token = token_credential
+                .get_token(STORAGE_TOKEN_SCOPE)
+                .await
+                .context(ErrorKind::Credential, "failed to get bearer token")?;
 
-        next[0].send(ctx, request, &next[1..]).await
+            request.insert_header(
+                AUTHORIZATION,
+                format!("Bearer {}", bearer_token.token.secret()),
+            );
+        }
+        StorageCredentials::Anonymous => {}
+        StorageCredentials::Fallback(_) => {
+            return Err(Error::message(
+                ErrorKind::Credential,
+                "fallback credentials cannot be nested",
+            ))
+        }
     }
+    Ok(())
 }
 
 fn generate_authorization(
@@ -224,3 +310,171 @@ fn lexy_sort<'a>(
     values.sort_unstable();
     values
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AddressingMode, CloudLocation};
+
+    #[test]
+    fn test_canonicalized_resource_per_addressing_mode() -> azure_core::Result<()> {
+        let virtual_host = CloudLocation::Public {
+            account: "myaccount".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+        };
+        let url = virtual_host
+            .url(ServiceType::Blob)?
+            .join("container/blob")?;
+        assert_eq!(
+            canonicalized_resource("myaccount", &url),
+            "/myaccount/container/blob"
+        );
+
+        let path_style = CloudLocation::Custom {
+            uri: "http://localhost:8080".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+            addressing_mode: AddressingMode::PathStyle {
+                account: "myaccount".to_owned(),
+            },
+        };
+        let url = Url::parse(&format!(
+            "{}/container/blob?comp=metadata",
+            path_style.url(ServiceType::Blob)?
+        ))?;
+        assert_eq!(
+            canonicalized_resource("myaccount", &url),
+            "/myaccount/myaccount/container/blob\ncomp:metadata"
+        );
+
+        Ok(())
+    }
+
+    /// Rejects bearer tokens with an error code.
+    #[derive(Debug)]
+    struct RejectBearer(&'static str);
+
+    #[async_trait::async_trait]
+    impl Policy for RejectBearer {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            let mut headers = Headers::new();
+            let status = if request.headers().get_optional_str(&AUTHORIZATION).is_some() {
+                headers.insert(ERROR_CODE, self.0);
+                StatusCode::Forbidden
+            } else {
+                StatusCode::Ok
+            };
+            Ok(azure_core::Response::new(
+                status,
+                headers,
+                Box::pin(futures::stream::empty()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_on_forbidden() -> azure_core::Result<()> {
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(RejectBearer("AuthenticationFailed"))];
+        let url = Url::parse("https://myaccount.blob.core.windows.net/container/blob")?;
+
+        let policy = AuthorizationPolicy::new(StorageCredentials::fallback([
+            StorageCredentials::bearer_token("token"),
+            StorageCredentials::sas_token("sv=2021-08-06&sig=signature")?,
+        ]));
+        let mut request = Request::new(url.clone(), Method::Get);
+        let response = policy.send(&Context::new(), &mut request, &next).await?;
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert!(request.headers().get_optional_str(&AUTHORIZATION).is_none());
+        assert!(request.url().query_pairs().any(|(k, _)| k == "sig"));
+
+        // the token was accepted but lacks the permission, which the SAS has
+        let forbidden: Vec<Arc<dyn Policy>> =
+            vec![Arc::new(RejectBearer("AuthorizationPermissionMismatch"))];
+        let mut request = Request::new(url.clone(), Method::Get);
+        let response = policy
+            .send(&Context::new(), &mut request, &forbidden)
+            .await?;
+        assert_eq!(response.status(), StatusCode::Ok);
+        assert!(request.headers().get_optional_str(&AUTHORIZATION).is_none());
+        assert!(request.url().query_pairs().any(|(k, _)| k == "sig"));
+
+        let policy = AuthorizationPolicy::new(StorageCredentials::fallback([
+            StorageCredentials::bearer_token("token"),
+        ]));
+        let mut request = Request::new(url, Method::Get);
+        let response = policy.send(&Context::new(), &mut request, &next).await?;
+        assert_eq!(response.status(), StatusCode::Forbidden);
+
+        Ok(())
+    }
+
+    /// Accepts only requests signed with the given key.
+    #[derive(Debug)]
+    struct AcceptKey(String);
+
+    #[async_trait::async_trait]
+    impl Policy for AcceptKey {
+        async fn send(
+            &self,
+            ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            let expected = generate_authorization(
+                request.headers(),
+                request.url(),
+                request.method(),
+                "myaccount",
+                &self.0,
+                ctx.get().unwrap(),
+            )?;
+            let mut headers = Headers::new();
+            let status =
+                if request.headers().get_optional_str(&AUTHORIZATION) == Some(expected.as_str()) {
+                    StatusCode::Ok
+                } else {
+                    headers.insert(ERROR_CODE, "AuthenticationFailed");
+                    StatusCode::Forbidden
+                };
+            Ok(azure_core::Response::new(
+                status,
+                headers,
+                Box::pin(futures::stream::empty()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_secondary_key() -> azure_core::Result<()> {
+        let secondary = base64::encode("secondary");
+        let policy = AuthorizationPolicy::new(StorageCredentials::access_key_with_secondary(
+            "myaccount",
+            base64::encode("primary"),
+            secondary.clone(),
+        ));
+        let mut ctx = Context::new();
+        ctx.insert(ServiceType::Blob);
+        let url = Url::parse("https://myaccount.blob.core.windows.net/container/blob")?;
+
+        // the primary key was regenerated
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(AcceptKey(secondary))];
+        let mut request = Request::new(url.clone(), Method::Get);
+        let response = policy.send(&ctx, &mut request, &next).await?;
+        assert_eq!(response.status(), StatusCode::Ok);
+
+        // both keys were regenerated
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(AcceptKey(base64::encode("other")))];
+        let mut request = Request::new(url, Method::Get);
+        let response = policy.send(&ctx, &mut request, &next).await?;
+        assert_eq!(response.status(), StatusCode::Forbidden);
+        assert_eq!(
+            response.headers().get_optional_str(&ERROR_CODE),
+            Some("AuthenticationFailed")
+        );
+        Ok(())
+    }
+}
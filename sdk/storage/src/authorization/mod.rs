@@ -1,12 +1,15 @@
 mod authorization_policy;
+mod sas_expiry_policy;
 
 use azure_core::{
-    auth::TokenCredential,
+    auth::{Secret, TokenCredential},
     error::{ErrorKind, ResultExt},
 };
 use std::sync::Arc;
 
+pub use authorization_policy::authorize_request;
 pub(crate) use authorization_policy::AuthorizationPolicy;
+pub(crate) use sas_expiry_policy::SasExpiryPolicy;
 
 /// Credentials for accessing a storage account.
 ///
@@ -20,11 +23,17 @@ pub(crate) use authorization_policy::AuthorizationPolicy;
 /// ```
 #[derive(Clone)]
 pub enum StorageCredentials {
-    Key(String, String),
-    SASToken(Vec<(String, String)>),
-    BearerToken(String),
+    Key(String, Secret),
+    /// An account key with the account's other key, tried when the first is
+    /// rejected.
+    KeyWithSecondary(String, Secret, Secret),
+    SASToken(Vec<(String, Secret)>),
+    BearerToken(Secret),
     TokenCredential(Arc<dyn TokenCredential>),
     Anonymous,
+    /// Credentials tried in order, moving on to the next one when a request is
+    /// rejected with `403 Forbidden`.
+    Fallback(Vec<StorageCredentials>),
 }
 
 impl StorageCredentials {
@@ -39,11 +48,37 @@ impl StorageCredentials {
     pub fn access_key<A, K>(account: A, key: K) -> Self
     where
         A: Into<String>,
-        K: Into<String>,
+        K: Into<Secret>,
     {
         Self::Key(account.into(), key.into())
     }
 
+    /// Create an Access Key based credential which falls back to the
+    /// account's other key
+    ///
+    /// Requests are signed with `primary`. If the service rejects them with
+    /// `403 Forbidden` and the `AuthenticationFailed` error code, because the
+    /// key was just regenerated, they are sent once more signed with
+    /// `secondary`. Rotating the keys one after the other then needs no
+    /// downtime: regenerate the secondary key, deploy it as the primary with
+    /// the former primary as secondary, and regenerate the former primary.
+    ///
+    /// Shared access signatures are signed with `primary`.
+    ///
+    /// ```
+    /// use azure_storage::prelude::*;
+    /// let storage_credentials =
+    ///     StorageCredentials::access_key_with_secondary("my_account", "KEY1", "KEY2");
+    /// ```
+    pub fn access_key_with_secondary<A, K, S>(account: A, primary: K, secondary: S) -> Self
+    where
+        A: Into<String>,
+        K: Into<Secret>,
+        S: Into<Secret>,
+    {
+        Self::KeyWithSecondary(account.into(), primary.into(), secondary.into())
+    }
+
     /// Create a Shared Access Signature (SAS) token based credential
     ///
     /// SAS tokens are HTTP query strings that provide delegated access to
@@ -57,7 +92,24 @@ impl StorageCredentials {
         S: AsRef<str>,
     {
         let params = get_sas_token_parms(token.as_ref())?;
-        Ok(Self::SASToken(params))
+        let credentials = Self::SASToken(params);
+        if let Some(expiry) = credentials.sas_expiry() {
+            if expiry <= time::OffsetDateTime::now_utc() {
+                warn!("the SAS token expired at {}", expiry);
+            }
+        }
+        Ok(credentials)
+    }
+
+    /// The signed expiry (`se`) of a SAS token credential.
+    pub fn sas_expiry(&self) -> Option<time::OffsetDateTime> {
+        match self {
+            Self::SASToken(params) => params
+                .iter()
+                .find(|(name, _)| name == "se")
+                .and_then(|(_, value)| sas_expiry_policy::parse_sas_expiry(value.secret())),
+            _ => None,
+        }
     }
 
     /// Create an Bearer Token based credential
@@ -72,7 +124,7 @@ impl StorageCredentials {
     /// ref: <https://docs.microsoft.com/rest/api/storageservices/authorize-with-azure-active-directory>
     pub fn bearer_token<T>(token: T) -> Self
     where
-        T: Into<String>,
+        T: Into<Secret>,
     {
         Self::BearerToken(token.into())
     }
@@ -112,6 +164,44 @@ impl StorageCredentials {
     pub fn anonymous() -> Self {
         Self::Anonymous
     }
+
+    /// Create a credential which tries each of `credentials` in order
+    ///
+    /// Requests are authorized with the first credential. If the service
+    /// rejects it with `403 Forbidden`, e.g. because a token expired or a
+    /// principal lacks a role assignment the next credential has, the request
+    /// is sent again with the next one, and so on, and the last response is
+    /// returned. This allows moving an application from one credential to
+    /// another without downtime, e.g. from a SAS token about to be revoked to
+    /// Azure AD.
+    ///
+    /// Nested fallback credentials are flattened, and so are keys with a
+    /// secondary key, into the two keys.
+    ///
+    /// ```
+    /// use azure_storage::prelude::*;
+    /// let storage_credentials = StorageCredentials::fallback([
+    ///     StorageCredentials::bearer_token("TOKEN"),
+    ///     StorageCredentials::sas_token("sv=2021-08-06&sig=SIGNATURE").unwrap(),
+    /// ]);
+    /// ```
+    pub fn fallback<I>(credentials: I) -> Self
+    where
+        I: IntoIterator<Item = StorageCredentials>,
+    {
+        let mut flattened = Vec::new();
+        for credential in credentials {
+            match credential {
+                Self::Fallback(nested) => flattened.extend(nested),
+                Self::KeyWithSecondary(account, primary, secondary) => {
+                    flattened.push(Self::Key(account.clone(), primary));
+                    flattened.push(Self::Key(account, secondary));
+                }
+                credential => flattened.push(credential),
+            }
+        }
+        Self::Fallback(flattened)
+    }
 }
 
 impl std::fmt::Debug for StorageCredentials {
@@ -121,6 +211,10 @@ impl std::fmt::Debug for StorageCredentials {
                 .debug_struct("StorageCredentials")
                 .field("credential", &"Key")
                 .finish(),
+            StorageCredentials::KeyWithSecondary(_, _, _) => f
+                .debug_struct("StorageCredentials")
+                .field("credential", &"KeyWithSecondary")
+                .finish(),
             StorageCredentials::SASToken(_) => f
                 .debug_struct("StorageCredentials")
                 .field("credential", &"SASToken")
@@ -137,6 +231,11 @@ impl std::fmt::Debug for StorageCredentials {
                 .debug_struct("StorageCredentials")
                 .field("credential", &"Anonymous")
                 .finish(),
+            StorageCredentials::Fallback(credentials) => f
+                .debug_struct("StorageCredentials")
+                .field("credential", &"Fallback")
+                .field("credentials", credentials)
+                .finish(),
         }
     }
 }
@@ -147,7 +246,7 @@ impl From<Arc<dyn TokenCredential>> for StorageCredentials {
     }
 }
 
-fn get_sas_token_parms(sas_token: &str) -> azure_core::Result<Vec<(String, String)>> {
+fn get_sas_token_parms(sas_token: &str) -> azure_core::Result<Vec<(String, Secret)>> {
     // Any base url will do: we just need to parse the SAS token
     // to get its query pairs.
     let base_url = url::Url::parse("https://blob.core.windows.net").unwrap();
@@ -161,12 +260,10 @@ fn get_sas_token_parms(sas_token: &str) -> azure_core::Result<Vec<(String, Strin
     } else {
         url.parse(&format!("?{sas_token}"))
     }
-    .with_context(ErrorKind::DataConversion, || {
-        format!("failed to parse SAS token: {sas_token}")
-    })?;
+    .context(ErrorKind::DataConversion, "failed to parse SAS token")?;
 
     Ok(url
         .query_pairs()
-        .map(|p| (String::from(p.0), String::from(p.1)))
+        .map(|p| (String::from(p.0), Secret::new(p.1)))
         .collect())
 }
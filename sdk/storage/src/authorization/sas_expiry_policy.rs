@@ -0,0 +1,135 @@
+use azure_core::{
+    date,
+    error::{Error, ErrorKind},
+    Context, Policy, PolicyResult, Request, StatusCode,
+};
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+/// Parses the signed expiry (`se`) of a SAS token, which is either a UTC date
+/// or an ISO 8601 date and time.
+pub(crate) fn parse_sas_expiry(value: &str) -> Option<OffsetDateTime> {
+    date::parse_rfc3339(value)
+        .or_else(|_| date::parse_rfc3339(&format!("{value}T00:00:00Z")))
+        .ok()
+}
+
+/// Fails requests made with an expired SAS token before they are sent, and
+/// turns the service's `403 AuthenticationFailed` once the token expired, e.g.
+/// while the request was being retried, into an `ErrorKind::SasExpired`
+/// error.
+#[derive(Debug, Clone)]
+pub(crate) struct SasExpiryPolicy {
+    expiry: Option<OffsetDateTime>,
+}
+
+impl SasExpiryPolicy {
+    pub(crate) fn new(expiry: Option<OffsetDateTime>) -> Self {
+        Self { expiry }
+    }
+
+    fn map_error(&self, error: Error, now: OffsetDateTime) -> Error {
+        let expiry = match self.expiry {
+            Some(expiry) if expiry <= now => expiry,
+            _ => return error,
+        };
+        // the service does not say why it failed to authenticate a request,
+        // but past its expiry a token is not valid anyway
+        let is_authentication_failure = matches!(
+            error.kind(),
+            ErrorKind::HttpResponse {
+                status: StatusCode::Forbidden,
+                error_code: Some(error_code),
+            } if error_code == "AuthenticationFailed"
+        );
+        if is_authentication_failure {
+            Error::full(ErrorKind::SasExpired { expiry }, error, expired(expiry))
+        } else {
+            error
+        }
+    }
+}
+
+fn expired(expiry: OffsetDateTime) -> String {
+    format!("the SAS token expired at {}", date::to_rfc3339(&expiry))
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for SasExpiryPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if let Some(expiry) = self.expiry {
+            if expiry <= OffsetDateTime::now_utc() {
+                return Err(Error::message(
+                    ErrorKind::SasExpired { expiry },
+                    expired(expiry),
+                ));
+            }
+        }
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        result.map_err(|error| self.map_error(error, OffsetDateTime::now_utc()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sas_expiry() {
+        assert_eq!(
+            parse_sas_expiry("2022-08-05T14:32:11Z"),
+            Some(date::parse_rfc3339("2022-08-05T14:32:11Z").unwrap())
+        );
+        assert_eq!(
+            parse_sas_expiry("2022-08-05"),
+            Some(date::parse_rfc3339("2022-08-05T00:00:00Z").unwrap())
+        );
+        assert_eq!(parse_sas_expiry("yesterday"), None);
+    }
+
+    fn forbidden(error_code: &str) -> Error {
+        ErrorKind::http_response(StatusCode::Forbidden, Some(error_code.into())).into_error()
+    }
+
+    #[test]
+    fn test_map_error() {
+        let expiry = date::parse_rfc3339("2022-08-05T14:32:11Z").unwrap();
+        let policy = SasExpiryPolicy::new(Some(expiry));
+        let after = expiry + time::Duration::seconds(1);
+
+        let error = policy.map_error(forbidden("AuthenticationFailed"), after);
+        assert_eq!(error.kind(), &ErrorKind::SasExpired { expiry });
+        // the response of the service is kept as the source
+        assert!(std::error::Error::source(&error).is_some());
+
+        let before = expiry - time::Duration::seconds(1);
+        let error = policy.map_error(forbidden("AuthenticationFailed"), before);
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+        let error = policy.map_error(forbidden("AuthorizationFailure"), after);
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+        let error = SasExpiryPolicy::new(None).map_error(forbidden("AuthenticationFailed"), after);
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_expired_before_sending() {
+        let expiry = date::parse_rfc3339("2022-08-05T14:32:11Z").unwrap();
+        let mut request = Request::new(
+            "https://account.blob.core.windows.net/container"
+                .parse()
+                .unwrap(),
+            azure_core::Method::Get,
+        );
+        let error = SasExpiryPolicy::new(Some(expiry))
+            .send(&Context::new(), &mut request, &[])
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::SasExpired { expiry });
+    }
+}
@@ -0,0 +1,308 @@
+use azure_core::{
+    error::{Error, ErrorKind},
+    Context, Policy, PolicyResult, Request, StatusCode,
+};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// Statuses which indicate the account is throttled or degraded.
+const FAILURE_STATUSES: &[StatusCode] = &[
+    StatusCode::RequestTimeout,
+    StatusCode::TooManyRequests,
+    StatusCode::InternalServerError,
+    StatusCode::BadGateway,
+    StatusCode::ServiceUnavailable,
+    StatusCode::GatewayTimeout,
+];
+
+/// Options for a [`CircuitBreakerPolicy`].
+///
+/// The circuit opens once at least `minimum_requests` of the last
+/// `window_size` attempts were made and the share of them that failed reaches
+/// `failure_rate_threshold`. While open every request fails immediately. After
+/// `cool_down` the circuit is half-open: up to `half_open_probes` requests are
+/// let through, and if all of them succeed the circuit closes again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerOptions {
+    failure_rate_threshold: f64,
+    window_size: usize,
+    minimum_requests: usize,
+    cool_down: Duration,
+    half_open_probes: usize,
+}
+
+impl Default for CircuitBreakerOptions {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold: 0.5,
+            window_size: 20,
+            minimum_requests: 10,
+            cool_down: Duration::from_secs(30),
+            half_open_probes: 1,
+        }
+    }
+}
+
+impl CircuitBreakerOptions {
+    setters! {
+        failure_rate_threshold: f64 => failure_rate_threshold,
+        window_size: usize => window_size,
+        minimum_requests: usize => minimum_requests,
+        cool_down: Duration => cool_down,
+        half_open_probes: usize => half_open_probes,
+    }
+}
+
+/// The error returned, as the source of an `ErrorKind::Other` error, for
+/// requests rejected while the circuit is open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerOpen {
+    /// Time left until the circuit lets probe requests through again.
+    pub retry_after: Duration,
+}
+
+impl std::fmt::Display for CircuitBreakerOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "circuit breaker is open, retry after {}ms",
+            self.retry_after.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for CircuitBreakerOpen {}
+
+#[derive(Debug)]
+enum State {
+    Closed { outcomes: VecDeque<bool> },
+    Open { until: OffsetDateTime },
+    HalfOpen { in_flight: usize, succeeded: usize },
+}
+
+/// A per-retry policy which stops sending requests to an account which keeps
+/// failing with throttling or server errors.
+///
+/// It sits below the retry policy, so every attempt counts towards the failure
+/// rate and retries stop as soon as the circuit opens.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerPolicy {
+    options: CircuitBreakerOptions,
+    state: Arc<Mutex<State>>,
+}
+
+impl CircuitBreakerPolicy {
+    pub fn new(options: CircuitBreakerOptions) -> Self {
+        Self {
+            options,
+            state: Arc::new(Mutex::new(State::Closed {
+                outcomes: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Decides whether a request may be sent now.
+    fn acquire(&self, now: OffsetDateTime) -> Result<Permit<'_>, CircuitBreakerOpen> {
+        let mut state = self.state.lock().unwrap();
+        let probe = match &mut *state {
+            State::Closed { .. } => false,
+            State::Open { until } if now < *until => {
+                return Err(CircuitBreakerOpen {
+                    retry_after: (*until - now).try_into().unwrap_or_default(),
+                })
+            }
+            State::Open { .. } => {
+                debug!("circuit breaker cool down elapsed, probing");
+                *state = State::HalfOpen {
+                    in_flight: 1,
+                    succeeded: 0,
+                };
+                true
+            }
+            State::HalfOpen { in_flight, .. } if *in_flight < self.options.half_open_probes => {
+                *in_flight += 1;
+                true
+            }
+            State::HalfOpen { .. } => {
+                return Err(CircuitBreakerOpen {
+                    retry_after: Duration::ZERO,
+                })
+            }
+        };
+        Ok(Permit {
+            policy: self,
+            probe,
+            recorded: false,
+        })
+    }
+
+    /// Records the outcome of a request which `acquire` let through, as a
+    /// probe of the half-open circuit or not.
+    fn record(&self, success: bool, probe: bool, now: OffsetDateTime) {
+        let mut state = self.state.lock().unwrap();
+        let open = State::Open {
+            until: now + self.options.cool_down,
+        };
+        match &mut *state {
+            State::Closed { outcomes } => {
+                outcomes.push_back(success);
+                while outcomes.len() > self.options.window_size.max(1) {
+                    outcomes.pop_front();
+                }
+                let failures = outcomes.iter().filter(|success| !**success).count();
+                if outcomes.len() >= self.options.minimum_requests
+                    && failures as f64
+                        >= self.options.failure_rate_threshold * outcomes.len() as f64
+                {
+                    warn!(
+                        "circuit breaker opened after {} failures in {} requests",
+                        failures,
+                        outcomes.len()
+                    );
+                    *state = open;
+                }
+            }
+            State::HalfOpen { .. } if probe && !success => {
+                warn!("circuit breaker probe failed, reopening");
+                *state = open;
+            }
+            State::HalfOpen {
+                in_flight,
+                succeeded,
+            } if probe => {
+                *in_flight = in_flight.saturating_sub(1);
+                *succeeded += 1;
+                if *succeeded >= self.options.half_open_probes.max(1) {
+                    debug!("circuit breaker closed");
+                    *state = State::Closed {
+                        outcomes: VecDeque::new(),
+                    };
+                }
+            }
+            // a request which started before the circuit opened
+            State::HalfOpen { .. } | State::Open { .. } => {}
+        }
+    }
+
+    /// Frees the slot of a probe which ended without an outcome, e.g. because
+    /// its request was cancelled.
+    fn release(&self) {
+        if let State::HalfOpen { in_flight, .. } = &mut *self.state.lock().unwrap() {
+            *in_flight = in_flight.saturating_sub(1);
+        }
+    }
+}
+
+/// A request let through by the circuit breaker. Dropping it without
+/// recording an outcome, e.g. when the request future is cancelled, frees its
+/// probe slot so that the half-open circuit does not stay stuck.
+#[derive(Debug)]
+struct Permit<'a> {
+    policy: &'a CircuitBreakerPolicy,
+    probe: bool,
+    recorded: bool,
+}
+
+impl Permit<'_> {
+    fn record(mut self, success: bool, now: OffsetDateTime) {
+        self.recorded = true;
+        self.policy.record(success, self.probe, now);
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            self.policy.release();
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for CircuitBreakerPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let permit = self
+            .acquire(OffsetDateTime::now_utc())
+            .map_err(|open| Error::new(ErrorKind::Other, open))?;
+
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        let success = match &result {
+            Ok(response) => !FAILURE_STATUSES.contains(&response.status()),
+            Err(error) => error.kind() != &ErrorKind::Io,
+        };
+        permit.record(success, OffsetDateTime::now_utc());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker_states() {
+        let policy = CircuitBreakerPolicy::new(
+            CircuitBreakerOptions::default()
+                .window_size(4usize)
+                .minimum_requests(4usize)
+                .failure_rate_threshold(0.5)
+                .cool_down(Duration::from_secs(10))
+                .half_open_probes(1usize),
+        );
+        let now = OffsetDateTime::now_utc();
+
+        for success in [true, true, false, false] {
+            policy.acquire(now).unwrap().record(success, now);
+        }
+
+        // two of the last four requests failed
+        let open = policy.acquire(now).unwrap_err();
+        assert_eq!(open.retry_after, Duration::from_secs(10));
+
+        // only one probe is let through once the cool down has elapsed
+        let later = now + Duration::from_secs(10);
+        let probe = policy.acquire(later).unwrap();
+        assert!(policy.acquire(later).is_err());
+
+        // a failed probe reopens the circuit
+        probe.record(false, later);
+        assert!(policy.acquire(later).is_err());
+
+        // a successful probe closes it
+        let later = later + Duration::from_secs(10);
+        policy.acquire(later).unwrap().record(true, later);
+        policy.acquire(later).unwrap();
+        policy.acquire(later).unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_probe() {
+        let policy = CircuitBreakerPolicy::new(
+            CircuitBreakerOptions::default()
+                .window_size(1usize)
+                .minimum_requests(1usize)
+                .cool_down(Duration::from_secs(10)),
+        );
+        let now = OffsetDateTime::now_utc();
+        policy.acquire(now).unwrap().record(false, now);
+
+        // a probe dropped without an outcome, as when its request is
+        // cancelled, lets another probe through
+        let later = now + Duration::from_secs(10);
+        drop(policy.acquire(later).unwrap());
+        let probe = policy.acquire(later).unwrap();
+        assert!(policy.acquire(later).is_err());
+        probe.record(true, later);
+        policy.acquire(later).unwrap();
+    }
+}
@@ -1,4 +1,7 @@
-use crate::authorization::AuthorizationPolicy;
+use crate::api_version::ApiVersionPolicy;
+use crate::authorization::{AuthorizationPolicy, SasExpiryPolicy};
+use crate::request_correlation::RequestCorrelationPolicy;
+use crate::retry_stats::{AttemptCountPolicy, RetryStatsPolicy};
 use crate::shared_access_signature::account_sas::{
     AccountSasPermissions, AccountSasResource, AccountSasResourceType, AccountSharedAccessSignature,
 };
@@ -22,9 +25,12 @@ pub const EMULATOR_ACCOUNT: &str = "devstoreaccount1";
 pub const EMULATOR_ACCOUNT_KEY: &str =
     "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
 
-const AZURE_VERSION: HeaderValue = HeaderValue::from_static("2019-12-12");
+/// The service version sent in the `x-ms-version` header of every request.
+pub const API_VERSION: &str = "2019-12-12";
 
-#[derive(Debug, Clone, Copy)]
+const AZURE_VERSION: HeaderValue = HeaderValue::from_static(API_VERSION);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ServiceType {
     Blob,
     Queue,
@@ -52,7 +58,7 @@ pub fn shared_access_signature(
     permissions: AccountSasPermissions,
 ) -> Result<AccountSharedAccessSignature, Error> {
     match storage_credentials {
-            StorageCredentials::Key(account, key) => {
+            StorageCredentials::Key(account, key) | StorageCredentials::KeyWithSecondary(account, key, _) => {
                 Ok(AccountSharedAccessSignature::new(account.clone(), key.clone(), resource, resource_type, expiry, permissions))
             }
             _ => Err(Error::message(ErrorKind::Credential, "failed shared access signature generation. SAS can be generated only from key and account clients")),
@@ -91,21 +97,43 @@ pub fn new_pipeline_from_options(
     options: ClientOptions,
     credentials: StorageCredentials,
 ) -> Pipeline {
+    let sas_expiry = credentials.sas_expiry();
+    let is_sas_token = matches!(credentials, StorageCredentials::SASToken(_));
+    let is_anonymous = matches!(credentials, StorageCredentials::Anonymous);
     let auth_policy: Arc<dyn azure_core::Policy> = Arc::new(AuthorizationPolicy::new(credentials));
 
     // The `AuthorizationPolicy` must be the **last** retry policy.
     // Policies can change the url and/or the headers, and the `AuthorizationPolicy`
     // must be able to inspect them or the resulting token will be invalid.
-    let per_retry_policies = vec![
-        Arc::new(options.timeout.clone()) as Arc<dyn azure_core::Policy>,
-        auth_policy,
+    // The `ApiVersionPolicy` and `SasExpiryPolicy` are per-call policies so that
+    // they see the final error once the retry policy has given up.
+    // The `RetryStatsPolicy` and `AttemptCountPolicy` measure the operation
+    // above the retry policy and count its attempts below it, for the
+    // response and for the metrics policy of the client options.
+    let mut per_call_policies: Vec<Arc<dyn azure_core::Policy>> =
+        vec![Arc::new(RetryStatsPolicy), Arc::new(ApiVersionPolicy)];
+    if is_sas_token {
+        per_call_policies.push(Arc::new(SasExpiryPolicy::new(sas_expiry)));
+    }
+    // Last of the storage per-call policies, which all run before the retry
+    // policy: every attempt is sent with the same client request id, and the
+    // errors it annotates are the final ones the retry policy gives up with.
+    per_call_policies.push(Arc::new(RequestCorrelationPolicy));
+
+    let mut per_retry_policies: Vec<Arc<dyn azure_core::Policy>> = vec![
+        Arc::new(AttemptCountPolicy),
+        Arc::new(options.timeout.clone()),
     ];
+    // anonymous requests are sent as they are, without an authorization step
+    if !is_anonymous {
+        per_retry_policies.push(auth_policy);
+    }
 
     Pipeline::new(
         option_env!("CARGO_PKG_NAME"),
         option_env!("CARGO_PKG_VERSION"),
         options,
-        Vec::new(),
+        per_call_policies,
         per_retry_policies,
     )
 }
@@ -0,0 +1,171 @@
+use azure_core::{
+    date,
+    error::{Error, ErrorKind},
+    headers::MS_DATE,
+    Context, Policy, PolicyResult, Request, StatusCode,
+};
+use std::sync::{Arc, Mutex};
+use time::{Duration, OffsetDateTime};
+
+/// Skews smaller than this are not corrected, as they cannot be why the
+/// service rejected a request.
+const MINIMUM_SKEW: Duration = Duration::minutes(5);
+
+/// Corrects the `x-ms-date` of requests for the difference between the local
+/// clock and the service's.
+///
+/// The service only accepts shared key signatures made within 15 minutes of its
+/// own time, so a device with a wrong clock cannot authenticate. When a request
+/// is rejected with `403 AuthenticationFailed` and the `Date` of the response
+/// is off by more than a few minutes, the policy records the difference and
+/// sends the request again, dated by the service's clock and signed anew. Later
+/// requests are dated the same way. Clones share the measured skew.
+#[derive(Debug, Clone, Default)]
+pub struct ClockSkewPolicy {
+    skew: Arc<Mutex<Option<Duration>>>,
+}
+
+impl ClockSkewPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How far the service's clock is ahead of the local one, once measured.
+    pub fn skew(&self) -> Option<Duration> {
+        *self.skew.lock().unwrap()
+    }
+
+    fn date_request(&self, request: &mut Request) {
+        if let Some(skew) = self.skew() {
+            request.insert_header(
+                MS_DATE,
+                date::to_rfc1123(&(OffsetDateTime::now_utc() + skew)),
+            );
+        }
+    }
+}
+
+/// The skew measured from a rejected request, if the request was rejected for
+/// failing authentication and the service's clock is off.
+fn measure_skew(error: &Error, now: OffsetDateTime) -> Option<Duration> {
+    if !matches!(
+        error.kind(),
+        ErrorKind::HttpResponse {
+            status: StatusCode::Forbidden,
+            error_code: Some(error_code),
+        } if error_code == "AuthenticationFailed"
+    ) {
+        return None;
+    }
+    let server_date = error.as_http_error()?.header("date")?;
+    let skew = date::parse_rfc1123(server_date).ok()? - now;
+    (skew.abs() >= MINIMUM_SKEW).then_some(skew)
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for ClockSkewPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        self.date_request(request);
+        let error = match next[0].send(ctx, request, &next[1..]).await {
+            Ok(response) => return Ok(response),
+            Err(error) => error,
+        };
+
+        let skew = match measure_skew(&error, OffsetDateTime::now_utc()) {
+            Some(skew) => skew,
+            None => return Err(error),
+        };
+        log::warn!(
+            "the service clock is {}s ahead of the local clock, correcting the request date",
+            skew.whole_seconds()
+        );
+        *self.skew.lock().unwrap() = Some(skew);
+        self.date_request(request);
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{headers::Headers, Response};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Rejects requests dated more than a minute away from the "service"
+    /// clock, which runs an hour ahead.
+    #[derive(Debug, Default)]
+    struct SkewedService {
+        requests: AtomicUsize,
+    }
+
+    fn service_now() -> OffsetDateTime {
+        OffsetDateTime::now_utc() + Duration::hours(1)
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for SkewedService {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            let request_date =
+                date::parse_rfc1123(request.headers().get_str(&MS_DATE).unwrap()).unwrap();
+            let mut headers = Headers::new();
+            headers.insert("date", date::to_rfc1123(&service_now()));
+            if (service_now() - request_date).abs() > Duration::minutes(1) {
+                headers.insert("x-ms-error-code", "AuthenticationFailed");
+                let response = Response::new(
+                    StatusCode::Forbidden,
+                    headers,
+                    Box::pin(futures::stream::empty()),
+                );
+                // as the retry policy reports rejected requests
+                let http_error = azure_core::error::HttpError::new(response).await;
+                return Err(Error::full(
+                    ErrorKind::http_response(
+                        StatusCode::Forbidden,
+                        Some("AuthenticationFailed".to_owned()),
+                    ),
+                    http_error,
+                    "forbidden",
+                ));
+            }
+            Ok(Response::new(
+                StatusCode::Ok,
+                headers,
+                Box::pin(futures::stream::empty()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_corrects_skew() -> azure_core::Result<()> {
+        let policy = ClockSkewPolicy::new();
+        let service = Arc::new(SkewedService::default());
+        let next: Vec<Arc<dyn Policy>> = vec![service.clone()];
+
+        let mut request = Request::new(
+            "https://account.blob.core.windows.net/".parse().unwrap(),
+            azure_core::Method::Get,
+        );
+        request.insert_header(MS_DATE, date::to_rfc1123(&OffsetDateTime::now_utc()));
+        policy.send(&Context::new(), &mut request, &next).await?;
+        assert_eq!(service.requests.load(Ordering::SeqCst), 2);
+        let skew = policy.skew().unwrap();
+        assert!((skew - Duration::hours(1)).abs() < Duration::seconds(5));
+
+        // later requests are dated right away
+        policy.send(&Context::new(), &mut request, &next).await?;
+        assert_eq!(service.requests.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+}
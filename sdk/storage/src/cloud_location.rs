@@ -23,28 +23,54 @@ pub enum CloudLocation {
     Custom {
         uri: String,
         credentials: StorageCredentials,
+        addressing_mode: AddressingMode,
     },
 }
 
+/// How the storage account is addressed in request URLs.
+///
+/// Azure Storage endpoints address the account as part of the host name.
+/// Azurite, Azure Stack, and many S3-style gateways instead use the first
+/// segment of the path.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AddressingMode {
+    /// `https://{account}.blob.core.windows.net/{container}/{blob}`
+    ///
+    /// The `uri` of a custom location is used verbatim.
+    #[default]
+    VirtualHost,
+    /// `https://{host}/{account}/{container}/{blob}`
+    ///
+    /// The account is appended to the `uri` of a custom location. As the
+    /// account is part of the path, it appears twice in the canonicalized
+    /// resource used for Shared Key signing (`/{account}/{account}/{container}`).
+    PathStyle { account: String },
+}
+
 impl CloudLocation {
     /// the base URL for a given cloud location
     pub fn url(&self, service_type: ServiceType) -> azure_core::Result<Url> {
+        self.subdomain_url(service_type.subdomain())
+    }
+
+    fn subdomain_url(&self, subdomain: &str) -> azure_core::Result<Url> {
         let url = match self {
             CloudLocation::Public { account, .. } => {
-                format!(
-                    "https://{}.{}.core.windows.net",
-                    account,
-                    service_type.subdomain()
-                )
+                format!("https://{account}.{subdomain}.core.windows.net")
             }
             CloudLocation::China { account, .. } => {
-                format!(
-                    "https://{}.{}.core.chinacloudapi.cn",
-                    account,
-                    service_type.subdomain()
-                )
+                format!("https://{account}.{subdomain}.core.chinacloudapi.cn")
             }
-            CloudLocation::Custom { uri, .. } => uri.clone(),
+            CloudLocation::Custom {
+                uri,
+                addressing_mode,
+                ..
+            } => match addressing_mode {
+                AddressingMode::VirtualHost => uri.clone(),
+                AddressingMode::PathStyle { account } => {
+                    format!("{}/{}", uri.trim_end_matches('/'), account)
+                }
+            },
             CloudLocation::Emulator { address, port } => {
                 format!("http://{address}:{port}/{EMULATOR_ACCOUNT}")
             }
@@ -52,6 +78,119 @@ impl CloudLocation {
         Ok(url::Url::parse(&url)?)
     }
 
+    /// The endpoints of every service of the account, e.g. to reach the `dfs`
+    /// endpoint of an account with a hierarchical namespace from a blob
+    /// client.
+    ///
+    /// Secondary endpoints, which only answer for accounts with read-access
+    /// geo-redundant replication, are known for the public and China clouds
+    /// and for the emulator. A custom location has a single URL, which is
+    /// the primary endpoint of every service but `dfs`. Its `dfs` endpoint is
+    /// only known when the host of the URL names the blob service, e.g.
+    /// `account.blob.example.com`, and is then the same host with `dfs`
+    /// instead. The emulator has no `dfs` endpoint.
+    pub fn service_endpoints(&self) -> azure_core::Result<ServiceEndpoints> {
+        let endpoint = |subdomain| -> azure_core::Result<ServiceEndpoint> {
+            let secondary = match self {
+                CloudLocation::Public { account, .. } => Some(Url::parse(&format!(
+                    "https://{account}-secondary.{subdomain}.core.windows.net"
+                ))?),
+                CloudLocation::China { account, .. } => Some(Url::parse(&format!(
+                    "https://{account}-secondary.{subdomain}.core.chinacloudapi.cn"
+                ))?),
+                CloudLocation::Emulator { address, port } => Some(Url::parse(&format!(
+                    "http://{address}:{port}/{EMULATOR_ACCOUNT}-secondary"
+                ))?),
+                CloudLocation::Custom { .. } => None,
+            };
+            Ok(ServiceEndpoint {
+                primary: self.subdomain_url(subdomain)?,
+                secondary,
+            })
+        };
+        Ok(ServiceEndpoints {
+            blob: endpoint(ServiceType::Blob.subdomain())?,
+            dfs: match self {
+                CloudLocation::Public { .. } | CloudLocation::China { .. } => {
+                    Some(endpoint(ServiceType::DataLake.subdomain())?)
+                }
+                CloudLocation::Custom { .. } => {
+                    let mut primary = self.subdomain_url(ServiceType::Blob.subdomain())?;
+                    let host = primary
+                        .host_str()
+                        .filter(|host| host.contains(".blob."))
+                        .map(|host| host.replacen(".blob.", ".dfs.", 1));
+                    match host {
+                        Some(host) => {
+                            primary.set_host(Some(&host))?;
+                            Some(ServiceEndpoint {
+                                primary,
+                                secondary: None,
+                            })
+                        }
+                        None => None,
+                    }
+                }
+                CloudLocation::Emulator { .. } => None,
+            },
+            file: endpoint("file")?,
+            queue: endpoint(ServiceType::Queue.subdomain())?,
+            table: endpoint(ServiceType::Table.subdomain())?,
+            static_website: None,
+        })
+    }
+
+    /// How the account is addressed in URLs for this location.
+    ///
+    /// The emulator always uses path-style addressing.
+    pub fn addressing_mode(&self) -> AddressingMode {
+        match self {
+            CloudLocation::Public { .. } | CloudLocation::China { .. } => {
+                AddressingMode::VirtualHost
+            }
+            CloudLocation::Emulator { .. } => AddressingMode::PathStyle {
+                account: EMULATOR_ACCOUNT.to_owned(),
+            },
+            CloudLocation::Custom {
+                addressing_mode, ..
+            } => addressing_mode.clone(),
+        }
+    }
+
+    /// Checks that requests to this location are protected by TLS.
+    ///
+    /// A custom location with a plain `http` URL is rejected, as Shared Key
+    /// signatures and SAS tokens would be sent in cleartext, unless its host
+    /// is a loopback address, it is accessed anonymously, or `allow_insecure`
+    /// is set. The emulator is always allowed.
+    pub fn check_secure(&self, allow_insecure: bool) -> azure_core::Result<()> {
+        let uri = match self {
+            CloudLocation::Custom {
+                uri, credentials, ..
+            } if !allow_insecure && !matches!(credentials, StorageCredentials::Anonymous) => uri,
+            _ => return Ok(()),
+        };
+        let url = Url::parse(uri)?;
+        let is_loopback = match url.host() {
+            Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+            Some(url::Host::Ipv4(address)) => address.is_loopback(),
+            Some(url::Host::Ipv6(address)) => address.is_loopback(),
+            None => false,
+        };
+        if url.scheme() == "http" && !is_loopback {
+            return Err(azure_core::Error::with_message(
+                azure_core::error::ErrorKind::Other,
+                || {
+                    format!(
+                        "refusing to send credentials to {uri} over plain http; \
+                         use https or allow insecure endpoints explicitly"
+                    )
+                },
+            ));
+        }
+        Ok(())
+    }
+
     pub fn credentials(&self) -> &StorageCredentials {
         match self {
             CloudLocation::Public { credentials, .. } => credentials,
@@ -60,6 +199,90 @@ impl CloudLocation {
             CloudLocation::Custom { credentials, .. } => credentials,
         }
     }
+
+    /// The same location, accessed with other credentials.
+    ///
+    /// The emulator, which has fixed credentials, becomes a custom location
+    /// with the same URL.
+    #[must_use]
+    pub fn with_credentials(&self, credentials: StorageCredentials) -> Self {
+        match self {
+            CloudLocation::Public { account, .. } => CloudLocation::Public {
+                account: account.clone(),
+                credentials,
+            },
+            CloudLocation::China { account, .. } => CloudLocation::China {
+                account: account.clone(),
+                credentials,
+            },
+            CloudLocation::Emulator { address, port } => CloudLocation::Custom {
+                uri: format!("http://{address}:{port}"),
+                credentials,
+                addressing_mode: AddressingMode::PathStyle {
+                    account: EMULATOR_ACCOUNT.to_owned(),
+                },
+            },
+            CloudLocation::Custom {
+                uri,
+                addressing_mode,
+                ..
+            } => CloudLocation::Custom {
+                uri: uri.clone(),
+                credentials,
+                addressing_mode: addressing_mode.clone(),
+            },
+        }
+    }
+}
+
+/// The primary endpoint of a service of an account and, if it is known, its
+/// secondary endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEndpoint {
+    pub primary: Url,
+    pub secondary: Option<Url>,
+}
+
+/// The endpoints of the services of an account, see
+/// [`CloudLocation::service_endpoints`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEndpoints {
+    pub blob: ServiceEndpoint,
+    /// The Data Lake Storage Gen2 endpoint, for hierarchical namespace
+    /// operations such as renames, if it is known.
+    pub dfs: Option<ServiceEndpoint>,
+    pub file: ServiceEndpoint,
+    pub queue: ServiceEndpoint,
+    pub table: ServiceEndpoint,
+    /// The endpoint of the static website, which is only known once set with
+    /// [`with_static_website_zone`](Self::with_static_website_zone).
+    pub static_website: Option<ServiceEndpoint>,
+}
+
+impl ServiceEndpoints {
+    /// Sets the static website endpoint from the zone of the account, e.g.
+    /// `z13` for `https://{account}.z13.web.core.windows.net`. The zone
+    /// depends on the account and is listed with its endpoints in the Azure
+    /// portal or by the management API.
+    ///
+    /// Only accounts addressed by host name in the public or China clouds
+    /// have a static website endpoint: for the others it is left unset.
+    #[must_use]
+    pub fn with_static_website_zone(mut self, zone: &str) -> Self {
+        let web = |url: &Url| {
+            let host = url.host_str()?;
+            let (account, rest) = host.split_once(".blob.")?;
+            if !matches!(rest, "core.windows.net" | "core.chinacloudapi.cn") {
+                return None;
+            }
+            Url::parse(&format!("https://{account}.{zone}.web.{rest}")).ok()
+        };
+        self.static_website = web(&self.blob.primary).map(|primary| ServiceEndpoint {
+            primary,
+            secondary: self.blob.secondary.as_ref().and_then(web),
+        });
+        self
+    }
 }
 
 impl TryFrom<&Url> for CloudLocation {
@@ -110,9 +333,8 @@ impl TryFrom<&Url> for CloudLocation {
     }
 }
 
-pub static EMULATOR_CREDENTIALS: Lazy<StorageCredentials> = Lazy::new(|| {
-    StorageCredentials::Key(EMULATOR_ACCOUNT.to_owned(), EMULATOR_ACCOUNT_KEY.to_owned())
-});
+pub static EMULATOR_CREDENTIALS: Lazy<StorageCredentials> =
+    Lazy::new(|| StorageCredentials::access_key(EMULATOR_ACCOUNT, EMULATOR_ACCOUNT_KEY));
 
 /// The well-known account used by Azurite and the legacy Azure Storage Emulator.
 /// <https://docs.microsoft.com/azure/storage/common/storage-use-azurite#well-known-storage-account-and-key>
@@ -161,4 +383,198 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_check_secure() {
+        let custom = |uri: &str| CloudLocation::Custom {
+            uri: uri.to_owned(),
+            credentials: StorageCredentials::access_key(EMULATOR_ACCOUNT, EMULATOR_ACCOUNT_KEY),
+            addressing_mode: AddressingMode::VirtualHost,
+        };
+
+        assert!(custom("https://storage.contoso.com")
+            .check_secure(false)
+            .is_ok());
+        assert!(custom("http://storage.contoso.com")
+            .check_secure(false)
+            .is_err());
+        assert!(custom("http://storage.contoso.com")
+            .check_secure(true)
+            .is_ok());
+        assert!(custom("http://localhost:10000").check_secure(false).is_ok());
+        assert!(custom("http://127.0.0.1:10000").check_secure(false).is_ok());
+        assert!(custom("http://[::1]:10000").check_secure(false).is_ok());
+
+        let anonymous = CloudLocation::Custom {
+            uri: "http://storage.contoso.com".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+            addressing_mode: AddressingMode::VirtualHost,
+        };
+        assert!(anonymous.check_secure(false).is_ok());
+        let emulator = CloudLocation::Emulator {
+            address: "azurite".to_owned(),
+            port: 10000,
+        };
+        assert!(emulator.check_secure(false).is_ok());
+    }
+
+    #[test]
+    fn test_custom_addressing_mode() -> azure_core::Result<()> {
+        let credentials = StorageCredentials::anonymous();
+
+        let virtual_host = CloudLocation::Custom {
+            uri: "https://myaccount.blob.example.com".to_owned(),
+            credentials: credentials.clone(),
+            addressing_mode: AddressingMode::VirtualHost,
+        };
+        assert_eq!(
+            virtual_host.url(ServiceType::Blob)?.as_str(),
+            "https://myaccount.blob.example.com/"
+        );
+
+        let path_style = CloudLocation::Custom {
+            uri: "http://localhost:8080/".to_owned(),
+            credentials,
+            addressing_mode: AddressingMode::PathStyle {
+                account: "myaccount".to_owned(),
+            },
+        };
+        assert_eq!(
+            path_style.url(ServiceType::Blob)?.as_str(),
+            "http://localhost:8080/myaccount"
+        );
+
+        let emulator = CloudLocation::Emulator {
+            address: "127.0.0.1".to_owned(),
+            port: 10000,
+        };
+        assert_eq!(
+            emulator.addressing_mode(),
+            AddressingMode::PathStyle {
+                account: EMULATOR_ACCOUNT.to_owned()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_credentials() -> azure_core::Result<()> {
+        let sas = StorageCredentials::sas_token("sv=2020-06-12&sig=abc")?;
+
+        let emulator = CloudLocation::Emulator {
+            address: "127.0.0.1".to_owned(),
+            port: 10000,
+        };
+        let with_sas = emulator.with_credentials(sas);
+        assert_eq!(
+            with_sas.url(ServiceType::Blob)?,
+            emulator.url(ServiceType::Blob)?
+        );
+        assert_eq!(with_sas.addressing_mode(), emulator.addressing_mode());
+        assert!(matches!(
+            with_sas.credentials(),
+            StorageCredentials::SASToken(_)
+        ));
+
+        let public = CloudLocation::Public {
+            account: "test".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+        };
+        let with_key = public.with_credentials(StorageCredentials::access_key("test", "a2V5"));
+        assert_eq!(
+            with_key.url(ServiceType::Blob)?,
+            public.url(ServiceType::Blob)?
+        );
+        assert!(matches!(
+            with_key.credentials(),
+            StorageCredentials::Key(..)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_service_endpoints() -> azure_core::Result<()> {
+        let public = CloudLocation::Public {
+            account: "account".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+        };
+        let endpoints = public.service_endpoints()?;
+        assert_eq!(
+            endpoints.dfs.as_ref().unwrap().primary.as_str(),
+            "https://account.dfs.core.windows.net/"
+        );
+        assert_eq!(
+            endpoints.file.secondary.as_ref().unwrap().as_str(),
+            "https://account-secondary.file.core.windows.net/"
+        );
+        assert_eq!(endpoints.static_website, None);
+
+        let website = endpoints
+            .with_static_website_zone("z13")
+            .static_website
+            .unwrap();
+        assert_eq!(
+            website.primary.as_str(),
+            "https://account.z13.web.core.windows.net/"
+        );
+        assert_eq!(
+            website.secondary.unwrap().as_str(),
+            "https://account-secondary.z13.web.core.windows.net/"
+        );
+
+        let emulator = CloudLocation::Emulator {
+            address: "127.0.0.1".to_owned(),
+            port: 10000,
+        };
+        let endpoints = emulator
+            .service_endpoints()?
+            .with_static_website_zone("z13");
+        assert_eq!(
+            endpoints.blob.secondary.unwrap().as_str(),
+            "http://127.0.0.1:10000/devstoreaccount1-secondary"
+        );
+        assert_eq!(endpoints.static_website, None);
+        assert_eq!(endpoints.dfs, None);
+
+        let custom = CloudLocation::Custom {
+            uri: "https://storage.example.com".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+            addressing_mode: AddressingMode::VirtualHost,
+        };
+        let endpoints = custom.service_endpoints()?;
+        assert_eq!(endpoints.queue.primary, endpoints.blob.primary);
+        assert_eq!(endpoints.queue.secondary, None);
+        assert_eq!(endpoints.dfs, None);
+
+        // the dfs host of a custom location is derived from its blob host
+        let custom = CloudLocation::Custom {
+            uri: "https://account.blob.core.usgovcloudapi.net".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+            addressing_mode: AddressingMode::VirtualHost,
+        };
+        let dfs = custom.service_endpoints()?.dfs.unwrap();
+        assert_eq!(
+            dfs.primary.as_str(),
+            "https://account.dfs.core.usgovcloudapi.net/"
+        );
+        assert_eq!(dfs.secondary, None);
+        let path_style = CloudLocation::Custom {
+            uri: "https://stack.blob.local.azurestack.external".to_owned(),
+            credentials: StorageCredentials::anonymous(),
+            addressing_mode: AddressingMode::PathStyle {
+                account: "account".to_owned(),
+            },
+        };
+        assert_eq!(
+            path_style
+                .service_endpoints()?
+                .dfs
+                .unwrap()
+                .primary
+                .as_str(),
+            "https://stack.dfs.local.azurestack.external/account"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,276 @@
+//! Client configuration which can be read from configuration files.
+//!
+//! ```
+//! # fn main() -> azure_core::Result<()> {
+//! use azure_storage::StorageConfig;
+//! let config: StorageConfig = serde_json::from_str(
+//!     r#"{
+//!         "account": "myaccount",
+//!         "auth": { "method": "access_key", "key": "SOMEACCESSKEY" },
+//!         "retry": { "mode": "exponential", "max_retries": 3 },
+//!         "timeout_secs": 30,
+//!         "application_id": "my-app"
+//!     }"#,
+//! )?;
+//! let cloud_location = config.cloud_location()?;
+//! let options = config.client_options();
+//! # Ok(())
+//! # }
+//! ```
+use crate::{AddressingMode, CloudLocation, StorageCredentials};
+use azure_core::{
+    auth::Secret,
+    error::{Error, ErrorKind},
+    prelude::Timeout,
+    ClientOptions, ExponentialRetryOptions, FixedRetryOptions, RetryOptions, TelemetryOptions,
+    TimeoutPolicy,
+};
+use std::time::Duration;
+
+/// The account, credentials and pipeline settings of a storage client.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct StorageConfig {
+    /// The storage account. Not needed for the emulator.
+    #[serde(default)]
+    pub account: Option<String>,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub cloud: CloudConfig,
+    #[serde(default)]
+    pub retry: Option<RetryConfig>,
+    /// The server timeout of each request, in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// An identifier of the application, prepended to the `User-Agent`.
+    #[serde(default)]
+    pub application_id: Option<String>,
+}
+
+/// How a client authenticates.
+///
+/// The secrets are held as [`Secret`]s, so `Debug` does not show them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AuthConfig {
+    #[default]
+    Anonymous,
+    AccessKey {
+        key: Secret,
+    },
+    SasToken {
+        token: Secret,
+    },
+    BearerToken {
+        token: Secret,
+    },
+}
+
+/// Where the account lives.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CloudConfig {
+    #[default]
+    Public,
+    China,
+    Emulator {
+        #[serde(default = "default_emulator_address")]
+        address: String,
+        #[serde(default = "default_emulator_port")]
+        port: u16,
+    },
+    Custom {
+        uri: String,
+        /// Whether the account is the first segment of the path rather than
+        /// part of the host name, as for Azurite or Azure Stack.
+        #[serde(default)]
+        path_style: bool,
+    },
+}
+
+fn default_emulator_address() -> String {
+    "127.0.0.1".to_owned()
+}
+
+fn default_emulator_port() -> u16 {
+    10000
+}
+
+/// How failed requests are retried. Unset fields keep their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case", deny_unknown_fields)]
+pub enum RetryConfig {
+    Exponential {
+        #[serde(default)]
+        max_retries: Option<u32>,
+        #[serde(default)]
+        initial_delay_ms: Option<u64>,
+        #[serde(default)]
+        max_delay_ms: Option<u64>,
+        #[serde(default)]
+        max_total_elapsed_secs: Option<u64>,
+    },
+    Fixed {
+        #[serde(default)]
+        max_retries: Option<u32>,
+        #[serde(default)]
+        delay_ms: Option<u64>,
+        #[serde(default)]
+        max_total_elapsed_secs: Option<u64>,
+    },
+    None,
+}
+
+impl StorageConfig {
+    pub fn credentials(&self) -> azure_core::Result<StorageCredentials> {
+        Ok(match &self.auth {
+            AuthConfig::Anonymous => StorageCredentials::anonymous(),
+            AuthConfig::AccessKey { key } => {
+                StorageCredentials::access_key(self.required_account()?, key.clone())
+            }
+            AuthConfig::SasToken { token } => StorageCredentials::sas_token(token.secret())?,
+            AuthConfig::BearerToken { token } => StorageCredentials::bearer_token(token.clone()),
+        })
+    }
+
+    pub fn cloud_location(&self) -> azure_core::Result<CloudLocation> {
+        Ok(match &self.cloud {
+            CloudConfig::Public => CloudLocation::Public {
+                account: self.required_account()?,
+                credentials: self.credentials()?,
+            },
+            CloudConfig::China => CloudLocation::China {
+                account: self.required_account()?,
+                credentials: self.credentials()?,
+            },
+            CloudConfig::Emulator { address, port } => CloudLocation::Emulator {
+                address: address.clone(),
+                port: *port,
+            },
+            CloudConfig::Custom { uri, path_style } => CloudLocation::Custom {
+                uri: uri.clone(),
+                credentials: self.credentials()?,
+                addressing_mode: if *path_style {
+                    AddressingMode::PathStyle {
+                        account: self.required_account()?,
+                    }
+                } else {
+                    AddressingMode::VirtualHost
+                },
+            },
+        })
+    }
+
+    /// Client options with the configured retry, timeout and telemetry
+    /// settings.
+    pub fn client_options(&self) -> ClientOptions {
+        let mut options = ClientOptions::default();
+        if let Some(retry) = &self.retry {
+            options = options.retry(retry.retry_options());
+        }
+        if let Some(timeout_secs) = self.timeout_secs {
+            options.timeout =
+                TimeoutPolicy::new(Some(Timeout::new(Duration::from_secs(timeout_secs))));
+        }
+        if let Some(application_id) = &self.application_id {
+            options = options.telemetry(TelemetryOptions::default().application_id(application_id));
+        }
+        options
+    }
+
+    fn required_account(&self) -> azure_core::Result<String> {
+        self.account.clone().ok_or_else(|| {
+            Error::message(ErrorKind::Other, "the storage configuration has no account")
+        })
+    }
+}
+
+impl RetryConfig {
+    pub fn retry_options(&self) -> RetryOptions {
+        match self {
+            RetryConfig::Exponential {
+                max_retries,
+                initial_delay_ms,
+                max_delay_ms,
+                max_total_elapsed_secs,
+            } => {
+                let mut options = ExponentialRetryOptions::default();
+                if let Some(max_retries) = max_retries {
+                    options.max_retries = *max_retries;
+                }
+                if let Some(initial_delay_ms) = initial_delay_ms {
+                    options.initial_delay = Duration::from_millis(*initial_delay_ms);
+                }
+                if let Some(max_delay_ms) = max_delay_ms {
+                    options.max_delay = Duration::from_millis(*max_delay_ms);
+                }
+                if let Some(max_total_elapsed_secs) = max_total_elapsed_secs {
+                    options.max_total_elapsed = Duration::from_secs(*max_total_elapsed_secs);
+                }
+                RetryOptions::exponential(options)
+            }
+            RetryConfig::Fixed {
+                max_retries,
+                delay_ms,
+                max_total_elapsed_secs,
+            } => {
+                let mut options = FixedRetryOptions::default();
+                if let Some(max_retries) = max_retries {
+                    options.max_retries = *max_retries;
+                }
+                if let Some(delay_ms) = delay_ms {
+                    options.delay = Duration::from_millis(*delay_ms);
+                }
+                if let Some(max_total_elapsed_secs) = max_total_elapsed_secs {
+                    options.max_total_elapsed = Duration::from_secs(*max_total_elapsed_secs);
+                }
+                RetryOptions::fixed(options)
+            }
+            RetryConfig::None => RetryOptions::none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_config() -> azure_core::Result<()> {
+        let config: StorageConfig = serde_json::from_str(
+            r#"{
+                "account": "account",
+                "auth": { "method": "access_key", "key": "a2V5" },
+                "cloud": { "type": "custom", "uri": "https://stack.local", "path_style": true },
+                "retry": { "mode": "fixed", "max_retries": 2 }
+            }"#,
+        )?;
+        assert_eq!(
+            config.auth,
+            AuthConfig::AccessKey {
+                key: Secret::new("a2V5")
+            }
+        );
+        assert!(!format!("{:?}", config.auth).contains("a2V5"));
+        match config.cloud_location()? {
+            CloudLocation::Custom {
+                addressing_mode, ..
+            } => assert_eq!(
+                addressing_mode,
+                AddressingMode::PathStyle {
+                    account: "account".to_owned()
+                }
+            ),
+            location => panic!("unexpected location {location:?}"),
+        }
+
+        let emulator: StorageConfig =
+            serde_json::from_str(r#"{ "cloud": { "type": "emulator" } }"#)?;
+        assert!(matches!(
+            emulator.cloud_location()?,
+            CloudLocation::Emulator { port: 10000, .. }
+        ));
+        assert!(StorageConfig::default().cloud_location().is_err());
+        Ok(())
+    }
+}
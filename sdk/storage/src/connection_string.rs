@@ -1,6 +1,6 @@
 use azure_core::error::{Error, ErrorKind, ResultExt};
 
-use crate::StorageCredentials;
+use crate::{clients::ServiceType, AddressingMode, CloudLocation, StorageCredentials};
 
 // Key names.
 pub const ACCOUNT_KEY_KEY_NAME: &str = "AccountKey";
@@ -188,15 +188,13 @@ impl<'a> ConnectionString<'a> {
                 if self.account_key.is_some() {
                     log::warn!("Both account key and SAS defined in connection string. Using only the provided SAS.");
                 }
-                Ok(StorageCredentials::SASToken(get_sas_token_parms(
-                    sas_token,
-                )?))
+                StorageCredentials::sas_token(sas_token)
             }
             ConnectionString {
                 account_name: Some(account),
                 account_key: Some(key),
                 ..
-            } =>  Ok(StorageCredentials::Key(account.to_string(), key.to_string())),
+            } =>  Ok(StorageCredentials::access_key(*account, *key)),
            _ => {
                 Err(Error::message(ErrorKind::Credential,
                     "Could not create a `StorageCredentail` from the provided connection string. Please validate that you have specified a means of authentication (key, SAS, etc.)."
@@ -204,30 +202,84 @@ impl<'a> ConnectionString<'a> {
             }
         }
     }
-}
 
-fn get_sas_token_parms(sas_token: &str) -> azure_core::Result<Vec<(String, String)>> {
-    // Any base url will do: we just need to parse the SAS token
-    // to get its query pairs.
-    let base_url = url::Url::parse("https://blob.core.windows.net").unwrap();
+    /// Where the given service of the connection string's account lives.
+    ///
+    /// An explicit endpoint for the service takes precedence over the account
+    /// name and endpoint suffix. Without a key or SAS the credentials are
+    /// anonymous.
+    pub fn cloud_location(&self, service_type: ServiceType) -> azure_core::Result<CloudLocation> {
+        if self.use_development_storage == Some(true) {
+            let (address, port) = match self.development_storage_proxy_uri {
+                Some(uri) => {
+                    let uri = url::Url::parse(uri)
+                        .with_context(ErrorKind::DataConversion, || {
+                            format!("failed to parse the development storage proxy URI: {uri}")
+                        })?;
+                    (
+                        uri.host_str().unwrap_or("127.0.0.1").to_owned(),
+                        uri.port().unwrap_or_else(|| emulator_port(service_type)),
+                    )
+                }
+                None => ("127.0.0.1".to_owned(), emulator_port(service_type)),
+            };
+            return Ok(CloudLocation::Emulator { address, port });
+        }
 
-    let url = url::Url::options().base_url(Some(&base_url));
+        let credentials = if self.account_key.is_some() || self.sas.is_some() {
+            self.storage_credentials()?
+        } else {
+            StorageCredentials::anonymous()
+        };
+        let endpoint = match service_type {
+            ServiceType::Blob | ServiceType::DataLake => self.blob_endpoint,
+            ServiceType::Queue => self.queue_endpoint,
+            ServiceType::Table => self.table_endpoint,
+        };
+        if let Some(uri) = endpoint {
+            return Ok(CloudLocation::Custom {
+                uri: uri.to_owned(),
+                credentials,
+                addressing_mode: AddressingMode::VirtualHost,
+            });
+        }
 
-    // this code handles the leading ?
-    // we support both with or without
-    let url = if sas_token.starts_with('?') {
-        url.parse(sas_token)
-    } else {
-        url.parse(&format!("?{sas_token}"))
+        let account = self.account_name.ok_or_else(|| {
+            Error::message(
+                ErrorKind::Credential,
+                "the connection string has neither an account name nor an endpoint",
+            )
+        })?;
+        Ok(match self.endpoint_suffix {
+            None | Some("core.windows.net") => CloudLocation::Public {
+                account: account.to_owned(),
+                credentials,
+            },
+            Some("core.chinacloudapi.cn") => CloudLocation::China {
+                account: account.to_owned(),
+                credentials,
+            },
+            Some(suffix) => CloudLocation::Custom {
+                uri: format!(
+                    "{}://{account}.{}.{suffix}",
+                    self.default_endpoints_protocol
+                        .as_ref()
+                        .unwrap_or(&EndpointProtocol::Https),
+                    service_type.subdomain()
+                ),
+                credentials,
+                addressing_mode: AddressingMode::VirtualHost,
+            },
+        })
     }
-    .with_context(ErrorKind::DataConversion, || {
-        format!("failed to parse SAS token: {sas_token}")
-    })?;
+}
 
-    Ok(url
-        .query_pairs()
-        .map(|p| (String::from(p.0), String::from(p.1)))
-        .collect())
+fn emulator_port(service_type: ServiceType) -> u16 {
+    match service_type {
+        ServiceType::Blob | ServiceType::DataLake => 10000,
+        ServiceType::Queue => 10001,
+        ServiceType::Table => 10002,
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,313 @@
+//! The MACs and hashes used by the storage clients.
+//!
+//! HMAC-SHA256, which signs requests and shared access signatures, is computed
+//! by the backend picked with a cargo feature:
+//!
+//! - `crypto-rust` (default): the pure Rust `hmac` and `sha2` crates
+//! - `crypto-ring`: `ring`
+//! - `crypto-openssl`: OpenSSL, e.g. with a FIPS validated provider
+//!
+//! When several are enabled, OpenSSL is preferred over `ring`, and `ring` over
+//! the pure Rust crates. Only the crates of the enabled backends are built.
+//!
+//! MD5 only guards against corruption in transit. It is computed by:
+//!
+//! - `md5` (default): the pure Rust `md5` crate
+//! - `md5-openssl`: OpenSSL, preferred when both are enabled
+//!
+//! FIPS providers of OpenSSL refuse MD5, which then fails with an error. Where
+//! MD5 may not be used at all, enable neither feature and build
+//! `azure_storage_blobs` without its default `md5` feature: transfers are then
+//! checked with the CRC64 of the service instead.
+use azure_core::error::{ErrorKind, ResultExt};
+
+#[cfg(not(any(
+    feature = "crypto-rust",
+    feature = "crypto-ring",
+    feature = "crypto-openssl"
+)))]
+compile_error!(
+    "azure_storage needs a HMAC-SHA256 backend: enable `crypto-rust`, `crypto-ring` or `crypto-openssl`"
+);
+
+/// An HMAC-SHA256 key, ready to sign any number of messages.
+#[derive(Clone)]
+pub struct HmacSha256(hmac_backend::Key);
+
+impl HmacSha256 {
+    pub fn new(key: &[u8]) -> azure_core::Result<Self> {
+        hmac_backend::new(key).map(Self).context(
+            ErrorKind::DataConversion,
+            "failed to create an HMAC-SHA256 key",
+        )
+    }
+
+    pub fn sign(&self, data: &[u8]) -> azure_core::Result<[u8; 32]> {
+        hmac_backend::sign(&self.0, data)
+            .context(ErrorKind::Other, "failed to compute an HMAC-SHA256")
+    }
+}
+
+impl std::fmt::Debug for HmacSha256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacSha256").finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(
+    feature = "crypto-rust",
+    not(any(feature = "crypto-ring", feature = "crypto-openssl"))
+))]
+mod hmac_backend {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    use std::convert::Infallible;
+
+    pub(super) type Key = Hmac<Sha256>;
+
+    pub(super) fn new(key: &[u8]) -> Result<Key, hmac::digest::InvalidLength> {
+        Hmac::<Sha256>::new_from_slice(key)
+    }
+
+    pub(super) fn sign(key: &Key, data: &[u8]) -> Result<[u8; 32], Infallible> {
+        let mut hmac = key.clone();
+        hmac.update(data);
+        Ok(hmac.finalize().into_bytes().into())
+    }
+}
+
+#[cfg(all(feature = "crypto-ring", not(feature = "crypto-openssl")))]
+mod hmac_backend {
+    use std::convert::Infallible;
+
+    pub(super) type Key = ring::hmac::Key;
+
+    pub(super) fn new(key: &[u8]) -> Result<Key, Infallible> {
+        Ok(ring::hmac::Key::new(ring::hmac::HMAC_SHA256, key))
+    }
+
+    pub(super) fn sign(key: &Key, data: &[u8]) -> Result<[u8; 32], Infallible> {
+        let mut signature = [0; 32];
+        signature.copy_from_slice(ring::hmac::sign(key, data).as_ref());
+        Ok(signature)
+    }
+}
+
+#[cfg(feature = "crypto-openssl")]
+mod hmac_backend {
+    use openssl::{
+        error::ErrorStack,
+        hash::MessageDigest,
+        pkey::{PKey, Private},
+        sign::Signer,
+    };
+
+    pub(super) type Key = PKey<Private>;
+
+    pub(super) fn new(key: &[u8]) -> Result<Key, ErrorStack> {
+        PKey::hmac(key)
+    }
+
+    pub(super) fn sign(key: &Key, data: &[u8]) -> Result<[u8; 32], ErrorStack> {
+        let mut signer = Signer::new(MessageDigest::sha256(), key)?;
+        signer.update(data)?;
+        let mut signature = [0; 32];
+        signer.sign(&mut signature)?;
+        Ok(signature)
+    }
+}
+
+/// The MD5 of `data`.
+#[cfg(any(feature = "md5", feature = "md5-openssl"))]
+pub fn md5(data: &[u8]) -> azure_core::Result<[u8; 16]> {
+    let mut md5 = Md5::new();
+    md5.update(data);
+    md5.finalize()
+}
+
+/// An incremental MD5 computation.
+#[cfg(any(feature = "md5", feature = "md5-openssl"))]
+#[derive(Clone)]
+pub struct Md5(md5_backend::Context);
+
+#[cfg(any(feature = "md5", feature = "md5-openssl"))]
+impl Md5 {
+    pub fn new() -> Self {
+        Self(md5_backend::Context::new())
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    pub fn finalize(self) -> azure_core::Result<[u8; 16]> {
+        self.0
+            .finalize()
+            .context(ErrorKind::Other, "failed to compute an MD5")
+    }
+}
+
+#[cfg(any(feature = "md5", feature = "md5-openssl"))]
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(feature = "md5", feature = "md5-openssl"))]
+impl std::fmt::Debug for Md5 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Md5").finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(feature = "md5", not(feature = "md5-openssl")))]
+mod md5_backend {
+    use std::convert::Infallible;
+
+    #[derive(Clone)]
+    pub(super) struct Context(md5::Context);
+
+    impl Context {
+        pub(super) fn new() -> Self {
+            Self(md5::Context::new())
+        }
+
+        pub(super) fn update(&mut self, data: &[u8]) {
+            self.0.consume(data);
+        }
+
+        pub(super) fn finalize(self) -> Result<[u8; 16], Infallible> {
+            Ok(self.0.compute().0)
+        }
+    }
+}
+
+#[cfg(feature = "md5-openssl")]
+mod md5_backend {
+    use openssl::{
+        error::ErrorStack,
+        hash::{Hasher, MessageDigest},
+    };
+
+    /// The hasher, or the first error of OpenSSL, reported when finalizing.
+    #[derive(Clone)]
+    pub(super) struct Context(Result<Hasher, ErrorStack>);
+
+    impl Context {
+        pub(super) fn new() -> Self {
+            Self(Hasher::new(MessageDigest::md5()))
+        }
+
+        pub(super) fn update(&mut self, data: &[u8]) {
+            if let Ok(hasher) = &mut self.0 {
+                if let Err(error) = hasher.update(data) {
+                    self.0 = Err(error);
+                }
+            }
+        }
+
+        pub(super) fn finalize(self) -> Result<[u8; 16], ErrorStack> {
+            let mut hasher = self.0?;
+            let digest = hasher.finish()?;
+            let mut md5 = [0; 16];
+            md5.copy_from_slice(&digest);
+            Ok(md5)
+        }
+    }
+}
+
+/// The polynomial of the CRC64 of Azure Storage, in reversed form.
+const CRC64_POLYNOMIAL: u64 = 0x9A6C_9329_AC4B_C9B5;
+
+static CRC64_TABLE: [u64; 256] = crc64_table();
+
+const fn crc64_table() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC64_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// The CRC64 of `data`, as checked by the `x-ms-content-crc64` header.
+pub fn crc64(data: &[u8]) -> u64 {
+    let mut crc64 = Crc64::new();
+    crc64.update(data);
+    crc64.finalize()
+}
+
+/// An incremental CRC64 computation.
+#[derive(Debug, Clone)]
+pub struct Crc64(u64);
+
+impl Crc64 {
+    pub fn new() -> Self {
+        Self(!0)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.0 = CRC64_TABLE[((self.0 ^ u64::from(*byte)) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub fn finalize(self) -> u64 {
+        !self.0
+    }
+}
+
+impl Default for Crc64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256() -> azure_core::Result<()> {
+        // RFC 4231, test case 2
+        let signature = HmacSha256::new(b"Jefe")?.sign(b"what do ya want for nothing?")?;
+        assert_eq!(
+            base64::encode(signature),
+            "W9zBRr9gdU5qBCQmCJV1x1oAPwidJzmDnexYuWTsOEM="
+        );
+        Ok(())
+    }
+
+    #[cfg(any(feature = "md5", feature = "md5-openssl"))]
+    #[test]
+    fn test_md5() -> azure_core::Result<()> {
+        let mut md5 = Md5::new();
+        md5.update(b"hello ");
+        md5.update(b"world");
+        assert_eq!(md5.finalize()?, super::md5(b"hello world")?);
+        assert_eq!(base64::encode(super::md5(b"")?), "1B2M2Y8AsgTpgAmY7PhCfg==");
+        Ok(())
+    }
+
+    #[test]
+    fn test_crc64() {
+        assert_eq!(crc64(b""), 0);
+        assert_eq!(crc64(b"123456789"), 0xAE8B_1486_0A79_9888);
+        let mut crc64 = Crc64::new();
+        crc64.update(b"1234");
+        crc64.update(b"56789");
+        assert_eq!(crc64.finalize(), super::crc64(b"123456789"));
+    }
+}
@@ -0,0 +1,88 @@
+//! Quick setup for examples, tests and tools.
+//!
+//! The account is taken from the environment, in this order:
+//!
+//! 1. `AZURE_STORAGE_CONNECTION_STRING`, a connection string
+//! 2. `STORAGE_ACCOUNT` with `STORAGE_ACCESS_KEY` or `STORAGE_SAS_TOKEN`
+//! 3. `STORAGE_ACCOUNT` alone, for anonymous access
+//!
+//! Production code should usually configure the location and credentials
+//! explicitly instead.
+use crate::{clients::ServiceType, CloudLocation, ConnectionString, StorageCredentials};
+use azure_core::error::{Error, ErrorKind};
+
+pub const CONNECTION_STRING_ENV: &str = "AZURE_STORAGE_CONNECTION_STRING";
+pub const ACCOUNT_ENV: &str = "STORAGE_ACCOUNT";
+pub const ACCESS_KEY_ENV: &str = "STORAGE_ACCESS_KEY";
+pub const SAS_TOKEN_ENV: &str = "STORAGE_SAS_TOKEN";
+
+/// Where the given service of the account configured in the environment
+/// lives.
+pub fn cloud_location_from_env(service_type: ServiceType) -> azure_core::Result<CloudLocation> {
+    cloud_location_from(service_type, |name| std::env::var(name).ok())
+}
+
+fn cloud_location_from(
+    service_type: ServiceType,
+    var: impl Fn(&str) -> Option<String>,
+) -> azure_core::Result<CloudLocation> {
+    if let Some(connection_string) = var(CONNECTION_STRING_ENV) {
+        return ConnectionString::new(&connection_string)?.cloud_location(service_type);
+    }
+
+    let account = var(ACCOUNT_ENV).ok_or_else(|| {
+        Error::with_message(ErrorKind::Credential, || {
+            format!("set {CONNECTION_STRING_ENV} or {ACCOUNT_ENV} to pick a storage account")
+        })
+    })?;
+    let credentials = match (var(ACCESS_KEY_ENV), var(SAS_TOKEN_ENV)) {
+        (Some(key), _) => StorageCredentials::access_key(account.clone(), key),
+        (None, Some(token)) => StorageCredentials::sas_token(token)?,
+        (None, None) => StorageCredentials::anonymous(),
+    };
+    Ok(CloudLocation::Public {
+        account,
+        credentials,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn location(vars: &[(&str, &str)]) -> azure_core::Result<CloudLocation> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        cloud_location_from(ServiceType::Blob, |name| {
+            vars.get(name).map(|value| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_cloud_location_from_env() -> azure_core::Result<()> {
+        assert!(location(&[]).is_err());
+        assert_eq!(
+            location(&[(ACCOUNT_ENV, "account"), (ACCESS_KEY_ENV, "a2V5")])?
+                .url(ServiceType::Blob)?
+                .as_str(),
+            "https://account.blob.core.windows.net/"
+        );
+        assert!(matches!(
+            location(&[(CONNECTION_STRING_ENV, "UseDevelopmentStorage=true")])?,
+            CloudLocation::Emulator { port: 10000, .. }
+        ));
+        assert_eq!(
+            location(&[
+                (ACCOUNT_ENV, "ignored"),
+                (
+                    CONNECTION_STRING_ENV,
+                    "AccountName=account;AccountKey=a2V5;EndpointSuffix=core.usgovcloudapi.net"
+                )
+            ])?
+            .url(ServiceType::Blob)?
+            .as_str(),
+            "https://account.blob.core.usgovcloudapi.net/"
+        );
+        Ok(())
+    }
+}
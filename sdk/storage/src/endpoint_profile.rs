@@ -0,0 +1,264 @@
+use crate::{clients::ServiceType, AddressingMode, CloudLocation, Feature, StorageCredentials};
+use azure_core::{
+    error::{Error, ErrorKind},
+    Context, Policy, PolicyResult, Request,
+};
+use std::{collections::BTreeSet, sync::Arc};
+
+/// The endpoints, service version and features of a storage deployment other
+/// than the public cloud, such as Azure Stack Hub.
+///
+/// The client builders take a profile to address the accounts of the
+/// deployment, pin the service version it supports, and fail the requests
+/// using a feature it lacks with an [`UnsupportedFeature`] error instead of
+/// sending them.
+///
+/// ```
+/// use azure_storage::{clients::ServiceType, EndpointProfile, Feature};
+///
+/// let profile = EndpointProfile::azure_stack_hub("local.azurestack.external");
+/// assert_eq!(
+///     profile.url("myaccount", ServiceType::Blob),
+///     "https://myaccount.blob.local.azurestack.external"
+/// );
+/// assert!(!profile.supports(Feature::BlobTags));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointProfile {
+    name: String,
+    endpoint_suffix: String,
+    api_version: String,
+    unsupported_features: BTreeSet<Feature>,
+}
+
+impl EndpointProfile {
+    /// The service version of Azure Stack Hub storage since its 2008 update.
+    pub const AZURE_STACK_HUB_API_VERSION: &'static str = "2019-07-07";
+
+    /// A profile whose accounts are reached at
+    /// `https://{account}.{service}.{endpoint_suffix}`, supporting the
+    /// features of `api_version`.
+    pub fn new(
+        name: impl Into<String>,
+        endpoint_suffix: impl Into<String>,
+        api_version: impl Into<String>,
+    ) -> Self {
+        let api_version = api_version.into();
+        Self {
+            name: name.into(),
+            endpoint_suffix: endpoint_suffix.into().trim_matches('.').to_owned(),
+            unsupported_features: Feature::ALL
+                .iter()
+                .copied()
+                .filter(|feature| !feature.is_supported_by(&api_version))
+                .collect(),
+            api_version,
+        }
+    }
+
+    /// Azure Stack Hub, whose endpoint suffix is made of the region and the
+    /// external domain of the deployment, e.g. `local.azurestack.external`.
+    ///
+    /// ref: <https://docs.microsoft.com/azure-stack/user/azure-stack-acs-differences>
+    pub fn azure_stack_hub(endpoint_suffix: impl Into<String>) -> Self {
+        Self::new(
+            "Azure Stack Hub",
+            endpoint_suffix,
+            Self::AZURE_STACK_HUB_API_VERSION,
+        )
+        .without(Feature::AccessTiers)
+        .without(Feature::SoftDelete)
+    }
+
+    /// The profile without `feature`.
+    #[must_use]
+    pub fn without(mut self, feature: Feature) -> Self {
+        self.unsupported_features.insert(feature);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn endpoint_suffix(&self) -> &str {
+        &self.endpoint_suffix
+    }
+
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        !self.unsupported_features.contains(&feature)
+    }
+
+    /// The URL of the service of `account`.
+    pub fn url(&self, account: &str, service_type: ServiceType) -> String {
+        format!(
+            "https://{account}.{}.{}",
+            service_type.subdomain(),
+            self.endpoint_suffix
+        )
+    }
+
+    /// The location of the service of `account`, accessed with `credentials`.
+    pub fn cloud_location(
+        &self,
+        account: &str,
+        credentials: impl Into<StorageCredentials>,
+        service_type: ServiceType,
+    ) -> CloudLocation {
+        CloudLocation::Custom {
+            uri: self.url(account, service_type),
+            credentials: credentials.into(),
+            addressing_mode: AddressingMode::VirtualHost,
+        }
+    }
+
+    /// The per-call policy failing the requests which use a feature the
+    /// profile lacks.
+    pub fn policy(&self) -> Arc<dyn Policy> {
+        Arc::new(EndpointProfilePolicy {
+            profile: self.clone(),
+        })
+    }
+
+    /// The first feature used by the request which the profile lacks.
+    fn unsupported_feature(&self, request: &Request) -> Option<Feature> {
+        self.unsupported_features
+            .iter()
+            .copied()
+            .find(|feature| feature.is_used_by(request.url(), request.headers()))
+    }
+}
+
+/// The error returned, as the source of an `ErrorKind::Other` error, for
+/// requests using a feature an [`EndpointProfile`] lacks. Such requests are not
+/// sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedFeature {
+    pub feature: Feature,
+    /// The name of the profile.
+    pub profile: String,
+    pub api_version: String,
+}
+
+impl std::fmt::Display for UnsupportedFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is not supported by {} (service version {})",
+            self.feature, self.profile, self.api_version
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedFeature {}
+
+#[derive(Debug, Clone)]
+struct EndpointProfilePolicy {
+    profile: EndpointProfile,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for EndpointProfilePolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if let Some(feature) = self.profile.unsupported_feature(request) {
+            return Err(Error::new(
+                ErrorKind::Other,
+                UnsupportedFeature {
+                    feature,
+                    profile: self.profile.name.clone(),
+                    api_version: self.profile.api_version.clone(),
+                },
+            ));
+        }
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::Method;
+
+    #[test]
+    fn test_azure_stack_hub() {
+        let profile = EndpointProfile::azure_stack_hub(".local.azurestack.external");
+        assert_eq!(profile.api_version(), "2019-07-07");
+        assert!(matches!(
+            profile.cloud_location("myaccount", StorageCredentials::anonymous(), ServiceType::Queue),
+            CloudLocation::Custom { uri, .. } if uri == "https://myaccount.queue.local.azurestack.external"
+        ));
+
+        for feature in [
+            Feature::BlobTags,
+            Feature::BlobVersions,
+            Feature::BlobExpiry,
+            Feature::AccessTiers,
+            Feature::SoftDelete,
+        ] {
+            assert!(!profile.supports(feature), "{feature}");
+        }
+
+        let request = |url: &str| {
+            Request::new(
+                format!("https://myaccount.blob.local.azurestack.external/{url}")
+                    .parse()
+                    .unwrap(),
+                Method::Put,
+            )
+        };
+        assert_eq!(
+            profile.unsupported_feature(&request("container/blob?comp=tier")),
+            Some(Feature::AccessTiers)
+        );
+        assert_eq!(
+            profile.unsupported_feature(&request(
+                "container?restype=container&comp=list&include=metadata,deleted"
+            )),
+            Some(Feature::SoftDelete)
+        );
+        assert_eq!(
+            profile.unsupported_feature(&request("container/blob?comp=tags")),
+            Some(Feature::BlobTags)
+        );
+        assert_eq!(
+            profile.unsupported_feature(&request("container/blob?comp=metadata")),
+            None
+        );
+
+        let public = EndpointProfile::new("public", "core.windows.net", "2020-02-10");
+        assert!(Feature::ALL.iter().all(|feature| public.supports(*feature)));
+    }
+
+    #[tokio::test]
+    async fn test_policy() {
+        let profile = EndpointProfile::azure_stack_hub("local.azurestack.external");
+        let mut request = Request::new(
+            "https://myaccount.blob.local.azurestack.external/container/blob?comp=expiry"
+                .parse()
+                .unwrap(),
+            Method::Put,
+        );
+        // the request is not sent, so there is no next policy
+        let error = profile
+            .policy()
+            .send(&Context::new(), &mut request, &[])
+            .await
+            .unwrap_err();
+        let unsupported = error.downcast_ref::<UnsupportedFeature>().unwrap();
+        assert_eq!(unsupported.feature, Feature::BlobExpiry);
+        assert_eq!(
+            error.to_string(),
+            "blob expiry is not supported by Azure Stack Hub (service version 2019-07-07)"
+        );
+    }
+}
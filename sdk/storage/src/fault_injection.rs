@@ -0,0 +1,225 @@
+//! Injects storage failures into a pipeline, to test how an application copes
+//! with them.
+//!
+//! This is meant for tests only: add a [`FaultInjectionPolicy`] with the
+//! `fault_injection` method of the service client builders and check retries
+//! and resumed transfers still succeed.
+use azure_core::{
+    error::{Error, ErrorKind},
+    headers::{Headers, CONTENT_LENGTH, ERROR_CODE},
+    Context, Policy, PolicyResult, Request, Response, ResponseBody, StatusCode,
+};
+use bytes::Bytes;
+use futures::StreamExt;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// A failure injected by a [`FaultInjectionPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// A `500 Internal Server Error` response, without sending the request.
+    InternalError,
+    /// A `503 Server Busy` response, without sending the request.
+    ServerBusy,
+    /// An I/O error after waiting for the timeout, without sending the request.
+    Timeout,
+    /// The response body fails with an I/O error halfway through.
+    TruncatedBody,
+    /// The request is sent after waiting for the delay.
+    Delay,
+}
+
+/// Options for a [`FaultInjectionPolicy`].
+///
+/// Each fault is injected into a request with the given probability, between
+/// 0 and 1. At most one fault is injected per request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaultInjectionOptions {
+    internal_error: f64,
+    server_busy: f64,
+    timeout: f64,
+    truncated_body: f64,
+    delay: f64,
+    timeout_duration: Duration,
+    delay_duration: Duration,
+}
+
+impl Default for FaultInjectionOptions {
+    fn default() -> Self {
+        Self {
+            internal_error: 0.0,
+            server_busy: 0.0,
+            timeout: 0.0,
+            truncated_body: 0.0,
+            delay: 0.0,
+            timeout_duration: Duration::from_secs(1),
+            delay_duration: Duration::from_secs(1),
+        }
+    }
+}
+
+impl FaultInjectionOptions {
+    setters! {
+        internal_error: f64 => internal_error,
+        server_busy: f64 => server_busy,
+        timeout: f64 => timeout,
+        truncated_body: f64 => truncated_body,
+        delay: f64 => delay,
+        timeout_duration: Duration => timeout_duration,
+        delay_duration: Duration => delay_duration,
+    }
+
+    /// The fault to inject for a uniformly distributed `roll` in `[0, 1)`.
+    fn choose(&self, roll: f64) -> Option<Fault> {
+        let mut threshold = 0.0;
+        for (probability, fault) in [
+            (self.internal_error, Fault::InternalError),
+            (self.server_busy, Fault::ServerBusy),
+            (self.timeout, Fault::Timeout),
+            (self.truncated_body, Fault::TruncatedBody),
+            (self.delay, Fault::Delay),
+        ] {
+            threshold += probability;
+            if roll < threshold {
+                return Some(fault);
+            }
+        }
+        None
+    }
+}
+
+/// A per-retry policy which injects random [`Fault`]s.
+///
+/// It sits below the retry policy, so each attempt may fail independently.
+/// Clones share the log of injected faults.
+#[derive(Debug, Clone)]
+pub struct FaultInjectionPolicy {
+    options: FaultInjectionOptions,
+    injected: Arc<Mutex<Vec<Fault>>>,
+}
+
+impl FaultInjectionPolicy {
+    pub fn new(options: FaultInjectionOptions) -> Self {
+        Self {
+            options,
+            injected: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// The faults injected so far, oldest first.
+    pub fn injected(&self) -> Vec<Fault> {
+        self.injected.lock().unwrap().clone()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for FaultInjectionPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let fault = match self.options.choose(rand::random()) {
+            Some(fault) => fault,
+            None => return next[0].send(ctx, request, &next[1..]).await,
+        };
+        debug!("injecting {:?} into {}", fault, request.url());
+        self.injected.lock().unwrap().push(fault);
+
+        match fault {
+            Fault::InternalError => Ok(error_response(
+                StatusCode::InternalServerError,
+                "InternalError",
+            )),
+            Fault::ServerBusy => Ok(error_response(StatusCode::ServiceUnavailable, "ServerBusy")),
+            Fault::Timeout => {
+                azure_core::sleep::sleep(self.options.timeout_duration).await;
+                Err(Error::message(ErrorKind::Io, "injected timeout"))
+            }
+            Fault::TruncatedBody => {
+                let response = next[0].send(ctx, request, &next[1..]).await?;
+                let length = response
+                    .headers()
+                    .get_optional_as::<usize, _>(&CONTENT_LENGTH)?
+                    .unwrap_or_default();
+                let (status, headers, body) = response.deconstruct();
+                Ok(Response::new(
+                    status,
+                    headers,
+                    Box::pin(truncate(body, length / 2)),
+                ))
+            }
+            Fault::Delay => {
+                azure_core::sleep::sleep(self.options.delay_duration).await;
+                next[0].send(ctx, request, &next[1..]).await
+            }
+        }
+    }
+}
+
+fn error_response(status: StatusCode, error_code: &'static str) -> Response {
+    let mut headers = Headers::new();
+    headers.insert(ERROR_CODE, error_code);
+    headers.insert(CONTENT_LENGTH, "0");
+    Response::new(status, headers, Box::pin(futures::stream::empty()))
+}
+
+/// Passes through the first `limit` bytes of `body`, then fails.
+fn truncate(
+    body: ResponseBody,
+    limit: usize,
+) -> impl futures::Stream<Item = azure_core::Result<Bytes>> {
+    futures::stream::unfold(Some((body, limit)), |state| async move {
+        let (mut body, remaining) = state?;
+        if remaining == 0 {
+            let error = Error::message(ErrorKind::Io, "injected truncated response body");
+            return Some((Err(error), None));
+        }
+        match body.next().await? {
+            Ok(mut bytes) => {
+                bytes.truncate(remaining);
+                let remaining = remaining - bytes.len();
+                Some((Ok(bytes), Some((body, remaining))))
+            }
+            Err(error) => Some((Err(error), None)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose() {
+        let options = FaultInjectionOptions::default()
+            .internal_error(0.1)
+            .timeout(0.2);
+        assert_eq!(options.choose(0.05), Some(Fault::InternalError));
+        assert_eq!(options.choose(0.15), Some(Fault::Timeout));
+        assert_eq!(options.choose(0.5), None);
+        assert_eq!(FaultInjectionOptions::default().choose(0.0), None);
+    }
+
+    #[tokio::test]
+    async fn test_truncate() {
+        let body = Response::new(
+            StatusCode::Ok,
+            Headers::new(),
+            Box::pin(futures::stream::iter(vec![
+                Ok(Bytes::from_static(b"abc")),
+                Ok(Bytes::from_static(b"def")),
+            ])),
+        )
+        .into_body();
+        let chunks: Vec<_> = truncate(body, 4).collect().await;
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].as_ref().unwrap(), &Bytes::from_static(b"abc"));
+        assert_eq!(chunks[1].as_ref().unwrap(), &Bytes::from_static(b"d"));
+        assert_eq!(chunks[2].as_ref().unwrap_err().kind(), &ErrorKind::Io);
+    }
+}
@@ -1,11 +1,11 @@
-use crate::{ConsistencyCRC64, ConsistencyMD5};
+use crate::{ConsistencyCRC64, ConsistencyMD5, RetryStats};
 use azure_core::error::Error;
 use azure_core::headers::{
     client_request_id_from_headers_optional, date_from_headers, request_id_from_headers,
     server_from_headers, version_from_headers, HeaderName, Headers, CONTENT_MD5,
 };
 use azure_core::RequestId;
-use std::convert::TryFrom;
+use std::{convert::TryFrom, time::Duration};
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone)]
@@ -15,18 +15,34 @@ pub struct CommonStorageResponseHeaders {
     pub version: String,
     pub date: OffsetDateTime,
     pub server: String,
+    /// How many attempts the operation took, retries included.
+    pub attempts: u32,
+    /// Time from the first attempt until the final response, including the
+    /// time spent waiting between retries.
+    pub elapsed: Duration,
 }
 
 impl TryFrom<&Headers> for CommonStorageResponseHeaders {
     type Error = Error;
 
     fn try_from(headers: &Headers) -> azure_core::Result<Self> {
+        // set by the pipeline, and missing from headers parsed without it
+        let stats = headers
+            .extensions()
+            .get::<RetryStats>()
+            .copied()
+            .unwrap_or(RetryStats {
+                attempts: 1,
+                elapsed: Duration::ZERO,
+            });
         Ok(Self {
             request_id: request_id_from_headers(headers)?,
             client_request_id: client_request_id_from_headers_optional(headers),
             version: version_from_headers(headers)?,
             date: date_from_headers(headers)?,
             server: server_from_headers(headers)?,
+            attempts: stats.attempts,
+            elapsed: stats.elapsed,
         })
     }
 }
@@ -34,6 +50,18 @@ impl TryFrom<&Headers> for CommonStorageResponseHeaders {
 pub const CONTENT_CRC64: HeaderName = HeaderName::from_static("x-ms-content-crc64");
 pub const COPY_ID: HeaderName = HeaderName::from_static("x-ms-copy-id");
 pub const RENAME_SOURCE: HeaderName = HeaderName::from_static("x-ms-rename-source");
+pub const ENCRYPTION_KEY_SHA256: HeaderName = HeaderName::from_static("x-ms-encryption-key-sha256");
+pub const ENCRYPTION_SCOPE: HeaderName = HeaderName::from_static("x-ms-encryption-scope");
+
+/// The SHA-256 of the customer-provided key the data was encrypted with.
+pub fn encryption_key_sha256_from_headers_optional(headers: &Headers) -> Option<String> {
+    headers.get_optional_string(&ENCRYPTION_KEY_SHA256)
+}
+
+/// The encryption scope the data was encrypted with.
+pub fn encryption_scope_from_headers_optional(headers: &Headers) -> Option<String> {
+    headers.get_optional_string(&ENCRYPTION_SCOPE)
+}
 
 pub fn content_crc64_from_headers(headers: &Headers) -> azure_core::Result<ConsistencyCRC64> {
     headers.get_as(&CONTENT_CRC64)
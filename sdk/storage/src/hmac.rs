@@ -1,18 +1,31 @@
+use crate::crypto::HmacSha256;
 use azure_core::error::{ErrorKind, ResultExt};
 use base64::encode;
-use hmac::{Hmac, Mac};
-use sha2::Sha256;
 
 pub fn sign(data: &str, key: &str) -> azure_core::Result<String> {
-    let mut hmac = Hmac::<Sha256>::new_from_slice(
-        &base64::decode(key).with_context(ErrorKind::DataConversion, || {
-            format!("failed to decode hmac. key: {key}")
-        })?,
-    )
-    .with_context(ErrorKind::DataConversion, || {
-        format!("failed to create hmac from key: {key}")
-    })?;
-    hmac.update(data.as_bytes());
-    let signature = hmac.finalize().into_bytes();
-    Ok(encode(signature))
+    SigningKey::new(key)?.sign(data)
+}
+
+/// A decoded account key, ready to sign any number of strings.
+#[derive(Clone)]
+pub struct SigningKey(HmacSha256);
+
+impl SigningKey {
+    pub fn new(key: &str) -> azure_core::Result<Self> {
+        let hmac = HmacSha256::new(
+            &base64::decode(key).context(ErrorKind::DataConversion, "failed to decode hmac key")?,
+        )
+        .context(ErrorKind::DataConversion, "failed to create hmac from key")?;
+        Ok(Self(hmac))
+    }
+
+    pub fn sign(&self, data: &str) -> azure_core::Result<String> {
+        self.0.sign(data.as_bytes()).map(encode)
+    }
+}
+
+impl std::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKey").finish_non_exhaustive()
+    }
 }
@@ -25,26 +25,50 @@ extern crate serde_derive;
 #[macro_use]
 extern crate azure_core;
 
+mod account_info;
+mod api_version;
 mod authorization;
+mod circuit_breaker;
 pub mod clients;
+mod clock_skew;
 mod cloud_location;
+mod config;
 mod connection_string;
 mod connection_string_builder;
 mod copy_id;
 mod copy_progress;
+pub mod crypto;
+pub mod dev;
+mod endpoint_profile;
+#[cfg(feature = "fault-injection")]
+pub mod fault_injection;
 pub mod hmac;
 mod macros;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod prelude;
+mod read_only;
+mod request_correlation;
+mod retry_stats;
 pub mod shared_access_signature;
 
 pub use self::connection_string::{ConnectionString, EndpointProtocol};
 pub use self::connection_string_builder::ConnectionStringBuilder;
-pub use authorization::StorageCredentials;
+pub use account_info::{AccountKind, SkuName};
+pub use api_version::Feature;
+pub use authorization::{authorize_request, StorageCredentials};
+pub use circuit_breaker::{CircuitBreakerOpen, CircuitBreakerOptions, CircuitBreakerPolicy};
+pub use clock_skew::ClockSkewPolicy;
 pub use cloud_location::*;
+pub use config::{AuthConfig, CloudConfig, RetryConfig, StorageConfig};
+pub use endpoint_profile::{EndpointProfile, UnsupportedFeature};
+pub use read_only::{ReadOnlyPolicy, ReadOnlyViolation};
+pub use retry_stats::RetryStats;
 pub mod headers;
 pub use copy_id::{copy_id_from_headers, CopyId};
 pub use copy_progress::CopyProgress;
 pub mod parsing_xml;
+mod storage_request;
 mod stored_access_policy;
 pub use azure_core::error::{Error, ErrorKind, ResultExt};
 
@@ -54,6 +78,7 @@ pub struct IPRange {
     pub end: std::net::IpAddr,
 }
 
+pub use storage_request::{StorageClient, StorageRequest};
 pub use stored_access_policy::{StoredAccessPolicy, StoredAccessPolicyList};
 
 pub use consistency::{ConsistencyCRC64, ConsistencyMD5};
@@ -62,7 +87,7 @@ mod consistency {
     use azure_core::error::{Error, ErrorKind, ResultExt};
     use bytes::Bytes;
     use serde::{Deserialize, Deserializer};
-    use std::{convert::TryInto, str::FromStr};
+    use std::{convert::TryInto, fmt, str::FromStr};
 
     #[derive(Debug, Clone, PartialEq, Eq)]
     pub struct ConsistencyCRC64(Bytes);
@@ -84,6 +109,14 @@ mod consistency {
                 })),
             }
         }
+        /// The CRC64 of `data`.
+        pub fn compute(data: &[u8]) -> Self {
+            Self::from(crate::crypto::crc64(data))
+        }
+        /// Encodes to base64, as sent in the `x-ms-content-crc64` header
+        pub fn encode(&self) -> String {
+            base64::encode(&self.0)
+        }
         pub fn bytes(&self) -> &Bytes {
             &self.0
         }
@@ -91,6 +124,34 @@ mod consistency {
             // we check the length when decoding, so this unwrap is safe
             self.0.as_ref().try_into().unwrap()
         }
+        /// The CRC64 as a number. Its bytes are stored little-endian.
+        pub fn value(&self) -> u64 {
+            u64::from_le_bytes(*self.as_slice())
+        }
+    }
+
+    impl From<u64> for ConsistencyCRC64 {
+        fn from(crc64: u64) -> Self {
+            Self::from(crc64.to_le_bytes())
+        }
+    }
+
+    impl From<[u8; CRC64_BYTE_LENGTH]> for ConsistencyCRC64 {
+        fn from(bytes: [u8; CRC64_BYTE_LENGTH]) -> Self {
+            Self(Bytes::copy_from_slice(&bytes))
+        }
+    }
+
+    impl PartialEq<u64> for ConsistencyCRC64 {
+        fn eq(&self, other: &u64) -> bool {
+            self.value() == *other
+        }
+    }
+
+    impl fmt::Display for ConsistencyCRC64 {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.encode())
+        }
     }
 
     impl AsRef<[u8; CRC64_BYTE_LENGTH]> for ConsistencyCRC64 {
@@ -137,6 +198,15 @@ mod consistency {
                 })),
             }
         }
+        /// The MD5 of `data`.
+        #[cfg(any(feature = "md5", feature = "md5-openssl"))]
+        pub fn compute(data: &[u8]) -> azure_core::Result<Self> {
+            crate::crypto::md5(data).map(Self::from)
+        }
+        /// Encodes to base64, as sent in the `Content-MD5` header
+        pub fn encode(&self) -> String {
+            base64::encode(&self.0)
+        }
         pub fn bytes(&self) -> &Bytes {
             &self.0
         }
@@ -146,6 +216,24 @@ mod consistency {
         }
     }
 
+    impl From<[u8; MD5_BYTE_LENGTH]> for ConsistencyMD5 {
+        fn from(bytes: [u8; MD5_BYTE_LENGTH]) -> Self {
+            Self(Bytes::copy_from_slice(&bytes))
+        }
+    }
+
+    impl PartialEq<[u8; MD5_BYTE_LENGTH]> for ConsistencyMD5 {
+        fn eq(&self, other: &[u8; MD5_BYTE_LENGTH]) -> bool {
+            self.as_slice() == other
+        }
+    }
+
+    impl fmt::Display for ConsistencyMD5 {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.encode())
+        }
+    }
+
     impl AsRef<[u8; MD5_BYTE_LENGTH]> for ConsistencyMD5 {
         fn as_ref(&self) -> &[u8; MD5_BYTE_LENGTH] {
             self.as_slice()
@@ -201,5 +289,27 @@ mod consistency {
                 ]))
             );
         }
+
+        #[test]
+        fn test_consistency_encoding() {
+            let md5 = ConsistencyMD5::decode("1B2M2Y8AsgTpgAmY7PhCfg==").unwrap();
+            assert_eq!(md5.to_string(), "1B2M2Y8AsgTpgAmY7PhCfg==");
+            assert_eq!(ConsistencyMD5::decode(md5.encode()).unwrap(), md5);
+            #[cfg(any(feature = "md5", feature = "md5-openssl"))]
+            {
+                assert_eq!(ConsistencyMD5::compute(b"").unwrap(), md5);
+                assert_eq!(md5, crate::crypto::md5(b"").unwrap());
+            }
+            assert!(ConsistencyMD5::decode("AAAA").is_err());
+
+            let crc64 = ConsistencyCRC64::compute(b"123456789");
+            assert_eq!(crc64, 0xAE8B_1486_0A79_9888);
+            assert_eq!(crc64.as_slice(), &0xAE8B_1486_0A79_9888u64.to_le_bytes());
+            assert_eq!(
+                crc64.to_string().parse::<ConsistencyCRC64>().unwrap(),
+                crc64
+            );
+            assert_eq!(ConsistencyCRC64::from(0).to_string(), "AAAAAAAAAAA=");
+        }
     }
 }
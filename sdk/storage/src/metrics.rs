@@ -0,0 +1,239 @@
+//! Metrics reported for every request sent through a storage pipeline.
+//!
+//! Register a [`MetricsSink`] with [`with_metrics`] (or the `metrics` method
+//! of the service client builders) to receive a [`RequestMetrics`] once each
+//! operation completes, including all of its retries.
+use crate::{clients::ServiceType, retry_stats::Attempts};
+use azure_core::{
+    error::ErrorKind, headers::CONTENT_LENGTH, ClientOptions, Context, Method, Policy,
+    PolicyResult, Request, StatusCode,
+};
+use std::{fmt::Debug, sync::Arc, time::Duration};
+use time::OffsetDateTime;
+
+/// The class of an HTTP status code, handy as a low cardinality label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusClass {
+    Informational,
+    Success,
+    Redirection,
+    ClientError,
+    ServerError,
+    /// No response was received, e.g. because of an I/O error.
+    NoResponse,
+}
+
+impl StatusClass {
+    pub fn from_status(status: StatusCode) -> Self {
+        match status as u16 {
+            100..=199 => StatusClass::Informational,
+            200..=299 => StatusClass::Success,
+            300..=399 => StatusClass::Redirection,
+            400..=499 => StatusClass::ClientError,
+            _ => StatusClass::ServerError,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusClass::Informational => "1xx",
+            StatusClass::Success => "2xx",
+            StatusClass::Redirection => "3xx",
+            StatusClass::ClientError => "4xx",
+            StatusClass::ServerError => "5xx",
+            StatusClass::NoResponse => "none",
+        }
+    }
+}
+
+/// What happened to a single operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestMetrics {
+    pub service: Option<ServiceType>,
+    /// The HTTP method and the `comp` query parameter, e.g. `PUT blocklist`.
+    pub operation: String,
+    pub status: Option<StatusCode>,
+    pub status_class: StatusClass,
+    /// Time from the first attempt until the final response, including any
+    /// time spent waiting between retries.
+    pub latency: Duration,
+    /// Number of attempts beyond the first one.
+    pub retries: u32,
+    pub request_bytes: u64,
+    /// The `Content-Length` of the final response, if any.
+    pub response_bytes: Option<u64>,
+}
+
+/// Receives the metrics of every operation.
+pub trait MetricsSink: Debug + Send + Sync {
+    fn record(&self, metrics: &RequestMetrics);
+}
+
+/// Adds the policy reporting to `sink` to the client options.
+pub fn with_metrics(mut options: ClientOptions, sink: Arc<dyn MetricsSink>) -> ClientOptions {
+    options
+        .per_call_policies_mut()
+        .push(Arc::new(MetricsPolicy { sink }));
+    options
+}
+
+/// Measures whole operations. It runs above the retry policy, and reads the
+/// attempts counted for the [`RetryStats`](crate::RetryStats) of the storage
+/// pipeline.
+#[derive(Debug, Clone)]
+struct MetricsPolicy {
+    sink: Arc<dyn MetricsSink>,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for MetricsPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let start = OffsetDateTime::now_utc();
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        let latency = (OffsetDateTime::now_utc() - start)
+            .try_into()
+            .unwrap_or_default();
+
+        let (status, response_bytes) = match &result {
+            Ok(response) => (
+                Some(response.status()),
+                response
+                    .headers()
+                    .get_optional_as::<u64, _>(&CONTENT_LENGTH)
+                    .ok()
+                    .flatten(),
+            ),
+            Err(error) => match error.kind() {
+                ErrorKind::HttpResponse { status, .. } => (Some(*status), None),
+                _ => (None, None),
+            },
+        };
+
+        self.sink.record(&RequestMetrics {
+            service: ctx.get::<ServiceType>().copied(),
+            operation: operation_name(request),
+            status,
+            status_class: status.map_or(StatusClass::NoResponse, StatusClass::from_status),
+            latency,
+            retries: ctx
+                .get::<Attempts>()
+                .map_or(0, |attempts| attempts.count().saturating_sub(1)),
+            request_bytes: request.body().len() as u64,
+            response_bytes,
+        });
+        result
+    }
+}
+
+fn operation_name(request: &Request) -> String {
+    let method = match request.method() {
+        Method::Get => "GET",
+        Method::Head => "HEAD",
+        Method::Put => "PUT",
+        Method::Post => "POST",
+        Method::Delete => "DELETE",
+        Method::Patch => "PATCH",
+        _ => "OTHER",
+    };
+    match request.url().query_pairs().find(|(key, _)| key == "comp") {
+        Some((_, comp)) => format!("{method} {comp}"),
+        None => method.to_owned(),
+    }
+}
+
+/// Reports to the global recorder of the [`metrics`](https://docs.rs/metrics)
+/// crate.
+///
+/// Records the `azure_storage_requests_total` counter, the
+/// `azure_storage_request_duration_seconds` histogram, the
+/// `azure_storage_retries_total` counter and the `azure_storage_request_bytes`
+/// and `azure_storage_response_bytes` histograms, labelled with the service,
+/// operation and status class.
+#[cfg(feature = "metrics-rs")]
+#[derive(Debug, Clone, Default)]
+pub struct MetricsRsSink;
+
+#[cfg(feature = "metrics-rs")]
+impl MetricsSink for MetricsRsSink {
+    fn record(&self, metrics: &RequestMetrics) {
+        let service = metrics.service.map_or("unknown", |service| match service {
+            ServiceType::Blob => "blob",
+            ServiceType::Queue => "queue",
+            ServiceType::Table => "table",
+            ServiceType::DataLake => "dfs",
+        });
+        let labels = [
+            ("service", service.to_owned()),
+            ("operation", metrics.operation.clone()),
+            ("status_class", metrics.status_class.as_str().to_owned()),
+        ];
+
+        ::metrics::counter!("azure_storage_requests_total", 1, &labels);
+        ::metrics::histogram!(
+            "azure_storage_request_duration_seconds",
+            metrics.latency.as_secs_f64(),
+            &labels
+        );
+        ::metrics::counter!(
+            "azure_storage_retries_total",
+            u64::from(metrics.retries),
+            &labels
+        );
+        ::metrics::histogram!(
+            "azure_storage_request_bytes",
+            metrics.request_bytes as f64,
+            &labels
+        );
+        if let Some(response_bytes) = metrics.response_bytes {
+            ::metrics::histogram!(
+                "azure_storage_response_bytes",
+                response_bytes as f64,
+                &labels
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    #[test]
+    fn test_operation_name() {
+        let request = Request::new(
+            Url::parse("https://account.blob.core.windows.net/container/blob?comp=blocklist")
+                .unwrap(),
+            Method::Put,
+        );
+        assert_eq!(operation_name(&request), "PUT blocklist");
+
+        let request = Request::new(
+            Url::parse("https://account.blob.core.windows.net/container/blob").unwrap(),
+            Method::Get,
+        );
+        assert_eq!(operation_name(&request), "GET");
+    }
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(
+            StatusClass::from_status(StatusCode::Created),
+            StatusClass::Success
+        );
+        assert_eq!(
+            StatusClass::from_status(StatusCode::NotFound),
+            StatusClass::ClientError
+        );
+        assert_eq!(
+            StatusClass::from_status(StatusCode::ServiceUnavailable),
+            StatusClass::ServerError
+        );
+    }
+}
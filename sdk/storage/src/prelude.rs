@@ -3,7 +3,7 @@ pub use crate::{
     shared_access_signature::{
         account_sas::{AccountSasPermissions, AccountSasResource, AccountSasResourceType},
         service_sas::{BlobSasPermissions, BlobSignedResource},
-        SasProtocol, SasToken,
+        SasProtocol, SasToken, SignedIp, SignedSasToken,
     },
     CopyId, IPRange, StorageCredentials,
 };
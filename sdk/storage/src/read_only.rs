@@ -0,0 +1,55 @@
+use azure_core::{
+    error::{Error, ErrorKind},
+    Context, Method, Policy, PolicyResult, Request,
+};
+use std::sync::Arc;
+
+/// The error returned, as the source of an `ErrorKind::Other` error, for
+/// requests which would modify data through a read-only client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadOnlyViolation {
+    pub method: Method,
+    pub url: String,
+}
+
+impl std::fmt::Display for ReadOnlyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} is not allowed on a read-only client",
+            self.method, self.url
+        )
+    }
+}
+
+impl std::error::Error for ReadOnlyViolation {}
+
+/// A per-call policy which only lets `GET` and `HEAD` requests through, so
+/// that a client meant for reading fails before sending anything else.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadOnlyPolicy;
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for ReadOnlyPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if !matches!(request.method(), &Method::Get | &Method::Head) {
+            let mut url = request.url().clone();
+            // the query may hold a SAS
+            url.set_query(None);
+            return Err(Error::new(
+                ErrorKind::Other,
+                ReadOnlyViolation {
+                    method: *request.method(),
+                    url: url.to_string(),
+                },
+            ));
+        }
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
@@ -0,0 +1,108 @@
+use azure_core::{
+    error::ErrorKind, headers::CLIENT_REQUEST_ID, Context, Policy, PolicyResult, Request,
+};
+use std::sync::Arc;
+
+/// Sends every request with an `x-ms-client-request-id`, and attaches it and
+/// the name of the operation to the errors of failed requests.
+///
+/// The service logs the client request id along with its own request id, so
+/// either can be used to find a failed request in the storage analytics logs
+/// or when opening a support ticket. Both are read back with
+/// `Error::client_request_id` and `Error::request_id`.
+///
+/// Only errors caused by a response or by the connection are annotated, so
+/// errors raised by other policies keep their documented shape.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestCorrelationPolicy;
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for RequestCorrelationPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let client_request_id = match request.headers().get_optional_str(&CLIENT_REQUEST_ID) {
+            Some(client_request_id) => client_request_id.to_owned(),
+            None => {
+                let client_request_id = uuid::Uuid::new_v4().to_string();
+                request.insert_header(CLIENT_REQUEST_ID, client_request_id.clone());
+                client_request_id
+            }
+        };
+
+        let result = next[0].send(ctx, request, &next[1..]).await;
+        result.map_err(|error| match error.kind() {
+            ErrorKind::HttpResponse { .. } | ErrorKind::Io => {
+                error.with_operation(operation_name(request), Some(client_request_id))
+            }
+            _ => error,
+        })
+    }
+}
+
+/// Names the operation of a request by its method and path, along with the
+/// `restype` and `comp` query parameters which select the operation on a
+/// resource. Other query parameters, such as SAS tokens, are left out.
+fn operation_name(request: &Request) -> String {
+    let url = request.url();
+    let mut name = format!("{} {}", request.method(), url.path());
+    let selectors: Vec<String> = url
+        .query_pairs()
+        .filter(|(key, _)| key == "restype" || key == "comp")
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect();
+    if !selectors.is_empty() {
+        name.push('?');
+        name.push_str(&selectors.join("&"));
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{Method, StatusCode};
+
+    #[derive(Debug)]
+    struct NotFound;
+
+    #[async_trait::async_trait]
+    impl Policy for NotFound {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            Err(
+                ErrorKind::http_response(StatusCode::NotFound, Some("BlobNotFound".into()))
+                    .into_error(),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_attaches_operation() {
+        let next: Vec<Arc<dyn Policy>> = vec![Arc::new(NotFound)];
+        let mut request = Request::new(
+            "https://account.blob.core.windows.net/container/blob?comp=tags&sig=secret"
+                .parse()
+                .unwrap(),
+            Method::Get,
+        );
+        let error = RequestCorrelationPolicy
+            .send(&Context::new(), &mut request, &next)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.operation(), Some("GET /container/blob?comp=tags"));
+        assert_eq!(error.http_status(), Some(StatusCode::NotFound));
+        let client_request_id = request.headers().get_str(&CLIENT_REQUEST_ID).unwrap();
+        assert_eq!(error.client_request_id(), Some(client_request_id));
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+    }
+}
@@ -0,0 +1,175 @@
+use azure_core::{Context, Policy, PolicyResult, Request};
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// How many attempts an operation took and how long they took, along with
+/// the waits between them.
+///
+/// The pipeline attaches it to the response of every operation, and it is
+/// reported by the `attempts` and `elapsed` fields of
+/// [`CommonStorageResponseHeaders`](crate::headers::CommonStorageResponseHeaders).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryStats {
+    /// The attempts of the operation, retries included.
+    pub attempts: u32,
+    /// Time from the first attempt until the final response.
+    pub elapsed: Duration,
+}
+
+/// The attempts of the operation in flight. It is shared by the policies
+/// measuring the operation, and counted by [`AttemptCountPolicy`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Attempts(Arc<AtomicU32>);
+
+impl Attempts {
+    pub(crate) fn count(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Times the operations and attaches their [`RetryStats`] to their response.
+/// It runs above the retry policy, while [`AttemptCountPolicy`] counts the
+/// attempts below it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RetryStatsPolicy;
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for RetryStatsPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let attempts = Attempts::default();
+        let mut ctx = ctx.clone();
+        ctx.insert(attempts.clone());
+
+        let start = OffsetDateTime::now_utc();
+        let mut response = next[0].send(&ctx, request, &next[1..]).await?;
+        let elapsed = (OffsetDateTime::now_utc() - start)
+            .try_into()
+            .unwrap_or_default();
+        response.extensions_mut().insert(RetryStats {
+            attempts: attempts.count(),
+            elapsed,
+        });
+        Ok(response)
+    }
+}
+
+/// Counts the attempts made for an operation. It runs below the retry policy.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AttemptCountPolicy;
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for AttemptCountPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if let Some(Attempts(attempts)) = ctx.get::<Attempts>() {
+            attempts.fetch_add(1, Ordering::Relaxed);
+        }
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::CommonStorageResponseHeaders;
+    use azure_core::{
+        headers::{Headers, DATE, REQUEST_ID, SERVER, VERSION},
+        Method, Response, StatusCode,
+    };
+
+    /// Retries once, like a retry policy would after a transient failure.
+    #[derive(Debug)]
+    struct RetryOnce;
+
+    #[async_trait::async_trait]
+    impl Policy for RetryOnce {
+        async fn send(
+            &self,
+            ctx: &Context,
+            request: &mut Request,
+            next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            let _ = next[0].send(ctx, request, &next[1..]).await;
+            next[0].send(ctx, request, &next[1..]).await
+        }
+    }
+
+    #[derive(Debug)]
+    struct Ok200;
+
+    #[async_trait::async_trait]
+    impl Policy for Ok200 {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            let mut headers = Headers::new();
+            headers.insert(REQUEST_ID, "6c3b5ec1-201e-0030-1c16-a3bd0e000000");
+            headers.insert(VERSION, "2019-12-12");
+            headers.insert(DATE, "Tue, 04 Jun 2019 17:22:03 GMT");
+            headers.insert(SERVER, "Windows-Azure-Blob/1.0");
+            Ok(Response::new(
+                StatusCode::Ok,
+                headers,
+                Box::pin(futures::stream::empty()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_stats() -> azure_core::Result<()> {
+        let next: Vec<Arc<dyn Policy>> = vec![
+            Arc::new(RetryOnce),
+            Arc::new(AttemptCountPolicy),
+            Arc::new(Ok200),
+        ];
+        let mut request = Request::new(
+            "https://account.blob.core.windows.net/container".parse()?,
+            Method::Get,
+        );
+        let ctx = Context::new();
+        let response = RetryStatsPolicy.send(&ctx, &mut request, &next).await?;
+
+        let common = CommonStorageResponseHeaders::try_from(response.headers())?;
+        assert_eq!(common.attempts, 2);
+        assert!(common.elapsed < Duration::from_secs(60));
+        // the headers are those of the service
+        assert_eq!(
+            response.headers(),
+            Ok200.send(&ctx, &mut request, &[]).await?.headers()
+        );
+        assert_eq!(response.headers().iter().count(), 4);
+
+        // the stats are those of each operation
+        let response = RetryStatsPolicy
+            .send(&ctx, &mut request, &next[1..])
+            .await?;
+        let common = CommonStorageResponseHeaders::try_from(response.headers())?;
+        assert_eq!(common.attempts, 1);
+
+        // headers which did not go through the pipeline report one attempt
+        let response = Ok200.send(&ctx, &mut request, &[]).await?;
+        let common = CommonStorageResponseHeaders::try_from(response.headers())?;
+        assert_eq!((common.attempts, common.elapsed), (1, Duration::ZERO));
+        Ok(())
+    }
+}
@@ -1,12 +1,17 @@
 use crate::{
     hmac::sign,
-    shared_access_signature::{format_date, format_form, SasProtocol, SasToken},
+    shared_access_signature::{
+        format_date, format_form, parse_permissions, skewed_start, unknown_variant,
+        validate_signed_ip, validate_validity_period, SasProtocol, SasToken, SignatureCache,
+        SignedIp, SignedSasToken, DEFAULT_CLOCK_SKEW,
+    },
 };
-use std::fmt;
+use azure_core::{auth::Secret, error::Error};
+use std::{fmt, str::FromStr, time::Duration};
 use time::OffsetDateTime;
 
 /// Service version of the shared access signature ([Azure documentation](https://docs.microsoft.com/rest/api/storageservices/create-service-sas#specifying-the-signed-version-field)).
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum AccountSasVersion {
     V20181109,
     V20150405,
@@ -25,7 +30,21 @@ impl fmt::Display for AccountSasVersion {
     }
 }
 
-#[derive(Copy, Clone)]
+impl FromStr for AccountSasVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        match s {
+            "2018-11-09" => Ok(Self::V20181109),
+            "2015-04-05" => Ok(Self::V20150405),
+            "2013-08-15" => Ok(Self::V20130815),
+            "2012-02-12" => Ok(Self::V20120212),
+            _ => Err(unknown_variant("AccountSasVersion", s)),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum AccountSasService {
     Blob,
     Queue,
@@ -44,8 +63,22 @@ impl fmt::Display for AccountSasService {
     }
 }
 
+impl FromStr for AccountSasService {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        match s {
+            "b" => Ok(Self::Blob),
+            "q" => Ok(Self::Queue),
+            "t" => Ok(Self::Table),
+            "f" => Ok(Self::File),
+            _ => Err(unknown_variant("AccountSasService", s)),
+        }
+    }
+}
+
 /// Which resources are accessible via the shared access signature ([Azure documentation](https://docs.microsoft.com/rest/api/storageservices/create-service-sas#specifying-the-signed-resource-blob-service-only)).
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum AccountSasResource {
     Blob,
     Queue,
@@ -64,7 +97,21 @@ impl fmt::Display for AccountSasResource {
     }
 }
 
-#[derive(Copy, Clone)]
+impl FromStr for AccountSasResource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        match s {
+            "b" => Ok(Self::Blob),
+            "q" => Ok(Self::Queue),
+            "t" => Ok(Self::Table),
+            "f" => Ok(Self::File),
+            _ => Err(unknown_variant("AccountSasResource", s)),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum AccountSasResourceType {
     Service,
     Container,
@@ -81,8 +128,21 @@ impl fmt::Display for AccountSasResourceType {
     }
 }
 
+impl FromStr for AccountSasResourceType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        match s {
+            "s" => Ok(Self::Service),
+            "c" => Ok(Self::Container),
+            "o" => Ok(Self::Object),
+            _ => Err(unknown_variant("AccountSasResourceType", s)),
+        }
+    }
+}
+
 /// Indicate which operations a key_client may perform on the resource ([Azure documentation](https://docs.microsoft.com/rest/api/storageservices/create-service-sas#specifying-permissions)).
-#[derive(Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub struct AccountSasPermissions {
     pub read: bool,
     pub write: bool,
@@ -128,23 +188,56 @@ impl fmt::Display for AccountSasPermissions {
     }
 }
 
+impl FromStr for AccountSasPermissions {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        let mut permissions = Self::default();
+        parse_permissions(s, "AccountSasPermissions", |permission| {
+            match permission {
+                'r' => permissions.read = true,
+                'a' => permissions.add = true,
+                'c' => permissions.create = true,
+                'w' => permissions.write = true,
+                'd' => permissions.delete = true,
+                'l' => permissions.list = true,
+                'u' => permissions.update = true,
+                'p' => permissions.process = true,
+                _ => return false,
+            }
+            true
+        })?;
+        Ok(permissions)
+    }
+}
+
+serde_via_str!(
+    AccountSasVersion,
+    AccountSasService,
+    AccountSasResource,
+    AccountSasResourceType,
+    AccountSasPermissions,
+);
+
 pub struct AccountSharedAccessSignature {
     account: String,
-    key: String,
+    key: Secret,
     version: AccountSasVersion,
     resource: AccountSasResource,
     resource_type: AccountSasResourceType,
     expiry: OffsetDateTime,
     permissions: AccountSasPermissions,
     start: Option<OffsetDateTime>,
-    ip: Option<String>,
+    clock_skew: Option<Duration>,
+    ip: Option<SignedIp>,
     protocol: Option<SasProtocol>,
+    signature_cache: SignatureCache,
 }
 
 impl AccountSharedAccessSignature {
     pub fn new(
         account: String,
-        key: String,
+        key: impl Into<Secret>,
         resource: AccountSasResource,
         resource_type: AccountSasResourceType,
         expiry: OffsetDateTime,
@@ -152,25 +245,69 @@ impl AccountSharedAccessSignature {
     ) -> Self {
         Self {
             account,
-            key,
+            key: key.into(),
             version: AccountSasVersion::V20181109,
             resource,
             resource_type,
             expiry,
             permissions,
             start: None,
+            clock_skew: None,
             ip: None,
             protocol: None,
+            signature_cache: SignatureCache::default(),
         }
     }
 
     setters! {
         version: AccountSasVersion => version,
         start: OffsetDateTime => Some(start),
-        ip: String => Some(ip),
+        ip: SignedIp => Some(ip),
         protocol: SasProtocol => Some(protocol),
     }
 
+    /// Tolerate up to `clock_skew` of difference between the local clock and
+    /// the clock of the service, by moving the start of the SAS back by that
+    /// much. If no start was set, the SAS starts now, minus `clock_skew`.
+    pub fn clock_skew(self, clock_skew: Duration) -> Self {
+        Self {
+            start: self.start.or_else(|| Some(OffsetDateTime::now_utc())),
+            clock_skew: Some(clock_skew),
+            ..self
+        }
+    }
+
+    /// Tolerate [`DEFAULT_CLOCK_SKEW`] of clock skew, see
+    /// [`clock_skew`](Self::clock_skew).
+    pub fn backdate_start(self) -> Self {
+        self.clock_skew(DEFAULT_CLOCK_SKEW)
+    }
+
+    /// Apply the recommended restrictions in one call: the SAS is only
+    /// accepted over HTTPS, from `ip`, and expires `lifetime` from now. Its
+    /// start is backdated by [`DEFAULT_CLOCK_SKEW`].
+    pub fn restrict(self, ip: impl Into<SignedIp>, lifetime: Duration) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            start: Some(now),
+            expiry: now + lifetime,
+            ip: Some(ip.into()),
+            protocol: Some(SasProtocol::Https),
+            ..self
+        }
+        .backdate_start()
+    }
+
+    /// Sign the SAS once, for reuse across many URLs.
+    pub fn freeze(&self) -> azure_core::Result<SignedSasToken> {
+        self.validate()?;
+        Ok(SignedSasToken::new(self.token(), self.expiry))
+    }
+
+    fn effective_start(&self) -> Option<OffsetDateTime> {
+        skewed_start(self.start, self.clock_skew)
+    }
+
     // Azure documentation: https://docs.microsoft.com/rest/api/storageservices/create-service-sas#constructing-the-signature-string
     fn signature(&self) -> String {
         match self.version {
@@ -181,16 +318,18 @@ impl AccountSharedAccessSignature {
                     self.permissions,
                     self.resource,
                     self.resource_type,
-                    self.start.map_or("".to_string(), format_date),
+                    self.effective_start().map_or("".to_string(), format_date),
                     format_date(self.expiry),
-                    self.ip.clone().unwrap_or_default(),
+                    self.ip.map_or(String::new(), |ip| ip.to_string()),
                     self.protocol
                         .as_ref()
                         .map_or("".to_string(), |v| v.to_string()),
                     self.version,
                 );
 
-                sign(&string_to_sign, &self.key).unwrap()
+                self.signature_cache.sign(string_to_sign, |string_to_sign| {
+                    sign(string_to_sign, self.key.secret()).unwrap()
+                })
             }
             _ => {
                 // TODO: support other version tags?
@@ -211,8 +350,8 @@ impl SasToken for AccountSharedAccessSignature {
             format!("sp={}", self.permissions),
         ];
 
-        if let Some(start) = &self.start {
-            elements.push(format!("st={}", format_form(format_date(*start))))
+        if let Some(start) = self.effective_start() {
+            elements.push(format!("st={}", format_form(format_date(start))))
         }
         if let Some(ip) = &self.ip {
             elements.push(format!("sip={ip}"))
@@ -225,6 +364,11 @@ impl SasToken for AccountSharedAccessSignature {
 
         elements.join("&")
     }
+
+    fn validate(&self) -> azure_core::Result<()> {
+        validate_validity_period(self.effective_start(), self.expiry)?;
+        validate_signed_ip(self.ip)
+    }
 }
 
 impl PartialEq for AccountSharedAccessSignature {
@@ -238,3 +382,54 @@ impl std::fmt::Debug for AccountSharedAccessSignature {
         write!(f, "SharedAccessSignature {{{}}}", self.signature())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_sas_permissions_round_trip() {
+        let permissions: AccountSasPermissions = "rwdl".parse().unwrap();
+        assert_eq!(
+            permissions,
+            AccountSasPermissions {
+                read: true,
+                write: true,
+                delete: true,
+                list: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(permissions.to_string(), "rwdl");
+        assert!("rz".parse::<AccountSasPermissions>().is_err());
+
+        let json = serde_json::to_string(&permissions).unwrap();
+        assert_eq!(json, "\"rwdl\"");
+        assert_eq!(
+            serde_json::from_str::<AccountSasPermissions>(&json).unwrap(),
+            permissions
+        );
+    }
+
+    #[test]
+    fn test_account_sas_enums_round_trip() {
+        for resource_type in [
+            AccountSasResourceType::Service,
+            AccountSasResourceType::Container,
+            AccountSasResourceType::Object,
+        ] {
+            assert_eq!(
+                resource_type
+                    .to_string()
+                    .parse::<AccountSasResourceType>()
+                    .unwrap(),
+                resource_type
+            );
+        }
+        assert_eq!(
+            serde_json::from_str::<AccountSasVersion>("\"2018-11-09\"").unwrap(),
+            AccountSasVersion::V20181109
+        );
+        assert!("x".parse::<AccountSasResource>().is_err());
+    }
+}
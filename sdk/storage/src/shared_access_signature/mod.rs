@@ -1,12 +1,146 @@
-use std::fmt;
+use azure_core::error::{Error, ErrorKind};
+use std::{
+    fmt,
+    net::IpAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use time::OffsetDateTime;
 use url::form_urlencoded;
 
+/// Implements `Serialize` and `Deserialize` with the `Display` and `FromStr`
+/// impls, so the values look the same in configuration files as in SAS tokens.
+macro_rules! serde_via_str {
+    ($($name:ty),* $(,)?) => {
+        $(
+            impl serde::Serialize for $name {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let s = String::deserialize(deserializer)?;
+                    s.parse().map_err(serde::de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
 pub mod account_sas;
 pub mod service_sas;
 
+/// The clock skew tolerated by the `backdate_start` method of the shared
+/// access signatures.
+pub const DEFAULT_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
 pub trait SasToken {
     fn token(&self) -> String;
+
+    /// Checks the token can be valid at all, i.e. that it expires after it
+    /// starts.
+    fn validate(&self) -> azure_core::Result<()> {
+        Ok(())
+    }
+}
+
+/// A SAS token whose signature was computed once, e.g. by the `freeze` method
+/// of the shared access signatures.
+///
+/// Clones share the token, which makes it cheap to sign many URLs with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedSasToken {
+    token: Arc<str>,
+    expiry: OffsetDateTime,
+}
+
+impl SignedSasToken {
+    pub(crate) fn new(token: String, expiry: OffsetDateTime) -> Self {
+        Self {
+            token: token.into(),
+            expiry,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.token
+    }
+
+    pub fn expiry(&self) -> OffsetDateTime {
+        self.expiry
+    }
+}
+
+impl SasToken for SignedSasToken {
+    fn token(&self) -> String {
+        self.token.to_string()
+    }
+}
+
+impl fmt::Display for SignedSasToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.token)
+    }
+}
+
+/// Remembers the signature of the last string to sign, so calling `token`
+/// repeatedly on an unchanged SAS computes the HMAC only once.
+#[derive(Debug, Default)]
+pub(crate) struct SignatureCache(Mutex<Option<(String, String)>>);
+
+impl SignatureCache {
+    pub(crate) fn sign(&self, string_to_sign: String, sign: impl FnOnce(&str) -> String) -> String {
+        let mut cache = self.0.lock().unwrap();
+        match &*cache {
+            Some((signed, signature)) if *signed == string_to_sign => signature.clone(),
+            _ => {
+                let signature = sign(&string_to_sign);
+                *cache = Some((string_to_sign, signature.clone()));
+                signature
+            }
+        }
+    }
+}
+
+/// The start of a SAS, moved back by the tolerated clock skew.
+pub(crate) fn skewed_start(
+    start: Option<OffsetDateTime>,
+    clock_skew: Option<Duration>,
+) -> Option<OffsetDateTime> {
+    start.map(|start| start - clock_skew.unwrap_or_default())
+}
+
+pub(crate) fn validate_validity_period(
+    start: Option<OffsetDateTime>,
+    expiry: OffsetDateTime,
+) -> azure_core::Result<()> {
+    match start {
+        Some(start) if start >= expiry => Err(Error::with_message(ErrorKind::Other, || {
+            format!(
+                "the SAS expiry {} is not after its start {}",
+                format_date(expiry),
+                format_date(start)
+            )
+        })),
+        _ => Ok(()),
+    }
+}
+
+/// Checks a range from [`crate::IPRange`], which is not checked when built.
+pub(crate) fn validate_signed_ip(ip: Option<SignedIp>) -> azure_core::Result<()> {
+    match ip {
+        Some(SignedIp::Range { start, end }) => SignedIp::range(start, end).map(|_| ()),
+        _ => Ok(()),
+    }
 }
 
 /// Converts an OffsetDateTime to an RFC3339 formatted string after truncating
@@ -28,8 +162,29 @@ pub(crate) fn format_form(d: String) -> String {
     form_urlencoded::byte_serialize(d.as_bytes()).collect::<String>()
 }
 
+/// Calls `set` with each permission letter of `s`, which returns whether it
+/// knows the letter.
+pub(crate) fn parse_permissions(
+    s: &str,
+    type_name: &str,
+    mut set: impl FnMut(char) -> bool,
+) -> azure_core::Result<()> {
+    match s.chars().find(|permission| !set(*permission)) {
+        Some(permission) => Err(Error::with_message(ErrorKind::DataConversion, || {
+            format!("unknown permission of {type_name} found: '{permission}' in \"{s}\"")
+        })),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn unknown_variant(type_name: &str, s: &str) -> Error {
+    Error::with_message(ErrorKind::DataConversion, || {
+        format!("unknown variant of {type_name} found: \"{s}\"")
+    })
+}
+
 /// Specifies the protocol permitted for a request made with the SAS ([Azure documentation](https://docs.microsoft.com/rest/api/storageservices/create-service-sas#specifying-the-http-protocol)).
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SasProtocol {
     Https,
     HttpHttps,
@@ -44,6 +199,97 @@ impl fmt::Display for SasProtocol {
     }
 }
 
+impl FromStr for SasProtocol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        match s {
+            "https" => Ok(Self::Https),
+            "http,https" => Ok(Self::HttpHttps),
+            _ => Err(unknown_variant("SasProtocol", s)),
+        }
+    }
+}
+
+serde_via_str!(SasProtocol);
+
+/// The IP address, or inclusive range of addresses, from which requests made
+/// with the SAS are accepted ([Azure documentation](https://docs.microsoft.com/rest/api/storageservices/create-service-sas#specifying-ip-address-or-ip-range)).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SignedIp {
+    Address(IpAddr),
+    Range { start: IpAddr, end: IpAddr },
+}
+
+impl SignedIp {
+    /// The addresses from `start` to `end`, which must be of the same family
+    /// with `start` not after `end`.
+    pub fn range(start: IpAddr, end: IpAddr) -> azure_core::Result<Self> {
+        if start.is_ipv4() != end.is_ipv4() || start > end {
+            return Err(Error::with_message(ErrorKind::DataConversion, || {
+                format!("{start}-{end} is not a range of IP addresses")
+            }));
+        }
+        Ok(Self::Range { start, end })
+    }
+
+    /// Whether `address` is accepted.
+    pub fn contains(&self, address: IpAddr) -> bool {
+        match *self {
+            Self::Address(ip) => ip == address,
+            Self::Range { start, end } => {
+                start.is_ipv4() == address.is_ipv4() && start <= address && address <= end
+            }
+        }
+    }
+}
+
+impl From<crate::IPRange> for SignedIp {
+    fn from(range: crate::IPRange) -> Self {
+        Self::Range {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl From<IpAddr> for SignedIp {
+    fn from(address: IpAddr) -> Self {
+        Self::Address(address)
+    }
+}
+
+impl fmt::Display for SignedIp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{address}"),
+            Self::Range { start, end } => write!(f, "{start}-{end}"),
+        }
+    }
+}
+
+impl FromStr for SignedIp {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        let parse = |address: &str| {
+            address.trim().parse::<IpAddr>().map_err(|error| {
+                Error::full(
+                    ErrorKind::DataConversion,
+                    error,
+                    format!("invalid IP address in SAS IP range \"{s}\""),
+                )
+            })
+        };
+        match s.split_once('-') {
+            Some((start, end)) => Self::range(parse(start)?, parse(end)?),
+            None => Ok(Self::Address(parse(s)?)),
+        }
+    }
+}
+
+serde_via_str!(SignedIp);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,4 +301,59 @@ mod tests {
         let date = datetime!(2022-08-22 15:11:43.4185122 +00:00:00);
         assert_eq!(format_date(date), "2022-08-22T15:11:43Z");
     }
+
+    #[test]
+    fn test_signature_cache() {
+        let cache = SignatureCache::default();
+        let mut calls = 0;
+        let mut sign = |string_to_sign: &str| {
+            calls += 1;
+            string_to_sign.to_uppercase()
+        };
+        assert_eq!(cache.sign("a".to_owned(), &mut sign), "A");
+        assert_eq!(cache.sign("a".to_owned(), &mut sign), "A");
+        assert_eq!(cache.sign("b".to_owned(), &mut sign), "B");
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_validity_period() {
+        let start = datetime!(2022-08-22 15:00:00 +00:00:00);
+        let skewed = skewed_start(Some(start), Some(DEFAULT_CLOCK_SKEW));
+        assert_eq!(skewed, Some(datetime!(2022-08-22 14:55:00 +00:00:00)));
+        assert!(validate_validity_period(skewed, start).is_ok());
+        assert!(validate_validity_period(Some(start), start).is_err());
+        assert!(validate_validity_period(None, start).is_ok());
+    }
+
+    #[test]
+    fn test_sas_protocol_round_trip() {
+        for protocol in [SasProtocol::Https, SasProtocol::HttpHttps] {
+            assert_eq!(
+                protocol.to_string().parse::<SasProtocol>().unwrap(),
+                protocol
+            );
+        }
+        assert!("ftp".parse::<SasProtocol>().is_err());
+        assert_eq!(
+            serde_json::from_str::<SasProtocol>("\"http,https\"").unwrap(),
+            SasProtocol::HttpHttps
+        );
+    }
+
+    #[test]
+    fn test_signed_ip() {
+        let range: SignedIp = "168.1.5.60-168.1.5.70".parse().unwrap();
+        assert_eq!(range.to_string(), "168.1.5.60-168.1.5.70");
+        assert!(range.contains("168.1.5.65".parse().unwrap()));
+        assert!(!range.contains("168.1.5.71".parse().unwrap()));
+        assert!(!range.contains("::1".parse().unwrap()));
+
+        let address: SignedIp = "168.1.5.65".parse().unwrap();
+        assert_eq!(address, SignedIp::Address("168.1.5.65".parse().unwrap()));
+
+        assert!("168.1.5.70-168.1.5.60".parse::<SignedIp>().is_err());
+        assert!("168.1.5.60-::1".parse::<SignedIp>().is_err());
+        assert!("localhost".parse::<SignedIp>().is_err());
+    }
 }
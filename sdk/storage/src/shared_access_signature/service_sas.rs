@@ -1,12 +1,21 @@
 use crate::{
-    hmac,
-    shared_access_signature::{format_date, format_form, SasProtocol, SasToken},
+    hmac::SigningKey,
+    shared_access_signature::{
+        format_date, format_form, parse_permissions, skewed_start, unknown_variant,
+        validate_signed_ip, validate_validity_period, SasProtocol, SasToken, SignatureCache,
+        SignedIp, SignedSasToken, DEFAULT_CLOCK_SKEW,
+    },
 };
-use std::fmt;
+use azure_core::{
+    auth::Secret,
+    error::{Error, ErrorKind},
+};
+use std::{fmt, str::FromStr, sync::Arc, time::Duration};
 use time::OffsetDateTime;
 
 const SERVICE_SAS_VERSION: &str = "2020-06-12";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BlobSignedResource {
     Blob,         // b
     BlobVersion,  // bv
@@ -27,7 +36,22 @@ impl fmt::Display for BlobSignedResource {
     }
 }
 
-#[derive(Default)]
+impl FromStr for BlobSignedResource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        match s {
+            "b" => Ok(Self::Blob),
+            "bv" => Ok(Self::BlobVersion),
+            "bs" => Ok(Self::BlobSnapshot),
+            "c" => Ok(Self::Container),
+            "d" => Ok(Self::Directory),
+            _ => Err(unknown_variant("BlobSignedResource", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct BlobSasPermissions {
     pub read: bool,             // r - Container | Directory | Blob
     pub add: bool,              // a - Container | Directory | Blob
@@ -90,57 +114,182 @@ impl fmt::Display for BlobSasPermissions {
     }
 }
 
+impl FromStr for BlobSasPermissions {
+    type Err = Error;
+
+    fn from_str(s: &str) -> azure_core::Result<Self> {
+        let mut permissions = Self::default();
+        parse_permissions(s, "BlobSasPermissions", |permission| {
+            match permission {
+                'r' => permissions.read = true,
+                'a' => permissions.add = true,
+                'c' => permissions.create = true,
+                'w' => permissions.write = true,
+                'd' => permissions.delete = true,
+                'x' => permissions.delete_version = true,
+                'y' => permissions.permanent_delete = true,
+                'l' => permissions.list = true,
+                't' => permissions.tags = true,
+                'm' => permissions.move_ = true,
+                'e' => permissions.execute = true,
+                'o' => permissions.ownership = true,
+                'p' => permissions.permissions = true,
+                _ => return false,
+            }
+            true
+        })?;
+        Ok(permissions)
+    }
+}
+
+serde_via_str!(BlobSignedResource, BlobSasPermissions);
+
 pub struct BlobSharedAccessSignature {
-    key: String,
+    key: Arc<SigningKey>,
     canonicalized_resource: String,
     resource: BlobSignedResource,
     permissions: BlobSasPermissions, // sp
     expiry: OffsetDateTime,          // se
     start: Option<OffsetDateTime>,   // st
+    clock_skew: Option<Duration>,
     identifier: Option<String>,
-    ip: Option<String>,
+    ip: Option<SignedIp>,
     protocol: Option<SasProtocol>,
+    signature_cache: SignatureCache,
 }
 
 impl BlobSharedAccessSignature {
+    /// # Panics
+    ///
+    /// Panics if `key` is not a base64 encoded account key, see
+    /// [`try_new`](Self::try_new).
     pub fn new(
-        key: String,
+        key: impl Into<Secret>,
         canonicalized_resource: String,
         permissions: BlobSasPermissions,
         expiry: OffsetDateTime,
         resource: BlobSignedResource,
     ) -> Self {
-        Self {
-            key,
+        Self::try_new(key, canonicalized_resource, permissions, expiry, resource)
+            .expect("invalid account key")
+    }
+
+    /// As [`new`](Self::new), failing if `key` is not a base64 encoded account
+    /// key.
+    pub fn try_new(
+        key: impl Into<Secret>,
+        canonicalized_resource: String,
+        permissions: BlobSasPermissions,
+        expiry: OffsetDateTime,
+        resource: BlobSignedResource,
+    ) -> azure_core::Result<Self> {
+        Ok(Self {
+            key: Arc::new(SigningKey::new(key.into().secret())?),
             canonicalized_resource,
             resource,
             permissions,
             expiry,
             start: None,
+            clock_skew: None,
             identifier: None,
             ip: None,
             protocol: None,
-        }
+            signature_cache: SignatureCache::default(),
+        })
     }
 
     setters! {
         start: OffsetDateTime => Some(start),
         identifier: String => Some(identifier),
-        ip: String => Some(ip),
+        ip: SignedIp => Some(ip),
         protocol: SasProtocol => Some(protocol),
     }
 
+    /// The resource the SAS is signed for, e.g. `/blob/account/container`.
+    pub fn canonicalized_resource(&self) -> &str {
+        &self.canonicalized_resource
+    }
+
+    /// Tolerate up to `clock_skew` of difference between the local clock and
+    /// the clock of the service, by moving the start of the SAS back by that
+    /// much. If no start was set, the SAS starts now, minus `clock_skew`.
+    pub fn clock_skew(self, clock_skew: Duration) -> Self {
+        Self {
+            start: self.start.or_else(|| Some(OffsetDateTime::now_utc())),
+            clock_skew: Some(clock_skew),
+            ..self
+        }
+    }
+
+    /// Tolerate [`DEFAULT_CLOCK_SKEW`] of clock skew, see
+    /// [`clock_skew`](Self::clock_skew).
+    pub fn backdate_start(self) -> Self {
+        self.clock_skew(DEFAULT_CLOCK_SKEW)
+    }
+
+    /// Apply the recommended restrictions in one call: the SAS is only
+    /// accepted over HTTPS, from `ip`, and expires `lifetime` from now. Its
+    /// start is backdated by [`DEFAULT_CLOCK_SKEW`].
+    pub fn restrict(self, ip: impl Into<SignedIp>, lifetime: Duration) -> Self {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            start: Some(now),
+            expiry: now + lifetime,
+            ip: Some(ip.into()),
+            protocol: Some(SasProtocol::Https),
+            ..self
+        }
+        .backdate_start()
+    }
+
+    /// Derive the SAS for the blob `blob_name` from a container SAS, with the
+    /// same permissions, validity and restrictions.
+    ///
+    /// The account key is decoded only once for all the derived SAS, which
+    /// can be created and signed from multiple threads.
+    pub fn for_blob(&self, blob_name: &str) -> azure_core::Result<Self> {
+        if !matches!(self.resource, BlobSignedResource::Container) {
+            return Err(Error::message(
+                ErrorKind::Other,
+                "blob SAS can only be derived from a container SAS",
+            ));
+        }
+        Ok(Self {
+            key: self.key.clone(),
+            canonicalized_resource: format!("{}/{}", self.canonicalized_resource, blob_name),
+            resource: BlobSignedResource::Blob,
+            permissions: self.permissions,
+            expiry: self.expiry,
+            start: self.start,
+            clock_skew: self.clock_skew,
+            identifier: self.identifier.clone(),
+            ip: self.ip,
+            protocol: self.protocol,
+            signature_cache: SignatureCache::default(),
+        })
+    }
+
+    /// Sign the SAS once, for reuse across many URLs.
+    pub fn freeze(&self) -> azure_core::Result<SignedSasToken> {
+        self.validate()?;
+        Ok(SignedSasToken::new(self.token(), self.expiry))
+    }
+
+    fn effective_start(&self) -> Option<OffsetDateTime> {
+        skewed_start(self.start, self.clock_skew)
+    }
+
     fn sign(&self) -> String {
         let content = vec![
             self.permissions.to_string(),
-            self.start.map_or("".to_string(), format_date),
+            self.effective_start().map_or("".to_string(), format_date),
             format_date(self.expiry),
             self.canonicalized_resource.clone(),
             self.identifier
                 .as_ref()
                 .unwrap_or(&"".to_string())
                 .to_string(),
-            self.ip.as_ref().unwrap_or(&"".to_string()).to_string(),
+            self.ip.map_or(String::new(), |ip| ip.to_string()),
             self.protocol
                 .map(|x| x.to_string())
                 .unwrap_or_else(|| "".to_string()),
@@ -154,7 +303,10 @@ impl BlobSharedAccessSignature {
             "".to_string(), // rsct
         ];
 
-        hmac::sign(&content.join("\n"), &self.key).expect("HMAC signing failed")
+        self.signature_cache
+            .sign(content.join("\n"), |string_to_sign| {
+                self.key.sign(string_to_sign).expect("HMAC signing failed")
+            })
     }
 }
 
@@ -167,8 +319,8 @@ impl SasToken for BlobSharedAccessSignature {
             format!("se={}", format_form(format_date(self.expiry))),
         ];
 
-        if let Some(start) = &self.start {
-            elements.push(format!("st={}", format_form(format_date(*start))))
+        if let Some(start) = self.effective_start() {
+            elements.push(format!("st={}", format_form(format_date(start))))
         }
 
         if let Some(ip) = &self.ip {
@@ -184,4 +336,105 @@ impl SasToken for BlobSharedAccessSignature {
 
         elements.join("&")
     }
+
+    fn validate(&self) -> azure_core::Result<()> {
+        validate_validity_period(self.effective_start(), self.expiry)?;
+        validate_signed_ip(self.ip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze() {
+        let expiry = OffsetDateTime::now_utc() + Duration::from_secs(3600);
+        let sas = BlobSharedAccessSignature::new(
+            base64::encode("key"),
+            "/blob/account/container/blob".to_owned(),
+            "r".parse().unwrap(),
+            expiry,
+            BlobSignedResource::Blob,
+        );
+        let signed = sas.freeze().unwrap();
+        assert_eq!(signed.as_str(), sas.token());
+        assert_eq!(signed.expiry(), expiry);
+
+        let sas = sas.start(expiry);
+        assert!(sas.freeze().is_err());
+
+        // the key is decoded when the SAS is created, not when it is signed
+        assert!(BlobSharedAccessSignature::try_new(
+            "not base64!",
+            "/blob/account/container/blob".to_owned(),
+            "r".parse().unwrap(),
+            expiry,
+            BlobSignedResource::Blob,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_for_blob() {
+        let key = base64::encode("key");
+        let expiry = OffsetDateTime::now_utc() + Duration::from_secs(3600);
+        let container_sas = BlobSharedAccessSignature::new(
+            key.clone(),
+            "/blob/account/container".to_owned(),
+            "r".parse().unwrap(),
+            expiry,
+            BlobSignedResource::Container,
+        );
+        let blob_sas = BlobSharedAccessSignature::new(
+            key,
+            "/blob/account/container/blob".to_owned(),
+            "r".parse().unwrap(),
+            expiry,
+            BlobSignedResource::Blob,
+        );
+        let derived = container_sas.for_blob("blob").unwrap();
+        assert_eq!(derived.token(), blob_sas.token());
+        assert!(derived.for_blob("other").is_err());
+    }
+
+    #[test]
+    fn test_restrict() {
+        let lifetime = Duration::from_secs(600);
+        let sas = BlobSharedAccessSignature::new(
+            base64::encode("key"),
+            "/blob/account/container/blob".to_owned(),
+            "r".parse().unwrap(),
+            OffsetDateTime::now_utc() + Duration::from_secs(3600 * 24 * 365),
+            BlobSignedResource::Blob,
+        )
+        .restrict("10.0.0.1-10.0.0.9".parse::<SignedIp>().unwrap(), lifetime);
+        assert!(sas.expiry <= OffsetDateTime::now_utc() + lifetime);
+        assert!(
+            sas.effective_start().unwrap() < OffsetDateTime::now_utc() - DEFAULT_CLOCK_SKEW / 2
+        );
+        let token = sas.freeze().unwrap();
+        assert!(token.as_str().contains("&sip=10.0.0.1-10.0.0.9&spr=https&"));
+
+        let reversed = crate::IPRange {
+            start: "10.0.0.9".parse().unwrap(),
+            end: "10.0.0.1".parse().unwrap(),
+        };
+        assert!(sas.ip(reversed).freeze().is_err());
+    }
+
+    #[test]
+    fn test_blob_sas_permissions_round_trip() {
+        let permissions: BlobSasPermissions = "racwdxyltmeop".parse().unwrap();
+        assert_eq!(permissions.to_string(), "racwdxyltmeop");
+        assert_eq!(
+            "lr".parse::<BlobSasPermissions>().unwrap().to_string(),
+            "rl"
+        );
+        assert!("rq".parse::<BlobSasPermissions>().is_err());
+        assert_eq!(
+            serde_json::from_str::<BlobSignedResource>("\"bv\"").unwrap(),
+            BlobSignedResource::BlobVersion
+        );
+    }
 }
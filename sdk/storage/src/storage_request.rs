@@ -0,0 +1,137 @@
+use crate::clients::finalize_request;
+use azure_core::{
+    headers::{AsHeaders, HeaderName, HeaderValue, Headers},
+    AppendToUrlQuery, Body, Context, Method, Request, Response,
+};
+use url::Url;
+
+/// A request to a storage resource being put together.
+///
+/// Query parameters, headers and the body are added in any order, and
+/// optional values (`Option<LeaseId>`, `Option<IfMatchCondition>`, ...) are
+/// skipped when unset. [`StorageRequest::into_request`] adds the headers every
+/// storage request carries.
+///
+/// ```
+/// use azure_core::{prelude::LeaseId, Method};
+/// use azure_storage::StorageRequest;
+///
+/// let lease_id: Option<LeaseId> = None;
+/// let request = StorageRequest::new(
+///     "https://account.blob.core.windows.net/container".parse().unwrap(),
+///     Method::Put,
+/// )
+/// .restype("container")
+/// .comp("metadata")
+/// .header(lease_id)
+/// .into_request()
+/// .unwrap();
+/// assert_eq!(
+///     request.url().as_str(),
+///     "https://account.blob.core.windows.net/container?restype=container&comp=metadata"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct StorageRequest {
+    url: Url,
+    method: Method,
+    headers: Headers,
+    body: Option<Body>,
+}
+
+impl StorageRequest {
+    pub fn new(url: Url, method: Method) -> Self {
+        Self {
+            url,
+            method,
+            headers: Headers::new(),
+            body: None,
+        }
+    }
+
+    /// Selects the kind of resource addressed, such as `container`.
+    #[must_use]
+    pub fn restype(self, restype: &str) -> Self {
+        self.query_pair("restype", restype)
+    }
+
+    /// Selects the operation on the resource, such as `lease` or `tags`.
+    #[must_use]
+    pub fn comp(self, comp: &str) -> Self {
+        self.query_pair("comp", comp)
+    }
+
+    #[must_use]
+    pub fn query_pair(mut self, key: &str, value: &str) -> Self {
+        self.url.query_pairs_mut().append_pair(key, value);
+        self
+    }
+
+    #[must_use]
+    pub fn query<Q: AppendToUrlQuery>(mut self, query: Q) -> Self {
+        query.append_to_url_query(&mut self.url);
+        self
+    }
+
+    #[must_use]
+    pub fn header<H: AsHeaders>(mut self, header: H) -> Self {
+        self.headers.add(header);
+        self
+    }
+
+    #[must_use]
+    pub fn header_value<K, V>(mut self, name: K, value: V) -> Self
+    where
+        K: Into<HeaderName>,
+        V: Into<HeaderValue>,
+    {
+        self.headers.insert(name, value);
+        self
+    }
+
+    #[must_use]
+    pub fn body(mut self, body: impl Into<Body>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// The request, with the date, service version and content length added.
+    pub fn into_request(self) -> azure_core::Result<Request> {
+        finalize_request(self.url, self.method, self.headers, self.body)
+    }
+}
+
+/// A client of a storage resource, which starts requests at the resource's
+/// URL and sends them through its pipeline.
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+pub trait StorageClient: Sync {
+    /// The URL of the resource.
+    fn url(&self) -> azure_core::Result<Url>;
+
+    /// Sends a finished request through the client's pipeline.
+    async fn send(
+        &self,
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response>;
+
+    /// Starts a request to the resource.
+    fn request(&self, method: Method) -> azure_core::Result<StorageRequest> {
+        Ok(StorageRequest::new(StorageClient::url(self)?, method))
+    }
+
+    /// Finishes `request` and sends it.
+    async fn execute(
+        &self,
+        context: &mut Context,
+        request: StorageRequest,
+    ) -> azure_core::Result<Response> {
+        let mut request = request.into_request()?;
+        StorageClient::send(self, context, &mut request).await
+    }
+}
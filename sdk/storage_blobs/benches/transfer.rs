@@ -0,0 +1,142 @@
+//! Benchmarks of request signing, XML parsing and the upload and download
+//! paths, run against in-memory transports so they measure the client rather
+//! than the network.
+//!
+//! Run with `cargo bench -p azure_storage_blobs --features mock-transport`.
+use azure_core::{headers::Headers, HttpClient, Request, Response, StatusCode, TransportOptions};
+use azure_storage::{hmac::SigningKey, StorageCredentials, EMULATOR_ACCOUNT_KEY};
+use azure_storage_blobs::{dev::InMemoryTransport, prelude::*};
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::StreamExt;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const SIZES: &[usize] = &[64 * 1024, 4 * 1024 * 1024, 32 * 1024 * 1024];
+
+fn signing(c: &mut Criterion) {
+    let key = SigningKey::new(EMULATOR_ACCOUNT_KEY).unwrap();
+    let string_to_sign = "PUT\n\n\n11\n\napplication/octet-stream\n\n\n\n\n\n\n\
+        x-ms-blob-type:BlockBlob\nx-ms-date:Thu, 01 Jul 2021 10:45:00 GMT\n\
+        x-ms-version:2019-12-12\n/account/container/blob";
+    c.bench_function("sign shared key", |b| b.iter(|| key.sign(string_to_sign)));
+}
+
+/// Answers every request with the same List Blobs page.
+#[derive(Debug)]
+struct ListBlobsPage(Bytes);
+
+impl ListBlobsPage {
+    fn new(blobs: usize) -> Self {
+        let blobs: String = (0..blobs)
+            .map(|index| {
+                format!(
+                    "<Blob><Name>directory/blob{index}</Name><Properties>\
+                     <Creation-Time>Thu, 01 Jul 2021 10:44:59 GMT</Creation-Time>\
+                     <Last-Modified>Thu, 01 Jul 2021 10:44:59 GMT</Last-Modified>\
+                     <Etag>0x8D93C7D4629C227</Etag><Content-Length>1024</Content-Length>\
+                     <Content-Type>application/octet-stream</Content-Type>\
+                     <Content-MD5>1B2M2Y8AsgTpgAmY7PhCfg==</Content-MD5>\
+                     <BlobType>BlockBlob</BlobType><AccessTier>Hot</AccessTier>\
+                     <LeaseStatus>unlocked</LeaseStatus><LeaseState>available</LeaseState>\
+                     <ServerEncrypted>true</ServerEncrypted></Properties></Blob>"
+                )
+            })
+            .collect();
+        Self(Bytes::from(format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+             <EnumerationResults><Blobs>{blobs}</Blobs><NextMarker /></EnumerationResults>"
+        )))
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpClient for ListBlobsPage {
+    async fn execute_request(&self, _request: &Request) -> azure_core::Result<Response> {
+        let mut headers = Headers::new();
+        headers.insert("date", "Thu, 01 Jul 2021 10:45:00 GMT");
+        headers.insert("x-ms-request-id", "831e1650-001e-001b-66ab-eeb76e000000");
+        let body = self.0.clone();
+        Ok(Response::new(
+            StatusCode::Ok,
+            headers,
+            Box::pin(futures::stream::once(async move { Ok(body) })),
+        ))
+    }
+}
+
+fn list_blobs(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("list blobs");
+    for blobs in [100, 5000] {
+        let container_client = ClientBuilder::new("account", StorageCredentials::anonymous())
+            .transport(TransportOptions::new(Arc::new(ListBlobsPage::new(blobs))))
+            .container_client("container");
+        group.throughput(Throughput::Elements(blobs as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(blobs), &blobs, |b, _| {
+            b.to_async(&runtime).iter(|| async {
+                let mut pages = container_client.list_blobs().into_stream();
+                pages.next().await.unwrap().unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn blob_client(transport: &InMemoryTransport) -> BlobClient {
+    ClientBuilder::new("account", StorageCredentials::anonymous())
+        .transport(transport.clone())
+        .blob_client("container", "blob")
+}
+
+fn upload(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let transport = InMemoryTransport::new();
+    let blob_client = blob_client(&transport);
+
+    let mut group = c.benchmark_group("upload");
+    group.sample_size(20);
+    for &size in SIZES {
+        let content = Bytes::from(vec![7u8; size]);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("put_block_blob", size), &size, |b, _| {
+            b.to_async(&runtime)
+                .iter(|| blob_client.put_block_blob(content.clone()).into_future())
+        });
+        group.bench_with_input(BenchmarkId::new("upload_stream", size), &size, |b, _| {
+            b.to_async(&runtime).iter(|| {
+                let chunks: Vec<_> = content
+                    .chunks(64 * 1024)
+                    .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+                    .collect();
+                blob_client
+                    .upload_stream(futures::stream::iter(chunks))
+                    .block_size(1024 * 1024usize)
+                    .into_future()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn download(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let transport = InMemoryTransport::new();
+    let blob_client = blob_client(&transport);
+
+    let mut group = c.benchmark_group("download");
+    group.sample_size(20);
+    for &size in SIZES {
+        runtime
+            .block_on(blob_client.put_block_blob(vec![7u8; size]).into_future())
+            .unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("get_content", size), &size, |b, _| {
+            b.to_async(&runtime).iter(|| blob_client.get_content())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, signing, list_blobs, upload, download);
+criterion_main!(benches);
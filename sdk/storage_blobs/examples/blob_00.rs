@@ -1,18 +1,11 @@
 #[macro_use]
 extern crate log;
 use azure_core::error::{ErrorKind, ResultExt};
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use futures::StreamExt;
 
 #[tokio::main]
 async fn main() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container = std::env::args()
         .nth(1)
         .expect("please specify container name as command line parameter");
@@ -20,8 +13,7 @@ async fn main() -> azure_core::Result<()> {
         .nth(2)
         .expect("please specify blob name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let service_client = BlobServiceClient::new(account, storage_credentials);
+    let service_client = ClientBuilder::from_env()?.blob_service_client();
 
     // this is how you would use the SAS token:
     // let storage_client = StorageAccountClient::new_sas_token(http_client.clone(), &account,
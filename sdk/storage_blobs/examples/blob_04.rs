@@ -1,5 +1,4 @@
 use azure_core::error::{ErrorKind, ResultExt};
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use bytes::{BufMut, Bytes};
 
@@ -7,18 +6,12 @@ use bytes::{BufMut, Bytes};
 async fn main() -> azure_core::Result<()> {
     env_logger::init();
 
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = std::env::args()
         .nth(1)
         .expect("please specify container name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_client = BlobServiceClient::new(account, storage_credentials)
+    let blob_client = ClientBuilder::from_env()?
+        .blob_service_client()
         .container_client(container_name)
         .blob_client("test1");
 
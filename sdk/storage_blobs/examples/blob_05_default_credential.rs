@@ -28,7 +28,7 @@ async fn main() -> azure_core::Result<()> {
         .get_token("https://storage.azure.com/")
         .await?;
 
-    let storage_credentials = StorageCredentials::BearerToken(bearer_token.token.secret().into());
+    let storage_credentials = StorageCredentials::bearer_token(bearer_token.token.secret());
     let blob_client = BlobServiceClient::new(account, storage_credentials)
         .container_client(&container)
         .blob_client(&blob);
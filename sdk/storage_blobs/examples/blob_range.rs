@@ -1,22 +1,15 @@
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use futures::stream::StreamExt;
 use uuid::Uuid;
 
 #[tokio::main]
 async fn main() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = format!("range-example-{}", Uuid::new_v4());
     let blob_name = format!("blob-{}.txt", Uuid::new_v4());
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let container_client =
-        BlobServiceClient::new(account, storage_credentials).container_client(container_name);
+    let container_client = ClientBuilder::from_env()?
+        .blob_service_client()
+        .container_client(container_name);
     container_client.create().await?;
 
     let blob_client = container_client.blob_client(&blob_name);
@@ -1,22 +1,14 @@
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use futures::StreamExt;
 use std::num::NonZeroU32;
 
 #[tokio::main]
 async fn main() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = std::env::args()
         .nth(1)
         .expect("please specify container name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let service_client = BlobServiceClient::new(account, storage_credentials);
+    let service_client = ClientBuilder::from_env()?.blob_service_client();
     let container_client = service_client.container_client(container_name);
 
     let max_results = NonZeroU32::new(3).unwrap();
@@ -1,24 +1,15 @@
 use azure_core::{date, prelude::*};
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use std::time::Duration;
 use time::OffsetDateTime;
 
 #[tokio::main]
 async fn main() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = std::env::args()
         .nth(1)
         .expect("please specify container name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let container_client =
-        ClientBuilder::new(account, storage_credentials).container_client(container_name);
+    let container_client = ClientBuilder::from_env()?.container_client(container_name);
 
     let mut metadata = Metadata::new();
     metadata.insert("prova".to_owned(), "pollo".to_owned());
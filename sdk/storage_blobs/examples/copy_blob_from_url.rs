@@ -1,14 +1,7 @@
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 
 #[tokio::main]
 async fn main() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let source_container = std::env::args()
         .nth(1)
         .expect("please specify source container name as first command line parameter");
@@ -22,8 +15,7 @@ async fn main() -> azure_core::Result<()> {
         .nth(4)
         .expect("please specify destination blob name as fourth command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_service = BlobServiceClient::new(account, storage_credentials);
+    let blob_service = ClientBuilder::from_env()?.blob_service_client();
     let blob_client = blob_service
         .container_client(&destination_container)
         .blob_client(&destination_blob);
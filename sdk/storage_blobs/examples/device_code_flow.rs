@@ -67,7 +67,7 @@ async fn main() -> azure_core::Result<()> {
     // using the access token.
 
     let storage_credentials =
-        StorageCredentials::BearerToken(authorization.access_token().secret().to_owned());
+        StorageCredentials::bearer_token(authorization.access_token().secret().to_owned());
     let blob_service_client = BlobServiceClient::new(storage_account_name, storage_credentials);
 
     // now we enumerate the containers in the
@@ -1,5 +1,4 @@
 use azure_core::{prelude::Timeout, Context};
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use futures::StreamExt;
 use std::num::NonZeroU32;
@@ -7,18 +6,11 @@ use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = std::env::args()
         .nth(1)
         .expect("please specify container name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_service = BlobServiceClient::new(account, storage_credentials);
+    let blob_service = ClientBuilder::from_env()?.blob_service_client();
     let container_client = blob_service.container_client(&container_name);
 
     let page = blob_service
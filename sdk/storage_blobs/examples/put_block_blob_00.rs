@@ -1,7 +1,6 @@
 #[macro_use]
 extern crate log;
 
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use bytes::Bytes;
 use std::time::Duration;
@@ -10,11 +9,6 @@ use std::time::Duration;
 async fn main() -> azure_core::Result<()> {
     env_logger::init();
     debug!("log initialized");
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
 
     let container = std::env::args()
         .nth(1)
@@ -23,8 +17,8 @@ async fn main() -> azure_core::Result<()> {
         .nth(2)
         .expect("please specify blob name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_client = BlobServiceClient::new(account, storage_credentials)
+    let blob_client = ClientBuilder::from_env()?
+        .blob_service_client()
         .container_client(&container)
         .blob_client(&blob_name);
 
@@ -3,7 +3,6 @@
 #[macro_use]
 extern crate log;
 
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use futures::future::try_join_all;
 
@@ -11,11 +10,6 @@ use futures::future::try_join_all;
 async fn main() -> azure_core::Result<()> {
     env_logger::init();
     debug!("log initialized");
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
 
     let container = std::env::args()
         .nth(1)
@@ -24,21 +18,19 @@ async fn main() -> azure_core::Result<()> {
         .nth(2)
         .expect("please specify blob name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_client = BlobServiceClient::new(account, storage_credentials)
+    let blob_client = ClientBuilder::from_env()?
+        .blob_service_client()
         .container_client(&container)
         .blob_client(&blob_name);
 
-    let mut block_list = BlockList::default();
+    let mut block_list = BlockList::new();
     let mut upload_block_futures = vec![];
-    for i in 1..=3 {
-        let block_id = format!("block-{i}");
-        let data = block_id.as_bytes().to_vec();
+    let block_ids = BlockIdSequence::new();
+    for (i, block_id) in (1..=3).zip(block_ids) {
+        let data = format!("block-{i}").into_bytes();
         let task = blob_client.put_block(block_id.clone(), data).into_future();
         upload_block_futures.push(task);
-        block_list
-            .blocks
-            .push(BlobBlockType::new_uncommitted(block_id));
+        block_list.push_uncommitted(block_id);
     }
 
     // Allow all blocks to upload.
@@ -1,7 +1,6 @@
 #[macro_use]
 extern crate log;
 use azure_core::prelude::*;
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use bytes::Bytes;
 
@@ -10,12 +9,6 @@ async fn main() -> azure_core::Result<()> {
     env_logger::init();
     trace!("example started");
 
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = std::env::args()
         .nth(1)
         .expect("please specify container_name name as command line parameter");
@@ -23,8 +16,8 @@ async fn main() -> azure_core::Result<()> {
         .nth(2)
         .expect("please specify blob name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_client = BlobServiceClient::new(account, storage_credentials)
+    let blob_client = ClientBuilder::from_env()?
+        .blob_service_client()
         .container_client(container_name)
         .blob_client(&blob_name);
 
@@ -45,7 +38,7 @@ async fn main() -> azure_core::Result<()> {
     // parameters (such as LeaseID, or ContentDisposition, etc...)
     // so make sure to check with the documentation.
     let res = blob_client
-        .put_page_blob(1024 * 3)
+        .create_page_blob(1024 * 3)
         .content_type("text/plain")
         .metadata(metadata)
         .sequence_number(100)
@@ -1,16 +1,9 @@
 #[macro_use]
 extern crate log;
-use azure_storage::prelude::*;
-use azure_storage_blobs::prelude::*;
+use azure_storage_blobs::{blob::BlobHttpHeaders, prelude::*};
 
 #[tokio::main]
 async fn main() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container = std::env::args()
         .nth(1)
         .expect("please specify container name as command line parameter");
@@ -22,8 +15,8 @@ async fn main() -> azure_core::Result<()> {
     // let storage_client = StorageAccountClient::new_sas_token(http_client.clone(), &account,
     //      "sv=2018-11-09&ss=b&srt=o&se=2021-01-15T12%3A09%3A01Z&sp=r&st=2021-01-15T11%3A09%3A01Z&spr=http,https&sig=some_signature")?;
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_client = BlobServiceClient::new(account, storage_credentials)
+    let blob_client = ClientBuilder::from_env()?
+        .blob_service_client()
         .container_client(&container)
         .blob_client(blob);
 
@@ -31,10 +24,11 @@ async fn main() -> azure_core::Result<()> {
 
     let properties = blob_client.get_properties().await?.blob.properties;
 
+    // the headers which are not sent are cleared, so start from the current ones
+    let http_headers = BlobHttpHeaders::from(&properties).content_md5(md5::compute("howdy"));
     blob_client
         .set_properties()
-        .set_from_blob_properties(properties)
-        .content_md5(md5::compute("howdy"))
+        .http_headers(http_headers)
         .await?;
 
     Ok(())
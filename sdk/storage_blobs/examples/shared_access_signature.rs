@@ -9,12 +9,6 @@ fn main() {
 }
 
 fn code() -> azure_core::Result<()> {
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = std::env::args()
         .nth(1)
         .expect("please specify container name as command line parameter");
@@ -26,8 +20,7 @@ fn code() -> azure_core::Result<()> {
     let now = OffsetDateTime::now_utc() - date::duration_from_minutes(15);
     let later = now + date::duration_from_hours(1);
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let service_client = BlobServiceClient::new(account, storage_credentials);
+    let service_client = ClientBuilder::from_env()?.blob_service_client();
     let container_client = service_client.container_client(container_name);
     let blob_client = container_client.blob_client(blob_name);
 
@@ -1,7 +1,6 @@
 #[macro_use]
 extern crate log;
 
-use azure_storage::prelude::*;
 use azure_storage_blobs::prelude::*;
 use bytes::Bytes;
 
@@ -9,11 +8,6 @@ use bytes::Bytes;
 async fn main() -> azure_core::Result<()> {
     env_logger::init();
     debug!("log initialized");
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
 
     let container_name = std::env::args()
         .nth(1)
@@ -22,9 +16,7 @@ async fn main() -> azure_core::Result<()> {
         .nth(2)
         .expect("please specify blob name as command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_client =
-        ClientBuilder::new(account, storage_credentials).blob_client(&container_name, &blob_name);
+    let blob_client = ClientBuilder::from_env()?.blob_client(&container_name, &blob_name);
 
     let data = Bytes::from_static(b"something");
 
@@ -1,4 +1,3 @@
-use azure_storage::StorageCredentials;
 use azure_storage_blobs::prelude::*;
 use futures::stream::StreamExt;
 
@@ -12,19 +11,11 @@ use futures::stream::StreamExt;
 async fn main() -> azure_core::Result<()> {
     let file_name = "azure_sdk_for_rust_stream_test.txt";
 
-    // First we retrieve the account name and access key from environment variables.
-    let account =
-        std::env::var("STORAGE_ACCOUNT").expect("Set env variable STORAGE_ACCOUNT first!");
-    let access_key =
-        std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
-
     let container_name = std::env::args()
         .nth(1)
         .expect("please specify container name as first command line parameter");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
-    let blob_client =
-        ClientBuilder::new(account, storage_credentials).blob_client(container_name, file_name);
+    let blob_client = ClientBuilder::from_env()?.blob_client(container_name, file_name);
 
     let mut stream = blob_client.get().into_stream();
     while let Some(res) = stream.next().await {
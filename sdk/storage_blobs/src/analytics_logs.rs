@@ -0,0 +1,368 @@
+//! Reading the Storage Analytics logs written to the `$logs` container.
+//!
+//! When logging is enabled in the service properties, each service writes the
+//! requests it served to `$logs/{service}/{yyyy}/{mm}/{dd}/{hhmm}/{counter}.log`,
+//! one request per line as semicolon separated fields. [`AnalyticsLogReader`]
+//! finds the log blobs of a time range and parses them into
+//! [`AnalyticsLogRecord`]s.
+//!
+//! ```no_run
+//! use azure_storage_blobs::{analytics_logs::AnalyticsLogReader, prelude::*};
+//! use futures::TryStreamExt;
+//! use time::{Duration, OffsetDateTime};
+//!
+//! # async fn example(service_client: BlobServiceClient) -> azure_core::Result<()> {
+//! let end = OffsetDateTime::now_utc();
+//! let reader = AnalyticsLogReader::new(&service_client);
+//! let mut records = Box::pin(reader.records("blob", end - Duration::hours(1), end));
+//! while let Some(record) = records.try_next().await? {
+//!     println!("{} {} {}", record.request_start_time, record.operation_type, record.http_status_code);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ref: <https://docs.microsoft.com/rest/api/storageservices/storage-analytics-log-format>
+use crate::prelude::*;
+use azure_core::{
+    date,
+    error::{Error, ErrorKind, ResultExt},
+};
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
+use std::{str::FromStr, time::Duration};
+use time::OffsetDateTime;
+
+pub const LOGS_CONTAINER: &str = "$logs";
+
+/// A request logged by Storage Analytics.
+///
+/// Fields logged empty are `None`. The fields after `client_request_id` are
+/// only logged from version 2.0 of the format on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyticsLogRecord {
+    pub version: String,
+    pub request_start_time: OffsetDateTime,
+    /// The operation, such as `GetBlob` or `PutBlob`.
+    pub operation_type: String,
+    /// The outcome, such as `Success` or `AnonymousThrottlingError`.
+    pub request_status: String,
+    pub http_status_code: u16,
+    pub end_to_end_latency: Duration,
+    pub server_latency: Duration,
+    /// `anonymous`, `sas`, `authenticated` or `OAuth`.
+    pub authentication_type: String,
+    pub requester_account_name: Option<String>,
+    pub owner_account_name: Option<String>,
+    pub service_type: Option<String>,
+    pub request_url: Option<String>,
+    pub requested_object_key: Option<String>,
+    pub request_id: Option<String>,
+    pub operation_count: u32,
+    /// The address, with the port, of the requester.
+    pub requester_ip_address: Option<String>,
+    pub request_version: Option<String>,
+    pub request_header_size: Option<u64>,
+    pub request_packet_size: Option<u64>,
+    pub response_header_size: Option<u64>,
+    pub response_packet_size: Option<u64>,
+    pub request_content_length: Option<u64>,
+    pub request_md5: Option<String>,
+    pub server_md5: Option<String>,
+    pub etag: Option<String>,
+    /// Logged in the obsolete RFC 850 format, e.g. `Friday, 09-Aug-11
+    /// 18:52:40 GMT`.
+    pub last_modified_time: Option<OffsetDateTime>,
+    pub conditions_used: Option<String>,
+    pub user_agent: Option<String>,
+    pub referrer: Option<String>,
+    pub client_request_id: Option<String>,
+    pub user_object_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub application_id: Option<String>,
+    pub audience: Option<String>,
+    pub issuer: Option<String>,
+    pub user_principal_name: Option<String>,
+    pub authorization_detail: Option<String>,
+}
+
+impl FromStr for AnalyticsLogRecord {
+    type Err = Error;
+
+    fn from_str(line: &str) -> azure_core::Result<Self> {
+        let fields = split_fields(line)?;
+        if fields.len() < 30 {
+            return Err(Error::with_message(ErrorKind::DataConversion, || {
+                format!(
+                    "a log record needs at least 30 fields, found {}",
+                    fields.len()
+                )
+            }));
+        }
+        let field = |index: usize| fields.get(index).cloned().flatten();
+        let required = |index: usize, name: &str| {
+            field(index).ok_or_else(|| {
+                Error::with_message(ErrorKind::DataConversion, || {
+                    format!("log record without {name}")
+                })
+            })
+        };
+        let latency = |index: usize, name: &str| -> azure_core::Result<Duration> {
+            Ok(Duration::from_millis(parse_number(
+                &required(index, name)?,
+                name,
+            )?))
+        };
+        let size = |index: usize, name: &str| -> azure_core::Result<Option<u64>> {
+            field(index)
+                .map(|value| parse_number(&value, name))
+                .transpose()
+        };
+
+        Ok(Self {
+            version: required(0, "version")?,
+            request_start_time: date::parse_rfc3339(&required(1, "request start time")?)
+                .context(ErrorKind::DataConversion, "invalid request start time")?,
+            operation_type: required(2, "operation type")?,
+            request_status: required(3, "request status")?,
+            http_status_code: parse_number(&required(4, "status code")?, "status code")?,
+            end_to_end_latency: latency(5, "end to end latency")?,
+            server_latency: latency(6, "server latency")?,
+            authentication_type: required(7, "authentication type")?,
+            requester_account_name: field(8),
+            owner_account_name: field(9),
+            service_type: field(10),
+            request_url: field(11),
+            requested_object_key: field(12),
+            request_id: field(13),
+            operation_count: field(14)
+                .map(|value| parse_number(&value, "operation count"))
+                .transpose()?
+                .unwrap_or_default(),
+            requester_ip_address: field(15),
+            request_version: field(16),
+            request_header_size: size(17, "request header size")?,
+            request_packet_size: size(18, "request packet size")?,
+            response_header_size: size(19, "response header size")?,
+            response_packet_size: size(20, "response packet size")?,
+            request_content_length: size(21, "request content length")?,
+            request_md5: field(22),
+            server_md5: field(23),
+            etag: field(24),
+            last_modified_time: field(25)
+                .map(|value| date::parse_rfc7231(&value))
+                .transpose()
+                .context(ErrorKind::DataConversion, "invalid last modified time")?,
+            conditions_used: field(26),
+            user_agent: field(27),
+            referrer: field(28),
+            client_request_id: field(29),
+            user_object_id: field(30),
+            tenant_id: field(31),
+            application_id: field(32),
+            audience: field(33),
+            issuer: field(34),
+            user_principal_name: field(35),
+            authorization_detail: field(37),
+        })
+    }
+}
+
+fn parse_number<T: FromStr>(value: &str, name: &str) -> azure_core::Result<T> {
+    value.parse().map_err(|_| {
+        Error::with_message(ErrorKind::DataConversion, || {
+            format!("invalid {name}: {value}")
+        })
+    })
+}
+
+/// Splits a log line at the semicolons outside of quotes. Quoted fields are
+/// unquoted, with `""` standing for a quote, and empty fields are `None`.
+fn split_fields(line: &str) -> azure_core::Result<Vec<Option<String>>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let mut field = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => {
+                        return Err(Error::message(
+                            ErrorKind::DataConversion,
+                            "unterminated quoted field in log record",
+                        ))
+                    }
+                }
+            }
+        }
+        loop {
+            match chars.next() {
+                Some(';') => break,
+                Some(c) => field.push(c),
+                None => {
+                    fields.push((!field.is_empty()).then_some(field));
+                    return Ok(fields);
+                }
+            }
+        }
+        fields.push((!field.is_empty()).then_some(field));
+    }
+}
+
+/// Parses the records of a log blob, one per line.
+pub fn parse_log(data: &[u8]) -> azure_core::Result<Vec<AnalyticsLogRecord>> {
+    let text = std::str::from_utf8(data).context(ErrorKind::DataConversion, "log is not UTF-8")?;
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Finds and reads the log blobs in the `$logs` container of an account.
+#[derive(Debug, Clone)]
+pub struct AnalyticsLogReader {
+    container_client: ContainerClient,
+}
+
+impl AnalyticsLogReader {
+    pub fn new(service_client: &BlobServiceClient) -> Self {
+        Self {
+            container_client: service_client.container_client(LOGS_CONTAINER),
+        }
+    }
+
+    /// The names of the log blobs of `service` (`blob`, `queue` or `table`)
+    /// covering the hours from `start` to `end`.
+    pub fn log_blobs(
+        &self,
+        service: &str,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> impl Stream<Item = azure_core::Result<String>> + 'static {
+        let container_client = self.container_client.clone();
+        stream::iter(hour_prefixes(service, start, end))
+            .map(move |prefix| container_client.list_blobs().prefix(prefix).into_stream())
+            .flatten()
+            .map_ok(|page| {
+                let names: Vec<_> = page
+                    .blobs
+                    .blobs()
+                    .map(|blob| Ok(blob.name.clone()))
+                    .collect();
+                stream::iter(names)
+            })
+            .try_flatten()
+    }
+
+    /// The requests `service` started from `start` until `end`, log blob by
+    /// log blob. Errors end the stream.
+    pub fn records(
+        &self,
+        service: &str,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> impl Stream<Item = azure_core::Result<AnalyticsLogRecord>> + 'static {
+        let container_client = self.container_client.clone();
+        self.log_blobs(service, start, end)
+            .and_then(move |name| {
+                let blob_client = container_client.blob_client(name);
+                async move { parse_log(&blob_client.get_content().await?) }
+            })
+            .map_ok(|records| stream::iter(records.into_iter().map(Ok)))
+            .try_flatten()
+            .try_filter(move |record| {
+                future::ready(record.request_start_time >= start && record.request_start_time < end)
+            })
+    }
+}
+
+/// The `{service}/{yyyy}/{mm}/{dd}/{hh}` prefixes of the hours from `start` to
+/// `end`.
+fn hour_prefixes(service: &str, start: OffsetDateTime, end: OffsetDateTime) -> Vec<String> {
+    let start = start.to_offset(time::UtcOffset::UTC);
+    let end = end.to_offset(time::UtcOffset::UTC);
+    let mut hour = start
+        .replace_time(time::Time::from_hms(start.hour(), 0, 0).unwrap_or(time::Time::MIDNIGHT));
+    let mut prefixes = Vec::new();
+    while hour < end {
+        prefixes.push(format!(
+            "{service}/{:04}/{:02}/{:02}/{:02}",
+            hour.year(),
+            u8::from(hour.month()),
+            hour.day(),
+            hour.hour()
+        ));
+        hour += time::Duration::HOUR;
+    }
+    prefixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() -> azure_core::Result<()> {
+        let records = parse_log(
+            br#"1.0;2011-08-09T18:52:40.9241789Z;GetBlob;AnonymousSuccess;200;18;10;anonymous;;myaccount;blob;"https://myaccount.blob.core.windows.net/thumbnails/lake.jpg?timeout=30000";"/myaccount/thumbnails/lake.jpg";a84aa705-8a85-48c5-b064-b43bd22979c3;0;123.100.2.10;2009-09-19;252;0;265;100;0;;;"0x8CE1B6EA95033D5";Friday, 09-Aug-11 18:52:40 GMT;;;;"8/9/2011 6:52:40 PM ba98eb12-700b-4d53-9230-33a3330571fc"
+2.0;2011-08-09T18:52:41.0000000Z;PutBlob;Success;201;30;25;OAuth;myaccount;myaccount;blob;"https://myaccount.blob.core.windows.net/c/a;b.txt";"/myaccount/c/a;b.txt";b84aa705-8a85-48c5-b064-b43bd22979c3;0;10.0.0.1:5000;2019-12-12;300;11;200;0;11;;;;;;"agent ""quoted""";;;a1b2;t1;app;https://storage.azure.com;issuer;user@contoso.com;;{"action":"write"}
+"#,
+        )?;
+        assert_eq!(records.len(), 2);
+
+        let get = &records[0];
+        assert_eq!(get.operation_type, "GetBlob");
+        assert_eq!(get.http_status_code, 200);
+        assert_eq!(get.end_to_end_latency, Duration::from_millis(18));
+        assert_eq!(get.requester_account_name, None);
+        assert_eq!(
+            get.requested_object_key.as_deref(),
+            Some("/myaccount/thumbnails/lake.jpg")
+        );
+        assert_eq!(get.etag.as_deref(), Some("0x8CE1B6EA95033D5"));
+        assert_eq!(
+            get.last_modified_time,
+            Some(date::parse_rfc3339("2011-08-09T18:52:40Z")?)
+        );
+        assert_eq!(get.request_md5, None);
+        assert_eq!(get.tenant_id, None);
+
+        let put = &records[1];
+        assert_eq!(
+            put.request_url.as_deref(),
+            Some("https://myaccount.blob.core.windows.net/c/a;b.txt")
+        );
+        assert_eq!(put.request_content_length, Some(11));
+        assert_eq!(put.user_agent.as_deref(), Some(r#"agent "quoted""#));
+        assert_eq!(put.user_principal_name.as_deref(), Some("user@contoso.com"));
+        assert_eq!(
+            put.authorization_detail.as_deref(),
+            Some(r#"{"action":"write"}"#)
+        );
+
+        assert!("1.0;2011-08-09T18:52:40Z;GetBlob"
+            .parse::<AnalyticsLogRecord>()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hour_prefixes() {
+        let start = date::parse_rfc3339("2021-12-31T22:30:00Z").unwrap();
+        let end = date::parse_rfc3339("2022-01-01T00:10:00Z").unwrap();
+        assert_eq!(
+            hour_prefixes("blob", start, end),
+            vec![
+                "blob/2021/12/31/22",
+                "blob/2021/12/31/23",
+                "blob/2022/01/01/00"
+            ]
+        );
+    }
+}
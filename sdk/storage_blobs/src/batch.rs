@@ -0,0 +1,429 @@
+//! The `multipart/mixed` bodies of Blob Batch requests, which send up to 256
+//! sub-requests at once, and the parsing of their responses.
+//!
+//! Each sub-request is checked by the service as if it was sent on its own,
+//! so it carries its own `x-ms-date`, `x-ms-version` and, unless the batch is
+//! authorized with a SAS, `Authorization` headers. Sub-requests are correlated
+//! with their responses by their `Content-ID`, the order in which they were
+//! added.
+//!
+//! A batch is sent with
+//! [`BlobServiceClient::submit_batch`](crate::prelude::BlobServiceClient::submit_batch),
+//! which adds these headers to the sub-requests lacking them, with the
+//! credentials of the client.
+//!
+//! ref: <https://docs.microsoft.com/rest/api/storageservices/blob-batch>
+
+use crate::prelude::*;
+use azure_core::{
+    error::{Error, ErrorKind, ResultExt},
+    headers::{self, HeaderName, Headers},
+    Body, Method, Request, StatusCode,
+};
+use bytes::Bytes;
+
+/// The most sub-requests a batch can have.
+pub const MAX_BATCH_REQUESTS: usize = 256;
+
+/// The body of a batch, made of the sub-requests added to it.
+///
+/// ```
+/// use azure_core::{Method, Request, Url};
+/// use azure_storage_blobs::batch::BatchBody;
+///
+/// let mut batch = BatchBody::with_boundary("batch_example");
+/// let url = Url::parse("https://account.blob.core.windows.net/container/blob")?;
+/// let content_id = batch.add(&Request::new(url, Method::Delete))?;
+/// assert_eq!(content_id, 0);
+/// assert_eq!(batch.content_type(), "multipart/mixed; boundary=batch_example");
+/// # Ok::<(), azure_core::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchBody {
+    boundary: String,
+    requests: Vec<Request>,
+}
+
+impl BatchBody {
+    pub fn new() -> Self {
+        Self::with_boundary(format!("batch_{}", uuid::Uuid::new_v4()))
+    }
+
+    /// A batch delimiting its sub-requests with `boundary`, which must not
+    /// occur in them.
+    pub fn with_boundary(boundary: impl Into<String>) -> Self {
+        Self {
+            boundary: boundary.into(),
+            requests: Vec::new(),
+        }
+    }
+
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The number of sub-requests.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Appends a sub-request, with its method, path, query, headers and body,
+    /// and returns its `Content-ID`.
+    pub fn add(&mut self, request: &Request) -> azure_core::Result<u32> {
+        if self.requests.len() >= MAX_BATCH_REQUESTS {
+            return Err(Error::with_message(ErrorKind::Other, || {
+                format!("a batch cannot have more than {MAX_BATCH_REQUESTS} sub-requests")
+            }));
+        }
+        if let Body::SeekableStream(_) = request.body() {
+            return Err(Error::message(
+                ErrorKind::Other,
+                "the sub-requests of a batch cannot have a streamed body",
+            ));
+        }
+        self.requests.push(request.clone());
+        Ok(self.requests.len() as u32 - 1)
+    }
+
+    /// Appends the deletion of a blob, and returns its `Content-ID`.
+    pub fn add_delete(&mut self, blob_client: &BlobClient) -> azure_core::Result<u32> {
+        self.add(&Request::new(blob_client.url()?, Method::Delete))
+    }
+
+    /// Appends setting the tier of a blob, and returns its `Content-ID`.
+    pub fn add_set_tier(
+        &mut self,
+        blob_client: &BlobClient,
+        access_tier: AccessTier,
+    ) -> azure_core::Result<u32> {
+        let mut url = blob_client.url()?;
+        url.query_pairs_mut().append_pair("comp", "tier");
+        let mut request = Request::new(url, Method::Put);
+        request.add_mandatory_header(&access_tier);
+        self.add(&request)
+    }
+
+    /// The sub-requests, e.g. to authorize them.
+    pub(crate) fn requests_mut(&mut self) -> &mut [Request] {
+        &mut self.requests
+    }
+
+    /// The `Content-Type` of the batch request.
+    pub fn content_type(&self) -> String {
+        format!("multipart/mixed; boundary={}", self.boundary)
+    }
+
+    /// The body of the batch request. Sub-requests without a `Content-Length`
+    /// are sent with one.
+    pub fn to_bytes(&self) -> Bytes {
+        let mut body = Vec::new();
+        for (content_id, request) in self.requests.iter().enumerate() {
+            let data = match request.body() {
+                Body::Bytes(bytes) => bytes.clone(),
+                // streamed bodies are refused when added
+                Body::SeekableStream(_) => Bytes::new(),
+            };
+            body.extend_from_slice(
+                format!(
+                    "--{}\r\nContent-Type: application/http\r\nContent-Transfer-Encoding: binary\r\n\
+                     Content-ID: {content_id}\r\n\r\n{} {} HTTP/1.1\r\n",
+                    self.boundary,
+                    request.method(),
+                    request.path_and_query(),
+                )
+                .as_bytes(),
+            );
+            for (name, value) in request.headers().iter() {
+                body.extend_from_slice(
+                    format!("{}: {}\r\n", name.as_str(), value.as_str()).as_bytes(),
+                );
+            }
+            if request
+                .headers()
+                .get_optional_str(&headers::CONTENT_LENGTH)
+                .is_none()
+            {
+                body.extend_from_slice(
+                    format!("{}: {}\r\n", headers::CONTENT_LENGTH.as_str(), data.len()).as_bytes(),
+                );
+            }
+            body.extend_from_slice(b"\r\n");
+            if !data.is_empty() {
+                body.extend_from_slice(&data);
+                body.extend_from_slice(b"\r\n");
+            }
+        }
+        body.extend_from_slice(b"--");
+        body.extend_from_slice(self.boundary.as_bytes());
+        body.extend_from_slice(b"--\r\n");
+        body.into()
+    }
+}
+
+impl Default for BatchBody {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The response to a sub-request of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchSubResponse {
+    /// The `Content-ID` of the sub-request. The service omits it when it
+    /// rejects a sub-request before reading its parts.
+    pub content_id: Option<u32>,
+    pub status: StatusCode,
+    pub headers: Headers,
+    pub body: Bytes,
+}
+
+impl BatchSubResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    /// The `x-ms-error-code` of a failed sub-request, e.g. `BlobNotFound`.
+    pub fn error_code(&self) -> Option<&str> {
+        self.headers.get_optional_str(&headers::ERROR_CODE)
+    }
+}
+
+/// Parses the body of a batch response, given its `Content-Type`, into the
+/// responses to the sub-requests in the order the service returned them.
+pub fn parse_batch_response(
+    content_type: &str,
+    body: &[u8],
+) -> azure_core::Result<Vec<BatchSubResponse>> {
+    let boundary = content_type
+        .split(';')
+        .filter_map(|parameter| parameter.trim().strip_prefix("boundary="))
+        .next()
+        .map(|boundary| boundary.trim_matches('"'))
+        .ok_or_else(|| {
+            Error::with_message(ErrorKind::DataConversion, || {
+                format!("the batch response has no boundary in its content type {content_type}")
+            })
+        })?;
+    let delimiter = format!("--{boundary}");
+
+    let mut responses = Vec::new();
+    // the preamble before the first delimiter is ignored
+    for part in split(body, delimiter.as_bytes()).skip(1) {
+        if part.starts_with(b"--") {
+            return Ok(responses);
+        }
+        responses.push(
+            parse_part(part).with_context(ErrorKind::DataConversion, || {
+                format!(
+                    "failed to parse part {} of the batch response",
+                    responses.len()
+                )
+            })?,
+        );
+    }
+    Err(Error::message(
+        ErrorKind::DataConversion,
+        "the batch response is truncated",
+    ))
+}
+
+/// Parses a part: its MIME headers, and the status line, headers and body of
+/// the HTTP response it holds.
+fn parse_part(part: &[u8]) -> azure_core::Result<BatchSubResponse> {
+    let part = part
+        .strip_prefix(b"\r\n")
+        .or_else(|| part.strip_prefix(b"\n"))
+        .unwrap_or(part);
+    let part = part
+        .strip_suffix(b"\r\n")
+        .or_else(|| part.strip_suffix(b"\n"))
+        .unwrap_or(part);
+
+    let (mime_headers, rest) = parse_headers(part)?;
+    let content_id = mime_headers
+        .get_optional_str(&HeaderName::from_static("content-id"))
+        .map(|content_id| content_id.parse())
+        .transpose()
+        .context(ErrorKind::DataConversion, "invalid Content-ID")?;
+
+    let (status_line, rest) = split_line(rest);
+    let status_line = std::str::from_utf8(status_line)?;
+    let status = status_line
+        .strip_prefix("HTTP/1.1 ")
+        .and_then(|status| status.get(..3))
+        .and_then(|status| status.parse::<u16>().ok())
+        .and_then(|status| StatusCode::try_from(status).ok())
+        .ok_or_else(|| {
+            Error::with_message(ErrorKind::DataConversion, || {
+                format!("invalid status line {status_line}")
+            })
+        })?;
+    let (headers, body) = parse_headers(rest)?;
+
+    Ok(BatchSubResponse {
+        content_id,
+        status,
+        headers,
+        body: Bytes::copy_from_slice(body),
+    })
+}
+
+/// Parses header lines up to an empty line, and returns the headers and what
+/// follows the empty line.
+fn parse_headers(mut data: &[u8]) -> azure_core::Result<(Headers, &[u8])> {
+    let mut headers = Headers::new();
+    loop {
+        let (line, rest) = split_line(data);
+        data = rest;
+        if line.is_empty() {
+            return Ok((headers, data));
+        }
+        let line = std::str::from_utf8(line)?;
+        let (name, value) = line.split_once(':').ok_or_else(|| {
+            Error::with_message(ErrorKind::DataConversion, || {
+                format!("invalid header line {line}")
+            })
+        })?;
+        headers.insert(name.trim().to_owned(), value.trim().to_owned());
+    }
+}
+
+/// Splits the first line, ended by CRLF or LF, from the rest of `data`.
+fn split_line(data: &[u8]) -> (&[u8], &[u8]) {
+    match data.iter().position(|b| *b == b'\n') {
+        Some(end) => {
+            let line = &data[..end];
+            (line.strip_suffix(b"\r").unwrap_or(line), &data[end + 1..])
+        }
+        None => (data, &[]),
+    }
+}
+
+/// Splits `data` on each occurrence of `delimiter`.
+fn split<'a>(data: &'a [u8], delimiter: &'a [u8]) -> impl Iterator<Item = &'a [u8]> {
+    let mut rest = Some(data);
+    std::iter::from_fn(move || {
+        let data = rest?;
+        match data
+            .windows(delimiter.len())
+            .position(|window| window == delimiter)
+        {
+            Some(start) => {
+                rest = Some(&data[start + delimiter.len()..]);
+                Some(&data[..start])
+            }
+            None => {
+                rest = None;
+                Some(data)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{Method, Url};
+
+    #[test]
+    fn test_batch_body() -> azure_core::Result<()> {
+        let mut batch = BatchBody::with_boundary("batch_1");
+        let mut delete = Request::new(
+            Url::parse("https://account.blob.core.windows.net/container/a")?,
+            Method::Delete,
+        );
+        delete.insert_header("x-ms-date", "Thu, 14 Jun 2018 16:46:54 GMT");
+        assert_eq!(batch.add(&delete)?, 0);
+        let mut tier = Request::new(
+            Url::parse("https://account.blob.core.windows.net/container/b?comp=tier")?,
+            Method::Put,
+        );
+        tier.insert_header("x-ms-access-tier", "Cool");
+        assert_eq!(batch.add(&tier)?, 1);
+        assert_eq!(batch.len(), 2);
+
+        assert_eq!(
+            std::str::from_utf8(&batch.to_bytes())?,
+            "--batch_1\r\n\
+             Content-Type: application/http\r\n\
+             Content-Transfer-Encoding: binary\r\n\
+             Content-ID: 0\r\n\
+             \r\n\
+             DELETE /container/a HTTP/1.1\r\n\
+             x-ms-date: Thu, 14 Jun 2018 16:46:54 GMT\r\n\
+             content-length: 0\r\n\
+             \r\n\
+             --batch_1\r\n\
+             Content-Type: application/http\r\n\
+             Content-Transfer-Encoding: binary\r\n\
+             Content-ID: 1\r\n\
+             \r\n\
+             PUT /container/b?comp=tier HTTP/1.1\r\n\
+             x-ms-access-tier: Cool\r\n\
+             content-length: 0\r\n\
+             \r\n\
+             --batch_1--\r\n"
+        );
+
+        for _ in 2..MAX_BATCH_REQUESTS {
+            batch.add(&delete)?;
+        }
+        assert!(batch.add(&delete).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_batch_response() -> azure_core::Result<()> {
+        const BODY: &[u8] = b"--batchresponse_6692\r\n\
+            Content-Type: application/http\r\n\
+            Content-ID: 0\r\n\
+            \r\n\
+            HTTP/1.1 202 Accepted\r\n\
+            x-ms-delete-type-permanent: true\r\n\
+            x-ms-request-id: 778fdc83-801e-0000-62ff-0334671e284f\r\n\
+            x-ms-version: 2018-11-09\r\n\
+            \r\n\
+            --batchresponse_6692\r\n\
+            Content-Type: application/http\r\n\
+            Content-ID: 1\r\n\
+            \r\n\
+            HTTP/1.1 404 The specified blob does not exist.\r\n\
+            x-ms-error-code: BlobNotFound\r\n\
+            x-ms-request-id: 778fdc83-801e-0000-62ff-0334671e2852\r\n\
+            Content-Length: 36\r\n\
+            Content-Type: application/xml\r\n\
+            \r\n\
+            <Error><Code>BlobNotFound</Code></Error>\r\n\
+            --batchresponse_6692--\r\n";
+
+        let responses = parse_batch_response("multipart/mixed; boundary=batchresponse_6692", BODY)?;
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].content_id, Some(0));
+        assert!(responses[0].is_success());
+        assert_eq!(
+            responses[0].headers.get_optional_str(&headers::REQUEST_ID),
+            Some("778fdc83-801e-0000-62ff-0334671e284f")
+        );
+        assert!(responses[0].body.is_empty());
+
+        assert_eq!(responses[1].content_id, Some(1));
+        assert_eq!(responses[1].status, StatusCode::NotFound);
+        assert_eq!(responses[1].error_code(), Some("BlobNotFound"));
+        assert_eq!(
+            &responses[1].body[..],
+            b"<Error><Code>BlobNotFound</Code></Error>"
+        );
+
+        assert!(parse_batch_response("multipart/mixed", BODY).is_err());
+        assert!(parse_batch_response(
+            "multipart/mixed; boundary=batchresponse_6692",
+            &BODY[..BODY.len() - 30]
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{blob::BlobProperties, prelude::*};
+
+/// The HTTP headers of a blob, set together by
+/// [`SetPropertiesBuilder::http_headers`](crate::blob::operations::SetPropertiesBuilder::http_headers).
+///
+/// The service clears the headers which are not sent along with the others,
+/// so they are best read from the current properties and changed from there:
+///
+/// ```
+/// use azure_storage_blobs::blob::BlobHttpHeaders;
+///
+/// # fn example(properties: &azure_storage_blobs::blob::BlobProperties) {
+/// let headers = BlobHttpHeaders::from(properties).cache_control("max-age=3600");
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobHttpHeaders {
+    pub cache_control: Option<BlobCacheControl>,
+    pub content_type: Option<BlobContentType>,
+    pub content_encoding: Option<BlobContentEncoding>,
+    pub content_language: Option<BlobContentLanguage>,
+    pub content_disposition: Option<BlobContentDisposition>,
+    pub content_md5: Option<BlobContentMD5>,
+}
+
+impl BlobHttpHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    setters! {
+        cache_control: BlobCacheControl => Some(cache_control),
+        content_type: BlobContentType => Some(content_type),
+        content_encoding: BlobContentEncoding => Some(content_encoding),
+        content_language: BlobContentLanguage => Some(content_language),
+        content_disposition: BlobContentDisposition => Some(content_disposition),
+        content_md5: BlobContentMD5 => Some(content_md5),
+    }
+}
+
+impl From<&BlobProperties> for BlobHttpHeaders {
+    fn from(properties: &BlobProperties) -> Self {
+        // Every property is named, so that a new one does not compile until
+        // it is mapped to a header or deliberately left out here.
+        let BlobProperties {
+            creation_time: _,
+            last_modified: _,
+            last_access_time: _,
+            etag: _,
+            content_length: _,
+            content_type,
+            content_encoding,
+            content_language,
+            content_disposition,
+            content_md5,
+            content_crc64: _,
+            cache_control,
+            blob_sequence_number: _,
+            blob_type: _,
+            access_tier: _,
+            access_tier_change_time: _,
+            lease_status: _,
+            lease_state: _,
+            lease_duration: _,
+            copy_id: _,
+            copy_status: _,
+            copy_source: _,
+            copy_progress: _,
+            copy_completion_time: _,
+            copy_status_description: _,
+            server_encrypted: _,
+            customer_provided_key_sha256: _,
+            encryption_scope: _,
+            incremental_copy: _,
+            access_tier_inferred: _,
+            deleted_time: _,
+            remaining_retention_days: _,
+            tag_count: _,
+            rehydrate_priority: _,
+            expiry_time: _,
+            extra: _,
+        } = properties;
+
+        Self {
+            cache_control: non_empty(cache_control.as_deref()),
+            content_type: non_empty(Some(content_type)),
+            content_encoding: non_empty(content_encoding.as_deref()),
+            content_language: non_empty(content_language.as_deref()),
+            content_disposition: non_empty(content_disposition.as_deref()),
+            content_md5: content_md5.clone().map(Into::into),
+        }
+    }
+}
+
+/// Listings return unset headers as empty elements, which are left unset.
+fn non_empty<T: From<String>>(value: Option<&str>) -> Option<T> {
+    value
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_owned().into())
+}
+
+impl From<BlobProperties> for BlobHttpHeaders {
+    fn from(properties: BlobProperties) -> Self {
+        Self::from(&properties)
+    }
+}
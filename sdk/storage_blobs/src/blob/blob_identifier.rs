@@ -0,0 +1,127 @@
+use crate::{
+    blob::BlobUrlParts,
+    options::{Snapshot, VersionId},
+    prelude::*,
+};
+use azure_core::Url;
+use azure_storage::StorageCredentials;
+use serde::{Deserialize, Serialize};
+
+/// Identifies a blob, or a snapshot or version of it, without credentials,
+/// e.g. to persist in a job queue or to use as a map key.
+///
+/// ```
+/// use azure_storage_blobs::blob::BlobIdentifier;
+///
+/// let identifier = BlobIdentifier::new(
+///     "https://account.blob.core.windows.net",
+///     "photos",
+///     "2022/lake view.jpg",
+/// );
+/// assert_eq!(
+///     identifier.url().unwrap().as_str(),
+///     "https://account.blob.core.windows.net/photos/2022/lake%20view.jpg"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BlobIdentifier {
+    /// The URL of the account's blob service, without a trailing `/`.
+    pub service_url: String,
+    pub container_name: String,
+    pub blob_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<Snapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<VersionId>,
+}
+
+impl BlobIdentifier {
+    pub fn new(
+        service_url: impl Into<String>,
+        container_name: impl Into<String>,
+        blob_name: impl Into<String>,
+    ) -> Self {
+        let service_url = service_url.into();
+        Self {
+            service_url: service_url.trim_end_matches('/').to_owned(),
+            container_name: container_name.into(),
+            blob_name: blob_name.into(),
+            snapshot: None,
+            version_id: None,
+        }
+    }
+
+    setters! {
+        snapshot: Snapshot => Some(snapshot),
+        version_id: VersionId => Some(version_id),
+    }
+
+    /// The URL of the blob, with its snapshot or version.
+    pub fn url(&self) -> azure_core::Result<Url> {
+        let mut parts = BlobUrlParts::parse(&Url::parse(&self.service_url)?)?;
+        parts.container_name = Some(self.container_name.clone());
+        parts.blob_name = Some(self.blob_name.clone());
+        parts.snapshot = self.snapshot;
+        parts.version_id = self.version_id;
+        parts.sas_token = None;
+        parts.to_url()
+    }
+
+    /// A client of the blob, reached with `credentials`.
+    ///
+    /// Clients do not address snapshots or versions: pass them to the
+    /// operations of the client.
+    pub fn blob_client(
+        &self,
+        credentials: impl Into<StorageCredentials>,
+    ) -> azure_core::Result<BlobClient> {
+        let parts = BlobUrlParts::parse(&Url::parse(&self.service_url)?)?;
+        let cloud_location = parts.cloud_location(credentials.into())?;
+        Ok(ClientBuilder::with_location(cloud_location)
+            .blob_client(self.container_name.clone(), self.blob_name.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_blob_identity() -> azure_core::Result<()> {
+        let blob_client = ClientBuilder::new(
+            "account",
+            StorageCredentials::access_key("account", base64::encode("key")),
+        )
+        .blob_client("container", "a/b");
+        let anonymous = ClientBuilder::new("account", StorageCredentials::Anonymous)
+            .blob_client("container", "a/b");
+        assert_eq!(blob_client, anonymous);
+        assert_ne!(blob_client, anonymous.container_client().blob_client("a/c"));
+        let clients = [blob_client.clone(), anonymous]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(clients.len(), 1);
+
+        let identifier = blob_client
+            .identifier()?
+            .snapshot("2011-03-09T01:42:34.9360000Z".parse::<Snapshot>()?);
+        assert_eq!(
+            identifier.service_url,
+            "https://account.blob.core.windows.net"
+        );
+        let json = serde_json::to_string(&identifier)?;
+        assert!(!json.contains("version_id"));
+        assert_eq!(serde_json::from_str::<BlobIdentifier>(&json)?, identifier);
+        assert_eq!(
+            identifier.url()?.as_str(),
+            "https://account.blob.core.windows.net/container/a/b\
+             ?snapshot=2011-03-09T01%3A42%3A34.9360000Z"
+        );
+        assert_eq!(
+            identifier.blob_client(StorageCredentials::Anonymous)?,
+            blob_client
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::options::{Snapshot, VersionId};
+use azure_core::{
+    auth::Secret,
+    error::{Error, ErrorKind, ResultExt},
+    AppendToUrlQuery, Url,
+};
+use azure_storage::{AddressingMode, CloudLocation, StorageCredentials};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use std::net::IpAddr;
+
+/// The characters escaped in container and blob names. `/` is kept, as blob
+/// names use it to form virtual directories.
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// The parts of the URL of an account, container or blob.
+///
+/// Both addressing modes are understood: the account is the first label of
+/// the host (`https://{account}.blob.core.windows.net/{container}/{blob}`),
+/// or the first segment of the path when the host is an IP address or
+/// `localhost`, as with the emulator (`http://127.0.0.1:10000/{account}/...`).
+///
+/// ```
+/// use azure_storage_blobs::blob::BlobUrlParts;
+///
+/// let url = "https://account.blob.core.windows.net/photos/2022/lake%20view.jpg\
+///            ?snapshot=2022-07-01T10%3A44%3A59.0000000Z&sv=2021-08-06&sig=c2ln"
+///     .parse()
+///     .unwrap();
+/// let parts = BlobUrlParts::parse(&url).unwrap();
+/// assert_eq!(parts.account_name.as_deref(), Some("account"));
+/// assert_eq!(parts.container_name.as_deref(), Some("photos"));
+/// assert_eq!(parts.blob_name.as_deref(), Some("2022/lake view.jpg"));
+/// assert!(parts.snapshot.is_some());
+/// assert!(parts.sas_token.is_some());
+/// assert_eq!(parts.to_url().unwrap(), url);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlobUrlParts {
+    pub scheme: String,
+    /// The host, with the account for virtual host addressing.
+    pub host: String,
+    pub port: Option<u16>,
+    pub account_name: Option<String>,
+    /// Whether the account is the first segment of the path.
+    pub path_style: bool,
+    pub container_name: Option<String>,
+    pub blob_name: Option<String>,
+    pub snapshot: Option<Snapshot>,
+    pub version_id: Option<VersionId>,
+    /// The rest of the query, normally a SAS token.
+    pub sas_token: Option<Secret>,
+}
+
+impl BlobUrlParts {
+    pub fn parse(url: &Url) -> azure_core::Result<Self> {
+        let host = url.host_str().ok_or_else(|| {
+            Error::with_message(ErrorKind::DataConversion, || {
+                format!("the URL has no host: {}", url.path())
+            })
+        })?;
+        let path_style = is_path_style_host(host);
+
+        let mut segments = url.path().split('/').filter(|segment| !segment.is_empty());
+        let account_name = if path_style {
+            segments.next().map(decode).transpose()?
+        } else {
+            host.split('.').next().map(ToOwned::to_owned)
+        };
+        let container_name = segments.next().map(decode).transpose()?;
+        let blob_name = segments.collect::<Vec<_>>().join("/");
+        let blob_name = (!blob_name.is_empty())
+            .then(|| decode(&blob_name))
+            .transpose()?;
+
+        let mut snapshot = None;
+        let mut version_id = None;
+        let mut rest = Vec::new();
+        for pair in url.query().unwrap_or_default().split('&') {
+            let mut parsed = url::form_urlencoded::parse(pair.as_bytes());
+            match parsed.next() {
+                Some((key, value)) if key == "snapshot" => snapshot = Some(value.parse()?),
+                Some((key, value)) if key == "versionid" => version_id = Some(value.parse()?),
+                Some(_) => rest.push(pair),
+                None => {}
+            }
+        }
+
+        Ok(Self {
+            scheme: url.scheme().to_owned(),
+            host: host.to_owned(),
+            port: url.port(),
+            account_name,
+            path_style,
+            container_name,
+            blob_name,
+            snapshot,
+            version_id,
+            sas_token: (!rest.is_empty()).then(|| Secret::new(rest.join("&"))),
+        })
+    }
+
+    /// The URL the parts make up.
+    pub fn to_url(&self) -> azure_core::Result<Url> {
+        let mut url = self.base_url()?;
+        {
+            let mut path = String::new();
+            if self.path_style {
+                if let Some(account_name) = &self.account_name {
+                    path.push('/');
+                    path.extend(utf8_percent_encode(account_name, PATH));
+                }
+            }
+            for name in [&self.container_name, &self.blob_name]
+                .into_iter()
+                .flatten()
+            {
+                path.push('/');
+                path.extend(utf8_percent_encode(name, PATH));
+            }
+            url.set_path(&path);
+        }
+        self.snapshot.append_to_url_query(&mut url);
+        self.version_id.append_to_url_query(&mut url);
+        if let Some(sas_token) = &self.sas_token {
+            let query = match url.query() {
+                Some(query) => format!("{query}&{}", sas_token.secret()),
+                None => sas_token.secret().to_owned(),
+            };
+            url.set_query(Some(&query));
+        }
+        Ok(url)
+    }
+
+    /// The credentials of the SAS token in the URL, if there is one.
+    pub fn sas_credentials(&self) -> azure_core::Result<Option<StorageCredentials>> {
+        self.sas_token
+            .as_ref()
+            .map(|sas_token| StorageCredentials::sas_token(sas_token.secret()))
+            .transpose()
+    }
+
+    /// Where the account of the URL is, reached with `credentials`.
+    pub fn cloud_location(
+        &self,
+        credentials: StorageCredentials,
+    ) -> azure_core::Result<CloudLocation> {
+        let account = self.account_name.clone().ok_or_else(|| {
+            Error::message(
+                ErrorKind::DataConversion,
+                "the URL does not name an account",
+            )
+        })?;
+        if !self.path_style && self.scheme == "https" && self.port.is_none() {
+            if self.host == format!("{account}.blob.core.windows.net") {
+                return Ok(CloudLocation::Public {
+                    account,
+                    credentials,
+                });
+            }
+            if self.host == format!("{account}.blob.core.chinacloudapi.cn") {
+                return Ok(CloudLocation::China {
+                    account,
+                    credentials,
+                });
+            }
+        }
+        let addressing_mode = if self.path_style {
+            AddressingMode::PathStyle { account }
+        } else {
+            AddressingMode::VirtualHost
+        };
+        Ok(CloudLocation::Custom {
+            uri: self.base_url()?.as_str().trim_end_matches('/').to_owned(),
+            credentials,
+            addressing_mode,
+        })
+    }
+
+    fn base_url(&self) -> azure_core::Result<Url> {
+        let url = match self.port {
+            Some(port) => format!("{}://{}:{port}", self.scheme, self.host),
+            None => format!("{}://{}", self.scheme, self.host),
+        };
+        Url::parse(&url).context(ErrorKind::DataConversion, "invalid URL parts")
+    }
+}
+
+fn is_path_style_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok()
+}
+
+fn decode(segment: &str) -> azure_core::Result<String> {
+    Ok(percent_decode_str(segment)
+        .decode_utf8()
+        .context(ErrorKind::DataConversion, "the URL path is not UTF-8")?
+        .into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() -> azure_core::Result<()> {
+        for url in [
+            "https://account.blob.core.windows.net/",
+            "https://account.blob.core.windows.net/container",
+            "https://account.blob.core.windows.net/container/a/b%20c.txt?versionid=2022-07-01T10%3A44%3A59.0000000Z",
+            "http://127.0.0.1:10000/devstoreaccount1/container/blob?sv=2021-08-06&sig=c2ln",
+            "https://blob.stack.local:8443/container/blob",
+        ] {
+            let url = Url::parse(url)?;
+            assert_eq!(BlobUrlParts::parse(&url)?.to_url()?, url);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_cloud_location() -> azure_core::Result<()> {
+        let parts = BlobUrlParts::parse(&"https://account.blob.core.windows.net/c/b".parse()?)?;
+        assert!(matches!(
+            parts.cloud_location(StorageCredentials::Anonymous)?,
+            CloudLocation::Public { account, .. } if account == "account"
+        ));
+
+        let parts = BlobUrlParts::parse(&"http://localhost:10000/devstoreaccount1/c/b".parse()?)?;
+        assert_eq!(parts.account_name.as_deref(), Some("devstoreaccount1"));
+        assert_eq!(parts.container_name.as_deref(), Some("c"));
+        let location = parts.cloud_location(StorageCredentials::Anonymous)?;
+        assert_eq!(
+            location
+                .url(azure_storage::clients::ServiceType::Blob)?
+                .as_str(),
+            "http://localhost:10000/devstoreaccount1"
+        );
+        assert!(parts.sas_credentials()?.is_none());
+        Ok(())
+    }
+}
@@ -1,4 +1,11 @@
-use crate::blob::{BlobBlockType, BlockWithSizeList};
+use crate::blob::{operations::PutBlockResponse, BlobBlockType, BlockWithSizeList};
+use crate::{limits::MAX_BLOCKS, options::BlockId};
+use azure_core::{
+    error::{Error, ErrorKind},
+    SeekableStream,
+};
+use futures::{io::AsyncRead, task::Poll, Stream, TryStreamExt};
+use std::{pin::Pin, sync::Arc};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct BlockList {
@@ -15,32 +22,295 @@ impl From<BlockWithSizeList> for BlockList {
     }
 }
 
+impl BlobBlockType {
+    fn block_id(&self) -> &BlockId {
+        match self {
+            BlobBlockType::Committed(id)
+            | BlobBlockType::Uncommitted(id)
+            | BlobBlockType::Latest(id) => id,
+        }
+    }
+}
+
+impl From<PutBlockResponse> for BlobBlockType {
+    /// A block which was just staged is uncommitted.
+    fn from(response: PutBlockResponse) -> Self {
+        BlobBlockType::Uncommitted(response.block_id)
+    }
+}
+
+impl Extend<BlobBlockType> for BlockList {
+    fn extend<T: IntoIterator<Item = BlobBlockType>>(&mut self, iter: T) {
+        self.blocks.extend(iter)
+    }
+}
+
+impl FromIterator<BlobBlockType> for BlockList {
+    fn from_iter<T: IntoIterator<Item = BlobBlockType>>(iter: T) -> Self {
+        Self {
+            blocks: iter.into_iter().collect(),
+        }
+    }
+}
+
 impl BlockList {
-    pub fn to_xml(&self) -> String {
-        let mut s = String::new();
-        s.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<BlockList>\n");
-        for bl in &self.blocks {
-            let node = match bl {
-                BlobBlockType::Committed(content) => {
-                    format!(
-                        "\t<Committed>{}</Committed>\n",
-                        base64::encode(content.as_ref())
-                    )
-                }
-                BlobBlockType::Uncommitted(content) => format!(
-                    "\t<Uncommitted>{}</Uncommitted>\n",
-                    base64::encode(content.as_ref())
-                ),
-                BlobBlockType::Latest(content) => {
-                    format!("\t<Latest>{}</Latest>\n", base64::encode(content.as_ref()))
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a block to the end of the list.
+    pub fn push(&mut self, block: impl Into<BlobBlockType>) -> &mut Self {
+        self.blocks.push(block.into());
+        self
+    }
+
+    /// Append a block from the committed block list.
+    pub fn push_committed(&mut self, block_id: impl Into<BlockId>) -> &mut Self {
+        self.push(BlobBlockType::new_committed(block_id))
+    }
+
+    /// Append a block from the uncommitted block list.
+    pub fn push_uncommitted(&mut self, block_id: impl Into<BlockId>) -> &mut Self {
+        self.push(BlobBlockType::new_uncommitted(block_id))
+    }
+
+    /// Append the most recently staged version of a block.
+    pub fn push_latest(&mut self, block_id: impl Into<BlockId>) -> &mut Self {
+        self.push(BlobBlockType::new_latest(block_id))
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Build a block list from a stream of staged blocks.
+    ///
+    /// Blocks are committed in the order the stream yields them, so use an
+    /// order preserving combinator such as `buffered` rather than
+    /// `buffer_unordered` when staging blocks concurrently.
+    pub async fn try_from_stream<S>(stream: S) -> azure_core::Result<Self>
+    where
+        S: Stream<Item = azure_core::Result<PutBlockResponse>>,
+    {
+        stream
+            .map_ok(BlobBlockType::from)
+            .try_collect::<Vec<_>>()
+            .await
+            .map(|blocks| Self { blocks })
+    }
+
+    /// Checks every block id is valid, that all block ids have the same
+    /// length and that there are no more blocks than a blob can have, see
+    /// [`TooManyBlocks`].
+    ///
+    /// The service rejects such block lists, so this catches the problem
+    /// before the blocks are committed.
+    pub fn validate(&self) -> azure_core::Result<()> {
+        if self.blocks.len() as u64 > MAX_BLOCKS {
+            return Err(Error::new(
+                ErrorKind::Other,
+                TooManyBlocks {
+                    blocks: self.blocks.len() as u64,
+                    max_blocks: MAX_BLOCKS,
+                },
+            ));
+        }
+        let mut ids = self.blocks.iter().map(BlobBlockType::block_id);
+        if let Some(first) = ids.next() {
+            first.validate()?;
+            for id in ids {
+                id.validate()?;
+                if id.len() != first.len() {
+                    return Err(Error::with_message(ErrorKind::Other, || {
+                        format!(
+                            "all block ids in a block list must have the same length. expected: {} len: {}",
+                            first.len(),
+                            id.len()
+                        )
+                    }));
                 }
-            };
+            }
+        }
+        Ok(())
+    }
+
+    /// The XML body of a Put Block List request.
+    ///
+    /// Lists of thousands of blocks are better sent with
+    /// [`BlockList::into_xml_stream`], which does not build the whole body in
+    /// memory.
+    pub fn to_xml(&self) -> String {
+        let mut xml = Vec::with_capacity(self.xml_len());
+        for segment in 0..self.segment_count() {
+            self.write_segment(segment, &mut xml);
+        }
+        // only ASCII is written
+        String::from_utf8(xml).unwrap()
+    }
+
+    /// The XML body of a Put Block List request as a stream, which writes the
+    /// blocks as they are read.
+    pub fn into_xml_stream(self) -> BlockListXmlStream {
+        BlockListXmlStream::new(self)
+    }
+
+    /// Calls `f` with the consecutive parts of the XML body, e.g. to hash the
+    /// body without building it.
+    pub(crate) fn for_each_xml_chunk(&self, mut f: impl FnMut(&[u8])) {
+        let mut chunk = Vec::with_capacity(XML_CHUNK_SIZE);
+        for segment in 0..self.segment_count() {
+            self.write_segment(segment, &mut chunk);
+            if chunk.len() >= XML_CHUNK_SIZE {
+                f(&chunk);
+                chunk.clear();
+            }
+        }
+        f(&chunk);
+    }
+
+    /// The length of the XML body in bytes.
+    pub fn xml_len(&self) -> usize {
+        XML_HEADER.len()
+            + self
+                .blocks
+                .iter()
+                .map(|block| {
+                    let tag = block_tag(block);
+                    // \t<tag>id</tag>\n
+                    2 * tag.len() + 7 + base64_len(block.block_id().len())
+                })
+                .sum::<usize>()
+            + XML_FOOTER.len()
+    }
 
-            s.push_str(&node);
+    /// The header, the blocks and the footer.
+    fn segment_count(&self) -> usize {
+        self.blocks.len() + 2
+    }
+
+    fn write_segment(&self, segment: usize, out: &mut Vec<u8>) {
+        if segment == 0 {
+            out.extend_from_slice(XML_HEADER.as_bytes());
+        } else if let Some(block) = self.blocks.get(segment - 1) {
+            let tag = block_tag(block);
+            out.extend_from_slice(format!("\t<{tag}>").as_bytes());
+            out.extend_from_slice(base64::encode(block.block_id().as_ref()).as_bytes());
+            out.extend_from_slice(format!("</{tag}>\n").as_bytes());
+        } else {
+            out.extend_from_slice(XML_FOOTER.as_bytes());
+        }
+    }
+}
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<BlockList>\n";
+const XML_FOOTER: &str = "</BlockList>";
+/// How much XML is generated at a time.
+const XML_CHUNK_SIZE: usize = 64 * 1024;
+
+fn block_tag(block: &BlobBlockType) -> &'static str {
+    match block {
+        BlobBlockType::Committed(_) => "Committed",
+        BlobBlockType::Uncommitted(_) => "Uncommitted",
+        BlobBlockType::Latest(_) => "Latest",
+    }
+}
+
+/// The length of the padded base64 encoding of `len` bytes.
+fn base64_len(len: usize) -> usize {
+    (len + 2) / 3 * 4
+}
+
+/// The error returned, as the source of an `ErrorKind::Other` error, for block
+/// lists with more blocks than a blob can have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TooManyBlocks {
+    pub blocks: u64,
+    pub max_blocks: u64,
+}
+
+impl std::fmt::Display for TooManyBlocks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} blocks are more than the {} blocks a blob can have",
+            self.blocks, self.max_blocks
+        )
+    }
+}
+
+impl std::error::Error for TooManyBlocks {}
+
+/// The XML body of a Put Block List request, generated as it is read. See
+/// [`BlockList::into_xml_stream`].
+#[derive(Debug, Clone)]
+pub struct BlockListXmlStream {
+    block_list: Arc<BlockList>,
+    len: usize,
+    next_segment: usize,
+    buffer: Vec<u8>,
+    buffer_read: usize,
+}
+
+impl BlockListXmlStream {
+    fn new(block_list: BlockList) -> Self {
+        Self {
+            len: block_list.xml_len(),
+            block_list: Arc::new(block_list),
+            next_segment: 0,
+            buffer: Vec::new(),
+            buffer_read: 0,
+        }
+    }
+
+    /// Refills the buffer with the next segments, if it was read.
+    fn fill_buffer(&mut self) {
+        if self.buffer_read < self.buffer.len() {
+            return;
         }
+        self.buffer.clear();
+        self.buffer_read = 0;
+        while self.buffer.len() < XML_CHUNK_SIZE
+            && self.next_segment < self.block_list.segment_count()
+        {
+            self.block_list
+                .write_segment(self.next_segment, &mut self.buffer);
+            self.next_segment += 1;
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl SeekableStream for BlockListXmlStream {
+    async fn reset(&mut self) -> azure_core::Result<()> {
+        self.next_segment = 0;
+        self.buffer.clear();
+        self.buffer_read = 0;
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
 
-        s.push_str("</BlockList>");
-        s
+impl AsyncRead for BlockListXmlStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        this.fill_buffer();
+        let available = &this.buffer[this.buffer_read..];
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        this.buffer_read += read;
+        Poll::Ready(Ok(read))
     }
 }
 
@@ -63,8 +333,80 @@ mod test {
             .push(BlobBlockType::new_uncommitted("numero3"));
         blocks.blocks.push(BlobBlockType::new_latest("numero4"));
 
-        let _retu: &str = &blocks.to_xml();
+        let xml = blocks.to_xml();
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<BlockList>\n\
+             \t<Committed>bnVtZXJvMQ==</Committed>\n\
+             \t<Uncommitted>bnVtZXJvMg==</Uncommitted>\n\
+             \t<Uncommitted>bnVtZXJvMw==</Uncommitted>\n\
+             \t<Latest>bnVtZXJvNA==</Latest>\n\
+             </BlockList>"
+        );
+        assert_eq!(blocks.xml_len(), xml.len());
+    }
+
+    #[tokio::test]
+    async fn xml_stream() {
+        use futures::io::AsyncReadExt;
+
+        let blocks = (0..5000)
+            .map(|i| BlobBlockType::new_uncommitted(BlockId::new(format!("{i:06}"))))
+            .collect::<BlockList>();
+        let xml = blocks.to_xml();
+        let mut chunks = Vec::new();
+        blocks.for_each_xml_chunk(|chunk| chunks.extend_from_slice(chunk));
+        assert_eq!(chunks, xml.as_bytes());
+
+        let mut stream = blocks.into_xml_stream();
+        assert_eq!(stream.len(), xml.len());
+        for _ in 0..2 {
+            let mut read = Vec::new();
+            stream.read_to_end(&mut read).await.unwrap();
+            assert_eq!(read, xml.as_bytes());
+            stream.reset().await.unwrap();
+        }
+    }
+
+    #[test]
+    fn push() {
+        let mut blocks = BlockList::new();
+        blocks
+            .push_committed("numero1")
+            .push_uncommitted("numero2")
+            .push_latest("numero3");
+        blocks.extend(vec![BlobBlockType::new_latest("numero4")]);
+
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks.blocks[0], BlobBlockType::new_committed("numero1"));
+        assert_eq!(blocks.blocks[3], BlobBlockType::new_latest("numero4"));
+    }
+
+    #[test]
+    fn validate() {
+        let mut blocks = BlockList {
+            blocks: vec![
+                BlobBlockType::new_committed(BlockId::random()),
+                BlobBlockType::new_uncommitted(BlockId::random()),
+            ],
+        };
+        assert!(blocks.validate().is_ok());
+
+        blocks.blocks.push(BlobBlockType::new_latest("short"));
+        assert!(blocks.validate().is_err());
+
+        assert!(BlockList::default().validate().is_ok());
 
-        // to assert with handcrafted XML
+        let blocks = (0..=MAX_BLOCKS)
+            .map(|i| BlobBlockType::new_uncommitted(BlockId::new(format!("{i:06}"))))
+            .collect::<BlockList>();
+        let error = blocks.validate().unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<TooManyBlocks>(),
+            Some(&TooManyBlocks {
+                blocks: MAX_BLOCKS + 1,
+                max_blocks: MAX_BLOCKS
+            })
+        );
     }
 }
@@ -0,0 +1,84 @@
+use azure_core::{
+    error::{Error, ErrorKind},
+    headers::ETAG,
+    Continuable, Etag, StatusCode,
+};
+
+/// The result of a request made with conditional headers such as
+/// `If-None-Match`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conditional<T> {
+    /// The resource did not match the condition, so it was returned.
+    Modified(T),
+    /// The service answered `304 Not Modified`, with the resource's current
+    /// ETag when it sent one.
+    NotModified(Option<Etag>),
+}
+
+impl<T> Conditional<T> {
+    pub fn is_modified(&self) -> bool {
+        matches!(self, Conditional::Modified(_))
+    }
+
+    /// The response, if the resource was modified.
+    pub fn modified(self) -> Option<T> {
+        match self {
+            Conditional::Modified(response) => Some(response),
+            Conditional::NotModified(_) => None,
+        }
+    }
+
+    /// Turns a `304 Not Modified` error into [`Conditional::NotModified`].
+    pub(crate) fn from_result(result: azure_core::Result<T>) -> azure_core::Result<Self> {
+        match result {
+            Ok(response) => Ok(Conditional::Modified(response)),
+            Err(error) => not_modified(&error)
+                .map(Conditional::NotModified)
+                .ok_or(error),
+        }
+    }
+}
+
+fn not_modified(error: &Error) -> Option<Option<Etag>> {
+    match error.kind() {
+        ErrorKind::HttpResponse {
+            status: StatusCode::NotModified,
+            ..
+        } => Some(
+            error
+                .as_http_error()
+                .and_then(|http_error| http_error.header(ETAG.as_str()))
+                .map(Etag::from),
+        ),
+        _ => None,
+    }
+}
+
+impl<T: Continuable> Continuable for Conditional<T> {
+    type Continuation = T::Continuation;
+
+    fn continuation(&self) -> Option<Self::Continuation> {
+        match self {
+            Conditional::Modified(response) => response.continuation(),
+            Conditional::NotModified(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_result() {
+        let result = Conditional::from_result(Ok(1)).unwrap();
+        assert_eq!(result, Conditional::Modified(1));
+
+        let error = ErrorKind::http_response(StatusCode::NotModified, None).into_error();
+        let result = Conditional::<i32>::from_result(Err(error)).unwrap();
+        assert_eq!(result, Conditional::NotModified(None));
+
+        let error = ErrorKind::http_response(StatusCode::NotFound, None).into_error();
+        assert!(Conditional::<i32>::from_result(Err(error)).is_err());
+    }
+}
@@ -0,0 +1,480 @@
+//! `tokio::io` adapters for blobs, so they can be handed to code written
+//! against `AsyncRead`, `AsyncSeek` and `AsyncWrite`.
+use crate::prelude::*;
+use azure_core::{error::Error, prelude::IfMatchCondition, Etag};
+use bytes::{Bytes, BytesMut};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use std::{
+    io::{self, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+
+/// How much a [`BlobReader`] downloads at once by default.
+pub const DEFAULT_READ_AHEAD: u64 = 4 * 1024 * 1024;
+/// Size of the blocks staged by a [`BlobWriter`] by default.
+pub const DEFAULT_WRITE_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Reads a blob with ranged downloads, as it is read or seeked.
+///
+/// Each download fetches `read_ahead` bytes from the current position. The
+/// length and ETag of the blob are read with a Get Blob Properties request
+/// before the first download, and the downloads are made on the condition
+/// that the ETag is unchanged, so that reading a blob which changes meanwhile
+/// fails rather than mixes two versions of it.
+pub struct BlobReader {
+    client: BlobClient,
+    read_ahead: u64,
+    position: u64,
+    len: Option<u64>,
+    etag: Option<Etag>,
+    buffer: Bytes,
+    buffer_start: u64,
+    seek: Option<SeekFrom>,
+    state: ReadState,
+}
+
+enum ReadState {
+    Idle,
+    Sizing(BoxFuture<'static, azure_core::Result<(u64, Etag)>>),
+    Fetching(u64, BoxFuture<'static, azure_core::Result<Bytes>>),
+}
+
+impl BlobReader {
+    pub(crate) fn new(client: BlobClient) -> Self {
+        Self {
+            client,
+            read_ahead: DEFAULT_READ_AHEAD,
+            position: 0,
+            len: None,
+            etag: None,
+            buffer: Bytes::new(),
+            buffer_start: 0,
+            seek: None,
+            state: ReadState::Idle,
+        }
+    }
+
+    /// Set how many bytes are downloaded at once.
+    pub fn read_ahead(mut self, read_ahead: u64) -> Self {
+        self.read_ahead = read_ahead.max(1);
+        self
+    }
+
+    /// The position of the next byte read.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// The bytes of the buffer from the current position on.
+    fn buffered(&self) -> &[u8] {
+        let end = self.buffer_start + self.buffer.len() as u64;
+        if self.buffer_start <= self.position && self.position < end {
+            &self.buffer[(self.position - self.buffer_start) as usize..]
+        } else {
+            &[]
+        }
+    }
+
+    /// Polls the length of the blob, reading it and its ETag first if needed.
+    fn poll_len(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        loop {
+            if let Some(len) = self.len {
+                return Poll::Ready(Ok(len));
+            }
+            match &mut self.state {
+                ReadState::Sizing(future) => {
+                    let result = futures::ready!(future.poll_unpin(cx));
+                    self.state = ReadState::Idle;
+                    let (len, etag) = result.map_err(into_io_error)?;
+                    self.len = Some(len);
+                    self.etag = Some(etag);
+                }
+                ReadState::Idle | ReadState::Fetching(..) => {
+                    let client = self.client.clone();
+                    self.state = ReadState::Sizing(
+                        async move {
+                            let properties = client.get_properties().await?.blob.properties;
+                            Ok((properties.content_length, properties.etag))
+                        }
+                        .boxed(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl AsyncRead for BlobReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            let buffered = this.buffered();
+            if !buffered.is_empty() {
+                let n = buffered.len().min(buf.remaining());
+                buf.put_slice(&buffered[..n]);
+                this.position += n as u64;
+                return Poll::Ready(Ok(()));
+            }
+
+            let len = futures::ready!(this.poll_len(cx))?;
+            match &mut this.state {
+                ReadState::Fetching(start, future) => {
+                    let start = *start;
+                    let result = futures::ready!(future.poll_unpin(cx));
+                    this.state = ReadState::Idle;
+                    let data = result.map_err(into_io_error)?;
+                    if data.is_empty() {
+                        // the blob shrank since its length was read
+                        this.len = Some(start);
+                        return Poll::Ready(Ok(()));
+                    }
+                    this.buffer = data;
+                    this.buffer_start = start;
+                }
+                ReadState::Idle | ReadState::Sizing(_) => {
+                    if this.position >= len || buf.remaining() == 0 {
+                        return Poll::Ready(Ok(()));
+                    }
+                    let start = this.position;
+                    let end = len.min(start + this.read_ahead);
+                    let client = this.client.clone();
+                    let etag = this.etag.clone();
+                    this.state =
+                        ReadState::Fetching(start, fetch(client, start, end, etag).boxed());
+                }
+            }
+        }
+    }
+}
+
+impl AsyncSeek for BlobReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let this = self.get_mut();
+        if let ReadState::Fetching(..) = this.state {
+            this.state = ReadState::Idle;
+        }
+        this.seek = Some(position);
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        let this = self.get_mut();
+        let position = match this.seek {
+            None => return Poll::Ready(Ok(this.position)),
+            Some(SeekFrom::Start(offset)) => Some(offset),
+            Some(SeekFrom::Current(offset)) => offset_position(this.position, offset),
+            Some(SeekFrom::End(offset)) => {
+                let len = futures::ready!(this.poll_len(cx))?;
+                offset_position(len, offset)
+            }
+        };
+        this.seek = None;
+        match position {
+            Some(position) => {
+                this.position = position;
+                Poll::Ready(Ok(position))
+            }
+            None => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            ))),
+        }
+    }
+}
+
+async fn fetch(
+    client: BlobClient,
+    start: u64,
+    end: u64,
+    etag: Option<Etag>,
+) -> azure_core::Result<Bytes> {
+    let mut data = BytesMut::with_capacity((end - start) as usize);
+    let mut builder = client.get().range(start..end).chunk_size(end - start);
+    if let Some(etag) = etag {
+        builder = builder.if_match(IfMatchCondition::Match(etag));
+    }
+    let mut stream = builder.into_stream();
+    while let Some(response) = stream.next().await {
+        data.extend_from_slice(&response?.data.collect().await?);
+    }
+    Ok(data.freeze())
+}
+
+/// Writes a block blob by staging blocks as they fill up, and committing them
+/// on shutdown.
+///
+/// Up to `max_concurrency` blocks are staged at once. Nothing is visible in
+/// the blob until `shutdown` succeeded: dropping the writer before leaves the
+/// blob as it was.
+pub struct BlobWriter {
+    client: BlobClient,
+    block_size: usize,
+    max_concurrency: usize,
+    buffer: BytesMut,
+    block_ids: BlockIdSequence,
+    blocks: u64,
+    staging: FuturesUnordered<BoxFuture<'static, azure_core::Result<()>>>,
+    commit: Option<BoxFuture<'static, azure_core::Result<()>>>,
+    committed: bool,
+}
+
+impl BlobWriter {
+    pub(crate) fn new(client: BlobClient) -> Self {
+        Self {
+            client,
+            block_size: DEFAULT_WRITE_BLOCK_SIZE,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            buffer: BytesMut::new(),
+            block_ids: BlockIdSequence::new(),
+            blocks: 0,
+            staging: FuturesUnordered::new(),
+            commit: None,
+            committed: false,
+        }
+    }
+
+    /// Set the size of the staged blocks.
+    pub fn block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// Set how many blocks are staged at once.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    fn stage_buffer(&mut self) {
+        let data = self.buffer.split().freeze();
+        let block_id = self.block_ids.block_id(self.blocks);
+        self.blocks += 1;
+        let put_block = self.client.put_block(block_id, data).into_future();
+        self.staging
+            .push(async move { put_block.await.map(|_| ()) }.boxed());
+    }
+
+    /// Polls the staged blocks until at most `limit` remain in flight.
+    fn poll_staging(&mut self, cx: &mut Context<'_>, limit: usize) -> Poll<io::Result<()>> {
+        while self.staging.len() > limit {
+            match futures::ready!(self.staging.poll_next_unpin(cx)) {
+                Some(result) => result.map_err(into_io_error)?,
+                None => break,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn check_not_committed(&self) -> io::Result<()> {
+        if self.committed || self.commit.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the blob writer was shut down",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl AsyncWrite for BlobWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.check_not_committed()?;
+        if this.buffer.len() >= this.block_size {
+            futures::ready!(this.poll_staging(cx, this.max_concurrency - 1))?;
+            this.stage_buffer();
+        }
+        // surface the failures of blocks staged meanwhile
+        while let Poll::Ready(Some(result)) = this.staging.poll_next_unpin(cx) {
+            result.map_err(into_io_error)?;
+        }
+
+        let n = buf.len().min(this.block_size - this.buffer.len());
+        this.buffer.extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    /// Stages the buffered data, even if it does not fill a block, and waits
+    /// for all the blocks to be staged.
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.committed || this.commit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        if !this.buffer.is_empty() {
+            futures::ready!(this.poll_staging(cx, this.max_concurrency - 1))?;
+            this.stage_buffer();
+        }
+        this.poll_staging(cx, 0)
+    }
+
+    /// Commits the staged blocks.
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.committed {
+            return Poll::Ready(Ok(()));
+        }
+        if self.commit.is_none() {
+            futures::ready!(self.as_mut().poll_flush(cx))?;
+            let this = self.as_mut().get_mut();
+            let block_list = (0..this.blocks)
+                .map(|index| BlobBlockType::new_uncommitted(this.block_ids.block_id(index)))
+                .collect();
+            let put_block_list = this.client.put_block_list(block_list).into_future();
+            this.commit = Some(async move { put_block_list.await.map(|_| ()) }.boxed());
+        }
+
+        let this = self.get_mut();
+        let result = futures::ready!(this.commit.as_mut().unwrap().poll_unpin(cx));
+        this.commit = None;
+        result.map_err(into_io_error)?;
+        this.committed = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn offset_position(base: u64, offset: i64) -> Option<u64> {
+    if offset >= 0 {
+        base.checked_add(offset as u64)
+    } else {
+        base.checked_sub(offset.unsigned_abs())
+    }
+}
+
+fn into_io_error(error: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+    use azure_storage::StorageCredentials;
+    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+    fn in_memory_client(transport: &InMemoryTransport) -> BlobClient {
+        ClientBuilder::new("account", StorageCredentials::anonymous())
+            .transport(transport.clone())
+            .blob_client("container", "blob")
+    }
+
+    #[tokio::test]
+    async fn test_read_and_seek_buffered() -> io::Result<()> {
+        let client = ClientBuilder::new("account", StorageCredentials::Anonymous)
+            .blob_client("container", "blob");
+        let mut reader = BlobReader::new(client);
+        // known length and buffered data, so no request is sent
+        reader.len = Some(10);
+        reader.buffer = Bytes::from_static(b"cdef");
+        reader.buffer_start = 2;
+
+        assert_eq!(reader.seek(SeekFrom::Start(3)).await?, 3);
+        let mut data = [0; 2];
+        reader.read_exact(&mut data).await?;
+        assert_eq!(&data, b"de");
+        assert_eq!(reader.position(), 5);
+
+        assert_eq!(reader.seek(SeekFrom::End(-10)).await?, 0);
+        assert!(reader.seek(SeekFrom::Current(-1)).await.is_err());
+        assert_eq!(reader.seek(SeekFrom::End(0)).await?, 10);
+        assert_eq!(reader.read(&mut data).await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_across_ranges() -> io::Result<()> {
+        let transport = InMemoryTransport::new();
+        let client = in_memory_client(&transport);
+        let content: Vec<u8> = (0..10).collect();
+        client
+            .put_block_blob(content.clone())
+            .await
+            .map_err(into_io_error)?;
+
+        let mut reader = client.reader().read_ahead(4);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+        assert_eq!(data, content);
+
+        reader.seek(SeekFrom::Start(3)).await?;
+        let mut data = [0; 6];
+        reader.read_exact(&mut data).await?;
+        assert_eq!(data, content[3..9]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_changed_blob() -> io::Result<()> {
+        let transport = InMemoryTransport::new();
+        let client = in_memory_client(&transport);
+        client
+            .put_block_blob(vec![1; 10])
+            .await
+            .map_err(into_io_error)?;
+
+        let mut reader = client.reader().read_ahead(4);
+        let mut data = [0; 4];
+        reader.read_exact(&mut data).await?;
+        assert_eq!(data, [1; 4]);
+
+        // the next ranges are downloaded on the condition of the first ETag
+        client
+            .put_block_blob(vec![2; 10])
+            .await
+            .map_err(into_io_error)?;
+        assert!(reader.read_exact(&mut data).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_in_blocks() -> io::Result<()> {
+        let transport = InMemoryTransport::new();
+        let client = in_memory_client(&transport);
+        let mut writer = client.writer().block_size(4).max_concurrency(1);
+
+        // a block is staged once the next write finds the buffer full
+        writer.write_all(b"0123").await?;
+        assert_eq!(transport.staged_block_count(), 0);
+        writer.write_all(b"456789").await?;
+        assert_eq!(transport.staged_block_count(), 2);
+        assert_eq!(transport.blob_content("container", "blob"), None);
+
+        // the rest is staged and the blocks committed on shutdown
+        writer.shutdown().await?;
+        assert_eq!(transport.staged_block_count(), 0);
+        assert_eq!(
+            transport.blob_content("container", "blob").unwrap(),
+            "0123456789"
+        );
+        assert!(writer.write_all(b"more").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_error() -> io::Result<()> {
+        let service = InMemoryTransport::answering(|_| {
+            test_util::error_response(StatusCode::Forbidden, "AuthorizationFailure")
+        });
+        let client = service.client_builder().blob_client("container", "blob");
+        let mut writer = client.writer().block_size(4);
+
+        // the failure of the block staged by a write surfaces from it
+        writer.write_all(b"0123").await?;
+        assert!(service.requests().is_empty());
+        let error = writer.write_all(b"4").await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(service.requests().len(), 1);
+        Ok(())
+    }
+}
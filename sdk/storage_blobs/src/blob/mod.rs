@@ -4,20 +4,32 @@ mod source_content_md5;
 pub use source_content_md5::*;
 mod blob_block_type;
 mod blob_block_with_size;
+mod blob_http_headers;
+mod blob_identifier;
+mod blob_url_parts;
 mod block_list;
 mod block_list_type;
 mod block_with_size_list;
+mod conditional;
+#[cfg(feature = "tokio-io")]
+mod io;
 mod page_range_list;
 
 pub use blob_block_type::BlobBlockType;
 pub use blob_block_with_size::BlobBlockWithSize;
-pub use block_list::BlockList;
+pub use blob_http_headers::BlobHttpHeaders;
+pub use blob_identifier::BlobIdentifier;
+pub use blob_url_parts::BlobUrlParts;
+pub use block_list::{BlockList, BlockListXmlStream, TooManyBlocks};
 pub use block_list_type::BlockListType;
 pub use block_with_size_list::BlockWithSizeList;
+pub use conditional::Conditional;
+#[cfg(feature = "tokio-io")]
+pub use io::{BlobReader, BlobWriter, DEFAULT_READ_AHEAD, DEFAULT_WRITE_BLOCK_SIZE};
 pub use lease_blob_options::{LeaseBlobOptions, LEASE_BLOB_OPTIONS_DEFAULT};
 pub use page_range_list::PageRangeList;
 
-use crate::options::{AccessTier, Snapshot, Tags, SNAPSHOT};
+use crate::options::{AccessTier, Snapshot, Tags, VersionId, SNAPSHOT, VERSION_ID};
 use azure_core::{
     content_type, date,
     headers::{self, Headers},
@@ -89,7 +101,7 @@ where
 pub struct Blob {
     pub name: String,
     pub snapshot: Option<Snapshot>,
-    pub version_id: Option<String>,
+    pub version_id: Option<VersionId>,
     pub is_current_version: Option<bool>,
     pub deleted: Option<bool>,
     pub properties: BlobProperties,
@@ -161,8 +173,12 @@ pub struct BlobProperties {
     pub encryption_scope: Option<String>,
     pub incremental_copy: Option<bool>,
     pub access_tier_inferred: Option<bool>,
+    /// When a soft deleted blob was deleted. Only set in listings with
+    /// `include_deleted`.
     #[serde(default, with = "azure_core::date::rfc1123::option")]
     pub deleted_time: Option<OffsetDateTime>,
+    /// The days a soft deleted blob is kept before it is permanently
+    /// deleted. Only set in listings with `include_deleted`.
     pub remaining_retention_days: Option<u32>,
     pub tag_count: Option<u32>,
     pub rehydrate_priority: Option<RehydratePriority>,
@@ -177,6 +193,45 @@ pub struct BlobProperties {
 }
 
 impl Blob {
+    /// Whether the blob is soft deleted, and can still be undeleted.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted.unwrap_or(false)
+    }
+
+    /// When a soft deleted blob will be permanently deleted, counting its
+    /// remaining retention days from `now`, the time of the listing.
+    ///
+    /// The service rounds the remaining retention to whole days, so the
+    /// blob can be removed up to a day earlier.
+    pub fn permanent_deletion_time(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        if !self.is_deleted() {
+            return None;
+        }
+        self.properties
+            .remaining_retention_days
+            .map(|days| now + time::Duration::days(days.into()))
+    }
+
+    /// The value of the index tag `key`. Tags are only listed with
+    /// `include_tags`, see
+    /// [`ListBlobsBuilder::include_tags`](crate::container::operations::ListBlobsBuilder::include_tags).
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags.as_ref()?.get(key)
+    }
+
+    /// How many index tags the blob has.
+    ///
+    /// Listings return the count of every blob, even without `include_tags`,
+    /// so blobs can be filtered on whether they are tagged without fetching
+    /// their tags. Without a count, e.g. with service versions before
+    /// 2019-12-12, the listed tags are counted.
+    pub fn tag_count(&self) -> u32 {
+        self.properties
+            .tag_count
+            .or_else(|| self.tags.as_ref().map(|tags| tags.len() as u32))
+            .unwrap_or(0)
+    }
+
     pub(crate) fn from_headers<BN: Into<String>>(
         blob_name: BN,
         h: &Headers,
@@ -218,6 +273,9 @@ impl Blob {
             .and_then(|cct| date::parse_rfc1123(cct).ok());
         let copy_status_description = h.get_optional_string(&headers::COPY_STATUS_DESCRIPTION);
         let server_encrypted = h.get_as(&headers::SERVER_ENCRYPTED)?;
+        let customer_provided_key_sha256 =
+            azure_storage::headers::encryption_key_sha256_from_headers_optional(h);
+        let encryption_scope = azure_storage::headers::encryption_scope_from_headers_optional(h);
 
         let mut metadata = HashMap::new();
         for (name, value) in h.iter() {
@@ -233,15 +291,17 @@ impl Blob {
         };
 
         let tags = h.get_optional_as(&headers::TAGS)?;
+        let tag_count = h.get_optional_as(&headers::TAG_COUNT)?;
 
         let snapshot = h.get_optional_as(&SNAPSHOT)?;
+        let version_id = h.get_optional_as(&VERSION_ID)?;
 
         Ok(Blob {
             name: blob_name.into(),
             snapshot,
             deleted: None,            //TODO
             is_current_version: None, //TODO
-            version_id: None,         //TODO
+            version_id,
             properties: BlobProperties {
                 creation_time,
                 last_modified,
@@ -269,14 +329,14 @@ impl Blob {
                 copy_status_description,
                 incremental_copy: None, // TODO: Not present or documentation bug?
                 server_encrypted,
-                customer_provided_key_sha256: None, // TODO
-                encryption_scope: None,             // TODO
-                access_tier_inferred: None,         // TODO: Not present
-                access_tier_change_time: None,      // TODO: Not present
-                deleted_time: None,                 // TODO
-                remaining_retention_days: None,     // TODO: Not present or documentation bug?
-                tag_count: None,                    // TODO
-                rehydrate_priority: None,           // TODO
+                customer_provided_key_sha256,
+                encryption_scope,
+                access_tier_inferred: None,     // TODO: Not present
+                access_tier_change_time: None,  // TODO: Not present
+                deleted_time: None,             // TODO
+                remaining_retention_days: None, // TODO: Not present or documentation bug?
+                tag_count,
+                rehydrate_priority: None, // TODO
                 expiry_time: None,
                 extra: HashMap::new(),
             },
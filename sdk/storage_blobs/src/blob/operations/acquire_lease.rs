@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -13,21 +14,17 @@ operation! {
 impl AcquireLeaseBuilder {
     pub fn into_future(mut self) -> AcquireLease {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            self.lease_duration.validate()?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("lease")
+                .header_value(LEASE_ACTION, "acquire")
+                .header(self.lease_duration)
+                .header(self.proposed_lease_id)
+                .header(self.lease_id);
 
-            url.query_pairs_mut().append_pair("comp", "lease");
-
-            let mut headers = Headers::new();
-            headers.insert(LEASE_ACTION, "acquire");
-            headers.add(self.lease_duration);
-            headers.add(self.proposed_lease_id);
-            headers.add(self.lease_id);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             AcquireLeaseResponse::from_headers(response.headers())
         })
@@ -35,9 +32,46 @@ impl AcquireLeaseBuilder {
 }
 
 azure_storage::response_from_headers!(AcquireLeaseResponse,
-    etag_from_headers => etag: String,
+    etag_from_headers => etag: Etag,
     last_modified_from_headers => last_modified: OffsetDateTime,
     lease_id_from_headers => lease_id: LeaseId,
     request_id_from_headers => request_id: RequestId,
     date_from_headers => date: OffsetDateTime
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+
+    #[tokio::test]
+    async fn test_acquire_lease_with_proposed_id() -> azure_core::Result<()> {
+        // grants the proposed lease
+        let service = InMemoryTransport::answering(|request| {
+            let mut headers = test_util::version_headers();
+            headers.insert(
+                LEASE_ID,
+                request.headers().get_str(&PROPOSED_LEASE_ID)?.to_owned(),
+            );
+            test_util::response(StatusCode::Created, headers)
+        });
+        let blob_client = service.client_builder().blob_client("container", "blob");
+
+        let lease_id = LeaseId::new(uuid::Uuid::from_u128(42));
+        let response = blob_client
+            .acquire_lease(LeaseDuration::Infinite)
+            .proposed_lease_id(lease_id)
+            .await?;
+        assert_eq!(response.lease_id, lease_id);
+
+        let request = service.take_requests().pop().unwrap();
+        assert_eq!(request.headers().get_str(&LEASE_ACTION)?, "acquire");
+        assert_eq!(request.headers().get_str(&LEASE_DURATION)?, "-1");
+        assert_eq!(
+            request.headers().get_str(&PROPOSED_LEASE_ID)?,
+            "00000000-0000-0000-0000-00000000002a"
+        );
+        Ok(())
+    }
+}
@@ -1,5 +1,13 @@
-use crate::{blob::operations::put_block::PutBlockResponse, prelude::*};
-use azure_core::{headers::*, prelude::*, Body};
+use crate::prelude::*;
+use azure_core::{headers::*, prelude::*, Body, Method, RequestId};
+use azure_storage::{
+    headers::{
+        consistency_from_headers, encryption_key_sha256_from_headers_optional,
+        encryption_scope_from_headers_optional,
+    },
+    ConsistencyCRC64, ConsistencyMD5, StorageClient,
+};
+use time::OffsetDateTime;
 
 operation! {
     AppendBlock,
@@ -17,30 +25,53 @@ operation! {
 impl AppendBlockBuilder {
     pub fn into_future(mut self) -> AppendBlock {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("appendblock")
+                .header(self.hash)
+                .header(self.condition_max_size)
+                .header(self.condition_append_position)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tag)
+                .header(self.lease_id)
+                .body(self.body);
 
-            url.query_pairs_mut().append_pair("comp", "appendblock");
-
-            let mut headers = Headers::new();
-            headers.add(self.hash);
-            headers.add(self.condition_max_size);
-            headers.add(self.condition_append_position);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.lease_id);
+            let response = self.client.execute(&mut self.context, request).await?;
+            AppendBlockResponse::from_headers(response.headers())
+        })
+    }
+}
 
-            let mut request = self.client.finalize_request(
-                url,
-                azure_core::Method::Put,
-                headers,
-                Some(self.body),
-            )?;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppendBlockResponse {
+    pub content_md5: Option<ConsistencyMD5>,
+    pub content_crc64: Option<ConsistencyCRC64>,
+    pub request_id: RequestId,
+    pub date: OffsetDateTime,
+    pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
+    pub encryption_scope: Option<String>,
+}
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+impl AppendBlockResponse {
+    pub(crate) fn from_headers(headers: &Headers) -> azure_core::Result<AppendBlockResponse> {
+        let (content_md5, content_crc64) = consistency_from_headers(headers)?;
+        let request_id = request_id_from_headers(headers)?;
+        let date = date_from_headers(headers)?;
+        let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers);
+        let encryption_scope = encryption_scope_from_headers_optional(headers);
 
-            PutBlockResponse::from_headers(response.headers())
+        Ok(AppendBlockResponse {
+            content_md5,
+            content_crc64,
+            request_id,
+            date,
+            request_server_encrypted,
+            encryption_key_sha256,
+            encryption_scope,
         })
     }
 }
-
-type AppendBlockResponse = PutBlockResponse;
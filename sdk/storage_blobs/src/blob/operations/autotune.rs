@@ -0,0 +1,140 @@
+use crate::options::Autotuner;
+use azure_core::{error::ErrorKind, prelude::Range, StatusCode};
+use futures::{stream::FuturesUnordered, Future, StreamExt};
+use std::{collections::VecDeque, time::Instant};
+
+/// How often a chunk throttled by the service is sent again.
+const MAX_THROTTLE_RETRIES: usize = 5;
+
+/// Transfers `len` bytes in chunks sized and run concurrently as `tuner`
+/// decides.
+///
+/// `start` is called in order of the chunks with their index and range, and
+/// again for chunks which were throttled. `complete` is called with the
+/// result of each chunk as it completes. Returns the number of chunks.
+pub(crate) async fn transfer<S, F, T, C>(
+    mut tuner: Autotuner,
+    len: u64,
+    mut start: S,
+    mut complete: C,
+) -> azure_core::Result<u64>
+where
+    S: FnMut(u64, Range) -> azure_core::Result<F>,
+    F: Future<Output = azure_core::Result<T>>,
+    C: FnMut(u64, Range, T) -> azure_core::Result<()>,
+{
+    let mut in_flight = FuturesUnordered::new();
+    let mut throttled = VecDeque::new();
+    let mut offset = 0;
+    let mut chunks = 0;
+
+    loop {
+        while in_flight.len() < tuner.concurrency() {
+            let (index, range, attempt) = match throttled.pop_front() {
+                Some(chunk) => chunk,
+                None if offset < len => {
+                    let range = Range::new(offset, len.min(offset + tuner.chunk_size()));
+                    offset = range.end;
+                    chunks += 1;
+                    (chunks - 1, range, 0)
+                }
+                None => break,
+            };
+            let future = start(index, range)?;
+            let started = Instant::now();
+            in_flight.push(async move { (index, range, attempt, started, future.await) });
+        }
+
+        let (index, range, attempt, started, result) = match in_flight.next().await {
+            Some(completed) => completed,
+            None => return Ok(chunks),
+        };
+        let now = Instant::now();
+        match result {
+            Ok(value) => {
+                tuner.record_success(range.len(), now.saturating_duration_since(started), now);
+                complete(index, range, value)?;
+            }
+            Err(error) if is_throttled(error.kind()) && attempt < MAX_THROTTLE_RETRIES => {
+                tuner.record_throttle(now);
+                throttled.push_back((index, range, attempt + 1));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+fn is_throttled(kind: &ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::HttpResponse {
+            status: StatusCode::ServiceUnavailable | StatusCode::TooManyRequests,
+            ..
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::AutotuneOptions;
+    use futures::future::ready;
+
+    #[tokio::test]
+    async fn test_transfer_retries_throttled_chunks() -> azure_core::Result<()> {
+        let tuner = Autotuner::new(AutotuneOptions::default(), 4, 4, 1, 4, Instant::now());
+        let mut started = Vec::new();
+        let mut completed = Vec::new();
+        let chunks = transfer(
+            tuner,
+            10,
+            |index, range| {
+                started.push(index);
+                // the second chunk is throttled once
+                let throttled = index == 1 && started.iter().filter(|i| **i == 1).count() == 1;
+                Ok(ready(if throttled {
+                    Err(ErrorKind::http_response(
+                        StatusCode::ServiceUnavailable,
+                        Some("ServerBusy".to_owned()),
+                    )
+                    .into_error())
+                } else {
+                    Ok(range.len())
+                }))
+            },
+            |index, range, len| {
+                assert_eq!(range.len(), len);
+                completed.push((index, range));
+                Ok(())
+            },
+        )
+        .await?;
+        assert_eq!(chunks, 3);
+        assert_eq!(started.iter().filter(|i| **i == 1).count(), 2);
+        completed.sort_by_key(|(index, _)| *index);
+        assert_eq!(
+            completed,
+            vec![
+                (0, Range::new(0, 4)),
+                (1, Range::new(4, 8)),
+                (2, Range::new(8, 10))
+            ]
+        );
+
+        let tuner = Autotuner::new(AutotuneOptions::default(), 4, 4, 1, 4, Instant::now());
+        let error = transfer(
+            tuner,
+            10,
+            |_, _| {
+                Ok(ready(Err::<(), _>(
+                    ErrorKind::http_response(StatusCode::ServiceUnavailable, None).into_error(),
+                )))
+            },
+            |_, _, _| Ok(()),
+        )
+        .await
+        .unwrap_err();
+        assert!(is_throttled(error.kind()), "gives up after the retries");
+        Ok(())
+    }
+}
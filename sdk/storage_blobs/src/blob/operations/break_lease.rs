@@ -1,5 +1,7 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
+use std::time::Duration;
 use time::OffsetDateTime;
 
 operation! {
@@ -12,20 +14,15 @@ operation! {
 impl BreakLeaseBuilder {
     pub fn into_future(mut self) -> BreakLease {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("lease")
+                .header_value(LEASE_ACTION, "break")
+                .header(self.lease_break_period)
+                .header(self.lease_id);
 
-            url.query_pairs_mut().append_pair("comp", "lease");
-
-            let mut headers = Headers::new();
-            headers.insert(LEASE_ACTION, "break");
-            headers.add(self.lease_break_period);
-            headers.add(self.lease_id);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             BreakLeaseResponse::from_headers(response.headers())
         })
@@ -33,9 +30,52 @@ impl BreakLeaseBuilder {
 }
 
 azure_storage::response_from_headers!(BreakLeaseResponse,
-    etag_from_headers => etag: String,
+    etag_from_headers => etag: Etag,
     last_modified_from_headers => last_modified: OffsetDateTime,
-    lease_time_from_headers => lease_time: u8,
+    lease_time_from_headers => lease_time: Duration,
     request_id_from_headers => request_id: RequestId,
     date_from_headers => date: OffsetDateTime
 );
+
+impl BreakLeaseResponse {
+    /// When the lease is broken and another client can acquire one, as
+    /// reported by the service's clock.
+    pub fn broken_at(&self) -> OffsetDateTime {
+        self.date + self.lease_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+
+    #[tokio::test]
+    async fn test_break_lease() -> azure_core::Result<()> {
+        // breaks the lease in 10 seconds
+        let service = InMemoryTransport::answering(|_| {
+            let mut headers = test_util::service_headers();
+            headers.insert("last-modified", "Thu, 01 Jul 2021 10:44:00 GMT");
+            headers.insert("etag", test_util::ETAG);
+            headers.insert("x-ms-lease-time", "10");
+            test_util::response(StatusCode::Accepted, headers)
+        });
+        let blob_client = service.client_builder().blob_client("container", "blob");
+
+        let response = blob_client
+            .break_lease()
+            .lease_break_period(Duration::from_secs(20))
+            .await?;
+        assert_eq!(response.lease_time, Duration::from_secs(10));
+        assert_eq!(
+            response.broken_at(),
+            time::macros::datetime!(2021-07-01 10:45:10 UTC)
+        );
+
+        let request = service.take_requests().pop().unwrap();
+        assert_eq!(request.headers().get_str(&LEASE_ACTION)?, "break");
+        assert_eq!(request.headers().get_str(&LEASE_BREAK_PERIOD)?, "20");
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -11,20 +12,15 @@ operation! {
 impl ChangeLeaseBuilder {
     pub fn into_future(mut self) -> ChangeLease {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("lease")
+                .header_value(LEASE_ACTION, "change")
+                .header(self.client.lease_id())
+                .header(self.proposed_lease_id);
 
-            url.query_pairs_mut().append_pair("comp", "lease");
-
-            let mut headers = Headers::new();
-            headers.insert(LEASE_ACTION, "change");
-            headers.add(self.client.lease_id());
-            headers.add(self.proposed_lease_id);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             ChangeLeaseResponse::from_headers(response.headers())
         })
@@ -32,7 +28,7 @@ impl ChangeLeaseBuilder {
 }
 
 azure_storage::response_from_headers!(ChangeLeaseResponse ,
-    etag_from_headers => etag: String,
+    etag_from_headers => etag: Etag,
     last_modified_from_headers => last_modified: OffsetDateTime,
     lease_id_from_headers => lease_id: LeaseId,
     request_id_from_headers => request_id: RequestId,
@@ -2,8 +2,9 @@ use crate::prelude::*;
 use azure_core::{
     headers::{BLOB_TYPE, PAGE_WRITE, *},
     prelude::*,
-    RequestId,
+    Etag, Method, RequestId,
 };
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -19,32 +20,26 @@ operation! {
 impl ClearPageBuilder {
     pub fn into_future(mut self) -> ClearPage {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "page");
-
-            let mut headers = Headers::new();
-            headers.insert(PAGE_WRITE, "clear");
-            headers.insert(BLOB_TYPE, "PageBlob");
-            headers.add(self.ba512_range);
-            headers.add(self.if_sequence_number);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.lease_id);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
-
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("page")
+                .header_value(PAGE_WRITE, "clear")
+                .header_value(BLOB_TYPE, "PageBlob")
+                .header(self.ba512_range)
+                .header(self.if_sequence_number)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.lease_id);
+
+            let response = self.client.execute(&mut self.context, request).await?;
             ClearPageResponse::from_headers(response.headers())
         })
     }
 }
 
 azure_storage::response_from_headers!(ClearPageResponse,
-    etag_from_headers => etag: String,
+    etag_from_headers => etag: Etag,
     last_modified_from_headers => last_modified: OffsetDateTime,
     sequence_number_from_headers => sequence_number: u64,
     request_id_from_headers => request_id: RequestId,
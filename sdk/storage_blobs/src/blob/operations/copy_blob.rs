@@ -2,12 +2,15 @@ use crate::{
     blob::{copy_status_from_headers, CopyStatus},
     prelude::*,
 };
-use azure_core::{headers::*, prelude::*, RequestId};
-use azure_storage::{copy_id_from_headers, CopyId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::{copy_id_from_headers, CopyId, StorageClient};
 use std::convert::{TryFrom, TryInto};
 use time::OffsetDateTime;
 use url::Url;
 
+/// Seals the destination append blob, making it read-only.
+const SEAL_BLOB: HeaderName = HeaderName::from_static("x-ms-seal-blob");
+
 operation! {
     CopyBlob,
     client: BlobClient,
@@ -18,6 +21,10 @@ operation! {
     ?if_match: IfMatchCondition,
     ?if_tags: IfTags,
     ?access_tier: AccessTier,
+    ?tags: Tags,
+    ?seal_blob: bool,
+    ?immutability_policy: ImmutabilityPolicy,
+    ?legal_hold: bool,
     ?lease_id: LeaseId,
     ?if_source_since: IfSourceModifiedSinceCondition,
     ?if_source_match: IfSourceMatchCondition,
@@ -28,34 +35,42 @@ operation! {
 impl CopyBlobBuilder {
     pub fn into_future(mut self) -> CopyBlob {
         Box::pin(async move {
-            let url = self.client.url()?;
-
-            let mut headers = Headers::new();
-            headers.insert(COPY_SOURCE, self.source_url.as_str().to_owned());
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .header_value(COPY_SOURCE, self.source_url.as_str().to_owned());
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
-            headers.add(self.if_sequence_number);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.access_tier);
-            headers.add(self.lease_id);
-            headers.add(self.if_source_since);
-            headers.add(self.if_source_match);
-            headers.add(self.source_lease_id);
-            headers.add(
-                self.rehydrate_priority
-                    .unwrap_or(RehydratePriority::Standard),
-            );
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            request = request
+                .header(self.if_sequence_number)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags)
+                .header(self.access_tier)
+                .header(self.tags);
+            if let Some(seal_blob) = self.seal_blob {
+                request = request.header_value(SEAL_BLOB, seal_blob.to_string());
+            }
+            if let Some(immutability_policy) = &self.immutability_policy {
+                request = request.header(immutability_policy.clone());
+            }
+            if let Some(legal_hold) = self.legal_hold {
+                request = request.header_value(LEGAL_HOLD, legal_hold.to_string());
+            }
+            let request = request
+                .header(self.lease_id)
+                .header(self.if_source_since)
+                .header(self.if_source_match)
+                .header(self.source_lease_id)
+                .header(
+                    self.rehydrate_priority
+                        .unwrap_or(RehydratePriority::Standard),
+                );
 
+            let response = self.client.execute(&mut self.context, request).await?;
             (response.headers()).try_into()
         })
     }
@@ -63,7 +78,7 @@ impl CopyBlobBuilder {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CopyBlobResponse {
-    pub etag: String,
+    pub etag: Etag,
     pub last_modified: OffsetDateTime,
     pub request_id: RequestId,
     pub version: String,
@@ -2,9 +2,10 @@ use crate::{
     blob::{copy_status_from_headers, CopyStatus, SourceContentMD5},
     prelude::*,
 };
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
 use azure_storage::{
     copy_id_from_headers, headers::content_md5_from_headers_optional, ConsistencyMD5, CopyId,
+    StorageClient,
 };
 use std::convert::{TryFrom, TryInto};
 use time::OffsetDateTime;
@@ -16,8 +17,13 @@ operation! {
     source_url: Url,
     ?is_synchronous: bool,
     ?metadata: Metadata,
+    ?access_tier: AccessTier,
+    ?tags: Tags,
+    ?immutability_policy: ImmutabilityPolicy,
+    ?legal_hold: bool,
     ?if_modified_since: IfModifiedSinceCondition,
     ?if_match: IfMatchCondition,
+    ?if_tags: IfTags,
     ?if_source_since: IfSourceModifiedSinceCondition,
     ?if_source_match: IfSourceMatchCondition,
     ?lease_id: LeaseId,
@@ -27,32 +33,36 @@ operation! {
 impl CopyBlobFromUrlBuilder {
     pub fn into_future(mut self) -> CopyBlobFromUrl {
         Box::pin(async move {
-            let url = self.client.url()?;
-
-            let mut headers = Headers::new();
-            headers.insert(COPY_SOURCE, self.source_url.to_string());
-            headers.insert(
-                REQUIRES_SYNC,
-                format!("{}", self.is_synchronous.unwrap_or(false)),
-            );
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .header_value(COPY_SOURCE, self.source_url.to_string())
+                .header_value(
+                    REQUIRES_SYNC,
+                    format!("{}", self.is_synchronous.unwrap_or(false)),
+                );
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.lease_id);
-            headers.add(self.if_source_since);
-            headers.add(self.if_source_match);
-            headers.add(self.source_content_md5);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            request = request.header(self.access_tier).header(self.tags);
+            if let Some(immutability_policy) = &self.immutability_policy {
+                request = request.header(immutability_policy.clone());
+            }
+            if let Some(legal_hold) = self.legal_hold {
+                request = request.header_value(LEGAL_HOLD, legal_hold.to_string());
+            }
+            let request = request
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags)
+                .header(self.lease_id)
+                .header(self.if_source_since)
+                .header(self.if_source_match)
+                .header(self.source_content_md5);
 
+            let response = self.client.execute(&mut self.context, request).await?;
             (response.headers()).try_into()
         })
     }
@@ -62,7 +72,7 @@ impl CopyBlobFromUrlBuilder {
 pub struct CopyBlobFromUrlResponse {
     pub content_md5: Option<ConsistencyMD5>,
     pub last_modified: OffsetDateTime,
-    pub etag: String,
+    pub etag: Etag,
     pub server: String,
     pub request_id: RequestId,
     pub version: String,
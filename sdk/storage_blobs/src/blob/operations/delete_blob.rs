@@ -1,10 +1,24 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{
+    error::{Error, ErrorKind},
+    headers::*,
+    prelude::*,
+    Method, RequestId,
+};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
+    /// Deletes the blob, or one of its snapshots or versions.
+    ///
+    /// With `permanent`, a snapshot or version which was soft deleted is
+    /// erased at once instead of when its retention ends. This needs
+    /// permanent delete to be enabled on the account.
     DeleteBlob,
     client: BlobClient,
+    ?snapshot: Snapshot,
+    ?version_id: VersionId,
+    ?permanent: bool,
     ?if_modified_since: IfModifiedSinceCondition,
     ?if_match: IfMatchCondition,
     ?if_tags: IfTags,
@@ -15,23 +29,41 @@ operation! {
 impl DeleteBlobBuilder {
     pub fn into_future(mut self) -> DeleteBlob {
         Box::pin(async move {
-            let url = self.client.url()?;
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(
-                self.delete_snapshots_method
-                    .unwrap_or(DeleteSnapshotsMethod::Include),
-            );
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.if_tags);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Delete, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let is_point_in_time = self.snapshot.is_some() || self.version_id.is_some();
+            let permanent = self.permanent.unwrap_or(false);
+            if permanent && !is_point_in_time {
+                return Err(Error::message(
+                    ErrorKind::Other,
+                    "only a snapshot or a version of a blob can be deleted permanently",
+                ));
+            }
+            // the snapshots of a blob go with it, but a snapshot or version
+            // has none
+            let delete_snapshots_method = if is_point_in_time {
+                None
+            } else {
+                Some(
+                    self.delete_snapshots_method
+                        .unwrap_or(DeleteSnapshotsMethod::Include),
+                )
+            };
+
+            let mut request = self
+                .client
+                .request(Method::Delete)?
+                .query(self.snapshot)
+                .query(self.version_id);
+            if permanent {
+                request = request.query_pair("deletetype", "permanent");
+            }
+            let request = request
+                .header(self.lease_id)
+                .header(delete_snapshots_method)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags);
+
+            let response = self.client.execute(&mut self.context, request).await?;
             DeleteBlobResponse::from_headers(response.headers())
         })
     }
@@ -50,3 +82,41 @@ azure_storage::response_from_headers!(DeleteBlobResponse ,
     request_id_from_headers => request_id: RequestId,
     date_from_headers => date: OffsetDateTime
 );
+
+#[cfg(all(test, not(feature = "azurite_workaround")))]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+
+    #[tokio::test]
+    async fn test_permanent_delete() -> azure_core::Result<()> {
+        let service = InMemoryTransport::answering(|_| {
+            let mut headers = test_util::service_headers();
+            headers.insert("x-ms-delete-type-permanent", "true");
+            test_util::response(StatusCode::Accepted, headers)
+        });
+        let blob_client = service.client_builder().blob_client("container", "blob");
+
+        assert!(blob_client.delete().permanent(true).await.is_err());
+        assert!(service.requests().is_empty());
+
+        let response = blob_client
+            .delete()
+            .version_id("2019-09-29T12:00:00.0000000Z".parse::<VersionId>()?)
+            .permanent(true)
+            .await?;
+        assert!(response.delete_type_permanent);
+
+        let request = service.take_requests().pop().unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("versionid=2019-09-29T12%3A00%3A00.0000000Z&deletetype=permanent")
+        );
+        assert!(request
+            .headers()
+            .get_optional_str(&DELETE_SNAPSHOTS)
+            .is_none());
+        Ok(())
+    }
+}
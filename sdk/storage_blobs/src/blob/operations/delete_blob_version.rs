@@ -1,5 +1,6 @@
 use crate::{blob::operations::DeleteBlobResponse, prelude::*};
-use azure_core::{headers::Headers, prelude::*};
+use azure_core::{prelude::*, Method};
+use azure_storage::StorageClient;
 
 operation! {
     DeleteBlobVersion,
@@ -12,22 +13,13 @@ operation! {
 impl DeleteBlobVersionBuilder {
     pub fn into_future(mut self) -> DeleteBlobVersion {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            self.version_id.append_to_url_query(&mut url);
+            let mut request = self.client.request(Method::Delete)?.query(self.version_id);
             if self.permanent.unwrap_or_default() {
-                url.query_pairs_mut().append_pair("deletetype", "permanent");
+                request = request.query_pair("deletetype", "permanent");
             }
+            let request = request.header(self.lease_id);
 
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Delete, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
-
+            let response = self.client.execute(&mut self.context, request).await?;
             DeleteBlobResponse::from_headers(response.headers())
         })
     }
@@ -0,0 +1,364 @@
+use super::{autotune, file_io};
+use crate::{blob::Blob, options::Autotuner, prelude::*, transform::ChunkTransforms};
+use azure_core::{
+    error::{Error, ErrorKind, ResultExt},
+    prelude::*,
+};
+use bytes::Bytes;
+use futures::{stream, StreamExt, TryStreamExt};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+/// Size of the ranges requested in parallel while downloading a file.
+pub const DEFAULT_DOWNLOAD_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// How much effort [`BlobClient::download_file`] spends making sure the
+/// downloaded file survives a crash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileDurability {
+    /// Write directly to the target file and leave flushing to the OS.
+    #[default]
+    None,
+    /// Write directly to the target file and `fsync` it before returning.
+    Sync,
+    /// Write to a temporary file next to the target, `fsync` it and rename it
+    /// over the target, so the target is never left partially written.
+    SyncAndRename,
+}
+
+operation! {
+    DownloadFile,
+    client: BlobClient,
+    path: PathBuf,
+    ?chunk_size: u64,
+    ?max_concurrency: usize,
+    ?autotune: AutotuneOptions,
+    ?durability: FileDurability,
+    ?verify_content_md5: bool,
+    ?transforms: ChunkTransforms,
+    ?blob_versioning: BlobVersioning,
+    ?lease_id: LeaseId
+}
+
+impl DownloadFileBuilder {
+    pub fn into_future(self) -> DownloadFile {
+        Box::pin(async move {
+            let mut properties = self.client.get_properties().context(self.context.clone());
+            if let Some(blob_versioning) = self.blob_versioning.clone() {
+                properties = properties.blob_versioning(blob_versioning);
+            }
+            if let Some(lease_id) = self.lease_id {
+                properties = properties.lease_id(lease_id);
+            }
+            let blob = properties.await?.blob;
+
+            let durability = self.durability.unwrap_or_default();
+            let target = match durability {
+                FileDurability::SyncAndRename => temporary_path(&self.path)?,
+                FileDurability::None | FileDurability::Sync => self.path.clone(),
+            };
+
+            let result = self.download_to(&target, &blob, durability).await;
+            if result.is_err() && target != self.path {
+                let _ = file_io::blocking(|| fs::remove_file(&target));
+            }
+            result?;
+
+            if target != self.path {
+                file_io::blocking(|| {
+                    fs::rename(&target, &self.path).with_context(ErrorKind::Io, || {
+                        format!(
+                            "failed to rename {} to {}",
+                            target.display(),
+                            self.path.display()
+                        )
+                    })?;
+                    sync_parent_dir(&self.path)
+                })?;
+            }
+
+            Ok(DownloadFileResponse {
+                path: self.path,
+                blob,
+            })
+        })
+    }
+
+    async fn download_to(
+        &self,
+        path: &Path,
+        blob: &Blob,
+        durability: FileDurability,
+    ) -> azure_core::Result<()> {
+        let len = blob.properties.content_length;
+        let transforms = self
+            .transforms
+            .as_ref()
+            .filter(|transforms| !transforms.is_empty());
+        let mut file = file_io::blocking(|| {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)
+                .with_context(ErrorKind::Io, || {
+                    format!("failed to create {}", path.display())
+                })?;
+            // sized up front so chunks can be written at their offsets in any
+            // order, unless transforms may change their length
+            if transforms.is_none() {
+                file.set_len(len).with_context(ErrorKind::Io, || {
+                    format!("failed to allocate {}", path.display())
+                })?;
+            }
+            Ok::<_, Error>(file)
+        })?;
+
+        let chunk_size = self.chunk_size.unwrap_or(DEFAULT_DOWNLOAD_CHUNK_SIZE);
+        let max_concurrency = self
+            .max_concurrency
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+            .max(1);
+        match (transforms, self.autotune.clone()) {
+            // the output of the transforms may not have the length of the
+            // range, so it is written after that of the previous ranges, in
+            // order, rather than at the offset of the range
+            (Some(transforms), _) => {
+                let ranges = chunk_ranges(len, chunk_size)?;
+                let mut chunks = stream::iter(ranges)
+                    .map(|range| self.download_range(range, blob))
+                    .buffered(max_concurrency);
+                let mut written = 0;
+                while let Some((range, data)) = chunks.try_next().await? {
+                    let data = transforms.download(range.start, data)?;
+                    write_at(&mut file, path, written, &data)?;
+                    written += data.len() as u64;
+                }
+            }
+            (None, Some(autotune)) => {
+                let tuner = Autotuner::new(
+                    autotune,
+                    max_concurrency,
+                    chunk_size,
+                    1,
+                    u64::MAX,
+                    Instant::now(),
+                );
+                autotune::transfer(
+                    tuner,
+                    len,
+                    |_, range| Ok(self.download_range(range, blob)),
+                    |_, _, (range, data)| write_at(&mut file, path, range.start, &data),
+                )
+                .await?;
+            }
+            (None, None) => {
+                let ranges = chunk_ranges(len, chunk_size)?;
+                let mut chunks = stream::iter(ranges)
+                    .map(|range| self.download_range(range, blob))
+                    .buffer_unordered(max_concurrency);
+                while let Some((range, data)) = chunks.try_next().await? {
+                    write_at(&mut file, path, range.start, &data)?;
+                }
+            }
+        }
+
+        // the length and Content-MD5 are those of the stored content, which
+        // transforms change on the way to the file
+        if transforms.is_none() {
+            let written = file_io::blocking(|| file.metadata())
+                .with_context(ErrorKind::Io, || {
+                    format!("failed to read {}", path.display())
+                })?
+                .len();
+            if written != len {
+                return Err(Error::with_message(ErrorKind::DataConversion, || {
+                    format!("downloaded {written} bytes but the blob has {len} bytes")
+                }));
+            }
+            if self.verify_content_md5.unwrap_or(true) {
+                file_io::blocking(|| verify_content_md5(&mut file, path, blob))?;
+            }
+        }
+
+        if durability != FileDurability::None {
+            file_io::blocking(|| file.sync_all()).with_context(ErrorKind::Io, || {
+                format!("failed to sync {}", path.display())
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn download_range(
+        &self,
+        range: Range,
+        blob: &Blob,
+    ) -> azure_core::Result<(Range, Bytes)> {
+        // pinning the etag makes the download fail rather than mix two
+        // versions of a blob that changes while it is being downloaded
+        let mut builder = self
+            .client
+            .get()
+            .range(range)
+            .chunk_size(range.len())
+            .if_match(IfMatchCondition::Match(blob.properties.etag.clone()))
+            .context(self.context.clone());
+        if let Some(blob_versioning) = self.blob_versioning.clone() {
+            builder = builder.blob_versioning(blob_versioning);
+        }
+        if let Some(lease_id) = self.lease_id {
+            builder = builder.lease_id(lease_id);
+        }
+
+        let response =
+            builder.into_stream().next().await.ok_or_else(|| {
+                Error::message(ErrorKind::Other, "get blob returned no response")
+            })??;
+        let data = response.data.collect().await?;
+        if data.len() as u64 != range.len() {
+            return Err(Error::with_message(ErrorKind::DataConversion, || {
+                format!(
+                    "expected {} bytes for range {}-{} but received {}",
+                    range.len(),
+                    range.start,
+                    range.end,
+                    data.len()
+                )
+            }));
+        }
+        Ok((range, data))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadFileResponse {
+    pub path: PathBuf,
+    pub blob: Blob,
+}
+
+fn chunk_ranges(len: u64, chunk_size: u64) -> azure_core::Result<Vec<Range>> {
+    if chunk_size == 0 {
+        return Err(Error::message(
+            ErrorKind::Other,
+            "chunk size must be greater than zero",
+        ));
+    }
+    Ok((0..len)
+        .step_by(chunk_size as usize)
+        .map(|start| Range::new(start, len.min(start + chunk_size)))
+        .collect())
+}
+
+fn write_at(file: &mut File, path: &Path, offset: u64, data: &[u8]) -> azure_core::Result<()> {
+    file_io::blocking(|| {
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.write_all(data))
+    })
+    .with_context(ErrorKind::Io, || {
+        format!("failed to write {}", path.display())
+    })
+}
+
+fn temporary_path(path: &Path) -> azure_core::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        Error::with_message(ErrorKind::Other, || {
+            format!("{} is not a file path", path.display())
+        })
+    })?;
+    let mut temporary = file_name.to_os_string();
+    temporary.push(format!(".{}.partial", uuid::Uuid::new_v4()));
+    Ok(path.with_file_name(temporary))
+}
+
+#[cfg(feature = "md5")]
+fn verify_content_md5(file: &mut File, path: &Path, blob: &Blob) -> azure_core::Result<()> {
+    if let Some(expected) = &blob.properties.content_md5 {
+        let actual = file_md5(file)
+            .with_context(ErrorKind::Io, || {
+                format!("failed to read {}", path.display())
+            })?
+            .finalize()?;
+        if *expected != actual {
+            return Err(Error::message(
+                ErrorKind::DataConversion,
+                "the MD5 of the downloaded file does not match the blob's Content-MD5",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Without the `md5` feature downloads are only checked against the blob's
+/// length.
+#[cfg(not(feature = "md5"))]
+fn verify_content_md5(_file: &mut File, _path: &Path, _blob: &Blob) -> azure_core::Result<()> {
+    Ok(())
+}
+
+#[cfg(feature = "md5")]
+fn file_md5(file: &mut File) -> std::io::Result<azure_storage::crypto::Md5> {
+    use std::io::Read;
+
+    let mut md5 = azure_storage::crypto::Md5::new();
+    file.seek(SeekFrom::Start(0))?;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        md5.update(&buffer[..read]);
+    }
+    Ok(md5)
+}
+
+/// Makes a rename durable by syncing the directory entry, where supported.
+fn sync_parent_dir(path: &Path) -> azure_core::Result<()> {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        File::open(parent)
+            .and_then(|dir| dir.sync_all())
+            .with_context(ErrorKind::Io, || {
+                format!("failed to sync {}", parent.display())
+            })?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_ranges() {
+        assert!(chunk_ranges(0, 4).unwrap().is_empty());
+        assert_eq!(
+            chunk_ranges(10, 4).unwrap(),
+            vec![Range::new(0, 4), Range::new(4, 8), Range::new(8, 10)]
+        );
+        assert_eq!(chunk_ranges(8, 4).unwrap().len(), 2);
+        assert!(chunk_ranges(8, 0).is_err());
+    }
+
+    #[test]
+    fn test_temporary_path() {
+        let path = temporary_path(Path::new("dir/blob.bin")).unwrap();
+        assert_eq!(path.parent(), Some(Path::new("dir")));
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("blob.bin."));
+        assert!(name.ends_with(".partial"));
+    }
+}
@@ -0,0 +1,311 @@
+use super::GetBlobResponse;
+use azure_core::{
+    error::{Error, ErrorKind},
+    prelude::*,
+    Etag, Pageable, ResponseBody,
+};
+use azure_storage::crypto::Crc64;
+use bytes::Bytes;
+use futures::{stream::BoxStream, StreamExt};
+use std::sync::{Arc, Mutex};
+
+/// The stream of [`GetBlobBuilder::into_tracked_bytes_stream`](super::GetBlobBuilder::into_tracked_bytes_stream), downloading
+/// `range` with the requests of `pages`.
+pub(super) fn tracked_bytes_stream(
+    pages: Pageable<GetBlobResponse, Error>,
+    range: Option<Range>,
+) -> (
+    BoxStream<'static, azure_core::Result<Bytes>>,
+    DownloadProgress,
+) {
+    let progress = DownloadProgress::new(range);
+    let state = TrackedDownload {
+        pages,
+        chunk: None,
+        progress: progress.clone(),
+    };
+    let stream = futures::stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        loop {
+            if let Some(chunk) = &mut state.chunk {
+                match chunk.body.next().await {
+                    Some(Ok(bytes)) => {
+                        chunk.crc64.update(&bytes);
+                        chunk.received += bytes.len() as u64;
+                        state.progress.lock().received += bytes.len() as u64;
+                        return Some((Ok(bytes), Some(state)));
+                    }
+                    Some(Err(error)) => return Some((Err(error), None)),
+                    None => {
+                        if let Some(chunk) = state.chunk.take() {
+                            if let Err(error) = state.progress.complete(chunk) {
+                                return Some((Err(error), None));
+                            }
+                        }
+                        continue;
+                    }
+                }
+            }
+            match state.pages.next().await {
+                Some(Ok(response)) => match state.progress.start(response) {
+                    Ok(chunk) => state.chunk = Some(chunk),
+                    Err(error) => return Some((Err(error), None)),
+                },
+                Some(Err(error)) => return Some((Err(error), None)),
+                None => return None,
+            }
+        }
+    });
+    (stream.boxed(), progress)
+}
+
+/// Where a download stands, see [`DownloadProgress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadCheckpoint {
+    /// The offset of the first byte downloaded.
+    pub start: u64,
+    /// The offset after the last byte to download, known once the first
+    /// chunk arrived.
+    pub end: Option<u64>,
+    /// How many bytes were received, including those of a chunk which was
+    /// not completely received.
+    pub received: u64,
+    /// The offset after the last completely received and checked chunk, from
+    /// which the download can safely restart.
+    pub verified_offset: u64,
+    /// The CRC64 of the last checked chunk, e.g. to check that what was
+    /// written before `verified_offset` was not lost.
+    pub last_chunk_crc64: Option<u64>,
+    /// The ETag of the blob downloaded.
+    pub etag: Option<Etag>,
+}
+
+impl DownloadCheckpoint {
+    /// Whether every chunk was received and checked.
+    pub fn is_complete(&self) -> bool {
+        self.end == Some(self.verified_offset)
+    }
+
+    /// The range left to download, unless the download completed or its
+    /// end is not known yet.
+    pub fn remaining_range(&self) -> Option<Range> {
+        match self.end {
+            Some(end) if end > self.verified_offset => Some(Range::new(self.verified_offset, end)),
+            _ => None,
+        }
+    }
+}
+
+/// The progress of a stream of
+/// [`GetBlobBuilder::into_tracked_bytes_stream`](super::GetBlobBuilder::into_tracked_bytes_stream), shared with it.
+#[derive(Debug, Clone)]
+pub struct DownloadProgress(Arc<Mutex<DownloadCheckpoint>>);
+
+impl DownloadProgress {
+    fn new(range: Option<Range>) -> Self {
+        let start = range.map_or(0, |range| range.start);
+        Self(Arc::new(Mutex::new(DownloadCheckpoint {
+            start,
+            end: range.map(|range| range.end),
+            received: 0,
+            verified_offset: start,
+            last_chunk_crc64: None,
+            etag: None,
+        })))
+    }
+
+    /// A copy of the current state of the download.
+    pub fn checkpoint(&self) -> DownloadCheckpoint {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DownloadCheckpoint> {
+        // the checkpoint is only updated with plain assignments, so it is
+        // consistent even if a thread panicked while holding the lock
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Records the start of a chunk, the response to one of the requests of
+    /// the download.
+    fn start(&self, response: GetBlobResponse) -> azure_core::Result<Chunk> {
+        let mut checkpoint = self.lock();
+        let etag = &response.blob.properties.etag;
+        match &checkpoint.etag {
+            Some(expected) if expected != etag => {
+                return Err(Error::with_message(ErrorKind::Other, || {
+                    format!(
+                        "the blob changed during the download: its ETag went from {expected} \
+                         to {etag}"
+                    )
+                }));
+            }
+            Some(_) => {}
+            None => checkpoint.etag = Some(etag.clone()),
+        }
+
+        let start = response
+            .content_range
+            .map_or(checkpoint.verified_offset, |range| range.start());
+        let total_length = response
+            .content_range
+            .map_or(start + response.blob.properties.content_length, |range| {
+                range.total_length()
+            });
+        checkpoint.end = Some(
+            checkpoint
+                .end
+                .map_or(total_length, |end| end.min(total_length)),
+        );
+
+        Ok(Chunk {
+            body: response.data,
+            start,
+            len: response.blob.properties.content_length,
+            received: 0,
+            crc64: Crc64::new(),
+            expected_crc64: response
+                .blob
+                .properties
+                .content_crc64
+                .map(|crc64| crc64.value()),
+        })
+    }
+
+    /// Checks a chunk which was completely received and records it.
+    fn complete(&self, chunk: Chunk) -> azure_core::Result<()> {
+        if chunk.received != chunk.len {
+            return Err(Error::with_message(ErrorKind::Io, || {
+                format!(
+                    "the chunk at offset {} ended after {} of its {} bytes",
+                    chunk.start, chunk.received, chunk.len
+                )
+            }));
+        }
+        let crc64 = chunk.crc64.finalize();
+        if let Some(expected) = chunk.expected_crc64 {
+            if crc64 != expected {
+                return Err(Error::with_message(ErrorKind::DataConversion, || {
+                    format!(
+                        "the chunk at offset {} has a CRC64 of {crc64:#x} instead of {expected:#x}",
+                        chunk.start
+                    )
+                }));
+            }
+        }
+        let mut checkpoint = self.lock();
+        checkpoint.verified_offset = chunk.start + chunk.len;
+        checkpoint.last_chunk_crc64 = Some(crc64);
+        Ok(())
+    }
+}
+
+struct TrackedDownload {
+    pages: Pageable<GetBlobResponse, Error>,
+    chunk: Option<Chunk>,
+    progress: DownloadProgress,
+}
+
+struct Chunk {
+    body: ResponseBody,
+    start: u64,
+    len: u64,
+    received: u64,
+    crc64: Crc64,
+    expected_crc64: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, prelude::*, test_util};
+    use azure_core::{headers, StatusCode};
+    use futures::TryStreamExt;
+
+    const CONTENT: &[u8] = b"0123456789abcdefghij";
+
+    /// Serves the ranges of a blob, and corrupts the CRC64 of the ranges
+    /// starting at `corrupt_at`.
+    fn ranges(corrupt_at: Option<u64>) -> InMemoryTransport {
+        InMemoryTransport::answering(move |request| {
+            let range = request.headers().get_str(&headers::MS_RANGE)?;
+            let (start, end) = range
+                .strip_prefix("bytes=")
+                .and_then(|range| range.split_once('-'))
+                .unwrap();
+            let start: u64 = start.parse().unwrap();
+            let end = (end.parse::<u64>().unwrap() + 1).min(CONTENT.len() as u64);
+            let content = &CONTENT[start as usize..end as usize];
+            let mut crc64 = azure_storage::crypto::crc64(content);
+            if corrupt_at == Some(start) {
+                crc64 += 1;
+            }
+
+            let mut headers = test_util::blob_headers(content.len() as u64);
+            headers.insert(headers::ETAG, "\"0x1\"");
+            headers.insert(
+                headers::CONTENT_RANGE,
+                format!("bytes {start}-{}/{}", end - 1, CONTENT.len()),
+            );
+            headers.insert(
+                azure_storage::headers::CONTENT_CRC64,
+                base64::encode(crc64.to_le_bytes()),
+            );
+            Ok(test_util::response_with_body(
+                StatusCode::PartialContent,
+                headers,
+                Bytes::copy_from_slice(content),
+            ))
+        })
+    }
+
+    fn ranges_client(service: &InMemoryTransport) -> BlobClient {
+        service.client_builder().blob_client("container", "blob")
+    }
+
+    #[tokio::test]
+    async fn test_tracked_download() -> azure_core::Result<()> {
+        let service = ranges(Some(16));
+        let blob_client = ranges_client(&service);
+
+        let (mut stream, progress) = blob_client
+            .get()
+            .chunk_size(8u64)
+            .into_tracked_bytes_stream();
+        assert_eq!(stream.try_next().await?.as_deref(), Some(&CONTENT[..8]));
+        let checkpoint = progress.checkpoint();
+        assert_eq!(checkpoint.received, 8);
+        assert_eq!(checkpoint.verified_offset, 0);
+        assert_eq!(checkpoint.end, Some(20));
+
+        assert_eq!(stream.try_next().await?.as_deref(), Some(&CONTENT[8..16]));
+        assert_eq!(stream.try_next().await?.as_deref(), Some(&CONTENT[16..]));
+        let error = stream.try_next().await.unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::DataConversion);
+        let checkpoint = progress.checkpoint();
+        assert_eq!(checkpoint.received, 20);
+        assert_eq!(checkpoint.verified_offset, 16);
+        assert_eq!(
+            checkpoint.last_chunk_crc64,
+            Some(azure_storage::crypto::crc64(&CONTENT[8..16]))
+        );
+        assert_eq!(checkpoint.remaining_range(), Some(Range::new(16, 20)));
+        assert!(!checkpoint.is_complete());
+        drop(stream);
+
+        let service = ranges(None);
+        let (stream, progress) = ranges_client(&service)
+            .get()
+            .resume_from(&checkpoint)
+            .into_tracked_bytes_stream();
+        let rest = stream.try_collect::<Vec<_>>().await?;
+        assert_eq!(rest.concat(), &CONTENT[16..]);
+        assert!(progress.checkpoint().is_complete());
+        let if_match: Vec<_> = service
+            .requests()
+            .iter()
+            .map(|request| request.headers().get_optional_string(&headers::IF_MATCH))
+            .collect();
+        assert_eq!(if_match, [Some("\"0x1\"".to_owned())]);
+        Ok(())
+    }
+}
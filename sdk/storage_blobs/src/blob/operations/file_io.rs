@@ -0,0 +1,30 @@
+/// Runs `f`, which does blocking file system I/O, from an async operation.
+///
+/// With the `tokio` feature, on a multi-threaded Tokio runtime, the worker
+/// thread hands its other tasks over to the rest of the runtime while `f`
+/// runs. Otherwise `f` blocks the thread of the executor, and the other tasks
+/// of that thread wait for the disk.
+pub(crate) fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "tokio")]
+    if let Ok(handle) = tokio::runtime::Handle::try_current() {
+        if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread {
+            return tokio::task::block_in_place(f);
+        }
+    }
+    f()
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_blocking_multi_thread() {
+        assert_eq!(blocking(|| 1), 1);
+    }
+
+    #[tokio::test]
+    async fn test_blocking_current_thread() {
+        assert_eq!(blocking(|| 1), 1);
+    }
+}
@@ -1,8 +1,17 @@
-use crate::{blob::Blob, prelude::*};
+use super::download_progress::{tracked_bytes_stream, DownloadCheckpoint, DownloadProgress};
+use crate::{
+    blob::{Blob, Conditional},
+    prelude::*,
+};
 use azure_core::{
-    error::Error, headers::*, prelude::*, Pageable, RequestId, Response as AzureResponse,
-    ResponseBody,
+    error::{Error, ErrorKind},
+    headers::*,
+    prelude::*,
+    Method, Pageable, Request, RequestId, Response as AzureResponse, ResponseBody, StatusCode,
 };
+use azure_storage::StorageClient;
+use bytes::Bytes;
+use futures::{stream::BoxStream, Stream, TryStreamExt};
 use time::OffsetDateTime;
 
 const DEFAULT_CHUNK_SIZE: u64 = 0x1000 * 0x1000;
@@ -22,41 +31,104 @@ operation! {
 
 impl GetBlobBuilder {
     pub fn into_stream(self) -> Pageable<GetBlobResponse, Error> {
-        let make_request = move |continuation: Option<Range>| {
-            let this = self.clone();
-            let mut ctx = self.context.clone();
-            async move {
-                let mut url = this.client.url()?;
+        let make_request =
+            move |continuation: Option<Range>| Self::get_range(self.clone(), continuation);
+        Pageable::new(make_request)
+    }
 
-                let range = match continuation {
-                    Some(range) => range,
-                    None => {
-                        initial_range(this.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE), this.range)
-                    }
-                };
+    /// The content of the blob as a stream of bytes, downloaded chunk by
+    /// chunk as the stream is polled.
+    pub fn into_bytes_stream(self) -> impl Stream<Item = azure_core::Result<Bytes>> {
+        self.into_stream()
+            .map_ok(|response| response.data)
+            .try_flatten()
+    }
 
-                this.blob_versioning.append_to_url_query(&mut url);
+    /// Like [`into_bytes_stream`](Self::into_bytes_stream), with the progress
+    /// of the download recorded in a [`DownloadProgress`], which can still be
+    /// read once the stream failed, was dropped or its task was cancelled.
+    ///
+    /// Each chunk is checked against its length and, when the service returns
+    /// one, its CRC64, before the download is considered to have safely
+    /// reached its end. The chunks must come from the same version of the
+    /// blob: a change of its ETag fails the stream.
+    pub fn into_tracked_bytes_stream(
+        self,
+    ) -> (
+        BoxStream<'static, azure_core::Result<Bytes>>,
+        DownloadProgress,
+    ) {
+        let range = self.range;
+        tracked_bytes_stream(self.into_stream(), range)
+    }
 
-                let mut headers = Headers::new();
-                for (name, value) in range.as_headers() {
-                    headers.insert(name, value);
-                }
+    /// Downloads what remains after `checkpoint`, from its last verified
+    /// chunk, and only if the blob still has the same ETag.
+    pub fn resume_from(mut self, checkpoint: &DownloadCheckpoint) -> Self {
+        if let Some(range) = checkpoint.remaining_range() {
+            self.range = Some(range);
+        }
+        if let Some(etag) = &checkpoint.etag {
+            self.if_match = Some(IfMatchCondition::Match(etag.clone()));
+        }
+        self
+    }
 
-                headers.add(this.lease_id);
-                headers.add(this.if_modified_since);
-                headers.add(this.if_match.clone());
-                headers.add(this.if_tags.clone());
+    /// Like [`into_stream`](Self::into_stream), but a `304 Not Modified`
+    /// answer to the conditional headers ends the stream with
+    /// [`Conditional::NotModified`] rather than an error.
+    pub fn into_conditional_stream(self) -> Pageable<Conditional<GetBlobResponse>, Error> {
+        let make_request = move |continuation: Option<Range>| {
+            let this = self.clone();
+            async move { Conditional::from_result(Self::get_range(this, continuation).await) }
+        };
+        Pageable::new(make_request)
+    }
 
-                let mut request =
-                    this.client
-                        .finalize_request(url, azure_core::Method::Get, headers, None)?;
+    async fn get_range(
+        this: GetBlobBuilder,
+        continuation: Option<Range>,
+    ) -> azure_core::Result<GetBlobResponse> {
+        let mut ctx = this.context.clone();
 
-                let response = this.client.send(&mut ctx, &mut request).await?;
+        let range = match continuation {
+            Some(range) => range,
+            None => initial_range(this.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE), this.range),
+        };
 
-                GetBlobResponse::try_from(this, response).await
+        let mut request = this.request(Some(range))?;
+        let response = match this
+            .client
+            .send(&mut ctx, &mut request)
+            .await
+            .map_err(range_not_satisfiable)
+        {
+            // no range of an empty blob can be satisfied, so a download from
+            // its start gets the whole, empty, blob instead
+            Err(error) if range.start == 0 && is_empty_blob(&error) => {
+                let mut request = this.request(None)?;
+                this.client.send(&mut ctx, &mut request).await?
             }
+            response => response?,
         };
-        Pageable::new(make_request)
+
+        GetBlobResponse::try_from(this, response).await
+    }
+
+    fn request(&self, range: Option<Range>) -> azure_core::Result<Request> {
+        let mut request = self
+            .client
+            .request(Method::Get)?
+            .query(&self.blob_versioning);
+        if let Some(range) = range {
+            request = request.header(range);
+        }
+        request
+            .header(self.lease_id)
+            .header(self.if_modified_since)
+            .header(self.if_match.clone())
+            .header(self.if_tags.clone())
+            .into_request()
     }
 }
 
@@ -66,7 +138,7 @@ pub struct GetBlobResponse {
     pub blob: Blob,
     pub data: ResponseBody,
     pub date: OffsetDateTime,
-    pub content_range: Option<Range>,
+    pub content_range: Option<ContentRange>,
     pub remaining_range: Option<Range>,
 }
 
@@ -95,7 +167,7 @@ impl GetBlobResponse {
             blob,
             data,
             date,
-            content_range: content_range.map(|cr| Range::new(cr.start(), cr.end())),
+            content_range,
             remaining_range,
         })
     }
@@ -108,6 +180,45 @@ impl Continuable for GetBlobResponse {
     }
 }
 
+// A 416 response carries the current length of the blob as `bytes */<length>`,
+// which lets readers whose blob shrank recover without another request.
+fn range_not_satisfiable(error: Error) -> Error {
+    if !matches!(
+        error.kind(),
+        ErrorKind::HttpResponse {
+            status: StatusCode::RequestedRangeNotSatisfiable,
+            ..
+        }
+    ) {
+        return error;
+    }
+    let length = error
+        .as_http_error()
+        .and_then(|http_error| http_error.header(CONTENT_RANGE.as_str()))
+        .and_then(unsatisfied_range_length);
+    Error::full(
+        ErrorKind::RangeNotSatisfiable { length },
+        error,
+        match length {
+            Some(length) => {
+                format!("the requested range starts beyond the blob length of {length}")
+            }
+            None => "the requested range starts beyond the end of the blob".to_owned(),
+        },
+    )
+}
+
+fn is_empty_blob(error: &Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::RangeNotSatisfiable { length: Some(0) }
+    )
+}
+
+fn unsatisfied_range_length(content_range: &str) -> Option<u64> {
+    content_range.strip_prefix("bytes */")?.parse().ok()
+}
+
 // calculate the first Range for use at the beginning of the Pageable.
 fn initial_range(chunk_size: u64, request_range: Option<Range>) -> Range {
     match request_range {
@@ -185,6 +296,26 @@ mod tests {
         assert_eq!(result, expected);
         Ok(())
     }
+
+    #[test]
+    fn test_range_not_satisfiable() {
+        assert_eq!(unsatisfied_range_length("bytes */1024"), Some(1024));
+        assert_eq!(unsatisfied_range_length("bytes 0-9/1024"), None);
+
+        let error = range_not_satisfiable(
+            ErrorKind::http_response(StatusCode::RequestedRangeNotSatisfiable, None).into_error(),
+        );
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::RangeNotSatisfiable { length: None }
+        );
+
+        let error = range_not_satisfiable(
+            ErrorKind::http_response(StatusCode::NotFound, None).into_error(),
+        );
+        assert!(matches!(error.kind(), ErrorKind::HttpResponse { .. }));
+    }
+
     #[test]
     fn test_remaining_range() -> azure_core::Result<()> {
         let result = remaining_range(3, None, None);
@@ -2,7 +2,8 @@ use crate::{
     blob::{BlockListType, BlockWithSizeList},
     prelude::*,
 };
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use std::str::from_utf8;
 use time::OffsetDateTime;
 
@@ -18,24 +19,16 @@ operation! {
 impl GetBlockListBuilder {
     pub fn into_future(mut self) -> GetBlockList {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Get)?
+                .comp("blocklist")
+                .query(&self.blob_versioning)
+                .query(self.block_list_type.unwrap_or(BlockListType::Committed))
+                .header(self.lease_id)
+                .header(self.if_tags);
 
-            url.query_pairs_mut().append_pair("comp", "blocklist");
-            self.blob_versioning.append_to_url_query(&mut url);
-
-            self.block_list_type
-                .unwrap_or(BlockListType::Committed)
-                .append_to_url_query(&mut url);
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_tags);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Get, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             let (_, headers, body) = response.deconstruct();
             let body = body.collect().await?;
@@ -47,7 +40,7 @@ impl GetBlockListBuilder {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetBlockListResponse {
-    pub etag: Option<String>,
+    pub etag: Option<Etag>,
     pub last_modified: Option<OffsetDateTime>,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
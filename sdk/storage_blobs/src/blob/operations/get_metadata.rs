@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, Method, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use std::convert::{TryFrom, TryInto};
 use time::OffsetDateTime;
 
@@ -16,22 +17,17 @@ operation! {
 impl GetMetadataBuilder {
     pub fn into_future(mut self) -> GetMetadata {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "metadata");
-            self.blob_versioning.append_to_url_query(&mut url);
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.if_tags);
-
-            let mut request = self
+            let request = self
                 .client
-                .finalize_request(url, Method::Get, headers, None)?;
+                .request(Method::Get)?
+                .comp("metadata")
+                .query(&self.blob_versioning)
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.headers().try_into()
         })
@@ -41,7 +37,7 @@ impl GetMetadataBuilder {
 #[derive(Debug, Clone)]
 pub struct GetMetadataResponse {
     pub request_id: RequestId,
-    pub etag: String,
+    pub etag: Etag,
     pub server: String,
     pub date: OffsetDateTime,
     pub metadata: Metadata,
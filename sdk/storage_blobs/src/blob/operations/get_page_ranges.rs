@@ -1,5 +1,6 @@
 use crate::{blob::PageRangeList, prelude::*};
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use std::str::from_utf8;
 use time::OffsetDateTime;
 
@@ -16,22 +17,17 @@ operation! {
 impl GetPageRangesBuilder {
     pub fn into_future(mut self) -> GetPageRanges {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Get)?
+                .comp("pagelist")
+                .query(&self.blob_versioning)
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags);
 
-            url.query_pairs_mut().append_pair("comp", "pagelist");
-            self.blob_versioning.append_to_url_query(&mut url);
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.if_tags);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Get, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             let (_, headers, body) = response.deconstruct();
             let body = body.collect().await?;
@@ -43,7 +39,7 @@ impl GetPageRangesBuilder {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetPageRangesResponse {
-    pub etag: String,
+    pub etag: Etag,
     pub last_modified: OffsetDateTime,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
@@ -1,5 +1,9 @@
-use crate::{blob::Blob, prelude::*};
-use azure_core::{headers::*, prelude::*, RequestId};
+use crate::{
+    blob::{Blob, Conditional},
+    prelude::*,
+};
+use azure_core::{headers::*, prelude::*, Method, RequestId};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -15,29 +19,42 @@ operation! {
 impl GetPropertiesBuilder {
     pub fn into_future(mut self) -> GetProperties {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Head)?
+                .query(&self.blob_versioning)
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags);
 
-            self.blob_versioning.append_to_url_query(&mut url);
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.if_tags);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Head, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             // TODO: Fix this
             //let blob = Blob::from_headers(&blob_name, &container_name, snapshot_time, &headers)?;
             let blob = Blob::from_headers(self.client.blob_name(), response.headers())?;
             GetPropertiesResponse::from_response(response.headers(), blob)
         })
     }
+
+    /// Like [`into_future`](Self::into_future), but a `304 Not Modified`
+    /// answer to the conditional headers is returned as
+    /// [`Conditional::NotModified`] rather than an error.
+    pub fn into_conditional_future(self) -> ConditionalGetProperties {
+        Box::pin(async move { Conditional::from_result(self.into_future().await) })
+    }
 }
 
+#[cfg(target_arch = "wasm32")]
+pub type ConditionalGetProperties = std::pin::Pin<
+    std::boxed::Box<
+        dyn std::future::Future<Output = azure_core::Result<Conditional<GetPropertiesResponse>>>
+            + 'static,
+    >,
+>;
+#[cfg(not(target_arch = "wasm32"))]
+pub type ConditionalGetProperties =
+    futures::future::BoxFuture<'static, azure_core::Result<Conditional<GetPropertiesResponse>>>;
+
 #[derive(Debug, Clone)]
 pub struct GetPropertiesResponse {
     pub blob: Blob,
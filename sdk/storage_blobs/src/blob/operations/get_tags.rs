@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, xml::read_xml, RequestId};
+use azure_core::{headers::*, prelude::*, xml::read_xml, Method, RequestId};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -13,21 +14,15 @@ operation! {
 impl GetTagsBuilder {
     pub fn into_future(mut self) -> GetTags {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "tags");
-
-            self.blob_versioning.append_to_url_query(&mut url);
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_tags);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Get, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let request = self
+                .client
+                .request(Method::Get)?
+                .comp("tags")
+                .query(&self.blob_versioning)
+                .header(self.lease_id)
+                .header(self.if_tags);
+
+            let response = self.client.execute(&mut self.context, request).await?;
 
             let (_, headers, body) = response.deconstruct();
             let body = body.collect().await?;
@@ -1,5 +1,6 @@
 mod acquire_lease;
 mod append_block;
+mod autotune;
 mod break_lease;
 mod change_lease;
 mod clear_page;
@@ -8,12 +9,18 @@ mod copy_blob_from_url;
 mod delete_blob;
 mod delete_blob_snapshot;
 mod delete_blob_version;
+mod download_file;
+mod download_progress;
+mod file_io;
 mod get_blob;
 mod get_block_list;
 mod get_metadata;
 mod get_page_ranges;
 mod get_properties;
 mod get_tags;
+mod move_blob;
+mod page_blob_image;
+mod preflight;
 mod put_append_blob;
 mod put_block;
 mod put_block_blob;
@@ -21,6 +28,7 @@ mod put_block_list;
 mod put_page;
 mod put_page_blob;
 mod release_lease;
+mod rename_blob;
 mod renew_lease;
 mod set_blob_tier;
 mod set_expiry;
@@ -28,6 +36,8 @@ mod set_metadata;
 mod set_properties;
 mod set_tags;
 mod snapshot_blob;
+mod upload_file;
+mod upload_stream;
 
 pub use acquire_lease::*;
 pub use append_block::*;
@@ -39,12 +49,17 @@ pub use copy_blob_from_url::*;
 pub use delete_blob::*;
 pub use delete_blob_snapshot::*;
 pub use delete_blob_version::*;
+pub use download_file::*;
+pub use download_progress::{DownloadCheckpoint, DownloadProgress};
 pub use get_blob::*;
 pub use get_block_list::*;
 pub use get_metadata::*;
 pub use get_page_ranges::*;
 pub use get_properties::*;
 pub use get_tags::*;
+pub use move_blob::*;
+pub use page_blob_image::*;
+pub use preflight::*;
 pub use put_append_blob::*;
 pub use put_block::*;
 pub use put_block_blob::*;
@@ -52,6 +67,7 @@ pub use put_block_list::*;
 pub use put_page::*;
 pub use put_page_blob::*;
 pub use release_lease::*;
+pub use rename_blob::*;
 pub use renew_lease::*;
 pub use set_blob_tier::*;
 pub use set_expiry::*;
@@ -59,3 +75,5 @@ pub use set_metadata::*;
 pub use set_properties::*;
 pub use set_tags::*;
 pub use snapshot_blob::*;
+pub use upload_file::*;
+pub use upload_stream::*;
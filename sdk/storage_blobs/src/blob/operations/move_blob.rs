@@ -0,0 +1,303 @@
+use crate::{blob::CopyStatus, prelude::*};
+use azure_core::{
+    error::{Error, ErrorKind},
+    prelude::*,
+    Etag,
+};
+use azure_storage::CopyId;
+use std::time::Duration;
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+operation! {
+    MoveBlob,
+    client: BlobClient,
+    destination: BlobClient,
+    ?if_match: IfMatchCondition,
+    ?source_lease_id: LeaseId,
+    ?wait_for_copy: bool,
+    ?poll_interval: Duration
+}
+
+impl MoveBlobBuilder {
+    pub fn into_future(self) -> MoveBlob {
+        Box::pin(async move {
+            let source_url = self.client.url()?;
+            if self.destination.url()? == source_url {
+                return Err(Error::message(
+                    ErrorKind::Other,
+                    "cannot move a blob onto itself",
+                ));
+            }
+
+            let mut properties = self.client.get_properties().context(self.context.clone());
+            if let Some(lease_id) = self.source_lease_id {
+                properties = properties.lease_id(lease_id);
+            }
+            let source_etag = properties.await?.blob.properties.etag;
+
+            let mut copy = self
+                .destination
+                .copy(source_url)
+                .if_source_match(IfSourceMatchCondition::Match(source_etag.to_string()))
+                .context(self.context.clone());
+            if let Some(if_match) = self.if_match {
+                copy = copy.if_match(if_match);
+            }
+            let copy = copy.await?;
+
+            let mut copy_status = copy.copy_status;
+            if self.wait_for_copy.unwrap_or(true) {
+                let poll_interval = self.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL);
+                while copy_status == CopyStatus::Pending {
+                    if !poll_interval.is_zero() {
+                        azure_core::sleep::sleep(poll_interval).await;
+                    }
+                    let blob = self
+                        .destination
+                        .get_properties()
+                        .context(self.context.clone())
+                        .await?
+                        .blob;
+                    // another copy replaced ours, which cannot be told apart
+                    // from the move failing
+                    if blob.properties.copy_id.as_ref() != Some(&copy.copy_id) {
+                        return Err(Error::with_message(ErrorKind::Other, || {
+                            format!(
+                                "copy {} to {} was replaced by another write",
+                                copy.copy_id,
+                                self.destination.blob_name()
+                            )
+                        }));
+                    }
+                    copy_status = blob.properties.copy_status.unwrap_or(CopyStatus::Success);
+                }
+            }
+            match copy_status {
+                CopyStatus::Success => {}
+                CopyStatus::Pending => {
+                    return Ok(MoveBlobResponse {
+                        copy_id: copy.copy_id,
+                        copy_status,
+                        source_etag,
+                        source_deleted: false,
+                    })
+                }
+                status => {
+                    return Err(Error::with_message(ErrorKind::Other, || {
+                        format!(
+                            "copying {} to {} ended with status {status}",
+                            self.client.blob_name(),
+                            self.destination.blob_name()
+                        )
+                    }))
+                }
+            }
+
+            // the copy is of the base blob alone, and its snapshots go with it
+            let mut delete = self
+                .client
+                .delete()
+                .delete_snapshots_method(DeleteSnapshotsMethod::Include)
+                .if_match(IfMatchCondition::Match(source_etag.clone()))
+                .context(self.context.clone());
+            if let Some(lease_id) = self.source_lease_id {
+                delete = delete.lease_id(lease_id);
+            }
+            delete.await?;
+
+            Ok(MoveBlobResponse {
+                copy_id: copy.copy_id,
+                copy_status,
+                source_etag,
+                source_deleted: true,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MoveBlobResponse {
+    pub copy_id: CopyId,
+    /// `Pending` when the move did not wait for the copy to complete.
+    pub copy_status: CopyStatus,
+    /// The ETag of the source which was copied.
+    pub source_etag: Etag,
+    /// Whether the source was deleted, which it is once the copy succeeded.
+    pub source_deleted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::{headers, Method, Request, Response, StatusCode};
+    use std::sync::{Arc, Mutex};
+
+    const COPY_ID: &str = "c4a7e3c6-7c4a-4b8a-9c8e-2c2d5a6f3b1e";
+
+    /// Serves a source blob with the ETag `"0x1"` and copies ending with the
+    /// status in `copy_status`.
+    fn service(copy_status: Arc<Mutex<&'static str>>) -> InMemoryTransport {
+        InMemoryTransport::answering(move |request| answer(request, &copy_status.lock().unwrap()))
+    }
+
+    fn answer(request: &Request, copy_status: &str) -> azure_core::Result<Response> {
+        match *request.method() {
+            Method::Head => {
+                let mut headers = test_util::blob_headers(4);
+                headers.insert(headers::ETAG, "\"0x1\"");
+                test_util::response(StatusCode::Ok, headers)
+            }
+            Method::Put => {
+                let mut headers = test_util::version_headers();
+                headers.insert(headers::VERSION, "2019-12-12");
+                headers.insert(headers::SERVER, "Windows-Azure-Blob/1.0");
+                headers.insert(azure_storage::headers::COPY_ID, COPY_ID);
+                headers.insert(headers::COPY_STATUS, copy_status.to_owned());
+                test_util::response(StatusCode::Accepted, headers)
+            }
+            _ => {
+                let mut headers = test_util::service_headers();
+                headers.insert(headers::DELETE_TYPE_PERMANENT, "false");
+                test_util::response(StatusCode::Accepted, headers)
+            }
+        }
+    }
+
+    /// The method, path and ETag condition of a request.
+    fn summary(request: &Request) -> (Method, String, Option<String>) {
+        let condition = request
+            .headers()
+            .get_optional_string(&headers::SOURCE_IF_MATCH)
+            .or_else(|| request.headers().get_optional_string(&headers::IF_MATCH));
+        (
+            *request.method(),
+            request.url().path().to_owned(),
+            condition,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_move() -> azure_core::Result<()> {
+        let copy_status = Arc::new(Mutex::new("pending"));
+        let service = service(copy_status.clone());
+        let container_client = service.client_builder().container_client("container");
+        let source = container_client.blob_client("old");
+        let destination = container_client.blob_client("new");
+
+        assert!(source.move_to(source.clone()).await.is_err());
+        assert!(service.requests().is_empty());
+
+        let response = source
+            .move_to(destination.clone())
+            .wait_for_copy(false)
+            .await?;
+        assert_eq!(response.copy_status, CopyStatus::Pending);
+        assert!(!response.source_deleted);
+        assert_eq!(service.take_requests().len(), 2);
+
+        *copy_status.lock().unwrap() = "success";
+        let response = source.move_to(destination.clone()).await?;
+        assert_eq!(response.copy_status, CopyStatus::Success);
+        assert!(response.source_deleted);
+        assert_eq!(response.source_etag, "\"0x1\"".into());
+        let etag = Some("\"0x1\"".to_owned());
+        assert_eq!(
+            service
+                .take_requests()
+                .iter()
+                .map(summary)
+                .collect::<Vec<_>>(),
+            [
+                (Method::Head, "/container/old".to_owned(), None),
+                (Method::Put, "/container/new".to_owned(), etag.clone()),
+                (Method::Delete, "/container/old".to_owned(), etag),
+            ]
+        );
+
+        *copy_status.lock().unwrap() = "failed";
+        assert!(source.move_to(destination).await.is_err());
+        // the source is kept when the copy failed
+        assert!(service
+            .requests()
+            .iter()
+            .all(|request| *request.method() != Method::Delete));
+        Ok(())
+    }
+
+    /// Serves a source blob, a pending copy, and then the properties of the
+    /// destination in `destination`, one answer per request.
+    fn polled_service(destination: Vec<(&'static str, &'static str)>) -> InMemoryTransport {
+        let destination = Mutex::new(destination.into_iter());
+        InMemoryTransport::answering(move |request| {
+            if *request.method() == Method::Head && request.url().path() == "/container/new" {
+                let (copy_id, copy_status) = destination.lock().unwrap().next().unwrap();
+                let mut headers = test_util::blob_headers(4);
+                headers.insert(azure_storage::headers::COPY_ID, copy_id);
+                headers.insert(headers::COPY_STATUS, copy_status);
+                return test_util::response(StatusCode::Ok, headers);
+            }
+            answer(request, "pending")
+        })
+    }
+
+    #[tokio::test]
+    async fn test_move_polls_copy() -> azure_core::Result<()> {
+        let service = polled_service(vec![(COPY_ID, "pending"), (COPY_ID, "success")]);
+        let container_client = service.client_builder().container_client("container");
+        let source = container_client.blob_client("old");
+
+        let response = source
+            .move_to(container_client.blob_client("new"))
+            .poll_interval(Duration::ZERO)
+            .await?;
+        assert_eq!(response.copy_status, CopyStatus::Success);
+        assert!(response.source_deleted);
+        let requests = service.take_requests();
+        assert_eq!(
+            requests.iter().map(summary).collect::<Vec<_>>(),
+            [
+                (Method::Head, "/container/old".to_owned(), None),
+                (
+                    Method::Put,
+                    "/container/new".to_owned(),
+                    Some("\"0x1\"".to_owned())
+                ),
+                (Method::Head, "/container/new".to_owned(), None),
+                (Method::Head, "/container/new".to_owned(), None),
+                (
+                    Method::Delete,
+                    "/container/old".to_owned(),
+                    Some("\"0x1\"".to_owned())
+                ),
+            ]
+        );
+        // the snapshots of the source are deleted with it
+        assert_eq!(
+            requests[4]
+                .headers()
+                .get_optional_str(&headers::DELETE_SNAPSHOTS),
+            Some("include")
+        );
+
+        // another copy replaced ours while it was pending
+        let service = polled_service(vec![
+            (COPY_ID, "pending"),
+            ("5d8b9a1e-0c3f-4e2a-8b7d-6f1e2a3c4b5d", "success"),
+        ]);
+        let container_client = service.client_builder().container_client("container");
+        let error = container_client
+            .blob_client("old")
+            .move_to(container_client.blob_client("new"))
+            .poll_interval(Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("replaced"));
+        assert!(service
+            .requests()
+            .iter()
+            .all(|request| *request.method() != Method::Delete));
+        Ok(())
+    }
+}
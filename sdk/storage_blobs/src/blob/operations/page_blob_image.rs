@@ -0,0 +1,515 @@
+use super::file_io;
+use crate::{
+    blob::Blob,
+    limits::{MAX_PAGE_WRITE_SIZE, PAGE_SIZE},
+    prelude::*,
+};
+use azure_core::{
+    error::{Error, ErrorKind, ResultExt},
+    prelude::*,
+};
+use bytes::Bytes;
+use futures::{stream, StreamExt, TryStreamExt};
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+use time::OffsetDateTime;
+
+/// Size of the footer at the end of a fixed VHD.
+pub const VHD_FOOTER_SIZE: u64 = 512;
+/// Largest range written or read with a single request while transferring a
+/// disk image.
+const MAX_TRANSFER_SIZE: u64 = MAX_PAGE_WRITE_SIZE;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+const VHD_COOKIE: &[u8; 8] = b"conectix";
+const VHD_FIXED_DISK: u32 = 2;
+/// Seconds from the Unix epoch to the VHD epoch, 2000-01-01 00:00:00 UTC.
+const VHD_EPOCH: i64 = 946_684_800;
+
+/// The layout of a local disk image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiskImageFormat {
+    /// A fixed VHD: the disk followed by a 512 byte footer. This is the
+    /// layout of the page blobs behind Azure disks, so images are copied as
+    /// they are.
+    #[default]
+    Vhd,
+    /// The disk alone. The VHD footer is left out when exporting and written
+    /// when importing.
+    Raw,
+}
+
+operation! {
+    ExportPageBlob,
+    client: BlobClient,
+    path: PathBuf,
+    ?format: DiskImageFormat,
+    ?max_concurrency: usize,
+    ?lease_id: LeaseId
+}
+
+impl ExportPageBlobBuilder {
+    pub fn into_future(self) -> ExportPageBlob {
+        Box::pin(async move {
+            let mut properties = self.client.get_properties().context(self.context.clone());
+            if let Some(lease_id) = self.lease_id {
+                properties = properties.lease_id(lease_id);
+            }
+            let blob = properties.await?.blob;
+            let blob_len = blob.properties.content_length;
+
+            let image_len = match self.format.unwrap_or_default() {
+                DiskImageFormat::Vhd => blob_len,
+                DiskImageFormat::Raw if blob_len >= VHD_FOOTER_SIZE => {
+                    let footer = self
+                        .download_range(Range::new(blob_len - VHD_FOOTER_SIZE, blob_len), &blob)
+                        .await?;
+                    if VhdFooter::parse(&footer).is_some() {
+                        blob_len - VHD_FOOTER_SIZE
+                    } else {
+                        blob_len
+                    }
+                }
+                DiskImageFormat::Raw => blob_len,
+            };
+
+            let mut page_ranges = self
+                .client
+                .get_page_ranges()
+                .if_match(IfMatchCondition::Match(blob.properties.etag.clone()))
+                .context(self.context.clone());
+            if let Some(lease_id) = self.lease_id {
+                page_ranges = page_ranges.lease_id(lease_id);
+            }
+            // page ranges are reported with inclusive ends
+            let allocated = page_ranges
+                .await?
+                .page_list
+                .ranges
+                .into_iter()
+                .map(|range| Range::new(range.start, range.end + 1));
+            let ranges = transfer_ranges(allocated, image_len);
+
+            let mut file = file_io::blocking(|| {
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(&self.path)
+                    .with_context(ErrorKind::Io, || {
+                        format!("failed to create {}", self.path.display())
+                    })?;
+                // the pages which are never written are left as holes
+                file.set_len(image_len).with_context(ErrorKind::Io, || {
+                    format!("failed to allocate {}", self.path.display())
+                })?;
+                Ok::<_, Error>(file)
+            })?;
+
+            let (this, blob_ref) = (&self, &blob);
+            let mut bytes_downloaded = 0;
+            let mut chunks = stream::iter(ranges)
+                .map(|range| async move {
+                    let data = this.download_range(range, blob_ref).await?;
+                    Ok::<_, Error>((range, data))
+                })
+                .buffer_unordered(max_concurrency(self.max_concurrency));
+            while let Some((range, data)) = chunks.try_next().await? {
+                file_io::blocking(|| {
+                    file.seek(SeekFrom::Start(range.start))
+                        .and_then(|_| file.write_all(&data))
+                })
+                .with_context(ErrorKind::Io, || {
+                    format!("failed to write {}", self.path.display())
+                })?;
+                bytes_downloaded += data.len() as u64;
+            }
+            drop(chunks);
+
+            Ok(ExportPageBlobResponse {
+                path: self.path,
+                blob,
+                bytes_downloaded,
+            })
+        })
+    }
+
+    async fn download_range(&self, range: Range, blob: &Blob) -> azure_core::Result<Bytes> {
+        let mut builder = self
+            .client
+            .get()
+            .range(range)
+            .chunk_size(range.len())
+            .if_match(IfMatchCondition::Match(blob.properties.etag.clone()))
+            .context(self.context.clone());
+        if let Some(lease_id) = self.lease_id {
+            builder = builder.lease_id(lease_id);
+        }
+        let response =
+            builder.into_stream().next().await.ok_or_else(|| {
+                Error::message(ErrorKind::Other, "get blob returned no response")
+            })??;
+        let data = response.data.collect().await?;
+        if data.len() as u64 != range.len() {
+            return Err(Error::with_message(ErrorKind::DataConversion, || {
+                format!(
+                    "expected {} bytes for range {}-{} but received {}",
+                    range.len(),
+                    range.start,
+                    range.end,
+                    data.len()
+                )
+            }));
+        }
+        Ok(data)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportPageBlobResponse {
+    pub path: PathBuf,
+    pub blob: Blob,
+    /// Bytes actually transferred, which is only the allocated pages.
+    pub bytes_downloaded: u64,
+}
+
+operation! {
+    ImportPageBlob,
+    client: BlobClient,
+    path: PathBuf,
+    ?format: DiskImageFormat,
+    ?max_concurrency: usize,
+    ?metadata: Metadata
+}
+
+impl ImportPageBlobBuilder {
+    pub fn into_future(self) -> ImportPageBlob {
+        Box::pin(async move {
+            let (mut file, file_len) = file_io::blocking(|| {
+                let file = File::open(&self.path).with_context(ErrorKind::Io, || {
+                    format!("failed to open {}", self.path.display())
+                })?;
+                let file_len = file
+                    .metadata()
+                    .with_context(ErrorKind::Io, || {
+                        format!("failed to read {}", self.path.display())
+                    })?
+                    .len();
+                Ok::<_, Error>((file, file_len))
+            })?;
+            if file_len % PAGE_SIZE != 0 {
+                return Err(Error::with_message(ErrorKind::Other, || {
+                    format!(
+                        "{} is {file_len} bytes long, which is not a multiple of {PAGE_SIZE}",
+                        self.path.display()
+                    )
+                }));
+            }
+
+            let footer = match self.format.unwrap_or_default() {
+                DiskImageFormat::Vhd => {
+                    file_io::blocking(|| check_vhd_footer(&mut file, file_len))
+                        .with_context(ErrorKind::Other, || {
+                            format!("{} is not a fixed VHD", self.path.display())
+                        })?;
+                    None
+                }
+                DiskImageFormat::Raw => Some(VhdFooter::new(file_len).to_bytes()),
+            };
+            let blob_len = file_len + footer.as_ref().map_or(0, |_| VHD_FOOTER_SIZE);
+
+            let mut create = self
+                .client
+                .create_page_blob(blob_len as u128)
+                .context(self.context.clone());
+            if let Some(metadata) = self.metadata.clone() {
+                create = create.metadata(metadata);
+            }
+            create.await?;
+
+            let mut pages = NonZeroPages::new(&mut file, file_len);
+            let footer = footer.map(|footer| Ok((file_len, Bytes::copy_from_slice(&footer))));
+            let this = &self;
+            let mut uploads = stream::iter(std::iter::from_fn(|| pages.next()).chain(footer))
+                .map(|pages| async move {
+                    let (offset, data) = pages?;
+                    let len = data.len() as u64;
+                    let range = BA512Range::new(offset, offset + len - 1)?;
+                    this.client
+                        .put_page(range, data)
+                        .context(this.context.clone())
+                        .await?;
+                    Ok::<_, Error>(len)
+                })
+                .buffer_unordered(max_concurrency(self.max_concurrency));
+            let mut bytes_uploaded = 0;
+            while let Some(len) = uploads.try_next().await? {
+                bytes_uploaded += len;
+            }
+
+            Ok(ImportPageBlobResponse {
+                blob_length: blob_len,
+                bytes_uploaded,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportPageBlobResponse {
+    pub blob_length: u64,
+    /// Bytes actually transferred, which is only the pages which are not all
+    /// zeros.
+    pub bytes_uploaded: u64,
+}
+
+fn max_concurrency(max_concurrency: Option<usize>) -> usize {
+    max_concurrency.unwrap_or(DEFAULT_MAX_CONCURRENCY).max(1)
+}
+
+/// Clips `allocated` to the first `len` bytes and splits it into ranges small
+/// enough for a single request.
+fn transfer_ranges(allocated: impl Iterator<Item = Range>, len: u64) -> Vec<Range> {
+    allocated
+        .filter(|range| range.start < len)
+        .flat_map(|range| {
+            let end = range.end.min(len);
+            (range.start..end)
+                .step_by(MAX_TRANSFER_SIZE as usize)
+                .map(move |start| Range::new(start, end.min(start + MAX_TRANSFER_SIZE)))
+        })
+        .collect()
+}
+
+fn check_vhd_footer(file: &mut File, len: u64) -> azure_core::Result<()> {
+    if len < VHD_FOOTER_SIZE {
+        return Err(Error::message(
+            ErrorKind::Other,
+            "the file is too short to hold a VHD footer",
+        ));
+    }
+    let mut footer = [0; VHD_FOOTER_SIZE as usize];
+    file.seek(SeekFrom::Start(len - VHD_FOOTER_SIZE))
+        .and_then(|_| file.read_exact(&mut footer))
+        .context(ErrorKind::Io, "failed to read the VHD footer")?;
+    let footer = VhdFooter::parse(&footer)
+        .ok_or_else(|| Error::message(ErrorKind::Other, "the VHD footer is missing or corrupt"))?;
+    if footer.disk_type != VHD_FIXED_DISK || footer.current_size != len - VHD_FOOTER_SIZE {
+        return Err(Error::message(
+            ErrorKind::Other,
+            "only fixed VHDs can be imported into a page blob",
+        ));
+    }
+    Ok(())
+}
+
+/// Reads a file a transfer at a time and yields the runs of pages which are
+/// not all zeros, with their offsets.
+struct NonZeroPages<'a> {
+    file: &'a mut File,
+    len: u64,
+    offset: u64,
+    pending: std::vec::IntoIter<(u64, Bytes)>,
+}
+
+impl<'a> NonZeroPages<'a> {
+    fn new(file: &'a mut File, len: u64) -> Self {
+        Self {
+            file,
+            len,
+            offset: 0,
+            pending: Vec::new().into_iter(),
+        }
+    }
+
+    fn next(&mut self) -> Option<azure_core::Result<(u64, Bytes)>> {
+        loop {
+            if let Some(run) = self.pending.next() {
+                return Some(Ok(run));
+            }
+            if self.offset >= self.len {
+                return None;
+            }
+            let mut buffer = vec![0; MAX_TRANSFER_SIZE.min(self.len - self.offset) as usize];
+            let (file, offset) = (&mut self.file, self.offset);
+            if let Err(error) = file_io::blocking(|| {
+                file.seek(SeekFrom::Start(offset))
+                    .and_then(|_| file.read_exact(&mut buffer))
+            }) {
+                self.offset = self.len;
+                return Some(Err(Error::new(ErrorKind::Io, error)));
+            }
+            let buffer = Bytes::from(buffer);
+            self.pending = non_zero_runs(&buffer)
+                .into_iter()
+                .map(|(start, end)| (self.offset + start as u64, buffer.slice(start..end)))
+                .collect::<Vec<_>>()
+                .into_iter();
+            self.offset += buffer.len() as u64;
+        }
+    }
+}
+
+/// The byte ranges of `data` covered by runs of pages which are not all zeros.
+fn non_zero_runs(data: &[u8]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for (index, page) in data.chunks(PAGE_SIZE as usize).enumerate() {
+        if page.iter().all(|byte| *byte == 0) {
+            continue;
+        }
+        let start = index * PAGE_SIZE as usize;
+        let end = start + page.len();
+        match runs.last_mut() {
+            Some(run) if run.1 == start => run.1 = end,
+            _ => runs.push((start, end)),
+        }
+    }
+    runs
+}
+
+/// The fields of a VHD footer this module reads and writes.
+///
+/// ref: <https://learn.microsoft.com/windows/win32/vstor/about-vhd>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct VhdFooter {
+    current_size: u64,
+    disk_type: u32,
+}
+
+impl VhdFooter {
+    fn new(size: u64) -> Self {
+        Self {
+            current_size: size,
+            disk_type: VHD_FIXED_DISK,
+        }
+    }
+
+    fn parse(footer: &[u8]) -> Option<Self> {
+        if footer.len() != VHD_FOOTER_SIZE as usize || &footer[0..8] != VHD_COOKIE {
+            return None;
+        }
+        let stored = u32::from_be_bytes(footer[64..68].try_into().ok()?);
+        if stored != checksum(footer) {
+            return None;
+        }
+        Some(Self {
+            current_size: u64::from_be_bytes(footer[48..56].try_into().ok()?),
+            disk_type: u32::from_be_bytes(footer[60..64].try_into().ok()?),
+        })
+    }
+
+    fn to_bytes(self) -> [u8; VHD_FOOTER_SIZE as usize] {
+        let mut footer = [0; VHD_FOOTER_SIZE as usize];
+        footer[0..8].copy_from_slice(VHD_COOKIE);
+        // features: reserved bit, always set
+        footer[8..12].copy_from_slice(&2u32.to_be_bytes());
+        // file format version 1.0
+        footer[12..16].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        // data offset: none, the disk is fixed
+        footer[16..24].copy_from_slice(&u64::MAX.to_be_bytes());
+        let timestamp = (OffsetDateTime::now_utc().unix_timestamp() - VHD_EPOCH).max(0) as u32;
+        footer[24..28].copy_from_slice(&timestamp.to_be_bytes());
+        footer[28..32].copy_from_slice(b"rust");
+        footer[32..36].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        footer[36..40].copy_from_slice(b"Wi2k");
+        footer[40..48].copy_from_slice(&self.current_size.to_be_bytes());
+        footer[48..56].copy_from_slice(&self.current_size.to_be_bytes());
+        let (cylinders, heads, sectors) = geometry(self.current_size);
+        footer[56..58].copy_from_slice(&cylinders.to_be_bytes());
+        footer[58] = heads;
+        footer[59] = sectors;
+        footer[60..64].copy_from_slice(&self.disk_type.to_be_bytes());
+        footer[68..84].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+        let checksum = checksum(&footer);
+        footer[64..68].copy_from_slice(&checksum.to_be_bytes());
+        footer
+    }
+}
+
+/// The one's complement of the sum of the footer's bytes, leaving out the
+/// checksum itself.
+fn checksum(footer: &[u8]) -> u32 {
+    let sum = footer
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !(64..68).contains(index))
+        .fold(0u32, |sum, (_, byte)| sum.wrapping_add(u32::from(*byte)));
+    !sum
+}
+
+/// The cylinders, heads and sectors per track of a disk of `size` bytes, as
+/// computed by the algorithm in the VHD specification.
+fn geometry(size: u64) -> (u16, u8, u8) {
+    let total_sectors = (size / PAGE_SIZE).min(65535 * 16 * 255);
+    let (sectors, heads, cylinders_times_heads) = if total_sectors >= 65535 * 16 * 63 {
+        (255, 16, total_sectors / 255)
+    } else {
+        let mut sectors = 17;
+        let mut cylinders_times_heads = total_sectors / sectors;
+        let mut heads = ((cylinders_times_heads + 1023) / 1024).max(4);
+        if cylinders_times_heads >= heads * 1024 || heads > 16 {
+            sectors = 31;
+            heads = 16;
+            cylinders_times_heads = total_sectors / sectors;
+        }
+        if cylinders_times_heads >= heads * 1024 {
+            sectors = 63;
+            heads = 16;
+            cylinders_times_heads = total_sectors / sectors;
+        }
+        (sectors, heads, cylinders_times_heads)
+    };
+    (
+        (cylinders_times_heads / heads) as u16,
+        heads as u8,
+        sectors as u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vhd_footer() {
+        let size = 30 * 1024 * 1024 * 1024;
+        let footer = VhdFooter::new(size).to_bytes();
+        assert_eq!(VhdFooter::parse(&footer), Some(VhdFooter::new(size)));
+        // 30 GiB takes the 63 sectors per track geometry
+        assert_eq!(geometry(size), (62415, 16, 63));
+
+        let mut corrupt = footer;
+        corrupt[48] ^= 1;
+        assert_eq!(VhdFooter::parse(&corrupt), None);
+    }
+
+    #[test]
+    fn test_non_zero_runs() {
+        let mut data = vec![0u8; 5 * 512];
+        data[0] = 1;
+        data[512 + 7] = 1;
+        data[4 * 512 + 100] = 1;
+        assert_eq!(non_zero_runs(&data), vec![(0, 1024), (2048, 2560)]);
+        assert!(non_zero_runs(&[0; 1024]).is_empty());
+    }
+
+    #[test]
+    fn test_transfer_ranges() {
+        let allocated = vec![
+            Range::new(0, 512),
+            Range::new(1024, 1024 + 2 * MAX_TRANSFER_SIZE),
+            Range::new(3 * MAX_TRANSFER_SIZE, 3 * MAX_TRANSFER_SIZE + 512),
+        ];
+        assert_eq!(
+            transfer_ranges(allocated.into_iter(), 2 * MAX_TRANSFER_SIZE + 512),
+            vec![
+                Range::new(0, 512),
+                Range::new(1024, 1024 + MAX_TRANSFER_SIZE),
+                Range::new(1024 + MAX_TRANSFER_SIZE, 2 * MAX_TRANSFER_SIZE + 512),
+            ]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use crate::prelude::*;
+use azure_core::{error::ErrorKind, headers::*, Method, Response, StatusCode};
+use azure_storage::StorageClient;
+use std::time::Duration;
+
+operation! {
+    Preflight,
+    client: BlobClient,
+    origin: String,
+    method: Method,
+    ?request_headers: Vec<String>
+}
+
+impl PreflightBuilder {
+    pub fn into_future(mut self) -> Preflight {
+        Box::pin(async move {
+            let mut request = self
+                .client
+                .request(Method::Options)?
+                .header_value(ORIGIN, self.origin.clone())
+                .header_value(
+                    ACCESS_CONTROL_REQUEST_METHOD,
+                    self.method.as_ref().to_owned(),
+                );
+            if let Some(request_headers) = &self.request_headers {
+                if !request_headers.is_empty() {
+                    request = request
+                        .header_value(ACCESS_CONTROL_REQUEST_HEADERS, request_headers.join(","));
+                }
+            }
+
+            match self.client.execute(&mut self.context, request).await {
+                Ok(response) => PreflightResponse::try_from(response),
+                // the service answers 403 when no CORS rule matches the request
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        ErrorKind::HttpResponse {
+                            status: StatusCode::Forbidden,
+                            ..
+                        }
+                    ) =>
+                {
+                    Ok(PreflightResponse::default())
+                }
+                Err(error) => Err(error),
+            }
+        })
+    }
+}
+
+/// The outcome of a CORS preflight request, as a browser would see it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreflightResponse {
+    /// Whether a CORS rule of the service matched the origin, method and
+    /// headers.
+    pub allowed: bool,
+    pub allowed_origin: Option<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub exposed_headers: Vec<String>,
+    pub allow_credentials: bool,
+    pub max_age: Option<Duration>,
+}
+
+impl PreflightResponse {
+    /// Check whether `method` is among the allowed methods.
+    pub fn allows_method(&self, method: Method) -> bool {
+        self.allowed_methods
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(method.as_ref()))
+    }
+
+    /// Check whether `header` is among the allowed request headers.
+    pub fn allows_header(&self, header: &str) -> bool {
+        self.allowed_headers
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(header))
+    }
+}
+
+impl TryFrom<Response> for PreflightResponse {
+    type Error = azure_core::Error;
+
+    fn try_from(response: Response) -> Result<Self, Self::Error> {
+        let headers = response.headers();
+        let allowed_origin = headers
+            .get_optional_string(&ACCESS_CONTROL_ALLOW_ORIGIN)
+            .filter(|origin| !origin.is_empty());
+        Ok(PreflightResponse {
+            allowed: allowed_origin.is_some(),
+            allowed_origin,
+            allowed_methods: split_list(headers, &ACCESS_CONTROL_ALLOW_METHODS),
+            allowed_headers: split_list(headers, &ACCESS_CONTROL_ALLOW_HEADERS),
+            exposed_headers: split_list(headers, &ACCESS_CONTROL_EXPOSE_HEADERS),
+            allow_credentials: headers
+                .get_optional_str(&ACCESS_CONTROL_ALLOW_CREDENTIALS)
+                .map_or(false, |value| value.eq_ignore_ascii_case("true")),
+            max_age: headers
+                .get_optional_as::<u64, _>(&ACCESS_CONTROL_MAX_AGE)?
+                .map(Duration::from_secs),
+        })
+    }
+}
+
+fn split_list(headers: &Headers, name: &HeaderName) -> Vec<String> {
+    headers
+        .get_optional_str(name)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(ToOwned::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preflight_response() -> azure_core::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, "https://contoso.com");
+        headers.insert(ACCESS_CONTROL_ALLOW_METHODS, "GET, PUT");
+        headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, "x-ms-meta-*,content-type");
+        headers.insert(ACCESS_CONTROL_MAX_AGE, "600");
+        let response = Response::new(StatusCode::Ok, headers, Box::pin(futures::stream::empty()));
+
+        let preflight = PreflightResponse::try_from(response)?;
+        assert!(preflight.allowed);
+        assert!(preflight.allows_method(Method::Put));
+        assert!(!preflight.allows_method(Method::Delete));
+        assert!(preflight.allows_header("Content-Type"));
+        assert_eq!(preflight.max_age, Some(Duration::from_secs(600)));
+        assert!(!preflight.allow_credentials);
+        Ok(())
+    }
+}
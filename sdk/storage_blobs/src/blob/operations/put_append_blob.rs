@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -11,33 +12,38 @@ operation! {
     ?content_disposition: BlobContentDisposition,
     ?metadata: Metadata,
     ?tags: Tags,
-    ?lease_id: LeaseId
+    ?lease_id: LeaseId,
+    ?cache_control: BlobCacheControl,
+    ?if_modified_since: IfModifiedSinceCondition,
+    ?if_match: IfMatchCondition,
+    ?if_tags: IfTags
 }
 
 impl PutAppendBlobBuilder {
     pub fn into_future(mut self) -> PutAppendBlob {
         Box::pin(async move {
-            let url = self.client.url()?;
-
-            let mut headers = Headers::new();
-            headers.insert(BLOB_TYPE, "AppendBlob");
-            headers.add(self.content_type);
-            headers.add(self.content_encoding);
-            headers.add(self.content_language);
-            headers.add(self.content_disposition);
-            headers.add(self.tags);
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .header_value(BLOB_TYPE, "AppendBlob")
+                .header(self.content_type)
+                .header(self.content_encoding)
+                .header(self.content_language)
+                .header(self.content_disposition)
+                .header(self.cache_control)
+                .header(self.tags);
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
-            headers.add(self.lease_id);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
+            let request = request
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             PutBlobResponse::from_headers(response.headers())
         })
     }
@@ -47,7 +53,7 @@ type PutAppendBlobResponse = PutBlobResponse;
 
 #[derive(Debug, Clone)]
 pub struct PutBlobResponse {
-    pub etag: String,
+    pub etag: Etag,
     pub last_modified: OffsetDateTime,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
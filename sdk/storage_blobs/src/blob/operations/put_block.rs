@@ -1,6 +1,12 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, Body, RequestId};
-use azure_storage::{headers::consistency_from_headers, ConsistencyCRC64, ConsistencyMD5};
+use azure_core::{headers::*, prelude::*, Body, Method, RequestId};
+use azure_storage::{
+    headers::{
+        consistency_from_headers, encryption_key_sha256_from_headers_optional,
+        encryption_scope_from_headers_optional,
+    },
+    ConsistencyCRC64, ConsistencyMD5, StorageClient,
+};
 use time::OffsetDateTime;
 
 operation! {
@@ -15,49 +21,54 @@ operation! {
 impl PutBlockBuilder {
     pub fn into_future(mut self) -> PutBlock {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .query(&self.block_id)
+                .comp("block")
+                .header(self.hash)
+                .header(self.lease_id)
+                .body(self.body);
 
-            self.block_id.append_to_url_query(&mut url);
-            url.query_pairs_mut().append_pair("comp", "block");
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-
-            let mut request = self.client.finalize_request(
-                url,
-                azure_core::Method::Put,
-                headers,
-                Some(self.body),
-            )?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
-            PutBlockResponse::from_headers(response.headers())
+            let response = self.client.execute(&mut self.context, request).await?;
+            PutBlockResponse::from_headers(self.block_id, response.headers())
         })
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PutBlockResponse {
+    pub block_id: BlockId,
     pub content_md5: Option<ConsistencyMD5>,
     pub content_crc64: Option<ConsistencyCRC64>,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
     pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
+    pub encryption_scope: Option<String>,
 }
 
 impl PutBlockResponse {
-    pub(crate) fn from_headers(headers: &Headers) -> azure_core::Result<PutBlockResponse> {
+    pub(crate) fn from_headers(
+        block_id: BlockId,
+        headers: &Headers,
+    ) -> azure_core::Result<PutBlockResponse> {
         let (content_md5, content_crc64) = consistency_from_headers(headers)?;
         let request_id = request_id_from_headers(headers)?;
         let date = date_from_headers(headers)?;
         let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers);
+        let encryption_scope = encryption_scope_from_headers_optional(headers);
 
         Ok(PutBlockResponse {
+            block_id,
             content_md5,
             content_crc64,
             request_id,
             date,
             request_server_encrypted,
+            encryption_key_sha256,
+            encryption_scope,
         })
     }
 }
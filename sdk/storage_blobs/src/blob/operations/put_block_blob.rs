@@ -1,8 +1,28 @@
+use super::BlobCommitOptions;
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, Body, RequestId};
-use azure_storage::{headers::consistency_from_headers, ConsistencyCRC64, ConsistencyMD5};
+use azure_core::{
+    error::{Error, ErrorKind},
+    headers::*,
+    prelude::*,
+    Body, Etag, Method, RequestId,
+};
+use azure_storage::{
+    headers::{
+        consistency_from_headers, encryption_key_sha256_from_headers_optional,
+        encryption_scope_from_headers_optional,
+    },
+    ConsistencyCRC64, ConsistencyMD5, StorageClient,
+};
+use bytes::{Bytes, BytesMut};
+use futures::AsyncReadExt;
 use time::OffsetDateTime;
 
+/// The size of the blocks staged when an oversized body is uploaded in
+/// blocks, unless the service version needs them smaller or the body larger.
+const AUTO_BLOCK_SIZE: usize = 64 * 1024 * 1024;
+
+pub use crate::limits::max_put_blob_size;
+
 operation! {
     PutBlockBlob,
     client: BlobClient,
@@ -15,52 +35,208 @@ operation! {
     ?metadata: Metadata,
     ?access_tier: AccessTier,
     ?tags: Tags,
-    ?lease_id: LeaseId
+    ?lease_id: LeaseId,
+    ?auto_block_upload: bool,
+    ?cache_control: BlobCacheControl,
+    ?if_modified_since: IfModifiedSinceCondition,
+    ?if_match: IfMatchCondition,
+    ?if_tags: IfTags
 }
 
 impl PutBlockBlobBuilder {
     pub fn into_future(mut self) -> PutBlockBlob {
         Box::pin(async move {
-            let url = self.client.url()?;
-
-            let mut headers = Headers::new();
-            headers.insert(BLOB_TYPE, "BlockBlob");
-            headers.add(self.hash);
-            headers.add(self.content_type);
-            headers.add(self.content_encoding);
-            headers.add(self.content_language);
-            headers.add(self.content_disposition);
-            headers.add(self.tags);
+            let len = self.body.len() as u64;
+            let api_version = self
+                .client
+                .container_client()
+                .service_client()
+                .api_version();
+            let max_size = max_put_blob_size(api_version);
+            if len > max_size {
+                if !self.auto_block_upload.unwrap_or(false) {
+                    return Err(Error::with_message(ErrorKind::Other, || {
+                        format!(
+                            "a body of {len} bytes is larger than the {max_size} bytes a single Put Blob \
+                             request accepts with service version {api_version}; upload it in blocks or \
+                             enable auto_block_upload"
+                        )
+                    }));
+                }
+                let limits = self.client.limits();
+                let min_block_size = limits.min_block_size_for(len)?;
+                let block_size =
+                    (AUTO_BLOCK_SIZE as u64).clamp(min_block_size, limits.max_block_size);
+                return self.upload_in_blocks(block_size as usize).await;
+            }
+
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .header_value(BLOB_TYPE, "BlockBlob")
+                .header(self.hash)
+                .header(self.content_type)
+                .header(self.content_encoding)
+                .header(self.content_language)
+                .header(self.content_disposition)
+                .header(self.cache_control)
+                .header(self.tags);
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
-            headers.add(self.access_tier);
-            headers.add(self.lease_id);
-
-            let mut request = self.client.finalize_request(
-                url,
-                azure_core::Method::Put,
-                headers,
-                Some(self.body),
-            )?;
+            let request = request
+                .header(self.access_tier)
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags)
+                .body(self.body);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             PutBlockBlobResponse::from_headers(response.headers())
         })
     }
+
+    /// Stages the body in blocks of `block_size` bytes and commits them. The
+    /// blocks are uploaded one after the other, so a streamed body is read one
+    /// block at a time.
+    ///
+    /// A CRC64 `hash` cannot be checked by Put Block List, so each block is
+    /// sent with its own CRC64 for the service to check, and the CRC64 of the
+    /// whole body is checked before the blocks are committed.
+    async fn upload_in_blocks(self, block_size: usize) -> azure_core::Result<PutBlockBlobResponse> {
+        let expected_crc64 = match &self.hash {
+            Some(Hash::CRC64(crc64)) => Some(crc64.clone()),
+            _ => None,
+        };
+        let mut crc64 = azure_storage::crypto::Crc64::new();
+        let block_ids = BlockIdSequence::new();
+        let mut block_list = BlockList::new();
+        let mut body = self.body;
+        if let Body::SeekableStream(stream) = &mut body {
+            stream.reset().await?;
+        }
+        let mut offset = 0;
+        loop {
+            let block = match &mut body {
+                Body::Bytes(bytes) => {
+                    let block = bytes.slice(offset..(offset + block_size).min(bytes.len()));
+                    offset += block.len();
+                    block
+                }
+                Body::SeekableStream(stream) => read_block(stream, block_size).await?,
+            };
+            if block.is_empty() {
+                break;
+            }
+            let block_id = block_ids.block_id(block_list.blocks.len() as u64);
+            let mut builder = self.client.put_block(block_id, block.clone());
+            if expected_crc64.is_some() {
+                crc64.update(&block);
+                builder = builder.hash(Hash::CRC64(ConsistencyCRC64::compute(&block)));
+            }
+            if let Some(lease_id) = self.lease_id {
+                builder = builder.lease_id(lease_id);
+            }
+            block_list.push(builder.context(self.context.clone()).await?);
+        }
+        if let Some(expected) = expected_crc64 {
+            if expected != crc64.finalize() {
+                return Err(Error::message(
+                    ErrorKind::DataConversion,
+                    "the CRC64 of the body does not match the CRC64 it was uploaded with",
+                ));
+            }
+        }
+
+        let mut builder = self
+            .client
+            .put_block_list(block_list)
+            .commit_options(BlobCommitOptions {
+                content_type: self.content_type,
+                content_encoding: self.content_encoding,
+                content_language: self.content_language,
+                content_disposition: self.content_disposition,
+                cache_control: self.cache_control,
+                metadata: self.metadata,
+                access_tier: self.access_tier,
+                tags: self.tags,
+                lease_id: self.lease_id,
+                if_modified_since: self.if_modified_since,
+                if_match: self.if_match,
+                if_tags: self.if_tags,
+            })
+            .context(self.context);
+        if let Some(Hash::MD5(md5)) = self.hash {
+            builder = builder.content_md5(md5);
+        }
+        let response = builder.await?;
+
+        Ok(PutBlockBlobResponse {
+            etag: response.etag,
+            last_modified: response.last_modified,
+            content_md5: None,
+            content_crc64: None,
+            request_id: response.request_id,
+            date: response.date,
+            request_server_encrypted: response.request_server_encrypted,
+            encryption_key_sha256: response.encryption_key_sha256,
+            encryption_scope: response.encryption_scope,
+        })
+    }
+
+    /// Sets the options which are set in `options`.
+    pub(crate) fn commit_options(mut self, options: BlobCommitOptions) -> Self {
+        self.content_type = options.content_type.or(self.content_type);
+        self.content_encoding = options.content_encoding.or(self.content_encoding);
+        self.content_language = options.content_language.or(self.content_language);
+        self.content_disposition = options.content_disposition.or(self.content_disposition);
+        self.cache_control = options.cache_control.or(self.cache_control);
+        self.metadata = options.metadata.or(self.metadata);
+        self.access_tier = options.access_tier.or(self.access_tier);
+        self.tags = options.tags.or(self.tags);
+        self.lease_id = options.lease_id.or(self.lease_id);
+        self.if_modified_since = options.if_modified_since.or(self.if_modified_since);
+        self.if_match = options.if_match.or(self.if_match);
+        self.if_tags = options.if_tags.or(self.if_tags);
+        self
+    }
+}
+
+/// Reads up to `block_size` bytes, fewer only at the end of the stream.
+async fn read_block(
+    stream: &mut Box<dyn azure_core::SeekableStream>,
+    block_size: usize,
+) -> azure_core::Result<Bytes> {
+    let mut block = BytesMut::zeroed(block_size);
+    let mut len = 0;
+    while len < block_size {
+        let read = stream
+            .read(&mut block[len..])
+            .await
+            .map_err(|error| Error::new(ErrorKind::Io, error))?;
+        if read == 0 {
+            break;
+        }
+        len += read;
+    }
+    block.truncate(len);
+    Ok(block.freeze())
 }
 
 #[derive(Debug, Clone)]
 pub struct PutBlockBlobResponse {
-    pub etag: String,
+    pub etag: Etag,
     pub last_modified: OffsetDateTime,
     pub content_md5: Option<ConsistencyMD5>,
     pub content_crc64: Option<ConsistencyCRC64>,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
     pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
+    pub encryption_scope: Option<String>,
 }
 
 impl PutBlockBlobResponse {
@@ -71,6 +247,8 @@ impl PutBlockBlobResponse {
         let request_id = request_id_from_headers(headers)?;
         let date = date_from_headers(headers)?;
         let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers);
+        let encryption_scope = encryption_scope_from_headers_optional(headers);
 
         Ok(PutBlockBlobResponse {
             etag,
@@ -80,6 +258,157 @@ impl PutBlockBlobResponse {
             request_id,
             date,
             request_server_encrypted,
+            encryption_key_sha256,
+            encryption_scope,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+    use azure_storage::headers::CONTENT_CRC64;
+    use std::{pin::Pin, task::Poll};
+
+    /// A stream which claims to be larger than what it yields.
+    #[derive(Debug, Clone)]
+    struct Oversized {
+        len: usize,
+        data: Bytes,
+        read: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl azure_core::SeekableStream for Oversized {
+        async fn reset(&mut self) -> azure_core::Result<()> {
+            self.read = 0;
+            Ok(())
+        }
+
+        fn len(&self) -> usize {
+            self.len
+        }
+    }
+
+    impl futures::AsyncRead for Oversized {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            let available = &this.data[this.read..];
+            let read = available.len().min(buf.len());
+            buf[..read].copy_from_slice(&available[..read]);
+            this.read += read;
+            Poll::Ready(Ok(read))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body() -> azure_core::Result<()> {
+        let service = InMemoryTransport::answering(|_| {
+            let mut headers = test_util::version_headers();
+            headers.insert("x-ms-request-server-encrypted", "true");
+            test_util::response(StatusCode::Created, headers)
+        });
+        // blocks are at most 4 MiB with this version, below the usual size
+        let api_version = "2015-12-11";
+        let client = service
+            .client_builder()
+            .api_version(api_version)
+            .blob_client("container", "blob");
+        let body = || {
+            let stream: Box<dyn azure_core::SeekableStream> = Box::new(Oversized {
+                len: max_put_blob_size(api_version) as usize + 1,
+                data: Bytes::from(vec![7; 5 * 1024 * 1024]),
+                read: 0,
+            });
+            Body::from(stream)
+        };
+
+        let error = client.put_block_blob(body()).await.unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::Other);
+        assert!(service.requests().is_empty());
+
+        client
+            .put_block_blob(body())
+            .auto_block_upload(true)
+            .await?;
+        let requests = service.take_requests();
+        assert_eq!(requests.len(), 3);
+        let sizes: Vec<_> = requests[..2]
+            .iter()
+            .map(|request| test_util::body_bytes(request).len())
+            .collect();
+        assert_eq!(sizes, [4 * 1024 * 1024, 1024 * 1024]);
+        assert_eq!(
+            requests[2].url().query().unwrap_or_default(),
+            "comp=blocklist"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_in_blocks() -> azure_core::Result<()> {
+        // answers as Put Block and Put Block List do
+        let service = InMemoryTransport::answering(|_| {
+            let mut headers = test_util::version_headers();
+            headers.insert("x-ms-request-server-encrypted", "true");
+            test_util::response(StatusCode::Created, headers)
+        });
+        let client = service.client_builder().blob_client("container", "blob");
+
+        let response = client
+            .put_block_blob(Bytes::from_static(b"0123456789"))
+            .upload_in_blocks(4)
+            .await?;
+        assert_eq!(response.etag, test_util::ETAG.into());
+
+        let queries: Vec<_> = service
+            .requests()
+            .iter()
+            .map(|request| request.url().query().unwrap_or_default().to_owned())
+            .collect();
+        assert_eq!(queries.len(), 4);
+        assert!(queries[..3]
+            .iter()
+            .all(|query| query.ends_with("comp=block")));
+        assert_eq!(queries[3], "comp=blocklist");
+
+        // each block is checked with its own CRC64
+        service.take_requests();
+        let body = Bytes::from_static(b"0123456789");
+        let crc64 = ConsistencyCRC64::compute(&body);
+        client
+            .put_block_blob(body.clone())
+            .hash(Hash::CRC64(crc64))
+            .upload_in_blocks(4)
+            .await?;
+        let requests = service.take_requests();
+        assert_eq!(
+            requests[0].headers().get_optional_string(&CONTENT_CRC64),
+            Some(ConsistencyCRC64::compute(b"0123").to_string())
+        );
+        assert!(requests[..3]
+            .iter()
+            .all(|request| request.headers().get_optional_str(&CONTENT_CRC64).is_some()));
+
+        // a body which does not match its CRC64 is not committed
+        let error = client
+            .put_block_blob(body)
+            .hash(Hash::CRC64(ConsistencyCRC64::compute(b"other")))
+            .upload_in_blocks(4)
+            .await
+            .unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::DataConversion);
+        assert!(service.requests().iter().all(|request| request
+            .url()
+            .query()
+            .unwrap_or_default()
+            .ends_with("comp=block")));
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId};
-use azure_storage::{headers::content_md5_from_headers, ConsistencyMD5};
-use bytes::Bytes;
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId, SeekableStream};
+use azure_storage::{
+    headers::{
+        consistency_from_headers, encryption_key_sha256_from_headers_optional,
+        encryption_scope_from_headers_optional,
+    },
+    ConsistencyCRC64, ConsistencyMD5, StorageClient,
+};
 use time::OffsetDateTime;
 
 operation! {
@@ -13,84 +18,187 @@ operation! {
     ?content_language: BlobContentLanguage,
     ?content_disposition: BlobContentDisposition,
     ?content_md5: BlobContentMD5,
+    ?cache_control: BlobCacheControl,
     ?metadata: Metadata,
     ?access_tier: AccessTier,
     ?tags: Tags,
-    ?lease_id: LeaseId
+    ?lease_id: LeaseId,
+    ?if_modified_since: IfModifiedSinceCondition,
+    ?if_match: IfMatchCondition,
+    ?if_tags: IfTags
 }
 
 impl PutBlockListBuilder {
     pub fn into_future(mut self) -> PutBlockList {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            self.block_list.validate()?;
 
-            url.query_pairs_mut().append_pair("comp", "blocklist");
-
-            let body = self.block_list.to_xml();
-            let body_bytes = Bytes::from(body);
-
-            // calculate the xml MD5. This can be made optional
-            // if needed, but i think it's best to calculate it.
-            let md5 = {
-                let hash = md5::compute(&body_bytes);
-                base64::encode(hash.0)
-            };
-
-            let mut headers = Headers::new();
-            headers.insert(CONTENT_MD5, &md5);
-            headers.add(self.content_type);
-            headers.add(self.content_encoding);
-            headers.add(self.content_language);
-            headers.add(self.content_disposition);
-            headers.add(self.content_md5);
-            headers.add(self.tags);
+            let mut request = self.client.request(Method::Put)?.comp("blocklist");
+            // the xml MD5 lets the service reject a block list corrupted in
+            // transit
+            #[cfg(feature = "md5")]
+            {
+                let mut md5 = azure_storage::crypto::Md5::new();
+                self.block_list
+                    .for_each_xml_chunk(|chunk| md5.update(chunk));
+                request = request.header(Hash::MD5(md5.finalize()?.into()));
+            }
+            #[cfg(not(feature = "md5"))]
+            {
+                let mut crc64 = azure_storage::crypto::Crc64::new();
+                self.block_list
+                    .for_each_xml_chunk(|chunk| crc64.update(chunk));
+                request = request.header(Hash::CRC64(crc64.finalize().into()));
+            }
+            request = request
+                .header(self.content_type)
+                .header(self.content_encoding)
+                .header(self.content_language)
+                .header(self.content_disposition)
+                .header(self.content_md5)
+                .header(self.cache_control)
+                .header(self.tags);
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
-            headers.add(self.access_tier);
-            headers.add(self.lease_id);
 
-            let mut request = self.client.finalize_request(
-                url,
-                azure_core::Method::Put,
-                headers,
-                Some(body_bytes.into()),
-            )?;
+            // lists of 50,000 blocks take megabytes of XML, which is
+            // generated as it is sent
+            let body: Box<dyn SeekableStream> =
+                Box::new(std::mem::take(&mut self.block_list).into_xml_stream());
+            let request = request
+                .header(self.access_tier)
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags)
+                .body(body);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             PutBlockListResponse::from_headers(response.headers())
         })
     }
+
+    /// Sets the options which are set in `options`.
+    pub(crate) fn commit_options(mut self, options: BlobCommitOptions) -> Self {
+        self.content_type = options.content_type.or(self.content_type);
+        self.content_encoding = options.content_encoding.or(self.content_encoding);
+        self.content_language = options.content_language.or(self.content_language);
+        self.content_disposition = options.content_disposition.or(self.content_disposition);
+        self.cache_control = options.cache_control.or(self.cache_control);
+        self.metadata = options.metadata.or(self.metadata);
+        self.access_tier = options.access_tier.or(self.access_tier);
+        self.tags = options.tags.or(self.tags);
+        self.lease_id = options.lease_id.or(self.lease_id);
+        self.if_modified_since = options.if_modified_since.or(self.if_modified_since);
+        self.if_match = options.if_match.or(self.if_match);
+        self.if_tags = options.if_tags.or(self.if_tags);
+        self
+    }
+}
+
+/// The properties of a blob, and the conditions to create it, which the
+/// uploads made of several requests pass on to the request creating the blob,
+/// Put Blob or Put Block List.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BlobCommitOptions {
+    pub(crate) content_type: Option<BlobContentType>,
+    pub(crate) content_encoding: Option<BlobContentEncoding>,
+    pub(crate) content_language: Option<BlobContentLanguage>,
+    pub(crate) content_disposition: Option<BlobContentDisposition>,
+    pub(crate) cache_control: Option<BlobCacheControl>,
+    pub(crate) metadata: Option<Metadata>,
+    pub(crate) access_tier: Option<AccessTier>,
+    pub(crate) tags: Option<Tags>,
+    pub(crate) lease_id: Option<LeaseId>,
+    pub(crate) if_modified_since: Option<IfModifiedSinceCondition>,
+    pub(crate) if_match: Option<IfMatchCondition>,
+    pub(crate) if_tags: Option<IfTags>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PutBlockListResponse {
-    pub etag: String,
+    pub etag: Etag,
     pub last_modified: OffsetDateTime,
-    pub content_md5: ConsistencyMD5,
+    /// The MD5 of the block list, echoed when it was sent with one.
+    pub content_md5: Option<ConsistencyMD5>,
+    /// The CRC64 of the block list, echoed when it was sent with one.
+    pub content_crc64: Option<ConsistencyCRC64>,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
     pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
+    pub encryption_scope: Option<String>,
 }
 
 impl PutBlockListResponse {
     pub(crate) fn from_headers(headers: &Headers) -> azure_core::Result<PutBlockListResponse> {
         let etag = etag_from_headers(headers)?;
         let last_modified = last_modified_from_headers(headers)?;
-        let content_md5 = content_md5_from_headers(headers)?;
+        let (content_md5, content_crc64) = consistency_from_headers(headers)?;
         let request_id = request_id_from_headers(headers)?;
         let date = date_from_headers(headers)?;
         let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers);
+        let encryption_scope = encryption_scope_from_headers_optional(headers);
 
         Ok(PutBlockListResponse {
             etag,
             last_modified,
             content_md5,
+            content_crc64,
             request_id,
             date,
             request_server_encrypted,
+            encryption_key_sha256,
+            encryption_scope,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+
+    #[tokio::test]
+    async fn test_put_block_list() -> azure_core::Result<()> {
+        // answers with the CRC64 of the block list, or with no hash at all
+        let service = InMemoryTransport::answering(|request| {
+            let mut headers = test_util::version_headers();
+            headers.insert(REQUEST_SERVER_ENCRYPTED, "true");
+            if let Some(crc64) = request
+                .headers()
+                .get_optional_str(&azure_storage::headers::CONTENT_CRC64)
+            {
+                headers.insert(azure_storage::headers::CONTENT_CRC64, crc64.to_owned());
+            }
+            test_util::response(StatusCode::Created, headers)
+        });
+        let client = service.client_builder().blob_client("container", "blob");
+        let mut block_list = BlockList::default();
+        block_list
+            .blocks
+            .push(BlobBlockType::new_uncommitted("block"));
+
+        let response = client.put_block_list(block_list).await?;
+        assert_eq!(response.content_md5, None);
+
+        let request = service.requests().pop().unwrap();
+        let md5 = request.headers().get_optional_string(&CONTENT_MD5);
+        #[cfg(feature = "md5")]
+        {
+            assert!(md5.is_some());
+            assert_eq!(response.content_crc64, None);
+        }
+        #[cfg(not(feature = "md5"))]
+        {
+            assert_eq!(md5, None);
+            assert!(response.content_crc64.is_some());
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,12 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, Body, RequestId};
-use azure_storage::{headers::content_md5_from_headers, ConsistencyMD5};
+use azure_core::{headers::*, prelude::*, Body, Etag, Method, RequestId};
+use azure_storage::{
+    headers::{
+        content_md5_from_headers, encryption_key_sha256_from_headers_optional,
+        encryption_scope_from_headers_optional,
+    },
+    ConsistencyMD5, StorageClient,
+};
 use time::OffsetDateTime;
 
 operation! {
@@ -19,29 +25,22 @@ operation! {
 impl PutPageBuilder {
     pub fn into_future(mut self) -> PutPage {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("page")
+                .header_value(PAGE_WRITE, "update")
+                .header_value(BLOB_TYPE, "PageBlob")
+                .header(self.ba512_range)
+                .header(self.hash)
+                .header(self.if_sequence_number)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags)
+                .header(self.lease_id)
+                .body(self.content);
 
-            url.query_pairs_mut().append_pair("comp", "page");
-
-            let mut headers = Headers::new();
-            headers.insert(PAGE_WRITE, "update");
-            headers.insert(BLOB_TYPE, "PageBlob");
-            headers.add(self.ba512_range);
-            headers.add(self.hash);
-            headers.add(self.if_sequence_number);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.if_tags);
-            headers.add(self.lease_id);
-
-            let mut request = self.client.finalize_request(
-                url,
-                azure_core::Method::Put,
-                headers,
-                Some(self.content),
-            )?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             PutPageResponse::from_headers(response.headers())
         })
     }
@@ -49,13 +48,15 @@ impl PutPageBuilder {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PutPageResponse {
-    pub etag: String,
+    pub etag: Etag,
     pub last_modified: OffsetDateTime,
     pub content_md5: ConsistencyMD5,
     pub sequence_number: u64,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
     pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
+    pub encryption_scope: Option<String>,
 }
 
 impl PutPageResponse {
@@ -67,6 +68,8 @@ impl PutPageResponse {
         let request_id = request_id_from_headers(headers)?;
         let date = date_from_headers(headers)?;
         let request_server_encrypted = request_server_encrypted_from_headers(headers)?;
+        let encryption_key_sha256 = encryption_key_sha256_from_headers_optional(headers);
+        let encryption_scope = encryption_scope_from_headers_optional(headers);
 
         Ok(Self {
             etag,
@@ -76,6 +79,8 @@ impl PutPageResponse {
             request_id,
             date,
             request_server_encrypted,
+            encryption_key_sha256,
+            encryption_scope,
         })
     }
 }
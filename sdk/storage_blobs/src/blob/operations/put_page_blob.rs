@@ -1,8 +1,10 @@
 use crate::{blob::operations::PutBlobResponse, prelude::*};
 use azure_core::{
-    headers::{Headers, BLOB_CONTENT_LENGTH, BLOB_TYPE},
+    headers::{BLOB_CONTENT_LENGTH, BLOB_TYPE},
     prelude::*,
+    Method,
 };
+use azure_storage::StorageClient;
 
 operation! {
     PutPageBlob,
@@ -15,35 +17,40 @@ operation! {
     ?metadata: Metadata,
     ?tags: Tags,
     ?lease_id: LeaseId,
-    ?sequence_number: SequenceNumber
+    ?sequence_number: SequenceNumber,
+    ?cache_control: BlobCacheControl,
+    ?if_modified_since: IfModifiedSinceCondition,
+    ?if_match: IfMatchCondition,
+    ?if_tags: IfTags
 }
 
 impl PutPageBlobBuilder {
     pub fn into_future(mut self) -> PutPageBlob {
         Box::pin(async move {
-            let url = self.client.url()?;
-
-            let mut headers = Headers::new();
-            headers.insert(BLOB_TYPE, "PageBlob");
-            headers.insert(BLOB_CONTENT_LENGTH, &format!("{}", self.length));
-            headers.add(self.content_type);
-            headers.add(self.content_encoding);
-            headers.add(self.content_language);
-            headers.add(self.content_disposition);
-            headers.add(self.tags);
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .header_value(BLOB_TYPE, "PageBlob")
+                .header_value(BLOB_CONTENT_LENGTH, format!("{}", self.length))
+                .header(self.content_type)
+                .header(self.content_encoding)
+                .header(self.content_language)
+                .header(self.content_disposition)
+                .header(self.cache_control)
+                .header(self.tags);
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
-            headers.add(self.lease_id);
-            headers.add(self.sequence_number);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
+            let request = request
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags)
+                .header(self.sequence_number);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             PutBlobResponse::from_headers(response.headers())
         })
     }
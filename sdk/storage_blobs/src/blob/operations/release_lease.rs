@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, RequestId};
+use azure_core::{headers::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -10,19 +11,14 @@ operation! {
 impl ReleaseLeaseBuilder {
     pub fn into_future(mut self) -> ReleaseLease {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("lease")
+                .header_value(LEASE_ACTION, "release")
+                .header(self.client.lease_id());
 
-            url.query_pairs_mut().append_pair("comp", "lease");
-
-            let mut headers = Headers::new();
-            headers.insert(LEASE_ACTION, "release");
-            headers.add(self.client.lease_id());
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             ReleaseLeaseResponse::from_headers(response.headers())
         })
@@ -30,7 +26,7 @@ impl ReleaseLeaseBuilder {
 }
 
 azure_storage::response_from_headers!(ReleaseLeaseResponse ,
-    etag_from_headers => etag: String,
+    etag_from_headers => etag: Etag,
     last_modified_from_headers => last_modified: OffsetDateTime,
     request_id_from_headers => request_id: RequestId,
     date_from_headers => date: OffsetDateTime
@@ -0,0 +1,147 @@
+use crate::prelude::*;
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::{headers::RENAME_SOURCE, StorageClient, StorageRequest};
+use time::OffsetDateTime;
+
+operation! {
+    RenameBlob,
+    client: BlobClient,
+    destination: String,
+    ?if_modified_since: IfModifiedSinceCondition,
+    ?if_match: IfMatchCondition,
+    ?lease_id: LeaseId,
+    ?if_source_since: IfSourceModifiedSinceCondition,
+    ?if_source_match: IfSourceMatchCondition,
+    ?source_lease_id: SourceLeaseId
+}
+
+impl RenameBlobBuilder {
+    pub fn into_future(mut self) -> RenameBlob {
+        Box::pin(async move {
+            let url = self
+                .client
+                .container_client()
+                .blob_client(self.destination.clone())
+                .dfs_url()?;
+
+            let request = StorageRequest::new(url, Method::Put)
+                .header_value(RENAME_SOURCE, self.client.account_relative_path()?)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.lease_id)
+                .header(self.if_source_since)
+                .header(self.if_source_match)
+                .header(self.source_lease_id);
+
+            let response = self.client.execute(&mut self.context, request).await?;
+            RenameBlobResponse::from_headers(response.headers())
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RenameBlobResponse {
+    pub etag: Option<Etag>,
+    pub last_modified: Option<OffsetDateTime>,
+    pub request_id: RequestId,
+    pub date: OffsetDateTime,
+}
+
+impl RenameBlobResponse {
+    pub fn from_headers(headers: &Headers) -> azure_core::Result<Self> {
+        Ok(Self {
+            etag: etag_from_headers_optional(headers)?,
+            last_modified: last_modified_from_headers_optional(headers)?,
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+    use azure_storage::{AddressingMode, CloudLocation, StorageCredentials};
+
+    #[tokio::test]
+    async fn test_rename() -> azure_core::Result<()> {
+        // answers as Path - Create does
+        let service = InMemoryTransport::answering(|_| {
+            test_util::response(StatusCode::Created, test_util::version_headers())
+        });
+        let client = service
+            .client_builder()
+            .blob_client("container", "dir/old name.txt");
+        assert_eq!(
+            client.dfs_url()?.as_str(),
+            "https://account.dfs.core.windows.net/container/dir/old%20name.txt"
+        );
+
+        let response = client.rename("dir/new.txt").await?;
+        assert!(response.etag.is_some());
+
+        let requests: Vec<_> = service
+            .requests()
+            .iter()
+            .map(|request| {
+                (
+                    request.url().to_string(),
+                    request.headers().get_optional_string(&RENAME_SOURCE),
+                )
+            })
+            .collect();
+        assert_eq!(
+            requests,
+            [(
+                "https://account.dfs.core.windows.net/container/dir/new.txt".to_owned(),
+                Some("/container/dir/old%20name.txt".to_owned())
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_dfs_url() -> azure_core::Result<()> {
+        // the emulator has no dfs endpoint
+        let client = ClientBuilder::emulator().blob_client("container", "blob");
+        assert_eq!(client.account_relative_path()?, "/container/blob");
+        assert!(client.dfs_url().is_err());
+
+        let custom = |uri: &str, addressing_mode| {
+            ClientBuilder::with_location(CloudLocation::Custom {
+                uri: uri.to_owned(),
+                credentials: StorageCredentials::anonymous(),
+                addressing_mode,
+            })
+            .blob_client("container", "blob")
+        };
+        let path_style = AddressingMode::PathStyle {
+            account: "account".to_owned(),
+        };
+        assert_eq!(
+            custom(
+                "https://stack.blob.local.azurestack.external",
+                path_style.clone()
+            )
+            .dfs_url()?
+            .as_str(),
+            "https://stack.dfs.local.azurestack.external/account/container/blob"
+        );
+        assert_eq!(
+            custom(
+                "https://account.blob.core.usgovcloudapi.net",
+                AddressingMode::VirtualHost
+            )
+            .dfs_url()?
+            .as_str(),
+            "https://account.dfs.core.usgovcloudapi.net/container/blob"
+        );
+        // rather than sending dfs requests to the blob endpoint
+        assert!(custom("https://storage.example.com", path_style)
+            .dfs_url()
+            .is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Method, RequestId};
+use azure_storage::StorageClient;
 use std::convert::{TryFrom, TryInto};
 
 operation! {
@@ -7,28 +8,26 @@ operation! {
     client: BlobClient,
     access_tier: AccessTier,
     ?rehydrate_priority: RehydratePriority,
-    ?blob_versioning: BlobVersioning
+    ?blob_versioning: BlobVersioning,
+    ?if_tags: IfTags
 }
 
 impl SetBlobTierBuilder {
     pub fn into_future(mut self) -> SetBlobTier {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-            url.query_pairs_mut().append_pair("comp", "tier");
-            self.blob_versioning.append_to_url_query(&mut url);
-
-            let mut headers = Headers::new();
-            headers.add(self.access_tier);
-            headers.add(
-                self.rehydrate_priority
-                    .unwrap_or(RehydratePriority::Standard),
-            );
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("tier")
+                .query(&self.blob_versioning)
+                .header(self.access_tier)
+                .header(
+                    self.rehydrate_priority
+                        .unwrap_or(RehydratePriority::Standard),
+                )
+                .header(self.if_tags);
+
+            let response = self.client.execute(&mut self.context, request).await?;
             response.headers().try_into()
         })
     }
@@ -1,5 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, RequestId, Response};
+use azure_core::{headers::*, prelude::*, Method, RequestId, Response};
+use azure_storage::StorageClient;
 use std::convert::{TryFrom, TryInto};
 
 operation! {
@@ -12,17 +13,14 @@ operation! {
 impl SetBlobExpiryBuilder {
     pub fn into_future(mut self) -> SetBlobExpiry {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-            url.query_pairs_mut().append_pair("comp", "expiry");
-
-            let mut headers = self.blob_expiry.to_headers();
-            headers.add(self.lease_id);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, azure_core::Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("expiry")
+                .header(self.blob_expiry)
+                .header(self.lease_id);
+
+            let response = self.client.execute(&mut self.context, request).await?;
             response.try_into()
         })
     }
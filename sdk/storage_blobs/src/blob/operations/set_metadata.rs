@@ -1,41 +1,46 @@
 use crate::prelude::*;
-use azure_core::{headers::*, prelude::*, Method, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::{
+    headers::{
+        encryption_key_sha256_from_headers_optional, encryption_scope_from_headers_optional,
+    },
+    StorageClient,
+};
 use std::convert::{TryFrom, TryInto};
 use time::OffsetDateTime;
 
 operation! {
+    /// Replaces the user-defined metadata of the blob, without changing its
+    /// content. Metadata not passed is removed.
     SetMetadata,
     client: BlobClient,
     ?if_modified_since: IfModifiedSinceCondition,
     ?if_match: IfMatchCondition,
     ?if_tags: IfTags,
     ?lease_id: LeaseId,
+    ?encryption_scope: EncryptionScope,
     ?metadata: Metadata
 }
 
 impl SetMetadataBuilder {
     pub fn into_future(mut self) -> SetMetadata {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "metadata");
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.if_tags);
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .comp("metadata")
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags)
+                .header(self.encryption_scope);
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
 
-            let mut request = self
-                .client
-                .finalize_request(url, Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             response.headers().try_into()
         })
     }
@@ -44,9 +49,13 @@ impl SetMetadataBuilder {
 #[derive(Debug, Clone)]
 pub struct SetMetadataResponse {
     pub request_id: RequestId,
-    pub etag: String,
+    pub etag: Etag,
+    pub last_modified: OffsetDateTime,
     pub server: String,
     pub date: OffsetDateTime,
+    pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
+    pub encryption_scope: Option<String>,
 }
 
 impl TryFrom<&Headers> for SetMetadataResponse {
@@ -56,8 +65,59 @@ impl TryFrom<&Headers> for SetMetadataResponse {
         Ok(SetMetadataResponse {
             request_id: request_id_from_headers(headers)?,
             etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
             server: server_from_headers(headers)?,
             date: date_from_headers(headers)?,
+            request_server_encrypted: request_server_encrypted_from_headers(headers)?,
+            encryption_key_sha256: encryption_key_sha256_from_headers_optional(headers),
+            encryption_scope: encryption_scope_from_headers_optional(headers),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+
+    #[tokio::test]
+    async fn test_set_metadata() -> azure_core::Result<()> {
+        let service = InMemoryTransport::answering(|_| {
+            let mut headers = test_util::version_headers();
+            headers.insert("server", "Windows-Azure-Blob/1.0");
+            headers.insert("x-ms-request-server-encrypted", "true");
+            headers.insert("x-ms-encryption-scope", "scope1");
+            test_util::response(StatusCode::Ok, headers)
+        });
+        let blob_client = service.client_builder().blob_client("container", "blob");
+
+        let mut metadata = Metadata::new();
+        metadata.insert("owner", "ops");
+        let response = blob_client
+            .set_metadata()
+            .metadata(metadata)
+            .lease_id(uuid::Uuid::nil().to_string().parse::<LeaseId>().unwrap())
+            .if_match(IfMatchCondition::Match("\"0x8D93C7D4629C226\"".into()))
+            .encryption_scope("scope1")
+            .await?;
+        assert!(response.request_server_encrypted);
+        assert_eq!(response.encryption_scope.as_deref(), Some("scope1"));
+
+        let request = service.take_requests().pop().unwrap();
+        assert_eq!(request.method(), &Method::Put);
+        assert_eq!(request.url().query(), Some("comp=metadata"));
+        let headers = request.headers();
+        assert_eq!(
+            headers.get_str(&HeaderName::from("x-ms-meta-owner"))?,
+            "ops"
+        );
+        assert_eq!(headers.get_str(&LEASE_ID)?, uuid::Uuid::nil().to_string());
+        assert_eq!(headers.get_str(&IF_MATCH)?, "\"0x8D93C7D4629C226\"");
+        assert_eq!(
+            headers.get_str(&azure_storage::headers::ENCRYPTION_SCOPE)?,
+            "scope1"
+        );
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
-use crate::{blob::BlobProperties, prelude::*};
+use crate::{
+    blob::{BlobHttpHeaders, BlobProperties},
+    prelude::*,
+};
 use azure_core::prelude::*;
 use azure_core::{
     headers::{
         date_from_headers, etag_from_headers, request_id_from_headers, server_from_headers, Headers,
     },
-    Method, RequestId,
+    Etag, Method, RequestId,
 };
+use azure_storage::StorageClient;
 use std::convert::{TryFrom, TryInto};
 use time::OffsetDateTime;
 
@@ -25,53 +29,52 @@ operation! {
 }
 
 impl SetPropertiesBuilder {
-    pub fn set_from_blob_properties(self, blob_properties: BlobProperties) -> Self {
-        let mut s = self;
-
-        if let Some(cc) = blob_properties.cache_control {
-            s = s.cache_control(cc);
-        }
-        if !blob_properties.content_type.is_empty() {
-            s = s.content_type(blob_properties.content_type);
-        }
-        if let Some(ce) = blob_properties.content_encoding {
-            s = s.content_encoding(ce);
+    /// Sets all the HTTP headers of the blob. Those which are `None` are
+    /// cleared, as the service does with headers left out of the request.
+    pub fn http_headers(self, http_headers: impl Into<BlobHttpHeaders>) -> Self {
+        let BlobHttpHeaders {
+            cache_control,
+            content_type,
+            content_encoding,
+            content_language,
+            content_disposition,
+            content_md5,
+        } = http_headers.into();
+        Self {
+            cache_control,
+            content_type,
+            content_encoding,
+            content_language,
+            content_disposition,
+            content_md5,
+            ..self
         }
-        if let Some(cl) = blob_properties.content_language {
-            s = s.content_language(cl);
-        }
-        if let Some(cd) = blob_properties.content_disposition {
-            s = s.content_disposition(cd);
-        }
-        if let Some(cmd5) = blob_properties.content_md5 {
-            s = s.content_md5(cmd5);
-        }
-        s
+    }
+
+    /// Sets the HTTP headers of the blob to those of `blob_properties`, see
+    /// [`BlobHttpHeaders`].
+    pub fn set_from_blob_properties(self, blob_properties: BlobProperties) -> Self {
+        self.http_headers(&blob_properties)
     }
 
     pub fn into_future(mut self) -> SetProperties {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "properties");
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.cache_control);
-            headers.add(self.content_type);
-            headers.add(self.content_encoding);
-            headers.add(self.content_language);
-            headers.add(self.content_disposition);
-            headers.add(self.content_md5);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
-            headers.add(self.if_tags);
-
-            let mut request = self
+            let request = self
                 .client
-                .finalize_request(url, Method::Put, headers, None)?;
+                .request(Method::Put)?
+                .comp("properties")
+                .header(self.lease_id)
+                .header(self.cache_control)
+                .header(self.content_type)
+                .header(self.content_encoding)
+                .header(self.content_language)
+                .header(self.content_disposition)
+                .header(self.content_md5)
+                .header(self.if_modified_since)
+                .header(self.if_match)
+                .header(self.if_tags);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             response.headers().try_into()
         })
     }
@@ -80,7 +83,7 @@ impl SetPropertiesBuilder {
 #[derive(Debug, Clone)]
 pub struct SetPropertiesResponse {
     pub request_id: RequestId,
-    pub etag: String,
+    pub etag: Etag,
     pub server: String,
     pub date: OffsetDateTime,
 }
@@ -97,3 +100,54 @@ impl TryFrom<&Headers> for SetPropertiesResponse {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob::Blob;
+    use azure_core::xml::read_xml;
+    use azure_storage::StorageCredentials;
+
+    #[test]
+    fn test_set_from_blob_properties() {
+        const BLOB: &[u8] = b"<Blob>
+            <Name>blob0.txt</Name>
+            <Properties>
+                <Creation-Time>Thu, 01 Jul 2021 10:44:59 GMT</Creation-Time>
+                <Last-Modified>Thu, 01 Jul 2021 10:44:59 GMT</Last-Modified>
+                <Etag>0x8D93C7D4629C227</Etag>
+                <Content-Length>8</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+                <Content-Encoding />
+                <Content-Language>en-US</Content-Language>
+                <Content-MD5>rvr3UC1SmUw7AZV2NqPN0g==</Content-MD5>
+                <Cache-Control>no-cache</Cache-Control>
+                <Content-Disposition />
+                <BlobType>BlockBlob</BlobType>
+                <ServerEncrypted>true</ServerEncrypted>
+            </Properties>
+        </Blob>";
+        let blob: Blob = read_xml(BLOB).unwrap();
+
+        let builder = ClientBuilder::new("account", StorageCredentials::Anonymous)
+            .blob_client("container", "blob0.txt")
+            .set_properties()
+            .content_encoding("gzip")
+            .set_from_blob_properties(blob.properties.clone());
+        assert_eq!(builder.content_type, Some("text/plain".into()));
+        assert_eq!(builder.content_language, Some("en-US".into()));
+        assert_eq!(builder.cache_control, Some("no-cache".into()));
+        assert_eq!(builder.content_encoding, None);
+        assert_eq!(builder.content_disposition, None);
+        assert_eq!(
+            builder.content_md5.as_ref().map(|md5| md5.to_string()),
+            Some("rvr3UC1SmUw7AZV2NqPN0g==".to_owned())
+        );
+
+        let http_headers =
+            BlobHttpHeaders::from(&blob.properties).content_disposition("attachment");
+        let builder = builder.http_headers(http_headers);
+        assert_eq!(builder.content_disposition, Some("attachment".into()));
+        assert_eq!(builder.content_type, Some("text/plain".into()));
+    }
+}
@@ -4,6 +4,7 @@ use azure_core::{
     headers::{date_from_headers, request_id_from_headers, Headers},
     Method, RequestId,
 };
+use azure_storage::StorageClient;
 use std::convert::{TryFrom, TryInto};
 use time::OffsetDateTime;
 
@@ -18,20 +19,15 @@ operation! {
 impl SetTagsBuilder {
     pub fn into_future(mut self) -> SetTags {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "tags");
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_tags);
-            let body = self.tags.to_xml()?;
-
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Put, headers, Some(body.into()))?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .comp("tags")
+                .header(self.lease_id)
+                .header(self.if_tags)
+                .body(self.tags.to_xml()?);
+
+            let response = self.client.execute(&mut self.context, request).await?;
             response.headers().try_into()
         })
     }
@@ -3,9 +3,9 @@ use azure_core::headers::etag_from_headers;
 use azure_core::{
     headers::{date_from_headers, last_modified_from_headers, request_id_from_headers, Headers},
     prelude::*,
-    Method::Put,
-    RequestId,
+    Etag, Method, RequestId,
 };
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -20,23 +20,20 @@ operation! {
 impl SnapshotBlobBuilder {
     pub fn into_future(mut self) -> SnapshotBlob {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "snapshot");
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_modified_since);
-            headers.add(self.if_match);
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .comp("snapshot")
+                .header(self.lease_id)
+                .header(self.if_modified_since)
+                .header(self.if_match);
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
 
-            let mut request = self.client.finalize_request(url, Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             response.headers().try_into()
         })
     }
@@ -45,7 +42,7 @@ impl SnapshotBlobBuilder {
 #[derive(Debug, Clone)]
 pub struct SnapshotBlobResponse {
     pub request_id: RequestId,
-    pub etag: String,
+    pub etag: Etag,
     pub date: OffsetDateTime,
     pub snapshot: Snapshot,
     pub last_modified: OffsetDateTime,
@@ -59,7 +56,7 @@ impl TryFrom<&Headers> for SnapshotBlobResponse {
             request_id: request_id_from_headers(headers)?,
             etag: etag_from_headers(headers)?,
             date: date_from_headers(headers)?,
-            snapshot: Snapshot::new(headers.get_str(&SNAPSHOT)?.to_string()),
+            snapshot: headers.get_as(&SNAPSHOT)?,
             last_modified: last_modified_from_headers(headers)?,
         })
     }
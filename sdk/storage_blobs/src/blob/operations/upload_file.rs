@@ -0,0 +1,586 @@
+use super::{autotune, file_io, BlobCommitOptions};
+use crate::{
+    limits::Limits,
+    options::Autotuner,
+    prelude::*,
+    transform::{ChunkInfo, ChunkTransforms},
+};
+use azure_core::{
+    error::{Error, ErrorKind, ResultExt},
+    prelude::*,
+    Etag, RequestId,
+};
+use bytes::Bytes;
+use futures::{stream, StreamExt, TryStreamExt};
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use time::OffsetDateTime;
+
+/// Files up to this size are uploaded with a single Put Blob request.
+pub const DEFAULT_SINGLE_UPLOAD_THRESHOLD: u64 = 32 * 1024 * 1024;
+/// Size of the blocks staged for files larger than the single upload threshold.
+pub const DEFAULT_UPLOAD_BLOCK_SIZE: u64 = 4 * 1024 * 1024;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+operation! {
+    UploadFile,
+    client: BlobClient,
+    path: PathBuf,
+    ?single_upload_threshold: u64,
+    ?block_size: u64,
+    ?max_concurrency: usize,
+    ?autotune: AutotuneOptions,
+    ?memory_map: bool,
+    ?gzip: bool,
+    ?transforms: ChunkTransforms,
+    ?content_type: BlobContentType,
+    ?content_encoding: BlobContentEncoding,
+    ?content_language: BlobContentLanguage,
+    ?content_disposition: BlobContentDisposition,
+    ?metadata: Metadata,
+    ?access_tier: AccessTier,
+    ?tags: Tags,
+    ?lease_id: LeaseId
+}
+
+impl UploadFileBuilder {
+    pub fn into_future(mut self) -> UploadFile {
+        Box::pin(async move {
+            let mut source = file_io::blocking(|| {
+                FileSource::open(&self.path, self.memory_map.unwrap_or(false))
+            })?;
+            let len = source.len();
+            let content_type = self
+                .content_type
+                .take()
+                .or_else(|| content_type_from_extension(&self.path).map(BlobContentType::from));
+            let gzip = self.gzip.unwrap_or(false);
+            let content_encoding = if gzip {
+                Some(gzip_content_encoding(self.content_encoding.take())?)
+            } else {
+                self.content_encoding.take()
+            };
+            let transforms = self.transforms.take().unwrap_or_default();
+            let commit_options = BlobCommitOptions {
+                content_type,
+                content_encoding,
+                content_language: self.content_language.take(),
+                content_disposition: self.content_disposition.take(),
+                metadata: self.metadata.take(),
+                access_tier: self.access_tier.take(),
+                tags: self.tags.take(),
+                lease_id: self.lease_id,
+                ..BlobCommitOptions::default()
+            };
+
+            if len
+                <= self
+                    .single_upload_threshold
+                    .unwrap_or(DEFAULT_SINGLE_UPLOAD_THRESHOLD)
+            {
+                let body = prepare(
+                    source.read_at(0, len)?,
+                    gzip,
+                    &transforms,
+                    ChunkInfo {
+                        index: 0,
+                        offset: 0,
+                    },
+                )?;
+                #[cfg(feature = "md5")]
+                let content_md5 = Some(BlobContentMD5::from(azure_storage::crypto::md5(&body)?));
+                #[cfg(not(feature = "md5"))]
+                let content_md5: Option<BlobContentMD5> = None;
+                #[cfg(not(feature = "md5"))]
+                let crc64 = azure_storage::ConsistencyCRC64::compute(&body);
+
+                let mut builder = self
+                    .client
+                    .put_block_blob(body)
+                    .commit_options(commit_options)
+                    .context(self.context.clone());
+                if let Some(content_md5) = &content_md5 {
+                    builder = builder.hash(content_md5.clone());
+                }
+                // without MD5 the service checks the upload with its CRC64
+                #[cfg(not(feature = "md5"))]
+                {
+                    builder = builder.hash(Hash::CRC64(crc64));
+                }
+
+                let response = builder.await?;
+                return Ok(UploadFileResponse {
+                    etag: response.etag,
+                    last_modified: response.last_modified,
+                    content_md5,
+                    request_id: response.request_id,
+                    date: response.date,
+                    request_server_encrypted: response.request_server_encrypted,
+                    encryption_key_sha256: response.encryption_key_sha256,
+                    encryption_scope: response.encryption_scope,
+                });
+            }
+
+            let block_size = self.block_size.unwrap_or(DEFAULT_UPLOAD_BLOCK_SIZE);
+            let max_concurrency = self
+                .max_concurrency
+                .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+                .max(1);
+            let limits = self.client.limits();
+
+            #[cfg(feature = "md5")]
+            let mut md5 = azure_storage::crypto::Md5::new();
+            let block_ids = BlockIdSequence::new();
+            let client = &self.client;
+            let context = &self.context;
+            let put_block = |index: u64, data: Bytes| {
+                #[cfg(not(feature = "md5"))]
+                let crc64 = azure_storage::ConsistencyCRC64::compute(&data);
+                let put_block = client
+                    .put_block(block_ids.block_id(index), data)
+                    .context(context.clone());
+                #[cfg(not(feature = "md5"))]
+                let put_block = put_block.hash(Hash::CRC64(crc64));
+                put_block.into_future()
+            };
+
+            let block_list = match (gzip, self.autotune.clone()) {
+                // the whole file is compressed as a single gzip member, which
+                // is cut into blocks: blocks compressed one by one would make
+                // a stream of many members, which some decoders truncate to
+                // the first one. The size of the compressed blocks is only
+                // known once compressed, so they are not autotuned.
+                #[cfg(feature = "gzip")]
+                (true, _) => {
+                    // only checks the block size, as the block count is unknown
+                    block_count(&limits, 0, block_size)?;
+                    let mut offset = 0;
+                    let blocks = stream::iter(GzipBlocks::new(&mut source, block_size).zip(0..))
+                        .map(|(data, index)| {
+                            limits.validate_blocks(block_size, index + 1)?;
+                            let data = data?;
+                            let chunk = ChunkInfo { index, offset };
+                            offset += data.len() as u64;
+                            let data = transforms.upload(&chunk, data)?;
+                            #[cfg(feature = "md5")]
+                            md5.update(&data);
+                            Ok(put_block(index, data))
+                        })
+                        .try_buffered(max_concurrency);
+                    BlockList::try_from_stream(blocks).await?
+                }
+                (_, Some(autotune)) => {
+                    let tuner = Autotuner::new(
+                        autotune,
+                        max_concurrency,
+                        block_size,
+                        limits.min_block_size_for(len)?,
+                        limits.max_block_size,
+                        Instant::now(),
+                    );
+                    // throttled blocks are read again, but hashed only once
+                    #[cfg(feature = "md5")]
+                    let mut hashed = 0;
+                    let blocks = autotune::transfer(
+                        tuner,
+                        len,
+                        |index, range| {
+                            let data = prepare(
+                                source.read_at(range.start, range.len())?,
+                                false,
+                                &transforms,
+                                ChunkInfo {
+                                    index,
+                                    offset: range.start,
+                                },
+                            )?;
+                            #[cfg(feature = "md5")]
+                            if range.start == hashed {
+                                md5.update(&data);
+                                hashed = range.end;
+                            }
+                            Ok(put_block(index, data))
+                        },
+                        |_, _, _| Ok(()),
+                    )
+                    .await?;
+                    (0..blocks)
+                        .map(|index| BlobBlockType::new_uncommitted(block_ids.block_id(index)))
+                        .collect()
+                }
+                (_, None) => {
+                    let block_count = block_count(&limits, len, block_size)?;
+                    let blocks = stream::iter(0..block_count)
+                        .map(|index| {
+                            let offset = index * block_size;
+                            let data = prepare(
+                                source.read_at(offset, block_size.min(len - offset))?,
+                                false,
+                                &transforms,
+                                ChunkInfo { index, offset },
+                            )?;
+                            #[cfg(feature = "md5")]
+                            md5.update(&data);
+                            Ok(put_block(index, data))
+                        })
+                        .try_buffered(max_concurrency);
+                    BlockList::try_from_stream(blocks).await?
+                }
+            };
+            #[cfg(feature = "md5")]
+            let content_md5 = Some(BlobContentMD5::from(md5.finalize()?));
+            #[cfg(not(feature = "md5"))]
+            let content_md5: Option<BlobContentMD5> = None;
+
+            let mut builder = self
+                .client
+                .put_block_list(block_list)
+                .commit_options(commit_options)
+                .context(self.context.clone());
+            if let Some(content_md5) = &content_md5 {
+                builder = builder.content_md5(content_md5.clone());
+            }
+
+            let response = builder.await?;
+            Ok(UploadFileResponse {
+                etag: response.etag,
+                last_modified: response.last_modified,
+                content_md5,
+                request_id: response.request_id,
+                date: response.date,
+                request_server_encrypted: response.request_server_encrypted,
+                encryption_key_sha256: response.encryption_key_sha256,
+                encryption_scope: response.encryption_scope,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadFileResponse {
+    pub etag: Etag,
+    pub last_modified: OffsetDateTime,
+    /// MD5 of the uploaded content, i.e. of the source file once compressed
+    /// and transformed, stored as the blob's `Content-MD5`. Only computed with
+    /// the `md5` feature.
+    pub content_md5: Option<BlobContentMD5>,
+    pub request_id: RequestId,
+    pub date: OffsetDateTime,
+    pub request_server_encrypted: bool,
+    pub encryption_key_sha256: Option<String>,
+    pub encryption_scope: Option<String>,
+}
+
+/// The file being uploaded, either read block by block or memory mapped.
+enum FileSource {
+    File {
+        file: File,
+        len: u64,
+    },
+    #[cfg(feature = "mmap")]
+    Mapped(Bytes),
+}
+
+impl FileSource {
+    fn open(path: &Path, memory_map: bool) -> azure_core::Result<Self> {
+        let file = File::open(path).with_context(ErrorKind::Io, || {
+            format!("failed to open {}", path.display())
+        })?;
+        let len = file
+            .metadata()
+            .with_context(ErrorKind::Io, || {
+                format!("failed to read {}", path.display())
+            })?
+            .len();
+
+        // an empty file cannot be mapped, and has nothing to map anyway
+        if !memory_map || len == 0 {
+            return Ok(FileSource::File { file, len });
+        }
+
+        #[cfg(feature = "mmap")]
+        {
+            // SAFETY: the file must not be modified while it is being uploaded,
+            // which is the same requirement as for a consistent Content-MD5.
+            let mmap = unsafe { memmap2::Mmap::map(&file) }.with_context(ErrorKind::Io, || {
+                format!("failed to memory map {}", path.display())
+            })?;
+            Ok(FileSource::Mapped(Bytes::from_owner(mmap)))
+        }
+        #[cfg(not(feature = "mmap"))]
+        Err(Error::message(
+            ErrorKind::Other,
+            "memory mapped uploads require the `mmap` feature",
+        ))
+    }
+
+    fn len(&self) -> u64 {
+        match self {
+            FileSource::File { len, .. } => *len,
+            #[cfg(feature = "mmap")]
+            FileSource::Mapped(bytes) => bytes.len() as u64,
+        }
+    }
+
+    /// Reads `len` bytes from `offset`. Mapped files are sliced without
+    /// copying.
+    fn read_at(&mut self, offset: u64, len: u64) -> azure_core::Result<Bytes> {
+        match self {
+            FileSource::File { file, .. } => {
+                let mut buffer = vec![0; len as usize];
+                file_io::blocking(|| {
+                    file.seek(SeekFrom::Start(offset))
+                        .and_then(|_| file.read_exact(&mut buffer))
+                })
+                .context(ErrorKind::Io, "failed to read file")?;
+                Ok(buffer.into())
+            }
+            #[cfg(feature = "mmap")]
+            FileSource::Mapped(bytes) => Ok(bytes.slice(offset as usize..(offset + len) as usize)),
+        }
+    }
+}
+
+/// The content encoding of a gzip compressed upload, which has to be gzip if
+/// one is set.
+fn gzip_content_encoding(
+    content_encoding: Option<BlobContentEncoding>,
+) -> azure_core::Result<BlobContentEncoding> {
+    #[cfg(feature = "gzip")]
+    {
+        use crate::compression::{is_gzip, GZIP};
+        match content_encoding {
+            Some(content_encoding) if !is_gzip(content_encoding.as_str()) => {
+                Err(Error::with_message(ErrorKind::Other, || {
+                    format!(
+                        "a gzip compressed upload cannot have a content encoding of {}",
+                        content_encoding.as_str()
+                    )
+                }))
+            }
+            _ => Ok(BlobContentEncoding::from_static(GZIP)),
+        }
+    }
+    #[cfg(not(feature = "gzip"))]
+    {
+        let _ = content_encoding;
+        Err(Error::message(
+            ErrorKind::Other,
+            "gzip compressed uploads require the `gzip` feature",
+        ))
+    }
+}
+
+/// Compresses the body of gzip compressed uploads made of a single request.
+fn compress(data: Bytes, gzip: bool) -> azure_core::Result<Bytes> {
+    #[cfg(feature = "gzip")]
+    if gzip {
+        return crate::compression::gzip(&data);
+    }
+    // without the feature, gzip uploads are rejected by gzip_content_encoding
+    #[cfg(not(feature = "gzip"))]
+    let _ = gzip;
+    Ok(data)
+}
+
+/// Turns a chunk read from the file into what is uploaded: its gzip
+/// compression if asked for, then the output of the transforms.
+fn prepare(
+    data: Bytes,
+    gzip: bool,
+    transforms: &ChunkTransforms,
+    chunk: ChunkInfo,
+) -> azure_core::Result<Bytes> {
+    transforms.upload(&chunk, compress(data, gzip)?)
+}
+
+/// The blocks of a gzip compressed upload: the file is read block by block
+/// and compressed as a single gzip member, which is cut into blocks.
+#[cfg(feature = "gzip")]
+struct GzipBlocks<'a> {
+    source: &'a mut FileSource,
+    offset: u64,
+    block_size: u64,
+    encoder: Option<crate::compression::GzipBlocks>,
+    blocks: std::collections::VecDeque<Bytes>,
+}
+
+#[cfg(feature = "gzip")]
+impl<'a> GzipBlocks<'a> {
+    fn new(source: &'a mut FileSource, block_size: u64) -> Self {
+        Self {
+            source,
+            offset: 0,
+            block_size,
+            encoder: Some(crate::compression::GzipBlocks::new(block_size as usize)),
+            blocks: Default::default(),
+        }
+    }
+
+    /// Compresses the file until a block is complete, or the file ended.
+    fn fill(&mut self) -> azure_core::Result<()> {
+        while self.blocks.is_empty() {
+            let encoder = match &mut self.encoder {
+                Some(encoder) => encoder,
+                None => return Ok(()),
+            };
+            let len = self.block_size.min(self.source.len() - self.offset);
+            let blocks = if len == 0 {
+                self.encoder.take().unwrap().finish()?
+            } else {
+                let data = self.source.read_at(self.offset, len)?;
+                self.offset += len;
+                encoder.write(&data)?
+            };
+            self.blocks.extend(blocks);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl Iterator for GzipBlocks<'_> {
+    type Item = azure_core::Result<Bytes>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(error) = self.fill() {
+            self.encoder = None;
+            return Some(Err(error));
+        }
+        self.blocks.pop_front().map(Ok)
+    }
+}
+
+fn block_count(limits: &Limits, len: u64, block_size: u64) -> azure_core::Result<u64> {
+    if block_size == 0 {
+        return Err(Error::message(
+            ErrorKind::Other,
+            "block size must be greater than zero",
+        ));
+    }
+    let count = (len + block_size - 1) / block_size;
+    limits
+        .validate_blocks(block_size, count)
+        .with_context(ErrorKind::Other, || {
+            format!("a file of {len} bytes cannot be uploaded in blocks of {block_size} bytes")
+        })?;
+    Ok(count)
+}
+
+/// Guesses the content type of a file from its extension.
+fn content_type_from_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let content_type = match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "htm" | "html" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "js" | "mjs" => "text/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "wasm" => "application/wasm",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => return None,
+    };
+    Some(content_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_from_extension() {
+        assert_eq!(
+            content_type_from_extension(Path::new("dir/index.HTML")),
+            Some("text/html")
+        );
+        assert_eq!(
+            content_type_from_extension(Path::new("photo.jpeg")),
+            Some("image/jpeg")
+        );
+        assert_eq!(content_type_from_extension(Path::new("Makefile")), None);
+        assert_eq!(content_type_from_extension(Path::new("data.bin")), None);
+    }
+
+    #[test]
+    fn test_block_count() {
+        let limits = Limits::for_api_version("2019-12-12");
+        assert_eq!(block_count(&limits, 0, 4).unwrap(), 0);
+        assert_eq!(block_count(&limits, 8, 4).unwrap(), 2);
+        assert_eq!(block_count(&limits, 9, 4).unwrap(), 3);
+        assert!(block_count(&limits, 9, 0).is_err());
+        assert!(block_count(&limits, limits.max_blocks + 1, 1).is_err());
+        assert!(block_count(&limits, 1, limits.max_block_size + 1).is_err());
+    }
+
+    #[test]
+    fn test_gzip() {
+        let data = Bytes::from_static(b"line\nline\nline\n");
+        assert_eq!(compress(data.clone(), false).unwrap(), data);
+        #[cfg(feature = "gzip")]
+        {
+            let compressed = compress(data.clone(), true).unwrap();
+            assert_eq!(crate::compression::gunzip(&compressed).unwrap(), data);
+            assert_eq!(gzip_content_encoding(None).unwrap().as_str(), "gzip");
+            assert!(gzip_content_encoding(Some("x-gzip".into())).is_ok());
+            assert!(gzip_content_encoding(Some("br".into())).is_err());
+        }
+        #[cfg(not(feature = "gzip"))]
+        assert!(gzip_content_encoding(None).is_err());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn test_gzip_blocks() -> azure_core::Result<()> {
+        use crate::dev::InMemoryTransport;
+        use azure_storage::StorageCredentials;
+
+        let transport = InMemoryTransport::new();
+        let blob_client = ClientBuilder::new("account", StorageCredentials::anonymous())
+            .transport(transport.clone())
+            .blob_client("container", "blob.log");
+        let content: Vec<u8> = (0..20_000u32)
+            .flat_map(|i| format!("{} {}\n", i, i * 7919 % 10_007).into_bytes())
+            .collect();
+        let path = std::env::temp_dir().join(format!("{}.log", uuid::Uuid::new_v4()));
+        std::fs::write(&path, &content).unwrap();
+
+        blob_client
+            .upload_file(&path)
+            .single_upload_threshold(0u64)
+            .block_size(4096u64)
+            .gzip(true)
+            .await?;
+        std::fs::remove_file(&path).unwrap();
+
+        // the blob is a single gzip member, so a decoder reading only the
+        // first one sees the whole file
+        let stored = transport.blob_content("container", "blob.log").unwrap();
+        assert!(stored.len() > 4096);
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(stored.as_ref())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, content);
+        Ok(())
+    }
+}
@@ -0,0 +1,347 @@
+use crate::{
+    prelude::*,
+    transform::{ChunkInfo, ChunkTransforms},
+};
+use azure_core::{prelude::*, Etag, RequestId};
+use bytes::{Bytes, BytesMut};
+use futures::{
+    future::{self, Either},
+    stream::FuturesUnordered,
+    Stream, TryStreamExt,
+};
+use time::OffsetDateTime;
+
+/// Size of the blocks staged while uploading a stream.
+pub const DEFAULT_STREAM_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Uploads a stream of bytes to a block blob.
+///
+/// The stream is only polled while fewer than `max_concurrency` blocks are
+/// being staged, so a fast producer is held back by a slow upload and at most
+/// `(max_concurrency + 1) * block_size` bytes are buffered. A stream which
+/// ends before filling a block is uploaded with a single Put Blob request.
+pub struct UploadStreamBuilder<S> {
+    client: BlobClient,
+    stream: S,
+    block_size: Option<usize>,
+    max_concurrency: Option<usize>,
+    transforms: Option<ChunkTransforms>,
+    content_type: Option<BlobContentType>,
+    content_encoding: Option<BlobContentEncoding>,
+    metadata: Option<Metadata>,
+    access_tier: Option<AccessTier>,
+    lease_id: Option<LeaseId>,
+    context: Context,
+}
+
+impl<S> UploadStreamBuilder<S>
+where
+    S: Stream<Item = azure_core::Result<Bytes>> + Send + 'static,
+{
+    pub(crate) fn new(client: BlobClient, stream: S) -> Self {
+        Self {
+            client,
+            stream,
+            block_size: None,
+            max_concurrency: None,
+            transforms: None,
+            content_type: None,
+            content_encoding: None,
+            metadata: None,
+            access_tier: None,
+            lease_id: None,
+            context: Context::new(),
+        }
+    }
+
+    setters! {
+        block_size: usize => Some(block_size),
+        max_concurrency: usize => Some(max_concurrency),
+        transforms: ChunkTransforms => Some(transforms),
+        content_type: BlobContentType => Some(content_type),
+        content_encoding: BlobContentEncoding => Some(content_encoding),
+        metadata: Metadata => Some(metadata),
+        access_tier: AccessTier => Some(access_tier),
+        lease_id: LeaseId => Some(lease_id),
+        context: Context => context,
+    }
+
+    pub fn into_future(self) -> UploadStream {
+        let Self {
+            client,
+            stream,
+            block_size,
+            max_concurrency,
+            transforms,
+            content_type,
+            content_encoding,
+            metadata,
+            access_tier,
+            lease_id,
+            context,
+        } = self;
+        Box::pin(async move {
+            let block_size = block_size.unwrap_or(DEFAULT_STREAM_BLOCK_SIZE).max(1);
+            let limits = client.limits();
+            limits.validate_blocks(block_size as u64, 1)?;
+            let max_concurrency = max_concurrency.unwrap_or(DEFAULT_MAX_CONCURRENCY).max(1);
+            let transforms = transforms.unwrap_or_default();
+
+            futures::pin_mut!(stream);
+            let mut buffer = BytesMut::new();
+            let mut len = 0;
+            let block_ids = BlockIdSequence::new();
+            let mut blocks = 0;
+            let mut staging = FuturesUnordered::new();
+            let mut ended = false;
+
+            while !ended {
+                // the blocks are staged while the stream is read, and once
+                // max_concurrency of them are in flight, the stream waits for
+                // one to complete
+                let next = loop {
+                    if staging.len() >= max_concurrency {
+                        staging.try_next().await?;
+                    } else if staging.is_empty() {
+                        break stream.try_next().await?;
+                    } else {
+                        match future::select(staging.try_next(), stream.try_next()).await {
+                            Either::Left((staged, _)) => {
+                                staged?;
+                            }
+                            Either::Right((next, _)) => break next?,
+                        }
+                    }
+                };
+                match next {
+                    Some(chunk) => {
+                        len += chunk.len() as u64;
+                        buffer.extend_from_slice(&chunk);
+                    }
+                    None => ended = true,
+                }
+
+                if ended && blocks == 0 && buffer.len() <= block_size {
+                    let chunk = ChunkInfo {
+                        index: 0,
+                        offset: 0,
+                    };
+                    let mut builder = client
+                        .put_block_blob(transforms.upload(&chunk, buffer.freeze())?)
+                        .context(context.clone());
+                    if let Some(content_type) = content_type {
+                        builder = builder.content_type(content_type);
+                    }
+                    if let Some(content_encoding) = content_encoding {
+                        builder = builder.content_encoding(content_encoding);
+                    }
+                    if let Some(metadata) = metadata {
+                        builder = builder.metadata(metadata);
+                    }
+                    if let Some(access_tier) = access_tier {
+                        builder = builder.access_tier(access_tier);
+                    }
+                    if let Some(lease_id) = lease_id {
+                        builder = builder.lease_id(lease_id);
+                    }
+
+                    let response = builder.await?;
+                    return Ok(UploadStreamResponse {
+                        etag: response.etag,
+                        last_modified: response.last_modified,
+                        request_id: response.request_id,
+                        date: response.date,
+                        len,
+                        blocks,
+                    });
+                }
+                while buffer.len() >= block_size || (ended && !buffer.is_empty()) {
+                    if staging.len() >= max_concurrency {
+                        staging.try_next().await?;
+                    }
+                    limits.validate_blocks(block_size as u64, blocks + 1)?;
+                    let chunk = ChunkInfo {
+                        index: blocks,
+                        offset: len - buffer.len() as u64,
+                    };
+                    let data = buffer.split_to(block_size.min(buffer.len())).freeze();
+                    let data = transforms.upload(&chunk, data)?;
+                    staging.push(
+                        client
+                            .put_block(block_ids.block_id(blocks), data)
+                            .context(context.clone())
+                            .into_future(),
+                    );
+                    blocks += 1;
+                }
+            }
+            while staging.try_next().await?.is_some() {}
+
+            let block_list = (0..blocks)
+                .map(|index| BlobBlockType::new_uncommitted(block_ids.block_id(index)))
+                .collect();
+            let mut builder = client.put_block_list(block_list).context(context);
+            if let Some(content_type) = content_type {
+                builder = builder.content_type(content_type);
+            }
+            if let Some(content_encoding) = content_encoding {
+                builder = builder.content_encoding(content_encoding);
+            }
+            if let Some(metadata) = metadata {
+                builder = builder.metadata(metadata);
+            }
+            if let Some(access_tier) = access_tier {
+                builder = builder.access_tier(access_tier);
+            }
+            if let Some(lease_id) = lease_id {
+                builder = builder.lease_id(lease_id);
+            }
+
+            let response = builder.await?;
+            Ok(UploadStreamResponse {
+                etag: response.etag,
+                last_modified: response.last_modified,
+                request_id: response.request_id,
+                date: response.date,
+                len,
+                blocks,
+            })
+        })
+    }
+}
+
+azure_core::future!(UploadStream);
+
+impl<S> std::future::IntoFuture for UploadStreamBuilder<S>
+where
+    S: Stream<Item = azure_core::Result<Bytes>> + Send + 'static,
+{
+    type IntoFuture = UploadStream;
+    type Output = <UploadStream as std::future::Future>::Output;
+    fn into_future(self) -> Self::IntoFuture {
+        Self::into_future(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadStreamResponse {
+    pub etag: Etag,
+    pub last_modified: OffsetDateTime,
+    pub request_id: RequestId,
+    pub date: OffsetDateTime,
+    /// The number of bytes read from the stream, before any transform.
+    pub len: u64,
+    /// The number of blocks staged, or 0 if the stream was uploaded with a
+    /// single request.
+    pub blocks: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+    use futures::StreamExt;
+
+    /// Answers every request as Put Block, Put Block List and Put Blob do.
+    fn service() -> InMemoryTransport {
+        InMemoryTransport::answering(|_| {
+            let mut headers = test_util::version_headers();
+            headers.insert("x-ms-request-server-encrypted", "true");
+            test_util::response(StatusCode::Created, headers)
+        })
+    }
+
+    /// The `comp` query parameter and the body length of the requests.
+    fn requests(service: &InMemoryTransport) -> Vec<(String, usize)> {
+        service
+            .requests()
+            .iter()
+            .map(|request| {
+                (
+                    test_util::query_param(request, "comp").unwrap_or_default(),
+                    request.body().len(),
+                )
+            })
+            .collect()
+    }
+
+    fn blob_client(service: &InMemoryTransport) -> BlobClient {
+        service.client_builder().blob_client("container", "blob")
+    }
+
+    fn chunks(chunks: &[&'static [u8]]) -> impl Stream<Item = azure_core::Result<Bytes>> {
+        futures::stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok(Bytes::from_static(chunk)))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_upload_stream_in_blocks() -> azure_core::Result<()> {
+        let service = service();
+        let response = blob_client(&service)
+            .upload_stream(chunks(&[b"abc", b"defgh", b"ij"]))
+            .block_size(4usize)
+            .max_concurrency(2usize)
+            .await?;
+        assert_eq!(response.len, 10);
+        assert_eq!(response.blocks, 3);
+
+        let requests = requests(&service);
+        let mut blocks: Vec<_> = requests[..3].iter().map(|(_, len)| *len).collect();
+        blocks.sort_unstable();
+        assert_eq!(blocks, vec![2, 4, 4]);
+        assert!(requests[..3].iter().all(|(comp, _)| comp == "block"));
+        assert_eq!(requests[3].0, "blocklist");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stage_blocks_while_reading() -> azure_core::Result<()> {
+        // the stream yields its second chunk only once the first block was
+        // received, which deadlocks unless blocks are staged while it is read
+        let (received, wait) = futures::channel::oneshot::channel();
+        let received = std::sync::Mutex::new(Some(received));
+        let service = InMemoryTransport::answering(move |_| {
+            if let Some(received) = received.lock().unwrap().take() {
+                let _ = received.send(());
+            }
+            let mut headers = test_util::version_headers();
+            headers.insert("x-ms-request-server-encrypted", "true");
+            test_util::response(StatusCode::Created, headers)
+        });
+        let stream = futures::stream::once(async { Ok(Bytes::from_static(b"abcd")) }).chain(
+            futures::stream::once(async move {
+                wait.await.unwrap();
+                Ok(Bytes::from_static(b"efgh"))
+            }),
+        );
+
+        let upload = blob_client(&service)
+            .upload_stream(stream)
+            .block_size(4usize)
+            .max_concurrency(4usize)
+            .into_future();
+        let response = tokio::time::timeout(std::time::Duration::from_secs(10), upload)
+            .await
+            .expect("the first block was not staged while reading the stream")?;
+        assert_eq!(response.blocks, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_small_stream_at_once() -> azure_core::Result<()> {
+        let service = service();
+        let response = blob_client(&service)
+            .upload_stream(chunks(&[b"abc"]))
+            .block_size(4usize)
+            .await?;
+        assert_eq!(response.blocks, 0);
+        assert_eq!(requests(&service), vec![(String::new(), 3)]);
+        Ok(())
+    }
+}
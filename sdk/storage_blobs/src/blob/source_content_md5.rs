@@ -1,14 +1,28 @@
 use azure_core::headers::{self, Header};
+use azure_storage::ConsistencyMD5;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct SourceContentMD5([u8; 16]);
 
+#[cfg(feature = "md5")]
 impl From<md5::Digest> for SourceContentMD5 {
     fn from(md5: md5::Digest) -> Self {
         Self(md5.0)
     }
 }
 
+impl From<[u8; 16]> for SourceContentMD5 {
+    fn from(md5: [u8; 16]) -> Self {
+        Self(md5)
+    }
+}
+
+impl From<ConsistencyMD5> for SourceContentMD5 {
+    fn from(md5: ConsistencyMD5) -> Self {
+        Self(*md5.as_slice())
+    }
+}
+
 impl Header for SourceContentMD5 {
     fn name(&self) -> headers::HeaderName {
         "x-ms-source-content-md5".into()
@@ -0,0 +1,313 @@
+//! Synchronous clients for code which does not want to adopt async, such as
+//! command line tools and build scripts.
+//!
+//! Like `reqwest::blocking`, the I/O of the clients is driven by an internal
+//! runtime on a thread of its own, shared by all clients derived from the same
+//! [`BlockingBlobServiceClient`], while the calling thread waits for the
+//! result. The clients can be used from within an async runtime, e.g. from
+//! code which does not know whether it runs in one, but block its thread
+//! while they wait.
+//!
+//! ```no_run
+//! # fn main() -> azure_core::Result<()> {
+//! use azure_storage::StorageCredentials;
+//! use azure_storage_blobs::{blocking::BlockingBlobServiceClient, prelude::*};
+//!
+//! let service_client = BlockingBlobServiceClient::new(BlobServiceClient::new(
+//!     "account",
+//!     StorageCredentials::anonymous(),
+//! ))?;
+//! let blob_client = service_client.container_client("container").blob_client("blob");
+//! blob_client.put_block_blob("hello world")?;
+//! let content = blob_client.get_content()?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::{
+    blob::{operations::*, Blob},
+    container::{operations::GetPropertiesResponse as GetContainerPropertiesResponse, Container},
+    prelude::*,
+};
+use azure_core::{
+    error::{ErrorKind, ResultExt},
+    Body,
+};
+use futures::{Future, TryStreamExt};
+use std::{path::PathBuf, sync::Arc};
+use tokio::runtime::Runtime;
+
+/// The runtime of the clients, with a single worker thread driving their I/O.
+#[derive(Debug)]
+struct Driver(Option<Runtime>);
+
+impl Driver {
+    fn new() -> azure_core::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("azure-storage-blobs-blocking")
+            .enable_all()
+            .build()
+            .context(ErrorKind::Io, "failed to start the blocking client runtime")?;
+        Ok(Self(Some(runtime)))
+    }
+
+    /// Poll `future` within the context of the runtime, so that the I/O it
+    /// starts is driven by the worker, on the calling thread unless it runs
+    /// an async runtime itself.
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        let runtime = self.0.as_ref().expect("the runtime was shut down");
+        let block_on = || {
+            let _guard = runtime.enter();
+            futures::executor::block_on(future)
+        };
+        if tokio::runtime::Handle::try_current().is_err() {
+            return block_on();
+        }
+        // the context of the other runtime must not be mixed with this one
+        std::thread::scope(|scope| {
+            scope
+                .spawn(block_on)
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+}
+
+impl Drop for Driver {
+    fn drop(&mut self) {
+        // dropping a runtime blocks, which panics within an async runtime
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// A synchronous [`BlobServiceClient`].
+#[derive(Debug, Clone)]
+pub struct BlockingBlobServiceClient {
+    client: BlobServiceClient,
+    runtime: Arc<Driver>,
+}
+
+impl BlockingBlobServiceClient {
+    /// Wrap an async client, starting the runtime which drives it.
+    pub fn new(client: BlobServiceClient) -> azure_core::Result<Self> {
+        Ok(Self {
+            client,
+            runtime: Arc::new(Driver::new()?),
+        })
+    }
+
+    /// Run any future, e.g. an operation of the async client configured
+    /// beyond what the blocking methods offer, to completion.
+    ///
+    /// The future is `Send` as, within an async runtime, it runs on a thread
+    /// of its own so that the two runtimes do not meet.
+    pub fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        self.runtime.block_on(future)
+    }
+
+    /// The async client.
+    pub fn as_async(&self) -> &BlobServiceClient {
+        &self.client
+    }
+
+    /// All containers of the account.
+    pub fn list_containers(&self) -> azure_core::Result<Vec<Container>> {
+        self.block_on(
+            self.client
+                .list_containers()
+                .into_stream()
+                .map_ok(|page| futures::stream::iter(page.containers.into_iter().map(Ok)))
+                .try_flatten()
+                .try_collect(),
+        )
+    }
+
+    pub fn container_client(&self, container_name: impl Into<String>) -> BlockingContainerClient {
+        BlockingContainerClient {
+            client: self.client.container_client(container_name),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// A synchronous [`ContainerClient`].
+#[derive(Debug, Clone)]
+pub struct BlockingContainerClient {
+    client: ContainerClient,
+    runtime: Arc<Driver>,
+}
+
+impl BlockingContainerClient {
+    /// See [`BlockingBlobServiceClient::block_on`].
+    pub fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        self.runtime.block_on(future)
+    }
+
+    /// The async client.
+    pub fn as_async(&self) -> &ContainerClient {
+        &self.client
+    }
+
+    pub fn create(&self) -> azure_core::Result<()> {
+        self.block_on(self.client.create().into_future())
+    }
+
+    pub fn delete(&self) -> azure_core::Result<()> {
+        self.block_on(self.client.delete().into_future())
+    }
+
+    /// Check whether the container exists.
+    pub fn exists(&self) -> azure_core::Result<bool> {
+        self.block_on(self.client.exists())
+    }
+
+    pub fn get_properties(&self) -> azure_core::Result<GetContainerPropertiesResponse> {
+        self.block_on(self.client.get_properties().into_future())
+    }
+
+    /// All blobs of the container.
+    pub fn list_blobs(&self) -> azure_core::Result<Vec<Blob>> {
+        self.block_on(
+            self.client
+                .list_blobs()
+                .into_stream()
+                .map_ok(|page| {
+                    let blobs: Vec<_> = page.blobs.blobs().cloned().map(Ok).collect();
+                    futures::stream::iter(blobs)
+                })
+                .try_flatten()
+                .try_collect(),
+        )
+    }
+
+    pub fn blob_client(&self, blob_name: impl Into<String>) -> BlockingBlobClient {
+        BlockingBlobClient {
+            client: self.client.blob_client(blob_name),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// A synchronous [`BlobClient`].
+#[derive(Debug, Clone)]
+pub struct BlockingBlobClient {
+    client: BlobClient,
+    runtime: Arc<Driver>,
+}
+
+impl BlockingBlobClient {
+    /// See [`BlockingBlobServiceClient::block_on`].
+    pub fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        self.runtime.block_on(future)
+    }
+
+    /// The async client.
+    pub fn as_async(&self) -> &BlobClient {
+        &self.client
+    }
+
+    /// Return an entire blob.
+    pub fn get_content(&self) -> azure_core::Result<Vec<u8>> {
+        self.block_on(self.client.get_content())
+    }
+
+    pub fn get_properties(&self) -> azure_core::Result<GetPropertiesResponse> {
+        self.block_on(self.client.get_properties().into_future())
+    }
+
+    /// Check whether the blob exists.
+    pub fn exists(&self) -> azure_core::Result<bool> {
+        self.block_on(self.client.exists())
+    }
+
+    pub fn put_block_blob(
+        &self,
+        body: impl Into<Body>,
+    ) -> azure_core::Result<PutBlockBlobResponse> {
+        self.block_on(self.client.put_block_blob(body).into_future())
+    }
+
+    pub fn delete(&self) -> azure_core::Result<DeleteBlobResponse> {
+        self.block_on(self.client.delete().into_future())
+    }
+
+    /// Upload a local file. See [`BlobClient::upload_file`].
+    pub fn upload_file(&self, path: impl Into<PathBuf>) -> azure_core::Result<UploadFileResponse> {
+        self.block_on(self.client.upload_file(path).into_future())
+    }
+
+    /// Download the blob into a local file. See [`BlobClient::download_file`].
+    pub fn download_file(
+        &self,
+        path: impl Into<PathBuf>,
+    ) -> azure_core::Result<DownloadFileResponse> {
+        self.block_on(self.client.download_file(path).into_future())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+
+    #[test]
+    fn test_blocking_exists() -> azure_core::Result<()> {
+        let service = InMemoryTransport::answering(|_| {
+            test_util::response(StatusCode::NotFound, Default::default())
+        });
+        let service_client =
+            BlockingBlobServiceClient::new(service.client_builder().blob_service_client())?;
+        let container_client = service_client.container_client("container");
+        assert!(!container_client.exists()?);
+        assert!(!container_client.blob_client("blob").exists()?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_blocking_within_runtime() -> azure_core::Result<()> {
+        let transport = crate::dev::InMemoryTransport::new();
+        let service_client = BlockingBlobServiceClient::new(
+            ClientBuilder::new("account", azure_storage::StorageCredentials::anonymous())
+                .transport(transport.clone())
+                .blob_service_client(),
+        )?;
+        let container_client = service_client.container_client("container");
+        container_client.create()?;
+        let blob_client = container_client.blob_client("blob");
+
+        // the file I/O runs on a thread which is not a worker of this runtime
+        let path = std::env::temp_dir().join(format!("{}.blocking", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "hello").unwrap();
+        blob_client.upload_file(&path)?;
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(blob_client.get_content()?, b"hello");
+        blob_client.download_file(&path)?;
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+        std::fs::remove_file(&path).unwrap();
+
+        // the runtime of the clients is dropped within this one
+        drop(service_client);
+        drop(container_client);
+        drop(blob_client);
+        Ok(())
+    }
+}
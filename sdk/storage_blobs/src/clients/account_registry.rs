@@ -0,0 +1,211 @@
+use super::{BlobClient, BlobServiceClient, ClientBuilder, ContainerClient};
+use azure_core::{
+    error::{Error, ErrorKind, ResultExt},
+    Url,
+};
+use percent_encoding::percent_decode_str;
+use std::collections::BTreeMap;
+
+/// Clients of several storage accounts, found by name or by the URL of a
+/// container or blob.
+///
+/// The clients of an account share its pipeline and credentials, so a URL is
+/// resolved to a client authenticated for its account. The query of a URL,
+/// such as a SAS token, is ignored.
+///
+/// ```
+/// use azure_storage::StorageCredentials;
+/// use azure_storage_blobs::prelude::*;
+///
+/// let mut registry = StorageAccountRegistry::new();
+/// registry.insert("logs", ClientBuilder::new("logs", StorageCredentials::Anonymous));
+/// registry.insert("media", ClientBuilder::new("media", StorageCredentials::Anonymous));
+///
+/// let blob_client = registry
+///     .blob_client_from_url(
+///         &"https://media.blob.core.windows.net/photos/2022/lake%20view.jpg".parse().unwrap(),
+///     )
+///     .unwrap();
+/// assert_eq!(blob_client.container_client().container_name(), "photos");
+/// assert_eq!(blob_client.blob_name(), "2022/lake view.jpg");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StorageAccountRegistry {
+    accounts: BTreeMap<String, BlobServiceClient>,
+}
+
+impl StorageAccountRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the client built by `builder` under `name`, replacing any
+    /// client registered under the same name.
+    pub fn insert(&mut self, name: impl Into<String>, builder: ClientBuilder) -> &mut Self {
+        self.insert_client(name, builder.blob_service_client())
+    }
+
+    /// Registers `client` under `name`, replacing any client registered under
+    /// the same name.
+    pub fn insert_client(
+        &mut self,
+        name: impl Into<String>,
+        client: BlobServiceClient,
+    ) -> &mut Self {
+        self.accounts.insert(name.into(), client);
+        self
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<BlobServiceClient> {
+        self.accounts.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BlobServiceClient> {
+        self.accounts.get(name)
+    }
+
+    /// The registered names, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.accounts.keys().map(String::as_str)
+    }
+
+    /// The client of the account `url` belongs to, and the segments of the
+    /// path after the account's own.
+    ///
+    /// When several registered accounts match, as with path-style addressing
+    /// on a shared host, the one with the longest path wins.
+    fn resolve<'u>(&self, url: &'u Url) -> azure_core::Result<(&BlobServiceClient, Vec<&'u str>)> {
+        let mut best: Option<(&BlobServiceClient, usize)> = None;
+        for client in self.accounts.values() {
+            let base = client.url()?;
+            if base.scheme() != url.scheme()
+                || base.host_str() != url.host_str()
+                || base.port_or_known_default() != url.port_or_known_default()
+            {
+                continue;
+            }
+            let base_segments: Vec<_> = path_segments(&base).collect();
+            let is_prefix = base_segments.len() <= path_segments(url).count()
+                && path_segments(url)
+                    .zip(&base_segments)
+                    .all(|(segment, base_segment)| segment == *base_segment);
+            if is_prefix && best.map_or(true, |(_, len)| base_segments.len() > len) {
+                best = Some((client, base_segments.len()));
+            }
+        }
+        let (client, skip) = best.ok_or_else(|| {
+            Error::with_message(ErrorKind::Other, || {
+                format!(
+                    "no registered storage account serves {}",
+                    without_query(url)
+                )
+            })
+        })?;
+        Ok((client, path_segments(url).skip(skip).collect()))
+    }
+
+    /// The client of the account serving `url`, the URL of the account, a
+    /// container or a blob.
+    pub fn service_client_from_url(&self, url: &Url) -> azure_core::Result<&BlobServiceClient> {
+        Ok(self.resolve(url)?.0)
+    }
+
+    /// The client of the container `url` addresses, or the container of the
+    /// blob it addresses.
+    pub fn container_client_from_url(&self, url: &Url) -> azure_core::Result<ContainerClient> {
+        let (client, segments) = self.resolve(url)?;
+        let container_name = segments.first().ok_or_else(|| {
+            Error::with_message(ErrorKind::DataConversion, || {
+                format!("{} does not name a container", without_query(url))
+            })
+        })?;
+        Ok(client.container_client(decode(container_name)?))
+    }
+
+    /// The client of the blob `url` addresses.
+    pub fn blob_client_from_url(&self, url: &Url) -> azure_core::Result<BlobClient> {
+        let (client, segments) = self.resolve(url)?;
+        if segments.len() < 2 {
+            return Err(Error::with_message(ErrorKind::DataConversion, || {
+                format!("{} does not name a blob", without_query(url))
+            }));
+        }
+        Ok(client
+            .container_client(decode(segments[0])?)
+            .blob_client(decode(&segments[1..].join("/"))?))
+    }
+}
+
+fn path_segments(url: &Url) -> impl Iterator<Item = &str> {
+    url.path().split('/').filter(|segment| !segment.is_empty())
+}
+
+fn decode(segment: &str) -> azure_core::Result<String> {
+    Ok(percent_decode_str(segment)
+        .decode_utf8()
+        .context(ErrorKind::DataConversion, "the URL path is not UTF-8")?
+        .into_owned())
+}
+
+/// The URL without its query, so that SAS tokens are not echoed in errors.
+fn without_query(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_query(None);
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_storage::{AddressingMode, CloudLocation, StorageCredentials};
+
+    #[test]
+    fn test_resolve_url() -> azure_core::Result<()> {
+        let mut registry = StorageAccountRegistry::new();
+        registry
+            .insert(
+                "public",
+                ClientBuilder::new("account1", StorageCredentials::Anonymous),
+            )
+            .insert(
+                "stack",
+                ClientBuilder::with_location(CloudLocation::Custom {
+                    uri: "https://stack.local".to_owned(),
+                    credentials: StorageCredentials::Anonymous,
+                    addressing_mode: AddressingMode::PathStyle {
+                        account: "account2".to_owned(),
+                    },
+                }),
+            )
+            .insert("emulator", ClientBuilder::emulator());
+
+        let blob_client = registry.blob_client_from_url(
+            &"https://account1.blob.core.windows.net/c/a/b.txt?sig=secret".parse()?,
+        )?;
+        assert_eq!(
+            blob_client.url()?.as_str(),
+            "https://account1.blob.core.windows.net/c/a/b.txt"
+        );
+
+        let blob_client =
+            registry.blob_client_from_url(&"https://stack.local/account2/c/a%20b.txt".parse()?)?;
+        assert_eq!(blob_client.blob_name(), "a b.txt");
+        assert_eq!(
+            blob_client.url()?.as_str(),
+            "https://stack.local/account2/c/a%20b.txt"
+        );
+
+        let container_client = registry
+            .container_client_from_url(&"http://127.0.0.1:10000/devstoreaccount1/c".parse()?)?;
+        assert_eq!(container_client.container_name(), "c");
+
+        assert!(registry
+            .blob_client_from_url(&"https://account1.blob.core.windows.net/c".parse()?)
+            .is_err());
+        let error = registry
+            .blob_client_from_url(&"https://other.blob.core.windows.net/c/b?sig=secret".parse()?)
+            .unwrap_err();
+        assert!(!error.to_string().contains("secret"));
+        Ok(())
+    }
+}
@@ -5,17 +5,17 @@ use crate::{
 };
 use azure_core::{
     error::{Error, ErrorKind},
-    headers::Headers,
     prelude::*,
-    Body, Method, Request, Response, StatusCode,
+    Body, Request, Response, StatusCode,
 };
 use azure_storage::{
+    clients::ServiceType,
     prelude::*,
     shared_access_signature::{
         service_sas::{BlobSharedAccessSignature, BlobSignedResource},
         SasToken,
     },
-    StorageCredentials,
+    StorageClient, StorageCredentials,
 };
 use futures::StreamExt;
 use time::OffsetDateTime;
@@ -53,10 +53,35 @@ impl BlobClient {
         }
     }
 
+    /// Create a client of the blob addressed by `url`, of any cloud location,
+    /// reached with `credentials`.
+    ///
+    /// The snapshot, version and SAS token of the URL are not kept by the
+    /// client. Parse the URL with [`BlobUrlParts`](crate::blob::BlobUrlParts)
+    /// to use them.
+    pub fn from_blob_url(
+        url: &Url,
+        credentials: impl Into<StorageCredentials>,
+    ) -> azure_core::Result<Self> {
+        let parts = crate::blob::BlobUrlParts::parse(url)?;
+        let (container_name, blob_name) = match (&parts.container_name, &parts.blob_name) {
+            (Some(container_name), Some(blob_name)) => (container_name.clone(), blob_name.clone()),
+            _ => {
+                return Err(Error::message(
+                    ErrorKind::DataConversion,
+                    "the URL does not name a container and blob",
+                ))
+            }
+        };
+        let cloud_location = parts.cloud_location(credentials.into())?;
+        Ok(ClientBuilder::with_location(cloud_location).blob_client(container_name, blob_name))
+    }
+
     /// Stream a blob in chunks.
     ///
     /// By default, blobs are downloaded in 1MB chunks to reduce the impact of
-    /// intermittent network issues while downloading large blobs.
+    /// intermittent network issues while downloading large blobs. An empty
+    /// blob is returned as a single response with an empty body.
     pub fn get(&self) -> GetBlobBuilder {
         GetBlobBuilder::new(self.clone())
     }
@@ -75,6 +100,76 @@ impl BlobClient {
         Ok(blob)
     }
 
+    /// Return an entire blob, decompressed if its `Content-Encoding` is gzip,
+    /// e.g. when it was uploaded with [`UploadFileBuilder::gzip`]. Blobs with
+    /// another content encoding are returned as they are stored.
+    #[cfg(feature = "gzip")]
+    pub async fn get_decompressed_content(&self) -> azure_core::Result<Vec<u8>> {
+        let mut blob = Vec::new();
+        let mut gzip = false;
+        let mut stream = self.get().into_stream();
+        while let Some(value) = stream.next().await {
+            let value = value?;
+            gzip = value
+                .blob
+                .properties
+                .content_encoding
+                .as_deref()
+                .map_or(false, crate::compression::is_gzip);
+            let data = value.data.collect().await?;
+            blob.extend(&data);
+        }
+        // an empty blob holds no gzip member to decompress
+        if gzip && !blob.is_empty() {
+            return crate::compression::gunzip(&blob);
+        }
+        Ok(blob)
+    }
+
+    /// Download a blob into a local file.
+    ///
+    /// The file is allocated up front and filled by downloading ranges of the
+    /// blob in parallel. The length and, when the blob has one, the
+    /// `Content-MD5` of the result are verified before returning.
+    ///
+    /// The chunk size and concurrency are fixed unless the adaptive mode is
+    /// enabled with [`DownloadFileBuilder::autotune`].
+    ///
+    /// With [`DownloadFileBuilder::transforms`], which may change the length
+    /// of the ranges, the ranges are still downloaded in parallel but written
+    /// one after the other, in order, without autotuning, and the result is
+    /// not verified against the stored content.
+    ///
+    /// The file is written with blocking I/O. With the `tokio` feature, on a
+    /// multi-threaded Tokio runtime, the other tasks of the thread writing it
+    /// are moved to other threads meanwhile; otherwise they wait for the disk.
+    pub fn download_file(&self, path: impl Into<std::path::PathBuf>) -> DownloadFileBuilder {
+        DownloadFileBuilder::new(self.clone(), path.into())
+    }
+
+    /// Upload a stream of bytes to a block blob, staging blocks as the
+    /// stream produces them.
+    pub fn upload_stream<S>(&self, stream: S) -> UploadStreamBuilder<S>
+    where
+        S: futures::Stream<Item = azure_core::Result<bytes::Bytes>> + Send + 'static,
+    {
+        UploadStreamBuilder::new(self.clone(), stream)
+    }
+
+    /// Read the blob through `tokio::io::AsyncRead` and `AsyncSeek`, with
+    /// ranged downloads.
+    #[cfg(feature = "tokio-io")]
+    pub fn reader(&self) -> crate::blob::BlobReader {
+        crate::blob::BlobReader::new(self.clone())
+    }
+
+    /// Write a block blob through `tokio::io::AsyncWrite`. The blob is only
+    /// replaced once the writer is shut down.
+    #[cfg(feature = "tokio-io")]
+    pub fn writer(&self) -> crate::blob::BlobWriter {
+        crate::blob::BlobWriter::new(self.clone())
+    }
+
     /// Get all user-defined metadata, standard HTTP properties, and system properties for the blob.
     pub fn get_properties(&self) -> GetPropertiesBuilder {
         GetPropertiesBuilder::new(self.clone())
@@ -82,8 +177,10 @@ impl BlobClient {
 
     /// Set blob properties.
     ///
-    /// Several properties are cleared from the blob if not passed.
-    /// Consider calling `set_from_blob_properties` with existing blob properties.
+    /// The HTTP headers of the blob which are not passed are cleared.
+    /// Consider starting from the existing ones with
+    /// [`SetPropertiesBuilder::http_headers`] and
+    /// [`BlobHttpHeaders::from`](crate::blob::BlobHttpHeaders).
     pub fn set_properties(&self) -> SetPropertiesBuilder {
         SetPropertiesBuilder::new(self.clone())
     }
@@ -113,19 +210,37 @@ impl BlobClient {
         SetBlobExpiryBuilder::new(self.clone(), blob_expiry)
     }
 
-    /// Creates a new page blob.
-    pub fn put_page_blob(&self, length: u128) -> PutPageBlobBuilder {
+    /// Creates a new, empty page blob of `length` bytes, replacing any existing
+    /// blob. The length must be a multiple of 512.
+    pub fn create_page_blob(&self, length: u128) -> PutPageBlobBuilder {
         PutPageBlobBuilder::new(self.clone(), length)
     }
 
-    /// Creates a new append blob.
-    pub fn put_append_blob(&self) -> PutAppendBlobBuilder {
+    /// Creates a new, empty append blob, replacing any existing blob.
+    pub fn create_append_blob(&self) -> PutAppendBlobBuilder {
         PutAppendBlobBuilder::new(self.clone())
     }
 
+    /// Creates a new block blob with `body` as content, replacing any existing
+    /// blob.
+    pub fn create_block_blob(&self, body: impl Into<Body>) -> PutBlockBlobBuilder {
+        PutBlockBlobBuilder::new(self.clone(), body.into())
+    }
+
+    /// Creates a new page blob. Same as [`create_page_blob`](Self::create_page_blob).
+    pub fn put_page_blob(&self, length: u128) -> PutPageBlobBuilder {
+        self.create_page_blob(length)
+    }
+
+    /// Creates a new append blob. Same as [`create_append_blob`](Self::create_append_blob).
+    pub fn put_append_blob(&self) -> PutAppendBlobBuilder {
+        self.create_append_blob()
+    }
+
     /// Creates a new block blob, or update the content of an existing block blob.
+    /// Same as [`create_block_blob`](Self::create_block_blob).
     pub fn put_block_blob(&self, body: impl Into<Body>) -> PutBlockBlobBuilder {
-        PutBlockBlobBuilder::new(self.clone(), body.into())
+        self.create_block_blob(body)
     }
 
     /// Copy the blob to a destination within the storage account.
@@ -139,6 +254,15 @@ impl BlobClient {
     }
 
     /// Create a lease on the blob to lock for write and delete operations.
+    ///
+    /// `lease_duration` is a `std::time::Duration` or `time::Duration` of 15 to
+    /// 60 seconds, or [`LeaseDuration::Infinite`]. Renew a finite lease every
+    /// [`LeaseDuration::renewal_interval`] to keep it.
+    ///
+    /// The service chooses the lease id unless one is proposed with
+    /// [`AcquireLeaseBuilder::proposed_lease_id`]. Acquiring a lease held with
+    /// the proposed id succeeds, so a process can take its lease back after a
+    /// restart.
     pub fn acquire_lease<LD: Into<LeaseDuration>>(
         &self,
         lease_duration: LD,
@@ -151,6 +275,43 @@ impl BlobClient {
         BreakLeaseBuilder::new(self.clone())
     }
 
+    /// Rename the blob within its container, for accounts with a
+    /// hierarchical namespace.
+    ///
+    /// Renames are atomic operations of the `dfs` endpoint of the account,
+    /// to which the request is sent with the credentials and options of the
+    /// client. Accounts without a hierarchical namespace reject them.
+    ///
+    /// The `if_match` and `lease_id` conditions apply to the destination, e.g.
+    /// `IfMatchCondition::NotMatch(Etag::any())` fails the rename rather than
+    /// replace an existing blob, while `if_source_match` and `source_lease_id`
+    /// apply to the blob being renamed.
+    pub fn rename(&self, destination: impl Into<String>) -> RenameBlobBuilder {
+        RenameBlobBuilder::new(self.clone(), destination.into())
+    }
+
+    /// Move the blob to another blob of the account, for accounts without a
+    /// hierarchical namespace, by copying it and then deleting it.
+    ///
+    /// The copy is made of the blob as it is when the move starts, and the
+    /// blob is only deleted if it did not change since, so a write racing
+    /// with the move fails it instead of being lost. The `if_match` condition
+    /// applies to the destination, e.g. `IfMatchCondition::NotMatch(Etag::any())`
+    /// fails the move rather than replace an existing blob, and
+    /// `source_lease_id` is needed to delete a leased blob.
+    ///
+    /// The move waits for the copy to complete, polling the destination every
+    /// `poll_interval` (one second by default, and without waiting when it is
+    /// zero), before deleting the blob. With `wait_for_copy(false)` a copy
+    /// which is still pending leaves the blob in place, which the response
+    /// tells.
+    ///
+    /// Only the base blob is copied, and deleting it deletes its snapshots
+    /// too, so a blob with snapshots loses them when it is moved.
+    pub fn move_to(&self, destination: BlobClient) -> MoveBlobBuilder {
+        MoveBlobBuilder::new(self.clone(), destination)
+    }
+
     /// Delete the blob.
     pub fn delete(&self) -> DeleteBlobBuilder {
         DeleteBlobBuilder::new(self.clone())
@@ -192,6 +353,26 @@ impl BlobClient {
         SetTagsBuilder::new(self.clone(), tags.into())
     }
 
+    /// Upload a local file as a block blob.
+    ///
+    /// Small files are sent with a single Put Blob request, larger ones are
+    /// staged as blocks and committed with Put Block List. The content type
+    /// defaults to one guessed from the file extension and the MD5 of the
+    /// file is stored as the blob's `Content-MD5`.
+    ///
+    /// The block size and concurrency are fixed unless the adaptive mode is
+    /// enabled with [`UploadFileBuilder::autotune`]. With the `gzip` feature,
+    /// [`UploadFileBuilder::gzip`] compresses the file as it is uploaded, and
+    /// [`UploadFileBuilder::transforms`] runs custom
+    /// [transforms](crate::transform) on its blocks.
+    ///
+    /// The file is read with blocking I/O. With the `tokio` feature, on a
+    /// multi-threaded Tokio runtime, the other tasks of the thread reading it
+    /// are moved to other threads meanwhile; otherwise they wait for the disk.
+    pub fn upload_file(&self, path: impl Into<std::path::PathBuf>) -> UploadFileBuilder {
+        UploadFileBuilder::new(self.clone(), path.into())
+    }
+
     /// Write a block blob by specifying the list of block IDs that make up the blob.
     ///
     /// In order to be written as part of a blob, a block must have been successfully written to the server in a prior Put Block operation.
@@ -209,6 +390,26 @@ impl BlobClient {
         GetPageRangesBuilder::new(self.clone())
     }
 
+    /// Download a page blob, such as the VHD of an Azure disk, into a local
+    /// disk image.
+    ///
+    /// Only the allocated pages of the blob are downloaded, and the rest of
+    /// the image is left sparse. The image is written with blocking I/O, as
+    /// [`BlobClient::download_file`] writes files.
+    pub fn export_page_blob(&self, path: impl Into<std::path::PathBuf>) -> ExportPageBlobBuilder {
+        ExportPageBlobBuilder::new(self.clone(), path.into())
+    }
+
+    /// Create a page blob from a local disk image, such as a VHD to be turned
+    /// into an Azure disk.
+    ///
+    /// Only the pages of the image which are not all zeros are uploaded.
+    /// Raw images get a fixed VHD footer appended. The image is read with
+    /// blocking I/O, as [`BlobClient::upload_file`] reads files.
+    pub fn import_page_blob(&self, path: impl Into<std::path::PathBuf>) -> ImportPageBlobBuilder {
+        ImportPageBlobBuilder::new(self.clone(), path.into())
+    }
+
     /// Commits a new block of data to the end of an existing append blob.
     pub fn append_block(&self, body: impl Into<Body>) -> AppendBlockBuilder {
         AppendBlockBuilder::new(self.clone(), body.into())
@@ -219,6 +420,18 @@ impl BlobClient {
         ClearPageBuilder::new(self.clone(), ba512_range)
     }
 
+    /// Send a CORS preflight (`OPTIONS`) request for the blob, as a browser
+    /// would before a cross-origin request from `origin` using `method`.
+    ///
+    /// Use it to check the CORS rules of the service are set as intended.
+    pub fn preflight(
+        &self,
+        origin: impl Into<String>,
+        method: azure_core::Method,
+    ) -> PreflightBuilder {
+        PreflightBuilder::new(self.clone(), origin.into(), method)
+    }
+
     /// Create a shared access signature.
     pub fn shared_access_signature(
         &self,
@@ -226,19 +439,32 @@ impl BlobClient {
         expiry: OffsetDateTime,
     ) -> azure_core::Result<BlobSharedAccessSignature> {
         match self.container_client.credentials() {
-            StorageCredentials::Key(account, ref key) => {
-
-        let canonicalized_resource = format!(
-            "/blob/{}/{}/{}",
-            account,
-            self.container_client.container_name(),
-            self.blob_name()
-        );
-                Ok(
-                BlobSharedAccessSignature::new(key.to_string(), canonicalized_resource, permissions, expiry, BlobSignedResource::Blob)
-            )},
-            _ => Err(Error::message(ErrorKind::Credential,
-                "Shared access signature generation - SAS can be generated only from key and account clients",
+            StorageCredentials::Key(account, ref key)
+            | StorageCredentials::KeyWithSecondary(account, ref key, _) => {
+                let canonicalized_resource = self.container_client.sas_canonicalized_resource(
+                    account,
+                    &format!(
+                        "{}/{}",
+                        self.container_client.container_name(),
+                        self.blob_name()
+                    ),
+                );
+                let sas = BlobSharedAccessSignature::try_new(
+                    key.clone(),
+                    canonicalized_resource,
+                    permissions,
+                    expiry,
+                    BlobSignedResource::Blob,
+                )?;
+                Ok(match self.container_client.sas_protocol() {
+                    Some(protocol) => sas.protocol(protocol),
+                    None => sas,
+                })
+            }
+            _ => Err(Error::message(
+                ErrorKind::Credential,
+                "Shared access signature generation - \
+                SAS can be generated only from key and account clients",
             )),
         }
     }
@@ -249,6 +475,7 @@ impl BlobClient {
         T: SasToken,
     {
         let mut url = self.url()?;
+        signature.validate()?;
         url.set_query(Some(&signature.token()));
         Ok(url)
     }
@@ -287,6 +514,70 @@ impl BlobClient {
         &self.container_client
     }
 
+    /// The size limits of the service version the requests are made with.
+    pub fn limits(&self) -> crate::limits::Limits {
+        self.container_client.service_client().limits()
+    }
+
+    /// The identity of the blob, without credentials.
+    pub fn identifier(&self) -> azure_core::Result<crate::blob::BlobIdentifier> {
+        let service_url = self
+            .container_client
+            .service_client()
+            .cloud_location()
+            .url(ServiceType::Blob)?;
+        Ok(crate::blob::BlobIdentifier::new(
+            service_url.as_str(),
+            self.container_client.container_name(),
+            self.blob_name.clone(),
+        ))
+    }
+
+    /// The account, container and blob the client addresses, for equality.
+    fn identity(&self) -> (Option<Url>, &str, &str) {
+        let service_url = self
+            .container_client
+            .service_client()
+            .cloud_location()
+            .url(ServiceType::Blob)
+            .ok();
+        (
+            service_url,
+            self.container_client.container_name(),
+            &self.blob_name,
+        )
+    }
+
+    /// A copy of the client whose requests time out after `timeout`, see
+    /// [`BlobServiceClient::with_timeout`].
+    #[must_use]
+    pub fn with_timeout(&self, timeout: std::time::Duration) -> Self {
+        Self::new(
+            self.container_client.with_timeout(timeout),
+            self.blob_name.clone(),
+        )
+    }
+
+    /// A copy of the client whose requests are made with another service
+    /// version, see [`BlobServiceClient::with_api_version`].
+    #[must_use]
+    pub fn with_api_version(&self, api_version: impl Into<String>) -> Self {
+        Self::new(
+            self.container_client.with_api_version(api_version),
+            self.blob_name.clone(),
+        )
+    }
+
+    /// A copy of the client which accesses the blob with other credentials,
+    /// see [`BlobServiceClient::with_credentials`].
+    #[must_use]
+    pub fn with_credentials(&self, credentials: impl Into<StorageCredentials>) -> Self {
+        Self::new(
+            self.container_client.with_credentials(credentials),
+            self.blob_name.clone(),
+        )
+    }
+
     /// Full URL for the blob.
     pub fn url(&self) -> azure_core::Result<url::Url> {
         let blob_name = self
@@ -297,15 +588,42 @@ impl BlobClient {
         Ok(url::Url::parse(&url)?)
     }
 
-    pub(crate) fn finalize_request(
-        &self,
-        url: Url,
-        method: Method,
-        headers: Headers,
-        request_body: Option<Body>,
-    ) -> azure_core::Result<Request> {
-        self.container_client
-            .finalize_request(url, method, headers, request_body)
+    /// The URL of the blob at the `dfs` endpoint of its account, which serves
+    /// the hierarchical namespace operations, such as
+    /// [`rename`](Self::rename), of the accounts which have one.
+    ///
+    /// Fails when the `dfs` endpoint is not known, e.g. for the emulator, see
+    /// [`CloudLocation::service_endpoints`](azure_storage::CloudLocation::service_endpoints).
+    pub fn dfs_url(&self) -> azure_core::Result<url::Url> {
+        let mut url = self
+            .container_client()
+            .service_client()
+            .get_blob_service_endpoints()?
+            .dfs
+            .ok_or_else(|| {
+                Error::message(
+                    ErrorKind::Other,
+                    "the dfs endpoint of the account is not known for its location",
+                )
+            })?
+            .primary;
+        let path = format!(
+            "{}{}",
+            url.path().trim_end_matches('/'),
+            self.account_relative_path()?
+        );
+        url.set_path(&path);
+        Ok(url)
+    }
+
+    /// The percent-encoded path of the blob in its account,
+    /// `/{container}/{blob}`, whether the account is addressed by host name or
+    /// by path.
+    pub(crate) fn account_relative_path(&self) -> azure_core::Result<String> {
+        let account_path = self.container_client().service_client().url()?;
+        let account_path = account_path.path().trim_end_matches('/');
+        let url = self.url()?;
+        Ok(url.path()[account_path.len()..].to_owned())
     }
 
     pub(crate) async fn send(
@@ -317,9 +635,42 @@ impl BlobClient {
     }
 }
 
+/// Clients are equal when they address the same blob of the same account,
+/// whatever their credentials and options.
+impl PartialEq for BlobClient {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for BlobClient {}
+
+impl std::hash::Hash for BlobClient {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl StorageClient for BlobClient {
+    fn url(&self) -> azure_core::Result<Url> {
+        BlobClient::url(self)
+    }
+
+    async fn send(
+        &self,
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response> {
+        BlobClient::send(self, context, request).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dev::InMemoryTransport;
 
     #[test]
     fn test_from_url() -> azure_core::Result<()> {
@@ -357,6 +708,189 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_options_snapshot() {
+        let blob_client = ClientBuilder::new("account", StorageCredentials::Anonymous)
+            .retry(azure_core::RetryOptions::none())
+            .blob_client("container", "blob");
+        let service_client = blob_client.container_client().service_client();
+        assert!(service_client.options().timeout.default_timeout().is_none());
+        assert_eq!(
+            format!("{:?}", service_client.options().retry_options()),
+            "RetryOptions { mode: None }"
+        );
+        assert_eq!(
+            service_client.api_version(),
+            azure_storage::clients::API_VERSION
+        );
+        assert!(matches!(
+            service_client.cloud_location(),
+            azure_storage::CloudLocation::Public { account, .. } if account == "account"
+        ));
+
+        let timeout = std::time::Duration::from_secs(5);
+        let with_timeout = blob_client.with_timeout(timeout);
+        assert_eq!(with_timeout.blob_name(), "blob");
+        let options = with_timeout.container_client().service_client().options();
+        assert_eq!(
+            options
+                .timeout
+                .default_timeout()
+                .map(|timeout| timeout.duration()),
+            Some(timeout)
+        );
+        assert_eq!(
+            format!("{:?}", options.retry_options()),
+            "RetryOptions { mode: None }"
+        );
+        assert!(service_client.options().timeout.default_timeout().is_none());
+
+        let with_sas = blob_client
+            .with_credentials(StorageCredentials::sas_token("sv=2020-06-12&sig=abc").unwrap());
+        assert_eq!(with_sas.url().unwrap(), blob_client.url().unwrap());
+        assert!(matches!(
+            with_sas.container_client.credentials(),
+            StorageCredentials::SASToken(_)
+        ));
+        let with_options = with_sas
+            .container_client()
+            .service_client()
+            .with_options(azure_core::ClientOptions::default());
+        assert!(matches!(
+            with_options.credentials(),
+            StorageCredentials::SASToken(_)
+        ));
+        assert_eq!(
+            format!("{:?}", with_options.options().retry_options()),
+            format!("{:?}", azure_core::RetryOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_sas_protocol() -> azure_core::Result<()> {
+        use azure_storage::prelude::SasProtocol;
+
+        let expiry = OffsetDateTime::now_utc() + time::Duration::hours(1);
+        let blob_client = ClientBuilder::new(
+            "account",
+            StorageCredentials::access_key("account", base64::encode("key")),
+        )
+        .sas_protocol(SasProtocol::Https)
+        .blob_client("container", "blob");
+        let sas = blob_client.shared_access_signature("r".parse()?, expiry)?;
+        assert!(sas.token().contains("&spr=https&"));
+        let sas = sas.protocol(SasProtocol::HttpHttps);
+        assert!(sas.token().contains("&spr=http,https&"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_allow_insecure() {
+        let builder = ClientBuilder::with_location(azure_storage::CloudLocation::Custom {
+            uri: "http://storage.contoso.com".to_owned(),
+            credentials: StorageCredentials::access_key("account", base64::encode("key")),
+            addressing_mode: Default::default(),
+        });
+        let blob_client = builder.clone().blob_client("container", "blob");
+        assert!(blob_client.url().is_err());
+
+        let blob_client = builder
+            .allow_insecure(true)
+            .blob_client("container", "blob");
+        assert_eq!(
+            blob_client.url().unwrap().as_str(),
+            "http://storage.contoso.com/container/blob"
+        );
+    }
+
+    #[test]
+    fn test_sas_resource() -> azure_core::Result<()> {
+        let permissions = BlobSasPermissions {
+            read: true,
+            ..Default::default()
+        };
+        let expiry = OffsetDateTime::now_utc() + time::Duration::hours(1);
+        // the key of a path-style location may be named after another account
+        let path_style = ClientBuilder::with_location(azure_storage::CloudLocation::Custom {
+            uri: "http://localhost:8080".to_owned(),
+            credentials: StorageCredentials::access_key("keyaccount", base64::encode("key")),
+            addressing_mode: azure_storage::AddressingMode::PathStyle {
+                account: "myaccount".to_owned(),
+            },
+        });
+        for (builder, resource) in [
+            (
+                ClientBuilder::emulator(),
+                "/blob/devstoreaccount1/container",
+            ),
+            (path_style, "/blob/myaccount/container"),
+        ] {
+            // the leading '/' of the container name is not part of the URL
+            for container_name in ["container", "/container"] {
+                let container_client = builder.clone().container_client(container_name);
+                let sas = container_client.shared_access_signature(permissions, expiry)?;
+                assert_eq!(sas.canonicalized_resource(), resource);
+                let url = container_client.generate_signed_container_url(&sas)?;
+                assert_eq!(format!("/blob{}", url.path()), resource);
+
+                let blob_client = container_client.blob_client("a/b");
+                let sas = blob_client.shared_access_signature(permissions, expiry)?;
+                assert_eq!(sas.canonicalized_resource(), format!("{resource}/a/b"));
+                let url = blob_client.generate_signed_blob_url(&sas)?;
+                assert_eq!(format!("/blob{}", url.path()), format!("{resource}/a/b"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sas_with_secondary_key() -> azure_core::Result<()> {
+        let permissions = BlobSasPermissions {
+            read: true,
+            ..Default::default()
+        };
+        let expiry = OffsetDateTime::now_utc() + time::Duration::hours(1);
+        let signed_url = |credentials| {
+            let blob_client = ClientBuilder::new("myaccount", credentials).blob_client("c", "b");
+            let sas = blob_client.shared_access_signature(permissions, expiry)?;
+            blob_client.generate_signed_blob_url(&sas)
+        };
+
+        // the signature is made with the primary key
+        let primary = base64::encode("primary");
+        assert_eq!(
+            signed_url(StorageCredentials::access_key_with_secondary(
+                "myaccount",
+                primary.clone(),
+                base64::encode("secondary"),
+            ))?,
+            signed_url(StorageCredentials::access_key("myaccount", primary))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_blob_url() -> azure_core::Result<()> {
+        let url = Url::parse(
+            "http://127.0.0.1:10000/devstoreaccount1/mycontainer/my%20blob\
+             ?snapshot=2022-07-01T10%3A44%3A59.0000000Z&sig=y",
+        )?;
+        let blob_client = BlobClient::from_blob_url(&url, StorageCredentials::Anonymous)?;
+        assert_eq!(blob_client.blob_name(), "my blob");
+        assert_eq!(
+            blob_client.url()?.as_str(),
+            "http://127.0.0.1:10000/devstoreaccount1/mycontainer/my%20blob"
+        );
+        assert!(matches!(
+            blob_client.container_client.credentials(),
+            StorageCredentials::Anonymous
+        ));
+
+        let url = Url::parse("https://accountname.blob.core.windows.net/mycontainer")?;
+        assert!(BlobClient::from_blob_url(&url, StorageCredentials::Anonymous).is_err());
+        Ok(())
+    }
+
     struct FakeSas {
         token: String,
     }
@@ -391,4 +925,72 @@ mod tests {
             "http://127.0.0.1:10000/devstoreaccount1/a/b/c/d?fake_token"
         );
     }
+
+    /// Fails every request.
+    fn no_service() -> InMemoryTransport {
+        InMemoryTransport::answering(|_| Err(Error::message(ErrorKind::Io, "no service")))
+    }
+
+    /// The service versions of the requests.
+    fn versions(service: &InMemoryTransport) -> Vec<String> {
+        service
+            .requests()
+            .iter()
+            .map(|request| {
+                request
+                    .headers()
+                    .get_optional_string(&azure_core::headers::VERSION)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_api_version() {
+        let service = no_service();
+        let blob_client = service
+            .client_builder()
+            .api_version("2017-11-09")
+            .blob_client("container", "blob");
+        let service_client = blob_client.container_client().service_client();
+        assert_eq!(service_client.api_version(), "2017-11-09");
+        assert_eq!(service_client.limits().max_put_blob_size, 256 * 1024 * 1024);
+
+        let newer = blob_client.with_api_version("2020-10-02");
+        assert!(blob_client.get_properties().await.is_err());
+        assert!(newer.get_properties().await.is_err());
+        assert!(newer.container_client().get_properties().await.is_err());
+        assert_eq!(
+            versions(&service),
+            ["2017-11-09", "2020-10-02", "2020-10-02"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_with_profile() {
+        let service = no_service();
+        let profile = azure_storage::EndpointProfile::azure_stack_hub("local.azurestack.external");
+        let blob_client =
+            ClientBuilder::with_profile("account", StorageCredentials::Anonymous, &profile)
+                .transport(service.clone())
+                .retry(azure_core::RetryOptions::none())
+                .blob_client("container", "blob");
+        assert_eq!(
+            blob_client.url().unwrap().as_str(),
+            "https://account.blob.local.azurestack.external/container/blob"
+        );
+
+        let error = blob_client.get_tags().await.unwrap_err();
+        assert_eq!(
+            error
+                .downcast_ref::<azure_storage::UnsupportedFeature>()
+                .unwrap()
+                .feature,
+            azure_storage::Feature::BlobTags
+        );
+        assert!(service.requests().is_empty());
+
+        assert!(blob_client.get_properties().await.is_err());
+        assert_eq!(versions(&service), ["2019-07-07"]);
+    }
 }
@@ -1,14 +1,17 @@
-use crate::service::operations::*;
+use crate::{batch::BatchBody, options::TagCondition, service::operations::*};
 use azure_core::{
-    headers::Headers, request_options::LeaseId, Body, ClientOptions, Context, Method, Pipeline,
-    Request, Response, Url,
+    headers::VERSION,
+    request_options::{LeaseId, Timeout},
+    ClientOptions, Context, Pipeline, Request, Response, TimeoutPolicy, Url,
 };
 use azure_storage::{
-    clients::{new_pipeline_from_options, shared_access_signature, ServiceType},
-    prelude::{AccountSasPermissions, AccountSasResource, AccountSasResourceType},
+    clients::{new_pipeline_from_options, shared_access_signature, ServiceType, API_VERSION},
+    prelude::{AccountSasPermissions, AccountSasResource, AccountSasResourceType, SasProtocol},
     shared_access_signature::account_sas::AccountSharedAccessSignature,
-    CloudLocation, StorageCredentials,
+    AddressingMode, CloudLocation, EndpointProfile, ServiceEndpoints, StorageClient, StorageConfig,
+    StorageCredentials,
 };
+use std::{sync::Arc, time::Duration};
 use time::OffsetDateTime;
 
 use super::{BlobClient, BlobLeaseClient, ContainerClient, ContainerLeaseClient};
@@ -18,6 +21,9 @@ use super::{BlobClient, BlobLeaseClient, ContainerClient, ContainerLeaseClient};
 pub struct ClientBuilder {
     cloud_location: CloudLocation,
     options: ClientOptions,
+    sas_protocol: Option<SasProtocol>,
+    allow_insecure: bool,
+    api_version: Option<String>,
 }
 
 impl ClientBuilder {
@@ -36,9 +42,53 @@ impl ClientBuilder {
         Self {
             options: ClientOptions::default(),
             cloud_location,
+            sas_protocol: None,
+            allow_insecure: false,
+            api_version: None,
         }
     }
 
+    /// Create a new instance of `ClientBuilder` from a configuration, e.g. read
+    /// from a configuration file.
+    pub fn from_config(config: &StorageConfig) -> azure_core::Result<Self> {
+        Ok(Self::with_location(config.cloud_location()?).client_options(config.client_options()))
+    }
+
+    /// Create a new instance of `ClientBuilder` for the account configured in
+    /// the environment, as described in [`azure_storage::dev`].
+    ///
+    /// Meant for examples, tests and tools.
+    pub fn from_env() -> azure_core::Result<Self> {
+        Ok(Self::with_location(
+            azure_storage::dev::cloud_location_from_env(ServiceType::Blob)?,
+        ))
+    }
+
+    /// Create a new instance of `ClientBuilder` for an account of a deployment
+    /// described by `profile`, such as Azure Stack Hub.
+    ///
+    /// The requests are made with the service version of the profile, and
+    /// those using a feature the profile lacks fail with an
+    /// [`azure_storage::UnsupportedFeature`] error without being sent.
+    #[must_use]
+    pub fn with_profile(
+        account: impl AsRef<str>,
+        credentials: impl Into<StorageCredentials>,
+        profile: &EndpointProfile,
+    ) -> Self {
+        let mut builder = Self::with_location(profile.cloud_location(
+            account.as_ref(),
+            credentials,
+            ServiceType::Blob,
+        ))
+        .api_version(profile.api_version());
+        builder
+            .options
+            .per_call_policies_mut()
+            .push(profile.policy());
+        builder
+    }
+
     /// Use the emulator with default settings
     #[must_use]
     pub fn emulator() -> Self {
@@ -53,8 +103,12 @@ impl ClientBuilder {
     pub fn blob_service_client(self) -> BlobServiceClient {
         let credentials = self.cloud_location.credentials();
         BlobServiceClient {
-            pipeline: new_pipeline_from_options(self.options, credentials.clone()),
+            pipeline: new_pipeline_from_options(self.options.clone(), credentials.clone()),
+            options: self.options,
             cloud_location: self.cloud_location,
+            sas_protocol: self.sas_protocol,
+            allow_insecure: self.allow_insecure,
+            api_version: self.api_version,
         }
     }
 
@@ -109,6 +163,39 @@ impl ClientBuilder {
         self
     }
 
+    /// Set the protocol of the shared access signatures created by the
+    /// clients, e.g. `SasProtocol::Https` to never create SAS usable over
+    /// plain HTTP. The protocol of each SAS can still be changed.
+    #[must_use]
+    pub fn sas_protocol(mut self, protocol: SasProtocol) -> Self {
+        self.sas_protocol = Some(protocol);
+        self
+    }
+
+    /// Allow a custom location with a plain `http` URL.
+    ///
+    /// By default the clients refuse to send requests with credentials over
+    /// plain HTTP, except to the emulator and loopback addresses, so that a
+    /// mistyped endpoint does not leak Shared Key signatures or SAS tokens.
+    #[must_use]
+    pub fn allow_insecure(mut self, allow_insecure: bool) -> Self {
+        self.allow_insecure = allow_insecure;
+        self
+    }
+
+    /// Send the requests with another service version than
+    /// [`API_VERSION`], e.g. an older one for an Azure Stack Hub endpoint.
+    ///
+    /// The operations of the crate are written against [`API_VERSION`]; those
+    /// using features newer than the version fail with
+    /// `ErrorKind::FeatureNotSupportedByApiVersion`, and the
+    /// [`limits`](BlobServiceClient::limits) follow the version.
+    #[must_use]
+    pub fn api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = Some(api_version.into());
+        self
+    }
+
     /// Set the retry options.
     #[must_use]
     pub fn retry(mut self, retry: impl Into<azure_core::RetryOptions>) -> Self {
@@ -116,6 +203,56 @@ impl ClientBuilder {
         self
     }
 
+    /// Report the metrics of every request to `sink`.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn metrics(mut self, sink: Arc<dyn azure_storage::metrics::MetricsSink>) -> Self {
+        self.options = azure_storage::metrics::with_metrics(self.options, sink);
+        self
+    }
+
+    /// Add a circuit breaker which fails requests fast while the account keeps
+    /// returning throttling or server errors.
+    #[must_use]
+    pub fn circuit_breaker(mut self, options: azure_storage::CircuitBreakerOptions) -> Self {
+        self.options
+            .per_retry_policies_mut()
+            .push(Arc::new(azure_storage::CircuitBreakerPolicy::new(options)));
+        self
+    }
+
+    /// Fail requests other than `GET` and `HEAD` with an
+    /// [`azure_storage::ReadOnlyViolation`] instead of sending them, e.g. for
+    /// a client handed to code which must not modify the account.
+    #[must_use]
+    pub fn read_only(mut self) -> Self {
+        self.options
+            .per_call_policies_mut()
+            .push(Arc::new(azure_storage::ReadOnlyPolicy));
+        self
+    }
+
+    /// Correct the date of requests when the local clock is too far off for
+    /// the service to accept shared key signatures. The policy can be kept to
+    /// read the measured skew.
+    #[must_use]
+    pub fn clock_skew_correction(mut self, policy: azure_storage::ClockSkewPolicy) -> Self {
+        self.options.per_call_policies_mut().push(Arc::new(policy));
+        self
+    }
+
+    /// Add a policy injecting random failures, to test how the application
+    /// copes with them. Meant for tests only.
+    #[cfg(feature = "fault-injection")]
+    #[must_use]
+    pub fn fault_injection(
+        mut self,
+        policy: azure_storage::fault_injection::FaultInjectionPolicy,
+    ) -> Self {
+        self.options.per_retry_policies_mut().push(Arc::new(policy));
+        self
+    }
+
     /// Set the transport options.
     #[must_use]
     pub fn transport(mut self, transport: impl Into<azure_core::TransportOptions>) -> Self {
@@ -123,6 +260,14 @@ impl ClientBuilder {
         self
     }
 
+    /// Set the telemetry options, such as an application ID prepended to the
+    /// `User-Agent` header.
+    #[must_use]
+    pub fn telemetry(mut self, telemetry: impl Into<azure_core::TelemetryOptions>) -> Self {
+        self.options = self.options.telemetry(telemetry);
+        self
+    }
+
     /// Override all of the client options.
     ///
     /// *Warning!*: This overrides all client options that have been previously set on this builder.
@@ -137,7 +282,11 @@ impl ClientBuilder {
 #[derive(Debug, Clone)]
 pub struct BlobServiceClient {
     pipeline: Pipeline,
+    options: ClientOptions,
     cloud_location: CloudLocation,
+    sas_protocol: Option<SasProtocol>,
+    allow_insecure: bool,
+    api_version: Option<String>,
 }
 
 impl BlobServiceClient {
@@ -161,9 +310,32 @@ impl BlobServiceClient {
         GetAccountInformationBuilder::new(self.clone())
     }
 
-    /// Get all the blobs with the given tags in the where expression
-    pub fn find_blobs_by_tags(&self, expression: String) -> FindBlobsByTagsBuilder {
-        FindBlobsByTagsBuilder::new(self.clone(), expression)
+    /// Get the settings of the account's blob service, such as logging,
+    /// metrics, CORS rules, soft delete and static website hosting.
+    pub fn get_properties(&self) -> GetBlobServicePropertiesBuilder {
+        GetBlobServicePropertiesBuilder::new(self.clone())
+    }
+
+    /// Send the sub-requests of a batch, e.g. to delete or set the tier of
+    /// many blobs, in a single request.
+    ///
+    /// Sub-requests are authorized on their own, with the credentials of the
+    /// client unless they carry an `Authorization` header, see
+    /// [`crate::batch`].
+    pub fn submit_batch(&self, batch: BatchBody) -> SubmitBatchBuilder {
+        SubmitBatchBuilder::new(self.clone(), batch)
+    }
+
+    /// Get all the blobs with the given tags in the where expression, across
+    /// the containers of the account.
+    ///
+    /// The expression is either written out by hand or built with
+    /// [`TagCondition`], which quotes the keys and values.
+    pub fn find_blobs_by_tags(
+        &self,
+        expression: impl Into<TagCondition>,
+    ) -> FindBlobsByTagsBuilder {
+        FindBlobsByTagsBuilder::new(self.clone(), expression.into())
     }
 
     /// List all the containers in the blob account
@@ -172,9 +344,18 @@ impl BlobServiceClient {
     }
 
     pub fn url(&self) -> azure_core::Result<url::Url> {
+        self.cloud_location.check_secure(self.allow_insecure)?;
         self.cloud_location.url(ServiceType::Blob)
     }
 
+    /// The endpoints of every service of the account, e.g. its `dfs` endpoint
+    /// for the operations of an account with a hierarchical namespace. See
+    /// [`CloudLocation::service_endpoints`].
+    pub fn get_blob_service_endpoints(&self) -> azure_core::Result<ServiceEndpoints> {
+        self.cloud_location.check_secure(self.allow_insecure)?;
+        self.cloud_location.service_endpoints()
+    }
+
     pub fn container_client<S: Into<String>>(&self, container_name: S) -> ContainerClient {
         ContainerClient::new(self.clone(), container_name.into())
     }
@@ -185,27 +366,123 @@ impl BlobServiceClient {
         expiry: OffsetDateTime,
         permissions: AccountSasPermissions,
     ) -> azure_core::Result<AccountSharedAccessSignature> {
-        shared_access_signature(
+        let sas = shared_access_signature(
             self.credentials(),
             AccountSasResource::Blob,
             resource_type,
             expiry,
             permissions,
-        )
+        )?;
+        Ok(match self.sas_protocol {
+            Some(protocol) => sas.protocol(protocol),
+            None => sas,
+        })
+    }
+
+    /// The options the client was built with, e.g. to log or check the
+    /// configuration of a client built elsewhere.
+    pub fn options(&self) -> &ClientOptions {
+        &self.options
+    }
+
+    /// Where the client sends its requests, and with which credentials.
+    pub fn cloud_location(&self) -> &CloudLocation {
+        &self.cloud_location
+    }
+
+    /// The version of the service API the requests are made with, see
+    /// [`ClientBuilder::api_version`].
+    pub fn api_version(&self) -> &str {
+        self.api_version.as_deref().unwrap_or(API_VERSION)
+    }
+
+    /// A copy of the client whose requests are made with another service
+    /// version, see [`ClientBuilder::api_version`].
+    #[must_use]
+    pub fn with_api_version(&self, api_version: impl Into<String>) -> Self {
+        Self {
+            api_version: Some(api_version.into()),
+            ..self.clone()
+        }
+    }
+
+    /// The size limits of the service version the requests are made with.
+    pub fn limits(&self) -> crate::limits::Limits {
+        crate::limits::Limits::for_api_version(self.api_version())
+    }
+
+    /// A copy of the client whose requests time out after `timeout`, unless
+    /// they set their own timeout.
+    #[must_use]
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let options = self
+            .options
+            .clone()
+            .timeout(TimeoutPolicy::new(Some(Timeout::new(timeout))));
+        self.rebuild(options, self.cloud_location.clone())
+    }
+
+    /// A copy of the client with other options, e.g. another retry policy or
+    /// transport, for the same account and credentials.
+    #[must_use]
+    pub fn with_options(&self, options: impl Into<ClientOptions>) -> Self {
+        self.rebuild(options.into(), self.cloud_location.clone())
+    }
+
+    /// A copy of the client which accesses the same account with other
+    /// credentials, e.g. to send some requests with a SAS.
+    #[must_use]
+    pub fn with_credentials(&self, credentials: impl Into<StorageCredentials>) -> Self {
+        let cloud_location = self.cloud_location.with_credentials(credentials.into());
+        self.rebuild(self.options.clone(), cloud_location)
+    }
+
+    /// A copy of the client which fails requests that would modify data, see
+    /// [`ClientBuilder::read_only`].
+    #[must_use]
+    pub fn read_only(&self) -> Self {
+        let mut options = self.options.clone();
+        options
+            .per_call_policies_mut()
+            .push(Arc::new(azure_storage::ReadOnlyPolicy));
+        self.rebuild(options, self.cloud_location.clone())
+    }
+
+    fn rebuild(&self, options: ClientOptions, cloud_location: CloudLocation) -> Self {
+        Self {
+            pipeline: new_pipeline_from_options(
+                options.clone(),
+                cloud_location.credentials().clone(),
+            ),
+            options,
+            cloud_location,
+            sas_protocol: self.sas_protocol,
+            allow_insecure: self.allow_insecure,
+            api_version: self.api_version.clone(),
+        }
     }
 
     pub(crate) fn credentials(&self) -> &StorageCredentials {
         self.cloud_location.credentials()
     }
 
-    pub(crate) fn finalize_request(
-        &self,
-        url: Url,
-        method: Method,
-        headers: Headers,
-        request_body: Option<Body>,
-    ) -> azure_core::Result<Request> {
-        azure_storage::clients::finalize_request(url, method, headers, request_body)
+    /// The canonicalized resource of a service SAS for `path`, a container
+    /// or a blob in it, signed with the key of `account`.
+    ///
+    /// The resource names what the URL of the clients addresses: the account
+    /// in the path of path-style locations, which may differ from the account
+    /// the key is named after, and the container without a leading `/`.
+    pub(crate) fn sas_canonicalized_resource(&self, account: &str, path: &str) -> String {
+        let account = match self.cloud_location.addressing_mode() {
+            AddressingMode::PathStyle { account } => account,
+            AddressingMode::VirtualHost => account.to_owned(),
+        };
+        format!("/blob/{}/{}", account, path.trim_start_matches('/'))
+    }
+
+    /// The protocol set with [`ClientBuilder::sas_protocol`].
+    pub(crate) fn sas_protocol(&self) -> Option<SasProtocol> {
+        self.sas_protocol
     }
 
     pub(crate) async fn send(
@@ -213,8 +490,27 @@ impl BlobServiceClient {
         context: &mut Context,
         request: &mut Request,
     ) -> azure_core::Result<Response> {
+        if let Some(api_version) = &self.api_version {
+            request.insert_header(VERSION, api_version.clone());
+        }
         self.pipeline
             .send(context.insert(ServiceType::Blob), request)
             .await
     }
 }
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl StorageClient for BlobServiceClient {
+    fn url(&self) -> azure_core::Result<Url> {
+        BlobServiceClient::url(self)
+    }
+
+    async fn send(
+        &self,
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response> {
+        BlobServiceClient::send(self, context, request).await
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    blob::operations::{AppendBlockBuilder, PutBlockBuilder, PutPageBuilder},
+    blob::{Blob, BlobType, Conditional},
+    prelude::*,
+};
+use azure_core::{
+    error::{Error, ErrorKind},
+    prelude::*,
+    Body, StatusCode,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use time::OffsetDateTime;
+
+/// A [`BlobClient`] which caches the blob's properties.
+///
+/// Within `ttl` of the last lookup, [`get_properties`](Self::get_properties)
+/// and [`exists`](Self::exists) are answered from the cache. Once the entry is
+/// stale it is revalidated with `If-None-Match`, so an unchanged blob costs a
+/// `304 Not Modified` instead of a full response. Clones share the cache.
+#[derive(Debug, Clone)]
+pub struct CachedBlobClient {
+    client: BlobClient,
+    ttl: Duration,
+    cache: Arc<Mutex<Option<CacheEntry>>>,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    blob: Option<Blob>,
+    validated_at: OffsetDateTime,
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, ttl: Duration, now: OffsetDateTime) -> bool {
+        now < self.validated_at + ttl
+    }
+}
+
+impl CachedBlobClient {
+    pub fn new(client: BlobClient, ttl: Duration) -> Self {
+        Self {
+            client,
+            ttl,
+            cache: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The wrapped client, for operations which bypass the cache.
+    pub fn client(&self) -> &BlobClient {
+        &self.client
+    }
+
+    /// Get the properties of the blob, or `None` if it does not exist.
+    pub async fn get_properties(&self) -> azure_core::Result<Option<Blob>> {
+        let now = OffsetDateTime::now_utc();
+        let cached = self.cache.lock().unwrap().clone();
+        if let Some(entry) = &cached {
+            if entry.is_fresh(self.ttl, now) {
+                return Ok(entry.blob.clone());
+            }
+        }
+
+        let mut request = self.client.get_properties();
+        if let Some(blob) = cached.as_ref().and_then(|entry| entry.blob.as_ref()) {
+            request = request.if_match(IfMatchCondition::NotMatch(blob.properties.etag.clone()));
+        }
+
+        let blob = match request.into_conditional_future().await {
+            Ok(Conditional::Modified(response)) => Some(response.blob),
+            Ok(Conditional::NotModified(_)) => cached.and_then(|entry| entry.blob),
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    ErrorKind::HttpResponse {
+                        status: StatusCode::NotFound,
+                        ..
+                    }
+                ) =>
+            {
+                None
+            }
+            Err(error) => return Err(error),
+        };
+
+        *self.cache.lock().unwrap() = Some(CacheEntry {
+            blob: blob.clone(),
+            validated_at: now,
+        });
+        Ok(blob)
+    }
+
+    /// Check whether the blob exists.
+    pub async fn exists(&self) -> azure_core::Result<bool> {
+        Ok(self.get_properties().await?.is_some())
+    }
+
+    /// The type of the blob, or `None` if it does not exist.
+    pub async fn blob_type(&self) -> azure_core::Result<Option<BlobType>> {
+        Ok(self
+            .get_properties()
+            .await?
+            .map(|blob| blob.properties.blob_type))
+    }
+
+    /// Fail unless the blob exists and is of the `expected` type.
+    pub async fn require_blob_type(&self, expected: BlobType) -> azure_core::Result<()> {
+        check_blob_type(self.client.blob_name(), self.blob_type().await?, expected)
+    }
+
+    /// Append a block, after checking that the blob is an append blob.
+    pub async fn append_block(
+        &self,
+        body: impl Into<Body>,
+    ) -> azure_core::Result<AppendBlockBuilder> {
+        self.require_blob_type(BlobType::AppendBlob).await?;
+        Ok(self.client.append_block(body))
+    }
+
+    /// Write a range of pages, after checking that the blob is a page blob.
+    pub async fn put_page(
+        &self,
+        ba512_range: BA512Range,
+        content: impl Into<Body>,
+    ) -> azure_core::Result<PutPageBuilder> {
+        self.require_blob_type(BlobType::PageBlob).await?;
+        Ok(self.client.put_page(ba512_range, content))
+    }
+
+    /// Stage a block, after checking that the blob is a block blob. A blob
+    /// which does not exist yet is fine, as staging blocks creates it.
+    pub async fn put_block(
+        &self,
+        block_id: impl Into<BlockId>,
+        body: impl Into<Body>,
+    ) -> azure_core::Result<PutBlockBuilder> {
+        if let Some(blob_type) = self.blob_type().await? {
+            check_blob_type(
+                self.client.blob_name(),
+                Some(blob_type),
+                BlobType::BlockBlob,
+            )?;
+        }
+        Ok(self.client.put_block(block_id, body))
+    }
+
+    /// Drop the cached properties, e.g. after modifying the blob.
+    pub fn invalidate(&self) {
+        *self.cache.lock().unwrap() = None;
+    }
+}
+
+fn check_blob_type(
+    blob_name: &str,
+    actual: Option<BlobType>,
+    expected: BlobType,
+) -> azure_core::Result<()> {
+    match actual {
+        Some(actual) if actual == expected => Ok(()),
+        Some(actual) => Err(Error::with_message(ErrorKind::Other, || {
+            format!("the operation needs a blob of type {expected}, but {blob_name} is of type {actual}")
+        })),
+        None => Err(Error::with_message(ErrorKind::Other, || {
+            format!("the operation needs a blob of type {expected}, but {blob_name} does not exist")
+        })),
+    }
+}
+
+impl From<BlobClient> for CachedBlobClient {
+    /// Caches the properties for 30 seconds.
+    fn from(client: BlobClient) -> Self {
+        Self::new(client, Duration::from_secs(30))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_entry_freshness() {
+        let now = OffsetDateTime::now_utc();
+        let entry = CacheEntry {
+            blob: None,
+            validated_at: now,
+        };
+        let ttl = Duration::from_secs(10);
+        assert!(entry.is_fresh(ttl, now));
+        assert!(entry.is_fresh(ttl, now + Duration::from_secs(9)));
+        assert!(!entry.is_fresh(ttl, now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_check_blob_type() {
+        assert!(check_blob_type("blob", Some(BlobType::AppendBlob), BlobType::AppendBlob).is_ok());
+        let error =
+            check_blob_type("blob", Some(BlobType::BlockBlob), BlobType::AppendBlob).unwrap_err();
+        assert!(error.to_string().contains("AppendBlob"));
+        assert!(check_blob_type("blob", None, BlobType::PageBlob).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use crate::prelude::*;
+use azure_core::error::{Error, ErrorKind};
+use azure_storage::StorageConfig;
+
+/// The configuration of a container or blob client, e.g. read from a
+/// configuration file.
+///
+/// ```
+/// # fn main() -> azure_core::Result<()> {
+/// use azure_storage_blobs::prelude::*;
+/// let config: BlobClientConfig = serde_json::from_str(
+///     r#"{
+///         "account": "myaccount",
+///         "auth": { "method": "sas_token", "token": "sv=2021-08-06&sig=SIGNATURE" },
+///         "container": "mycontainer"
+///     }"#,
+/// )?;
+/// let container_client = config.container_client()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BlobClientConfig {
+    #[serde(flatten)]
+    pub storage: StorageConfig,
+    pub container: String,
+    #[serde(default)]
+    pub blob: Option<String>,
+}
+
+impl BlobClientConfig {
+    pub fn container_client(&self) -> azure_core::Result<ContainerClient> {
+        Ok(ClientBuilder::from_config(&self.storage)?.container_client(&self.container))
+    }
+
+    /// A client for the configured blob. Fails if the configuration has no
+    /// blob.
+    pub fn blob_client(&self) -> azure_core::Result<BlobClient> {
+        let blob = self.blob.as_ref().ok_or_else(|| {
+            Error::message(
+                ErrorKind::Other,
+                "the blob client configuration has no blob",
+            )
+        })?;
+        Ok(self.container_client()?.blob_client(blob))
+    }
+}
@@ -1,18 +1,18 @@
-use crate::{clients::*, container::operations::*, prelude::PublicAccess};
+use crate::{blob::Blob, clients::*, container::operations::*, prelude::PublicAccess};
 use azure_core::{
     error::{Error, ErrorKind},
-    headers::Headers,
     prelude::*,
-    Body, Method, Request, Response, StatusCode, Url,
+    Request, Response, StatusCode, Url,
 };
 use azure_storage::{
     prelude::BlobSasPermissions,
     shared_access_signature::{
         service_sas::{BlobSharedAccessSignature, BlobSignedResource},
-        SasToken,
+        SasProtocol, SasToken,
     },
-    CloudLocation, StorageCredentials,
+    CloudLocation, StorageClient, StorageCredentials,
 };
+use futures::Future;
 use time::OffsetDateTime;
 
 #[derive(Debug, Clone)]
@@ -53,12 +53,20 @@ impl ContainerClient {
         DeleteBuilder::new(self.clone())
     }
 
+    /// Replace the user-defined metadata of the container
+    pub fn set_metadata(&self) -> SetMetadataBuilder {
+        SetMetadataBuilder::new(self.clone())
+    }
+
     /// Get a container acl
     pub fn get_acl(&self) -> GetACLBuilder {
         GetACLBuilder::new(self.clone())
     }
 
     /// Set a container acl
+    ///
+    /// The public access level and the stored access policies, if any, are
+    /// replaced together by a single request.
     pub fn set_acl(&self, public_access: PublicAccess) -> SetACLBuilder {
         SetACLBuilder::new(self.clone(), public_access)
     }
@@ -73,7 +81,62 @@ impl ContainerClient {
         ListBlobsBuilder::new(self.clone())
     }
 
-    /// Acquite a lease on a container
+    /// Run `callback` on every blob whose name starts with `prefix`, with at
+    /// most `max_concurrency` callbacks running at once.
+    pub fn for_each_blob<F, Fut>(
+        &self,
+        prefix: impl Into<Prefix>,
+        max_concurrency: usize,
+        callback: F,
+    ) -> ForEachBlobBuilder<F>
+    where
+        F: Fn(Blob) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = azure_core::Result<()>> + Send + 'static,
+    {
+        ForEachBlobBuilder::new(self.clone(), prefix.into(), max_concurrency, callback)
+    }
+
+    /// Fetch the properties of the blobs `blob_names`, with at most
+    /// `max_concurrency` requests at once.
+    pub fn get_properties_bulk<I>(
+        &self,
+        blob_names: I,
+        max_concurrency: usize,
+    ) -> GetPropertiesBulkBuilder<I>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+        I::IntoIter: Send + 'static,
+    {
+        GetPropertiesBulkBuilder::new(self.clone(), blob_names, max_concurrency)
+    }
+
+    /// Delete the blobs whose names start with `prefix` and which were last
+    /// modified before `older_than`, along with their snapshots and previous
+    /// versions when those are included.
+    ///
+    /// Use `dry_run` to see what would be deleted first.
+    pub fn purge(
+        &self,
+        prefix: impl Into<Prefix>,
+        older_than: OffsetDateTime,
+        include_snapshots: bool,
+        include_versions: bool,
+    ) -> PurgeBuilder {
+        PurgeBuilder::new(
+            self.clone(),
+            prefix.into(),
+            older_than,
+            include_snapshots,
+            include_versions,
+        )
+    }
+
+    /// Acquire a lease on a container
+    ///
+    /// `lease_duration` is a `std::time::Duration` or `time::Duration` of 15 to
+    /// 60 seconds, or [`LeaseDuration::Infinite`]. Renew a finite lease every
+    /// [`LeaseDuration::renewal_interval`] to keep it.
     pub fn acquire_lease<LD: Into<LeaseDuration>>(
         &self,
         lease_duration: LD,
@@ -114,6 +177,67 @@ impl ContainerClient {
         &self.container_name
     }
 
+    pub fn service_client(&self) -> &BlobServiceClient {
+        &self.service_client
+    }
+
+    /// A copy of the client whose requests time out after `timeout`, see
+    /// [`BlobServiceClient::with_timeout`].
+    #[must_use]
+    pub fn with_timeout(&self, timeout: std::time::Duration) -> Self {
+        Self::new(
+            self.service_client.with_timeout(timeout),
+            self.container_name.clone(),
+        )
+    }
+
+    /// A copy of the client whose requests are made with another service
+    /// version, see [`BlobServiceClient::with_api_version`].
+    #[must_use]
+    pub fn with_api_version(&self, api_version: impl Into<String>) -> Self {
+        Self::new(
+            self.service_client.with_api_version(api_version),
+            self.container_name.clone(),
+        )
+    }
+
+    /// A read-only client of the container which sends no credentials, for a
+    /// container with public read access.
+    ///
+    /// The access level is read with this client's credentials first, and a
+    /// private container is rejected. The anonymous client skips signing
+    /// requests altogether, and fails requests other than `GET` and `HEAD`
+    /// with an [`azure_storage::ReadOnlyViolation`] instead of sending them.
+    /// With [`PublicAccess::Blob`] only the blobs can be read, not the list
+    /// of blobs or the container's properties.
+    pub async fn public_reader(&self) -> azure_core::Result<ContainerClient> {
+        let container = self.get_properties().await?.container;
+        if container.public_access == PublicAccess::None {
+            return Err(Error::with_message(ErrorKind::Credential, || {
+                format!(
+                    "container {} does not allow public read access",
+                    self.container_name
+                )
+            }));
+        }
+        Ok(Self::new(
+            self.service_client
+                .with_credentials(StorageCredentials::Anonymous)
+                .read_only(),
+            self.container_name.clone(),
+        ))
+    }
+
+    /// A copy of the client which accesses the container with other
+    /// credentials, see [`BlobServiceClient::with_credentials`].
+    #[must_use]
+    pub fn with_credentials(&self, credentials: impl Into<StorageCredentials>) -> Self {
+        Self::new(
+            self.service_client.with_credentials(credentials),
+            self.container_name.clone(),
+        )
+    }
+
     /// Create a shared access signature.
     pub fn shared_access_signature(
         &self,
@@ -121,16 +245,22 @@ impl ContainerClient {
         expiry: OffsetDateTime,
     ) -> azure_core::Result<BlobSharedAccessSignature> {
         match self.service_client.credentials() {
-            StorageCredentials::Key(account, ref key) => {
-                let canonicalized_resource =
-                    format!("/blob/{}/{}", account, self.container_name(),);
-                Ok(BlobSharedAccessSignature::new(
-                    key.to_string(),
+            StorageCredentials::Key(account, ref key)
+            | StorageCredentials::KeyWithSecondary(account, ref key, _) => {
+                let canonicalized_resource = self
+                    .service_client
+                    .sas_canonicalized_resource(account, self.container_name());
+                let sas = BlobSharedAccessSignature::try_new(
+                    key.clone(),
                     canonicalized_resource,
                     permissions,
                     expiry,
                     BlobSignedResource::Container,
-                ))
+                )?;
+                Ok(match self.service_client.sas_protocol() {
+                    Some(protocol) => sas.protocol(protocol),
+                    None => sas,
+                })
             }
             _ => Err(Error::message(
                 ErrorKind::Credential,
@@ -140,11 +270,37 @@ impl ContainerClient {
         }
     }
 
+    /// Create signed URLs for many blobs of the container, e.g. to hand out
+    /// download links.
+    ///
+    /// `signature` is a container SAS, from
+    /// [`shared_access_signature`](Self::shared_access_signature), from which
+    /// a blob SAS with the same permissions and validity is derived for each
+    /// blob. The URLs are created lazily, in the order of `blob_names`. Use
+    /// [`BlobSharedAccessSignature::for_blob`] directly to sign in parallel.
+    pub fn generate_signed_blob_urls<'a, I>(
+        &'a self,
+        signature: &'a BlobSharedAccessSignature,
+        blob_names: I,
+    ) -> impl Iterator<Item = azure_core::Result<url::Url>> + 'a
+    where
+        I: IntoIterator + 'a,
+        I::Item: AsRef<str>,
+    {
+        blob_names.into_iter().map(move |blob_name| {
+            let blob_name = blob_name.as_ref();
+            let signature = signature.for_blob(blob_name)?;
+            self.blob_client(blob_name)
+                .generate_signed_blob_url(&signature)
+        })
+    }
+
     pub fn generate_signed_container_url<T>(&self, signature: &T) -> azure_core::Result<url::Url>
     where
         T: SasToken,
     {
         let mut url = self.url()?;
+        signature.validate()?;
         url.set_query(Some(&signature.token()));
         Ok(url)
     }
@@ -169,6 +325,15 @@ impl ContainerClient {
         self.service_client.credentials()
     }
 
+    pub(crate) fn sas_protocol(&self) -> Option<SasProtocol> {
+        self.service_client.sas_protocol()
+    }
+
+    pub(crate) fn sas_canonicalized_resource(&self, account: &str, path: &str) -> String {
+        self.service_client
+            .sas_canonicalized_resource(account, path)
+    }
+
     pub(crate) async fn send(
         &self,
         context: &mut Context,
@@ -176,16 +341,21 @@ impl ContainerClient {
     ) -> azure_core::Result<Response> {
         self.service_client.send(context, request).await
     }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl StorageClient for ContainerClient {
+    fn url(&self) -> azure_core::Result<Url> {
+        ContainerClient::url(self)
+    }
 
-    pub(crate) fn finalize_request(
+    async fn send(
         &self,
-        url: Url,
-        method: Method,
-        headers: Headers,
-        request_body: Option<Body>,
-    ) -> azure_core::Result<Request> {
-        self.service_client
-            .finalize_request(url, method, headers, request_body)
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response> {
+        ContainerClient::send(self, context, request).await
     }
 }
 
@@ -225,7 +395,8 @@ mod integration_tests {
             .await
             .expect("create container should succeed");
 
-        let md5 = md5::compute("world");
+        // the MD5 of "world"
+        let md5 = base64::decode("fXkwN6B2AYZXSwKC8vQ15w==").unwrap();
         container_client
             .blob_client("hello.txt")
             .put_block_blob("world")
@@ -248,7 +419,7 @@ mod integration_tests {
                 .as_ref()
                 .expect("has content_md5")
                 .as_slice(),
-            &md5.0
+            md5.as_slice()
         );
 
         container_client
@@ -257,3 +428,68 @@ mod integration_tests {
             .expect("delete container should succeed");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_storage::ReadOnlyViolation;
+
+    /// Answers Get Container Properties with the given access level.
+    fn public_container(public_access: &'static str) -> InMemoryTransport {
+        InMemoryTransport::answering(move |_| {
+            let mut headers = test_util::version_headers();
+            headers.insert("x-ms-lease-status", "unlocked");
+            headers.insert("x-ms-lease-state", "available");
+            headers.insert("x-ms-has-immutability-policy", "false");
+            headers.insert("x-ms-has-legal-hold", "false");
+            if !public_access.is_empty() {
+                headers.insert("x-ms-blob-public-access", public_access);
+            }
+            test_util::response(StatusCode::Ok, headers)
+        })
+    }
+
+    fn container_client(service: &InMemoryTransport) -> ContainerClient {
+        test_util::keyed_client_builder("account", service.clone()).container_client("container")
+    }
+
+    /// Whether each request was signed.
+    fn signed(service: &InMemoryTransport) -> Vec<bool> {
+        service
+            .requests()
+            .iter()
+            .map(|request| {
+                request
+                    .headers()
+                    .get_optional_str(&azure_core::headers::AUTHORIZATION)
+                    .is_some()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_public_reader() -> azure_core::Result<()> {
+        let service = public_container("container");
+        let reader = container_client(&service).public_reader().await?;
+        reader.get_properties().await?;
+        assert_eq!(signed(&service), vec![true, false]);
+
+        let error = reader.delete().await.unwrap_err();
+        let violation = error.downcast_ref::<ReadOnlyViolation>().unwrap();
+        assert_eq!(violation.method, azure_core::Method::Delete);
+        assert_eq!(
+            violation.url,
+            "https://account.blob.core.windows.net/container"
+        );
+        assert_eq!(service.requests().len(), 2, "nothing sent");
+
+        let service = public_container("");
+        let error = container_client(&service)
+            .public_reader()
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::Credential);
+        Ok(())
+    }
+}
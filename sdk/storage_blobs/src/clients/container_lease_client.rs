@@ -1,5 +1,6 @@
 use crate::{container::operations::*, prelude::*};
-use azure_core::{headers::Headers, prelude::*, Body, Context, Method, Request, Response, Url};
+use azure_core::{prelude::*, Context, Request, Response, Url};
+use azure_storage::StorageClient;
 
 #[derive(Debug, Clone)]
 pub struct ContainerLeaseClient {
@@ -35,22 +36,27 @@ impl ContainerLeaseClient {
         self.container_client.url()
     }
 
-    pub(crate) fn finalize_request(
+    pub(crate) async fn send(
         &self,
-        url: Url,
-        method: Method,
-        headers: Headers,
-        request_body: Option<Body>,
-    ) -> azure_core::Result<Request> {
-        self.container_client
-            .finalize_request(url, method, headers, request_body)
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response> {
+        self.container_client.send(context, request).await
     }
+}
 
-    pub(crate) async fn send(
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl StorageClient for ContainerLeaseClient {
+    fn url(&self) -> azure_core::Result<Url> {
+        ContainerLeaseClient::url(self)
+    }
+
+    async fn send(
         &self,
         context: &mut Context,
         request: &mut Request,
     ) -> azure_core::Result<Response> {
-        self.container_client.send(context, request).await
+        ContainerLeaseClient::send(self, context, request).await
     }
 }
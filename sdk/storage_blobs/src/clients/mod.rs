@@ -1,11 +1,17 @@
+mod account_registry;
 mod blob_client;
 mod blob_lease_client;
 mod blob_service_client;
+mod cached_blob_client;
+mod config;
 mod container_client;
 mod container_lease_client;
 
+pub use account_registry::StorageAccountRegistry;
 pub use blob_client::BlobClient;
 pub use blob_lease_client::BlobLeaseClient;
 pub use blob_service_client::{BlobServiceClient, ClientBuilder};
+pub use cached_blob_client::CachedBlobClient;
+pub use config::BlobClientConfig;
 pub use container_client::ContainerClient;
 pub use container_lease_client::ContainerLeaseClient;
@@ -0,0 +1,149 @@
+//! Compression of blob contents with gzip, e.g. to archive text-heavy logs
+//! when the network is the bottleneck.
+//!
+//! Uploads compress their body with
+//! [`UploadFileBuilder::gzip`](crate::blob::operations::UploadFileBuilder::gzip),
+//! which stores the blob with a `Content-Encoding` of `gzip`, and
+//! [`BlobClient::get_decompressed_content`](crate::prelude::BlobClient::get_decompressed_content)
+//! reverses it on download.
+//!
+//! Blobs uploaded in blocks are compressed as a single gzip member, which is
+//! cut into blocks, so that decoders reading only the first member of a gzip
+//! stream, such as browsers, see the whole content.
+
+use azure_core::error::{ErrorKind, ResultExt};
+use bytes::Bytes;
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
+use std::io::{Read, Write};
+
+/// Compresses a content written in pieces into a single gzip member, cut into
+/// blocks of `block_size` bytes, the last one being shorter.
+pub(crate) struct GzipBlocks {
+    encoder: GzEncoder<Vec<u8>>,
+    block_size: usize,
+}
+
+impl GzipBlocks {
+    pub(crate) fn new(block_size: usize) -> Self {
+        Self {
+            encoder: GzEncoder::new(Vec::with_capacity(block_size), Compression::default()),
+            block_size: block_size.max(1),
+        }
+    }
+
+    /// Compresses `data`, returning the blocks it completed.
+    pub(crate) fn write(&mut self, data: &[u8]) -> azure_core::Result<Vec<Bytes>> {
+        self.encoder
+            .write_all(data)
+            .context(ErrorKind::Io, "failed to compress with gzip")?;
+        Ok(split_blocks(self.encoder.get_mut(), self.block_size, false))
+    }
+
+    /// Ends the gzip member, returning the remaining blocks.
+    pub(crate) fn finish(self) -> azure_core::Result<Vec<Bytes>> {
+        let mut compressed = self
+            .encoder
+            .finish()
+            .context(ErrorKind::Io, "failed to compress with gzip")?;
+        Ok(split_blocks(&mut compressed, self.block_size, true))
+    }
+}
+
+/// Takes the full blocks out of `buffer`, and the last partial one if `all`.
+fn split_blocks(buffer: &mut Vec<u8>, block_size: usize, all: bool) -> Vec<Bytes> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    while buffer.len() - start >= block_size {
+        blocks.push(Bytes::copy_from_slice(&buffer[start..start + block_size]));
+        start += block_size;
+    }
+    if all && start < buffer.len() {
+        blocks.push(Bytes::copy_from_slice(&buffer[start..]));
+        start = buffer.len();
+    }
+    buffer.drain(..start);
+    blocks
+}
+
+/// The `Content-Encoding` of gzip compressed blobs.
+pub const GZIP: &str = "gzip";
+
+/// Compresses `data` into a gzip member.
+pub fn gzip(data: &[u8]) -> azure_core::Result<Bytes> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 2), Compression::default());
+    encoder
+        .write_all(data)
+        .and_then(|_| encoder.finish())
+        .map(Bytes::from)
+        .context(ErrorKind::Io, "failed to compress with gzip")
+}
+
+/// Decompresses `data`, made of one or more gzip members.
+pub fn gunzip(data: &[u8]) -> azure_core::Result<Vec<u8>> {
+    let mut decompressed = Vec::with_capacity(data.len() * 4);
+    MultiGzDecoder::new(data)
+        .read_to_end(&mut decompressed)
+        .context(ErrorKind::DataConversion, "failed to decompress gzip")?;
+    Ok(decompressed)
+}
+
+/// Whether a `Content-Encoding` is gzip. Encodings are case-insensitive and
+/// `x-gzip` is an alias of `gzip`.
+pub fn is_gzip(content_encoding: &str) -> bool {
+    let content_encoding = content_encoding.trim();
+    content_encoding.eq_ignore_ascii_case(GZIP) || content_encoding.eq_ignore_ascii_case("x-gzip")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gzip() {
+        let line = b"2022-11-04T10:00:00Z INFO request completed\n";
+        let first = line.repeat(1000);
+        let second = b"last line\n".to_vec();
+
+        let compressed = gzip(&first).unwrap();
+        assert!(compressed.len() < first.len() / 10);
+        assert_eq!(gunzip(&compressed).unwrap(), first);
+
+        // blocks compressed one by one decompress to their concatenation
+        let mut members = compressed.to_vec();
+        members.extend(gzip(&second).unwrap());
+        assert_eq!(gunzip(&members).unwrap(), [first.clone(), second].concat());
+
+        assert_eq!(gunzip(&gzip(b"").unwrap()).unwrap(), b"");
+        assert!(gunzip(b"not gzip").is_err());
+
+        assert!(is_gzip("gzip"));
+        assert!(is_gzip("GZip"));
+        assert!(is_gzip("x-gzip"));
+        assert!(!is_gzip("br"));
+    }
+
+    #[test]
+    fn test_gzip_blocks() {
+        let content: Vec<u8> = (0..200_000u32)
+            .flat_map(|i| format!("line {}\n", i % 977).into_bytes())
+            .collect();
+        let mut encoder = GzipBlocks::new(1024);
+        let mut blocks = Vec::new();
+        for piece in content.chunks(10_000) {
+            blocks.extend(encoder.write(piece).unwrap());
+        }
+        blocks.extend(encoder.finish().unwrap());
+        assert!(blocks.len() > 1);
+        assert!(blocks[..blocks.len() - 1]
+            .iter()
+            .all(|block| block.len() == 1024));
+
+        // a decoder reading only the first member sees the whole content
+        let compressed = blocks.concat();
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, content);
+    }
+}
@@ -0,0 +1,93 @@
+use azure_core::error::{Error, ErrorKind};
+
+/// The error returned, as the source of an `ErrorKind::HttpResponse` error,
+/// when an operation on a container with an active lease was sent without
+/// the lease id (`LeaseIdMissing`).
+///
+/// ```no_run
+/// use azure_storage_blobs::{container::LeaseRequired, prelude::*};
+///
+/// # async fn example(container_client: ContainerClient) -> azure_core::Result<()> {
+/// match container_client.delete().await {
+///     Err(error) if error.downcast_ref::<LeaseRequired>().is_some() => {
+///         // break the lease, or find its id, and try again
+///     }
+///     result => result?,
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct LeaseRequired {
+    container_name: String,
+    source: Error,
+}
+
+impl LeaseRequired {
+    pub fn container_name(&self) -> &str {
+        &self.container_name
+    }
+}
+
+impl std::fmt::Display for LeaseRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "container {} has an active lease, and the request did not pass its lease id",
+            self.container_name
+        )
+    }
+}
+
+impl std::error::Error for LeaseRequired {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Turns the service's `LeaseIdMissing` error into a [`LeaseRequired`]
+/// error. Other errors are returned as they are.
+pub(crate) fn lease_required(error: Error, container_name: &str) -> Error {
+    match error.kind() {
+        ErrorKind::HttpResponse {
+            error_code: Some(error_code),
+            ..
+        } if error_code == "LeaseIdMissing" => {
+            let kind = error.kind().clone();
+            let lease_required = LeaseRequired {
+                container_name: container_name.to_owned(),
+                source: error,
+            };
+            let message = lease_required.to_string();
+            Error::full(kind, lease_required, message)
+        }
+        _ => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+
+    #[tokio::test]
+    async fn test_lease_required() {
+        // rejects every request as the container is leased
+        let service = InMemoryTransport::answering(|_| {
+            test_util::error_response(StatusCode::PreconditionFailed, "LeaseIdMissing")
+        });
+        let container_client = service.client_builder().container_client("leased");
+
+        let error = container_client.delete().await.unwrap_err();
+        let lease_required = error.downcast_ref::<super::LeaseRequired>().unwrap();
+        assert_eq!(lease_required.container_name(), "leased");
+        assert_eq!(error.http_status(), Some(StatusCode::PreconditionFailed));
+        assert_eq!(
+            error.request_id(),
+            Some("831e1650-001e-001b-66ab-eeb76e000000")
+        );
+
+        let error = container_client.set_metadata().await.unwrap_err();
+        assert!(error.downcast_ref::<super::LeaseRequired>().is_some());
+    }
+}
@@ -1,10 +1,15 @@
 use azure_core::{
-    date,
     error::{Error, ErrorKind, ResultExt},
     headers::{self, AsHeaders, Headers},
+    Etag,
 };
+mod lease_required;
 pub mod operations;
 
+pub(crate) use lease_required::lease_required;
+pub use lease_required::LeaseRequired;
+
+use azure_core::headers::HeaderName;
 use azure_core::{
     headers::{
         BLOB_PUBLIC_ACCESS, HAS_IMMUTABILITY_POLICY, HAS_LEGAL_HOLD, LEASE_DURATION, LEASE_STATE,
@@ -17,6 +22,13 @@ use std::collections::HashMap;
 use time::OffsetDateTime;
 use xml::{Element, Xml};
 
+const DEFAULT_ENCRYPTION_SCOPE: HeaderName =
+    HeaderName::from_static("x-ms-default-encryption-scope");
+const DENY_ENCRYPTION_SCOPE_OVERRIDE: HeaderName =
+    HeaderName::from_static("x-ms-deny-encryption-scope-override");
+const IMMUTABLE_STORAGE_WITH_VERSIONING_ENABLED: HeaderName =
+    HeaderName::from_static("x-ms-immutable-storage-with-versioning-enabled");
+
 create_enum!(
     PublicAccess,
     (None, "none"),
@@ -47,13 +59,20 @@ pub(crate) fn public_access_from_header(header_map: &Headers) -> azure_core::Res
 pub struct Container {
     pub name: String,
     pub last_modified: OffsetDateTime,
-    pub e_tag: String,
+    pub e_tag: Etag,
     pub lease_status: LeaseStatus,
     pub lease_state: LeaseState,
     pub lease_duration: Option<LeaseDuration>,
     pub public_access: PublicAccess,
     pub has_immutability_policy: bool,
     pub has_legal_hold: bool,
+    /// Whether blobs can be made immutable individually.
+    pub immutable_storage_with_versioning_enabled: bool,
+    /// The encryption scope of blobs written without one.
+    pub default_encryption_scope: Option<String>,
+    /// Whether writes are restricted to the default encryption scope.
+    pub deny_encryption_scope_override: bool,
+    /// User defined metadata, without the `x-ms-meta-` prefix.
     pub metadata: HashMap<String, String>,
 }
 
@@ -68,13 +87,16 @@ impl Container {
         Container {
             name: name.to_owned(),
             last_modified: OffsetDateTime::now_utc(),
-            e_tag: "".to_owned(),
+            e_tag: Etag::from(""),
             lease_status: LeaseStatus::Unlocked,
             lease_state: LeaseState::Available,
             lease_duration: None,
             public_access: PublicAccess::None,
             has_immutability_policy: false,
             has_legal_hold: false,
+            immutable_storage_with_versioning_enabled: false,
+            default_encryption_scope: None,
+            deny_encryption_scope_override: false,
             metadata: HashMap::new(),
         }
     }
@@ -86,8 +108,7 @@ impl Container {
     where
         NAME: Into<String>,
     {
-        let last_modified = headers.get_str(&headers::LAST_MODIFIED)?;
-        let last_modified = date::parse_rfc1123(last_modified)?;
+        let last_modified = headers::last_modified_from_headers(headers)?;
 
         let e_tag = headers.get_as(&headers::ETAG)?;
 
@@ -100,11 +121,19 @@ impl Container {
 
         let has_immutability_policy = headers.get_as(&HAS_IMMUTABILITY_POLICY)?;
         let has_legal_hold = headers.get_as(&HAS_LEGAL_HOLD)?;
+        let immutable_storage_with_versioning_enabled = headers
+            .get_optional_as(&IMMUTABLE_STORAGE_WITH_VERSIONING_ENABLED)?
+            .unwrap_or_default();
+
+        let default_encryption_scope = headers.get_optional_string(&DEFAULT_ENCRYPTION_SCOPE);
+        let deny_encryption_scope_override = headers
+            .get_optional_as(&DENY_ENCRYPTION_SCOPE_OVERRIDE)?
+            .unwrap_or_default();
 
         let mut metadata: HashMap<String, String> = HashMap::new();
         for (key, value) in headers.iter() {
-            if key.as_str().starts_with(META_PREFIX.as_str()) {
-                metadata.insert(key.as_str().to_owned(), value.as_str().to_owned());
+            if let Some(key) = key.as_str().strip_prefix(META_PREFIX.as_str()) {
+                metadata.insert(key.to_owned(), value.as_str().to_owned());
             }
         }
 
@@ -118,6 +147,9 @@ impl Container {
             public_access,
             has_immutability_policy,
             has_legal_hold,
+            immutable_storage_with_versioning_enabled,
+            default_encryption_scope,
+            deny_encryption_scope_override,
             metadata,
         })
     }
@@ -125,7 +157,7 @@ impl Container {
     pub(crate) fn parse(elem: &Element) -> azure_core::Result<Container> {
         let name = cast_must(elem, &["Name"]).map_kind(ErrorKind::DataConversion)?;
         let last_modified = cast_must(elem, &["Properties", "Last-Modified"])?;
-        let e_tag = cast_must(elem, &["Properties", "Etag"])?;
+        let e_tag = cast_must::<String>(elem, &["Properties", "Etag"])?.into();
         let lease_state = cast_must(elem, &["Properties", "LeaseState"])?;
         let lease_duration = cast_optional(elem, &["Properties", "LeaseDuration"])?;
         let lease_status = cast_must(elem, &["Properties", "LeaseStatus"])?;
@@ -133,6 +165,16 @@ impl Container {
             cast_optional(elem, &["Properties", "PublicAccess"])?.unwrap_or(PublicAccess::None);
         let has_immutability_policy = cast_must(elem, &["Properties", "HasImmutabilityPolicy"])?;
         let has_legal_hold = cast_must(elem, &["Properties", "HasLegalHold"])?;
+        let immutable_storage_with_versioning_enabled = cast_optional(
+            elem,
+            &["Properties", "ImmutableStorageWithVersioningEnabled"],
+        )?
+        .unwrap_or_default();
+        let default_encryption_scope =
+            cast_optional(elem, &["Properties", "DefaultEncryptionScope"])?;
+        let deny_encryption_scope_override =
+            cast_optional(elem, &["Properties", "DenyEncryptionScopeOverride"])?
+                .unwrap_or_default();
         let metadata = {
             let mut hm = HashMap::new();
             let metadata = traverse(elem, &["Metadata"], true)?;
@@ -186,7 +228,45 @@ impl Container {
             public_access,
             has_immutability_policy,
             has_legal_hold,
+            immutable_storage_with_versioning_enabled,
+            default_encryption_scope,
+            deny_encryption_scope_override,
             metadata,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_from_response() -> azure_core::Result<()> {
+        let mut headers = Headers::new();
+        headers.insert("last-modified", "Thu, 01 Jul 2021 10:44:59 GMT");
+        headers.insert("etag", "\"0x8D93C7D4629C227\"");
+        headers.insert("x-ms-lease-status", "locked");
+        headers.insert("x-ms-lease-state", "leased");
+        headers.insert("x-ms-lease-duration", "infinite");
+        headers.insert("x-ms-blob-public-access", "blob");
+        headers.insert("x-ms-has-immutability-policy", "false");
+        headers.insert("x-ms-has-legal-hold", "true");
+        headers.insert("x-ms-default-encryption-scope", "scope");
+        headers.insert("x-ms-deny-encryption-scope-override", "true");
+        headers.insert("x-ms-meta-owner", "team");
+
+        let container = Container::from_response("container", &headers)?;
+        assert_eq!(container.lease_status, LeaseStatus::Locked);
+        assert_eq!(container.lease_duration, Some(LeaseDuration::Infinite));
+        assert_eq!(container.public_access, PublicAccess::Blob);
+        assert!(container.has_legal_hold);
+        assert!(!container.immutable_storage_with_versioning_enabled);
+        assert_eq!(container.default_encryption_scope.as_deref(), Some("scope"));
+        assert!(container.deny_encryption_scope_override);
+        assert_eq!(
+            container.metadata.get("owner").map(String::as_str),
+            Some("team")
+        );
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::prelude::*;
-use azure_core::Method;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
 use time::OffsetDateTime;
 
 operation! {
@@ -15,23 +15,19 @@ operation! {
 impl AcquireLeaseBuilder {
     pub fn into_future(mut self) -> AcquireLease {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("restype", "container");
-            url.query_pairs_mut().append_pair("comp", "lease");
-
-            let mut headers = Headers::new();
-            headers.insert(LEASE_ACTION, "acquire");
-            headers.add(self.lease_duration);
-            headers.add(self.lease_id);
-            headers.add(self.proposed_lease_id);
-            headers.add(self.if_modified_since);
-
-            let mut request = self
+            self.lease_duration.validate()?;
+            let request = self
                 .client
-                .finalize_request(url, Method::Put, headers, None)?;
+                .request(Method::Put)?
+                .restype("container")
+                .comp("lease")
+                .header_value(LEASE_ACTION, "acquire")
+                .header(self.lease_duration)
+                .header(self.lease_id)
+                .header(self.proposed_lease_id)
+                .header(self.if_modified_since);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             AcquireLeaseResponse::from_headers(response.headers())
         })
@@ -39,7 +35,7 @@ impl AcquireLeaseBuilder {
 }
 
 azure_storage::response_from_headers!(AcquireLeaseResponse ,
-    etag_from_headers => etag: String,
+    etag_from_headers => etag: Etag,
     last_modified_from_headers => last_modified: OffsetDateTime,
     lease_id_from_headers => lease_id: LeaseId,
     request_id_from_headers => request_id: RequestId,
@@ -1,6 +1,7 @@
 use crate::prelude::*;
-use azure_core::Method;
-use azure_core::{headers::*, prelude::*, RequestId};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
+use std::time::Duration;
 use time::OffsetDateTime;
 
 operation! {
@@ -13,21 +14,16 @@ operation! {
 impl BreakLeaseBuilder {
     pub fn into_future(mut self) -> BreakLease {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("restype", "container");
-            url.query_pairs_mut().append_pair("comp", "lease");
-
-            let mut headers = Headers::new();
-            headers.insert(LEASE_ACTION, "break");
-            headers.add(self.lease_id);
-            headers.add(self.lease_break_period);
-
-            let mut request = self
+            let request = self
                 .client
-                .finalize_request(url, Method::Put, headers, None)?;
+                .request(Method::Put)?
+                .restype("container")
+                .comp("lease")
+                .header_value(LEASE_ACTION, "break")
+                .header(self.lease_id)
+                .header(self.lease_break_period);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             BreakLeaseResponse::from_headers(response.headers())
         })
@@ -35,9 +31,17 @@ impl BreakLeaseBuilder {
 }
 
 azure_storage::response_from_headers!(BreakLeaseResponse,
-    etag_from_headers => etag: String,
+    etag_from_headers => etag: Etag,
     last_modified_from_headers => last_modified: OffsetDateTime,
-    lease_time_from_headers => lease_time: u8,
+    lease_time_from_headers => lease_time: Duration,
     request_id_from_headers => request_id: RequestId,
     date_from_headers => date: OffsetDateTime
 );
+
+impl BreakLeaseResponse {
+    /// When the lease is broken and another client can acquire one, as
+    /// reported by the service's clock.
+    pub fn broken_at(&self) -> OffsetDateTime {
+        self.date + self.lease_time
+    }
+}
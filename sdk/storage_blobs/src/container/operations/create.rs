@@ -1,6 +1,6 @@
 use crate::{container::PublicAccess, prelude::*};
-use azure_core::Method;
-use azure_core::{headers::AsHeaders, headers::Headers, prelude::*};
+use azure_core::{prelude::*, Method};
+use azure_storage::StorageClient;
 
 operation! {
     Create,
@@ -12,30 +12,15 @@ operation! {
 impl CreateBuilder {
     pub fn into_future(mut self) -> Create {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("restype", "container");
-
-            let mut headers = Headers::new();
+            let mut request = self.client.request(Method::Put)?.restype("container");
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
+            let request = request.header(self.public_access.unwrap_or(PublicAccess::None));
 
-            for (name, value) in self
-                .public_access
-                .unwrap_or(PublicAccess::None)
-                .as_headers()
-            {
-                headers.insert(name, value);
-            }
-
-            let mut request = self
-                .client
-                .finalize_request(url, Method::Put, headers, None)?;
-
-            let _response = self.client.send(&mut self.context, &mut request).await?;
+            let _response = self.client.execute(&mut self.context, request).await?;
 
             // TODO: Capture and return the response headers
             Ok(())
@@ -1,5 +1,6 @@
-use crate::prelude::*;
-use azure_core::{headers::Headers, prelude::*, Method};
+use crate::{container::lease_required, prelude::*};
+use azure_core::{prelude::*, Method};
+use azure_storage::StorageClient;
 
 operation! {
     Delete,
@@ -11,19 +12,18 @@ operation! {
 impl DeleteBuilder {
     pub fn into_future(mut self) -> Delete {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("restype", "container");
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-            headers.add(self.if_modified_since);
-
-            let mut request = self
+            let request = self
                 .client
-                .finalize_request(url, Method::Delete, headers, None)?;
+                .request(Method::Delete)?
+                .restype("container")
+                .header(self.lease_id)
+                .header(self.if_modified_since);
 
-            let _response = self.client.send(&mut self.context, &mut request).await?;
+            let _response = self
+                .client
+                .execute(&mut self.context, request)
+                .await
+                .map_err(|error| lease_required(error, self.client.container_name()))?;
 
             // TODO: Capture and return the response headers
             Ok(())
@@ -0,0 +1,186 @@
+use crate::{blob::Blob, prelude::*};
+use azure_core::{error::Error, prelude::*};
+use futures::{Future, TryStreamExt};
+
+/// Lists the blobs of a container and runs a callback on each of them.
+///
+/// Pages are fetched as the callbacks make progress, so at most
+/// `max_concurrency` callbacks run at once and only one page is held in
+/// memory. By default the first failing callback cancels the callbacks still
+/// running and is returned; with `continue_on_error` every blob is processed
+/// and the failures are collected in the response instead. Listing errors
+/// always end the loop.
+pub struct ForEachBlobBuilder<F> {
+    client: ContainerClient,
+    prefix: Prefix,
+    max_concurrency: usize,
+    callback: F,
+    continue_on_error: Option<bool>,
+    include_metadata: Option<bool>,
+    include_tags: Option<bool>,
+    context: Context,
+}
+
+impl<F, Fut> ForEachBlobBuilder<F>
+where
+    F: Fn(Blob) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = azure_core::Result<()>> + Send + 'static,
+{
+    pub(crate) fn new(
+        client: ContainerClient,
+        prefix: Prefix,
+        max_concurrency: usize,
+        callback: F,
+    ) -> Self {
+        Self {
+            client,
+            prefix,
+            max_concurrency,
+            callback,
+            continue_on_error: None,
+            include_metadata: None,
+            include_tags: None,
+            context: Context::new(),
+        }
+    }
+
+    setters! {
+        continue_on_error: bool => Some(continue_on_error),
+        include_metadata: bool => Some(include_metadata),
+        include_tags: bool => Some(include_tags),
+        context: Context => context,
+    }
+
+    pub fn into_future(self) -> ForEachBlob {
+        let Self {
+            client,
+            prefix,
+            max_concurrency,
+            callback,
+            continue_on_error,
+            include_metadata,
+            include_tags,
+            context,
+        } = self;
+        Box::pin(async move {
+            let continue_on_error = continue_on_error.unwrap_or(false);
+            let mut list_blobs = client.list_blobs().prefix(prefix).context(context);
+            if let Some(include_metadata) = include_metadata {
+                list_blobs = list_blobs.include_metadata(include_metadata);
+            }
+            if let Some(include_tags) = include_tags {
+                list_blobs = list_blobs.include_tags(include_tags);
+            }
+
+            let callback = &callback;
+            let results = list_blobs
+                .into_stream()
+                .map_ok(|page| {
+                    let blobs: Vec<_> = page.blobs.blobs().cloned().map(Ok).collect();
+                    futures::stream::iter(blobs)
+                })
+                .try_flatten()
+                .map_ok(|blob| async move {
+                    let blob_name = blob.name.clone();
+                    Ok::<_, Error>((blob_name, callback(blob).await))
+                })
+                .try_buffer_unordered(max_concurrency.max(1));
+            futures::pin_mut!(results);
+
+            let mut response = ForEachBlobResponse::default();
+            while let Some((blob_name, result)) = results.try_next().await? {
+                match result {
+                    Ok(()) => response.processed += 1,
+                    Err(error) if continue_on_error => {
+                        response.failures.push(BlobFailure { blob_name, error })
+                    }
+                    Err(error) => {
+                        return Err(Error::full(
+                            error.kind().clone(),
+                            error,
+                            format!("failed to process blob {blob_name}"),
+                        ))
+                    }
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+azure_core::future!(ForEachBlob);
+
+impl<F, Fut> std::future::IntoFuture for ForEachBlobBuilder<F>
+where
+    F: Fn(Blob) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = azure_core::Result<()>> + Send + 'static,
+{
+    type IntoFuture = ForEachBlob;
+    type Output = <ForEachBlob as std::future::Future>::Output;
+    fn into_future(self) -> Self::IntoFuture {
+        Self::into_future(self)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ForEachBlobResponse {
+    /// The number of blobs whose callback succeeded.
+    pub processed: u64,
+    /// The blobs whose callback failed, when continuing on errors.
+    pub failures: Vec<BlobFailure>,
+}
+
+#[derive(Debug)]
+pub struct BlobFailure {
+    pub blob_name: String,
+    pub error: Error,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::error::ErrorKind;
+
+    /// A container listing `blob0` to `blob3` in a single page.
+    fn container_client() -> ContainerClient {
+        let service = InMemoryTransport::answering(|_| {
+            let blobs: String = (0..4)
+                .map(|index| {
+                    test_util::blob_xml(&format!("blob{index}"), "", test_util::LAST_MODIFIED)
+                })
+                .collect();
+            test_util::list_blobs_response(&blobs, "")
+        });
+        service.client_builder().container_client("container")
+    }
+
+    async fn fail_on_blob2(blob: Blob) -> azure_core::Result<()> {
+        if blob.name == "blob2" {
+            Err(Error::message(ErrorKind::Other, "boom"))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_for_each_blob_continue_on_error() -> azure_core::Result<()> {
+        let response = container_client()
+            .for_each_blob("", 2, fail_on_blob2)
+            .continue_on_error(true)
+            .await?;
+        assert_eq!(response.processed, 3);
+        assert_eq!(response.failures.len(), 1);
+        assert_eq!(response.failures[0].blob_name, "blob2");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_each_blob_stops_on_error() {
+        let error = container_client()
+            .for_each_blob("", 2, fail_on_blob2)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("blob2"));
+    }
+}
@@ -2,8 +2,8 @@ use crate::{
     container::{public_access_from_header, PublicAccess},
     prelude::*,
 };
-use azure_core::{date, headers::*, prelude::*, Method, RequestId, Response};
-use azure_storage::StoredAccessPolicyList;
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId, Response};
+use azure_storage::{StorageClient, StoredAccessPolicyList};
 use time::OffsetDateTime;
 
 operation! {
@@ -15,16 +15,9 @@ operation! {
 impl GetACLBuilder {
     pub fn into_future(mut self) -> GetACL {
         Box::pin(async move {
-            let url = self.client.url()?;
+            let request = self.client.request(Method::Get)?.header(self.lease_id);
 
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-
-            let mut request = self
-                .client
-                .finalize_request(url, Method::Get, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
             GetACLResponse::from_response(response).await
         })
     }
@@ -33,7 +26,7 @@ impl GetACLBuilder {
 #[derive(Debug, Clone)]
 pub struct GetACLResponse {
     pub public_access: PublicAccess,
-    pub etag: String,
+    pub etag: Etag,
     pub last_modified: OffsetDateTime,
     pub request_id: RequestId,
     pub date: OffsetDateTime,
@@ -51,13 +44,11 @@ impl GetACLResponse {
 
         let etag = headers.get_as(&ETAG)?;
 
-        let last_modified = headers.get_str(&LAST_MODIFIED)?;
-        let last_modified = date::parse_rfc1123(last_modified)?;
+        let last_modified = last_modified_from_headers(&headers)?;
 
         let request_id = headers.get_as(&REQUEST_ID)?;
 
-        let date = headers.get_str(&DATE)?;
-        let date = date::parse_rfc1123(date)?;
+        let date = date_from_headers(&headers)?;
 
         let stored_access_policy_list = StoredAccessPolicyList::from_xml(&body)?;
 
@@ -1,10 +1,10 @@
 use crate::{container::Container, prelude::*};
-use azure_core::{date, Method};
 use azure_core::{
     headers::{self, Headers},
     prelude::*,
-    RequestId,
+    Method, RequestId,
 };
+use azure_storage::StorageClient;
 use std::convert::{TryFrom, TryInto};
 use time::OffsetDateTime;
 
@@ -17,18 +17,13 @@ operation! {
 impl GetPropertiesBuilder {
     pub fn into_future(mut self) -> GetProperties {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("restype", "container");
-
-            let mut headers = Headers::new();
-            headers.add(self.lease_id);
-
-            let mut request = self
+            let request = self
                 .client
-                .finalize_request(url, Method::Head, headers, None)?;
+                .request(Method::Head)?
+                .restype("container")
+                .header(self.lease_id);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             (self.client.container_name(), response.headers()).try_into()
         })
@@ -57,7 +52,7 @@ impl GetPropertiesResponse {
     ) -> azure_core::Result<GetPropertiesResponse> {
         let request_id = headers.get_as(&headers::REQUEST_ID)?;
 
-        let date = date::parse_rfc1123(headers.get_str(&headers::DATE)?)?;
+        let date = headers::date_from_headers(headers)?;
 
         let container = Container::from_response(container_name, headers)?;
 
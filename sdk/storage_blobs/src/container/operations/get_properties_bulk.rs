@@ -0,0 +1,125 @@
+use crate::{blob::operations::GetPropertiesResponse, prelude::*};
+use azure_core::prelude::*;
+use futures::{stream::BoxStream, StreamExt};
+
+/// Fetches the properties of many blobs of a container, with at most
+/// `max_concurrency` `HEAD` requests in flight over the client's pipeline.
+///
+/// The stream yields each blob name with its result as soon as it is known,
+/// so the results are not in the order of the names. A failure for one blob,
+/// e.g. because it does not exist, does not end the stream.
+pub struct GetPropertiesBulkBuilder<I> {
+    client: ContainerClient,
+    blob_names: I,
+    max_concurrency: usize,
+    context: Context,
+}
+
+impl<I> GetPropertiesBulkBuilder<I>
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+    I::IntoIter: Send + 'static,
+{
+    pub(crate) fn new(client: ContainerClient, blob_names: I, max_concurrency: usize) -> Self {
+        Self {
+            client,
+            blob_names,
+            max_concurrency,
+            context: Context::new(),
+        }
+    }
+
+    setters! {
+        context: Context => context,
+    }
+
+    pub fn into_stream(
+        self,
+    ) -> BoxStream<'static, (String, azure_core::Result<GetPropertiesResponse>)> {
+        let Self {
+            client,
+            blob_names,
+            max_concurrency,
+            context,
+        } = self;
+        futures::stream::iter(blob_names)
+            .map(move |blob_name| {
+                let blob_name = blob_name.into();
+                let get_properties = client
+                    .blob_client(blob_name.clone())
+                    .get_properties()
+                    .context(context.clone());
+                async move { (blob_name, get_properties.await) }
+            })
+            .buffer_unordered(max_concurrency.max(1))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+    use std::{
+        collections::BTreeMap,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_get_properties_bulk() {
+        // answers `HEAD` requests for every blob but `missing`, and records
+        // how many requests were in flight at once
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let service = InMemoryTransport::answering_async({
+            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+            move |request| {
+                let missing = request.url().path().ends_with("/missing");
+                let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                async move {
+                    let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    if missing {
+                        test_util::error_response(StatusCode::NotFound, "BlobNotFound")
+                    } else {
+                        test_util::response(StatusCode::Ok, test_util::blob_headers(8))
+                    }
+                }
+            }
+        });
+        let container_client = service.client_builder().container_client("container");
+
+        let names = ["a", "b", "missing", "c", "d"];
+        let results = container_client
+            .get_properties_bulk(names, 2)
+            .into_stream()
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(results.len(), names.len());
+        assert_eq!(results["a"].as_ref().unwrap().blob.name, "a");
+        assert_eq!(
+            results["d"]
+                .as_ref()
+                .unwrap()
+                .blob
+                .properties
+                .content_length,
+            8
+        );
+        assert_eq!(
+            results["missing"].as_ref().unwrap_err().http_status(),
+            Some(StatusCode::NotFound)
+        );
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,12 +1,14 @@
+use crate::streaming_xml::XmlItemSplitter;
 use crate::{blob::Blob, prelude::*};
-use azure_core::Method;
 use azure_core::{
     error::Error,
-    headers::{date_from_headers, request_id_from_headers, Headers},
+    headers::{date_from_headers, request_id_from_headers},
     prelude::*,
     xml::read_xml,
-    Pageable, RequestId, Response as AzureResponse,
+    Method, Pageable, RequestId, Response as AzureResponse, ResponseBody,
 };
+use azure_storage::StorageClient;
+use futures::{stream::BoxStream, StreamExt};
 use time::OffsetDateTime;
 
 operation! {
@@ -14,6 +16,7 @@ operation! {
     ListBlobs,
     client: ContainerClient,
     ?prefix: Prefix,
+    ?marker: NextMarker,
     ?delimiter: Delimiter,
     ?max_results: MaxResults,
     ?include_snapshots: bool,
@@ -26,65 +29,157 @@ operation! {
 }
 
 impl ListBlobsBuilder {
+    /// List the blobs and prefixes a page at a time.
+    ///
+    /// The entries are decoded with the same incremental decoder as
+    /// [`ListBlobsBuilder::into_item_stream`], without holding the raw body,
+    /// but each [`ListBlobsResponse`] holds all the entries of its page, so
+    /// that callers can see the page's marker and metadata alongside them.
+    /// Use `into_item_stream` to hold one entry at a time.
     pub fn into_stream(self) -> Pageable<ListBlobsResponse, Error> {
         let make_request = move |continuation: Option<NextMarker>| {
             let this = self.clone();
-            let mut ctx = self.context.clone();
             async move {
-                let mut url = this.client.url()?;
+                let response = this.send(continuation).await?;
+                ListBlobsResponse::try_from(response).await
+            }
+        };
 
-                url.query_pairs_mut().append_pair("restype", "container");
-                url.query_pairs_mut().append_pair("comp", "list");
+        Pageable::new(make_request)
+    }
 
-                if let Some(next_marker) = continuation {
-                    next_marker.append_to_url_query(&mut url);
+    /// List the blobs and prefixes one at a time, each decoded as soon as its
+    /// part of the response arrives rather than once the whole page of up to
+    /// `max_results` entries was read.
+    ///
+    /// The next page is requested when the entries of the previous page are
+    /// exhausted.
+    pub fn into_item_stream(self) -> BoxStream<'static, azure_core::Result<BlobItem>> {
+        enum Page {
+            Next(Option<NextMarker>),
+            Body(ListBlobsBody),
+        }
+
+        futures::stream::unfold(Some((self, Page::Next(None))), |state| async move {
+            let (this, mut page) = state?;
+            loop {
+                match page {
+                    Page::Next(continuation) => match this.send(continuation).await {
+                        Ok(response) => page = Page::Body(ListBlobsBody::new(response)),
+                        Err(error) => return Some((Err(error), None)),
+                    },
+                    Page::Body(mut body) => match body.next_item().await {
+                        Ok(Some(item)) => return Some((Ok(item), Some((this, Page::Body(body))))),
+                        Ok(None) => match body.finish() {
+                            Ok(internal) => match next_marker(internal.next_marker) {
+                                Some(next_marker) => page = Page::Next(Some(next_marker)),
+                                None => return None,
+                            },
+                            Err(error) => return Some((Err(error), None)),
+                        },
+                        Err(error) => return Some((Err(error), None)),
+                    },
                 }
+            }
+        })
+        .boxed()
+    }
 
-                this.prefix.append_to_url_query(&mut url);
-                this.delimiter.append_to_url_query(&mut url);
-                this.max_results.append_to_url_query(&mut url);
-
-                // This code will construct the "include" query pair
-                // attribute. It only allocates a Vec of references ('static
-                // str) and, finally, a single string.
-                let mut optional_includes = Vec::new();
-                if this.include_snapshots.unwrap_or(false) {
-                    optional_includes.push("snapshots");
-                }
-                if this.include_metadata.unwrap_or(false) {
-                    optional_includes.push("metadata");
-                }
-                if this.include_uncommitted_blobs.unwrap_or(false) {
-                    optional_includes.push("uncommittedblobs");
-                }
-                if this.include_copy.unwrap_or(false) {
-                    optional_includes.push("copy");
-                }
-                if this.include_deleted.unwrap_or(false) {
-                    optional_includes.push("deleted");
-                }
-                if this.include_tags.unwrap_or(false) {
-                    optional_includes.push("tags");
-                }
-                if this.include_versions.unwrap_or(false) {
-                    optional_includes.push("versions");
-                }
-                if !optional_includes.is_empty() {
-                    url.query_pairs_mut()
-                        .append_pair("include", &optional_includes.join(","));
-                }
+    async fn send(&self, continuation: Option<NextMarker>) -> azure_core::Result<AzureResponse> {
+        let mut ctx = self.context.clone();
+        let mut request = self
+            .client
+            .request(Method::Get)?
+            .restype("container")
+            .comp("list");
+
+        // a marker set on the builder resumes an earlier listing
+        if let Some(next_marker) = continuation.or_else(|| self.marker.clone()) {
+            request = request.query(next_marker);
+        }
+
+        request = request
+            .query(&self.prefix)
+            .query(&self.delimiter)
+            .query(self.max_results);
+
+        // This code will construct the "include" query pair
+        // attribute. It only allocates a Vec of references ('static
+        // str) and, finally, a single string.
+        let mut optional_includes = Vec::new();
+        if self.include_snapshots.unwrap_or(false) {
+            optional_includes.push("snapshots");
+        }
+        if self.include_metadata.unwrap_or(false) {
+            optional_includes.push("metadata");
+        }
+        if self.include_uncommitted_blobs.unwrap_or(false) {
+            optional_includes.push("uncommittedblobs");
+        }
+        if self.include_copy.unwrap_or(false) {
+            optional_includes.push("copy");
+        }
+        if self.include_deleted.unwrap_or(false) {
+            optional_includes.push("deleted");
+        }
+        if self.include_tags.unwrap_or(false) {
+            optional_includes.push("tags");
+        }
+        if self.include_versions.unwrap_or(false) {
+            optional_includes.push("versions");
+        }
+        if !optional_includes.is_empty() {
+            request = request.query_pair("include", &optional_includes.join(","));
+        }
+
+        self.client.execute(&mut ctx, request).await
+    }
+}
 
-                let mut request =
-                    this.client
-                        .finalize_request(url, Method::Get, Headers::new(), None)?;
+/// Decodes the entries of a list blobs response as its body arrives.
+struct ListBlobsBody {
+    body: ResponseBody,
+    splitter: XmlItemSplitter,
+}
 
-                let response = this.client.send(&mut ctx, &mut request).await?;
+impl ListBlobsBody {
+    fn new(response: AzureResponse) -> Self {
+        let (_, _, body) = response.deconstruct();
+        Self {
+            body,
+            // EnumerationResults > Blobs > Blob or BlobPrefix
+            splitter: XmlItemSplitter::new(3),
+        }
+    }
 
-                ListBlobsResponse::try_from(response).await
+    /// The next entry, or `None` at the end of the page.
+    async fn next_item(&mut self) -> azure_core::Result<Option<BlobItem>> {
+        loop {
+            if let Some(item) = self.splitter.next_item()? {
+                match item.name.as_str() {
+                    "Blob" => return Ok(Some(BlobItem::Blob(read_xml(&item.xml)?))),
+                    "BlobPrefix" => return Ok(Some(BlobItem::BlobPrefix(read_xml(&item.xml)?))),
+                    _ => continue,
+                }
             }
-        };
+            match self.body.next().await {
+                Some(bytes) => self.splitter.push(&bytes?),
+                None => return Ok(None),
+            }
+        }
+    }
 
-        Pageable::new(make_request)
+    /// The rest of the page, without its entries.
+    fn finish(self) -> azure_core::Result<ListBlobsResponseInternal> {
+        read_xml(&self.splitter.finish()?)
+    }
+}
+
+fn next_marker(next_marker: Option<String>) -> Option<NextMarker> {
+    match next_marker {
+        Some(ref nm) if nm.is_empty() => None,
+        Some(nm) => Some(nm.into()),
+        None => None,
     }
 }
 
@@ -148,25 +243,24 @@ pub struct BlobPrefix {
 
 impl ListBlobsResponse {
     pub async fn try_from(response: AzureResponse) -> azure_core::Result<Self> {
-        let (_, headers, body) = response.deconstruct();
-        let body = body.collect().await?;
+        let request_id = request_id_from_headers(response.headers())?;
+        let date = date_from_headers(response.headers())?;
 
-        let list_blobs_response_internal: ListBlobsResponseInternal = read_xml(&body)?;
-
-        let next_marker = match list_blobs_response_internal.next_marker {
-            Some(ref nm) if nm.is_empty() => None,
-            Some(nm) => Some(nm.into()),
-            None => None,
-        };
+        let mut body = ListBlobsBody::new(response);
+        let mut items = Vec::new();
+        while let Some(item) = body.next_item().await? {
+            items.push(item);
+        }
+        let list_blobs_response_internal = body.finish()?;
 
         Ok(Self {
-            request_id: request_id_from_headers(&headers)?,
-            date: date_from_headers(&headers)?,
+            request_id,
+            date,
             prefix: list_blobs_response_internal.prefix,
             max_results: list_blobs_response_internal.max_results,
             delimiter: list_blobs_response_internal.delimiter,
-            blobs: list_blobs_response_internal.blobs,
-            next_marker,
+            blobs: Blobs { items },
+            next_marker: next_marker(list_blobs_response_internal.next_marker),
         })
     }
 }
@@ -181,6 +275,7 @@ impl Continuable for ListBlobsResponse {
 #[cfg(test)]
 mod tests {
     use bytes::Bytes;
+    use time::macros::datetime;
 
     use super::*;
 
@@ -456,4 +551,156 @@ mod tests {
 
         let _list_blobs_response_internal: ListBlobsResponseInternal = read_xml(XML).unwrap();
     }
+
+    #[test]
+    fn parse_xml_with_deleted_blob() {
+        const XML: &[u8] = br#"<?xml version="1.0" encoding="utf-8"?>
+        <EnumerationResults ServiceEndpoint="https://account.blob.core.windows.net/" ContainerName="logs">
+          <Blobs>
+            <Blob>
+              <Name>2023/02/18/app.log</Name>
+              <Deleted>true</Deleted>
+              <Properties>
+                <Creation-Time>Sat, 18 Feb 2023 22:39:00 GMT</Creation-Time>
+                <Last-Modified>Sat, 18 Feb 2023 22:39:00 GMT</Last-Modified>
+                <Etag>0x8DB11F5B3C2A1E0</Etag>
+                <Content-Length>512</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+                <BlobType>BlockBlob</BlobType>
+                <AccessTier>Hot</AccessTier>
+                <AccessTierInferred>true</AccessTierInferred>
+                <ServerEncrypted>true</ServerEncrypted>
+                <DeletedTime>Mon, 20 Feb 2023 08:00:00 GMT</DeletedTime>
+                <RemainingRetentionDays>6</RemainingRetentionDays>
+              </Properties>
+              <OrMetadata />
+            </Blob>
+          </Blobs>
+          <NextMarker />
+        </EnumerationResults>"#;
+
+        let list_blobs_response_internal: ListBlobsResponseInternal = read_xml(XML).unwrap();
+        let blob = list_blobs_response_internal.blobs.blobs().next().unwrap();
+        assert!(blob.is_deleted());
+        assert_eq!(
+            blob.properties.deleted_time,
+            Some(datetime!(2023-02-20 08:00:00 UTC))
+        );
+        assert_eq!(blob.properties.remaining_retention_days, Some(6));
+        assert_eq!(
+            blob.permanent_deletion_time(datetime!(2023-02-21 12:00:00 UTC)),
+            Some(datetime!(2023-02-27 12:00:00 UTC))
+        );
+    }
+
+    #[test]
+    fn parse_xml_with_tags() {
+        const XML: &[u8] = br#"<?xml version="1.0" encoding="utf-8"?>
+        <EnumerationResults ServiceEndpoint="https://account.blob.core.windows.net/" ContainerName="logs">
+          <Blobs>
+            <Blob>
+              <Name>app.log</Name>
+              <Properties>
+                <Creation-Time>Sat, 18 Feb 2023 22:39:00 GMT</Creation-Time>
+                <Last-Modified>Sat, 18 Feb 2023 22:39:00 GMT</Last-Modified>
+                <Etag>0x8DB11F5B3C2A1E0</Etag>
+                <Content-Length>512</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+                <BlobType>BlockBlob</BlobType>
+                <ServerEncrypted>true</ServerEncrypted>
+                <TagCount>2</TagCount>
+              </Properties>
+              <OrMetadata />
+              <Tags>
+                <TagSet>
+                  <Tag><Key>project</Key><Value>archive</Value></Tag>
+                  <Tag><Key>status</Key><Value>processed</Value></Tag>
+                </TagSet>
+              </Tags>
+            </Blob>
+            <Blob>
+              <Name>untagged.log</Name>
+              <Properties>
+                <Creation-Time>Sat, 18 Feb 2023 22:39:00 GMT</Creation-Time>
+                <Last-Modified>Sat, 18 Feb 2023 22:39:00 GMT</Last-Modified>
+                <Etag>0x8DB11F5B3C2A1E1</Etag>
+                <Content-Length>0</Content-Length>
+                <Content-Type>text/plain</Content-Type>
+                <BlobType>BlockBlob</BlobType>
+              </Properties>
+            </Blob>
+          </Blobs>
+          <NextMarker />
+        </EnumerationResults>"#;
+
+        let list_blobs_response_internal: ListBlobsResponseInternal = read_xml(XML).unwrap();
+        let blobs = list_blobs_response_internal
+            .blobs
+            .blobs()
+            .collect::<Vec<_>>();
+        assert_eq!(blobs[0].properties.tag_count, Some(2));
+        assert_eq!(blobs[0].tag_count(), 2);
+        assert_eq!(blobs[0].tag("status"), Some("processed"));
+        assert_eq!(blobs[0].tag("owner"), None);
+        assert_eq!(blobs[0].tags.as_ref().unwrap().len(), 2);
+        assert_eq!(blobs[1].tag_count(), 0);
+        assert_eq!(blobs[1].tags, None);
+    }
+
+    /// Answers the first list request with a page ending in a marker, and
+    /// the second with the last page, a few bytes at a time.
+    fn container_client() -> ContainerClient {
+        let service = crate::dev::InMemoryTransport::answering(|request| {
+            let query = request.url().query().unwrap_or_default().to_owned();
+            let (name, next_marker) = if query.contains("marker=page2") {
+                ("b", "")
+            } else {
+                ("a", "page2")
+            };
+            let body = format!(
+                "\u{feff}<?xml version=\"1.0\" encoding=\"utf-8\"?><EnumerationResults ContainerName=\"c\"><Blobs>\
+                 <Blob><Name>{name}</Name><Properties><Creation-Time>Sat, 18 Feb 2023 22:39:00 GMT</Creation-Time>\
+                 <Last-Modified>Sat, 18 Feb 2023 22:39:00 GMT</Last-Modified><Etag>0x8DB11F5B3C2A1E0</Etag>\
+                 <Content-Length>1</Content-Length><Content-Type>text/plain</Content-Type>\
+                 <BlobType>BlockBlob</BlobType></Properties></Blob>\
+                 <BlobPrefix><Name>{name}/</Name></BlobPrefix></Blobs><NextMarker>{next_marker}</NextMarker></EnumerationResults>"
+            );
+            let chunks = body
+                .into_bytes()
+                .chunks(16)
+                .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+                .collect::<Vec<_>>();
+            Ok(AzureResponse::new(
+                azure_core::StatusCode::Ok,
+                crate::test_util::service_headers(),
+                Box::pin(futures::stream::iter(chunks)),
+            ))
+        });
+        service.client_builder().container_client("c")
+    }
+
+    #[tokio::test]
+    async fn test_item_stream() -> azure_core::Result<()> {
+        let items = container_client()
+            .list_blobs()
+            .delimiter("/")
+            .into_item_stream()
+            .map(|item| {
+                item.map(|item| match item {
+                    BlobItem::Blob(blob) => blob.name,
+                    BlobItem::BlobPrefix(prefix) => prefix.name,
+                })
+            })
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<azure_core::Result<Vec<_>>>()?;
+        assert_eq!(items, ["a", "a/", "b", "b/"]);
+
+        let mut pages = container_client().list_blobs().into_stream();
+        let page = pages.next().await.unwrap()?;
+        assert_eq!(page.blobs.items.len(), 2);
+        assert_eq!(page.next_marker, Some(NextMarker::new("page2".to_owned())));
+        Ok(())
+    }
 }
@@ -2,19 +2,27 @@ pub mod acquire_lease;
 pub mod break_lease;
 pub mod create;
 pub mod delete;
+pub mod for_each_blob;
 pub mod get_acl;
 pub mod get_properties;
+pub mod get_properties_bulk;
 pub mod list_blobs;
+pub mod purge;
 pub mod release_lease;
 pub mod renew_lease;
 pub mod set_acl;
+pub mod set_metadata;
 pub use self::acquire_lease::*;
 pub use self::break_lease::*;
 pub use self::create::*;
 pub use self::delete::*;
+pub use self::for_each_blob::*;
 pub use self::get_acl::*;
 pub use self::get_properties::*;
+pub use self::get_properties_bulk::*;
 pub use self::list_blobs::*;
+pub use self::purge::*;
 pub use self::release_lease::*;
 pub use self::renew_lease::*;
 pub use self::set_acl::*;
+pub use self::set_metadata::*;
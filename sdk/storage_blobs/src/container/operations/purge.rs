@@ -0,0 +1,244 @@
+use crate::{blob::Blob, container::operations::BlobFailure, prelude::*};
+use azure_core::{error::Error, prelude::*, StatusCode};
+use futures::{future, stream, Stream, TryStreamExt};
+use time::OffsetDateTime;
+
+const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+operation! {
+    /// Deletes the blobs of a container whose names start with a prefix and
+    /// which were last modified before a cutoff.
+    ///
+    /// Snapshots and previous versions are deleted on their own when they are
+    /// included. Deleting a base blob always takes its snapshots with it, as
+    /// the service does not allow deleting a blob which has snapshots
+    /// otherwise. Blobs which are already soft deleted are left alone.
+    ///
+    /// `into_stream` reports each blob as it is handled; awaiting the builder
+    /// sums the reports up.
+    Purge,
+    client: ContainerClient,
+    prefix: Prefix,
+    older_than: OffsetDateTime,
+    include_snapshots: bool,
+    include_versions: bool,
+    ?max_concurrency: usize,
+    ?dry_run: bool
+}
+
+impl PurgeBuilder {
+    /// One item per matching blob, snapshot or version, in the order their
+    /// deletions complete. Listing errors end the stream.
+    pub fn into_stream(self) -> impl Stream<Item = azure_core::Result<PurgedBlob>> {
+        let list_blobs = self
+            .client
+            .list_blobs()
+            .prefix(self.prefix.clone())
+            .include_snapshots(self.include_snapshots)
+            .include_versions(self.include_versions)
+            .context(self.context.clone());
+        let older_than = self.older_than;
+        let max_concurrency = self
+            .max_concurrency
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+            .max(1);
+
+        list_blobs
+            .into_stream()
+            .map_ok(|page| {
+                let blobs: Vec<_> = page.blobs.blobs().cloned().map(Ok).collect();
+                stream::iter(blobs)
+            })
+            .try_flatten()
+            .try_filter(move |blob| future::ready(is_expired(blob, older_than)))
+            .map_ok(move |blob| {
+                let this = self.clone();
+                async move { Ok(this.purge_blob(blob).await) }
+            })
+            .try_buffer_unordered(max_concurrency)
+    }
+
+    pub fn into_future(self) -> Purge {
+        Box::pin(async move {
+            let results = self.into_stream();
+            futures::pin_mut!(results);
+
+            let mut response = PurgeResponse::default();
+            while let Some(purged) = results.try_next().await? {
+                match purged.outcome {
+                    PurgeOutcome::Deleted | PurgeOutcome::WouldDelete => response.purged += 1,
+                    PurgeOutcome::Failed(error) => response.failures.push(BlobFailure {
+                        blob_name: purged.blob.name,
+                        error,
+                    }),
+                }
+            }
+            Ok(response)
+        })
+    }
+
+    async fn purge_blob(&self, blob: Blob) -> PurgedBlob {
+        if self.dry_run.unwrap_or(false) {
+            return PurgedBlob {
+                blob,
+                outcome: PurgeOutcome::WouldDelete,
+            };
+        }
+
+        let blob_client = self.client.blob_client(&blob.name);
+        let result = match (&blob.snapshot, &blob.version_id) {
+            (Some(snapshot), _) => blob_client
+                .delete_snapshot(*snapshot)
+                .context(self.context.clone())
+                .await
+                .map(|_| ()),
+            (None, Some(version_id)) if blob.is_current_version != Some(true) => blob_client
+                .delete_version_id(*version_id)
+                .context(self.context.clone())
+                .await
+                .map(|_| ()),
+            _ => blob_client
+                .delete()
+                .delete_snapshots_method(DeleteSnapshotsMethod::Include)
+                .context(self.context.clone())
+                .await
+                .map(|_| ()),
+        };
+        let outcome = match result {
+            Ok(()) => PurgeOutcome::Deleted,
+            // a snapshot can go along with its base blob before its own turn
+            Err(error) if error.http_status() == Some(StatusCode::NotFound) => {
+                PurgeOutcome::Deleted
+            }
+            Err(error) => PurgeOutcome::Failed(error),
+        };
+        PurgedBlob { blob, outcome }
+    }
+}
+
+fn is_expired(blob: &Blob, older_than: OffsetDateTime) -> bool {
+    blob.deleted != Some(true) && blob.properties.last_modified < older_than
+}
+
+#[derive(Debug)]
+pub struct PurgedBlob {
+    /// The blob, snapshot or version, as listed.
+    pub blob: Blob,
+    pub outcome: PurgeOutcome,
+}
+
+#[derive(Debug)]
+pub enum PurgeOutcome {
+    Deleted,
+    /// The blob matched, but nothing was deleted as this is a dry run.
+    WouldDelete,
+    Failed(Error),
+}
+
+#[derive(Debug, Default)]
+pub struct PurgeResponse {
+    /// The number of blobs, snapshots and versions deleted, or which would
+    /// have been in a dry run.
+    pub purged: u64,
+    pub failures: Vec<BlobFailure>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::{Method, StatusCode};
+
+    /// Lists a fixed set of blobs and accepts their deletion.
+    fn container() -> InMemoryTransport {
+        InMemoryTransport::answering(|request| {
+            if request.method() == &Method::Delete {
+                let mut headers = test_util::service_headers();
+                headers.insert("x-ms-delete-type-permanent", "false");
+                return test_util::response(StatusCode::Accepted, headers);
+            }
+
+            let old = "Thu, 01 Jul 2021 10:44:59 GMT";
+            let new = "Fri, 01 Jul 2022 10:44:59 GMT";
+            let blobs = [
+                test_util::blob_xml("logs/old", "", old),
+                test_util::blob_xml(
+                    "logs/old",
+                    "<Snapshot>2021-07-01T10:44:59.0000000Z</Snapshot>",
+                    old,
+                ),
+                test_util::blob_xml(
+                    "logs/versioned",
+                    "<VersionId>2021-07-01T10:44:59.0000000Z</VersionId>",
+                    old,
+                ),
+                test_util::blob_xml("logs/deleted", "<Deleted>true</Deleted>", old),
+                test_util::blob_xml("logs/new", "", new),
+            ]
+            .concat();
+            test_util::list_blobs_response(&blobs, "")
+        })
+    }
+
+    /// The paths and queries of the deletions.
+    fn deleted(service: &InMemoryTransport) -> Vec<String> {
+        service
+            .requests()
+            .iter()
+            .filter(|request| request.method() == &Method::Delete)
+            .map(|request| {
+                let url = request.url();
+                let query = url.query().map(|q| format!("?{q}")).unwrap_or_default();
+                format!("{}{query}", url.path())
+            })
+            .collect()
+    }
+
+    fn container_client(service: &InMemoryTransport) -> ContainerClient {
+        service.client_builder().container_client("container")
+    }
+
+    fn cutoff() -> OffsetDateTime {
+        azure_core::date::parse_rfc3339("2022-01-01T00:00:00Z").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_purge() -> azure_core::Result<()> {
+        let service = container();
+        let response = container_client(&service)
+            .purge("logs/", cutoff(), true, true)
+            .max_concurrency(1usize)
+            .await?;
+        assert_eq!(response.purged, 3);
+        assert!(response.failures.is_empty());
+
+        let mut deleted = deleted(&service);
+        deleted.sort();
+        assert_eq!(
+            deleted,
+            vec![
+                "/container/logs/old",
+                "/container/logs/old?snapshot=2021-07-01T10%3A44%3A59.0000000Z",
+                "/container/logs/versioned?versionid=2021-07-01T10%3A44%3A59.0000000Z",
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_purge_dry_run() -> azure_core::Result<()> {
+        let service = container();
+        let purged: Vec<_> = container_client(&service)
+            .purge("logs/", cutoff(), false, false)
+            .dry_run(true)
+            .into_stream()
+            .try_collect()
+            .await?;
+        assert_eq!(purged.len(), 3);
+        assert!(purged
+            .iter()
+            .all(|purged| matches!(purged.outcome, PurgeOutcome::WouldDelete)));
+        assert!(deleted(&service).is_empty());
+        Ok(())
+    }
+}
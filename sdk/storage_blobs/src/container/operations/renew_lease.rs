@@ -1,6 +1,6 @@
 use crate::{container::operations::AcquireLeaseResponse, prelude::*};
-use azure_core::headers::*;
-use azure_core::Method;
+use azure_core::{headers::*, Method};
+use azure_storage::StorageClient;
 
 pub type RenewLeaseResponse = AcquireLeaseResponse;
 
@@ -12,20 +12,15 @@ operation! {
 impl RenewLeaseBuilder {
     pub fn into_future(mut self) -> RenewLease {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("restype", "container");
-            url.query_pairs_mut().append_pair("comp", "lease");
-
-            let mut headers = Headers::new();
-            headers.insert(LEASE_ACTION, "renew");
-            headers.add(self.client.lease_id());
-
-            let mut request = self
+            let request = self
                 .client
-                .finalize_request(url, Method::Put, headers, None)?;
+                .request(Method::Put)?
+                .restype("container")
+                .comp("lease")
+                .header_value(LEASE_ACTION, "renew")
+                .header(self.client.lease_id());
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             RenewLeaseResponse::from_headers(response.headers())
         })
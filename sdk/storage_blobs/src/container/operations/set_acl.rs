@@ -1,6 +1,10 @@
-use crate::{container::public_access_from_header, prelude::*};
-use azure_core::{headers::*, prelude::*, Body, Method};
-use azure_storage::StoredAccessPolicyList;
+use crate::{
+    container::{lease_required, public_access_from_header},
+    prelude::*,
+};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::{StorageClient, StoredAccessPolicyList};
+use time::OffsetDateTime;
 
 operation! {
     SetACL,
@@ -14,29 +18,96 @@ operation! {
 impl SetACLBuilder {
     pub fn into_future(mut self) -> SetACL {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("restype", "container");
-            url.query_pairs_mut().append_pair("comp", "acl");
-
-            let xml = self.stored_access_policy_list.map(|xml| xml.to_xml());
-
-            let mut headers = Headers::new();
-            for (name, value) in self.public_access.as_headers() {
-                headers.insert(name, value);
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .restype("container")
+                .comp("acl")
+                .header(self.public_access)
+                .header(self.lease_id)
+                .header(self.if_modified_since);
+            if let Some(stored_access_policy_list) = &self.stored_access_policy_list {
+                request = request.body(stored_access_policy_list.to_xml());
             }
-            headers.add(self.lease_id);
-            headers.add(self.if_modified_since);
 
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Put, headers, xml.map(Body::from))?;
+            let response = self
+                .client
+                .execute(&mut self.context, request)
+                .await
+                .map_err(|error| lease_required(error, self.client.container_name()))?;
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            SetACLResponse::from_headers(response.headers(), self.public_access)
+        })
+    }
+}
 
-            public_access_from_header(response.headers())
+#[derive(Debug, Clone)]
+pub struct SetACLResponse {
+    /// The public access level of the container once the request succeeded.
+    pub public_access: PublicAccess,
+    pub etag: Etag,
+    /// Pass it as `IfModifiedSinceCondition::Unmodified` to the next
+    /// `set_acl` to fail rather than overwrite a concurrent change.
+    pub last_modified: OffsetDateTime,
+    pub request_id: RequestId,
+    pub date: OffsetDateTime,
+}
+
+impl SetACLResponse {
+    fn from_headers(headers: &Headers, requested: PublicAccess) -> azure_core::Result<Self> {
+        // the service does not echo the level it set
+        let public_access = match headers.get_optional_str(&BLOB_PUBLIC_ACCESS) {
+            Some(_) => public_access_from_header(headers)?,
+            None => requested,
+        };
+        Ok(Self {
+            public_access,
+            etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
+            request_id: request_id_from_headers(headers)?,
+            date: date_from_headers(headers)?,
         })
     }
 }
 
-type SetACLResponse = PublicAccess;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use azure_core::StatusCode;
+    use azure_storage::StoredAccessPolicy;
+
+    #[tokio::test]
+    async fn test_set_acl() -> azure_core::Result<()> {
+        let service = InMemoryTransport::answering(|_| {
+            test_util::response(StatusCode::Ok, test_util::version_headers())
+        });
+        let container_client = service.client_builder().container_client("container");
+
+        let start = OffsetDateTime::UNIX_EPOCH;
+        let mut policies = StoredAccessPolicyList::default();
+        policies.stored_access.push(StoredAccessPolicy::new(
+            "readers",
+            start,
+            start + time::Duration::days(1),
+            "r",
+        ));
+        let response = container_client
+            .set_acl(PublicAccess::Blob)
+            .stored_access_policy_list(policies)
+            .await?;
+        assert_eq!(response.public_access, PublicAccess::Blob);
+        assert_eq!(response.etag.opaque_tag(), "0x8D93C7D4629C227");
+
+        // the access level and the policies are set by a single request
+        let requests = service.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].headers().get_optional_str(&BLOB_PUBLIC_ACCESS),
+            Some("blob")
+        );
+        let body = test_util::body_bytes(&requests[0]);
+        assert!(String::from_utf8_lossy(&body).contains("<Id>readers"));
+        Ok(())
+    }
+}
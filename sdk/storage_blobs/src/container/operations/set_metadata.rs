@@ -0,0 +1,62 @@
+use crate::{container::lease_required, prelude::*};
+use azure_core::{headers::*, prelude::*, Etag, Method, RequestId};
+use azure_storage::StorageClient;
+use std::convert::{TryFrom, TryInto};
+use time::OffsetDateTime;
+
+operation! {
+    /// Replaces the user-defined metadata of the container. Metadata not
+    /// passed is removed.
+    SetMetadata,
+    client: ContainerClient,
+    ?lease_id: LeaseId,
+    ?if_modified_since: IfModifiedSinceCondition,
+    ?metadata: Metadata
+}
+
+impl SetMetadataBuilder {
+    pub fn into_future(mut self) -> SetMetadata {
+        Box::pin(async move {
+            let mut request = self
+                .client
+                .request(Method::Put)?
+                .restype("container")
+                .comp("metadata")
+                .header(self.lease_id)
+                .header(self.if_modified_since);
+            if let Some(metadata) = &self.metadata {
+                for m in metadata.iter() {
+                    request = request.header(m);
+                }
+            }
+
+            let response = self
+                .client
+                .execute(&mut self.context, request)
+                .await
+                .map_err(|error| lease_required(error, self.client.container_name()))?;
+            response.headers().try_into()
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetMetadataResponse {
+    pub request_id: RequestId,
+    pub etag: Etag,
+    pub last_modified: OffsetDateTime,
+    pub date: OffsetDateTime,
+}
+
+impl TryFrom<&Headers> for SetMetadataResponse {
+    type Error = azure_core::Error;
+
+    fn try_from(headers: &Headers) -> Result<Self, Self::Error> {
+        Ok(SetMetadataResponse {
+            request_id: request_id_from_headers(headers)?,
+            etag: etag_from_headers(headers)?,
+            last_modified: last_modified_from_headers(headers)?,
+            date: date_from_headers(headers)?,
+        })
+    }
+}
@@ -0,0 +1,535 @@
+use azure_core::{
+    date,
+    error::{Error, ErrorKind},
+    headers::{self, Headers},
+    Body, HttpClient, Method, Request, Response, StatusCode, TransportOptions,
+};
+use bytes::Bytes;
+use futures::{future::BoxFuture, AsyncReadExt, FutureExt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+use time::OffsetDateTime;
+
+/// An in-memory stand-in for the blob service, to test and benchmark code
+/// built on the clients without a network or an emulator.
+///
+/// It understands creating and deleting containers, Put Blob, Put Block, Put
+/// Block List, Get Blob (including ranges), Get Blob Properties and Delete
+/// Blob. Other requests are answered with `501 Not Implemented`. Conditions,
+/// but for `If-Match` on Get Blob, leases and authorization are ignored.
+/// Blobs are keyed by the URL path, so clients should address the account by
+/// host name, as [`ClientBuilder::new`](crate::prelude::ClientBuilder::new)
+/// does. Clones share the stored data and the recorded requests.
+///
+/// To test how code handles errors, or answers this service does not model,
+/// [`InMemoryTransport::answering`] replaces the service with a closure.
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> azure_core::Result<()> {
+/// use azure_storage::StorageCredentials;
+/// use azure_storage_blobs::{dev::InMemoryTransport, prelude::*};
+///
+/// let transport = InMemoryTransport::new();
+/// let blob_client = ClientBuilder::new("account", StorageCredentials::anonymous())
+///     .transport(transport.clone())
+///     .blob_client("container", "blob");
+/// blob_client.put_block_blob("hello").await?;
+/// assert_eq!(blob_client.get_content().await?, b"hello");
+/// assert_eq!(transport.blob_content("container", "blob").unwrap(), "hello");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct InMemoryTransport {
+    state: Arc<Mutex<State>>,
+    answer: Option<Arc<Answer>>,
+    requests: Option<Arc<Mutex<Vec<Request>>>>,
+}
+
+type Answer = dyn Fn(&Request) -> BoxFuture<'static, azure_core::Result<Response>> + Send + Sync;
+
+#[derive(Debug, Default)]
+struct State {
+    containers: HashSet<String>,
+    blobs: HashMap<String, StoredBlob>,
+    /// Staged blocks, keyed by blob path and block id.
+    blocks: HashMap<(String, String), Bytes>,
+    versions: u64,
+}
+
+#[derive(Debug, Clone)]
+struct StoredBlob {
+    content: Bytes,
+    blob_type: String,
+    content_type: Option<String>,
+    etag: String,
+    created: OffsetDateTime,
+    last_modified: OffsetDateTime,
+}
+
+impl InMemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A transport answering every request with `answer` instead of the
+    /// in-memory service, and recording the requests.
+    pub fn answering(
+        answer: impl Fn(&Request) -> azure_core::Result<Response> + Send + Sync + 'static,
+    ) -> Self {
+        Self::answering_async(move |request| futures::future::ready(answer(request)))
+    }
+
+    /// Like [`InMemoryTransport::answering`], with answers that take time,
+    /// e.g. to observe concurrent requests.
+    pub fn answering_async<F>(answer: impl Fn(&Request) -> F + Send + Sync + 'static) -> Self
+    where
+        F: Future<Output = azure_core::Result<Response>> + Send + 'static,
+    {
+        Self {
+            answer: Some(Arc::new(move |request| answer(request).boxed())),
+            ..Self::default()
+        }
+        .recording()
+    }
+
+    /// Records the requests from now on, to inspect them with
+    /// [`InMemoryTransport::requests`].
+    pub fn recording(mut self) -> Self {
+        self.requests.get_or_insert_with(Default::default);
+        self
+    }
+
+    /// The requests recorded so far, empty unless the transport is
+    /// [recording](InMemoryTransport::recording).
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests
+            .as_ref()
+            .map(|requests| requests.lock().unwrap().clone())
+            .unwrap_or_default()
+    }
+
+    /// The requests recorded so far, forgetting them.
+    pub fn take_requests(&self) -> Vec<Request> {
+        self.requests
+            .as_ref()
+            .map(|requests| std::mem::take(&mut *requests.lock().unwrap()))
+            .unwrap_or_default()
+    }
+
+    /// The content of a stored blob.
+    pub fn blob_content(&self, container: &str, blob: &str) -> Option<Bytes> {
+        self.state
+            .lock()
+            .unwrap()
+            .blobs
+            .get(&format!("{container}/{blob}"))
+            .map(|blob| blob.content.clone())
+    }
+
+    /// The number of stored blobs.
+    pub fn blob_count(&self) -> usize {
+        self.state.lock().unwrap().blobs.len()
+    }
+
+    /// The number of blocks staged and not yet committed, across all blobs.
+    pub fn staged_block_count(&self) -> usize {
+        self.state.lock().unwrap().blocks.len()
+    }
+
+    fn handle(&self, request: &Request, body: Bytes) -> Response {
+        let path = request.url().path().trim_start_matches('/').to_owned();
+        let query: HashMap<String, String> = request.url().query_pairs().into_owned().collect();
+        let comp = query.get("comp").map(String::as_str);
+        let is_container = query.get("restype").map(String::as_str) == Some("container");
+        let mut state = self.state.lock().unwrap();
+
+        match (request.method(), is_container, comp) {
+            (Method::Put, true, None) => {
+                if state.containers.insert(path) {
+                    response(StatusCode::Created, state.new_version_headers())
+                } else {
+                    error(StatusCode::Conflict, "ContainerAlreadyExists")
+                }
+            }
+            (Method::Delete, true, None) => {
+                if state.containers.remove(&path) {
+                    let prefix = format!("{path}/");
+                    state.blobs.retain(|name, _| !name.starts_with(&prefix));
+                    response(StatusCode::Accepted, Headers::new())
+                } else {
+                    error(StatusCode::NotFound, "ContainerNotFound")
+                }
+            }
+            (Method::Put, false, None) => {
+                let blob_type = request
+                    .headers()
+                    .get_optional_string(&headers::BLOB_TYPE)
+                    .unwrap_or_else(|| "BlockBlob".to_owned());
+                let content_type = request
+                    .headers()
+                    .get_optional_string(&headers::CONTENT_TYPE);
+                state.put_blob(path, body, blob_type, content_type, request)
+            }
+            (Method::Put, false, Some("block")) => match query.get("blockid") {
+                Some(block_id) => {
+                    state.blocks.insert((path, block_id.clone()), body);
+                    let mut headers = Headers::new();
+                    headers.insert(headers::REQUEST_SERVER_ENCRYPTED, "true");
+                    response(StatusCode::Created, headers)
+                }
+                None => error(StatusCode::BadRequest, "MissingRequiredQueryParameter"),
+            },
+            (Method::Put, false, Some("blocklist")) => {
+                let mut content = Vec::new();
+                for block_id in block_list_ids(&body) {
+                    match state.blocks.get(&(path.clone(), block_id)) {
+                        Some(block) => content.extend_from_slice(block),
+                        None => return error(StatusCode::BadRequest, "InvalidBlockList"),
+                    }
+                }
+                state.blocks.retain(|(name, _), _| name != &path);
+                let content_type =
+                    request
+                        .headers()
+                        .get_optional_string(&headers::HeaderName::from_static(
+                            "x-ms-blob-content-type",
+                        ));
+                state.put_blob(
+                    path,
+                    content.into(),
+                    "BlockBlob".to_owned(),
+                    content_type,
+                    request,
+                )
+            }
+            (Method::Get | Method::Head, false, None) => match state.blobs.get(&path) {
+                Some(blob) => blob.get(request),
+                None => error(StatusCode::NotFound, "BlobNotFound"),
+            },
+            (Method::Delete, false, None) => match state.blobs.remove(&path) {
+                Some(_) => {
+                    let mut headers = Headers::new();
+                    headers.insert(headers::DELETE_TYPE_PERMANENT, "false");
+                    response(StatusCode::Accepted, headers)
+                }
+                None => error(StatusCode::NotFound, "BlobNotFound"),
+            },
+            _ => error(StatusCode::NotImplemented, "NotImplemented"),
+        }
+    }
+}
+
+impl State {
+    fn new_version_headers(&mut self) -> Headers {
+        self.versions += 1;
+        let mut headers = Headers::new();
+        headers.insert(headers::ETAG, format!("\"0x{:X}\"", self.versions));
+        headers.insert(
+            headers::LAST_MODIFIED,
+            date::to_rfc1123(&OffsetDateTime::now_utc()),
+        );
+        headers
+    }
+
+    fn put_blob(
+        &mut self,
+        path: String,
+        content: Bytes,
+        blob_type: String,
+        content_type: Option<String>,
+        request: &Request,
+    ) -> Response {
+        let mut headers = self.new_version_headers();
+        // like the service, echoes the hash the request was sent with
+        for name in [headers::CONTENT_MD5, azure_storage::headers::CONTENT_CRC64] {
+            if let Some(hash) = request.headers().get_optional_str(&name) {
+                headers.insert(name, hash.to_owned());
+            }
+        }
+        headers.insert(headers::REQUEST_SERVER_ENCRYPTED, "true");
+        let now = OffsetDateTime::now_utc();
+        let created = self.blobs.get(&path).map_or(now, |blob| blob.created);
+        self.blobs.insert(
+            path,
+            StoredBlob {
+                content,
+                blob_type,
+                content_type,
+                etag: headers
+                    .get_optional_string(&headers::ETAG)
+                    .unwrap_or_default(),
+                created,
+                last_modified: now,
+            },
+        );
+        response(StatusCode::Created, headers)
+    }
+}
+
+impl StoredBlob {
+    fn get(&self, request: &Request) -> Response {
+        if let Some(etag) = request.headers().get_optional_str(&headers::IF_MATCH) {
+            if etag != "*" && etag != self.etag {
+                return error(StatusCode::PreconditionFailed, "ConditionNotMet");
+            }
+        }
+        let len = self.content.len() as u64;
+        let mut headers = Headers::new();
+        headers.insert(headers::ETAG, self.etag.clone());
+        headers.insert(
+            headers::LAST_MODIFIED,
+            date::to_rfc1123(&self.last_modified),
+        );
+        headers.insert(headers::CREATION_TIME, date::to_rfc1123(&self.created));
+        headers.insert(headers::BLOB_TYPE, self.blob_type.clone());
+        headers.insert(headers::SERVER_ENCRYPTED, "true");
+        if let Some(content_type) = &self.content_type {
+            headers.insert(headers::CONTENT_TYPE, content_type.clone());
+        }
+
+        let range = request
+            .headers()
+            .get_optional_str(&headers::MS_RANGE)
+            .or_else(|| request.headers().get_optional_str(&headers::RANGE))
+            .and_then(parse_range);
+        let (status, content) = match range {
+            Some((start, _)) if start >= len => {
+                let mut headers = error_headers("InvalidRange");
+                headers.insert(headers::CONTENT_RANGE, format!("bytes */{len}"));
+                return response(StatusCode::RequestedRangeNotSatisfiable, headers);
+            }
+            Some((start, end)) => {
+                let end = end.map_or(len, |end| (end + 1).min(len));
+                headers.insert(
+                    headers::CONTENT_RANGE,
+                    format!("bytes {start}-{}/{len}", end - 1),
+                );
+                (
+                    StatusCode::PartialContent,
+                    self.content.slice(start as usize..end as usize),
+                )
+            }
+            None => (StatusCode::Ok, self.content.clone()),
+        };
+        headers.insert(headers::CONTENT_LENGTH, content.len().to_string());
+
+        if request.method() == &Method::Head {
+            response(status, headers)
+        } else {
+            response_with_body(status, headers, content)
+        }
+    }
+}
+
+/// Parses `bytes=<start>-[<end>]`.
+fn parse_range(range: &str) -> Option<(u64, Option<u64>)> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    let end = match end {
+        "" => None,
+        end => Some(end.parse().ok()?),
+    };
+    Some((start.parse().ok()?, end))
+}
+
+/// The block ids of a Put Block List body, in order. Committed and
+/// uncommitted blocks are not told apart.
+fn block_list_ids(body: &[u8]) -> Vec<String> {
+    let body = String::from_utf8_lossy(body);
+    let mut ids = Vec::new();
+    let mut rest = body.as_ref();
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let tag_end = match rest.find('>') {
+            Some(tag_end) => tag_end,
+            None => break,
+        };
+        let tag = &rest[..tag_end];
+        rest = &rest[tag_end + 1..];
+        if matches!(tag, "Latest" | "Committed" | "Uncommitted") {
+            if let Some(end) = rest.find("</") {
+                ids.push(rest[..end].to_owned());
+                rest = &rest[end..];
+            }
+        }
+    }
+    ids
+}
+
+fn error_headers(error_code: &str) -> Headers {
+    let mut headers = Headers::new();
+    headers.insert(headers::ERROR_CODE, error_code.to_owned());
+    headers
+}
+
+fn error(status: StatusCode, error_code: &str) -> Response {
+    response(status, error_headers(error_code))
+}
+
+fn response(status: StatusCode, headers: Headers) -> Response {
+    response_with_body(status, headers, Bytes::new())
+}
+
+fn response_with_body(status: StatusCode, mut headers: Headers, body: Bytes) -> Response {
+    headers.insert(headers::DATE, date::to_rfc1123(&OffsetDateTime::now_utc()));
+    headers.insert(headers::REQUEST_ID, uuid::Uuid::new_v4().to_string());
+    Response::new(
+        status,
+        headers,
+        Box::pin(futures::stream::once(async move { Ok(body) })),
+    )
+}
+
+#[async_trait::async_trait]
+impl HttpClient for InMemoryTransport {
+    async fn execute_request(&self, request: &Request) -> azure_core::Result<Response> {
+        if let Some(requests) = &self.requests {
+            requests.lock().unwrap().push(request.clone());
+        }
+        if let Some(answer) = &self.answer {
+            return answer(request).await;
+        }
+        let body = match request.body() {
+            Body::Bytes(bytes) => bytes.clone(),
+            Body::SeekableStream(stream) => {
+                let mut stream = stream.clone();
+                stream.reset().await?;
+                let mut body = Vec::with_capacity(stream.len());
+                stream
+                    .read_to_end(&mut body)
+                    .await
+                    .map_err(|error| Error::new(ErrorKind::Io, error))?;
+                body.into()
+            }
+        };
+        Ok(self.handle(request, body))
+    }
+}
+
+impl fmt::Debug for InMemoryTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryTransport")
+            .field("state", &self.state)
+            .field("answering", &self.answer.is_some())
+            .field("requests", &self.requests)
+            .finish()
+    }
+}
+
+impl From<InMemoryTransport> for TransportOptions {
+    fn from(transport: InMemoryTransport) -> Self {
+        TransportOptions::new(Arc::new(transport))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use azure_core::prelude::Range;
+    use azure_storage::StorageCredentials;
+    use futures::{StreamExt, TryStreamExt};
+
+    #[test]
+    fn test_block_list_ids() {
+        let body = b"<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList>\
+            <Uncommitted>YQ==</Uncommitted><Latest>Yg==</Latest></BlockList>";
+        assert_eq!(block_list_ids(body), vec!["YQ==", "Yg=="]);
+        assert_eq!(parse_range("bytes=2-5"), Some((2, Some(5))));
+        assert_eq!(parse_range("bytes=2-"), Some((2, None)));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_transport() -> azure_core::Result<()> {
+        let transport = InMemoryTransport::new();
+        let container_client = ClientBuilder::new("account", StorageCredentials::anonymous())
+            .transport(transport.clone())
+            .container_client("container");
+        container_client.create().await?;
+        assert!(container_client.create().await.is_err());
+
+        let blob_client = container_client.blob_client("blob");
+        let stream = futures::stream::iter(
+            [&b"hello "[..], &b"world"[..]].map(|chunk| Ok(Bytes::from_static(chunk))),
+        );
+        blob_client.upload_stream(stream).block_size(4usize).await?;
+        assert_eq!(
+            transport.blob_content("container", "blob").unwrap(),
+            "hello world"
+        );
+
+        let mut chunks = blob_client
+            .get()
+            .range(Range::new(6, 11))
+            .chunk_size(2u64)
+            .into_stream();
+        let mut content = Vec::new();
+        while let Some(chunk) = chunks.next().await {
+            content.extend(chunk?.data.collect().await?);
+        }
+        assert_eq!(content, b"world");
+
+        let properties = blob_client.get_properties().await?;
+        assert_eq!(properties.blob.properties.content_length, 11);
+
+        blob_client.delete().await?;
+        assert!(!blob_client.exists().await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_empty_blob() -> azure_core::Result<()> {
+        let transport = InMemoryTransport::new();
+        let blob_client = ClientBuilder::new("account", StorageCredentials::anonymous())
+            .transport(transport.clone())
+            .blob_client("container", "empty");
+
+        blob_client.put_block_blob(Bytes::new()).await?;
+        assert_eq!(transport.blob_content("container", "empty").unwrap(), "");
+
+        // a ranged request is not satisfiable, the download falls back to a
+        // single request for the whole blob
+        let responses = blob_client.get().into_stream().collect::<Vec<_>>().await;
+        assert_eq!(responses.len(), 1);
+        let response = responses.into_iter().next().unwrap()?;
+        assert_eq!(response.blob.properties.content_length, 0);
+        assert_eq!(response.content_range, None);
+        assert!(response.data.collect().await?.is_empty());
+        assert!(blob_client.get_content().await?.is_empty());
+
+        let (stream, progress) = blob_client.get().into_tracked_bytes_stream();
+        let content = stream.try_collect::<Vec<_>>().await?;
+        assert!(content.concat().is_empty());
+        assert!(progress.checkpoint().is_complete());
+
+        // reading past the end of an empty blob is still an error
+        let error = blob_client
+            .get()
+            .range(Range::new(1, 2))
+            .into_stream()
+            .next()
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            error.kind(),
+            &ErrorKind::RangeNotSatisfiable { length: Some(0) }
+        );
+
+        let path = std::env::temp_dir().join(format!("{}.empty", uuid::Uuid::new_v4()));
+        let response = blob_client.download_file(&path).await?;
+        assert_eq!(response.blob.properties.content_length, 0);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+
+        let uploaded = blob_client.container_client().blob_client("uploaded");
+        uploaded.upload_file(&path).await?;
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(transport.blob_content("container", "uploaded").unwrap(), "");
+        Ok(())
+    }
+}
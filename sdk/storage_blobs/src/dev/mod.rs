@@ -0,0 +1,217 @@
+//! Utilities for development setups, such as seeding an emulator with data
+//! for benchmarks, demos and integration tests.
+//!
+//! ```no_run
+//! # async fn seed() -> azure_core::Result<()> {
+//! use azure_storage_blobs::prelude::*;
+//!
+//! let client = ClientBuilder::emulator().blob_service_client();
+//! let dataset = azure_storage_blobs::dev::seed(&client)
+//!     .containers(2usize)
+//!     .blobs_per_container(100usize)
+//!     .blob_sizes(vec![1024, 64 * 1024])
+//!     .await?;
+//! println!("seeded {} blobs, {} bytes", dataset.blobs, dataset.bytes);
+//! # Ok(())
+//! # }
+//! ```
+#[cfg(any(test, feature = "mock-transport"))]
+mod in_memory;
+#[cfg(any(test, feature = "mock-transport"))]
+pub use in_memory::InMemoryTransport;
+
+use crate::prelude::*;
+use azure_core::{error::ErrorKind, prelude::*, StatusCode};
+use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
+
+const DEFAULT_CONTAINER_PREFIX: &str = "seed-";
+const DEFAULT_CONTAINERS: usize = 1;
+const DEFAULT_BLOBS_PER_CONTAINER: usize = 10;
+const DEFAULT_BLOB_SIZE: usize = 1024;
+const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// Bulk-create containers and blobs with generated content.
+pub fn seed(client: &BlobServiceClient) -> SeedBuilder {
+    SeedBuilder::new(client.clone())
+}
+
+operation! {
+    /// Creates `containers` containers named `{container_prefix}{index}`, each
+    /// with `blobs_per_container` block blobs named `blob-{index}`.
+    ///
+    /// Blob `i` gets the `i`-th entry of `blob_sizes` and `access_tiers`,
+    /// wrapping around, so a dataset can mix sizes and tiers. The content is
+    /// pseudo-random but derived from `seed`, so runs with the same settings
+    /// produce the same data. Existing containers are reused and existing
+    /// blobs are overwritten.
+    Seed,
+    client: BlobServiceClient,
+    ?container_prefix: String,
+    ?containers: usize,
+    ?blobs_per_container: usize,
+    ?blob_sizes: Vec<usize>,
+    ?access_tiers: Vec<AccessTier>,
+    ?metadata: Metadata,
+    ?max_concurrency: usize,
+    ?seed: u64
+}
+
+impl SeedBuilder {
+    pub fn into_future(self) -> Seed {
+        Box::pin(async move {
+            let prefix = self
+                .container_prefix
+                .unwrap_or_else(|| DEFAULT_CONTAINER_PREFIX.to_owned());
+            let containers = self.containers.unwrap_or(DEFAULT_CONTAINERS);
+            let blobs_per_container = self
+                .blobs_per_container
+                .unwrap_or(DEFAULT_BLOBS_PER_CONTAINER);
+            let blob_sizes = self
+                .blob_sizes
+                .filter(|sizes| !sizes.is_empty())
+                .unwrap_or_else(|| vec![DEFAULT_BLOB_SIZE]);
+            let access_tiers = self.access_tiers.unwrap_or_default();
+            let max_concurrency = self
+                .max_concurrency
+                .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+                .max(1);
+            let seed = self.seed.unwrap_or_default();
+
+            let container_names: Vec<String> = (0..containers)
+                .map(|index| format!("{prefix}{index}"))
+                .collect();
+            for name in &container_names {
+                let result = self
+                    .client
+                    .container_client(name)
+                    .create()
+                    .context(self.context.clone())
+                    .await;
+                match result {
+                    Ok(_) => {}
+                    Err(error)
+                        if matches!(
+                            error.kind(),
+                            ErrorKind::HttpResponse {
+                                status: StatusCode::Conflict,
+                                ..
+                            }
+                        ) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+
+            // the content of each blob is generated only when its upload
+            // starts, so at most max_concurrency blobs are held in memory
+            let mut uploads = Vec::with_capacity(containers * blobs_per_container);
+            for (container_index, name) in container_names.iter().enumerate() {
+                for blob_index in 0..blobs_per_container {
+                    let size = blob_sizes[blob_index % blob_sizes.len()];
+                    let blob_seed = seed ^ ((container_index as u64) << 32 | blob_index as u64);
+                    let blob_client = self
+                        .client
+                        .container_client(name)
+                        .blob_client(format!("blob-{blob_index}"));
+                    let access_tier = (!access_tiers.is_empty())
+                        .then(|| access_tiers[blob_index % access_tiers.len()]);
+                    let metadata = self.metadata.clone();
+                    let context = self.context.clone();
+                    uploads.push(async move {
+                        let mut builder = blob_client
+                            .put_block_blob(generate_content(blob_seed, size))
+                            .context(context);
+                        if let Some(metadata) = metadata {
+                            builder = builder.metadata(metadata);
+                        }
+                        if let Some(access_tier) = access_tier {
+                            builder = builder.access_tier(access_tier);
+                        }
+                        builder.await.map(|_| size as u64)
+                    });
+                }
+            }
+
+            let (blobs, bytes) = futures::stream::iter(uploads)
+                .buffer_unordered(max_concurrency)
+                .try_fold((0, 0), |(blobs, bytes), size| async move {
+                    Ok((blobs + 1, bytes + size))
+                })
+                .await?;
+
+            Ok(SeedResponse {
+                containers: container_names,
+                blobs,
+                bytes,
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SeedResponse {
+    /// The names of the seeded containers.
+    pub containers: Vec<String>,
+    /// The number of blobs created.
+    pub blobs: u64,
+    /// The total size of the blobs created.
+    pub bytes: u64,
+}
+
+/// Pseudo-random content generated with xorshift, which is plenty for test
+/// data and avoids a dependency on a random number crate.
+fn generate_content(seed: u64, size: usize) -> Bytes {
+    // xorshift gets stuck at zero
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut content = Vec::with_capacity(size + 8);
+    while content.len() < size {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        content.extend_from_slice(&state.to_le_bytes());
+    }
+    content.truncate(size);
+    content.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+
+    #[tokio::test]
+    async fn test_seed() -> azure_core::Result<()> {
+        let service = InMemoryTransport::answering(|_| {
+            let mut headers = test_util::version_headers();
+            headers.insert("x-ms-request-server-encrypted", "true");
+            test_util::response(StatusCode::Created, headers)
+        });
+        let client = service.client_builder().blob_service_client();
+
+        let response = seed(&client)
+            .containers(2usize)
+            .blobs_per_container(3usize)
+            .blob_sizes(vec![1, 4])
+            .await?;
+        assert_eq!(response.containers, vec!["seed-0", "seed-1"]);
+        assert_eq!(response.blobs, 6);
+        assert_eq!(response.bytes, 12);
+
+        let requests: Vec<_> = service
+            .requests()
+            .iter()
+            .map(|request| (request.url().path().to_owned(), request.body().len()))
+            .collect();
+        assert_eq!(requests.len(), 8);
+        assert!(requests.contains(&("/seed-1/blob-1".to_owned(), 4)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_content() {
+        assert_eq!(generate_content(1, 13).len(), 13);
+        assert_eq!(generate_content(1, 13), generate_content(1, 13));
+        assert_ne!(generate_content(1, 13), generate_content(2, 13));
+        assert!(generate_content(0, 0).is_empty());
+    }
+}
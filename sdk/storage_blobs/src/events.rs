@@ -0,0 +1,288 @@
+//! Models of the events Azure Storage publishes to Event Grid when blobs are
+//! created or deleted.
+//!
+//! Events are delivered either in the Event Grid schema
+//! ([`EventGridEvent`]) or in the CloudEvents 1.0 schema ([`CloudEvent`]).
+//! Both keep the event data as JSON; [`blob_event`](EventGridEvent::blob_event)
+//! turns it into a typed [`BlobEvent`].
+//!
+//! ref: <https://learn.microsoft.com/azure/event-grid/event-schema-blob-storage>
+use azure_core::error::{Error, ErrorKind, ResultExt};
+use serde::de::DeserializeOwned;
+use time::OffsetDateTime;
+
+pub const BLOB_CREATED_EVENT_TYPE: &str = "Microsoft.Storage.BlobCreated";
+pub const BLOB_DELETED_EVENT_TYPE: &str = "Microsoft.Storage.BlobDeleted";
+
+/// An event in the Event Grid schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventGridEvent {
+    pub id: String,
+    /// The storage account, e.g.
+    /// `/subscriptions/{id}/resourceGroups/{group}/providers/Microsoft.Storage/storageAccounts/{account}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// The blob, e.g. `/blobServices/default/containers/{container}/blobs/{blob}`.
+    pub subject: String,
+    pub event_type: String,
+    #[serde(with = "azure_core::date::rfc3339")]
+    pub event_time: OffsetDateTime,
+    pub data: serde_json::Value,
+    #[serde(default)]
+    pub data_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_version: Option<String>,
+}
+
+impl EventGridEvent {
+    /// The typed data of a blob event.
+    pub fn blob_event(&self) -> azure_core::Result<BlobEvent> {
+        BlobEvent::new(&self.event_type, &self.data)
+    }
+
+    /// The container and blob names, from the subject.
+    pub fn container_and_blob_name(&self) -> Option<(&str, &str)> {
+        container_and_blob_name(&self.subject)
+    }
+}
+
+/// An event in the CloudEvents 1.0 schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudEvent {
+    pub id: String,
+    /// The storage account, like the topic of an [`EventGridEvent`].
+    pub source: String,
+    #[serde(rename = "specversion")]
+    pub spec_version: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(
+        default,
+        with = "azure_core::date::rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time: Option<OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(
+        rename = "datacontenttype",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub data_content_type: Option<String>,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl CloudEvent {
+    /// The typed data of a blob event.
+    pub fn blob_event(&self) -> azure_core::Result<BlobEvent> {
+        BlobEvent::new(&self.event_type, &self.data)
+    }
+
+    /// The container and blob names, from the subject.
+    pub fn container_and_blob_name(&self) -> Option<(&str, &str)> {
+        self.subject.as_deref().and_then(container_and_blob_name)
+    }
+}
+
+/// The data of a blob event, by event type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlobEvent {
+    Created(BlobCreatedEventData),
+    Deleted(BlobDeletedEventData),
+    /// Any other event type, e.g. from a newer schema.
+    Other {
+        event_type: String,
+        data: serde_json::Value,
+    },
+}
+
+impl BlobEvent {
+    pub fn new(event_type: &str, data: &serde_json::Value) -> azure_core::Result<Self> {
+        Ok(match event_type {
+            BLOB_CREATED_EVENT_TYPE => Self::Created(from_value(event_type, data)?),
+            BLOB_DELETED_EVENT_TYPE => Self::Deleted(from_value(event_type, data)?),
+            _ => Self::Other {
+                event_type: event_type.to_owned(),
+                data: data.clone(),
+            },
+        })
+    }
+}
+
+fn from_value<T: DeserializeOwned>(
+    event_type: &str,
+    data: &serde_json::Value,
+) -> azure_core::Result<T> {
+    T::deserialize(data).with_context(ErrorKind::DataConversion, || {
+        format!("failed to deserialize the data of a {event_type} event")
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiagnostics {
+    pub batch_id: String,
+}
+
+/// The data of a `Microsoft.Storage.BlobCreated` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobCreatedEventData {
+    /// The operation which created the blob, e.g. `PutBlob` or `PutBlockList`.
+    pub api: String,
+    #[serde(default)]
+    pub client_request_id: Option<String>,
+    pub request_id: String,
+    #[serde(rename = "eTag")]
+    pub etag: String,
+    pub content_type: String,
+    pub content_length: u64,
+    /// Only for accounts with a hierarchical namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_offset: Option<u64>,
+    pub blob_type: String,
+    /// Only for accounts with a hierarchical namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_url: Option<String>,
+    pub url: String,
+    /// Orders the events of a blob: compare the sequencers of two events
+    /// of the same blob as strings to find which happened last.
+    pub sequencer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_diagnostics: Option<StorageDiagnostics>,
+}
+
+/// The data of a `Microsoft.Storage.BlobDeleted` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobDeletedEventData {
+    pub api: String,
+    #[serde(default)]
+    pub client_request_id: Option<String>,
+    pub request_id: String,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    pub blob_type: String,
+    pub url: String,
+    pub sequencer: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_diagnostics: Option<StorageDiagnostics>,
+}
+
+/// Parse the body of an Event Grid delivery: either an array of events or a
+/// single one.
+pub fn parse_event_grid_events(body: &[u8]) -> azure_core::Result<Vec<EventGridEvent>> {
+    parse_events(body)
+}
+
+/// Parse the body of a CloudEvents delivery: either a batch of events or a
+/// single one.
+pub fn parse_cloud_events(body: &[u8]) -> azure_core::Result<Vec<CloudEvent>> {
+    parse_events(body)
+}
+
+fn parse_events<T: DeserializeOwned>(body: &[u8]) -> azure_core::Result<Vec<T>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        Many(Vec<T>),
+        One(T),
+    }
+
+    match serde_json::from_slice(body) {
+        Ok(OneOrMany::Many(events)) => Ok(events),
+        Ok(OneOrMany::One(event)) => Ok(vec![event]),
+        Err(error) => Err(Error::full(
+            ErrorKind::DataConversion,
+            error,
+            "failed to parse the events",
+        )),
+    }
+}
+
+fn container_and_blob_name(subject: &str) -> Option<(&str, &str)> {
+    let path = subject.strip_prefix("/blobServices/default/containers/")?;
+    path.split_once("/blobs/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOB_CREATED: &str = r#"[{
+        "topic": "/subscriptions/id/resourceGroups/group/providers/Microsoft.Storage/storageAccounts/account",
+        "subject": "/blobServices/default/containers/container/blobs/dir/blob.txt",
+        "eventType": "Microsoft.Storage.BlobCreated",
+        "eventTime": "2017-06-26T18:41:00.9584103Z",
+        "id": "831e1650-001e-001b-66ab-eeb76e069631",
+        "data": {
+            "api": "PutBlockList",
+            "clientRequestId": "6d79dbfb-0e37-4fc4-981f-442c9ca65760",
+            "requestId": "831e1650-001e-001b-66ab-eeb76e000000",
+            "eTag": "0x8D4BCC2E4835CD0",
+            "contentType": "text/plain",
+            "contentLength": 524288,
+            "blobType": "BlockBlob",
+            "url": "https://account.blob.core.windows.net/container/dir/blob.txt",
+            "sequencer": "00000000000004420000000000028963",
+            "storageDiagnostics": {
+                "batchId": "b68529f3-68cd-4744-baa4-3c0498ec19f0"
+            }
+        },
+        "dataVersion": "",
+        "metadataVersion": "1"
+    }]"#;
+
+    const BLOB_DELETED: &str = r#"{
+        "source": "/subscriptions/id/resourceGroups/group/providers/Microsoft.Storage/storageAccounts/account",
+        "subject": "/blobServices/default/containers/container/blobs/blob.txt",
+        "type": "Microsoft.Storage.BlobDeleted",
+        "time": "2017-11-07T20:09:22.5674003Z",
+        "id": "4c2359fe-001e-00ba-0e04-58586806d298",
+        "data": {
+            "api": "DeleteBlob",
+            "requestId": "4c2359fe-001e-00ba-0e04-585868000000",
+            "contentType": "text/plain",
+            "blobType": "BlockBlob",
+            "url": "https://account.blob.core.windows.net/container/blob.txt",
+            "sequencer": "0000000000000281000000000002F5CA"
+        },
+        "specversion": "1.0"
+    }"#;
+
+    #[test]
+    fn test_parse_event_grid_events() {
+        let events = parse_event_grid_events(BLOB_CREATED.as_bytes()).unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(
+            event.container_and_blob_name(),
+            Some(("container", "dir/blob.txt"))
+        );
+        match event.blob_event().unwrap() {
+            BlobEvent::Created(data) => {
+                assert_eq!(data.api, "PutBlockList");
+                assert_eq!(data.etag, "0x8D4BCC2E4835CD0");
+                assert_eq!(data.content_length, 524288);
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_cloud_events() {
+        let events = parse_cloud_events(BLOB_DELETED.as_bytes()).unwrap();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.spec_version, "1.0");
+        assert_eq!(
+            event.container_and_blob_name(),
+            Some(("container", "blob.txt"))
+        );
+        assert!(matches!(event.blob_event().unwrap(), BlobEvent::Deleted(_)));
+    }
+}
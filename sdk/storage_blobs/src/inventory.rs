@@ -0,0 +1,419 @@
+//! Reading the reports of blob inventory runs.
+//!
+//! Each run of an inventory rule writes a `{rule}-manifest.json` and one or
+//! more CSV or Apache Parquet data files to the rule's destination container,
+//! under `{yyyy}/{mm}/{dd}/{hh-mm-ss}/{rule}/`. [`InventoryReader`] finds the
+//! runs and turns their data files into [`InventoryRecord`]s.
+//!
+//! Parquet reports need the `inventory-parquet` feature.
+//!
+//! ```no_run
+//! use azure_storage_blobs::{inventory::InventoryReader, prelude::*};
+//!
+//! # async fn example(container_client: ContainerClient) -> azure_core::Result<()> {
+//! let reader = InventoryReader::new(container_client);
+//! if let Some(run) = reader.latest_run(Some("daily")).await? {
+//!     for record in reader.records(&run).await? {
+//!         println!("{} {:?}", record.name, record.access_tier);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ref: <https://docs.microsoft.com/azure/storage/blobs/blob-inventory>
+use crate::{blob::BlobType, prelude::*};
+use azure_core::{
+    date,
+    error::{Error, ErrorKind, ResultExt},
+};
+use futures::TryStreamExt;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+const MANIFEST_SUFFIX: &str = "-manifest.json";
+
+/// The manifest of an inventory run.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryManifest {
+    pub destination_container: String,
+    pub endpoint: String,
+    pub files: Vec<InventoryFile>,
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub inventory_start_time: OffsetDateTime,
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub inventory_completion_time: OffsetDateTime,
+    pub rule_definition: InventoryRuleDefinition,
+    pub rule_name: String,
+    pub status: String,
+    pub summary: InventorySummary,
+    pub version: String,
+}
+
+impl InventoryManifest {
+    pub fn from_json(json: &[u8]) -> azure_core::Result<Self> {
+        serde_json::from_slice(json).context(
+            ErrorKind::DataConversion,
+            "failed to parse the inventory manifest",
+        )
+    }
+
+    /// Whether the run finished writing its data files.
+    pub fn succeeded(&self) -> bool {
+        self.status == "Succeeded"
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryFile {
+    /// The name of the data file in the destination container.
+    pub blob: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryRuleDefinition {
+    /// `csv` or `parquet`.
+    pub format: String,
+    pub object_type: String,
+    pub schedule: String,
+    pub schema_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventorySummary {
+    pub object_count: u64,
+    pub total_object_size: u64,
+}
+
+fn deserialize_rfc3339<'de, D>(deserializer: D) -> Result<OffsetDateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    date::parse_rfc3339(&s).map_err(serde::de::Error::custom)
+}
+
+/// An inventory run, found by its manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryRun {
+    /// The name of the manifest in the destination container.
+    pub manifest_blob: String,
+    pub manifest: InventoryManifest,
+}
+
+/// A blob, snapshot or version listed by an inventory run.
+///
+/// The common fields are parsed. Every field of the rule's schema is also
+/// kept as text in `fields`, as it appeared in the report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryRecord {
+    pub name: String,
+    pub creation_time: Option<OffsetDateTime>,
+    pub last_modified: Option<OffsetDateTime>,
+    pub etag: Option<String>,
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub content_md5: Option<String>,
+    pub blob_type: Option<BlobType>,
+    pub access_tier: Option<AccessTier>,
+    pub snapshot: Option<Snapshot>,
+    pub version_id: Option<VersionId>,
+    pub is_current_version: Option<bool>,
+    pub deleted: Option<bool>,
+    pub remaining_retention_days: Option<u32>,
+    pub fields: HashMap<String, String>,
+}
+
+impl InventoryRecord {
+    fn from_fields(fields: HashMap<String, String>) -> azure_core::Result<Self> {
+        let name = field(&fields, "Name")
+            .ok_or_else(|| {
+                Error::message(ErrorKind::DataConversion, "inventory record without a name")
+            })?
+            .to_owned();
+        Ok(Self {
+            creation_time: parse_field(&fields, "Creation-Time", date::parse_rfc3339)?,
+            last_modified: parse_field(&fields, "Last-Modified", date::parse_rfc3339)?,
+            etag: field(&fields, "Etag").map(ToOwned::to_owned),
+            content_length: parse_field(&fields, "Content-Length", parse_number)?,
+            content_type: field(&fields, "Content-Type").map(ToOwned::to_owned),
+            content_md5: field(&fields, "Content-MD5").map(ToOwned::to_owned),
+            blob_type: parse_field(&fields, "BlobType", str::parse)?,
+            access_tier: parse_field(&fields, "AccessTier", str::parse)?,
+            snapshot: parse_field(&fields, "Snapshot", str::parse)?,
+            version_id: parse_field(&fields, "VersionId", str::parse)?,
+            is_current_version: parse_field(&fields, "IsCurrentVersion", parse_bool)?,
+            deleted: parse_field(&fields, "Deleted", parse_bool)?,
+            remaining_retention_days: parse_field(&fields, "RemainingRetentionDays", parse_number)?,
+            name,
+            fields,
+        })
+    }
+}
+
+/// The value of a field, looked up ignoring case. Empty values are missing.
+fn field<'a>(fields: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_field<T>(
+    fields: &HashMap<String, String>,
+    name: &str,
+    parse: impl Fn(&str) -> azure_core::Result<T>,
+) -> azure_core::Result<Option<T>> {
+    field(fields, name)
+        .map(|value| {
+            parse(value).with_context(ErrorKind::DataConversion, || {
+                format!("invalid inventory field {name}: {value}")
+            })
+        })
+        .transpose()
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> azure_core::Result<T> {
+    value
+        .parse()
+        .map_err(|_| Error::message(ErrorKind::DataConversion, "not a number"))
+}
+
+fn parse_bool(value: &str) -> azure_core::Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(Error::message(ErrorKind::DataConversion, "not a boolean")),
+    }
+}
+
+/// Parses a CSV inventory data file, whose first row names the fields.
+pub fn parse_csv(data: &[u8]) -> azure_core::Result<Vec<InventoryRecord>> {
+    let mut reader = csv::ReaderBuilder::new().from_reader(data);
+    let headers = reader
+        .headers()
+        .context(ErrorKind::DataConversion, "failed to read the CSV header")?
+        .clone();
+    reader
+        .records()
+        .map(|record| {
+            let record =
+                record.context(ErrorKind::DataConversion, "failed to read a CSV record")?;
+            let fields = headers
+                .iter()
+                .zip(record.iter())
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect();
+            InventoryRecord::from_fields(fields)
+        })
+        .collect()
+}
+
+/// Parses an Apache Parquet inventory data file.
+#[cfg(feature = "inventory-parquet")]
+pub fn parse_parquet(data: bytes::Bytes) -> azure_core::Result<Vec<InventoryRecord>> {
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::Field,
+    };
+
+    fn to_text(field: &Field) -> String {
+        let timestamp = match field {
+            Field::Null => return String::new(),
+            Field::Str(s) => return s.clone(),
+            Field::TimestampMillis(ms) => i128::from(*ms) * 1_000_000,
+            Field::TimestampMicros(us) => i128::from(*us) * 1_000,
+            other => return other.to_string(),
+        };
+        OffsetDateTime::from_unix_timestamp_nanos(timestamp)
+            .map(|timestamp| date::to_rfc3339(&timestamp))
+            .unwrap_or_default()
+    }
+
+    let reader = SerializedFileReader::new(data)
+        .context(ErrorKind::DataConversion, "failed to open the Parquet file")?;
+    let rows = reader
+        .get_row_iter(None)
+        .context(ErrorKind::DataConversion, "failed to read the Parquet file")?;
+    rows.map(|row| {
+        let row = row.context(ErrorKind::DataConversion, "failed to read a Parquet row")?;
+        let fields = row
+            .get_column_iter()
+            .map(|(name, field)| (name.clone(), to_text(field)))
+            .collect();
+        InventoryRecord::from_fields(fields)
+    })
+    .collect()
+}
+
+/// Finds and reads the inventory runs written to a destination container.
+#[derive(Debug, Clone)]
+pub struct InventoryReader {
+    container_client: ContainerClient,
+}
+
+impl InventoryReader {
+    pub fn new(container_client: ContainerClient) -> Self {
+        Self { container_client }
+    }
+
+    /// The runs in the container, newest first, optionally only those of the
+    /// rule named `rule_name`.
+    pub async fn runs(&self, rule_name: Option<&str>) -> azure_core::Result<Vec<InventoryRun>> {
+        let mut manifest_blobs = Vec::new();
+        let mut pages = self.container_client.list_blobs().into_stream();
+        while let Some(page) = pages.try_next().await? {
+            manifest_blobs.extend(
+                page.blobs
+                    .blobs()
+                    .map(|blob| blob.name.clone())
+                    .filter(|name| is_manifest(name, rule_name)),
+            );
+        }
+        // the names start with the date and time of the run
+        manifest_blobs.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut runs = Vec::with_capacity(manifest_blobs.len());
+        for manifest_blob in manifest_blobs {
+            let json = self
+                .container_client
+                .blob_client(&manifest_blob)
+                .get_content()
+                .await?;
+            let manifest = InventoryManifest::from_json(&json)?;
+            runs.push(InventoryRun {
+                manifest_blob,
+                manifest,
+            });
+        }
+        Ok(runs)
+    }
+
+    /// The newest run which succeeded, optionally of the rule named
+    /// `rule_name`.
+    pub async fn latest_run(
+        &self,
+        rule_name: Option<&str>,
+    ) -> azure_core::Result<Option<InventoryRun>> {
+        Ok(self
+            .runs(rule_name)
+            .await?
+            .into_iter()
+            .find(|run| run.manifest.succeeded()))
+    }
+
+    /// Every record of a run, read from its data files in order.
+    pub async fn records(&self, run: &InventoryRun) -> azure_core::Result<Vec<InventoryRecord>> {
+        let mut records = Vec::new();
+        for file in &run.manifest.files {
+            records.extend(self.file_records(run, file).await?);
+        }
+        Ok(records)
+    }
+
+    /// The records of one of the data files of a run.
+    pub async fn file_records(
+        &self,
+        run: &InventoryRun,
+        file: &InventoryFile,
+    ) -> azure_core::Result<Vec<InventoryRecord>> {
+        let data = self
+            .container_client
+            .blob_client(&file.blob)
+            .get_content()
+            .await?;
+        match run
+            .manifest
+            .rule_definition
+            .format
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "csv" => parse_csv(&data),
+            #[cfg(feature = "inventory-parquet")]
+            "parquet" => parse_parquet(data.into()),
+            format => Err(Error::with_message(ErrorKind::Other, || {
+                format!("inventory reports in the {format} format are not supported")
+            })),
+        }
+    }
+}
+
+fn is_manifest(name: &str, rule_name: Option<&str>) -> bool {
+    match rule_name {
+        Some(rule_name) => name.ends_with(&format!("/{rule_name}/{rule_name}{MANIFEST_SUFFIX}")),
+        None => name.ends_with(MANIFEST_SUFFIX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() -> azure_core::Result<()> {
+        let manifest = InventoryManifest::from_json(
+            br#"{
+                "destinationContainer": "inventory",
+                "endpoint": "https://account.blob.core.windows.net",
+                "files": [
+                    { "blob": "2021/05/26/13-25-36/daily/daily_1.csv", "size": 12710092 }
+                ],
+                "inventoryCompletionTime": "2021-05-26T13:35:56Z",
+                "inventoryStartTime": "2021-05-26T13:25:36Z",
+                "ruleDefinition": {
+                    "filters": { "blobTypes": ["blockBlob"], "includeSnapshots": false },
+                    "format": "csv",
+                    "objectType": "blob",
+                    "schedule": "daily",
+                    "schemaFields": ["Name", "Content-Length"]
+                },
+                "ruleName": "daily",
+                "status": "Succeeded",
+                "summary": { "objectCount": 110000, "totalObjectSize": 23789775 },
+                "version": "1.0"
+            }"#,
+        )?;
+        assert!(manifest.succeeded());
+        assert_eq!(manifest.files[0].size, 12710092);
+        assert_eq!(manifest.summary.object_count, 110000);
+
+        assert!(is_manifest(
+            "2021/05/26/13-25-36/daily/daily-manifest.json",
+            Some("daily")
+        ));
+        assert!(!is_manifest(
+            "2021/05/26/13-25-36/weekly/weekly-manifest.json",
+            Some("daily")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_csv() -> azure_core::Result<()> {
+        let records = parse_csv(
+            b"Name,Creation-Time,Content-Length,BlobType,AccessTier,Metadata,Deleted\n\
+              logs/a.txt,2021-05-26T13:25:36.0000000Z,12,BlockBlob,Hot,\"a=1,b=2\",\n\
+              \"logs/b, c.txt\",2021-05-26T13:25:37.0000000Z,0,BlockBlob,,,true\n",
+        )?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].name, "logs/a.txt");
+        assert_eq!(records[0].content_length, Some(12));
+        assert_eq!(records[0].blob_type, Some(BlobType::BlockBlob));
+        assert_eq!(records[0].access_tier, Some(AccessTier::Hot));
+        assert_eq!(records[0].deleted, None);
+        assert_eq!(records[0].fields["Metadata"], "a=1,b=2");
+        assert_eq!(records[1].name, "logs/b, c.txt");
+        assert_eq!(records[1].access_tier, None);
+        assert_eq!(records[1].deleted, Some(true));
+        assert!(records[1].creation_time.is_some());
+        Ok(())
+    }
+}
@@ -20,7 +20,7 @@ async fn main() -> azure_core::Result<()> {
     let container = std::env::var("STORAGE_CONTAINER").expect("missing STORAGE_CONTAINER");
     let blob_name = std::env::var("STORAGE_BLOB_NAME").expect("missing STORAGE_BLOB_NAME");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
+    let storage_credentials = StorageCredentials::access_key(account.clone(), access_key);
     let blob_client = ClientBuilder::new(account, storage_credentials).blob_client(&container, blob_name);
 
     blob_client.put_block_blob("hello world").content_type("text/plain").await?;
@@ -52,10 +52,30 @@ extern crate serde_derive;
 #[macro_use]
 extern crate azure_core;
 
+pub mod analytics_logs;
+pub mod batch;
 pub mod blob;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "gzip")]
+pub mod compression;
 pub mod container;
+pub mod dev;
+pub mod events;
+#[cfg(feature = "inventory")]
+pub mod inventory;
+pub mod limits;
+#[cfg(feature = "object-store")]
+pub mod object_store;
 pub mod prelude;
+#[cfg(feature = "queue-trigger")]
+pub mod queue_trigger;
+pub mod replication;
 pub mod service;
+pub mod transform;
 
 mod clients;
 mod options;
+mod streaming_xml;
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,155 @@
+//! The size limits of the blob service.
+//!
+//! Several limits were raised by newer service versions, so they are looked
+//! up with the version the requests are made with, see
+//! [`BlobServiceClient::api_version`](crate::prelude::BlobServiceClient::api_version).
+//!
+//! ref: <https://docs.microsoft.com/rest/api/storageservices/understanding-block-blobs--append-blobs--and-page-blobs>
+
+use crate::blob::TooManyBlocks;
+use azure_core::error::{Error, ErrorKind};
+
+const MIB: u64 = 1024 * 1024;
+
+/// The most blocks a block blob can have, committed or not.
+pub const MAX_BLOCKS: u64 = 50_000;
+/// The size of the pages of page blobs, to which page writes are aligned.
+pub const PAGE_SIZE: u64 = 512;
+/// The largest range a single Put Page request writes.
+pub const MAX_PAGE_WRITE_SIZE: u64 = 4 * MIB;
+/// The largest block a single Append Block request appends.
+pub const MAX_APPEND_BLOCK_SIZE: u64 = 4 * MIB;
+
+/// The limits of a service version.
+///
+/// ```
+/// use azure_storage_blobs::limits::Limits;
+///
+/// let limits = Limits::for_api_version("2019-12-12");
+/// assert_eq!(limits.max_block_size, 4000 * 1024 * 1024);
+/// // the smallest block size which fits a 1 TiB upload
+/// assert_eq!(limits.min_block_size_for(1 << 40).unwrap(), 21_990_233);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The largest block a single Put Block request stages.
+    pub max_block_size: u64,
+    /// The most blocks a block blob can have.
+    pub max_blocks: u64,
+    /// The largest body a single Put Blob request accepts.
+    pub max_put_blob_size: u64,
+    /// The largest range a single Put Page request writes.
+    pub max_page_write_size: u64,
+    /// The largest block a single Append Block request appends.
+    pub max_append_block_size: u64,
+}
+
+impl Limits {
+    pub fn for_api_version(api_version: &str) -> Self {
+        Self {
+            max_block_size: max_block_size(api_version),
+            max_blocks: MAX_BLOCKS,
+            max_put_blob_size: max_put_blob_size(api_version),
+            max_page_write_size: MAX_PAGE_WRITE_SIZE,
+            max_append_block_size: MAX_APPEND_BLOCK_SIZE,
+        }
+    }
+
+    /// The largest block blob which can be uploaded in blocks.
+    pub fn max_block_blob_size(&self) -> u64 {
+        self.max_block_size.saturating_mul(self.max_blocks)
+    }
+
+    /// The smallest block size with which `len` bytes fit in the most blocks
+    /// a blob can have.
+    pub fn min_block_size_for(&self, len: u64) -> azure_core::Result<u64> {
+        let block_size = ((len + self.max_blocks - 1) / self.max_blocks).max(1);
+        if block_size > self.max_block_size {
+            return Err(Error::with_message(ErrorKind::Other, || {
+                format!(
+                    "{len} bytes are more than the {} bytes of the largest block blob",
+                    self.max_block_blob_size()
+                )
+            }));
+        }
+        Ok(block_size)
+    }
+
+    /// Checks that `count` blocks of `block_size` bytes can be staged. Too
+    /// many blocks are reported with a [`TooManyBlocks`] error.
+    pub fn validate_blocks(&self, block_size: u64, count: u64) -> azure_core::Result<()> {
+        if block_size > self.max_block_size {
+            return Err(Error::with_message(ErrorKind::Other, || {
+                format!(
+                    "blocks of {block_size} bytes are larger than the {} bytes of the largest block",
+                    self.max_block_size
+                )
+            }));
+        }
+        if count > self.max_blocks {
+            return Err(Error::new(
+                ErrorKind::Other,
+                TooManyBlocks {
+                    blocks: count,
+                    max_blocks: self.max_blocks,
+                },
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The largest block a single Put Block request stages with the given service
+/// version.
+///
+/// ref: <https://docs.microsoft.com/rest/api/storageservices/put-block#remarks>
+pub fn max_block_size(api_version: &str) -> u64 {
+    // service versions are ISO 8601 dates, so they sort lexicographically
+    if api_version >= "2019-12-12" {
+        4000 * MIB
+    } else if api_version >= "2016-05-31" {
+        100 * MIB
+    } else {
+        4 * MIB
+    }
+}
+
+/// The largest body a single Put Blob request accepts with the given service
+/// version. Larger blobs have to be staged with Put Block and committed with
+/// Put Block List.
+///
+/// ref: <https://docs.microsoft.com/rest/api/storageservices/put-blob#remarks>
+pub fn max_put_blob_size(api_version: &str) -> u64 {
+    if api_version >= "2019-12-12" {
+        5000 * MIB
+    } else if api_version >= "2016-05-31" {
+        256 * MIB
+    } else {
+        64 * MIB
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        assert_eq!(max_put_blob_size("2019-12-12"), 5000 * MIB);
+        assert_eq!(max_put_blob_size("2018-03-28"), 256 * MIB);
+        assert_eq!(max_put_blob_size("2015-12-11"), 64 * MIB);
+        assert_eq!(max_block_size("2018-03-28"), 100 * MIB);
+
+        let limits = Limits::for_api_version("2015-12-11");
+        assert_eq!(limits.max_block_blob_size(), 4 * MIB * MAX_BLOCKS);
+        assert_eq!(limits.min_block_size_for(0).unwrap(), 1);
+        assert_eq!(
+            limits.min_block_size_for(4 * MIB * MAX_BLOCKS).unwrap(),
+            4 * MIB
+        );
+        assert!(limits.min_block_size_for(4 * MIB * MAX_BLOCKS + 1).is_err());
+        assert!(limits.validate_blocks(4 * MIB, MAX_BLOCKS).is_ok());
+        assert!(limits.validate_blocks(4 * MIB + 1, 1).is_err());
+        assert!(limits.validate_blocks(1, MAX_BLOCKS + 1).is_err());
+    }
+}
@@ -0,0 +1,424 @@
+//! An [`ObjectStore`] backed by a container, for the crates built on the
+//! `object_store` abstraction (DataFusion, Polars, delta-rs, ...).
+//!
+//! ```no_run
+//! use azure_storage::StorageCredentials;
+//! use azure_storage_blobs::{object_store::BlobObjectStore, prelude::*};
+//! use std::sync::Arc;
+//!
+//! let container = ClientBuilder::new("account", StorageCredentials::anonymous())
+//!     .container_client("container");
+//! let store: Arc<dyn object_store::ObjectStore> = Arc::new(BlobObjectStore::new(container));
+//! ```
+use crate::{
+    blob::{Blob, CopyStatus},
+    prelude::*,
+};
+use ::object_store::{
+    path::Path, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMode, PutMultipartOptions, PutOptions, PutPayload, PutResult, UploadPart,
+};
+use azure_core::{
+    error::{Error, ErrorKind},
+    prelude::*,
+    Etag, StatusCode,
+};
+use bytes::Bytes;
+use futures::{stream::BoxStream, FutureExt, StreamExt, TryStreamExt};
+use std::{fmt, time::Duration};
+use time::OffsetDateTime;
+
+const STORE: &str = "AzureStorageBlobs";
+const COPY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An [`ObjectStore`] whose objects are the block blobs of a container.
+///
+/// Paths map to blob names as they are, `/` being the delimiter of
+/// hierarchical listings. Multipart uploads stage blocks and commit them on
+/// completion; aborted uploads leave uncommitted blocks behind, which the
+/// service discards after a week.
+#[derive(Debug, Clone)]
+pub struct BlobObjectStore {
+    container_client: ContainerClient,
+}
+
+impl BlobObjectStore {
+    pub fn new(container_client: ContainerClient) -> Self {
+        Self { container_client }
+    }
+
+    pub fn container_client(&self) -> &ContainerClient {
+        &self.container_client
+    }
+
+    fn blob_client(&self, location: &Path) -> BlobClient {
+        self.container_client.blob_client(location.as_ref())
+    }
+
+    async fn put_committed(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        condition: Option<IfMatchCondition>,
+    ) -> azure_core::Result<PutResult> {
+        let mut builder = self
+            .blob_client(location)
+            .put_block_blob(Bytes::from(payload));
+        if let Some(condition) = condition {
+            builder = builder.if_match(condition);
+        }
+        let response = builder.await?;
+        Ok(PutResult {
+            e_tag: Some(response.etag.to_string()),
+            version: None,
+        })
+    }
+
+    async fn head_blob(&self, location: &Path, version: Option<&str>) -> azure_core::Result<Blob> {
+        let mut builder = self.blob_client(location).get_properties();
+        if let Some(version) = version {
+            builder = builder.blob_versioning(version.parse::<VersionId>()?);
+        }
+        Ok(builder.await?.blob)
+    }
+
+    async fn copy_blob(
+        &self,
+        from: &Path,
+        to: &Path,
+        condition: Option<IfMatchCondition>,
+    ) -> azure_core::Result<()> {
+        let source_url = self.blob_client(from).url()?;
+        let blob_client = self.blob_client(to);
+        let mut builder = blob_client.copy(source_url);
+        if let Some(condition) = condition {
+            builder = builder.if_match(condition);
+        }
+        let mut status = builder.await?.copy_status;
+
+        // copies within an account are usually synchronous, but may not be
+        while status == CopyStatus::Pending {
+            azure_core::sleep::sleep(COPY_POLL_INTERVAL).await;
+            let blob = blob_client.get_properties().await?.blob;
+            status = blob.properties.copy_status.unwrap_or(CopyStatus::Success);
+        }
+        match status {
+            CopyStatus::Success => Ok(()),
+            status => Err(Error::with_message(ErrorKind::Other, || {
+                format!("copying {from} to {to} ended with status {status}")
+            })),
+        }
+    }
+}
+
+impl fmt::Display for BlobObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BlobObjectStore({})",
+            self.container_client.container_name()
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for BlobObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        options: PutOptions,
+    ) -> ::object_store::Result<PutResult> {
+        let condition = match options.mode {
+            PutMode::Overwrite => None,
+            PutMode::Create => Some(IfMatchCondition::NotMatch(Etag::any())),
+            PutMode::Update(version) => match version.e_tag {
+                Some(etag) => Some(IfMatchCondition::Match(etag.into())),
+                None => {
+                    return Err(::object_store::Error::Generic {
+                        store: STORE,
+                        source: "conditional updates need an ETag".into(),
+                    })
+                }
+            },
+        };
+        self.put_committed(location, payload, condition)
+            .await
+            .map_err(|error| into_object_store_error(error, location))
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _options: PutMultipartOptions,
+    ) -> ::object_store::Result<Box<dyn MultipartUpload>> {
+        Ok(Box::new(BlobMultipartUpload {
+            blob_client: self.blob_client(location),
+            location: location.clone(),
+            block_ids: BlockIdSequence::new(),
+            parts: 0,
+        }))
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> ::object_store::Result<GetResult> {
+        let into_error = |error| into_object_store_error(error, location);
+        let blob = self
+            .head_blob(location, options.version.as_deref())
+            .await
+            .map_err(into_error)?;
+        let meta = object_meta(&blob);
+        options.check_preconditions(&meta)?;
+
+        let range = match &options.range {
+            Some(range) => {
+                range
+                    .as_range(meta.size)
+                    .map_err(|error| ::object_store::Error::Generic {
+                        store: STORE,
+                        source: Box::new(error),
+                    })?
+            }
+            None => 0..meta.size,
+        };
+
+        let payload = if options.head || range.is_empty() {
+            futures::stream::empty().boxed()
+        } else {
+            let mut builder = self
+                .blob_client(location)
+                .get()
+                .range(range.clone())
+                // fail rather than mix versions if the blob changes meanwhile
+                .if_match(IfMatchCondition::Match(blob.properties.etag.clone()));
+            if let Some(version) = &options.version {
+                let version = version
+                    .parse::<VersionId>()
+                    .map_err(|error| into_object_store_error(error, location))?;
+                builder = builder.blob_versioning(version);
+            }
+            let location = location.clone();
+            builder
+                .into_bytes_stream()
+                .map_err(move |error| into_object_store_error(error, &location))
+                .boxed()
+        };
+
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(payload),
+            meta,
+            range,
+            attributes: Default::default(),
+        })
+    }
+
+    async fn delete(&self, location: &Path) -> ::object_store::Result<()> {
+        match self.blob_client(location).delete().await {
+            Ok(_) => Ok(()),
+            Err(error) if status(&error) == Some(StatusCode::NotFound) => Ok(()),
+            Err(error) => Err(into_object_store_error(error, location)),
+        }
+    }
+
+    fn list(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, ::object_store::Result<ObjectMeta>> {
+        let mut builder = self.container_client.list_blobs();
+        if let Some(prefix) = list_prefix(prefix) {
+            builder = builder.prefix(prefix);
+        }
+        builder
+            .into_stream()
+            .map_ok(|response| {
+                let objects: Vec<_> = response
+                    .blobs
+                    .blobs()
+                    .map(|blob| Ok(object_meta(blob)))
+                    .collect();
+                futures::stream::iter(objects)
+            })
+            .map_err(|error| into_object_store_error(error, &Path::default()))
+            .try_flatten()
+            .boxed()
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        prefix: Option<&Path>,
+    ) -> ::object_store::Result<ListResult> {
+        let mut builder = self.container_client.list_blobs().delimiter("/");
+        if let Some(prefix) = list_prefix(prefix) {
+            builder = builder.prefix(prefix);
+        }
+
+        let mut result = ListResult {
+            common_prefixes: Vec::new(),
+            objects: Vec::new(),
+        };
+        let mut pages = builder.into_stream();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|error| into_object_store_error(error, &Path::default()))?;
+            result.objects.extend(page.blobs.blobs().map(object_meta));
+            result.common_prefixes.extend(
+                page.blobs
+                    .prefixes()
+                    .map(|prefix| Path::from(prefix.name.trim_end_matches('/'))),
+            );
+        }
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> ::object_store::Result<()> {
+        self.copy_blob(from, to, None)
+            .await
+            .map_err(|error| into_object_store_error(error, from))
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> ::object_store::Result<()> {
+        self.copy_blob(from, to, Some(IfMatchCondition::NotMatch(Etag::any())))
+            .await
+            .map_err(|error| into_object_store_error(error, to))
+    }
+}
+
+/// Stages each part as a block, committing them in order on completion.
+#[derive(Debug)]
+struct BlobMultipartUpload {
+    blob_client: BlobClient,
+    location: Path,
+    block_ids: BlockIdSequence,
+    parts: u64,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for BlobMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let index = self.parts;
+        self.parts += 1;
+        let put_block = self
+            .blob_client
+            .put_block(self.block_ids.block_id(index), Bytes::from(data))
+            .into_future();
+        let location = self.location.clone();
+        async move {
+            put_block
+                .await
+                .map(|_| ())
+                .map_err(|error| into_object_store_error(error, &location))
+        }
+        .boxed()
+    }
+
+    async fn complete(&mut self) -> ::object_store::Result<PutResult> {
+        let block_list = (0..self.parts)
+            .map(|index| BlobBlockType::new_uncommitted(self.block_ids.block_id(index)))
+            .collect();
+        let response = self
+            .blob_client
+            .put_block_list(block_list)
+            .await
+            .map_err(|error| into_object_store_error(error, &self.location))?;
+        Ok(PutResult {
+            e_tag: Some(response.etag.to_string()),
+            version: None,
+        })
+    }
+
+    async fn abort(&mut self) -> ::object_store::Result<()> {
+        // uncommitted blocks cannot be deleted; the service discards them
+        Ok(())
+    }
+}
+
+fn list_prefix(prefix: Option<&Path>) -> Option<String> {
+    prefix
+        .map(|prefix| prefix.as_ref())
+        .filter(|prefix| !prefix.is_empty())
+        .map(|prefix| format!("{prefix}/"))
+}
+
+fn object_meta(blob: &Blob) -> ObjectMeta {
+    ObjectMeta {
+        location: Path::from(blob.name.as_str()),
+        last_modified: to_chrono(blob.properties.last_modified),
+        size: blob.properties.content_length,
+        e_tag: Some(blob.properties.etag.to_string()),
+        version: blob.version_id.map(|version_id| version_id.to_string()),
+    }
+}
+
+fn to_chrono(time: OffsetDateTime) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::from_timestamp(time.unix_timestamp(), time.nanosecond()).unwrap_or_default()
+}
+
+fn status(error: &Error) -> Option<StatusCode> {
+    match error.kind() {
+        ErrorKind::HttpResponse { status, .. } => Some(*status),
+        _ => None,
+    }
+}
+
+fn into_object_store_error(error: Error, location: &Path) -> ::object_store::Error {
+    let path = location.to_string();
+    match status(&error) {
+        Some(StatusCode::NotFound) => ::object_store::Error::NotFound {
+            path,
+            source: Box::new(error),
+        },
+        Some(StatusCode::Conflict) => ::object_store::Error::AlreadyExists {
+            path,
+            source: Box::new(error),
+        },
+        Some(StatusCode::PreconditionFailed) => ::object_store::Error::Precondition {
+            path,
+            source: Box::new(error),
+        },
+        Some(StatusCode::NotModified) => ::object_store::Error::NotModified {
+            path,
+            source: Box::new(error),
+        },
+        Some(StatusCode::Unauthorized) => ::object_store::Error::Unauthenticated {
+            path,
+            source: Box::new(error),
+        },
+        Some(StatusCode::Forbidden) => ::object_store::Error::PermissionDenied {
+            path,
+            source: Box::new(error),
+        },
+        _ => ::object_store::Error::Generic {
+            store: STORE,
+            source: Box::new(error),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_prefix() {
+        assert_eq!(list_prefix(None), None);
+        assert_eq!(list_prefix(Some(&Path::default())), None);
+        assert_eq!(
+            list_prefix(Some(&Path::from("a/b"))),
+            Some("a/b/".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        let error = Error::new(
+            ErrorKind::http_response(StatusCode::NotFound, Some("BlobNotFound".to_owned())),
+            "not found",
+        );
+        assert!(matches!(
+            into_object_store_error(error, &Path::from("a")),
+            ::object_store::Error::NotFound { .. }
+        ));
+    }
+}
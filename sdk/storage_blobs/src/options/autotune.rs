@@ -0,0 +1,211 @@
+use std::time::{Duration, Instant};
+
+/// Options of the adaptive mode of the transfer operations, e.g.
+/// [`UploadFileBuilder::autotune`](crate::blob::operations::UploadFileBuilder::autotune).
+///
+/// Instead of a fixed concurrency and chunk size, the transfer adapts both to
+/// what it observes, so that one setting works from a laptop on Wi-Fi to a
+/// server with a 10 GbE link:
+///
+/// * the concurrency grows by one request per round of requests, and is
+///   halved when the service throttles the transfer with `503 Server Busy`
+///   or the throughput falls below half of the best round (AIMD),
+/// * the chunk size doubles while requests complete in less than half of
+///   `target_latency`, as short requests are dominated by their overhead,
+///   and halves when they take more than twice as long.
+///
+/// Chunks throttled by the service are retried with the reduced concurrency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutotuneOptions {
+    min_concurrency: usize,
+    max_concurrency: usize,
+    min_chunk_size: u64,
+    max_chunk_size: u64,
+    target_latency: Duration,
+}
+
+impl Default for AutotuneOptions {
+    fn default() -> Self {
+        Self {
+            min_concurrency: 1,
+            max_concurrency: 64,
+            min_chunk_size: 1024 * 1024,
+            max_chunk_size: 100 * 1024 * 1024,
+            target_latency: Duration::from_secs(2),
+        }
+    }
+}
+
+impl AutotuneOptions {
+    setters! {
+        min_concurrency: usize => min_concurrency,
+        max_concurrency: usize => max_concurrency,
+        min_chunk_size: u64 => min_chunk_size,
+        max_chunk_size: u64 => max_chunk_size,
+        target_latency: Duration => target_latency,
+    }
+}
+
+/// A throughput drop beyond this share of the best round is taken as
+/// congestion.
+const CONGESTION_THRESHOLD: f64 = 0.5;
+
+/// The state of an adaptive transfer, see [`AutotuneOptions`].
+#[derive(Debug)]
+pub(crate) struct Autotuner {
+    options: AutotuneOptions,
+    concurrency: usize,
+    chunk_size: u64,
+    best_throughput: f64,
+    round_started: Instant,
+    round_bytes: u64,
+    round_latency: Duration,
+    round_requests: usize,
+}
+
+impl Autotuner {
+    /// Starts with the given concurrency and chunk size, clamped to the
+    /// options. The chunk sizes of the options are further bounded by
+    /// `min_chunk_size` and `max_chunk_size`, e.g. so that a blob fits in the
+    /// blocks it can have.
+    pub(crate) fn new(
+        options: AutotuneOptions,
+        concurrency: usize,
+        chunk_size: u64,
+        min_chunk_size: u64,
+        max_chunk_size: u64,
+        now: Instant,
+    ) -> Self {
+        let mut options = options;
+        options.min_concurrency = options.min_concurrency.max(1);
+        options.max_concurrency = options.max_concurrency.max(options.min_concurrency);
+        options.min_chunk_size = options
+            .min_chunk_size
+            .max(min_chunk_size)
+            .min(max_chunk_size)
+            .max(1);
+        options.max_chunk_size = options
+            .max_chunk_size
+            .min(max_chunk_size)
+            .max(options.min_chunk_size);
+        Self {
+            concurrency: concurrency.clamp(options.min_concurrency, options.max_concurrency),
+            chunk_size: chunk_size.clamp(options.min_chunk_size, options.max_chunk_size),
+            options,
+            best_throughput: 0.0,
+            round_started: now,
+            round_bytes: 0,
+            round_latency: Duration::ZERO,
+            round_requests: 0,
+        }
+    }
+
+    /// How many requests to keep in flight.
+    pub(crate) fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// The size of the next chunk.
+    pub(crate) fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    /// Records a request which transferred `bytes` in `latency`. Once a round
+    /// of `concurrency` requests completed, the concurrency and chunk size are
+    /// adjusted.
+    pub(crate) fn record_success(&mut self, bytes: u64, latency: Duration, now: Instant) {
+        self.round_bytes += bytes;
+        self.round_latency += latency;
+        self.round_requests += 1;
+        if self.round_requests < self.concurrency {
+            return;
+        }
+
+        let elapsed = now
+            .saturating_duration_since(self.round_started)
+            .as_secs_f64()
+            .max(f64::EPSILON);
+        let throughput = self.round_bytes as f64 / elapsed;
+        if throughput >= self.best_throughput * CONGESTION_THRESHOLD {
+            self.concurrency = (self.concurrency + 1).min(self.options.max_concurrency);
+        } else {
+            self.decrease_concurrency();
+        }
+        self.best_throughput = self.best_throughput.max(throughput);
+
+        let mean_latency = self.round_latency / self.round_requests as u32;
+        if mean_latency < self.options.target_latency / 2 {
+            self.chunk_size = (self.chunk_size * 2).min(self.options.max_chunk_size);
+        } else if mean_latency > self.options.target_latency * 2 {
+            self.chunk_size = (self.chunk_size / 2).max(self.options.min_chunk_size);
+        }
+        self.start_round(now);
+    }
+
+    /// Records a request throttled by the service.
+    pub(crate) fn record_throttle(&mut self, now: Instant) {
+        self.decrease_concurrency();
+        // the throughput before the throttling is not reachable anymore
+        self.best_throughput = 0.0;
+        self.start_round(now);
+    }
+
+    fn decrease_concurrency(&mut self) {
+        self.concurrency = (self.concurrency / 2).max(self.options.min_concurrency);
+    }
+
+    fn start_round(&mut self, now: Instant) {
+        self.round_started = now;
+        self.round_bytes = 0;
+        self.round_latency = Duration::ZERO;
+        self.round_requests = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_autotuner() {
+        let start = Instant::now();
+        let options = AutotuneOptions::default().max_concurrency(4usize);
+        let mut tuner = Autotuner::new(options, 2, 4 * MIB, 2 * MIB, u64::MAX, start);
+        assert_eq!((tuner.concurrency(), tuner.chunk_size()), (2, 4 * MIB));
+
+        // fast requests: more of them, and larger
+        let latency = Duration::from_millis(100);
+        tuner.record_success(4 * MIB, latency, start + latency);
+        assert_eq!(tuner.concurrency(), 2, "the round is not complete");
+        tuner.record_success(4 * MIB, latency, start + latency);
+        assert_eq!((tuner.concurrency(), tuner.chunk_size()), (3, 8 * MIB));
+
+        let mut now = start + latency;
+        for _ in 0..10 {
+            now += latency;
+            for _ in 0..tuner.concurrency() {
+                tuner.record_success(8 * MIB, latency, now);
+            }
+        }
+        assert_eq!((tuner.concurrency(), tuner.chunk_size()), (4, 100 * MIB));
+
+        // throttling halves the concurrency
+        tuner.record_throttle(now);
+        assert_eq!(tuner.concurrency(), 2);
+        tuner.record_throttle(now);
+        tuner.record_throttle(now);
+        assert_eq!(tuner.concurrency(), 1);
+
+        // slow requests: smaller chunks, down to the minimum
+        for _ in 0..10 {
+            let latency = Duration::from_secs(10);
+            now += latency;
+            for _ in 0..tuner.concurrency() {
+                tuner.record_success(MIB, latency, now);
+            }
+        }
+        assert_eq!(tuner.chunk_size(), 2 * MIB);
+    }
+}
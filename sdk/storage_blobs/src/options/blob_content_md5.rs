@@ -1,21 +1,54 @@
+use super::Hash;
 use azure_core::headers::{self, Header};
 use azure_storage::ConsistencyMD5;
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct BlobContentMD5([u8; 16]);
 
+#[cfg(feature = "md5")]
 impl From<md5::Digest> for BlobContentMD5 {
     fn from(md5: md5::Digest) -> Self {
         BlobContentMD5(md5.0)
     }
 }
 
+impl BlobContentMD5 {
+    pub fn as_slice(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl From<[u8; 16]> for BlobContentMD5 {
+    fn from(md5: [u8; 16]) -> Self {
+        BlobContentMD5(md5)
+    }
+}
+
 impl From<ConsistencyMD5> for BlobContentMD5 {
     fn from(md5: ConsistencyMD5) -> Self {
         BlobContentMD5(*md5.as_slice())
     }
 }
 
+impl From<BlobContentMD5> for ConsistencyMD5 {
+    fn from(md5: BlobContentMD5) -> Self {
+        md5.0.into()
+    }
+}
+
+impl From<BlobContentMD5> for Hash {
+    fn from(md5: BlobContentMD5) -> Self {
+        Hash::MD5(md5.into())
+    }
+}
+
+impl fmt::Display for BlobContentMD5 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&base64::encode(self.0))
+    }
+}
+
 impl Header for BlobContentMD5 {
     fn name(&self) -> headers::HeaderName {
         "x-ms-blob-content-md5".into()
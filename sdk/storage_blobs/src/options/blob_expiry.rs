@@ -1,4 +1,7 @@
-use azure_core::{date, headers::Headers};
+use azure_core::{
+    date,
+    headers::{AsHeaders, HeaderName, HeaderValue, Headers},
+};
 use time::OffsetDateTime;
 
 const EXPIRY_TIME: &str = "x-ms-expiry-time";
@@ -35,3 +38,11 @@ impl BlobExpiry {
         headers
     }
 }
+
+impl AsHeaders for BlobExpiry {
+    type Iter = std::collections::hash_map::IntoIter<HeaderName, HeaderValue>;
+
+    fn as_headers(&self) -> Self::Iter {
+        self.to_headers().into_iter()
+    }
+}
@@ -1,5 +1,12 @@
-use azure_core::AppendToUrlQuery;
-use bytes::Bytes;
+use azure_core::{
+    error::{Error, ErrorKind},
+    AppendToUrlQuery,
+};
+use bytes::{BufMut, Bytes, BytesMut};
+use uuid::Uuid;
+
+/// The maximum length of a block id, before base64 encoding.
+pub const MAX_BLOCK_ID_LENGTH: usize = 64;
 
 /// Struct wrapping the bytes of a block blob block-id,
 ///
@@ -16,11 +23,42 @@ impl BlockId {
         Self(block_id.into())
     }
 
+    /// Returns a new random block id.
+    ///
+    /// Random block ids are always 16 bytes long, so they can be mixed freely
+    /// within a block list.
+    pub fn random() -> Self {
+        Self::new(Bytes::copy_from_slice(Uuid::new_v4().as_bytes()))
+    }
+
     /// Returns clone of bytes,
     ///
     pub fn bytes(&self) -> Bytes {
         self.0.clone()
     }
+
+    /// Returns the length of the block id before base64 encoding.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the block id is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks the block id is neither empty nor longer than 64 bytes.
+    pub fn validate(&self) -> azure_core::Result<()> {
+        if self.is_empty() || self.len() > MAX_BLOCK_ID_LENGTH {
+            return Err(Error::with_message(ErrorKind::Other, || {
+                format!(
+                    "block id must be between 1 and {MAX_BLOCK_ID_LENGTH} bytes long. len: {}",
+                    self.len()
+                )
+            }));
+        }
+        Ok(())
+    }
 }
 
 impl AppendToUrlQuery for BlockId {
@@ -44,3 +82,92 @@ impl AsRef<[u8]> for BlockId {
         self.0.as_ref()
     }
 }
+
+/// Generates fixed-length, ordered block ids for a single upload.
+///
+/// Each id is a random 16 byte prefix, shared by the whole sequence, followed
+/// by the big-endian block index. The prefix keeps ids from colliding with
+/// uncommitted blocks left over by other uploads to the same blob.
+///
+/// ```
+/// # use azure_storage_blobs::prelude::*;
+/// let mut ids = BlockIdSequence::new();
+/// let first = ids.next().unwrap();
+/// let second = ids.next().unwrap();
+/// assert_eq!(first.len(), second.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlockIdSequence {
+    prefix: Uuid,
+    index: u64,
+}
+
+impl BlockIdSequence {
+    pub fn new() -> Self {
+        Self::with_prefix(Uuid::new_v4())
+    }
+
+    /// Create a sequence with a known prefix, which allows resuming an upload.
+    pub fn with_prefix(prefix: Uuid) -> Self {
+        Self { prefix, index: 0 }
+    }
+
+    pub fn prefix(&self) -> Uuid {
+        self.prefix
+    }
+
+    /// The block id at `index` in this sequence.
+    pub fn block_id(&self, index: u64) -> BlockId {
+        let mut bytes = BytesMut::with_capacity(24);
+        bytes.put_slice(self.prefix.as_bytes());
+        bytes.put_u64(index);
+        BlockId::new(bytes.freeze())
+    }
+}
+
+impl Default for BlockIdSequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for BlockIdSequence {
+    type Item = BlockId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let block_id = self.block_id(self.index);
+        self.index += 1;
+        Some(block_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_block_ids_have_same_length() {
+        assert_eq!(BlockId::random().len(), BlockId::random().len());
+        assert_ne!(BlockId::random(), BlockId::random());
+    }
+
+    #[test]
+    fn test_sequence() {
+        let prefix = Uuid::new_v4();
+        let ids: Vec<_> = BlockIdSequence::with_prefix(prefix).take(300).collect();
+        assert!(ids.iter().all(|id| id.len() == 24));
+        assert_eq!(ids[256], BlockIdSequence::with_prefix(prefix).block_id(256));
+
+        // base64 of equal length input is equal length output
+        let encoded: Vec<_> = ids.iter().map(base64::encode).collect();
+        assert!(encoded.iter().all(|e| e.len() == encoded[0].len()));
+        assert!(encoded.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(BlockId::new("a").validate().is_ok());
+        assert!(BlockId::new("").validate().is_err());
+        assert!(BlockId::new(vec![0u8; 65]).validate().is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use azure_core::headers::{self, Header};
+use azure_storage::headers::ENCRYPTION_SCOPE;
+
+/// The encryption scope which encrypts the data and metadata of a request,
+/// instead of the container's default scope.
+///
+/// See: <https://docs.microsoft.com/azure/storage/blobs/encryption-scope-overview>
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptionScope(std::borrow::Cow<'static, str>);
+
+impl EncryptionScope {
+    pub fn new(scope: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        Self(scope.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl From<&'static str> for EncryptionScope {
+    fn from(s: &'static str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl From<String> for EncryptionScope {
+    fn from(s: String) -> Self {
+        Self::new(s)
+    }
+}
+
+impl Header for EncryptionScope {
+    fn name(&self) -> headers::HeaderName {
+        ENCRYPTION_SCOPE
+    }
+
+    fn value(&self) -> headers::HeaderValue {
+        self.0.to_string().into()
+    }
+}
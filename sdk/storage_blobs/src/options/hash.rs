@@ -1,10 +1,12 @@
 use azure_core::headers::{self, Header, CONTENT_MD5};
-use azure_storage::headers::CONTENT_CRC64;
+use azure_storage::{headers::CONTENT_CRC64, ConsistencyCRC64, ConsistencyMD5};
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+/// A hash of the body of a request, which the service checks before storing
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Hash {
-    MD5([u8; 16]),
-    CRC64(u64),
+    MD5(ConsistencyMD5),
+    CRC64(ConsistencyCRC64),
 }
 
 impl Header for Hash {
@@ -17,15 +19,34 @@ impl Header for Hash {
 
     fn value(&self) -> headers::HeaderValue {
         match self {
-            Hash::MD5(md5) => base64::encode(md5),
-            Hash::CRC64(crc64) => format!("{crc64}"),
+            Hash::MD5(md5) => md5.encode(),
+            Hash::CRC64(crc64) => crc64.encode(),
         }
         .into()
     }
 }
 
+impl From<ConsistencyMD5> for Hash {
+    fn from(md5: ConsistencyMD5) -> Self {
+        Hash::MD5(md5)
+    }
+}
+
+impl From<ConsistencyCRC64> for Hash {
+    fn from(crc64: ConsistencyCRC64) -> Self {
+        Hash::CRC64(crc64)
+    }
+}
+
+impl From<[u8; 16]> for Hash {
+    fn from(md5: [u8; 16]) -> Self {
+        Hash::MD5(md5.into())
+    }
+}
+
+#[cfg(feature = "md5")]
 impl From<md5::Digest> for Hash {
     fn from(md5: md5::Digest) -> Self {
-        Hash::MD5(md5.0)
+        Hash::MD5(md5.0.into())
     }
 }
@@ -0,0 +1,84 @@
+use azure_core::{
+    date,
+    headers::{AsHeaders, HeaderName, HeaderValue},
+};
+use time::OffsetDateTime;
+
+const IMMUTABILITY_POLICY_UNTIL_DATE: HeaderName =
+    HeaderName::from_static("x-ms-immutability-policy-until-date");
+const IMMUTABILITY_POLICY_MODE: HeaderName =
+    HeaderName::from_static("x-ms-immutability-policy-mode");
+pub(crate) const LEGAL_HOLD: HeaderName = HeaderName::from_static("x-ms-legal-hold");
+
+/// A time-based retention policy which keeps a blob from being modified or
+/// deleted until it expires.
+///
+/// Requires version-level immutability support on the container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImmutabilityPolicy {
+    pub expiry: OffsetDateTime,
+    pub mode: ImmutabilityPolicyMode,
+}
+
+impl ImmutabilityPolicy {
+    pub fn new(expiry: OffsetDateTime, mode: ImmutabilityPolicyMode) -> Self {
+        Self { expiry, mode }
+    }
+}
+
+/// Whether an immutability policy can still be shortened or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImmutabilityPolicyMode {
+    Unlocked,
+    Locked,
+}
+
+impl AsHeaders for ImmutabilityPolicy {
+    type Iter = std::vec::IntoIter<(HeaderName, HeaderValue)>;
+
+    fn as_headers(&self) -> Self::Iter {
+        let mode = match self.mode {
+            ImmutabilityPolicyMode::Unlocked => "Unlocked",
+            ImmutabilityPolicyMode::Locked => "Locked",
+        };
+        vec![
+            (
+                IMMUTABILITY_POLICY_UNTIL_DATE,
+                date::to_rfc1123(&self.expiry).into(),
+            ),
+            (IMMUTABILITY_POLICY_MODE, mode.into()),
+        ]
+        .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_immutability_policy_headers() {
+        let policy = ImmutabilityPolicy::new(
+            datetime!(2030-01-02 03:04:05 UTC),
+            ImmutabilityPolicyMode::Locked,
+        );
+        let headers: Vec<_> = policy
+            .as_headers()
+            .map(|(name, value)| (name.as_str().to_owned(), value.as_str().to_owned()))
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                (
+                    "x-ms-immutability-policy-until-date".to_owned(),
+                    "Wed, 02 Jan 2030 03:04:05 GMT".to_owned()
+                ),
+                (
+                    "x-ms-immutability-policy-mode".to_owned(),
+                    "Locked".to_owned()
+                ),
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Various blob related request options
 
 mod access_tier;
+mod autotune;
 mod ba512_range;
 mod blob_cache_control;
 mod blob_content_disposition;
@@ -14,11 +15,17 @@ mod block_id;
 mod condition_append_position;
 mod condition_max_size;
 mod delete_snapshot_method;
+mod encryption_scope;
 mod hash;
+mod immutability_policy;
+mod point_in_time;
 mod rehydrate_policy;
+mod tag_condition;
 mod tags;
 
 pub use access_tier::AccessTier;
+pub use autotune::AutotuneOptions;
+pub(crate) use autotune::Autotuner;
 pub use ba512_range::BA512Range;
 pub use blob_cache_control::BlobCacheControl;
 pub use blob_content_disposition::BlobContentDisposition;
@@ -28,42 +35,20 @@ pub use blob_content_md5::BlobContentMD5;
 pub use blob_content_type::BlobContentType;
 pub use blob_expiry::BlobExpiry;
 pub use blob_versioning::BlobVersioning;
-pub use block_id::BlockId;
+pub use block_id::{BlockId, BlockIdSequence, MAX_BLOCK_ID_LENGTH};
 pub use condition_append_position::ConditionAppendPosition;
 pub use condition_max_size::ConditionMaxSize;
 pub use delete_snapshot_method::DeleteSnapshotsMethod;
+pub use encryption_scope::EncryptionScope;
 pub use hash::Hash;
+pub(crate) use immutability_policy::LEGAL_HOLD;
+pub use immutability_policy::{ImmutabilityPolicy, ImmutabilityPolicyMode};
+pub use point_in_time::{Snapshot, VersionId};
 pub use rehydrate_policy::RehydratePriority;
+pub use tag_condition::TagCondition;
 pub use tags::Tags;
 
-use std::str::FromStr;
-
-use azure_core::error::Error;
 use azure_core::headers::HeaderName;
 
-request_query!(
-    /// This type could also be a DateTime but the docs clearly states to treat is as opaque so we do not convert it in any way.
-    ///
-    ///See: <https://docs.microsoft.com/rest/api/storageservices/get-blob>"]
-    VersionId,
-    "version_id"
-);
-
-request_query!(
-    /// This type could also be a DateTime but the docs clearly states to treat is as opaque so we do not convert it in any way.
-    ///
-    /// See: <https://docs.microsoft.com/rest/api/storageservices/get-blob>"]
-    #[derive(PartialEq, Eq, Serialize, Deserialize)]
-    Snapshot,
-    "snapshot"
-);
-
-impl FromStr for Snapshot {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self::new(s.to_string()))
-    }
-}
-
 pub const SNAPSHOT: HeaderName = HeaderName::from_static("x-ms-snapshot");
+pub const VERSION_ID: HeaderName = HeaderName::from_static("x-ms-version-id");
@@ -0,0 +1,133 @@
+use azure_core::{
+    date,
+    error::{Error, ErrorKind, ResultExt},
+    AppendToUrlQuery,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use time::{OffsetDateTime, UtcOffset};
+
+/// Formats a point in time the way the service names snapshots and versions,
+/// in UTC with seven fractional digits: `2011-03-09T01:42:34.9360000Z`.
+fn format(date_time: &OffsetDateTime) -> String {
+    let utc = date_time.to_offset(UtcOffset::UTC);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:07}Z",
+        utc.year(),
+        u8::from(utc.month()),
+        utc.day(),
+        utc.hour(),
+        utc.minute(),
+        utc.second(),
+        utc.nanosecond() / 100
+    )
+}
+
+macro_rules! point_in_time {
+    ($(#[$outer:meta])* $name:ident, $query:expr, $what:expr) => {
+        $(#[$outer])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(OffsetDateTime);
+
+        impl $name {
+            pub fn new(date_time: OffsetDateTime) -> Self {
+                Self(date_time)
+            }
+
+            pub fn date_time(&self) -> OffsetDateTime {
+                self.0
+            }
+        }
+
+        impl From<OffsetDateTime> for $name {
+            fn from(date_time: OffsetDateTime) -> Self {
+                Self(date_time)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> azure_core::Result<Self> {
+                date::parse_rfc3339(s)
+                    .map(Self)
+                    .with_context(ErrorKind::DataConversion, || {
+                        format!("invalid {}: {s}", $what)
+                    })
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&format(&self.0))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        impl AppendToUrlQuery for $name {
+            fn append_to_url_query(&self, url: &mut url::Url) {
+                url.query_pairs_mut().append_pair($query, &self.to_string());
+            }
+        }
+    };
+}
+
+point_in_time!(
+    /// A snapshot of a blob, named by the time it was taken.
+    ///
+    /// Snapshots order chronologically.
+    ///
+    /// See: <https://docs.microsoft.com/rest/api/storageservices/creating-a-snapshot-of-a-blob>
+    Snapshot,
+    "snapshot",
+    "snapshot"
+);
+
+point_in_time!(
+    /// A version of a blob, named by the time it was created.
+    ///
+    /// Versions order chronologically, so the latest versions of a blob are
+    /// the greatest.
+    ///
+    /// See: <https://docs.microsoft.com/azure/storage/blobs/versioning-overview>
+    VersionId,
+    "versionid",
+    "version id"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_order() -> azure_core::Result<()> {
+        let older: VersionId = "2019-09-29T12:00:00.0000000Z".parse()?;
+        let newer: VersionId = "2019-09-29T12:00:00.0000001Z".parse()?;
+        assert!(older < newer);
+        assert_eq!(newer.to_string(), "2019-09-29T12:00:00.0000001Z");
+
+        let snapshot: Snapshot = "2011-03-09T01:42:34.936Z".parse()?;
+        assert_eq!(snapshot.to_string(), "2011-03-09T01:42:34.9360000Z");
+        let mut url = url::Url::parse("https://account.blob.core.windows.net/c/b")?;
+        snapshot.append_to_url_query(&mut url);
+        assert_eq!(
+            url.query(),
+            Some("snapshot=2011-03-09T01%3A42%3A34.9360000Z")
+        );
+
+        assert!("latest".parse::<Snapshot>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use azure_core::request_options::IfTags;
+use std::fmt;
+
+/// A condition on the index tags of a blob, checked by the service before an
+/// operation is carried out.
+///
+/// Conditions are sent in the `x-ms-if-tags` header and can be passed wherever
+/// an `IfTags` is expected:
+///
+/// ```
+/// use azure_storage_blobs::prelude::TagCondition;
+///
+/// let condition = TagCondition::equals("status", "processed")
+///     .and(TagCondition::less_than("priority", "5"));
+/// assert_eq!(
+///     condition.to_string(),
+///     r#""status" = 'processed' AND "priority" < '5'"#
+/// );
+/// ```
+///
+/// Conditions are also the expressions of
+/// [`BlobServiceClient::find_blobs_by_tags`](crate::prelude::BlobServiceClient::find_blobs_by_tags),
+/// which can further be restricted to a container with
+/// [`TagCondition::container`].
+///
+/// ref: <https://docs.microsoft.com/azure/storage/blobs/storage-manage-find-blobs#conditional-blob-operations-with-blob-index-tags>
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "String")]
+pub struct TagCondition(String);
+
+impl TagCondition {
+    /// A condition written out by hand. It is sent as is.
+    pub fn new(expression: impl Into<String>) -> Self {
+        Self(expression.into())
+    }
+
+    pub fn equals(key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        Self::comparison(key.as_ref(), "=", value.as_ref())
+    }
+
+    pub fn not_equals(key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        Self::comparison(key.as_ref(), "<>", value.as_ref())
+    }
+
+    pub fn greater_than(key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        Self::comparison(key.as_ref(), ">", value.as_ref())
+    }
+
+    pub fn greater_or_equal(key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        Self::comparison(key.as_ref(), ">=", value.as_ref())
+    }
+
+    pub fn less_than(key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        Self::comparison(key.as_ref(), "<", value.as_ref())
+    }
+
+    pub fn less_or_equal(key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        Self::comparison(key.as_ref(), "<=", value.as_ref())
+    }
+
+    /// The blob must be in the given container. Only
+    /// [`find_blobs_by_tags`](crate::prelude::BlobServiceClient::find_blobs_by_tags)
+    /// accepts this condition.
+    pub fn container(container_name: impl AsRef<str>) -> Self {
+        Self(format!(
+            "@container = {}",
+            quote_tag_value(container_name.as_ref())
+        ))
+    }
+
+    /// Both conditions must hold.
+    #[must_use]
+    pub fn and(self, other: TagCondition) -> Self {
+        Self(format!("{} AND {}", self.0, other.0))
+    }
+
+    /// Either condition must hold. The alternatives are parenthesized, so the
+    /// result can be combined further with [`TagCondition::and`].
+    #[must_use]
+    pub fn or(self, other: TagCondition) -> Self {
+        Self(format!("({} OR {})", self.0, other.0))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn comparison(key: &str, operator: &str, value: &str) -> Self {
+        Self(format!(
+            "{} {operator} {}",
+            quote_tag_key(key),
+            quote_tag_value(value)
+        ))
+    }
+}
+
+/// Quote a tag key for use in a tag expression. Double quotes in the key are
+/// doubled.
+fn quote_tag_key(key: &str) -> String {
+    format!("\"{}\"", key.replace('"', "\"\""))
+}
+
+/// Quote a tag value for use in a tag expression. Single quotes in the value
+/// are doubled.
+fn quote_tag_value(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+impl fmt::Display for TagCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for TagCondition {
+    fn from(expression: String) -> Self {
+        Self::new(expression)
+    }
+}
+
+impl From<&str> for TagCondition {
+    fn from(expression: &str) -> Self {
+        Self::new(expression)
+    }
+}
+
+impl From<TagCondition> for IfTags {
+    fn from(condition: TagCondition) -> Self {
+        IfTags::new(condition.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::headers::Header;
+
+    #[test]
+    fn test_tag_condition() {
+        let condition = TagCondition::equals("status", "processed")
+            .or(TagCondition::not_equals("owner", "o'brien"))
+            .and(TagCondition::greater_or_equal("date", "2022-01-01"));
+        assert_eq!(
+            condition.as_str(),
+            r#"("status" = 'processed' OR "owner" <> 'o''brien') AND "date" >= '2022-01-01'"#
+        );
+
+        let if_tags: IfTags = condition.clone().into();
+        assert_eq!(if_tags.value().as_str(), condition.as_str());
+
+        let condition = TagCondition::container("o'brien").and(TagCondition::equals("a", "b"));
+        assert_eq!(
+            condition.as_str(),
+            r#"@container = 'o''brien' AND "a" = 'b'"#
+        );
+    }
+}
@@ -62,6 +62,31 @@ impl Tags {
         });
     }
 
+    /// The value of the tag `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.tag_set
+            .tags
+            .iter()
+            .find(|tag| tag.key == key)
+            .map(|tag| tag.value.as_str())
+    }
+
+    /// The keys and values of the tags.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.tag_set
+            .tags
+            .iter()
+            .map(|tag| (tag.key.as_str(), tag.value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tag_set.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tag_set.tags.is_empty()
+    }
+
     pub fn to_xml(&self) -> azure_core::Result<String> {
         Ok(format!(
             "<?xml version=\"1.0\" encoding=\"utf-8\"?>{}",
@@ -158,6 +183,13 @@ mod tests {
         assert_eq!(tags.tag_set.tags[0].value, "tag-value-1");
         assert_eq!(tags.tag_set.tags[1].key, "tag-name-2");
         assert_eq!(tags.tag_set.tags[1].value, "tag-value-2");
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags.get("tag-name-2"), Some("tag-value-2"));
+        assert_eq!(tags.get("tag-name-3"), None);
+        assert_eq!(
+            tags.iter().collect::<Vec<_>>(),
+            [("tag-name-1", "tag-value-1"), ("tag-name-2", "tag-value-2")]
+        );
         let as_xml = tags.to_xml()?;
         assert_eq!(as_xml, xml);
 
@@ -3,8 +3,8 @@ pub use crate::options::*;
 pub use crate::{
     blob::{Blob, BlobBlockType, BlockList, BlockListType},
     clients::{
-        BlobClient, BlobLeaseClient, BlobServiceClient, ClientBuilder, ContainerClient,
-        ContainerLeaseClient,
+        BlobClient, BlobClientConfig, BlobLeaseClient, BlobServiceClient, CachedBlobClient,
+        ClientBuilder, ContainerClient, ContainerLeaseClient, StorageAccountRegistry,
     },
 };
 pub use azure_storage::{StoredAccessPolicy, StoredAccessPolicyList};
@@ -0,0 +1,256 @@
+//! Processes blobs as they are created, from the Event Grid notifications
+//! delivered to a storage queue.
+//!
+//! Subscribe the queue to the `Microsoft.Storage.BlobCreated` events of the
+//! account, then hand a [`QueueTrigger`] a callback receiving a [`BlobClient`]
+//! for each new blob. A message is deleted once the callback succeeded for all
+//! the blobs it notifies; otherwise it becomes visible again after the
+//! visibility timeout and is retried, until it is moved to the poison queue.
+use crate::{events::*, prelude::*};
+use azure_core::error::{Error, ErrorKind};
+use azure_storage_queues::{operations::Message, prelude::*};
+use futures::{Future, StreamExt};
+use std::time::Duration;
+
+/// Options for a [`QueueTrigger`].
+#[derive(Debug, Clone)]
+pub struct QueueTriggerOptions {
+    concurrency: usize,
+    max_dequeue_count: u64,
+    visibility_timeout: Duration,
+    poll_interval: Duration,
+    poison_queue: Option<QueueClient>,
+}
+
+impl Default for QueueTriggerOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 16,
+            max_dequeue_count: 5,
+            visibility_timeout: Duration::from_secs(300),
+            poll_interval: Duration::from_secs(1),
+            poison_queue: None,
+        }
+    }
+}
+
+impl QueueTriggerOptions {
+    setters! {
+        concurrency: usize => concurrency,
+        max_dequeue_count: u64 => max_dequeue_count,
+        visibility_timeout: Duration => visibility_timeout,
+        poll_interval: Duration => poll_interval,
+        poison_queue: QueueClient => Some(poison_queue),
+    }
+}
+
+/// What became of a queue message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageOutcome {
+    /// The callback succeeded and the message was deleted.
+    Processed,
+    /// The callback failed; the message will be retried.
+    Failed,
+    /// The message could not be parsed or failed too often, and was moved to
+    /// the poison queue (or dropped, without one).
+    Poisoned,
+}
+
+/// Calls a callback for each blob created, as notified by the messages of a
+/// storage queue.
+#[derive(Debug, Clone)]
+pub struct QueueTrigger {
+    queue: QueueClient,
+    blob_service: BlobServiceClient,
+    options: QueueTriggerOptions,
+}
+
+impl QueueTrigger {
+    pub fn new(
+        queue: QueueClient,
+        blob_service: BlobServiceClient,
+        options: QueueTriggerOptions,
+    ) -> Self {
+        Self {
+            queue,
+            blob_service,
+            options,
+        }
+    }
+
+    /// Processes messages until receiving them fails, waiting for the poll
+    /// interval whenever the queue is empty.
+    pub async fn run<F, Fut>(&self, callback: F) -> azure_core::Result<()>
+    where
+        F: Fn(BlobClient, BlobCreatedEventData) -> Fut,
+        Fut: Future<Output = azure_core::Result<()>>,
+    {
+        loop {
+            if self.run_once(&callback).await?.is_empty() {
+                azure_core::sleep::sleep(self.options.poll_interval).await;
+            }
+        }
+    }
+
+    /// Receives one batch of messages and processes them, at most
+    /// `concurrency` at a time.
+    pub async fn run_once<F, Fut>(&self, callback: &F) -> azure_core::Result<Vec<MessageOutcome>>
+    where
+        F: Fn(BlobClient, BlobCreatedEventData) -> Fut,
+        Fut: Future<Output = azure_core::Result<()>>,
+    {
+        let number_of_messages = self.options.concurrency.clamp(1, 32) as u8;
+        let messages = self
+            .queue
+            .get_messages()
+            .number_of_messages(number_of_messages)
+            .visibility_timeout(self.options.visibility_timeout)
+            .await?
+            .messages;
+
+        let outcomes = futures::stream::iter(messages)
+            .map(|message| self.process(message, callback))
+            .buffer_unordered(self.options.concurrency.max(1))
+            .collect()
+            .await;
+        Ok(outcomes)
+    }
+
+    async fn process<F, Fut>(&self, message: Message, callback: &F) -> MessageOutcome
+    where
+        F: Fn(BlobClient, BlobCreatedEventData) -> Fut,
+        Fut: Future<Output = azure_core::Result<()>>,
+    {
+        if message.dequeue_count > self.options.max_dequeue_count {
+            log::warn!(
+                "message {} was dequeued {} times",
+                message.message_id,
+                message.dequeue_count
+            );
+            return self.poison(message).await;
+        }
+
+        let events = match decode_message(&message.message_text) {
+            Ok(events) => events,
+            Err(error) => {
+                log::warn!("message {} is invalid: {}", message.message_id, error);
+                return self.poison(message).await;
+            }
+        };
+
+        for (container_name, blob_name, data) in events {
+            let blob_client = self.blob_client(&container_name, &blob_name);
+            if let Err(error) = callback(blob_client, data).await {
+                log::warn!(
+                    "processing message {} failed: {}",
+                    message.message_id,
+                    error
+                );
+                return MessageOutcome::Failed;
+            }
+        }
+
+        match self.queue.pop_receipt_client(message).delete().await {
+            Ok(_) => MessageOutcome::Processed,
+            Err(error) => {
+                // the message will be processed again
+                log::warn!("deleting a processed message failed: {}", error);
+                MessageOutcome::Failed
+            }
+        }
+    }
+
+    async fn poison(&self, message: Message) -> MessageOutcome {
+        if let Some(poison_queue) = &self.options.poison_queue {
+            if let Err(error) = poison_queue.put_message(message.message_text.clone()).await {
+                log::warn!("moving message {} failed: {}", message.message_id, error);
+                return MessageOutcome::Failed;
+            }
+        }
+        match self.queue.pop_receipt_client(message).delete().await {
+            Ok(_) => MessageOutcome::Poisoned,
+            Err(_) => MessageOutcome::Failed,
+        }
+    }
+
+    fn blob_client(&self, container_name: &str, blob_name: &str) -> BlobClient {
+        self.blob_service
+            .container_client(container_name)
+            .blob_client(blob_name)
+    }
+}
+
+/// Decodes the blob created events of a message. Event Grid base64 encodes the
+/// events it delivers to storage queues, but plain JSON is accepted too.
+fn decode_message(text: &str) -> azure_core::Result<Vec<(String, String, BlobCreatedEventData)>> {
+    let body = match base64::decode(text.trim()) {
+        Ok(body) => body,
+        Err(_) => text.as_bytes().to_vec(),
+    };
+
+    let mut blobs = Vec::new();
+    if let Ok(events) = parse_event_grid_events(&body) {
+        for event in events {
+            if let BlobEvent::Created(data) = event.blob_event()? {
+                let (container, blob) = event
+                    .container_and_blob_name()
+                    .ok_or_else(|| invalid_subject(&event.subject))?;
+                blobs.push((container.to_owned(), blob.to_owned(), data));
+            }
+        }
+    } else {
+        for event in parse_cloud_events(&body)? {
+            if let BlobEvent::Created(data) = event.blob_event()? {
+                let (container, blob) = event
+                    .container_and_blob_name()
+                    .ok_or_else(|| invalid_subject(event.subject.as_deref().unwrap_or_default()))?;
+                blobs.push((container.to_owned(), blob.to_owned(), data));
+            }
+        }
+    }
+    Ok(blobs)
+}
+
+fn invalid_subject(subject: &str) -> Error {
+    Error::with_message(ErrorKind::DataConversion, || {
+        format!("the event subject {subject} is not a blob")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = r#"{
+        "topic": "/subscriptions/id/resourceGroups/group/providers/Microsoft.Storage/storageAccounts/account",
+        "subject": "/blobServices/default/containers/container/blobs/blob.txt",
+        "eventType": "Microsoft.Storage.BlobCreated",
+        "eventTime": "2017-06-26T18:41:00.9584103Z",
+        "id": "831e1650-001e-001b-66ab-eeb76e069631",
+        "data": {
+            "api": "PutBlob",
+            "requestId": "831e1650-001e-001b-66ab-eeb76e000000",
+            "eTag": "0x8D4BCC2E4835CD0",
+            "contentType": "text/plain",
+            "contentLength": 11,
+            "blobType": "BlockBlob",
+            "url": "https://account.blob.core.windows.net/container/blob.txt",
+            "sequencer": "00000000000004420000000000028963"
+        },
+        "dataVersion": "",
+        "metadataVersion": "1"
+    }"#;
+
+    #[test]
+    fn test_decode_message() -> azure_core::Result<()> {
+        for text in [EVENT.to_owned(), base64::encode(EVENT)] {
+            let blobs = decode_message(&text)?;
+            assert_eq!(blobs.len(), 1);
+            assert_eq!(blobs[0].0, "container");
+            assert_eq!(blobs[0].1, "blob.txt");
+            assert_eq!(blobs[0].2.content_length, 11);
+        }
+        assert!(decode_message("not an event").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,565 @@
+//! Mirrors the blobs of a container, or those under a prefix, into another
+//! container, possibly of another account, e.g. for disaster recovery.
+//!
+//! A [`ReplicationJob`] works in passes. Each pass lists the source and
+//! starts a server-side copy (Copy Blob) of every blob modified since the
+//! previous complete pass, as decided by the [`ConflictPolicy`]. The
+//! [`ReplicationCheckpoint`] is saved to a [`CheckpointStore`] after each page
+//! of the listing, so that a restarted job resumes the pass where it stopped.
+//!
+//! Blobs deleted from the source are not deleted from the destination, and
+//! copies are only started: the destination blobs report the progress of
+//! their copy in their properties. A pass which leaves copies pending does not
+//! move the watermark past them, and the next pass checks them again rather
+//! than restarting them.
+use crate::{
+    blob::{Blob, CopyStatus},
+    container::operations::BlobFailure,
+    prelude::*,
+};
+use azure_core::{
+    error::{Error, ErrorKind, ResultExt},
+    prelude::*,
+    StatusCode, Url,
+};
+use azure_storage::{prelude::BlobSasPermissions, StorageCredentials};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Debug, sync::Mutex, time::Duration};
+use time::OffsetDateTime;
+
+/// What to do with a source blob which also exists in the destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Copy the source blob over the destination blob.
+    #[default]
+    Overwrite,
+    /// Keep the destination blob.
+    SkipExisting,
+    /// Keep the destination blob if it was modified after the source blob,
+    /// e.g. because it was written to during a failover.
+    NewerWins,
+}
+
+/// Options for a [`ReplicationJob`].
+#[derive(Debug, Clone)]
+pub struct ReplicationOptions {
+    prefix: Option<Prefix>,
+    concurrency: usize,
+    conflict_policy: ConflictPolicy,
+    poll_interval: Duration,
+    sas_lifetime: Duration,
+}
+
+impl Default for ReplicationOptions {
+    fn default() -> Self {
+        Self {
+            prefix: None,
+            concurrency: 16,
+            conflict_policy: ConflictPolicy::default(),
+            poll_interval: Duration::from_secs(60),
+            sas_lifetime: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl ReplicationOptions {
+    setters! {
+        prefix: Prefix => Some(prefix),
+        concurrency: usize => concurrency,
+        conflict_policy: ConflictPolicy => conflict_policy,
+        poll_interval: Duration => poll_interval,
+        sas_lifetime: Duration => sas_lifetime,
+    }
+}
+
+/// The progress of a [`ReplicationJob`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicationCheckpoint {
+    /// The blobs last modified before this instant were replicated by a
+    /// complete pass.
+    #[serde(default, with = "azure_core::date::rfc3339::option")]
+    pub replicated_until: Option<OffsetDateTime>,
+    /// When the pass in progress started, as told by the service.
+    #[serde(default, with = "azure_core::date::rfc3339::option")]
+    pub pass_started: Option<OffsetDateTime>,
+    /// Where the listing of the pass in progress resumes.
+    #[serde(default)]
+    pub marker: Option<NextMarker>,
+    /// How many blobs of the pass in progress failed to replicate.
+    #[serde(default)]
+    pub failures: u64,
+    /// How many copies of the pass in progress had not completed when they
+    /// were last checked.
+    #[serde(default)]
+    pub pending: u64,
+}
+
+/// Where a [`ReplicationJob`] persists its [`ReplicationCheckpoint`].
+#[async_trait::async_trait]
+pub trait CheckpointStore: Debug + Send + Sync {
+    /// The last checkpoint saved, if any.
+    async fn load(&self) -> azure_core::Result<Option<ReplicationCheckpoint>>;
+
+    async fn save(&self, checkpoint: &ReplicationCheckpoint) -> azure_core::Result<()>;
+}
+
+/// Keeps the checkpoint as JSON in a blob.
+#[async_trait::async_trait]
+impl CheckpointStore for BlobClient {
+    async fn load(&self) -> azure_core::Result<Option<ReplicationCheckpoint>> {
+        match self.get_content().await {
+            Ok(content) => serde_json::from_slice(&content)
+                .map(Some)
+                .with_context(ErrorKind::DataConversion, || {
+                    format!("invalid replication checkpoint in {}", self.blob_name())
+                }),
+            Err(error) if is_not_found(&error) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn save(&self, checkpoint: &ReplicationCheckpoint) -> azure_core::Result<()> {
+        self.put_block_blob(serde_json::to_vec(checkpoint)?)
+            .content_type("application/json")
+            .await?;
+        Ok(())
+    }
+}
+
+/// Keeps the checkpoint in memory, for jobs which start over when the
+/// process restarts.
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore(Mutex<Option<ReplicationCheckpoint>>);
+
+#[async_trait::async_trait]
+impl CheckpointStore for MemoryCheckpointStore {
+    async fn load(&self) -> azure_core::Result<Option<ReplicationCheckpoint>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    async fn save(&self, checkpoint: &ReplicationCheckpoint) -> azure_core::Result<()> {
+        *self.0.lock().unwrap() = Some(checkpoint.clone());
+        Ok(())
+    }
+}
+
+/// The outcome of a replication pass.
+#[derive(Debug, Default)]
+pub struct ReplicationStats {
+    /// The number of source blobs listed.
+    pub listed: u64,
+    /// The number of copies started.
+    pub copied: u64,
+    /// The total length of the blobs copied.
+    pub bytes_copied: u64,
+    /// The number of blobs left as they were, unchanged since the previous
+    /// pass or kept by the conflict policy.
+    pub skipped: u64,
+    /// The number of copies, started by this pass or an earlier one, which
+    /// had not completed. They are checked again by the next pass.
+    pub pending: u64,
+    /// The blobs which failed to replicate. They are tried again by the next
+    /// pass.
+    pub failures: Vec<BlobFailure>,
+}
+
+enum Outcome {
+    /// A copy of the given length was started, and is pending or not.
+    Copied(u64, bool),
+    /// A copy started by an earlier pass is still pending.
+    Pending,
+    Skipped,
+}
+
+/// Mirrors a source container into a destination container, see the
+/// [module documentation](self).
+#[derive(Debug)]
+pub struct ReplicationJob<C> {
+    source: ContainerClient,
+    destination: ContainerClient,
+    checkpoints: C,
+    options: ReplicationOptions,
+}
+
+impl<C> ReplicationJob<C>
+where
+    C: CheckpointStore,
+{
+    pub fn new(
+        source: ContainerClient,
+        destination: ContainerClient,
+        checkpoints: C,
+        options: ReplicationOptions,
+    ) -> Self {
+        Self {
+            source,
+            destination,
+            checkpoints,
+            options,
+        }
+    }
+
+    /// Runs passes until one fails, waiting for the poll interval between
+    /// them. `on_pass` receives the outcome of each pass, e.g. to report it.
+    pub async fn run<F>(&self, mut on_pass: F) -> azure_core::Result<()>
+    where
+        F: FnMut(ReplicationStats),
+    {
+        loop {
+            on_pass(self.run_once().await?);
+            azure_core::sleep::sleep(self.options.poll_interval).await;
+        }
+    }
+
+    /// Runs one pass, or the rest of the pass in progress.
+    ///
+    /// The pass fails if listing the source or saving the checkpoint fails;
+    /// blobs which fail to replicate are reported in the stats instead.
+    pub async fn run_once(&self) -> azure_core::Result<ReplicationStats> {
+        let mut checkpoint = self.checkpoints.load().await?.unwrap_or_default();
+        let mut stats = ReplicationStats::default();
+
+        let mut list_blobs = self.source.list_blobs();
+        if let Some(prefix) = self.options.prefix.clone() {
+            list_blobs = list_blobs.prefix(prefix);
+        }
+        if let Some(marker) = checkpoint.marker.clone() {
+            list_blobs = list_blobs.marker(marker);
+        }
+        let mut pages = list_blobs.into_stream();
+        while let Some(page) = pages.next().await {
+            let page = page?;
+            // the service's clock, so that the watermark is not skewed
+            checkpoint.pass_started.get_or_insert(page.date);
+
+            let blobs: Vec<_> = page.blobs.blobs().cloned().collect();
+            stats.listed += blobs.len() as u64;
+            let replicated_until = checkpoint.replicated_until;
+            let mut outcomes = futures::stream::iter(blobs)
+                .map(|blob| async move {
+                    let outcome = if replicated_until
+                        .map_or(false, |until| blob.properties.last_modified < until)
+                    {
+                        Ok(Outcome::Skipped)
+                    } else {
+                        self.replicate(&blob, page.date).await
+                    };
+                    (blob.name, outcome)
+                })
+                .buffer_unordered(self.options.concurrency.max(1));
+            while let Some((blob_name, outcome)) = outcomes.next().await {
+                match outcome {
+                    Ok(Outcome::Copied(len, pending)) => {
+                        stats.copied += 1;
+                        stats.bytes_copied += len;
+                        if pending {
+                            checkpoint.pending += 1;
+                            stats.pending += 1;
+                        }
+                    }
+                    Ok(Outcome::Pending) => {
+                        checkpoint.pending += 1;
+                        stats.pending += 1;
+                    }
+                    Ok(Outcome::Skipped) => stats.skipped += 1,
+                    Err(error) => {
+                        checkpoint.failures += 1;
+                        stats.failures.push(BlobFailure { blob_name, error });
+                    }
+                }
+            }
+
+            checkpoint.marker = page.next_marker.clone();
+            self.checkpoints.save(&checkpoint).await?;
+        }
+
+        // failed blobs and pending copies are older than the pass, so the
+        // watermark stays put for the next pass to pick them up again
+        if checkpoint.failures == 0 && checkpoint.pending == 0 {
+            checkpoint.replicated_until = checkpoint.pass_started;
+        }
+        checkpoint.pass_started = None;
+        checkpoint.marker = None;
+        checkpoint.failures = 0;
+        checkpoint.pending = 0;
+        self.checkpoints.save(&checkpoint).await?;
+        Ok(stats)
+    }
+
+    /// Replicates a blob, signing the copy source as of `now`: a resumed pass
+    /// may have started longer ago than the lifetime of the signature.
+    async fn replicate(&self, blob: &Blob, now: OffsetDateTime) -> azure_core::Result<Outcome> {
+        let destination = self.destination.blob_client(&blob.name);
+        let existing = match destination.get_properties().await {
+            Ok(properties) => Some(properties.blob.properties),
+            Err(error) if is_not_found(&error) => None,
+            Err(error) => return Err(error),
+        };
+        if let Some(existing) = existing {
+            // copying again would restart the copy of an earlier pass
+            if existing.copy_status == Some(CopyStatus::Pending)
+                && existing
+                    .copy_source
+                    .as_deref()
+                    .and_then(|url| url.split('?').next())
+                    == Some(self.source.blob_client(&blob.name).url()?.as_str())
+            {
+                return Ok(Outcome::Pending);
+            }
+            match self.options.conflict_policy {
+                ConflictPolicy::Overwrite => {}
+                ConflictPolicy::SkipExisting => return Ok(Outcome::Skipped),
+                ConflictPolicy::NewerWins => {
+                    if existing.last_modified > blob.properties.last_modified {
+                        return Ok(Outcome::Skipped);
+                    }
+                }
+            }
+        }
+
+        let source_url = self.source_url(&blob.name, now)?;
+        let copy = destination.copy(source_url).await?;
+        match copy.copy_status {
+            CopyStatus::Success => Ok(Outcome::Copied(blob.properties.content_length, false)),
+            CopyStatus::Pending => Ok(Outcome::Copied(blob.properties.content_length, true)),
+            status => Err(Error::with_message(ErrorKind::Other, || {
+                format!("copying {} ended with status {status}", blob.name)
+            })),
+        }
+    }
+
+    /// The URL the destination copies the blob from, signed for reading when
+    /// the source is accessed with a key.
+    fn source_url(&self, blob_name: &str, now: OffsetDateTime) -> azure_core::Result<Url> {
+        let blob_client = self.source.blob_client(blob_name);
+        match self.source.credentials() {
+            StorageCredentials::Key(..) | StorageCredentials::KeyWithSecondary(..) => {
+                let permissions = BlobSasPermissions {
+                    read: true,
+                    ..Default::default()
+                };
+                let sas = blob_client
+                    .shared_access_signature(permissions, now + self.options.sas_lifetime)?;
+                blob_client.generate_signed_blob_url(&sas)
+            }
+            StorageCredentials::SASToken(pairs) => {
+                let mut url = blob_client.url()?;
+                url.query_pairs_mut()
+                    .extend_pairs(pairs.iter().map(|(name, value)| (name, value.secret())));
+                Ok(url)
+            }
+            // within an account the copy is authorized by the destination's
+            // credentials; public blobs need none
+            _ => blob_client.url(),
+        }
+    }
+}
+
+fn is_not_found(error: &Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::HttpResponse {
+            status: StatusCode::NotFound,
+            ..
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dev::InMemoryTransport, test_util};
+    use std::sync::Arc;
+
+    const MODIFIED: &str = "Thu, 01 Jul 2021 10:44:59 GMT";
+
+    /// The source and destination accounts.
+    #[derive(Debug, Default)]
+    struct Accounts {
+        /// The source URLs of the copies into the destination.
+        copies: Mutex<Vec<String>>,
+        /// Makes copying `blob1` fail.
+        fail: Mutex<bool>,
+        /// The status of the copies into the destination.
+        copy_status: Mutex<&'static str>,
+    }
+
+    impl Accounts {
+        fn new(copy_status: &'static str) -> Arc<Self> {
+            Arc::new(Self {
+                copy_status: Mutex::new(copy_status),
+                ..Self::default()
+            })
+        }
+    }
+
+    /// Lists `blob0` and `blob1` in the source, and answers copies into the
+    /// destination and the properties of the blobs they copied.
+    fn service(accounts: Arc<Accounts>) -> InMemoryTransport {
+        InMemoryTransport::answering(move |request| {
+            if request.url().host_str() == Some("source.blob.core.windows.net") {
+                let blobs: String = (0..2)
+                    .map(|index| test_util::blob_xml(&format!("blob{index}"), "", MODIFIED))
+                    .collect();
+                return test_util::list_blobs_response(&blobs, "");
+            }
+
+            if *request.method() == azure_core::Method::Head {
+                let name = request.url().path().trim_start_matches("/container");
+                let copy = accounts
+                    .copies
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .rev()
+                    .find(|source| source.contains(&format!("{name}?")))
+                    .cloned();
+                return match copy {
+                    Some(source) => {
+                        let mut headers = test_util::blob_headers(8);
+                        headers.insert("x-ms-copy-status", *accounts.copy_status.lock().unwrap());
+                        headers.insert("x-ms-copy-source", source);
+                        test_util::response(StatusCode::Ok, headers)
+                    }
+                    None => test_util::error_response(StatusCode::NotFound, "BlobNotFound"),
+                };
+            }
+
+            let source = request
+                .headers()
+                .get_optional_str(&azure_core::headers::COPY_SOURCE)
+                .unwrap_or_default()
+                .to_owned();
+            if source.contains("blob1") && *accounts.fail.lock().unwrap() {
+                return Err(
+                    ErrorKind::http_response(StatusCode::InternalServerError, None).into_error(),
+                );
+            }
+            accounts.copies.lock().unwrap().push(source);
+            let mut headers = test_util::service_headers();
+            headers.insert("etag", test_util::ETAG);
+            headers.insert("last-modified", MODIFIED);
+            headers.insert("x-ms-version", "2020-06-12");
+            headers.insert("server", "Windows-Azure-Blob/1.0");
+            headers.insert("x-ms-copy-id", "2b2a4af4-0b1c-4c9f-8fdc-f5e5d5a6e2a1");
+            headers.insert("x-ms-copy-status", *accounts.copy_status.lock().unwrap());
+            test_util::response(StatusCode::Accepted, headers)
+        })
+    }
+
+    fn container_client(service: &InMemoryTransport, account: &str) -> ContainerClient {
+        test_util::keyed_client_builder(account, service.clone()).container_client("container")
+    }
+
+    #[tokio::test]
+    async fn test_replication_pass() -> azure_core::Result<()> {
+        let accounts = Accounts::new("success");
+        *accounts.fail.lock().unwrap() = true;
+        let service = service(accounts.clone());
+        let job = ReplicationJob::new(
+            container_client(&service, "source"),
+            container_client(&service, "destination"),
+            MemoryCheckpointStore::default(),
+            ReplicationOptions::default(),
+        );
+
+        let stats = job.run_once().await?;
+        assert_eq!((stats.listed, stats.copied, stats.bytes_copied), (2, 1, 8));
+        assert_eq!(stats.failures.len(), 1);
+        assert_eq!(stats.failures[0].blob_name, "blob1");
+        let copies = accounts.copies.lock().unwrap().clone();
+        assert!(copies[0].starts_with("https://source.blob.core.windows.net/container/blob0?"));
+        assert!(copies[0].contains("sig="));
+        let checkpoint = job.checkpoints.load().await?.unwrap();
+        assert_eq!(checkpoint.replicated_until, None, "blob1 is to be retried");
+
+        *accounts.fail.lock().unwrap() = false;
+        let stats = job.run_once().await?;
+        assert_eq!((stats.copied, stats.failures.len()), (2, 0));
+        let checkpoint = job.checkpoints.load().await?.unwrap();
+        assert_eq!(
+            checkpoint.replicated_until,
+            Some(azure_core::date::parse_rfc1123(
+                "Thu, 01 Jul 2021 10:45:00 GMT"
+            )?)
+        );
+        assert_eq!(checkpoint.pass_started, None);
+
+        // nothing was modified since
+        let stats = job.run_once().await?;
+        assert_eq!((stats.listed, stats.copied, stats.skipped), (2, 0, 2));
+
+        let json = serde_json::to_string(&checkpoint)?;
+        assert_eq!(
+            serde_json::from_str::<ReplicationCheckpoint>(&json)?,
+            checkpoint
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resumed_pass() -> azure_core::Result<()> {
+        let accounts = Accounts::new("success");
+        let service = service(accounts.clone());
+        let checkpoints = MemoryCheckpointStore::default();
+        let pass_started = azure_core::date::parse_rfc1123("Tue, 01 Jun 2021 10:45:00 GMT")?;
+        checkpoints
+            .save(&ReplicationCheckpoint {
+                pass_started: Some(pass_started),
+                ..ReplicationCheckpoint::default()
+            })
+            .await?;
+        let job = ReplicationJob::new(
+            container_client(&service, "source"),
+            container_client(&service, "destination"),
+            checkpoints,
+            ReplicationOptions::default(),
+        );
+
+        job.run_once().await?;
+        // the signatures expire a day after the listing, not after the start
+        // of the pass a month earlier
+        let copies = accounts.copies.lock().unwrap().clone();
+        let expiry = Url::parse(&copies[0])?
+            .query_pairs()
+            .find(|(name, _)| name == "se")
+            .map(|(_, value)| value.into_owned());
+        assert_eq!(expiry.as_deref(), Some("2021-07-02T10:45:00Z"));
+        let checkpoint = job.checkpoints.load().await?.unwrap();
+        assert_eq!(checkpoint.replicated_until, Some(pass_started));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pending_copies() -> azure_core::Result<()> {
+        let accounts = Accounts::new("pending");
+        let service = service(accounts.clone());
+        let job = ReplicationJob::new(
+            container_client(&service, "source"),
+            container_client(&service, "destination"),
+            MemoryCheckpointStore::default(),
+            ReplicationOptions::default(),
+        );
+
+        let stats = job.run_once().await?;
+        assert_eq!((stats.copied, stats.pending), (2, 2));
+        let checkpoint = job.checkpoints.load().await?.unwrap();
+        assert_eq!(checkpoint.replicated_until, None, "the copies are pending");
+        assert_eq!(checkpoint.pending, 0);
+
+        // the pending copies are checked, not started again
+        let stats = job.run_once().await?;
+        assert_eq!((stats.listed, stats.copied, stats.pending), (2, 0, 2));
+        assert_eq!(accounts.copies.lock().unwrap().len(), 2);
+        let checkpoint = job.checkpoints.load().await?.unwrap();
+        assert_eq!(checkpoint.replicated_until, None);
+
+        *accounts.copy_status.lock().unwrap() = "success";
+        let stats = job.run_once().await?;
+        assert_eq!((stats.copied, stats.pending), (2, 0));
+        let checkpoint = job.checkpoints.load().await?.unwrap();
+        assert!(checkpoint.replicated_until.is_some());
+        Ok(())
+    }
+}
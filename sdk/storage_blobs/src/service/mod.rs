@@ -1 +1,4 @@
+mod blob_service_properties;
 pub mod operations;
+
+pub use blob_service_properties::*;
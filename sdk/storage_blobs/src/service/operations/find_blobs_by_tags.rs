@@ -1,39 +1,45 @@
-use azure_core::{prelude::*, xml::read_xml, Response as HttpResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{prelude::*, xml::read_xml, Method, Response as HttpResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 
-use crate::prelude::BlobServiceClient;
+use crate::{
+    options::{TagCondition, Tags},
+    prelude::BlobServiceClient,
+};
 
 operation! {
     #[stream]
     FindBlobsByTags,
     client: BlobServiceClient,
-    expression: String,
+    expression: TagCondition,
+    ?container: String,
     ?next_marker: NextMarker,
     ?max_results: MaxResults
 }
 
 impl FindBlobsByTagsBuilder {
+    /// The expression sent to the service: the `@container` condition of
+    /// [`container`](Self::container), if any, and the given expression.
+    pub fn where_expression(&self) -> TagCondition {
+        match &self.container {
+            Some(container) => TagCondition::container(container).and(self.expression.clone()),
+            None => self.expression.clone(),
+        }
+    }
+
     pub fn into_stream(self) -> FindBlobsByTags {
         let make_request = move |next_marker: Option<NextMarker>| {
             let this = self.clone();
             let mut ctx = self.context.clone();
             async move {
-                let mut url = this.client.url()?;
-
-                url.query_pairs_mut().append_pair("comp", "blobs");
+                let mut request = this.client.request(Method::Get)?.comp("blobs");
                 if let Some(next_marker) = next_marker {
-                    url.query_pairs_mut()
-                        .append_pair("next", next_marker.as_str());
+                    request = request.query_pair("next", next_marker.as_str());
                 }
-                url.query_pairs_mut().append_pair("where", &this.expression);
-                let mut request = this.client.finalize_request(
-                    url,
-                    azure_core::Method::Get,
-                    azure_core::headers::Headers::new(),
-                    None,
-                )?;
+                let request = request
+                    .query_pair("where", this.where_expression().as_str())
+                    .query(this.max_results);
 
-                let response = this.client.send(&mut ctx, &mut request).await?;
+                let response = this.client.execute(&mut ctx, request).await?;
 
                 FindBlobsByTagsResponse::try_from(response).await
             }
@@ -49,7 +55,8 @@ pub struct FindBlobsByTagsResponse {
     pub blobs: Vec<Blob>,
     pub delimiter: Option<String>,
     next_marker: Option<NextMarker>,
-    pub r#where: Option<String>,
+    /// The expression the service evaluated.
+    pub r#where: Option<TagCondition>,
     pub common: CommonStorageResponseHeaders,
 }
 
@@ -81,7 +88,7 @@ impl FindBlobsByTagsResponse {
 struct ListBlobsByTagsBody {
     pub delimiter: Option<String>,
     pub next_marker: Option<String>,
-    pub r#where: Option<String>,
+    pub r#where: Option<TagCondition>,
     pub blobs: Blobs,
 }
 
@@ -96,7 +103,20 @@ struct Blobs {
 pub struct Blob {
     pub name: String,
     pub container_name: String,
-    pub tag_value: String,
+    /// The value of the tag of a single tag expression, returned by service
+    /// versions before 2020-04-08.
+    #[serde(default)]
+    pub tag_value: Option<String>,
+    /// The tags of the blob matched by the expression.
+    #[serde(default)]
+    pub tags: Option<Tags>,
+}
+
+impl Blob {
+    /// The value of the tag `key` of the blob, if it was returned.
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags.as_ref()?.get(key)
+    }
 }
 
 #[cfg(test)]
@@ -121,5 +141,53 @@ mod tests {
         let body: ListBlobsByTagsBody = read_xml(BODY).unwrap();
         assert_eq!(body.blobs.blobs.len(), 1);
         assert_eq!(body.blobs.blobs[0].name, "test1");
+        assert_eq!(body.blobs.blobs[0].tag_value.as_deref(), Some("value1"));
+        assert_eq!(body.r#where.unwrap().as_str(), "tag1='value1'");
+    }
+
+    #[test]
+    fn parse_body_with_tags() {
+        const BODY: &[u8] = b"<?xml version=\"1.0\" encoding=\"utf-8\"?>
+        <EnumerationResults ServiceEndpoint=\"https://account.blob.core.windows.net/\">
+          <Where>@container = 'photos' AND \"status\" = 'done'</Where>
+          <Blobs>
+            <Blob>
+              <Name>a.jpg</Name>
+              <ContainerName>photos</ContainerName>
+              <Tags>
+                <TagSet>
+                  <Tag><Key>status</Key><Value>done</Value></Tag>
+                </TagSet>
+              </Tags>
+            </Blob>
+          </Blobs>
+          <NextMarker/>
+        </EnumerationResults>";
+
+        let body: ListBlobsByTagsBody = read_xml(BODY).unwrap();
+        let blob = &body.blobs.blobs[0];
+        assert_eq!(blob.tag_value, None);
+        assert_eq!(blob.tag("status"), Some("done"));
+        assert_eq!(blob.tag("owner"), None);
+        assert_eq!(
+            body.r#where,
+            Some(TagCondition::container("photos").and(TagCondition::equals("status", "done")))
+        );
+    }
+
+    #[test]
+    fn where_expression() {
+        let client = crate::prelude::ClientBuilder::new(
+            "account",
+            azure_storage::StorageCredentials::Anonymous,
+        )
+        .blob_service_client();
+        let builder = client.find_blobs_by_tags(TagCondition::equals("status", "done"));
+        assert_eq!(builder.where_expression().as_str(), r#""status" = 'done'"#);
+        let builder = builder.container("photos");
+        assert_eq!(
+            builder.where_expression().as_str(),
+            r#"@container = 'photos' AND "status" = 'done'"#
+        );
     }
 }
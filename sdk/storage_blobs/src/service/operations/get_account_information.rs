@@ -1,7 +1,7 @@
 use crate::prelude::BlobServiceClient;
-use azure_core::headers::{account_kind_from_headers, sku_name_from_headers, Headers};
+use azure_core::headers::{Headers, ACCOUNT_KIND, SKU_NAME};
 use azure_core::Method;
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_storage::{headers::CommonStorageResponseHeaders, AccountKind, SkuName, StorageClient};
 
 operation! {
     GetAccountInformation,
@@ -11,16 +11,13 @@ operation! {
 impl GetAccountInformationBuilder {
     pub fn into_future(mut self) -> GetAccountInformation {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self
+                .client
+                .request(Method::Get)?
+                .restype("account")
+                .comp("properties");
 
-            for (k, v) in [("restype", "account"), ("comp", "properties")].iter() {
-                url.query_pairs_mut().append_pair(k, v);
-            }
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             GetAccountInformationResponse::try_from(response.headers())
         })
@@ -30,15 +27,27 @@ impl GetAccountInformationBuilder {
 #[derive(Debug, Clone)]
 pub struct GetAccountInformationResponse {
     pub common: CommonStorageResponseHeaders,
-    pub sku_name: String,
-    pub account_kind: String,
+    pub sku_name: SkuName,
+    pub account_kind: AccountKind,
 }
 
 impl GetAccountInformationResponse {
+    /// Whether blobs can be moved between the hot, cool and archive access
+    /// tiers, e.g. with [`BlobClient::set_blob_tier`](crate::prelude::BlobClient::set_blob_tier).
+    /// Premium accounts have no access tiers.
+    pub fn supports_tiering(&self) -> bool {
+        self.account_kind.supports_tiering() && !self.sku_name.is_premium()
+    }
+
+    /// Whether the account is backed by SSDs.
+    pub fn is_premium(&self) -> bool {
+        self.sku_name.is_premium()
+    }
+
     pub(crate) fn try_from(headers: &Headers) -> azure_core::Result<GetAccountInformationResponse> {
         let common = CommonStorageResponseHeaders::try_from(headers)?;
-        let sku_name = sku_name_from_headers(headers)?;
-        let account_kind = account_kind_from_headers(headers)?;
+        let sku_name = headers.get_as(&SKU_NAME)?;
+        let account_kind = headers.get_as(&ACCOUNT_KIND)?;
 
         Ok(GetAccountInformationResponse {
             common,
@@ -47,3 +56,92 @@ impl GetAccountInformationResponse {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(
+        sku_name: &'static str,
+        account_kind: &'static str,
+    ) -> GetAccountInformationResponse {
+        let mut headers = Headers::new();
+        headers.insert(
+            azure_core::headers::REQUEST_ID,
+            "b0b5a7de-601e-0061-2a8f-7a1d7e000000",
+        );
+        headers.insert(azure_core::headers::DATE, "Sun, 06 Nov 1994 08:49:37 GMT");
+        headers.insert(azure_core::headers::VERSION, "2019-12-12");
+        headers.insert(azure_core::headers::SERVER, "Windows-Azure-Blob/1.0");
+        headers.insert(SKU_NAME, sku_name);
+        headers.insert(ACCOUNT_KIND, account_kind);
+        GetAccountInformationResponse::try_from(&headers).unwrap()
+    }
+
+    #[test]
+    fn test_account_capabilities() {
+        let standard = response("Standard_RAGRS", "StorageV2");
+        assert_eq!(standard.sku_name, SkuName::StandardRagrs);
+        assert_eq!(standard.account_kind, AccountKind::StorageV2);
+        assert!(standard.supports_tiering());
+        assert!(!standard.is_premium());
+
+        // premium page blobs have performance tiers instead
+        let premium = response("Premium_LRS", "StorageV2");
+        assert!(!premium.supports_tiering());
+        assert!(premium.is_premium());
+
+        assert!(!response("Premium_LRS", "BlockBlobStorage").supports_tiering());
+        assert!(!response("Standard_LRS", "Storage").supports_tiering());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[derive(Debug, Default)]
+    struct Sink(std::sync::Mutex<Vec<azure_storage::metrics::RequestMetrics>>);
+
+    #[cfg(feature = "metrics")]
+    impl azure_storage::metrics::MetricsSink for Sink {
+        fn record(&self, metrics: &azure_storage::metrics::RequestMetrics) {
+            self.0.lock().unwrap().push(metrics.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_stats() -> azure_core::Result<()> {
+        use crate::{dev::InMemoryTransport, test_util};
+        use azure_core::{FixedRetryOptions, RetryOptions, StatusCode};
+        use std::time::Duration;
+
+        // the service is busy once
+        let busy = std::sync::atomic::AtomicBool::new(true);
+        let service = InMemoryTransport::answering(move |_| {
+            if busy.swap(false, std::sync::atomic::Ordering::Relaxed) {
+                return test_util::error_response(StatusCode::ServiceUnavailable, "ServerBusy");
+            }
+            let mut headers = test_util::service_headers();
+            headers.insert(azure_core::headers::VERSION, "2019-12-12");
+            headers.insert(azure_core::headers::SERVER, "Windows-Azure-Blob/1.0");
+            headers.insert(SKU_NAME, "Standard_LRS");
+            headers.insert(ACCOUNT_KIND, "StorageV2");
+            test_util::response(StatusCode::Ok, headers)
+        });
+        let builder = service.client_builder().retry(RetryOptions::fixed(
+            FixedRetryOptions::default().delay(Duration::from_millis(10)),
+        ));
+        #[cfg(feature = "metrics")]
+        let sink = std::sync::Arc::new(Sink::default());
+        #[cfg(feature = "metrics")]
+        let builder = builder.metrics(sink.clone());
+
+        let response = builder
+            .blob_service_client()
+            .get_account_information()
+            .await?;
+        assert_eq!(response.common.attempts, 2);
+        assert!(response.common.elapsed >= Duration::from_millis(10));
+        // the metrics count the same attempts
+        #[cfg(feature = "metrics")]
+        assert_eq!(sink.0.lock().unwrap()[0].retries, 1);
+        Ok(())
+    }
+}
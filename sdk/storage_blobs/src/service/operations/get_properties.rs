@@ -0,0 +1,94 @@
+use crate::{prelude::BlobServiceClient, service::BlobServiceProperties};
+use azure_core::{xml::read_xml, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
+use std::convert::TryInto;
+
+operation! {
+    GetBlobServiceProperties,
+    client: BlobServiceClient,
+}
+
+impl GetBlobServicePropertiesBuilder {
+    pub fn into_future(mut self) -> GetBlobServiceProperties {
+        Box::pin(async move {
+            let request = self
+                .client
+                .request(Method::Get)?
+                .restype("service")
+                .comp("properties");
+
+            let response = self.client.execute(&mut self.context, request).await?;
+
+            GetBlobServicePropertiesResponse::try_from(response).await
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GetBlobServicePropertiesResponse {
+    pub common: CommonStorageResponseHeaders,
+    pub blob_service_properties: BlobServiceProperties,
+}
+
+impl GetBlobServicePropertiesResponse {
+    async fn try_from(response: AzureResponse) -> azure_core::Result<Self> {
+        let (_, headers, body) = response.deconstruct();
+        let body = body.collect().await?;
+
+        let blob_service_properties: BlobServiceProperties = read_xml(&body)?;
+
+        Ok(Self {
+            common: (&headers).try_into()?,
+            blob_service_properties,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_service_properties_deserialize() -> azure_core::Result<()> {
+        const XML: &[u8] = b"<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<StorageServiceProperties>
+    <Logging>
+        <Version>1.0</Version>
+        <Read>false</Read>
+        <Write>true</Write>
+        <Delete>true</Delete>
+        <RetentionPolicy><Enabled>true</Enabled><Days>7</Days></RetentionPolicy>
+    </Logging>
+    <HourMetrics>
+        <Version>1.0</Version>
+        <Enabled>true</Enabled>
+        <IncludeAPIs>false</IncludeAPIs>
+        <RetentionPolicy><Enabled>false</Enabled></RetentionPolicy>
+    </HourMetrics>
+    <Cors />
+    <DefaultServiceVersion>2020-10-02</DefaultServiceVersion>
+    <DeleteRetentionPolicy><Enabled>true</Enabled><Days>14</Days></DeleteRetentionPolicy>
+    <StaticWebsite>
+        <Enabled>true</Enabled>
+        <IndexDocument>index.html</IndexDocument>
+        <ErrorDocument404Path>404.html</ErrorDocument404Path>
+    </StaticWebsite>
+</StorageServiceProperties>";
+
+        let properties: BlobServiceProperties = read_xml(XML)?;
+        assert!(properties.logging.unwrap().write);
+        assert!(properties.minute_metrics.is_none());
+        assert_eq!(
+            properties.default_service_version.as_deref(),
+            Some("2020-10-02")
+        );
+        assert_eq!(properties.delete_retention_policy.unwrap().days, Some(14));
+        let static_website = properties.static_website.unwrap();
+        assert_eq!(
+            static_website.error_document404_path.as_deref(),
+            Some("404.html")
+        );
+        assert!(static_website.default_index_document_path.is_none());
+        Ok(())
+    }
+}
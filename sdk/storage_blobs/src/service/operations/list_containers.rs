@@ -1,12 +1,14 @@
-use crate::clients::BlobServiceClient;
+use crate::clients::{BlobServiceClient, ContainerClient};
 use crate::container::Container;
 use azure_core::{
     error::{Error, ErrorKind, ResultExt},
-    headers::Headers,
     prelude::*,
     Method, Pageable, Response,
 };
-use azure_storage::parsing_xml::{cast_optional, traverse};
+use azure_storage::{
+    parsing_xml::{cast_optional, traverse},
+    StorageClient,
+};
 use xml::Element;
 
 #[derive(Debug, Clone)]
@@ -44,14 +46,14 @@ impl ListContainersBuilder {
             let this = self.clone();
             let mut ctx = self.context.clone();
             async move {
-                let mut url = this.client.url()?;
-
-                url.query_pairs_mut().append_pair("comp", "list");
-
-                this.prefix.append_to_url_query(&mut url);
+                let mut request = this
+                    .client
+                    .request(Method::Get)?
+                    .comp("list")
+                    .query(&this.prefix);
 
                 if let Some(next_marker) = continuation {
-                    next_marker.append_to_url_query(&mut url);
+                    request = request.query(next_marker);
                 }
 
                 if let Some(include) = match (this.include_metadata, this.include_deleted) {
@@ -60,17 +62,13 @@ impl ListContainersBuilder {
                     (false, true) => Some("deleted"),
                     (false, false) => None,
                 } {
-                    url.query_pairs_mut().append_pair("include", include);
+                    request = request.query_pair("include", include);
                 }
-                this.max_results.append_to_url_query(&mut url);
+                let request = request.query(this.max_results);
 
-                let mut request =
-                    this.client
-                        .finalize_request(url, Method::Get, Headers::new(), None)?;
+                let response = this.client.execute(&mut ctx, request).await?;
 
-                let response = this.client.send(&mut ctx, &mut request).await?;
-
-                ListContainersResponse::try_from(response).await
+                ListContainersResponse::try_from(this.client, response).await
             }
         };
 
@@ -82,10 +80,20 @@ impl ListContainersBuilder {
 pub struct ListContainersResponse {
     pub containers: Vec<Container>,
     pub next_marker: Option<String>,
+    client: BlobServiceClient,
 }
 
 impl ListContainersResponse {
-    async fn try_from(response: Response) -> azure_core::Result<Self> {
+    /// Clients for the listed containers, sharing the pipeline, and so the
+    /// credentials and options, of the client which listed them.
+    pub fn into_clients(self) -> impl Iterator<Item = ContainerClient> {
+        let client = self.client;
+        self.containers
+            .into_iter()
+            .map(move |container| client.container_client(container.name))
+    }
+
+    async fn try_from(client: BlobServiceClient, response: Response) -> azure_core::Result<Self> {
         let body = response.into_body().collect_string().await?;
         let elem: Element = body.parse().map_kind(ErrorKind::Other)?;
 
@@ -104,6 +112,7 @@ impl ListContainersResponse {
         Ok(Self {
             containers,
             next_marker,
+            client,
         })
     }
 }
@@ -114,3 +123,48 @@ impl Continuable for ListContainersResponse {
         self.next_marker.clone().map(NextMarker::from)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::{headers::Headers, StatusCode};
+    use azure_storage::StorageCredentials;
+
+    #[tokio::test]
+    async fn test_into_clients() -> azure_core::Result<()> {
+        const XML: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<EnumerationResults ServiceEndpoint=\"https://account.blob.core.windows.net/\">
+    <Containers>
+        <Container>
+            <Name>first</Name>
+            <Properties>
+                <Last-Modified>Thu, 01 Jul 2021 10:44:59 GMT</Last-Modified>
+                <Etag>\"0x8D93C7D4629C227\"</Etag>
+                <LeaseStatus>unlocked</LeaseStatus>
+                <LeaseState>available</LeaseState>
+                <HasImmutabilityPolicy>false</HasImmutabilityPolicy>
+                <HasLegalHold>false</HasLegalHold>
+            </Properties>
+        </Container>
+    </Containers>
+    <NextMarker />
+</EnumerationResults>";
+        let response = Response::new(
+            StatusCode::Ok,
+            Headers::new(),
+            Box::pin(futures::stream::once(async {
+                Ok(bytes::Bytes::from_static(XML.as_bytes()))
+            })),
+        );
+        let client = BlobServiceClient::new("account", StorageCredentials::Anonymous);
+
+        let response = ListContainersResponse::try_from(client, response).await?;
+        let clients: Vec<_> = response.into_clients().collect();
+        assert_eq!(clients.len(), 1);
+        assert_eq!(
+            clients[0].url()?.as_str(),
+            "https://account.blob.core.windows.net/first"
+        );
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
 mod find_blobs_by_tags;
 mod get_account_information;
+mod get_properties;
 mod list_containers;
+mod submit_batch;
 
 pub use find_blobs_by_tags::*;
 pub use get_account_information::*;
+pub use get_properties::*;
 pub use list_containers::*;
+pub use submit_batch::*;
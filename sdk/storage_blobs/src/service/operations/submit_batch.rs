@@ -0,0 +1,220 @@
+use crate::{
+    batch::{parse_batch_response, BatchBody, BatchSubResponse},
+    prelude::BlobServiceClient,
+};
+use azure_core::{date, headers, Body, Method, Request, Response as HttpResponse};
+use azure_storage::{
+    clients::ServiceType, headers::CommonStorageResponseHeaders, StorageClient, StorageCredentials,
+};
+use time::OffsetDateTime;
+
+operation! {
+    SubmitBatch,
+    client: BlobServiceClient,
+    batch: BatchBody,
+}
+
+impl SubmitBatchBuilder {
+    pub fn into_future(mut self) -> SubmitBatch {
+        Box::pin(async move {
+            // the service checks each sub-request as if it was sent on its
+            // own, but only the batch request carries a SAS
+            let now = date::to_rfc1123(&OffsetDateTime::now_utc());
+            for request in self.batch.requests_mut() {
+                complete_headers(request, &now, self.client.api_version());
+                let authorized = request
+                    .headers()
+                    .get_optional_str(&headers::AUTHORIZATION)
+                    .is_some();
+                if !authorized
+                    && !matches!(self.client.credentials(), StorageCredentials::SASToken(_))
+                {
+                    azure_storage::authorize_request(
+                        request,
+                        self.client.credentials(),
+                        ServiceType::Blob,
+                    )
+                    .await?;
+                }
+            }
+
+            let request = self
+                .client
+                .request(Method::Post)?
+                .comp("batch")
+                .header_value(headers::CONTENT_TYPE, self.batch.content_type())
+                .body(self.batch.to_bytes());
+
+            let response = self.client.execute(&mut self.context, request).await?;
+            SubmitBatchResponse::try_from(response).await
+        })
+    }
+}
+
+/// Adds the headers the service expects of every sub-request to one lacking
+/// them.
+fn complete_headers(request: &mut Request, date: &str, version: &str) {
+    let len = match request.body() {
+        Body::Bytes(bytes) => bytes.len(),
+        Body::SeekableStream(stream) => stream.len(),
+    };
+    for (name, value) in [
+        (headers::MS_DATE, date.to_owned()),
+        (headers::VERSION, version.to_owned()),
+        (headers::CONTENT_LENGTH, len.to_string()),
+    ] {
+        if request.headers().get_optional_str(&name).is_none() {
+            request.insert_header(name, value);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubmitBatchResponse {
+    pub common: CommonStorageResponseHeaders,
+    /// The responses to the sub-requests, which succeed or fail on their own.
+    pub responses: Vec<BatchSubResponse>,
+}
+
+impl SubmitBatchResponse {
+    /// The response to the sub-request with the given `Content-ID`.
+    pub fn response(&self, content_id: u32) -> Option<&BatchSubResponse> {
+        self.responses
+            .iter()
+            .find(|response| response.content_id == Some(content_id))
+    }
+
+    async fn try_from(response: HttpResponse) -> azure_core::Result<Self> {
+        let (_status_code, headers, body) = response.deconstruct();
+        let body = body.collect().await?;
+        let content_type = headers.get_str(&headers::CONTENT_TYPE)?;
+
+        Ok(Self {
+            responses: parse_batch_response(content_type, &body)?,
+            common: CommonStorageResponseHeaders::try_from(&headers)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{batch::BatchBody, dev::InMemoryTransport, prelude::*, test_util};
+    use azure_core::StatusCode;
+    use azure_storage::StorageCredentials;
+
+    /// Answers every batch with the deletion of the first blob succeeding and
+    /// the tier of the second one failing.
+    fn service() -> InMemoryTransport {
+        InMemoryTransport::answering(|_| {
+            let mut headers = test_util::service_headers();
+            headers.insert(headers::VERSION, "2021-08-06");
+            headers.insert(headers::SERVER, "Windows-Azure-Blob/1.0");
+            headers.insert(
+                headers::CONTENT_TYPE,
+                "multipart/mixed; boundary=batchresponse_1",
+            );
+            Ok(test_util::response_with_body(
+                StatusCode::Accepted,
+                headers,
+                "--batchresponse_1\r\n\
+                 Content-Type: application/http\r\n\
+                 Content-ID: 0\r\n\
+                 \r\n\
+                 HTTP/1.1 202 Accepted\r\n\
+                 x-ms-delete-type-permanent: true\r\n\
+                 \r\n\
+                 --batchresponse_1\r\n\
+                 Content-Type: application/http\r\n\
+                 Content-ID: 1\r\n\
+                 \r\n\
+                 HTTP/1.1 404 The specified blob does not exist.\r\n\
+                 x-ms-error-code: BlobNotFound\r\n\
+                 \r\n\
+                 --batchresponse_1--\r\n",
+            ))
+        })
+    }
+
+    fn batch(client: &BlobServiceClient) -> azure_core::Result<BatchBody> {
+        let container_client = client.container_client("container");
+        let mut batch = BatchBody::with_boundary("batch_1");
+        batch.add_delete(&container_client.blob_client("a"))?;
+        batch.add_set_tier(&container_client.blob_client("b"), AccessTier::Cool)?;
+        Ok(batch)
+    }
+
+    /// The header lines of the sub-requests in the body of a batch request.
+    fn sub_request_headers(request: &Request, name: &str) -> Vec<String> {
+        let body = test_util::body_bytes(request);
+        std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .filter(|line| line.starts_with(&format!("{name}: ")))
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch() -> azure_core::Result<()> {
+        let service = service();
+        let client =
+            test_util::keyed_client_builder("account", service.clone()).blob_service_client();
+
+        let response = client.submit_batch(batch(&client)?).await?;
+        assert!(response.response(0).unwrap().is_success());
+        assert_eq!(
+            response.response(1).unwrap().error_code(),
+            Some("BlobNotFound")
+        );
+
+        let request = service.requests().pop().unwrap();
+        assert_eq!(
+            test_util::query_param(&request, "comp").as_deref(),
+            Some("batch")
+        );
+        assert!(request
+            .headers()
+            .get_str(&headers::AUTHORIZATION)?
+            .starts_with("SharedKey account:"));
+        // each sub-request is signed on its own
+        let authorizations = sub_request_headers(&request, "authorization");
+        assert_eq!(authorizations.len(), 2);
+        assert!(authorizations
+            .iter()
+            .all(|line| line.starts_with("authorization: SharedKey account:")));
+        assert_ne!(authorizations[0], authorizations[1]);
+        assert_eq!(sub_request_headers(&request, "x-ms-date").len(), 2);
+        assert_eq!(
+            sub_request_headers(&request, "x-ms-version"),
+            vec![format!("x-ms-version: {}", client.api_version()); 2]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_submit_batch_with_sas() -> azure_core::Result<()> {
+        let service = service();
+        let client = ClientBuilder::new(
+            "account",
+            StorageCredentials::sas_token("sv=2021-08-06&sig=signature")?,
+        )
+        .transport(service.clone())
+        .retry(azure_core::RetryOptions::none())
+        .blob_service_client();
+
+        client.submit_batch(batch(&client)?).await?;
+
+        // only the batch request carries the SAS
+        let request = service.requests().pop().unwrap();
+        assert_eq!(
+            test_util::query_param(&request, "sig").as_deref(),
+            Some("signature")
+        );
+        assert!(sub_request_headers(&request, "authorization").is_empty());
+        let body = test_util::body_bytes(&request);
+        assert!(!std::str::from_utf8(&body).unwrap().contains("sig="));
+        assert_eq!(sub_request_headers(&request, "x-ms-date").len(), 2);
+        Ok(())
+    }
+}
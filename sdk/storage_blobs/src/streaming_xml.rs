@@ -0,0 +1,197 @@
+use azure_core::error::{Error, ErrorKind};
+use quick_xml::{events::Event, Reader};
+
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// An element split out of an XML document by [`XmlItemSplitter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct XmlItem {
+    pub name: String,
+    pub xml: Vec<u8>,
+}
+
+/// Splits the elements at a given depth out of an XML document as its bytes
+/// arrive, so that list responses can be decoded entry by entry instead of
+/// after the whole body was read.
+///
+/// The rest of the document, the "envelope", is kept and returned by
+/// [`XmlItemSplitter::finish`] with the items removed.
+#[derive(Debug)]
+pub(crate) struct XmlItemSplitter {
+    /// The depth of the items, where the root element is at depth 1.
+    item_depth: usize,
+    /// The bytes not yet split.
+    buffer: Vec<u8>,
+    /// The number of elements open at the start of `buffer`.
+    depth: usize,
+    envelope: Vec<u8>,
+}
+
+impl XmlItemSplitter {
+    pub fn new(item_depth: usize) -> Self {
+        Self {
+            item_depth,
+            buffer: Vec::new(),
+            depth: 0,
+            envelope: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// The next complete item, or `None` until more bytes are pushed.
+    pub fn next_item(&mut self) -> azure_core::Result<Option<XmlItem>> {
+        // The reader skips a byte order mark without counting it in its
+        // positions, so it is moved to the envelope first.
+        if self.envelope.is_empty() {
+            if UTF8_BOM.starts_with(&self.buffer) {
+                return Ok(None);
+            }
+            if self.buffer.starts_with(&UTF8_BOM) {
+                self.envelope.extend(self.buffer.drain(..UTF8_BOM.len()));
+            }
+        }
+
+        let mut reader = Reader::from_reader(self.buffer.as_slice());
+        // The buffer starts inside elements opened in bytes already split.
+        reader.check_end_names(false);
+
+        let mut depth = self.depth;
+        // The end of the last tag outside of an item, and the depth there.
+        let mut checkpoint = (0, depth);
+        let mut item_start = None;
+        loop {
+            let position = reader.buffer_position();
+            let event = match reader.read_event() {
+                Ok(Event::Eof) | Err(quick_xml::Error::UnexpectedEof(_)) => break,
+                Ok(event) => event,
+                Err(error) => {
+                    return Err(Error::full(
+                        ErrorKind::DataConversion,
+                        error,
+                        "failed to split the xml response",
+                    ))
+                }
+            };
+            let (name, is_start, is_end) = match &event {
+                Event::Start(start) => (start.name(), true, false),
+                Event::End(end) => (end.name(), false, true),
+                Event::Empty(empty) => (empty.name(), true, true),
+                _ => continue,
+            };
+            // A tag cut off by the end of the buffer is read as a complete one.
+            if !self.buffer[..reader.buffer_position()].ends_with(b">") {
+                break;
+            }
+            if is_start {
+                depth += 1;
+                if depth == self.item_depth {
+                    item_start = Some((
+                        position,
+                        String::from_utf8_lossy(name.as_ref()).into_owned(),
+                    ));
+                }
+            }
+            if is_end && depth == self.item_depth {
+                if let Some((start, name)) = item_start.take() {
+                    let end = reader.buffer_position();
+                    let item = XmlItem {
+                        name,
+                        xml: self.buffer[start..end].to_vec(),
+                    };
+                    self.envelope.extend_from_slice(&self.buffer[..start]);
+                    self.buffer.drain(..end);
+                    self.depth = depth - 1;
+                    return Ok(Some(item));
+                }
+            }
+            if is_end {
+                depth = depth.saturating_sub(1);
+            }
+            if item_start.is_none() {
+                checkpoint = (reader.buffer_position(), depth);
+            }
+        }
+
+        let (end, depth) = checkpoint;
+        self.envelope.extend(self.buffer.drain(..end));
+        self.depth = depth;
+        Ok(None)
+    }
+
+    /// The document without its items, once all bytes were pushed and all
+    /// items taken.
+    pub fn finish(mut self) -> azure_core::Result<Vec<u8>> {
+        if let Some(item) = self.next_item()? {
+            return Err(Error::with_message(ErrorKind::Other, || {
+                format!(
+                    "the {} element was not taken from the xml response",
+                    item.name
+                )
+            }));
+        }
+        if self.depth != 0 {
+            return Err(Error::message(
+                ErrorKind::DataConversion,
+                "the xml response ended inside an element",
+            ));
+        }
+        self.envelope.append(&mut self.buffer);
+        Ok(self.envelope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_items_across_chunks() {
+        const XML: &[u8] = b"\xEF\xBB\xBF<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<EnumerationResults ContainerName=\"c\">
+  <Prefix>a/</Prefix>
+  <Blobs>
+    <Blob><Name>a/1</Name><Metadata><Blob>nested</Blob></Metadata></Blob>
+    <BlobPrefix><Name>a/b/</Name></BlobPrefix>
+    <Blob><Name>a/&lt;2&gt;</Name></Blob>
+  </Blobs>
+  <NextMarker>marker</NextMarker>
+</EnumerationResults>";
+
+        for chunk_size in [1, 7, XML.len()] {
+            let mut splitter = XmlItemSplitter::new(3);
+            let mut items = Vec::new();
+            for chunk in XML.chunks(chunk_size) {
+                splitter.push(chunk);
+                while let Some(item) = splitter.next_item().unwrap() {
+                    items.push(item);
+                }
+            }
+            let envelope = String::from_utf8(splitter.finish().unwrap()).unwrap();
+
+            let names = items
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["Blob", "BlobPrefix", "Blob"]);
+            assert_eq!(
+                items[0].xml,
+                b"<Blob><Name>a/1</Name><Metadata><Blob>nested</Blob></Metadata></Blob>"
+            );
+            assert_eq!(items[2].xml, b"<Blob><Name>a/&lt;2&gt;</Name></Blob>");
+            assert!(envelope.contains("<Prefix>a/</Prefix>"));
+            assert!(envelope.contains("<NextMarker>marker</NextMarker>"));
+            assert!(!envelope.contains("<Name>"));
+        }
+    }
+
+    #[test]
+    fn test_truncated_response() {
+        let mut splitter = XmlItemSplitter::new(3);
+        splitter.push(b"<EnumerationResults><Blobs><Blob><Name>a");
+        assert!(splitter.next_item().unwrap().is_none());
+        assert!(splitter.finish().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Helpers for the unit tests: clients of an [`InMemoryTransport`] and the
+//! answers of the service.
+
+use crate::{dev::InMemoryTransport, prelude::*};
+use azure_core::{
+    headers::{self, Headers},
+    Request, Response, StatusCode, TransportOptions,
+};
+use azure_storage::StorageCredentials;
+use bytes::Bytes;
+
+/// The date of every answer of the fake services.
+pub(crate) const DATE: &str = "Thu, 01 Jul 2021 10:45:00 GMT";
+/// The last modification time of the blobs of the fake services.
+pub(crate) const LAST_MODIFIED: &str = "Thu, 01 Jul 2021 10:44:59 GMT";
+pub(crate) const ETAG: &str = "\"0x8D93C7D4629C227\"";
+pub(crate) const REQUEST_ID: &str = "831e1650-001e-001b-66ab-eeb76e000000";
+
+impl InMemoryTransport {
+    /// A builder of anonymous clients of `account`, sending their requests to
+    /// the transport without retrying them.
+    pub(crate) fn client_builder(&self) -> ClientBuilder {
+        client_builder(self.clone())
+    }
+}
+
+/// A builder of anonymous clients of `account`, sending their requests to
+/// `transport` without retrying them.
+pub(crate) fn client_builder(transport: impl Into<TransportOptions>) -> ClientBuilder {
+    ClientBuilder::new("account", StorageCredentials::anonymous())
+        .transport(transport)
+        .retry(azure_core::RetryOptions::none())
+}
+
+/// A builder of clients of `account` signing with a shared key, sending their
+/// requests to `transport` without retrying them.
+pub(crate) fn keyed_client_builder(
+    account: &str,
+    transport: impl Into<TransportOptions>,
+) -> ClientBuilder {
+    ClientBuilder::new(
+        account,
+        StorageCredentials::access_key(account, base64::encode("key")),
+    )
+    .transport(transport)
+    .retry(azure_core::RetryOptions::none())
+}
+
+/// The headers of every answer: the date and the request id.
+pub(crate) fn service_headers() -> Headers {
+    let mut headers = Headers::new();
+    headers.insert(headers::DATE, DATE);
+    headers.insert(headers::REQUEST_ID, REQUEST_ID);
+    headers
+}
+
+/// The service headers and the headers of a new version of a blob or
+/// container.
+pub(crate) fn version_headers() -> Headers {
+    let mut headers = service_headers();
+    headers.insert(headers::ETAG, ETAG);
+    headers.insert(headers::LAST_MODIFIED, LAST_MODIFIED);
+    headers
+}
+
+/// The version headers and the properties of a block blob of `len` bytes,
+/// as Get Blob Properties returns them.
+pub(crate) fn blob_headers(len: u64) -> Headers {
+    let mut headers = version_headers();
+    headers.insert(headers::CREATION_TIME, LAST_MODIFIED);
+    headers.insert(headers::BLOB_TYPE, "BlockBlob");
+    headers.insert(headers::SERVER_ENCRYPTED, "true");
+    headers.insert(headers::CONTENT_LENGTH, len.to_string());
+    headers
+}
+
+/// An answer with an empty body.
+pub(crate) fn response(status: StatusCode, headers: Headers) -> azure_core::Result<Response> {
+    Ok(response_with_body(status, headers, Bytes::new()))
+}
+
+pub(crate) fn response_with_body(
+    status: StatusCode,
+    headers: Headers,
+    body: impl Into<Bytes>,
+) -> Response {
+    let body = body.into();
+    Response::new(
+        status,
+        headers,
+        Box::pin(futures::stream::once(async move { Ok(body) })),
+    )
+}
+
+/// An error answer with the service headers and `error_code`.
+pub(crate) fn error_response(
+    status: StatusCode,
+    error_code: &'static str,
+) -> azure_core::Result<Response> {
+    let mut headers = service_headers();
+    headers.insert(headers::ERROR_CODE, error_code);
+    response(status, headers)
+}
+
+/// The listing entry of a block blob of 8 bytes, with `extra` elements before
+/// its properties.
+pub(crate) fn blob_xml(name: &str, extra: &str, last_modified: &str) -> String {
+    format!(
+        "<Blob><Name>{name}</Name>{extra}<Properties>\
+         <Creation-Time>{last_modified}</Creation-Time>\
+         <Last-Modified>{last_modified}</Last-Modified>\
+         <Etag>0x8D93C7D4629C227</Etag><Content-Length>8</Content-Length>\
+         <Content-Type>text/plain</Content-Type><BlobType>BlockBlob</BlobType>\
+         </Properties></Blob>"
+    )
+}
+
+/// A page of List Blobs listing `blobs`, the concatenated entries, and
+/// continued at `next_marker` unless it is empty.
+pub(crate) fn list_blobs_response(blobs: &str, next_marker: &str) -> azure_core::Result<Response> {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+         <EnumerationResults><Blobs>{blobs}</Blobs><NextMarker>{next_marker}</NextMarker>\
+         </EnumerationResults>"
+    );
+    Ok(response_with_body(StatusCode::Ok, service_headers(), body))
+}
+
+/// The value of the `name` query parameter of a request.
+pub(crate) fn query_param(request: &Request, name: &str) -> Option<String> {
+    request
+        .url()
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// The body of a request made of bytes.
+pub(crate) fn body_bytes(request: &Request) -> Bytes {
+    match request.body() {
+        azure_core::Body::Bytes(bytes) => bytes.clone(),
+        azure_core::Body::SeekableStream(_) => panic!("the body of the request is a stream"),
+    }
+}
@@ -0,0 +1,266 @@
+//! Per-chunk processing of the data moved by the high-level transfers, e.g.
+//! to encrypt, hash or inspect it without reimplementing them.
+//!
+//! Transforms are registered as layers of a [`ChunkTransforms`] with
+//! [`UploadFileBuilder::transforms`](crate::blob::operations::UploadFileBuilder::transforms),
+//! [`UploadStreamBuilder::transforms`](crate::blob::operations::UploadStreamBuilder::transforms)
+//! and
+//! [`DownloadFileBuilder::transforms`](crate::blob::operations::DownloadFileBuilder::transforms).
+//! Uploads run the layers in the order they were added, after the optional
+//! gzip compression of the chunk, and downloads run them in reverse, so that
+//! the same layers undo on download what they did on upload.
+//!
+//! On upload a chunk is a block, or the whole content of a transfer made of a
+//! single request. On download it is a range of the blob, whose bounds have no
+//! relation to the blocks it was uploaded in, and the outputs of the ranges
+//! are written one after the other, in order. So only transforms whose output
+//! for a byte depends only on that byte and its offset, e.g. a stream cipher
+//! in counter mode, undo on download what they did on upload, and only
+//! without gzip compression, which changes the offsets. Other transforms,
+//! e.g. compression or authenticated encryption, can only be used on upload,
+//! and their output must then be read back by other means.
+//!
+//! A chunk sent again after being throttled is transformed again, so the
+//! output of a transform should only depend on the chunk and its position.
+//! Transforms run on the task driving the transfer: blocking in them stalls
+//! it, so rate limits belong in the pipeline rather than here.
+
+use bytes::Bytes;
+use std::{fmt::Debug, sync::Arc};
+
+/// The position of an uploaded chunk in its transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkInfo {
+    /// The index of the chunk, which is the index of its block.
+    pub index: u64,
+    /// The offset of the chunk in the source of the upload, before it is
+    /// transformed. The blocks of gzip compressed uploads are cut from the
+    /// compressed source, so their offset is in it.
+    pub offset: u64,
+}
+
+/// A layer of [`ChunkTransforms`]. Both directions default to passing the
+/// chunk through unchanged.
+pub trait ChunkTransform: Debug + Send + Sync {
+    /// Transforms a chunk read from the source of an upload before it is
+    /// sent.
+    fn upload(&self, _chunk: &ChunkInfo, data: Bytes) -> azure_core::Result<Bytes> {
+        Ok(data)
+    }
+
+    /// Transforms a chunk downloaded at `offset` in the blob before it is
+    /// written after the output of the previous chunks.
+    fn download(&self, _offset: u64, data: Bytes) -> azure_core::Result<Bytes> {
+        Ok(data)
+    }
+}
+
+/// The layers of transforms of a transfer. Clones share the layers.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkTransforms(Vec<Arc<dyn ChunkTransform>>);
+
+impl ChunkTransforms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer, run after the layers already added on upload and before
+    /// them on download.
+    pub fn layer(mut self, transform: impl ChunkTransform + 'static) -> Self {
+        self.0.push(Arc::new(transform));
+        self
+    }
+
+    pub fn push(&mut self, transform: Arc<dyn ChunkTransform>) {
+        self.0.push(transform);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Runs the upload transforms of every layer, in order.
+    pub fn upload(&self, chunk: &ChunkInfo, data: Bytes) -> azure_core::Result<Bytes> {
+        self.0
+            .iter()
+            .try_fold(data, |data, transform| transform.upload(chunk, data))
+    }
+
+    /// Runs the download transforms of every layer, in reverse order.
+    pub fn download(&self, offset: u64, data: Bytes) -> azure_core::Result<Bytes> {
+        self.0
+            .iter()
+            .rev()
+            .try_fold(data, |data, transform| transform.download(offset, data))
+    }
+}
+
+impl<T> From<T> for ChunkTransforms
+where
+    T: ChunkTransform + 'static,
+{
+    fn from(transform: T) -> Self {
+        Self::new().layer(transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// XORs every byte with a key shifted by the offset of the chunk, as a
+    /// stand-in for a stream cipher.
+    #[derive(Debug)]
+    struct Xor(u8);
+
+    impl Xor {
+        fn apply(&self, offset: u64, data: &[u8]) -> Bytes {
+            data.iter()
+                .enumerate()
+                .map(|(i, byte)| byte ^ self.0.wrapping_add((offset as usize + i) as u8))
+                .collect()
+        }
+    }
+
+    impl ChunkTransform for Xor {
+        fn upload(&self, chunk: &ChunkInfo, data: Bytes) -> azure_core::Result<Bytes> {
+            Ok(self.apply(chunk.offset, &data))
+        }
+
+        fn download(&self, offset: u64, data: Bytes) -> azure_core::Result<Bytes> {
+            Ok(self.apply(offset, &data))
+        }
+    }
+
+    /// Records the chunks uploaded, in the order the layers see them.
+    #[derive(Debug, Default, Clone)]
+    struct Record(Arc<Mutex<Vec<(u64, Bytes)>>>);
+
+    impl ChunkTransform for Record {
+        fn upload(&self, chunk: &ChunkInfo, data: Bytes) -> azure_core::Result<Bytes> {
+            self.0.lock().unwrap().push((chunk.index, data.clone()));
+            Ok(data)
+        }
+    }
+
+    /// Writes every downloaded byte twice, as a stand-in for transforms which
+    /// change the length of the chunks.
+    #[derive(Debug)]
+    struct Repeat;
+
+    impl ChunkTransform for Repeat {
+        fn download(&self, _offset: u64, data: Bytes) -> azure_core::Result<Bytes> {
+            Ok(data.iter().flat_map(|byte| [*byte, *byte]).collect())
+        }
+    }
+
+    #[test]
+    fn test_chunk_transforms() {
+        let record = Record::default();
+        let transforms = ChunkTransforms::new().layer(Xor(7)).layer(record.clone());
+        assert_eq!(transforms.len(), 2);
+
+        let chunk = ChunkInfo {
+            index: 1,
+            offset: 4,
+        };
+        let uploaded = transforms
+            .upload(&chunk, Bytes::from_static(b"data"))
+            .unwrap();
+        assert_ne!(uploaded, "data");
+        // the layers added later see the output of the earlier ones
+        assert_eq!(*record.0.lock().unwrap(), [(1, uploaded.clone())]);
+        assert_eq!(transforms.download(4, uploaded).unwrap(), "data");
+
+        let repeated = ChunkTransforms::from(Repeat)
+            .download(4, Bytes::from_static(b"data"))
+            .unwrap();
+        assert_eq!(repeated, "ddaattaa");
+
+        assert!(ChunkTransforms::new().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transformed_transfers() -> azure_core::Result<()> {
+        use crate::{dev::InMemoryTransport, prelude::*};
+        use azure_storage::StorageCredentials;
+
+        let transport = InMemoryTransport::new();
+        let blob_client = ClientBuilder::new("account", StorageCredentials::anonymous())
+            .transport(transport.clone())
+            .blob_client("container", "blob");
+        let content: Vec<u8> = (0..100u8).collect();
+        let path = std::env::temp_dir().join(format!("{}.transformed", uuid::Uuid::new_v4()));
+        std::fs::write(&path, &content).unwrap();
+
+        let record = Record::default();
+        blob_client
+            .upload_file(&path)
+            .single_upload_threshold(0u64)
+            .block_size(32u64)
+            .transforms(ChunkTransforms::new().layer(Xor(3)).layer(record.clone()))
+            .await?;
+        let stored = transport.blob_content("container", "blob").unwrap();
+        assert_eq!(stored, Xor(3).apply(0, &content));
+        let mut indexes: Vec<_> = record
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(index, _)| *index)
+            .collect();
+        indexes.sort_unstable();
+        assert_eq!(indexes, [0, 1, 2, 3]);
+
+        blob_client
+            .download_file(&path)
+            .chunk_size(16u64)
+            .transforms(Xor(3))
+            .await?;
+        assert_eq!(std::fs::read(&path).unwrap(), content);
+
+        let stream = futures::stream::iter([Ok(Bytes::from(content.clone()))]);
+        blob_client
+            .upload_stream(stream)
+            .block_size(40usize)
+            .transforms(Xor(5))
+            .await?;
+        let stored = transport.blob_content("container", "blob").unwrap();
+        assert_eq!(Xor(5).apply(0, &stored), content);
+
+        std::fs::remove_file(&path).unwrap();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_length_changing_download() -> azure_core::Result<()> {
+        use crate::{dev::InMemoryTransport, prelude::*};
+        use azure_storage::StorageCredentials;
+
+        let blob_client = ClientBuilder::new("account", StorageCredentials::anonymous())
+            .transport(InMemoryTransport::new())
+            .blob_client("container", "blob");
+        let content: Vec<u8> = (0..100u8).collect();
+        blob_client.put_block_blob(content.clone()).await?;
+
+        // the ranges are downloaded in parallel, but their outputs, twice as
+        // long, are written in order one after the other
+        let path = std::env::temp_dir().join(format!("{}.repeated", uuid::Uuid::new_v4()));
+        blob_client
+            .download_file(&path)
+            .chunk_size(16u64)
+            .max_concurrency(4usize)
+            .transforms(Repeat)
+            .await?;
+        let repeated: Vec<u8> = content.iter().flat_map(|byte| [*byte, *byte]).collect();
+        assert_eq!(std::fs::read(&path).unwrap(), repeated);
+
+        std::fs::remove_file(&path).unwrap();
+        Ok(())
+    }
+}
@@ -569,6 +569,6 @@ fn initialize() -> BlobServiceClient {
     let access_key =
         std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
+    let storage_credentials = StorageCredentials::access_key(account.clone(), access_key);
     BlobServiceClient::new(account, storage_credentials)
 }
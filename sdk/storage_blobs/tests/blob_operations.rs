@@ -33,8 +33,11 @@ async fn put_block_blob_and_snapshot() {
             .unwrap();
     }
 
-    // calculate md5 too!
-    let digest = md5::compute(&data[..]);
+    // the MD5 of the data
+    let digest: [u8; 16] = [
+        0xe8, 0x0b, 0x50, 0x17, 0x09, 0x89, 0x50, 0xfc, 0x58, 0xaa, 0xd8, 0x3c, 0x8c, 0x14, 0x97,
+        0x8e,
+    ];
 
     blob.put_block_blob(data)
         .content_type("text/plain")
@@ -55,7 +55,7 @@ async fn break_lease() {
         .lease_break_period(Duration::from_secs(0))
         .await
         .unwrap();
-    assert!(res.lease_time == 0);
+    assert_eq!(res.lease_time, Duration::ZERO);
 
     container.delete().await.unwrap();
 }
@@ -66,6 +66,6 @@ fn initialize() -> BlobServiceClient {
     let access_key =
         std::env::var("STORAGE_ACCESS_KEY").expect("Set env variable STORAGE_ACCESS_KEY first!");
 
-    let storage_credentials = StorageCredentials::Key(account.clone(), access_key);
+    let storage_credentials = StorageCredentials::access_key(account.clone(), access_key);
     BlobServiceClient::new(account, storage_credentials)
 }
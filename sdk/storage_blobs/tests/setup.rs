@@ -5,14 +5,17 @@ use azure_storage_blobs::prelude::BlobServiceClient;
 use std::sync::Arc;
 
 pub fn initialize(transaction_name: impl Into<String>) -> azure_core::Result<BlobServiceClient> {
-    let account_name = (std::env::var(mock_transport::TESTING_MODE_KEY).as_deref()
-        == Ok(mock_transport::TESTING_MODE_RECORD))
-    .then(get_account)
-    .unwrap_or_default();
-    let storage_credentials = (std::env::var(mock_transport::TESTING_MODE_KEY).as_deref()
-        == Ok(mock_transport::TESTING_MODE_RECORD))
-    .then(|| StorageCredentials::TokenCredential(Arc::new(DefaultAzureCredential::default())))
-    .unwrap_or_else(|| StorageCredentials::BearerToken(String::default()));
+    let (account_name, storage_credentials) = if mock_transport::is_recording() {
+        (
+            get_account(),
+            StorageCredentials::TokenCredential(Arc::new(DefaultAzureCredential::default())),
+        )
+    } else {
+        (
+            String::default(),
+            StorageCredentials::bearer_token(String::default()),
+        )
+    };
     let transport_options = TransportOptions::new_custom_policy(
         mock_transport::new_mock_transport(transaction_name.into()),
     );
@@ -4,6 +4,7 @@ use azure_core::{ClientOptions, Pipeline};
 use azure_storage::clients::{new_pipeline_from_options, ServiceType};
 use azure_storage::prelude::StorageCredentials;
 use azure_storage::CloudLocation;
+use std::sync::Arc;
 
 /// A builder for the blob service client.
 #[derive(Debug, Clone)]
@@ -55,6 +56,36 @@ impl DataLakeClientBuilder {
         self
     }
 
+    /// Report the metrics of every request to `sink`.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn metrics(mut self, sink: Arc<dyn azure_storage::metrics::MetricsSink>) -> Self {
+        self.options = azure_storage::metrics::with_metrics(self.options, sink);
+        self
+    }
+
+    /// Add a circuit breaker which fails requests fast while the account keeps
+    /// returning throttling or server errors.
+    #[must_use]
+    pub fn circuit_breaker(mut self, options: azure_storage::CircuitBreakerOptions) -> Self {
+        self.options
+            .per_retry_policies_mut()
+            .push(Arc::new(azure_storage::CircuitBreakerPolicy::new(options)));
+        self
+    }
+
+    /// Add a policy injecting random failures, to test how the application
+    /// copes with them. Meant for tests only.
+    #[cfg(feature = "fault-injection")]
+    #[must_use]
+    pub fn fault_injection(
+        mut self,
+        policy: azure_storage::fault_injection::FaultInjectionPolicy,
+    ) -> Self {
+        self.options.per_retry_policies_mut().push(Arc::new(policy));
+        self
+    }
+
     /// Set the transport options.
     #[must_use]
     pub fn transport(mut self, transport: impl Into<azure_core::TransportOptions>) -> Self {
@@ -62,6 +93,14 @@ impl DataLakeClientBuilder {
         self
     }
 
+    /// Set the telemetry options, such as an application ID prepended to the
+    /// `User-Agent` header.
+    #[must_use]
+    pub fn telemetry(mut self, telemetry: impl Into<azure_core::TelemetryOptions>) -> Self {
+        self.options = self.options.telemetry(telemetry);
+        self
+    }
+
     /// Override all of the client options.
     ///
     /// *Warning!*: This overrides all client options that have been previously set on this builder.
@@ -1,7 +1,7 @@
 use crate::operations::*;
 use crate::request_options::*;
 use crate::{clients::FileSystemClient, prelude::PathClient, Properties};
-use azure_core::prelude::IfMatchCondition;
+use azure_core::{prelude::IfMatchCondition, Etag};
 use url::Url;
 
 #[derive(Debug, Clone)]
@@ -51,7 +51,7 @@ impl DirectoryClient {
 
     pub fn create_if_not_exists(&self) -> PutPathBuilder<Self> {
         self.create()
-            .if_match_condition(IfMatchCondition::NotMatch("*".to_string()))
+            .if_match_condition(IfMatchCondition::NotMatch(Etag::any()))
     }
 
     pub fn rename<P>(&self, destination_path: P) -> RenamePathBuilder<Self>
@@ -74,7 +74,7 @@ impl DirectoryClient {
         P: Into<String>,
     {
         self.rename(destination_path)
-            .if_match_condition(IfMatchCondition::NotMatch("*".to_string()))
+            .if_match_condition(IfMatchCondition::NotMatch(Etag::any()))
     }
 
     pub fn delete<R>(&self, recursive: R) -> DeletePathBuilder<Self>
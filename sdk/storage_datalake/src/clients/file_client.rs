@@ -1,6 +1,6 @@
 use super::{FileSystemClient, PathClient};
 use crate::{operations::*, request_options::*, Properties};
-use azure_core::prelude::IfMatchCondition;
+use azure_core::{prelude::IfMatchCondition, Etag};
 use bytes::Bytes;
 use url::Url;
 
@@ -42,7 +42,7 @@ impl FileClient {
 
     pub fn create_if_not_exists(&self) -> PutPathBuilder<Self> {
         self.create()
-            .if_match_condition(IfMatchCondition::NotMatch("*".to_string()))
+            .if_match_condition(IfMatchCondition::NotMatch(Etag::any()))
     }
 
     pub fn append<B>(&self, position: i64, bytes: B) -> PatchPathBuilder<Self>
@@ -80,7 +80,7 @@ impl FileClient {
         P: Into<String>,
     {
         self.rename(destination_path)
-            .if_match_condition(IfMatchCondition::NotMatch("*".to_string()))
+            .if_match_condition(IfMatchCondition::NotMatch(Etag::any()))
     }
 
     pub fn delete(&self) -> DeletePathBuilder<Self> {
@@ -48,7 +48,7 @@ impl GetFileSystemPropertiesResponse {
 
         Ok(GetFileSystemPropertiesResponse {
             common_storage_response_headers: (&headers).try_into()?,
-            etag: Etag::from(etag_from_headers(&headers)?),
+            etag: etag_from_headers(&headers)?,
             last_modified: last_modified_from_headers(&headers)?,
             namespace_enabled: namespace_enabled_from_headers(&headers)?,
             properties: (&headers).try_into()?,
@@ -50,7 +50,7 @@ impl SetFileSystemPropertiesResponse {
 
         Ok(SetFileSystemPropertiesResponse {
             common_storage_response_headers: (&headers).try_into()?,
-            etag: Etag::from(etag_from_headers(&headers)?),
+            etag: etag_from_headers(&headers)?,
             last_modified: last_modified_from_headers(&headers)?,
         })
     }
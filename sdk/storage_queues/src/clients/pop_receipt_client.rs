@@ -1,5 +1,6 @@
 use crate::{operations::*, prelude::*};
 use azure_core::{Context, Request, Response};
+use azure_storage::StorageClient;
 
 #[derive(Debug, Clone)]
 pub struct PopReceiptClient {
@@ -43,17 +44,6 @@ impl PopReceiptClient {
         self.client.send(context, request).await
     }
 
-    pub(crate) fn finalize_request(
-        &self,
-        url: url::Url,
-        method: azure_core::Method,
-        headers: azure_core::headers::Headers,
-        request_body: Option<azure_core::Body>,
-    ) -> azure_core::Result<Request> {
-        self.client
-            .finalize_request(url, method, headers, request_body)
-    }
-
     pub(crate) fn url(&self) -> azure_core::Result<url::Url> {
         let mut url = self.client.messages_url()?;
 
@@ -67,3 +57,19 @@ impl PopReceiptClient {
         Ok(url)
     }
 }
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl StorageClient for PopReceiptClient {
+    fn url(&self) -> azure_core::Result<url::Url> {
+        PopReceiptClient::url(self)
+    }
+
+    async fn send(
+        &self,
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response> {
+        PopReceiptClient::send(self, context, request).await
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     operations::*, PopReceipt, PopReceiptClient, QueueServiceClient, QueueStoredAccessPolicy,
 };
 use azure_core::{prelude::*, Context, Request, Response};
+use azure_storage::StorageClient;
 use std::fmt::Debug;
 
 #[derive(Debug, Clone)]
@@ -110,23 +111,28 @@ impl QueueClient {
         Ok(url)
     }
 
-    pub(crate) fn finalize_request(
+    pub(crate) async fn send(
         &self,
-        url: url::Url,
-        method: azure_core::Method,
-        headers: azure_core::headers::Headers,
-        request_body: Option<azure_core::Body>,
-    ) -> azure_core::Result<Request> {
-        self.service_client
-            .finalize_request(url, method, headers, request_body)
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response> {
+        self.service_client.send(context, request).await
     }
+}
 
-    pub(crate) async fn send(
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl StorageClient for QueueClient {
+    fn url(&self) -> azure_core::Result<url::Url> {
+        QueueClient::url(self)
+    }
+
+    async fn send(
         &self,
         context: &mut Context,
         request: &mut Request,
     ) -> azure_core::Result<Response> {
-        self.service_client.send(context, request).await
+        QueueClient::send(self, context, request).await
     }
 }
 
@@ -3,15 +3,17 @@ use azure_core::{ClientOptions, Context, Pipeline, Request, Response};
 use azure_storage::{
     clients::{new_pipeline_from_options, ServiceType},
     prelude::StorageCredentials,
-    CloudLocation,
+    CloudLocation, StorageClient,
 };
 use std::fmt::Debug;
+use std::sync::Arc;
 
 /// A builder for the queue service client.
 #[derive(Debug, Clone)]
 pub struct QueueServiceClientBuilder {
     cloud_location: CloudLocation,
     options: ClientOptions,
+    allow_insecure: bool,
 }
 
 impl QueueServiceClientBuilder {
@@ -30,6 +32,7 @@ impl QueueServiceClientBuilder {
         Self {
             options: ClientOptions::default(),
             cloud_location,
+            allow_insecure: false,
         }
     }
 
@@ -49,6 +52,7 @@ impl QueueServiceClientBuilder {
         QueueServiceClient {
             pipeline: new_pipeline_from_options(self.options, credentials.clone()),
             cloud_location: self.cloud_location,
+            allow_insecure: self.allow_insecure,
         }
     }
 
@@ -59,6 +63,16 @@ impl QueueServiceClientBuilder {
         self
     }
 
+    /// Allow a custom location with a plain `http` URL.
+    ///
+    /// By default the clients refuse to send requests with credentials over
+    /// plain HTTP, except to the emulator and loopback addresses.
+    #[must_use]
+    pub fn allow_insecure(mut self, allow_insecure: bool) -> Self {
+        self.allow_insecure = allow_insecure;
+        self
+    }
+
     /// Set the retry options.
     #[must_use]
     pub fn retry(mut self, retry: impl Into<azure_core::RetryOptions>) -> Self {
@@ -66,6 +80,36 @@ impl QueueServiceClientBuilder {
         self
     }
 
+    /// Report the metrics of every request to `sink`.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn metrics(mut self, sink: Arc<dyn azure_storage::metrics::MetricsSink>) -> Self {
+        self.options = azure_storage::metrics::with_metrics(self.options, sink);
+        self
+    }
+
+    /// Add a circuit breaker which fails requests fast while the account keeps
+    /// returning throttling or server errors.
+    #[must_use]
+    pub fn circuit_breaker(mut self, options: azure_storage::CircuitBreakerOptions) -> Self {
+        self.options
+            .per_retry_policies_mut()
+            .push(Arc::new(azure_storage::CircuitBreakerPolicy::new(options)));
+        self
+    }
+
+    /// Add a policy injecting random failures, to test how the application
+    /// copes with them. Meant for tests only.
+    #[cfg(feature = "fault-injection")]
+    #[must_use]
+    pub fn fault_injection(
+        mut self,
+        policy: azure_storage::fault_injection::FaultInjectionPolicy,
+    ) -> Self {
+        self.options.per_retry_policies_mut().push(Arc::new(policy));
+        self
+    }
+
     /// Set the transport options.
     #[must_use]
     pub fn transport(mut self, transport: impl Into<azure_core::TransportOptions>) -> Self {
@@ -73,6 +117,14 @@ impl QueueServiceClientBuilder {
         self
     }
 
+    /// Set the telemetry options, such as an application ID prepended to the
+    /// `User-Agent` header.
+    #[must_use]
+    pub fn telemetry(mut self, telemetry: impl Into<azure_core::TelemetryOptions>) -> Self {
+        self.options = self.options.telemetry(telemetry);
+        self
+    }
+
     /// Override all of the client options.
     ///
     /// *Warning!*: This overrides all client options that have been previously set on this builder.
@@ -87,6 +139,7 @@ impl QueueServiceClientBuilder {
 pub struct QueueServiceClient {
     pipeline: Pipeline,
     cloud_location: CloudLocation,
+    allow_insecure: bool,
 }
 
 impl QueueServiceClient {
@@ -124,19 +177,10 @@ impl QueueServiceClient {
     }
 
     pub fn url(&self) -> azure_core::Result<url::Url> {
+        self.cloud_location.check_secure(self.allow_insecure)?;
         self.cloud_location.url(ServiceType::Queue)
     }
 
-    pub(crate) fn finalize_request(
-        &self,
-        url: url::Url,
-        method: azure_core::Method,
-        headers: azure_core::headers::Headers,
-        request_body: Option<azure_core::Body>,
-    ) -> azure_core::Result<Request> {
-        azure_storage::clients::finalize_request(url, method, headers, request_body)
-    }
-
     pub(crate) async fn send(
         &self,
         context: &mut Context,
@@ -147,3 +191,19 @@ impl QueueServiceClient {
             .await
     }
 }
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl StorageClient for QueueServiceClient {
+    fn url(&self) -> azure_core::Result<url::Url> {
+        QueueServiceClient::url(self)
+    }
+
+    async fn send(
+        &self,
+        context: &mut Context,
+        request: &mut Request,
+    ) -> azure_core::Result<Response> {
+        QueueServiceClient::send(self, context, request).await
+    }
+}
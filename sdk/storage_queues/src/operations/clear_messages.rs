@@ -1,5 +1,5 @@
-use azure_core::{error::Error, headers::Headers, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{error::Error, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient, StorageRequest};
 use std::convert::TryInto;
 
 use crate::QueueClient;
@@ -12,13 +12,9 @@ operation! {
 impl ClearMessagesBuilder {
     pub fn into_future(mut self) -> ClearMessages {
         Box::pin(async move {
-            let url = self.client.messages_url()?;
+            let request = StorageRequest::new(self.client.messages_url()?, Method::Delete);
 
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Delete, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })
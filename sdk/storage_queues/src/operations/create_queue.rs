@@ -1,6 +1,6 @@
 use crate::clients::QueueClient;
-use azure_core::{error::Error, headers::Headers, prelude::*, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{error::Error, prelude::*, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 
 operation! {
@@ -12,20 +12,14 @@ operation! {
 impl CreateQueueBuilder {
     pub fn into_future(mut self) -> CreateQueue {
         Box::pin(async move {
-            let url = self.client.url()?;
-
-            let mut headers = Headers::new();
+            let mut request = self.client.request(Method::Put)?;
             if let Some(metadata) = &self.metadata {
                 for m in metadata.iter() {
-                    headers.add(m);
+                    request = request.header(m);
                 }
             }
 
-            let mut request = self
-                .client
-                .finalize_request(url, Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })
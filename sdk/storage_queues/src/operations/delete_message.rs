@@ -1,6 +1,6 @@
 use crate::clients::PopReceiptClient;
-use azure_core::{error::Error, headers::Headers, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{error::Error, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 
 operation! {
@@ -11,13 +11,9 @@ operation! {
 impl DeleteMessageBuilder {
     pub fn into_future(mut self) -> DeleteMessage {
         Box::pin(async move {
-            let url = self.client.url()?;
+            let request = self.client.request(Method::Delete)?;
 
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Delete, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })
@@ -1,6 +1,6 @@
 use crate::{prelude::*, PopReceipt};
-use azure_core::{headers::Headers, prelude::*, xml::read_xml, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{xml::read_xml, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient, StorageRequest};
 use serde::Deserialize;
 use std::convert::TryInto;
 use time::OffsetDateTime;
@@ -15,16 +15,11 @@ operation! {
 impl GetMessagesBuilder {
     pub fn into_future(mut self) -> GetMessages {
         Box::pin(async move {
-            let mut url = self.client.messages_url()?;
+            let request = StorageRequest::new(self.client.messages_url()?, Method::Get)
+                .query(&self.visibility_timeout)
+                .query(&self.number_of_messages);
 
-            self.visibility_timeout.append_to_url_query(&mut url);
-            self.number_of_messages.append_to_url_query(&mut url);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             GetMessagesResponse::try_from(response).await
         })
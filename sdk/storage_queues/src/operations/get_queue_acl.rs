@@ -1,6 +1,6 @@
 use crate::{clients::QueueClient, QueueStoredAccessPolicy};
-use azure_core::{headers::Headers, Method, Response as AzureResponse};
-use azure_storage::{headers::CommonStorageResponseHeaders, StoredAccessPolicyList};
+use azure_core::{Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient, StoredAccessPolicyList};
 use std::convert::TryInto;
 
 operation! {
@@ -11,15 +11,9 @@ operation! {
 impl GetQueueACLBuilder {
     pub fn into_future(mut self) -> GetQueueACL {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self.client.request(Method::Get)?.comp("acl");
 
-            url.query_pairs_mut().append_pair("comp", "acl");
-
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             GetQueueACLResponse::try_from(response).await
         })
@@ -1,11 +1,8 @@
 use crate::clients::QueueClient;
 use azure_core::{
-    error::Error,
-    headers::{HeaderName, Headers},
-    prelude::*,
-    Method, Response as AzureResponse,
+    error::Error, headers::HeaderName, prelude::*, Method, Response as AzureResponse,
 };
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 
 operation! {
@@ -16,15 +13,9 @@ operation! {
 impl GetQueueMetadataBuilder {
     pub fn into_future(mut self) -> GetQueueMetadata {
         Box::pin(async move {
-            let mut url = self.client.url()?;
+            let request = self.client.request(Method::Get)?.comp("metadata");
 
-            url.query_pairs_mut().append_pair("comp", "metadata");
-
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })
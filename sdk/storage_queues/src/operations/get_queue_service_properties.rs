@@ -1,6 +1,6 @@
 use crate::{QueueServiceClient, QueueServiceProperties};
-use azure_core::{headers::Headers, xml::read_xml, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{xml::read_xml, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 
 operation! {
@@ -11,16 +11,13 @@ operation! {
 impl GetQueueServicePropertiesBuilder {
     pub fn into_future(mut self) -> GetQueueServiceProperties {
         Box::pin(async move {
-            let mut url = self.client.url()?.clone();
+            let request = self
+                .client
+                .request(Method::Get)?
+                .restype("service")
+                .comp("properties");
 
-            url.query_pairs_mut().append_pair("restype", "service");
-            url.query_pairs_mut().append_pair("comp", "properties");
-
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             GetQueueServicePropertiesResponse::try_from(response).await
         })
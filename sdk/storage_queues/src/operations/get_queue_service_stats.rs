@@ -1,12 +1,6 @@
 use crate::QueueServiceClient;
-use azure_core::{
-    date,
-    error::{ErrorKind, ResultExt},
-    headers::Headers,
-    xml::read_xml,
-    Method, Response as AzureResponse,
-};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{xml::read_xml, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 use time::OffsetDateTime;
 
@@ -18,16 +12,13 @@ operation! {
 impl GetQueueServiceStatsBuilder {
     pub fn into_future(mut self) -> GetQueueServiceStats {
         Box::pin(async move {
-            let mut url = self.client.url()?.clone();
+            let request = self
+                .client
+                .request(Method::Get)?
+                .restype("service")
+                .comp("stats");
 
-            url.query_pairs_mut().append_pair("restype", "service");
-            url.query_pairs_mut().append_pair("comp", "stats");
-
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             GetQueueServiceStatsResponse::try_from(response).await
         })
@@ -59,7 +50,8 @@ struct GetQueueServiceStatsResponseInternal {
 #[serde(rename_all = "PascalCase")]
 struct GeoReplication {
     pub status: Status,
-    pub last_sync_time: Option<String>,
+    #[serde(default, with = "azure_core::date::rfc1123::option")]
+    pub last_sync_time: Option<OffsetDateTime>,
 }
 
 impl GetQueueServiceStatsResponse {
@@ -72,14 +64,7 @@ impl GetQueueServiceStatsResponse {
         Ok(GetQueueServiceStatsResponse {
             common_storage_response_headers: (&headers).try_into()?,
             status: response.geo_replication.status,
-            last_sync_time: response
-                .geo_replication
-                .last_sync_time
-                .map(|t| {
-                    date::parse_rfc1123(&t)
-                        .context(ErrorKind::DataConversion, "failed to parse last sync time")
-                })
-                .transpose()?,
+            last_sync_time: response.geo_replication.last_sync_time,
         })
     }
 }
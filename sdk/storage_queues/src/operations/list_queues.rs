@@ -1,9 +1,8 @@
 use crate::QueueServiceClient;
 use azure_core::{
-    error::Error, headers::Headers, prelude::*, xml::read_xml, Method, Pageable,
-    Response as AzureResponse,
+    error::Error, prelude::*, xml::read_xml, Method, Pageable, Response as AzureResponse,
 };
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 
 operation! {
@@ -20,27 +19,23 @@ impl ListQueuesBuilder {
         let make_request = move |continuation: Option<NextMarker>| {
             let mut this = self.clone();
             async move {
-                let mut url = this.client.url()?.clone();
-
-                url.query_pairs_mut().append_pair("comp", "list");
-
-                this.prefix.append_to_url_query(&mut url);
+                let mut request = this
+                    .client
+                    .request(Method::Get)?
+                    .comp("list")
+                    .query(&this.prefix);
 
                 if let Some(next_marker) = continuation {
-                    next_marker.append_to_url_query(&mut url);
+                    request = request.query(next_marker);
                 }
 
-                this.max_results.append_to_url_query(&mut url);
+                request = request.query(this.max_results);
 
                 if this.include_metadata.unwrap_or(false) {
-                    url.query_pairs_mut().append_pair("include", "metadata");
+                    request = request.query_pair("include", "metadata");
                 }
 
-                let mut request =
-                    this.client
-                        .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-                let response = this.client.send(&mut this.context, &mut request).await?;
+                let response = this.client.execute(&mut this.context, request).await?;
 
                 ListQueuesResponse::try_from(response).await
             }
@@ -1,6 +1,6 @@
 use crate::prelude::*;
-use azure_core::{headers::Headers, prelude::*, xml::read_xml, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{xml::read_xml, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient, StorageRequest};
 use std::convert::TryInto;
 use time::OffsetDateTime;
 
@@ -13,16 +13,11 @@ operation! {
 impl PeekMessagesBuilder {
     pub fn into_future(mut self) -> PeekMessages {
         Box::pin(async move {
-            let mut url = self.client.messages_url()?;
+            let request = StorageRequest::new(self.client.messages_url()?, Method::Get)
+                .query_pair("peekonly", "true")
+                .query(&self.number_of_messages);
 
-            url.query_pairs_mut().append_pair("peekonly", "true");
-            self.number_of_messages.append_to_url_query(&mut url);
-
-            let mut request =
-                self.client
-                    .finalize_request(url, Method::Get, Headers::new(), None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             PeekMessagesResponse::try_from(response).await
         })
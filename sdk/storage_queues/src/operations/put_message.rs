@@ -1,8 +1,6 @@
 use crate::prelude::*;
-use azure_core::{
-    date, headers::Headers, prelude::*, xml::read_xml, Method, Response as AzureResponse,
-};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{xml::read_xml, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient, StorageRequest};
 use std::convert::TryInto;
 use time::OffsetDateTime;
 
@@ -17,11 +15,6 @@ operation! {
 impl PutMessageBuilder {
     pub fn into_future(mut self) -> PutMessage {
         Box::pin(async move {
-            let mut url = self.client.messages_url()?;
-
-            self.visibility_timeout.append_to_url_query(&mut url);
-            self.ttl.append_to_url_query(&mut url);
-
             // since the format is fixed we just decorate the message with the tags.
             // This could be made optional in the future and/or more
             // stringent.
@@ -30,14 +23,12 @@ impl PutMessageBuilder {
                 self.body
             );
 
-            let mut request = self.client.finalize_request(
-                url,
-                Method::Post,
-                Headers::new(),
-                Some(message.into()),
-            )?;
+            let request = StorageRequest::new(self.client.messages_url()?, Method::Post)
+                .query(&self.visibility_timeout)
+                .query(&self.ttl)
+                .body(message);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             PutMessageResponse::try_from(response).await
         })
@@ -69,14 +60,14 @@ pub struct QueueMessage {
 struct QueueMessageInternal {
     #[serde(rename = "MessageId")]
     pub message_id: String,
-    #[serde(rename = "InsertionTime")]
-    pub insertion_time: String,
-    #[serde(rename = "ExpirationTime")]
-    pub expiration_time: String,
+    #[serde(rename = "InsertionTime", with = "azure_core::date::rfc1123")]
+    pub insertion_time: OffsetDateTime,
+    #[serde(rename = "ExpirationTime", with = "azure_core::date::rfc1123")]
+    pub expiration_time: OffsetDateTime,
     #[serde(rename = "PopReceipt")]
     pub pop_receipt: String,
-    #[serde(rename = "TimeNextVisible")]
-    pub time_next_visible: String,
+    #[serde(rename = "TimeNextVisible", with = "azure_core::date::rfc1123")]
+    pub time_next_visible: OffsetDateTime,
 }
 
 impl PutMessageResponse {
@@ -89,10 +80,10 @@ impl PutMessageResponse {
 
         let queue_message = QueueMessage {
             message_id: queue_message.message_id,
-            insertion_time: date::parse_rfc1123(&queue_message.insertion_time)?,
-            expiration_time: date::parse_rfc1123(&queue_message.expiration_time)?,
+            insertion_time: queue_message.insertion_time,
+            expiration_time: queue_message.expiration_time,
             pop_receipt: queue_message.pop_receipt,
-            time_next_visible: date::parse_rfc1123(&queue_message.time_next_visible)?,
+            time_next_visible: queue_message.time_next_visible,
         };
 
         Ok(Self {
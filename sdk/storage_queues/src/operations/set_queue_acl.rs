@@ -1,6 +1,6 @@
 use crate::{clients::QueueClient, QueueStoredAccessPolicy};
-use azure_core::{error::Error, headers::Headers, Method, Response as AzureResponse};
-use azure_storage::{headers::CommonStorageResponseHeaders, StoredAccessPolicyList};
+use azure_core::{error::Error, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient, StoredAccessPolicyList};
 use std::convert::TryInto;
 
 operation! {
@@ -12,10 +12,6 @@ operation! {
 impl SetQueueACLBuilder {
     pub fn into_future(mut self) -> SetQueueACL {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "acl");
-
             // convert the queue_stored_access_policies slice
             // in a StoredAccessPolicyList to get its XML
             // representation.
@@ -28,14 +24,9 @@ impl SetQueueACLBuilder {
                 StoredAccessPolicyList::new(stored_access).to_xml()
             };
 
-            let mut request = self.client.finalize_request(
-                url,
-                Method::Put,
-                Headers::new(),
-                Some(xml_body.into()),
-            )?;
+            let request = self.client.request(Method::Put)?.comp("acl").body(xml_body);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })
@@ -1,6 +1,6 @@
 use crate::clients::QueueClient;
-use azure_core::{error::Error, headers::Headers, prelude::*, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{error::Error, prelude::*, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 
 operation! {
@@ -12,20 +12,12 @@ operation! {
 impl SetQueueMetadataBuilder {
     pub fn into_future(mut self) -> SetQueueMetadata {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            url.query_pairs_mut().append_pair("comp", "metadata");
-
-            let mut headers = Headers::new();
+            let mut request = self.client.request(Method::Put)?.comp("metadata");
             for m in self.metadata.iter() {
-                headers.add(m);
+                request = request.header(m);
             }
 
-            let mut request = self
-                .client
-                .finalize_request(url, Method::Put, headers, None)?;
-
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })
@@ -1,6 +1,6 @@
 use crate::{QueueServiceClient, QueueServiceProperties};
-use azure_core::{error::Error, headers::Headers, xml::to_xml, Method, Response as AzureResponse};
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_core::{error::Error, xml::to_xml, Method, Response as AzureResponse};
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 
 operation! {
@@ -12,21 +12,16 @@ operation! {
 impl SetQueueServicePropertiesBuilder {
     pub fn into_future(mut self) -> SetQueueServiceProperties {
         Box::pin(async move {
-            let mut url = self.client.url()?.clone();
-
-            url.query_pairs_mut().append_pair("restype", "service");
-            url.query_pairs_mut().append_pair("comp", "properties");
-
             let xml_body = to_xml(&self.properties)?;
 
-            let mut request = self.client.finalize_request(
-                url,
-                Method::Put,
-                Headers::new(),
-                Some(xml_body.into()),
-            )?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .restype("service")
+                .comp("properties")
+                .body(xml_body);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })
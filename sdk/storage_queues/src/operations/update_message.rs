@@ -1,12 +1,10 @@
 use crate::{clients::PopReceiptClient, prelude::*};
 use azure_core::{
     error::Error,
-    headers::Headers,
     headers::{rfc1123_from_headers_mandatory, HeaderName},
-    prelude::*,
     Method, Response as AzureResponse,
 };
-use azure_storage::headers::CommonStorageResponseHeaders;
+use azure_storage::{headers::CommonStorageResponseHeaders, StorageClient};
 use std::convert::TryInto;
 use time::OffsetDateTime;
 
@@ -20,10 +18,6 @@ operation! {
 impl UpdateMessageBuilder {
     pub fn into_future(mut self) -> UpdateMessage {
         Box::pin(async move {
-            let mut url = self.client.url()?;
-
-            self.visibility_timeout.append_to_url_query(&mut url);
-
             // since the format is fixed we just decorate the message with the tags.
             // This could be made optional in the future and/or more
             // stringent.
@@ -32,14 +26,13 @@ impl UpdateMessageBuilder {
                 self.body
             );
 
-            let mut request = self.client.finalize_request(
-                url,
-                Method::Put,
-                Headers::new(),
-                Some(message.into()),
-            )?;
+            let request = self
+                .client
+                .request(Method::Put)?
+                .query(&self.visibility_timeout)
+                .body(message);
 
-            let response = self.client.send(&mut self.context, &mut request).await?;
+            let response = self.client.execute(&mut self.context, request).await?;
 
             response.try_into()
         })